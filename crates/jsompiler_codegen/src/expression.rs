@@ -0,0 +1,523 @@
+use crate::precedence::Precedence;
+use crate::{Codegen, Quote};
+use jsompiler_lexer::symbol::{LiteralToken, NumberLiteral};
+use jsompiler_parser::{
+    Expression, ExpressionId, Parameter, Property, PropertyKey, Statement, TemplateLiteral,
+};
+
+impl<'a> Codegen<'a> {
+    // Print an expression where it has to bind at least as tightly as `precedence`
+    pub fn expression(&mut self, id: ExpressionId, precedence: Precedence) {
+        let expression = &self.ast[id];
+        let is_in = matches!(expression, Expression::Binary { op, .. } if op.text == "in");
+        if self.precedence(expression) < precedence || (is_in && self.forbid_in) {
+            self.parenthesized(id);
+        } else {
+            self.expression_unwrapped(id);
+        }
+    }
+
+    pub fn parenthesized(&mut self, id: ExpressionId) {
+        let forbid_in = self.forbid_in;
+        self.forbid_in = false;
+        self.print("(");
+        self.expression_unwrapped(id);
+        self.print(")");
+        self.forbid_in = forbid_in;
+    }
+
+    fn precedence(&self, expression: &Expression) -> Precedence {
+        match expression {
+            Expression::Literal {
+                value: LiteralToken::Number(NumberLiteral::Value(value)),
+                ..
+            } if value.is_sign_negative() => Precedence::Prefix,
+            Expression::Unary { op_type, .. } if op_type == "Postfix" => Precedence::Postfix,
+            Expression::Unary { .. } | Expression::AwaitExpression { .. } => Precedence::Prefix,
+            Expression::Binary { op, .. } => Precedence::of_binary(&op.text),
+            Expression::MemberAccess { .. } | Expression::FunctionCall { .. } => Precedence::Call,
+            Expression::SpreadElement { .. } | Expression::ArrowFunctionExpression { .. } => {
+                Precedence::Assignment
+            }
+            _ => Precedence::Primary,
+        }
+    }
+
+    // The innermost expression at the start of `id`, the one printed first
+    fn leftmost(&self, mut id: ExpressionId) -> &'a Expression {
+        let ast = self.ast;
+        loop {
+            id = match &ast[id] {
+                Expression::Binary { left, .. } => *left,
+                Expression::MemberAccess { object, .. } if is_bare_operand(&ast[*object]) => {
+                    *object
+                }
+                Expression::FunctionCall { callee, .. } if is_bare_operand(&ast[*callee]) => {
+                    *callee
+                }
+                Expression::Unary { op_type, expr, .. } if op_type == "Postfix" => *expr,
+                expression => return expression,
+            };
+        }
+    }
+
+    // Whether an expression would start with `function`, `async function` or `class`
+    pub fn starts_like_declaration(&self, id: ExpressionId) -> bool {
+        matches!(
+            self.leftmost(id),
+            Expression::FunctionExpression(_)
+                | Expression::GeneratorExpression(_)
+                | Expression::AsyncFunctionExpression(_)
+                | Expression::AsyncGeneratorExpression(_)
+                | Expression::ClassExpression(_)
+        )
+    }
+
+    // Whether an expression statement starting with `id` would be read as something else: a
+    // declaration, a block, or a `let` declaration for `let[0]`
+    pub fn starts_ambiguously(&self, id: ExpressionId) -> bool {
+        self.starts_like_declaration(id)
+            || match self.leftmost(id) {
+                Expression::ObjectLiteral { .. } => true,
+                Expression::Identifier(identifier) => &self.ast[identifier.value] == "let",
+                _ => false,
+            }
+    }
+
+    fn expression_unwrapped(&mut self, id: ExpressionId) {
+        let ast = self.ast;
+        self.mark(ast[id].span(), None);
+        match &ast[id] {
+            Expression::Identifier(identifier) | Expression::PrivateIdentifier(identifier) => {
+                self.identifier(identifier)
+            }
+            Expression::Literal { value, .. } => self.literal(value),
+            Expression::TemplateLiteral(template) => self.template(template),
+            Expression::ThisExpression { .. } => self.print("this"),
+            Expression::SpreadElement { argument, .. } => {
+                self.print("...");
+                self.expression(*argument, Precedence::Assignment);
+            }
+            Expression::MemberAccess {
+                object,
+                property,
+                computed,
+                ..
+            } => {
+                // `1.toString()` would read the dot as a decimal point
+                let is_integer = match &ast[*object] {
+                    Expression::Literal {
+                        value: LiteralToken::Number(number),
+                        ..
+                    } => number_text(number, self.options.compact)
+                        .bytes()
+                        .all(|byte| byte.is_ascii_digit()),
+                    _ => false,
+                };
+                if is_integer || !is_bare_operand(&ast[*object]) {
+                    self.parenthesized(*object);
+                } else {
+                    self.expression(*object, Precedence::Call);
+                }
+                if *computed {
+                    self.print("[");
+                    self.expression(*property, Precedence::Lowest);
+                    self.print("]");
+                } else {
+                    self.print(".");
+                    self.expression(*property, Precedence::Primary);
+                }
+            }
+            Expression::Unary {
+                op, op_type, expr, ..
+            } => {
+                if op_type == "Postfix" {
+                    self.expression(*expr, Precedence::Call);
+                    self.print(&op.text);
+                } else {
+                    self.print(&op.text);
+                    if op.text.starts_with(char::is_alphabetic) {
+                        self.space();
+                    }
+                    self.expression(*expr, Precedence::Prefix);
+                }
+            }
+            Expression::Binary {
+                left, op, right, ..
+            } => self.binary(*left, &op.text, *right),
+            Expression::FunctionCall { callee, args, .. } => {
+                if is_bare_operand(&ast[*callee]) {
+                    self.expression(*callee, Precedence::Call);
+                } else {
+                    self.parenthesized(*callee);
+                }
+                self.print("(");
+                self.list(args, |codegen, &arg| {
+                    codegen.expression(arg, Precedence::Assignment)
+                });
+                self.print(")");
+            }
+            Expression::ArrayLiteral { elements, .. } => {
+                self.print("[");
+                self.list(elements, |codegen, &element| {
+                    if !is_hole(&codegen.ast[element]) {
+                        codegen.expression(element, Precedence::Assignment);
+                    }
+                });
+                // A trailing comma is ignored, so a hole at the end needs one more
+                if elements.last().is_some_and(|&last| is_hole(&ast[last])) {
+                    self.print(",");
+                }
+                self.print("]");
+            }
+            Expression::ObjectLiteral { properties, .. } => {
+                if properties.is_empty() {
+                    self.print("{}");
+                    return;
+                }
+                self.print("{");
+                self.space();
+                self.list(properties, |codegen, property| codegen.property(property));
+                self.space();
+                self.print("}");
+            }
+            Expression::AwaitExpression { argument, .. } => {
+                self.print("await");
+                self.space();
+                self.expression(*argument, Precedence::Prefix);
+            }
+            Expression::ClassExpression(class) => {
+                self.class(class.identifier.as_ref(), class.heritage, &class.body)
+            }
+            Expression::FunctionExpression(function) => self.function(
+                function.name.as_ref(),
+                &function.parameters,
+                &function.body,
+                false,
+                false,
+            ),
+            Expression::GeneratorExpression(function) => self.function(
+                function.name.as_ref(),
+                &function.parameters,
+                &function.body,
+                false,
+                true,
+            ),
+            Expression::AsyncFunctionExpression(function) => self.function(
+                function.name.as_ref(),
+                &function.parameters,
+                &function.body,
+                true,
+                false,
+            ),
+            Expression::AsyncGeneratorExpression(function) => self.function(
+                function.name.as_ref(),
+                &function.parameters,
+                &function.body,
+                true,
+                true,
+            ),
+            Expression::RegularExpressionLiteral { pattern, flags, .. } => {
+                self.print(&format!("/{pattern}/{flags}"))
+            }
+            Expression::ArrowFunctionExpression {
+                parameters, body, ..
+            } => self.arrow(parameters, body),
+            Expression::JSXElement(element) => self.jsx_element(element),
+            Expression::JSXFragment(fragment) => self.jsx_fragment(fragment),
+        }
+    }
+
+    fn binary(&mut self, left: ExpressionId, op: &str, right: ExpressionId) {
+        let precedence = Precedence::of_binary(op);
+        let (mut left_precedence, right_precedence) = if precedence.is_right_associative() {
+            (precedence.next(), precedence)
+        } else {
+            (precedence, precedence.next())
+        };
+        // `-a ** b` is a syntax error, the operand has to be `(-a) ** b`
+        if op == "**" {
+            left_precedence = Precedence::Postfix;
+        }
+        // `??` can't be mixed with `||` or `&&` without parentheses
+        let mixes_logical = |expression: &Expression| {
+            op == "??"
+                && matches!(expression, Expression::Binary { op, .. } if op.text == "||" || op.text == "&&")
+        };
+
+        if mixes_logical(&self.ast[left]) {
+            self.parenthesized(left);
+        } else {
+            self.expression(left, left_precedence);
+        }
+        self.space();
+        self.print(op);
+        self.space();
+        if mixes_logical(&self.ast[right]) {
+            self.parenthesized(right);
+        } else {
+            self.expression(right, right_precedence);
+        }
+    }
+
+    // Items separated by commas
+    pub fn list<T>(&mut self, items: &[T], mut item: impl FnMut(&mut Self, &T)) {
+        for (index, value) in items.iter().enumerate() {
+            if index > 0 {
+                self.comma();
+            }
+            item(self, value);
+        }
+    }
+
+    fn arrow(&mut self, parameters: &[Parameter], body: &[jsompiler_parser::StatementId]) {
+        let ast = self.ast;
+        match parameters {
+            [Parameter::Identifier(parameter)] if self.options.compact => {
+                self.identifier(parameter)
+            }
+            _ => self.parameters(parameters),
+        }
+        self.space();
+        self.print("=>");
+        self.space();
+        match body {
+            [id] => match &ast[*id] {
+                Statement::ExpressionStatement { expression, .. } => {
+                    // A body starting with `{` would be read as a block
+                    if matches!(self.leftmost(*expression), Expression::ObjectLiteral { .. }) {
+                        self.parenthesized(*expression);
+                    } else {
+                        self.expression(*expression, Precedence::Assignment);
+                    }
+                }
+                _ => self.braced(body),
+            },
+            _ => self.braced(body),
+        }
+    }
+
+    fn property(&mut self, property: &Property) {
+        match property {
+            Property::KeyValue { key, value, .. } => {
+                self.property_key(key);
+                self.print(":");
+                self.space();
+                self.expression(*value, Precedence::Assignment);
+            }
+            Property::Shorthand(identifier) => self.identifier(identifier),
+            Property::Method {
+                key, params, body, ..
+            } => {
+                self.property_key(key);
+                self.parameters(params);
+                self.space();
+                self.braced(body);
+            }
+            Property::Getter { key, body, .. } => {
+                self.print("get");
+                self.space();
+                self.property_key(key);
+                self.print("()");
+                self.space();
+                self.braced(std::slice::from_ref(body));
+            }
+            Property::Setter {
+                key, param, body, ..
+            } => {
+                self.print("set");
+                self.space();
+                self.property_key(key);
+                self.parameters(std::slice::from_ref(param));
+                self.space();
+                self.braced(std::slice::from_ref(body));
+            }
+            Property::Spread(spread) => self.expression(*spread, Precedence::Assignment),
+        }
+    }
+
+    pub fn property_key(&mut self, key: &PropertyKey) {
+        match key {
+            PropertyKey::Identifier(identifier) => self.identifier(identifier),
+            PropertyKey::StringLiteral { value, .. } => self.print(&self.string(value)),
+            PropertyKey::NumberLiteral { value, .. } => {
+                self.print(&number_text(value, self.options.compact))
+            }
+            PropertyKey::Computed(expression) => {
+                self.print("[");
+                self.expression(*expression, Precedence::Assignment);
+                self.print("]");
+            }
+        }
+    }
+
+    fn literal(&mut self, value: &LiteralToken) {
+        match value {
+            LiteralToken::Number(number) => self.print(&number_text(number, self.options.compact)),
+            LiteralToken::String(string) => self.print(&self.string(string)),
+            LiteralToken::Boolean(true) => self.print("true"),
+            LiteralToken::Boolean(false) => self.print("false"),
+            LiteralToken::Null => self.print("null"),
+            LiteralToken::Undefined => self.print("undefined"),
+        }
+    }
+
+    fn template(&mut self, template: &TemplateLiteral) {
+        match template {
+            TemplateLiteral::NonSubstitutionTemplate { text, .. } => {
+                let text = format!("`{}`", self.quasi(*text));
+                self.print(&text);
+            }
+            TemplateLiteral::SubstitutionTemplate {
+                template_head,
+                expression,
+                template_spans,
+                ..
+            } => {
+                let head = format!("`{}${{", self.quasi(*template_head));
+                self.print(&head);
+                self.expression(*expression, Precedence::Lowest);
+                let mut middle = template_spans.template_middle_list.as_ref();
+                while let Some(list) = middle {
+                    let text = format!("}}{}${{", self.quasi(list.template_middle));
+                    self.print(&text);
+                    self.expression(list.expression, Precedence::Lowest);
+                    middle = list.template_middle_list.as_deref();
+                }
+                let tail = format!("}}{}`", self.quasi(template_spans.template_tail));
+                self.print(&tail);
+            }
+        }
+    }
+
+    // The text of a template between substitutions, escaped for printing between backticks
+    fn quasi(&self, id: ExpressionId) -> String {
+        let Expression::Literal {
+            value: LiteralToken::String(text),
+            ..
+        } = &self.ast[id]
+        else {
+            return String::new();
+        };
+        let mut escaped = String::with_capacity(text.len());
+        let mut chars = text.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '`' => escaped.push_str("\\`"),
+                '\\' => escaped.push_str("\\\\"),
+                '$' if chars.peek() == Some(&'{') => escaped.push_str("\\$"),
+                '\r' => escaped.push_str("\\r"),
+                c => escaped.push(c),
+            }
+        }
+        escaped
+    }
+
+    pub fn string(&self, value: &str) -> String {
+        quote_string(value, self.options.quote)
+    }
+}
+
+// A string literal in the preferred quote, or the other one if it needs fewer escapes
+pub fn quote_string(value: &str, quote: Quote) -> String {
+    let (preferred, other) = match quote {
+        Quote::Double => ('"', '\''),
+        Quote::Single => ('\'', '"'),
+    };
+    let count = |quote| value.chars().filter(|&c| c == quote).count();
+    let quote = if count(preferred) > count(other) {
+        other
+    } else {
+        preferred
+    };
+
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push(quote);
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            '\u{8}' => escaped.push_str("\\b"),
+            '\u{b}' => escaped.push_str("\\v"),
+            '\u{c}' => escaped.push_str("\\f"),
+            // `\0` followed by a digit would be a legacy octal escape
+            '\0' if !chars.peek().is_some_and(char::is_ascii_digit) => escaped.push_str("\\0"),
+            '\u{2028}' => escaped.push_str("\\u2028"),
+            '\u{2029}' => escaped.push_str("\\u2029"),
+            c if c == quote => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c if c.is_ascii_control() => escaped.push_str(&format!("\\x{:02x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push(quote);
+    escaped
+}
+
+// Whether an expression can be called or have a member accessed without parentheses. Function,
+// class and object literals can't, the parser only reads them as operands when wrapped.
+pub fn is_bare_operand(expression: &Expression) -> bool {
+    !matches!(
+        expression,
+        Expression::FunctionExpression(_)
+            | Expression::GeneratorExpression(_)
+            | Expression::AsyncFunctionExpression(_)
+            | Expression::AsyncGeneratorExpression(_)
+            | Expression::ClassExpression(_)
+            | Expression::ObjectLiteral { .. }
+    )
+}
+
+fn is_hole(expression: &Expression) -> bool {
+    matches!(
+        expression,
+        Expression::Literal { value: LiteralToken::Undefined, span } if span.start == span.end
+    )
+}
+
+pub fn number_text(number: &NumberLiteral, compact: bool) -> String {
+    match number {
+        NumberLiteral::Value(value) => float_text(*value, compact),
+        NumberLiteral::NaN => "NaN".to_string(),
+        NumberLiteral::Infinity => "Infinity".to_string(),
+        NumberLiteral::BigInt(value) => format!("{value}n"),
+    }
+}
+
+// Readable mode switches to exponent notation where JavaScript's own `toString` does, compact
+// mode wherever it is shorter
+fn float_text(value: f64, compact: bool) -> String {
+    if value.is_nan() {
+        return "NaN".to_string();
+    }
+    if value.is_infinite() {
+        return if value < 0.0 { "-Infinity" } else { "Infinity" }.to_string();
+    }
+    let decimal = format!("{value}");
+    let exponent = format!("{value:e}");
+    if !compact {
+        let magnitude = value.abs();
+        return if magnitude >= 1e21 || (magnitude != 0.0 && magnitude < 1e-6) {
+            exponent
+        } else {
+            decimal
+        };
+    }
+    let decimal = match decimal.strip_prefix("0.") {
+        Some(fraction) => format!(".{fraction}"),
+        None => match decimal.strip_prefix("-0.") {
+            Some(fraction) => format!("-.{fraction}"),
+            None => decimal,
+        },
+    };
+    if exponent.len() < decimal.len() {
+        exponent
+    } else {
+        decimal
+    }
+}
@@ -0,0 +1,290 @@
+use crate::Codegen;
+use crate::expression::number_text;
+use crate::precedence::Precedence;
+use jsompiler_parser::{
+    BindingElement, BindingPattern, BindingProperty, BindingRestElement, ClassElement,
+    ClassElementName, ExpressionId, Identifier, Parameter, StatementId,
+};
+
+impl Codegen<'_> {
+    pub fn function(
+        &mut self,
+        name: Option<&Identifier>,
+        parameters: &[Parameter],
+        body: &[StatementId],
+        is_async: bool,
+        is_generator: bool,
+    ) {
+        if is_async {
+            self.print("async");
+        }
+        self.print("function");
+        if is_generator {
+            self.print("*");
+        }
+        self.space();
+        if let Some(name) = name {
+            self.identifier(name);
+        }
+        self.parameters(parameters);
+        self.space();
+        self.braced(body);
+    }
+
+    pub fn parameters(&mut self, parameters: &[Parameter]) {
+        self.print("(");
+        self.list(parameters, |codegen, parameter| match parameter {
+            Parameter::Identifier(identifier) => codegen.identifier(identifier),
+            Parameter::Pattern(pattern) => codegen.binding_pattern(pattern),
+            Parameter::Default {
+                target, default, ..
+            } => {
+                codegen.binding_pattern(target);
+                codegen.initializer(Some(*default));
+            }
+            Parameter::RestParameter { argument, .. } => {
+                codegen.print("...");
+                codegen.binding_pattern(argument);
+            }
+        });
+        self.print(")");
+    }
+
+    // ` = value` after a binding, if it has a value
+    fn initializer(&mut self, value: Option<ExpressionId>) {
+        if let Some(value) = value {
+            self.space();
+            self.print("=");
+            self.space();
+            self.expression(value, Precedence::Assignment);
+        }
+    }
+
+    pub fn binding_pattern(&mut self, pattern: &BindingPattern) {
+        match pattern {
+            BindingPattern::Identifier(identifier) => self.identifier(identifier),
+            BindingPattern::ObjectPattern {
+                properties, rest, ..
+            } => {
+                if properties.is_empty() && rest.is_none() {
+                    self.print("{}");
+                    return;
+                }
+                self.print("{");
+                self.space();
+                self.list(properties, |codegen, property| match property {
+                    BindingProperty::KeyValue { key, value, .. } => {
+                        codegen.property_key(key);
+                        codegen.print(":");
+                        codegen.space();
+                        codegen.binding_element(value);
+                    }
+                    BindingProperty::Shorthand { name, default, .. } => {
+                        codegen.identifier(name);
+                        codegen.initializer(*default);
+                    }
+                });
+                if let Some(rest) = rest {
+                    if !properties.is_empty() {
+                        self.comma();
+                    }
+                    self.binding_rest(rest);
+                }
+                self.space();
+                self.print("}");
+            }
+            BindingPattern::ArrayPattern { elements, rest, .. } => {
+                self.print("[");
+                self.list(elements, |codegen, element| {
+                    if let Some(element) = element {
+                        codegen.binding_element(element);
+                    }
+                });
+                match rest {
+                    Some(rest) => {
+                        if !elements.is_empty() {
+                            self.comma();
+                        }
+                        self.binding_rest(rest);
+                    }
+                    // A trailing comma is ignored, so an elision at the end needs one more
+                    None if matches!(elements.last(), Some(None)) => self.print(","),
+                    None => {}
+                }
+                self.print("]");
+            }
+        }
+    }
+
+    fn binding_element(&mut self, element: &BindingElement) {
+        self.binding_pattern(&element.target);
+        self.initializer(element.default);
+    }
+
+    fn binding_rest(&mut self, rest: &BindingRestElement) {
+        self.print("...");
+        self.binding_pattern(&rest.argument);
+    }
+
+    pub fn class(
+        &mut self,
+        name: Option<&Identifier>,
+        heritage: Option<ExpressionId>,
+        body: &[ClassElement],
+    ) {
+        self.print("class");
+        self.space();
+        if let Some(name) = name {
+            self.identifier(name);
+            self.space();
+        }
+        if let Some(heritage) = heritage {
+            self.print("extends");
+            self.space();
+            self.expression(heritage, Precedence::Call);
+            self.space();
+        }
+        self.print("{");
+        if !body.is_empty() {
+            self.indent += 1;
+            for element in body {
+                self.newline();
+                self.class_element(element);
+            }
+            self.indent -= 1;
+            self.newline();
+        }
+        self.close_brace();
+    }
+
+    fn class_element(&mut self, element: &ClassElement) {
+        match element {
+            ClassElement::Constructor {
+                name, params, body, ..
+            } => self.method(name, params, body),
+            ClassElement::MethodDefinition {
+                name,
+                params,
+                body,
+                is_static,
+                ..
+            } => {
+                self.modifier("static", *is_static);
+                self.method(name, params, body);
+            }
+            ClassElement::AsyncMethodDefinition {
+                name,
+                params,
+                body,
+                is_static,
+                ..
+            } => {
+                self.modifier("static", *is_static);
+                self.modifier("async", true);
+                self.method(name, params, body);
+            }
+            ClassElement::GeneratorMethodDefinition {
+                name,
+                params,
+                body,
+                is_static,
+                ..
+            } => {
+                self.modifier("static", *is_static);
+                self.print("*");
+                self.method(name, params, body);
+            }
+            ClassElement::AsyncGeneratorMethodDefinition {
+                name,
+                params,
+                body,
+                is_static,
+                ..
+            } => {
+                self.modifier("static", *is_static);
+                self.modifier("async", true);
+                self.print("*");
+                self.method(name, params, body);
+            }
+            ClassElement::GetterDefinition {
+                name,
+                body,
+                is_static,
+                ..
+            } => {
+                self.modifier("static", *is_static);
+                self.modifier("get", true);
+                self.method(name, &[], body);
+            }
+            ClassElement::SetterDefinition {
+                name,
+                param,
+                body,
+                is_static,
+                ..
+            } => {
+                self.modifier("static", *is_static);
+                self.modifier("set", true);
+                self.method(name, std::slice::from_ref(param), body);
+            }
+            ClassElement::FieldDefinition {
+                name,
+                value,
+                is_static,
+                ..
+            } => {
+                self.modifier("static", *is_static);
+                self.class_element_name(name);
+                self.initializer(*value);
+                self.semicolon();
+            }
+            ClassElement::AccessorDefinition {
+                name,
+                value,
+                is_static,
+                ..
+            } => {
+                self.modifier("static", *is_static);
+                self.modifier("accessor", true);
+                self.class_element_name(name);
+                self.initializer(*value);
+                self.semicolon();
+            }
+            ClassElement::StaticBlock { body, .. } => {
+                self.modifier("static", true);
+                self.braced(body);
+            }
+        }
+    }
+
+    // A keyword such as `static` or `get` in front of a class element
+    fn modifier(&mut self, keyword: &str, present: bool) {
+        if present {
+            self.print(keyword);
+            self.space();
+        }
+    }
+
+    fn method(&mut self, name: &ClassElementName, params: &[Parameter], body: &[StatementId]) {
+        self.class_element_name(name);
+        self.parameters(params);
+        self.space();
+        self.braced(body);
+    }
+
+    fn class_element_name(&mut self, name: &ClassElementName) {
+        match name {
+            ClassElementName::PropertyName(identifier)
+            | ClassElementName::PrivateIdentifier(identifier) => self.identifier(identifier),
+            ClassElementName::StringLiteral { value, .. } => self.print(&self.string(value)),
+            ClassElementName::NumberLiteral { value, .. } => {
+                self.print(&number_text(value, self.options.compact))
+            }
+            ClassElementName::Computed(expression) => {
+                self.print("[");
+                self.expression(*expression, Precedence::Assignment);
+                self.print("]");
+            }
+        }
+    }
+}
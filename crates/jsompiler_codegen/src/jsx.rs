@@ -0,0 +1,103 @@
+use crate::Codegen;
+use crate::precedence::Precedence;
+use jsompiler_parser::{
+    JSXAttribute, JSXAttributeValue, JSXChild, JSXElement, JSXExpressionContainer, JSXFragment,
+};
+
+impl Codegen<'_> {
+    pub fn jsx_element(&mut self, element: &JSXElement) {
+        let name = element.opening.name.text(self.ast);
+        self.print("<");
+        self.print(&name);
+        for attribute in &element.opening.attributes {
+            self.print(" ");
+            self.jsx_attribute(attribute);
+        }
+        if element.opening.self_closing {
+            self.space();
+            self.print("/>");
+            return;
+        }
+        self.print(">");
+        self.jsx_children(&element.children);
+        self.print("</");
+        self.print(&name);
+        self.print(">");
+    }
+
+    pub fn jsx_fragment(&mut self, fragment: &JSXFragment) {
+        self.print("<>");
+        self.jsx_children(&fragment.children);
+        self.print("</>");
+    }
+
+    fn jsx_attribute(&mut self, attribute: &JSXAttribute) {
+        match attribute {
+            JSXAttribute::Attribute { name, value, .. } => {
+                self.print(&name.text(self.ast));
+                let Some(value) = value else {
+                    return;
+                };
+                self.print("=");
+                match value {
+                    // Attribute strings have no escapes, and entities in them are decoded
+                    JSXAttributeValue::StringLiteral { value, .. } if !value.contains('&') => {
+                        if !value.contains('"') {
+                            self.print(&format!("\"{value}\""));
+                        } else if !value.contains('\'') {
+                            self.print(&format!("'{value}'"));
+                        } else {
+                            self.print(&format!("{{{}}}", self.string(value)));
+                        }
+                    }
+                    JSXAttributeValue::StringLiteral { value, .. } => {
+                        self.print(&format!("{{{}}}", self.string(value)))
+                    }
+                    JSXAttributeValue::ExpressionContainer(container) => {
+                        self.jsx_expression_container(container)
+                    }
+                    JSXAttributeValue::Element(element) => self.jsx_element(element),
+                    JSXAttributeValue::Fragment(fragment) => self.jsx_fragment(fragment),
+                }
+            }
+            JSXAttribute::SpreadAttribute { argument, .. } => {
+                self.print("{...");
+                self.expression(*argument, Precedence::Assignment);
+                self.print("}");
+            }
+        }
+    }
+
+    fn jsx_children(&mut self, children: &[JSXChild]) {
+        for child in children {
+            match child {
+                // Text that would read as markup or an entity goes in a string instead
+                JSXChild::Text { value, .. } if value.contains(['{', '}', '<', '>', '&']) => {
+                    self.print(&format!("{{{}}}", self.string(value)))
+                }
+                JSXChild::Text { value, .. } => self.output.push_str(value),
+                JSXChild::Element(element) => self.jsx_element(element),
+                JSXChild::Fragment(fragment) => self.jsx_fragment(fragment),
+                JSXChild::ExpressionContainer(container) => {
+                    self.jsx_expression_container(container)
+                }
+                JSXChild::Spread { expression, .. } => {
+                    self.print("{...");
+                    self.expression(*expression, Precedence::Assignment);
+                    self.print("}");
+                }
+            }
+        }
+    }
+
+    fn jsx_expression_container(&mut self, container: &JSXExpressionContainer) {
+        match container {
+            JSXExpressionContainer::Expression { expression, .. } => {
+                self.print("{");
+                self.expression(*expression, Precedence::Assignment);
+                self.print("}");
+            }
+            JSXExpressionContainer::Empty { .. } => self.print("{}"),
+        }
+    }
+}
@@ -0,0 +1,166 @@
+mod expression;
+mod function;
+mod jsx;
+mod precedence;
+mod source_map;
+mod statement;
+mod test;
+
+use jsompiler_parser::{Ast, Identifier, Span};
+use source_map::SourceMapBuilder;
+
+pub use expression::{is_bare_operand, number_text, quote_string};
+pub use precedence::Precedence;
+pub use source_map::{Mapping, SourceMap, SourceMapOptions};
+
+// Quote that string literals are printed with. A string holding more of the preferred quote
+// than of the other is printed with the other, so fewer quotes need escaping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Quote {
+    #[default]
+    Double,
+    Single,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CodegenOptions {
+    pub compact: bool, // No indentation, newlines or optional spaces
+    pub quote: Quote,
+}
+
+// Print an AST back to JavaScript. The output parses back to the same tree, whatever
+// parentheses and line breaks the original source had.
+pub fn generate(ast: &Ast, options: CodegenOptions) -> String {
+    let mut codegen = Codegen::new(ast, options);
+    codegen.program();
+    codegen.output
+}
+
+// Print an AST parsed from `source` along with a map from the output back to `source`, or
+// through `map_options.input_map` to whatever `source` was generated from
+pub fn generate_with_source_map(
+    ast: &Ast,
+    source: &str,
+    options: CodegenOptions,
+    map_options: SourceMapOptions,
+) -> (String, SourceMap) {
+    let mut codegen = Codegen::new(ast, options);
+    codegen.source_map = Some(SourceMapBuilder::new(source));
+    codegen.program();
+    let map = match codegen.source_map {
+        Some(builder) => builder.finish(map_options),
+        None => SourceMap::default(),
+    };
+    (codegen.output, map)
+}
+
+struct Codegen<'a> {
+    ast: &'a Ast,
+    options: CodegenOptions,
+    output: String,
+    indent: usize,
+    pending_semicolon: bool, // Compact mode leaves out a semicolon right before a `}`
+    forbid_in: bool,         // In a `for` initializer, where `in` would start a for-in loop
+    source_map: Option<SourceMapBuilder>,
+    pending_mapping: Option<(usize, Option<&'a str>)>, // Source of the next token
+}
+
+impl<'a> Codegen<'a> {
+    fn new(ast: &'a Ast, options: CodegenOptions) -> Self {
+        Codegen {
+            ast,
+            options,
+            output: String::new(),
+            indent: 0,
+            pending_semicolon: false,
+            forbid_in: false,
+            source_map: None,
+            pending_mapping: None,
+        }
+    }
+
+    // Append a token, with a space before it when it would otherwise merge with the previous
+    // one, as in `return a`, `a+ +b` or `a/ /b/`
+    fn print(&mut self, text: &str) {
+        let Some(first) = text.chars().next() else {
+            return;
+        };
+        if self.pending_semicolon {
+            self.pending_semicolon = false;
+            self.output.push(';');
+        }
+        if self
+            .output
+            .chars()
+            .next_back()
+            .is_some_and(|last| needs_space(last, first))
+        {
+            self.output.push(' ');
+        }
+        if let Some((index, name)) = self.pending_mapping.take()
+            && let Some(source_map) = &mut self.source_map
+        {
+            source_map.add(&self.output, index, name);
+        }
+        self.output.push_str(text);
+    }
+
+    // Map the next token printed to the start of `span`. Nodes built by transforms have no
+    // span and get no mapping.
+    fn mark(&mut self, span: Span, name: Option<&'a str>) {
+        if self.source_map.is_some() && span != Span::default() {
+            self.pending_mapping = Some((span.start, name));
+        }
+    }
+
+    fn identifier(&mut self, identifier: &Identifier) {
+        let name = &self.ast[identifier.value];
+        self.mark(identifier.span, Some(name));
+        self.print(name);
+    }
+
+    // Whitespace that is only there for readability
+    fn space(&mut self) {
+        if !self.options.compact {
+            self.output.push(' ');
+        }
+    }
+
+    fn newline(&mut self) {
+        if !self.options.compact {
+            self.output.push('\n');
+            self.output.push_str(&"  ".repeat(self.indent));
+        }
+    }
+
+    fn semicolon(&mut self) {
+        if self.options.compact {
+            self.pending_semicolon = true;
+        } else {
+            self.print(";");
+        }
+    }
+
+    // Closing brace of a block or class body, where a statement needs no semicolon
+    fn close_brace(&mut self) {
+        self.pending_semicolon = false;
+        self.print("}");
+    }
+
+    // Separator of parameters, arguments, elements and properties
+    fn comma(&mut self) {
+        self.print(",");
+        self.space();
+    }
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '$' || c == '\\' || !c.is_ascii()
+}
+
+fn needs_space(last: char, first: char) -> bool {
+    (is_identifier_char(last) && (is_identifier_char(first) || first == '`'))
+        || (last == '+' && first == '+')
+        || (last == '-' && first == '-')
+        || (last == '/' && (first == '/' || first == '*'))
+}
@@ -0,0 +1,71 @@
+// How tightly an expression binds, loosest first. An operand is wrapped in parentheses when
+// it binds more loosely than its position requires.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Precedence {
+    Lowest,
+    Assignment, // Assignment operators, arrow functions, spread and yield
+    Coalesce,
+    LogicalOr,
+    LogicalAnd,
+    BitwiseOr,
+    BitwiseXor,
+    BitwiseAnd,
+    Equality,
+    Relational,
+    Shift,
+    Additive,
+    Multiplicative,
+    Exponentiation,
+    Prefix,
+    Postfix,
+    Call, // Calls and member accesses
+    Primary,
+}
+
+impl Precedence {
+    pub fn of_binary(op: &str) -> Self {
+        match op {
+            "??" => Precedence::Coalesce,
+            "||" => Precedence::LogicalOr,
+            "&&" => Precedence::LogicalAnd,
+            "|" => Precedence::BitwiseOr,
+            "^" => Precedence::BitwiseXor,
+            "&" => Precedence::BitwiseAnd,
+            "==" | "!=" | "===" | "!==" => Precedence::Equality,
+            "<" | ">" | "<=" | ">=" | "in" | "instanceof" => Precedence::Relational,
+            "<<" | ">>" | ">>>" => Precedence::Shift,
+            "+" | "-" => Precedence::Additive,
+            "*" | "/" | "%" => Precedence::Multiplicative,
+            "**" => Precedence::Exponentiation,
+            _ => Precedence::Assignment, // `=`, `+=` and the other assignment operators
+        }
+    }
+
+    // The next tighter level
+    pub fn next(self) -> Self {
+        match self {
+            Precedence::Lowest => Precedence::Assignment,
+            Precedence::Assignment => Precedence::Coalesce,
+            Precedence::Coalesce => Precedence::LogicalOr,
+            Precedence::LogicalOr => Precedence::LogicalAnd,
+            Precedence::LogicalAnd => Precedence::BitwiseOr,
+            Precedence::BitwiseOr => Precedence::BitwiseXor,
+            Precedence::BitwiseXor => Precedence::BitwiseAnd,
+            Precedence::BitwiseAnd => Precedence::Equality,
+            Precedence::Equality => Precedence::Relational,
+            Precedence::Relational => Precedence::Shift,
+            Precedence::Shift => Precedence::Additive,
+            Precedence::Additive => Precedence::Multiplicative,
+            Precedence::Multiplicative => Precedence::Exponentiation,
+            Precedence::Exponentiation => Precedence::Prefix,
+            Precedence::Prefix => Precedence::Postfix,
+            Precedence::Postfix => Precedence::Call,
+            Precedence::Call | Precedence::Primary => Precedence::Primary,
+        }
+    }
+
+    // `a ** b ** c` is `a ** (b ** c)`, and so are assignments
+    pub fn is_right_associative(self) -> bool {
+        matches!(self, Precedence::Exponentiation | Precedence::Assignment)
+    }
+}
@@ -0,0 +1,387 @@
+use jsompiler_common::{Error, ErrorKind};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+
+// One segment of `mappings`: the generated position and where it came from. Lines and
+// columns are zero-based and columns count UTF-16 code units, as in the encoded map. A
+// segment without a source marks generated code with no original; its original fields are 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mapping {
+    pub generated_line: u32,
+    pub generated_column: u32,
+    pub source: Option<u32>, // Index into `sources`
+    pub original_line: u32,
+    pub original_column: u32,
+    pub name: Option<u32>, // Index into `names`
+}
+
+// A Source Map v3, with its mappings decoded and sorted by generated position
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SourceMap {
+    pub file: Option<String>,
+    pub sources: Vec<String>,
+    pub sources_content: Vec<Option<String>>, // Empty or one per source
+    pub names: Vec<String>,
+    pub mappings: Vec<Mapping>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SourceMapOptions {
+    pub source: String,               // Name of the original file, listed in `sources`
+    pub file: Option<String>,         // Name of the generated file
+    pub include_content: bool,        // Embed the original code as `sourcesContent`
+    pub input_map: Option<SourceMap>, // Map of the original file back to an earlier stage
+}
+
+impl SourceMap {
+    pub fn to_json(&self) -> String {
+        let mut map = json!({
+            "version": 3,
+            "sources": self.sources,
+            "names": self.names,
+            "mappings": encode_mappings(&self.mappings),
+        });
+        if let Some(file) = &self.file {
+            map["file"] = json!(file);
+        }
+        if !self.sources_content.is_empty() {
+            map["sourcesContent"] = json!(self.sources_content);
+        }
+        map.to_string()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        let map: Value = serde_json::from_str(json).map_err(|error| Error {
+            error_kind: ErrorKind::SyntaxError,
+            message: format!("Invalid source map JSON: {error}"),
+            line_number: error.line(),
+            pos: error.column(),
+        })?;
+        if map["version"] != 3 {
+            return Err(source_map_error("Only version 3 source maps are supported"));
+        }
+        if map.get("sections").is_some() {
+            return Err(source_map_error("Index source maps are not supported"));
+        }
+
+        // `sourceRoot` is prepended to every source, as browsers do
+        let root = map["sourceRoot"].as_str().unwrap_or_default();
+        let strings = |field: &str| -> Vec<Option<String>> {
+            map[field]
+                .as_array()
+                .map(|values| {
+                    values
+                        .iter()
+                        .map(|value| value.as_str().map(str::to_string))
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        let sources = strings("sources")
+            .into_iter()
+            .map(|source| format!("{root}{}", source.unwrap_or_default()))
+            .collect();
+        let names = strings("names").into_iter().flatten().collect();
+        let Some(mappings) = map["mappings"].as_str() else {
+            return Err(source_map_error("Missing `mappings`"));
+        };
+
+        Ok(SourceMap {
+            file: map["file"].as_str().map(str::to_string),
+            sources,
+            sources_content: strings("sourcesContent"),
+            names,
+            mappings: decode_mappings(mappings)?,
+        })
+    }
+
+    // The mapping covering a generated position: the last one on its line at or before it,
+    // unless that one is unmapped
+    pub fn lookup(&self, line: u32, column: u32) -> Option<&Mapping> {
+        let end = self.mappings.partition_point(|mapping| {
+            (mapping.generated_line, mapping.generated_column) <= (line, column)
+        });
+        self.mappings[..end]
+            .last()
+            .filter(|mapping| mapping.generated_line == line && mapping.source.is_some())
+    }
+
+    // Trace this map's original positions through `input`, the map of the code this one was
+    // generated from, giving a map from the final output straight to `input`'s sources.
+    // Positions `input` has no mapping for become unmapped.
+    pub fn compose(&self, input: &SourceMap) -> SourceMap {
+        let mut names = Names::default();
+        let mut mappings = Vec::new();
+        for mapping in &self.mappings {
+            let original = mapping
+                .source
+                .and_then(|_| input.lookup(mapping.original_line, mapping.original_column));
+            let Some(original) = original else {
+                mappings.push(Mapping {
+                    source: None,
+                    original_line: 0,
+                    original_column: 0,
+                    name: None,
+                    ..*mapping
+                });
+                continue;
+            };
+            let name = original
+                .name
+                .and_then(|name| input.names.get(name as usize))
+                .or_else(|| mapping.name.and_then(|name| self.names.get(name as usize)));
+            mappings.push(Mapping {
+                generated_line: mapping.generated_line,
+                generated_column: mapping.generated_column,
+                source: original.source,
+                original_line: original.original_line,
+                original_column: original.original_column,
+                name: name.map(|name| names.index(name)),
+            });
+        }
+        SourceMap {
+            file: self.file.clone(),
+            sources: input.sources.clone(),
+            sources_content: input.sources_content.clone(),
+            names: names.names,
+            mappings,
+        }
+    }
+}
+
+fn source_map_error(message: &str) -> Error {
+    Error {
+        error_kind: ErrorKind::SyntaxError,
+        message: message.to_string(),
+        line_number: 0,
+        pos: 0,
+    }
+}
+
+#[derive(Default)]
+struct Names {
+    names: Vec<String>,
+    indices: HashMap<String, u32>,
+}
+
+impl Names {
+    fn index(&mut self, name: &str) -> u32 {
+        if let Some(&index) = self.indices.get(name) {
+            return index;
+        }
+        let index = self.names.len() as u32;
+        self.names.push(name.to_string());
+        self.indices.insert(name.to_string(), index);
+        index
+    }
+}
+
+// Collects mappings while the code generator prints, tracking the generated position
+pub struct SourceMapBuilder {
+    line_starts: Vec<usize>, // Character index where each line of the source starts
+    source: Vec<char>,
+    names: Names,
+    mappings: Vec<Mapping>,
+    generated_line: u32,
+    generated_column: u32,
+    scanned: usize, // Bytes of the output already counted into the generated position
+}
+
+impl SourceMapBuilder {
+    pub fn new(source: &str) -> Self {
+        let source: Vec<char> = source.chars().collect();
+        let mut line_starts = vec![0];
+        for (index, c) in source.iter().enumerate() {
+            let is_line_break = match c {
+                '\n' | '\u{2028}' | '\u{2029}' => true,
+                '\r' => source.get(index + 1) != Some(&'\n'),
+                _ => false,
+            };
+            if is_line_break {
+                line_starts.push(index + 1);
+            }
+        }
+        SourceMapBuilder {
+            line_starts,
+            source,
+            names: Names::default(),
+            mappings: Vec::new(),
+            generated_line: 0,
+            generated_column: 0,
+            scanned: 0,
+        }
+    }
+
+    // Map the end of `output`, where the next token goes, to a character of the source
+    pub fn add(&mut self, output: &str, index: usize, name: Option<&str>) {
+        for c in output[self.scanned..].chars() {
+            if c == '\n' {
+                self.generated_line += 1;
+                self.generated_column = 0;
+            } else {
+                self.generated_column += c.len_utf16() as u32;
+            }
+        }
+        self.scanned = output.len();
+
+        let index = index.min(self.source.len());
+        let line = self.line_starts.partition_point(|&start| start <= index) - 1;
+        let original_column: usize = self.source[self.line_starts[line]..index]
+            .iter()
+            .map(|c| c.len_utf16())
+            .sum();
+        let mapping = Mapping {
+            generated_line: self.generated_line,
+            generated_column: self.generated_column,
+            source: Some(0),
+            original_line: line as u32,
+            original_column: original_column as u32,
+            name: name.map(|name| self.names.index(name)),
+        };
+        match self.mappings.last_mut() {
+            Some(last)
+                if (last.generated_line, last.generated_column)
+                    == (mapping.generated_line, mapping.generated_column) =>
+            {
+                *last = mapping
+            }
+            _ => self.mappings.push(mapping),
+        }
+    }
+
+    pub fn finish(self, options: SourceMapOptions) -> SourceMap {
+        let map = SourceMap {
+            file: options.file,
+            sources: vec![options.source],
+            sources_content: if options.include_content {
+                vec![Some(self.source.iter().collect())]
+            } else {
+                Vec::new()
+            },
+            names: self.names.names,
+            mappings: self.mappings,
+        };
+        match &options.input_map {
+            Some(input) => map.compose(input),
+            None => map,
+        }
+    }
+}
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+// Base64 VLQ: the sign in the lowest bit, then five bits per digit with a continuation bit
+pub fn encode_vlq(value: i64, out: &mut String) {
+    let mut rest = if value < 0 {
+        (-value << 1) | 1
+    } else {
+        value << 1
+    };
+    loop {
+        let mut digit = rest & 0b11111;
+        rest >>= 5;
+        if rest > 0 {
+            digit |= 0b100000;
+        }
+        out.push(BASE64[digit as usize] as char);
+        if rest == 0 {
+            break;
+        }
+    }
+}
+
+pub fn decode_vlq(chars: &mut impl Iterator<Item = char>) -> Option<i64> {
+    let mut value = 0i64;
+    let mut shift = 0;
+    loop {
+        let next = chars.next()?;
+        let digit = BASE64.iter().position(|&c| c as char == next)? as i64;
+        value |= (digit & 0b11111) << shift;
+        shift += 5;
+        if digit & 0b100000 == 0 || shift > 60 {
+            break;
+        }
+    }
+    Some(if value & 1 == 1 {
+        -(value >> 1)
+    } else {
+        value >> 1
+    })
+}
+
+// Lines are separated by `;` and segments by `,`. Every field is relative to the same field
+// of the previous segment, except the generated column, which restarts on each line.
+fn encode_mappings(mappings: &[Mapping]) -> String {
+    let mut out = String::new();
+    let mut line = 0;
+    let mut previous_column = 0i64;
+    let mut previous = (0i64, 0i64, 0i64, 0i64); // Source, line, column and name
+    for (index, mapping) in mappings.iter().enumerate() {
+        if mapping.generated_line > line {
+            for _ in line..mapping.generated_line {
+                out.push(';');
+            }
+            line = mapping.generated_line;
+            previous_column = 0;
+        } else if index > 0 {
+            out.push(',');
+        }
+        encode_vlq(mapping.generated_column as i64 - previous_column, &mut out);
+        previous_column = mapping.generated_column as i64;
+        let Some(source) = mapping.source else {
+            continue;
+        };
+        encode_vlq(source as i64 - previous.0, &mut out);
+        encode_vlq(mapping.original_line as i64 - previous.1, &mut out);
+        encode_vlq(mapping.original_column as i64 - previous.2, &mut out);
+        previous.0 = source as i64;
+        previous.1 = mapping.original_line as i64;
+        previous.2 = mapping.original_column as i64;
+        if let Some(name) = mapping.name {
+            encode_vlq(name as i64 - previous.3, &mut out);
+            previous.3 = name as i64;
+        }
+    }
+    out
+}
+
+// Segments with only a generated column mark code with no original and are kept unmapped
+fn decode_mappings(mappings: &str) -> Result<Vec<Mapping>, Error> {
+    let mut decoded = Vec::new();
+    let mut previous = [0i64; 5]; // Generated column, source, line, column and name
+    for (line, text) in mappings.split(';').enumerate() {
+        previous[0] = 0;
+        for segment in text.split(',').filter(|segment| !segment.is_empty()) {
+            let mut chars = segment.chars().peekable();
+            let mut fields = Vec::with_capacity(5);
+            while chars.peek().is_some() {
+                let Some(value) = decode_vlq(&mut chars) else {
+                    return Err(source_map_error(&format!(
+                        "Invalid VLQ segment `{segment}` in `mappings`"
+                    )));
+                };
+                fields.push(value);
+            }
+            if !matches!(fields.len(), 1 | 4 | 5) {
+                return Err(source_map_error(&format!(
+                    "Segment `{segment}` has {} fields",
+                    fields.len()
+                )));
+            }
+            for (field, value) in fields.iter().enumerate() {
+                previous[field] += value;
+            }
+            let mapped = fields.len() > 1;
+            decoded.push(Mapping {
+                generated_line: line as u32,
+                generated_column: previous[0] as u32,
+                source: mapped.then_some(previous[1] as u32),
+                original_line: if mapped { previous[2] as u32 } else { 0 },
+                original_column: if mapped { previous[3] as u32 } else { 0 },
+                name: (fields.len() == 5).then_some(previous[4] as u32),
+            });
+        }
+    }
+    decoded.sort_by_key(|mapping| (mapping.generated_line, mapping.generated_column));
+    Ok(decoded)
+}
@@ -0,0 +1,474 @@
+use crate::Codegen;
+use crate::precedence::Precedence;
+use jsompiler_lexer::symbol::{KeywordToken, Token};
+use jsompiler_parser::{
+    ExportStatement, ExpressionId, ForInOfLeft, LetStatement, Node, Statement, StatementId,
+};
+
+// What gets printed as one statement. A declaration of several variables is parsed into one
+// `LetStatement` per declarator, all sharing the span and keyword of the declaration.
+enum Group<'a> {
+    Declaration(Vec<&'a LetStatement>),
+    Statement(StatementId),
+}
+
+impl<'a> Codegen<'a> {
+    pub fn program(&mut self) {
+        let ast = self.ast;
+        // The hashbang has to stay first and end its line, even in compact output
+        if let Some(hashbang) = &ast.hashbang {
+            self.output.push_str("#!");
+            self.output.push_str(hashbang);
+            if self.options.compact {
+                self.output.push('\n');
+            }
+        }
+        for node in &ast.body {
+            match node {
+                Node::Statement(statements) => {
+                    for group in self.groups(statements) {
+                        self.line();
+                        self.group(&group);
+                    }
+                }
+                Node::Expression(expression) => {
+                    self.line();
+                    self.expression_statement(*expression);
+                }
+            }
+        }
+        self.pending_semicolon = false;
+        if !self.options.compact && !self.output.is_empty() {
+            self.output.push('\n');
+        }
+    }
+
+    // Start a statement on a new line, unless it is the first thing printed
+    fn line(&mut self) {
+        if !self.output.is_empty() {
+            self.newline();
+        }
+    }
+
+    fn groups(&self, statements: &[StatementId]) -> Vec<Group<'a>> {
+        let ast = self.ast;
+        let mut groups: Vec<Group<'a>> = Vec::new();
+        for &id in statements {
+            let Statement::LetStatement(declarator) = &ast[id] else {
+                groups.push(Group::Statement(id));
+                continue;
+            };
+            if let Some(Group::Declaration(declarators)) = groups.last_mut()
+                && declarators[0].span == declarator.span
+                && declarators[0].token == declarator.token
+            {
+                declarators.push(declarator);
+                continue;
+            }
+            groups.push(Group::Declaration(vec![declarator]));
+        }
+        groups
+    }
+
+    fn group(&mut self, group: &Group<'a>) {
+        match group {
+            Group::Declaration(declarators) => {
+                self.mark(declarators[0].span, None);
+                self.declaration(declarators);
+                self.semicolon();
+            }
+            Group::Statement(id) => self.statement(*id),
+        }
+    }
+
+    pub fn block(&mut self, statements: &[StatementId]) {
+        self.print("{");
+        if !statements.is_empty() {
+            self.indent += 1;
+            for group in self.groups(statements) {
+                self.newline();
+                self.group(&group);
+            }
+            self.indent -= 1;
+            self.newline();
+        }
+        self.close_brace();
+    }
+
+    // A body that needs braces: functions, `try`, `catch`, `finally` and static blocks. The
+    // parser stores them as a single block statement.
+    pub fn braced(&mut self, statements: &[StatementId]) {
+        let ast = self.ast;
+        match statements {
+            [id] => match &ast[*id] {
+                Statement::BlockStatement(block) => self.block(&block.statements),
+                _ => self.block(statements),
+            },
+            _ => self.block(statements),
+        }
+    }
+
+    // The body of `if`, `else`, loops, labels and `with`, normally a single statement
+    fn body(&mut self, statements: &[StatementId]) {
+        let groups = self.groups(statements);
+        match groups.as_slice() {
+            [] => self.print(";"),
+            [group] => {
+                self.space();
+                self.group(group);
+            }
+            _ => {
+                self.space();
+                self.block(statements);
+            }
+        }
+    }
+
+    fn is_block(&self, statements: &[StatementId]) -> bool {
+        matches!(statements, [id] if matches!(self.ast[*id], Statement::BlockStatement(_)))
+    }
+
+    // Whether a body ends in an `if` without `else`, which would take an `else` that follows
+    // the body as its own
+    fn ends_with_open_if(&self, statements: &[StatementId]) -> bool {
+        let Some(&last) = statements.last() else {
+            return false;
+        };
+        match &self.ast[last] {
+            Statement::IfStatement(statement) => match &statement.alternative {
+                Some(alternative) => self.ends_with_open_if(alternative),
+                None => true,
+            },
+            Statement::WhileStatement(statement) => self.ends_with_open_if(&statement.consequence),
+            Statement::ForLoopStatement(statement) => self.ends_with_open_if(&statement.body),
+            Statement::ForInStatement(statement) => self.ends_with_open_if(&statement.body),
+            Statement::ForOfStatement(statement) => self.ends_with_open_if(&statement.body),
+            Statement::LabeledStatement(statement) => self.ends_with_open_if(&statement.body),
+            Statement::WithStatement(statement) => self.ends_with_open_if(&statement.body),
+            _ => false,
+        }
+    }
+
+    fn statement(&mut self, id: StatementId) {
+        let ast = self.ast;
+        self.mark(ast[id].span(), None);
+        match &ast[id] {
+            Statement::LetStatement(declarator) => {
+                self.declaration(&[declarator]);
+                self.semicolon();
+            }
+            Statement::ReturnStatement(statement) => {
+                self.print("return");
+                if let Some(value) = statement.value {
+                    self.space();
+                    self.expression(value, Precedence::Lowest);
+                }
+                self.semicolon();
+            }
+            Statement::ExpressionStatement { expression, .. } => {
+                self.expression_statement(*expression)
+            }
+            Statement::BlockStatement(block) => self.block(&block.statements),
+            Statement::IfStatement(statement) => {
+                self.keyword_head("if", statement.condition);
+                let Some(alternative) = &statement.alternative else {
+                    self.body(&statement.consequence);
+                    return;
+                };
+                if self.ends_with_open_if(&statement.consequence) {
+                    self.space();
+                    self.block(&statement.consequence);
+                    self.space();
+                } else if self.is_block(&statement.consequence) {
+                    self.body(&statement.consequence);
+                    self.space();
+                } else {
+                    self.body(&statement.consequence);
+                    self.newline();
+                }
+                self.print("else");
+                self.body(alternative);
+            }
+            Statement::WhileStatement(statement) => {
+                self.keyword_head("while", statement.condition);
+                self.body(&statement.consequence);
+            }
+            Statement::AssignmentStatement(statement) => {
+                if self.starts_ambiguously(statement.target) {
+                    self.print("(");
+                    self.assignment(statement.target, statement.value);
+                    self.print(")");
+                } else {
+                    self.assignment(statement.target, statement.value);
+                }
+                self.semicolon();
+            }
+            Statement::ForLoopStatement(statement) => {
+                self.print("for");
+                self.space();
+                self.print("(");
+                self.forbid_in = true;
+                self.for_header_part(&statement.initializer);
+                self.forbid_in = false;
+                self.print(";");
+                if !statement.condition.is_empty() {
+                    self.space();
+                    self.for_header_part(&statement.condition);
+                }
+                self.print(";");
+                if !statement.increment.is_empty() {
+                    self.space();
+                    self.for_header_part(&statement.increment);
+                }
+                self.print(")");
+                self.body(&statement.body);
+            }
+            Statement::YieldStatement(statement) => {
+                self.print("yield");
+                if let Some(&argument) = statement.expression.first() {
+                    self.space();
+                    self.expression(argument, Precedence::Assignment);
+                }
+                self.semicolon();
+            }
+            Statement::FunctionDeclaration(function) => self.function(
+                function.name.as_ref(),
+                &function.parameters,
+                &function.body,
+                function.is_async,
+                function.is_generator,
+            ),
+            Statement::ClassDeclaration(class) => {
+                self.class(class.identifier.as_ref(), class.heritage, &class.body)
+            }
+            Statement::ExportStatement(statement) => {
+                self.print("export");
+                self.space();
+                match statement {
+                    ExportStatement::Declaration { declarations, .. } => {
+                        for group in self.groups(declarations) {
+                            self.group(&group);
+                        }
+                    }
+                    ExportStatement::DefaultDeclaration { declaration, .. } => {
+                        self.print("default");
+                        self.space();
+                        self.statement(*declaration);
+                    }
+                    ExportStatement::DefaultExpression { expression, .. } => {
+                        self.print("default");
+                        self.space();
+                        // A function or class here would be read as a declaration
+                        if self.starts_like_declaration(*expression) {
+                            self.parenthesized(*expression);
+                        } else {
+                            self.expression(*expression, Precedence::Assignment);
+                        }
+                        self.semicolon();
+                    }
+                }
+            }
+            Statement::LabeledStatement(statement) => {
+                self.identifier(&statement.label);
+                self.print(":");
+                self.body(&statement.body);
+            }
+            Statement::DoWhileStatement(statement) => {
+                self.print("do");
+                self.body(&statement.body);
+                if self.is_block(&statement.body) {
+                    self.space();
+                } else {
+                    self.newline();
+                }
+                self.keyword_head("while", statement.condition);
+                self.semicolon();
+            }
+            Statement::ForInStatement(statement) => {
+                self.print("for");
+                self.space();
+                self.print("(");
+                self.for_in_of_left(&statement.left);
+                self.print("in");
+                self.space();
+                self.expression(statement.right, Precedence::Lowest);
+                self.print(")");
+                self.body(&statement.body);
+            }
+            Statement::ForOfStatement(statement) => {
+                self.print("for");
+                if statement.is_await {
+                    self.print("await");
+                }
+                self.space();
+                self.print("(");
+                self.for_in_of_left(&statement.left);
+                self.print("of");
+                self.space();
+                self.expression(statement.right, Precedence::Assignment);
+                self.print(")");
+                self.body(&statement.body);
+            }
+            Statement::SwitchStatement(statement) => {
+                self.keyword_head("switch", statement.discriminant);
+                self.space();
+                self.print("{");
+                self.indent += 1;
+                for case in &statement.cases {
+                    self.newline();
+                    match case.test {
+                        Some(test) => {
+                            self.print("case");
+                            self.space();
+                            self.expression(test, Precedence::Lowest);
+                        }
+                        None => self.print("default"),
+                    }
+                    self.print(":");
+                    self.indent += 1;
+                    for group in self.groups(&case.consequent) {
+                        self.newline();
+                        self.group(&group);
+                    }
+                    self.indent -= 1;
+                }
+                self.indent -= 1;
+                if !statement.cases.is_empty() {
+                    self.newline();
+                }
+                self.close_brace();
+            }
+            Statement::ThrowStatement(statement) => {
+                self.print("throw");
+                self.space();
+                self.expression(statement.argument, Precedence::Lowest);
+                self.semicolon();
+            }
+            Statement::TryStatement(statement) => {
+                self.print("try");
+                self.space();
+                self.braced(&statement.block);
+                if let Some(handler) = &statement.handler {
+                    self.space();
+                    self.print("catch");
+                    self.space();
+                    if let Some(param) = &handler.param {
+                        self.print("(");
+                        self.binding_pattern(param);
+                        self.print(")");
+                        self.space();
+                    }
+                    self.braced(&handler.body);
+                }
+                if let Some(finalizer) = &statement.finalizer {
+                    self.space();
+                    self.print("finally");
+                    self.space();
+                    self.braced(finalizer);
+                }
+            }
+            Statement::WithStatement(statement) => {
+                self.keyword_head("with", statement.object);
+                self.body(&statement.body);
+            }
+            Statement::EmptyStatement { .. } | Statement::Error { .. } => self.print(";"),
+            Statement::DebuggerStatement { .. } => {
+                self.print("debugger");
+                self.semicolon();
+            }
+            Statement::BreakStatement { label, .. }
+            | Statement::ContinueStatement { label, .. } => {
+                self.print(match &ast[id] {
+                    Statement::BreakStatement { .. } => "break",
+                    _ => "continue",
+                });
+                if let Some(label) = label {
+                    self.identifier(label);
+                }
+                self.semicolon();
+            }
+        }
+    }
+
+    // `if (condition)`, `while (condition)` and the like
+    fn keyword_head(&mut self, keyword: &str, expression: ExpressionId) {
+        self.print(keyword);
+        self.space();
+        self.print("(");
+        self.expression(expression, Precedence::Lowest);
+        self.print(")");
+    }
+
+    fn expression_statement(&mut self, expression: ExpressionId) {
+        if self.starts_ambiguously(expression) {
+            self.parenthesized(expression);
+        } else {
+            self.expression(expression, Precedence::Lowest);
+        }
+        self.semicolon();
+    }
+
+    fn assignment(&mut self, target: ExpressionId, value: ExpressionId) {
+        self.expression(target, Precedence::Call);
+        self.space();
+        self.print("=");
+        self.space();
+        self.expression(value, Precedence::Assignment);
+    }
+
+    fn declaration(&mut self, declarators: &[&LetStatement]) {
+        self.print(declaration_keyword(&declarators[0].token));
+        self.space();
+        for (index, declarator) in declarators.iter().enumerate() {
+            if index > 0 {
+                self.comma();
+            }
+            self.binding_pattern(&declarator.name);
+            if let Some(value) = declarator.value {
+                self.space();
+                self.print("=");
+                self.space();
+                self.expression(value, Precedence::Assignment);
+            }
+        }
+    }
+
+    // One of the three parts of a `for (;;)` header
+    fn for_header_part(&mut self, statements: &[StatementId]) {
+        let ast = self.ast;
+        for group in self.groups(statements) {
+            match group {
+                Group::Declaration(declarators) => self.declaration(&declarators),
+                Group::Statement(id) => match &ast[id] {
+                    Statement::ExpressionStatement { expression, .. } => {
+                        self.expression(*expression, Precedence::Lowest)
+                    }
+                    Statement::AssignmentStatement(statement) => {
+                        self.assignment(statement.target, statement.value)
+                    }
+                    _ => {}
+                },
+            }
+        }
+    }
+
+    fn for_in_of_left(&mut self, left: &ForInOfLeft) {
+        match left {
+            ForInOfLeft::Declaration { token, name, .. } => {
+                self.print(declaration_keyword(token));
+                self.space();
+                self.binding_pattern(name);
+            }
+            ForInOfLeft::Target(target) => self.expression(*target, Precedence::Call),
+        }
+        self.space();
+    }
+}
+
+fn declaration_keyword(token: &Token) -> &'static str {
+    match token {
+        Token::Keyword(KeywordToken::Var) => "var",
+        Token::Keyword(KeywordToken::Const) => "const",
+        _ => "let",
+    }
+}
@@ -0,0 +1,441 @@
+#[allow(unused_imports)]
+use super::source_map::{decode_vlq, encode_vlq};
+#[allow(unused_imports)]
+use super::{
+    CodegenOptions, Mapping, Quote, SourceMap, SourceMapOptions, generate, generate_with_source_map,
+};
+#[allow(unused_imports)]
+use jsompiler_lexer::symbol::{SYMBOLS, Span};
+#[allow(unused_imports)]
+use jsompiler_lexer::{Lexer, LexerOptions};
+#[allow(unused_imports)]
+use jsompiler_parser::{Ast, Expression, Identifier, Node, Parser, ParserOptions, Statement};
+
+#[allow(dead_code)]
+fn parse(source: &str) -> Ast {
+    let mut lexer = Lexer::with_options(source.to_string(), LexerOptions { jsx: true });
+    lexer.scan_all_tokens();
+    let mut parser = Parser::with_lexer(lexer, ParserOptions { jsx: true });
+    parser.parse();
+    assert!(parser.errors.is_empty(), "{source}: {:?}", parser.errors);
+    assert!(
+        parser.lexer_errors.is_empty(),
+        "{source}: {:?}",
+        parser.lexer_errors
+    );
+    parser.ast
+}
+
+#[allow(dead_code)]
+fn readable(source: &str) -> String {
+    generate(&parse(source), CodegenOptions::default())
+}
+
+#[allow(dead_code)]
+fn compact(source: &str) -> String {
+    let options = CodegenOptions {
+        compact: true,
+        ..Default::default()
+    };
+    generate(&parse(source), options)
+}
+
+// The expression statement `source`, parsed as the right side of `x = source` since the
+// parser can't start a statement with a parenthesis
+#[allow(dead_code)]
+fn compact_statement(source: &str) -> String {
+    let mut ast = parse(&format!("x = {source}"));
+    let Some(Node::Statement(statements)) = ast.body.first() else {
+        panic!("expected a statement");
+    };
+    let Statement::AssignmentStatement(assignment) = &ast[statements[0]] else {
+        panic!("expected an assignment");
+    };
+    let statement = Statement::ExpressionStatement {
+        expression: assignment.value,
+        span: assignment.span,
+    };
+    let id = ast.alloc_statement(statement);
+    ast.body = vec![Node::Statement(vec![id])];
+    let options = CodegenOptions {
+        compact: true,
+        ..Default::default()
+    };
+    generate(&ast, options)
+}
+
+#[test]
+fn test_parentheses_follow_precedence() {
+    assert_eq!(compact("x = (a + b) * c"), "x=(a+b)*c");
+    assert_eq!(compact("x = a + (b * c)"), "x=a+b*c");
+    assert_eq!(compact("x = a - (b - c)"), "x=a-(b-c)");
+    assert_eq!(compact("x = (a - b) - c"), "x=a-b-c");
+    assert_eq!(compact("x = (a, b) => (a + b)"), "x=(a,b)=>a+b");
+    assert_eq!(compact("x = (() => 1)()"), "x=(()=>1)()");
+    assert_eq!(compact("x = (a + b).c"), "x=(a+b).c");
+    assert_eq!(compact("x = (1).toString()"), "x=(1).toString()");
+}
+
+// Operators the parser doesn't read yet, on a hand-built AST
+#[test]
+fn test_parentheses_of_right_associative_and_mixed_operators() {
+    let mut ast = Ast::new();
+    let name = |ast: &mut Ast, name: &str| {
+        let name = ast.atoms.intern(name);
+        ast.alloc_expression(Expression::Identifier(Identifier::new(
+            name,
+            Span::default(),
+        )))
+    };
+    let binary = |ast: &mut Ast, left, op: &str, right| {
+        ast.alloc_expression(Expression::Binary {
+            left,
+            op: SYMBOLS[op].clone(),
+            right,
+            span: Span::default(),
+        })
+    };
+    let (a, b, c) = (
+        name(&mut ast, "a"),
+        name(&mut ast, "b"),
+        name(&mut ast, "c"),
+    );
+    let right = binary(&mut ast, b, "**", c);
+    let right_nested = binary(&mut ast, a, "**", right);
+    let left = binary(&mut ast, a, "**", b);
+    let left_nested = binary(&mut ast, left, "**", c);
+    let negated = ast.alloc_expression(Expression::Unary {
+        op: SYMBOLS["-"].clone(),
+        op_type: "Prefix".to_string(),
+        expr: a,
+        span: Span::default(),
+    });
+    let negated_base = binary(&mut ast, negated, "**", b);
+    let or = binary(&mut ast, b, "||", c);
+    let coalesce = binary(&mut ast, a, "??", or);
+    let assign = binary(&mut ast, b, "=", c);
+    let assign_nested = binary(&mut ast, a, "=", assign);
+
+    let expressions = [
+        right_nested,
+        left_nested,
+        negated_base,
+        coalesce,
+        assign_nested,
+    ];
+    let statements = expressions.map(|expression| {
+        ast.alloc_statement(Statement::ExpressionStatement {
+            expression,
+            span: Span::default(),
+        })
+    });
+    ast.body = vec![Node::Statement(statements.to_vec())];
+    let options = CodegenOptions {
+        compact: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        generate(&ast, options),
+        "a**b**c;(a**b)**c;(-a)**b;a??(b||c);a=b=c"
+    );
+}
+
+#[test]
+fn test_tokens_stay_apart() {
+    assert_eq!(compact("a + +b"), "a+ +b");
+    assert_eq!(compact("a - -b"), "a- -b");
+    assert_eq!(compact("- -a"), "- -a");
+    assert_eq!(compact("a++ + b"), "a++ +b");
+    assert_eq!(compact("a in b"), "a in b");
+    assert_eq!(compact("return1 = 'x'"), "return1=\"x\"");
+}
+
+#[test]
+fn test_statements_that_would_be_misread() {
+    assert_eq!(compact_statement("(function () {})()"), "(function(){})()");
+    assert_eq!(compact_statement("({}).toString()"), "({}).toString()");
+    assert_eq!(compact_statement("class {}"), "(class{})");
+    assert_eq!(compact_statement("a.b"), "a.b");
+    assert_eq!(compact("x = () => ({})"), "x=()=>({})");
+    assert_eq!(compact("x = () => ({}).a"), "x=()=>({}).a");
+}
+
+// Function, class and object literals keep their parentheses as a callee or member object,
+// and the output parses back to the same code
+#[test]
+fn test_literal_operands_keep_parentheses() {
+    let cases = [
+        (
+            "w = (function(){ return 1 })()",
+            "w=(function(){return 1})()",
+        ),
+        ("(function(){})()", "(function(){})()"),
+        ("({}).toString()", "({}).toString()"),
+        ("x = ({}).a", "x=({}).a"),
+        ("(class{}).name", "(class{}).name"),
+        (
+            "x = (async function(){}).call(a)",
+            "x=(async function(){}).call(a)",
+        ),
+    ];
+    for (source, expected) in cases {
+        let output = compact(source);
+        assert_eq!(output, expected);
+        assert_eq!(compact(&output), output, "{source}");
+        let output = readable(source);
+        assert_eq!(readable(&output), output, "{source}");
+    }
+}
+
+#[test]
+fn test_semicolons_do_not_rely_on_asi() {
+    assert_eq!(compact("a\n(b)"), "a;b");
+    assert_eq!(
+        compact("let a = 1\nlet b = [c]\n[d]"),
+        "let a=1;let b=[c];[d]"
+    );
+    assert_eq!(compact("if (a) b\nelse c"), "if(a)b;else c");
+    assert_eq!(
+        compact("function f() { return a }"),
+        "function f(){return a}"
+    );
+    assert_eq!(compact("class A { a = 1; b }"), "class A{a=1;b}");
+    assert_eq!(compact("do a(); while (b)"), "do a();while(b)");
+}
+
+#[test]
+fn test_dangling_else_gets_braces() {
+    let mut ast = parse("if (a) if (b) c(); else d();");
+    // Move the `else` to the outer `if`, which the source can't express without braces
+    let Some(Node::Statement(statements)) = ast.body.first() else {
+        panic!("expected a statement");
+    };
+    let outer = statements[0];
+    let Statement::IfStatement(statement) = &ast[outer] else {
+        panic!("expected an if statement");
+    };
+    let inner = statement.consequence[0];
+    let Statement::IfStatement(statement) = &mut ast[inner] else {
+        panic!("expected an if statement");
+    };
+    let alternative = statement.alternative.take();
+    let Statement::IfStatement(statement) = &mut ast[outer] else {
+        panic!("expected an if statement");
+    };
+    statement.alternative = alternative;
+
+    let options = CodegenOptions {
+        compact: true,
+        ..Default::default()
+    };
+    assert_eq!(generate(&ast, options), "if(a){if(b)c()}else d()");
+}
+
+#[test]
+fn test_string_quotes() {
+    assert_eq!(compact("'a'"), "\"a\"");
+    assert_eq!(compact("'say \"hi\"'"), "'say \"hi\"'");
+    assert_eq!(compact("\"it's\""), "\"it's\"");
+    assert_eq!(compact("'a\\nb\\\\c'"), "\"a\\nb\\\\c\"");
+    let options = CodegenOptions {
+        compact: true,
+        quote: Quote::Single,
+    };
+    assert_eq!(generate(&parse("\"a\""), options.clone()), "'a'");
+    assert_eq!(generate(&parse("\"it's\""), options), "\"it's\"");
+}
+
+#[test]
+fn test_numbers() {
+    assert_eq!(compact("x = 1000000"), "x=1e6");
+    assert_eq!(readable("x = 1000000"), "x = 1000000;\n");
+    assert_eq!(compact("x = 0.5"), "x=.5");
+    assert_eq!(compact("x = 0.000001 + y"), "x=1e-6+y");
+    assert_eq!(compact("x = 0.0000015"), "x=1.5e-6");
+    assert_eq!(readable("x = 0.0000001"), "x = 1e-7;\n");
+    for source in [
+        "x = 0.000001 + y",
+        "x = 0.0000015",
+        "x = 0.5",
+        "x = 0.0000001",
+    ] {
+        let output = compact(source);
+        assert_eq!(compact(&output), output, "{source}");
+    }
+    assert_eq!(compact("x = 0x10"), "x=16");
+    assert_eq!(compact("x = 10n"), "x=10n");
+}
+
+#[test]
+fn test_readable_layout() {
+    let source = "function f(a, b = 1) { if (a) { return [a, b] } else return { a, b: 2 } }";
+    assert_eq!(
+        readable(source),
+        "function f(a, b = 1) {\n  if (a) {\n    return [a, b];\n  } else return { a, b: 2 };\n}\n"
+    );
+    assert_eq!(
+        readable("for (let i = 0, j; i < n; i++) { continue }"),
+        "for (let i = 0, j; i < n; i++) {\n  continue;\n}\n"
+    );
+    assert_eq!(
+        readable("class A extends B { static x = 1; get y() { return 2 } }"),
+        "class A extends B {\n  static x = 1;\n  get y() {\n    return 2;\n  }\n}\n"
+    );
+    assert_eq!(
+        readable("#!/usr/bin/env node\na = 1"),
+        "#!/usr/bin/env node\na = 1;\n"
+    );
+    assert_eq!(
+        compact("#!/usr/bin/env node\na = 1"),
+        "#!/usr/bin/env node\na=1"
+    );
+}
+
+#[test]
+fn test_jsx() {
+    assert_eq!(
+        compact("x = <a b=\"c\" {...d}>text {e} <f/></a>"),
+        "x=<a b=\"c\" {...d}>text {e} <f/></a>"
+    );
+    assert_eq!(compact("x = <>{'<'}</>"), "x=<>{\"<\"}</>");
+}
+
+// Printing, parsing the output and printing again gives the same code
+#[test]
+fn test_output_parses_back() {
+    let sources = [
+        "var a = 1, b = [1, , 2, ,], { c, d: [e] = f, ...g } = h;",
+        "label: for (const [k, v] of map) { if (k in v) break label; }",
+        "for (x = ('a' in b); x; x--) {}",
+        "switch (a) { case 1: b(); default: c() }",
+        "try { a() } catch ({ message }) { b(message) } finally { c() }",
+        "async function* g() { yield await a; }",
+        "x = `a${b}c${d}\\``;",
+        "x = { [a]: 1, 'b-c': 2, 3: d, get e() { return 1 }, f(g) {}, ...h };",
+        "class A { #a = 1; static { this.b = 2 } async *c() {} [d]() {} }",
+    ];
+    for source in sources {
+        for options in [
+            CodegenOptions::default(),
+            CodegenOptions {
+                compact: true,
+                ..Default::default()
+            },
+        ] {
+            let output = generate(&parse(source), options.clone());
+            assert_eq!(generate(&parse(&output), options), output, "{source}");
+        }
+    }
+}
+
+#[test]
+fn test_vlq() {
+    for (value, encoded) in [
+        (0, "A"),
+        (1, "C"),
+        (-1, "D"),
+        (15, "e"),
+        (16, "gB"),
+        (-1024, "hgC"),
+    ] {
+        let mut out = String::new();
+        encode_vlq(value, &mut out);
+        assert_eq!(out, encoded);
+        assert_eq!(decode_vlq(&mut encoded.chars()), Some(value));
+    }
+    assert_eq!(decode_vlq(&mut "g".chars()), None);
+}
+
+#[test]
+fn test_source_map_points_at_original_tokens() {
+    let source = "let a = 1;\n  foo(a);";
+    let options = CodegenOptions {
+        compact: true,
+        ..Default::default()
+    };
+    let map_options = SourceMapOptions {
+        source: "input.js".to_string(),
+        file: Some("output.js".to_string()),
+        include_content: true,
+        input_map: None,
+    };
+    let (code, map) = generate_with_source_map(&parse(source), source, options, map_options);
+    assert_eq!(code, "let a=1;foo(a)");
+
+    let foo = map.lookup(0, 8).unwrap();
+    assert_eq!((foo.original_line, foo.original_column), (1, 2));
+    assert_eq!(map.names[foo.name.unwrap() as usize], "foo");
+    let argument = map.lookup(0, 12).unwrap();
+    assert_eq!((argument.original_line, argument.original_column), (1, 6));
+    assert_eq!(map.names[argument.name.unwrap() as usize], "a");
+
+    let json = map.to_json();
+    assert!(json.starts_with(r#"{"version":3,"sources":["input.js"],"names":["a","foo"]"#));
+    assert_eq!(SourceMap::from_json(&json).unwrap(), map);
+    assert!(SourceMap::from_json(r#"{"version":2,"mappings":""}"#).is_err());
+}
+
+// Two stages of printing, the second map composed with the first, lead back to the source
+#[test]
+fn test_source_map_composes_with_input_map() {
+    let source = "let value = 1;\n\n  value;";
+    let options = CodegenOptions {
+        compact: true,
+        ..Default::default()
+    };
+    let map_options = SourceMapOptions {
+        source: "a.js".to_string(),
+        ..Default::default()
+    };
+    let (first, first_map) = generate_with_source_map(&parse(source), source, options, map_options);
+    assert_eq!(first, "let value=1;value");
+
+    let map_options = SourceMapOptions {
+        source: "a.min.js".to_string(),
+        input_map: Some(SourceMap::from_json(&first_map.to_json()).unwrap()),
+        ..Default::default()
+    };
+    let (second, map) = generate_with_source_map(
+        &parse(&first),
+        &first,
+        CodegenOptions::default(),
+        map_options,
+    );
+    assert_eq!(second, "let value = 1;\nvalue;\n");
+    assert_eq!(map.sources, ["a.js"]);
+    let value = map.lookup(1, 0).unwrap();
+    assert_eq!((value.original_line, value.original_column), (2, 2));
+    assert_eq!(map.names[value.name.unwrap() as usize], "value");
+}
+
+// A segment with only a generated column ends the mapping before it
+#[test]
+fn test_source_map_keeps_unmapped_segments() {
+    let json = r#"{"version":3,"sources":["a.js"],"names":[],"mappings":"AAAA,EAAC,E;AACA"}"#;
+    let map = SourceMap::from_json(json).unwrap();
+    assert_eq!(map.mappings.len(), 4);
+    assert_eq!(map.mappings[2].source, None);
+    let mapped = map.lookup(0, 3).unwrap();
+    assert_eq!((mapped.original_line, mapped.original_column), (0, 1));
+    assert_eq!(map.lookup(0, 4), None);
+    assert_eq!(map.lookup(0, 9), None);
+    assert!(map.lookup(1, 0).is_some());
+    assert_eq!(SourceMap::from_json(&map.to_json()).unwrap(), map);
+
+    // Composing through the unmapped range doesn't attribute it to the mapping before it
+    let outer = SourceMap {
+        sources: vec!["b.js".to_string()],
+        mappings: vec![Mapping {
+            generated_line: 0,
+            generated_column: 0,
+            source: Some(0),
+            original_line: 0,
+            original_column: 5,
+            name: None,
+        }],
+        ..Default::default()
+    };
+    let composed = outer.compose(&map);
+    assert_eq!(composed.mappings[0].source, None);
+    assert_eq!(composed.lookup(0, 0), None);
+}
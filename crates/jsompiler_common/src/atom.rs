@@ -0,0 +1,61 @@
+use std::collections::HashMap;
+use std::ops::Index;
+use std::sync::Arc;
+
+// An interned string: an index into the `Atoms` table of the AST it was parsed into. Atoms
+// are `Copy`, compare as integers and cost nothing to clone into the AST. Only the table
+// knows the text, so an atom means nothing without the table it came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Atom(u32);
+
+// The strings behind a set of atoms, each stored once. The table lives as long as whatever
+// owns it, usually an `Ast`, so memory isn't held for the life of the process. Strings are
+// only ever appended, which means a table extended from a copy of another gives every atom
+// of the original the same text.
+#[derive(Debug, Clone, Default)]
+pub struct Atoms {
+    strings: Vec<Arc<str>>,
+    indices: HashMap<Arc<str>, Atom>,
+}
+
+impl Atoms {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn intern(&mut self, text: &str) -> Atom {
+        if let Some(&atom) = self.indices.get(text) {
+            return atom;
+        }
+        let atom = Atom(u32::try_from(self.strings.len()).expect("too many atoms"));
+        let text: Arc<str> = text.into();
+        self.strings.push(text.clone());
+        self.indices.insert(text, atom);
+        atom
+    }
+
+    // The atom of `text` if it was interned, without adding it
+    pub fn find(&self, text: &str) -> Option<Atom> {
+        self.indices.get(text).copied()
+    }
+
+    pub fn get(&self, atom: Atom) -> &str {
+        &self.strings[atom.0 as usize]
+    }
+
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+}
+
+impl Index<Atom> for Atoms {
+    type Output = str;
+
+    fn index(&self, atom: Atom) -> &str {
+        self.get(atom)
+    }
+}
@@ -1,9 +1,14 @@
+mod atom;
+
+pub use atom::{Atom, Atoms};
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum ErrorKind {
     LexerError,
     SyntaxError,
     SemanticError,
     UnexpectedToken,
+    RuntimeError, // An exception nothing caught
 }
 
 #[derive(Debug, Clone, PartialEq)]
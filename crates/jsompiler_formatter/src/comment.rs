@@ -0,0 +1,247 @@
+use crate::Formatter;
+use crate::doc::{self, Doc};
+use jsompiler_parser::{Comment, CommentKind, CommentPlacement, ExpressionId, NodeId, Span};
+
+impl<'a> Formatter<'a> {
+    // `doc` with the comments attached before and after `node`. Dangling comments are left to
+    // the node itself, which prints them between its brackets.
+    pub fn with_comments(&mut self, node: NodeId, span: Span, doc: Doc) -> Doc {
+        let Some(indices) = self.comments.get(&node).cloned() else {
+            return doc;
+        };
+        let ast = self.ast;
+        let leading: Vec<usize> = indices
+            .iter()
+            .copied()
+            .filter(|&index| placement(&ast.comments[index]) == Some(CommentPlacement::Leading))
+            .filter(|&index| !self.printed[index])
+            .collect();
+        let trailing: Vec<usize> = indices
+            .iter()
+            .copied()
+            .filter(|&index| placement(&ast.comments[index]) == Some(CommentPlacement::Trailing))
+            .filter(|&index| !self.printed[index])
+            .collect();
+
+        let mut parts = Vec::new();
+        for (position, &index) in leading.iter().enumerate() {
+            self.printed[index] = true;
+            let comment = &ast.comments[index];
+            let next = leading
+                .get(position + 1)
+                .map_or(span.start, |&next| ast.comments[next].span.start);
+            parts.push(self.comment(comment));
+            match self.line_breaks(comment.span.end, next) {
+                0 if comment.kind == CommentKind::Block => parts.push(doc::text(" ")),
+                0 | 1 => parts.push(Doc::HardLine),
+                _ => parts.extend([Doc::HardLine, Doc::HardLine]),
+            }
+        }
+        parts.push(doc);
+        let mut end = span.end;
+        for index in trailing {
+            self.printed[index] = true;
+            let comment = &ast.comments[index];
+            let printed = self.comment(comment);
+            // A comment on a line of its own stays there
+            let line_breaks = self.line_breaks(end, comment.span.start);
+            end = comment.span.end;
+            if line_breaks > 0 {
+                let mut suffix = vec![Doc::HardLine; line_breaks.min(2)];
+                suffix.push(printed);
+                parts.extend([doc::line_suffix(doc::concat(suffix)), Doc::BreakParent]);
+            } else if comment.kind == CommentKind::Line {
+                let suffix = doc::concat(vec![doc::text(" "), printed]);
+                parts.extend([doc::line_suffix(suffix), Doc::BreakParent]);
+            } else {
+                parts.extend([doc::text(" "), printed]);
+            }
+        }
+        doc::concat(parts)
+    }
+
+    // Comments inside `span` that nothing printed yet, as in `{ /* empty */ }`
+    pub fn dangling_comments(&mut self, span: Span) -> Vec<&'a Comment> {
+        let ast = self.ast;
+        let mut comments = Vec::new();
+        for (index, comment) in ast.comments.iter().enumerate() {
+            if !self.printed[index]
+                && span.start <= comment.span.start
+                && comment.span.end <= span.end
+            {
+                self.printed[index] = true;
+                comments.push(comment);
+            }
+        }
+        comments
+    }
+
+    // `open` and `close` around the dangling comments of `span`, for an empty list or block
+    pub fn empty_bracketed(&mut self, open: &str, span: Span, close: &str) -> Doc {
+        let comments = self.dangling_comments(span);
+        if comments.is_empty() {
+            return doc::text(format!("{open}{close}"));
+        }
+        let has_line_comment = comments
+            .iter()
+            .any(|comment| comment.kind == CommentKind::Line);
+        let printed = comments
+            .into_iter()
+            .map(|comment| self.comment(comment))
+            .collect();
+        if has_line_comment {
+            doc::concat(vec![
+                doc::text(open),
+                doc::indent(doc::concat(vec![
+                    Doc::HardLine,
+                    doc::join(Doc::HardLine, printed),
+                ])),
+                Doc::HardLine,
+                doc::text(close),
+            ])
+        } else {
+            // Braces are padded, as `{/*` would not lex as a comment after a brace
+            let padding = if open == "{" { " " } else { "" };
+            doc::concat(vec![
+                doc::text(format!("{open}{padding}")),
+                doc::join(doc::text(" "), printed),
+                doc::text(format!("{padding}{close}")),
+            ])
+        }
+    }
+
+    // What goes before an item of a list like the properties of an object, which carry no
+    // comments of their own: the comments in the `gap` before it, and a blank line if the
+    // source had one. Comments trailing the previous item are left to it. Called before the
+    // item is printed, so the comments are not also taken by a node inside it.
+    pub fn list_item_prefix(&mut self, gap: Span, first: bool) -> Doc {
+        let ast = self.ast;
+        let mut previous_end = gap.start;
+        let mut leading = Vec::new();
+        for (index, comment) in ast.comments.iter().enumerate() {
+            if self.printed[index] || comment.span.start < gap.start || gap.end < comment.span.end {
+                continue;
+            }
+            if placement(comment) == Some(CommentPlacement::Trailing) {
+                previous_end = previous_end.max(comment.span.end);
+            } else {
+                self.printed[index] = true;
+                leading.push(comment);
+            }
+        }
+
+        let mut parts = Vec::new();
+        let next = leading
+            .first()
+            .map_or(gap.end, |comment| comment.span.start);
+        if !first && self.line_breaks(previous_end, next) > 1 {
+            parts.push(Doc::HardLine);
+        }
+        for (position, comment) in leading.iter().enumerate() {
+            parts.push(self.comment(comment));
+            let next = leading
+                .get(position + 1)
+                .map_or(gap.end, |next| next.span.start);
+            match self.line_breaks(comment.span.end, next) {
+                0 if comment.kind == CommentKind::Block => parts.push(doc::text(" ")),
+                _ => parts.push(Doc::HardLine),
+            }
+        }
+        doc::concat(parts)
+    }
+
+    // Comments nothing was attached to or printed, kept at the end rather than lost
+    pub fn remaining_comments(&mut self) -> Vec<Doc> {
+        let ast = self.ast;
+        let mut docs = Vec::new();
+        for (index, comment) in ast.comments.iter().enumerate() {
+            if !self.printed[index] {
+                self.printed[index] = true;
+                docs.push(self.comment(comment));
+            }
+        }
+        docs
+    }
+
+    // A block comment whose lines all start with `*`, like a JSDoc comment, is reindented
+    // to line up with the code. Other comments are kept as written.
+    pub fn comment(&self, comment: &Comment) -> Doc {
+        match comment.kind {
+            CommentKind::Line => doc::text(format!("//{}", comment.text.trim_end())),
+            CommentKind::Block => {
+                let lines: Vec<&str> = comment.text.split('\n').collect();
+                let (last, middle) = lines[1..].split_last().unwrap_or((&"", &[]));
+                let is_starred = lines.len() > 1
+                    && middle
+                        .iter()
+                        .chain([last].into_iter().filter(|last| !last.trim().is_empty()))
+                        .all(|line| line.trim_start().starts_with('*'));
+                if !is_starred {
+                    return doc::text(format!("/*{}*/", comment.text));
+                }
+                let mut parts = vec![doc::text(format!("/*{}", lines[0].trim_end()))];
+                for line in middle {
+                    parts.push(Doc::HardLine);
+                    parts.push(doc::text(format!(" {}", line.trim())));
+                }
+                parts.push(Doc::HardLine);
+                parts.push(doc::text(format!(" {}*/", last.trim_start())));
+                doc::concat(parts)
+            }
+        }
+    }
+
+    // Whether the leading comments printed before `expression`, or before `statement` around
+    // it, leave a line break ahead of the code, as a line comment does
+    pub fn starts_with_line_break(
+        &self,
+        statement: Option<(NodeId, Span)>,
+        expression: ExpressionId,
+    ) -> bool {
+        let ast = self.ast;
+        let mut nodes: Vec<(NodeId, Span)> = statement.into_iter().collect();
+        let mut id = Some(expression);
+        while let Some(operand) = id {
+            nodes.push((operand.into(), ast[operand].span()));
+            id = self.left_operand(operand);
+        }
+        nodes.into_iter().any(|(node, span)| {
+            self.comments
+                .get(&node)
+                .into_iter()
+                .flatten()
+                .any(|&index| {
+                    let comment = &ast.comments[index];
+                    !self.printed[index]
+                        && placement(comment) == Some(CommentPlacement::Leading)
+                        && (comment.kind == CommentKind::Line
+                            || comment.text.contains('\n')
+                            || self.has_line_break(comment.span.end, span.start))
+                })
+        })
+    }
+
+    // Where the comments attached to `node` start and end, to tell whether the source had a
+    // blank line between it and its neighbours
+    pub fn extended_span(&self, node: NodeId, span: Span) -> Span {
+        let ast = self.ast;
+        let mut extended = span;
+        for &index in self.comments.get(&node).into_iter().flatten() {
+            let comment = &ast.comments[index];
+            match placement(comment) {
+                Some(CommentPlacement::Leading) => {
+                    extended.start = extended.start.min(comment.span.start)
+                }
+                Some(CommentPlacement::Trailing) => {
+                    extended.end = extended.end.max(comment.span.end)
+                }
+                _ => {}
+            }
+        }
+        extended
+    }
+}
+
+fn placement(comment: &Comment) -> Option<CommentPlacement> {
+    comment.attachment.map(|(_, placement)| placement)
+}
@@ -0,0 +1,208 @@
+// The document the formatter builds before choosing line breaks. A group is printed on one
+// line if it fits in the remaining width, otherwise every line break directly inside it
+// becomes a newline and its child groups get the same choice.
+#[derive(Debug, Clone)]
+pub enum Doc {
+    Text(String),
+    Concat(Vec<Doc>),
+    Group { contents: Box<Doc>, breaks: bool }, // `breaks` when a hard line forces it open
+    Indent(Box<Doc>),
+    Line,     // A space, or a newline when the group breaks
+    SoftLine, // Nothing, or a newline when the group breaks
+    HardLine, // Always a newline, breaking every group around it
+    IfBreak { broken: Box<Doc>, flat: Box<Doc> },
+    LineSuffix(Box<Doc>), // Deferred to the end of the line, for `// comments`
+    BreakParent,          // Breaks every group around it without printing anything
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Flat,
+    Break,
+}
+
+pub fn text(text: impl Into<String>) -> Doc {
+    Doc::Text(text.into())
+}
+
+pub fn concat(docs: Vec<Doc>) -> Doc {
+    Doc::Concat(docs)
+}
+
+pub fn group(contents: Doc) -> Doc {
+    let breaks = contents.will_break();
+    Doc::Group {
+        contents: Box::new(contents),
+        breaks,
+    }
+}
+
+pub fn indent(contents: Doc) -> Doc {
+    Doc::Indent(Box::new(contents))
+}
+
+pub fn line_suffix(contents: Doc) -> Doc {
+    Doc::LineSuffix(Box::new(contents))
+}
+
+pub fn if_break(broken: Doc, flat: Doc) -> Doc {
+    Doc::IfBreak {
+        broken: Box::new(broken),
+        flat: Box::new(flat),
+    }
+}
+
+// `(contents)`, with the contents on lines of their own when `broken`
+pub fn in_parentheses(contents: Doc, broken: bool) -> Doc {
+    if broken {
+        concat(vec![
+            text("("),
+            indent(concat(vec![Doc::HardLine, contents])),
+            Doc::HardLine,
+            text(")"),
+        ])
+    } else {
+        concat(vec![text("("), contents, text(")")])
+    }
+}
+
+// `docs` separated by `separator`
+pub fn join(separator: Doc, docs: Vec<Doc>) -> Doc {
+    let mut joined = Vec::with_capacity(docs.len() * 2);
+    for (index, doc) in docs.into_iter().enumerate() {
+        if index > 0 {
+            joined.push(separator.clone());
+        }
+        joined.push(doc);
+    }
+    Doc::Concat(joined)
+}
+
+impl Doc {
+    // Whether a hard line inside forces the groups around it to break. Groups already know
+    // the answer for their contents, so this only looks down to the nearest groups.
+    pub fn will_break(&self) -> bool {
+        match self {
+            Doc::HardLine | Doc::BreakParent => true,
+            Doc::Group { breaks, .. } => *breaks,
+            Doc::Concat(docs) => docs.iter().any(Doc::will_break),
+            Doc::Indent(contents) => contents.will_break(),
+            Doc::IfBreak { broken, .. } => broken.will_break(),
+            Doc::Text(_) | Doc::Line | Doc::SoftLine | Doc::LineSuffix(_) => false,
+        }
+    }
+
+    // The first character this document prints, if it prints any
+    pub fn first_char(&self) -> Option<char> {
+        match self {
+            Doc::Text(text) => text.chars().next(),
+            Doc::Concat(docs) => docs.iter().find_map(Doc::first_char),
+            Doc::Group { contents, .. } | Doc::Indent(contents) => contents.first_char(),
+            Doc::IfBreak { flat, .. } => flat.first_char(),
+            _ => None,
+        }
+    }
+}
+
+// Print `doc`, breaking groups that don't fit in `width` columns. Each indentation level is
+// `indent_unit`, which counts `indent_width` columns.
+pub fn print(doc: &Doc, width: usize, indent_unit: &str, indent_width: usize) -> String {
+    let mut output = String::new();
+    let mut column = 0;
+    let mut line_suffixes: Vec<(usize, Mode, &Doc)> = Vec::new();
+    let mut stack: Vec<(usize, Mode, &Doc)> = vec![(0, Mode::Break, doc)];
+
+    loop {
+        let Some((level, mode, doc)) = stack.pop() else {
+            if line_suffixes.is_empty() {
+                break;
+            }
+            stack.extend(line_suffixes.drain(..).rev());
+            continue;
+        };
+        match doc {
+            Doc::Text(text) => {
+                output.push_str(text);
+                // Template literals can span lines
+                column = match text.rfind('\n') {
+                    Some(index) => text[index + 1..].chars().count(),
+                    None => column + text.chars().count(),
+                };
+            }
+            Doc::Concat(docs) => {
+                for doc in docs.iter().rev() {
+                    stack.push((level, mode, doc));
+                }
+            }
+            Doc::Group { contents, breaks } => {
+                let flat = (level, Mode::Flat, contents.as_ref());
+                let remaining = width as isize - column as isize;
+                if !breaks && (mode == Mode::Flat || fits(flat, &stack, remaining)) {
+                    stack.push(flat);
+                } else {
+                    stack.push((level, Mode::Break, contents));
+                }
+            }
+            Doc::Indent(contents) => stack.push((level + 1, mode, contents)),
+            Doc::IfBreak { broken, flat } => {
+                stack.push((level, mode, if mode == Mode::Break { broken } else { flat }))
+            }
+            Doc::Line if mode == Mode::Flat => {
+                output.push(' ');
+                column += 1;
+            }
+            Doc::SoftLine if mode == Mode::Flat => {}
+            // Print the line suffixes first, then come back to the line break
+            Doc::Line | Doc::SoftLine | Doc::HardLine if !line_suffixes.is_empty() => {
+                stack.push((level, mode, doc));
+                stack.extend(line_suffixes.drain(..).rev());
+            }
+            Doc::Line | Doc::SoftLine | Doc::HardLine => {
+                let trimmed = output.trim_end_matches([' ', '\t']).len();
+                output.truncate(trimmed);
+                output.push('\n');
+                output.push_str(&indent_unit.repeat(level));
+                column = level * indent_width;
+            }
+            Doc::LineSuffix(contents) => line_suffixes.push((level, mode, contents)),
+            Doc::BreakParent => {}
+        }
+    }
+    output
+}
+
+// Whether `next` printed flat, followed by the rest of the line, fits in `remaining` columns.
+// The rest of the line is whatever is on the stack up to its first line break.
+fn fits(next: (usize, Mode, &Doc), rest: &[(usize, Mode, &Doc)], mut remaining: isize) -> bool {
+    let mut stack = vec![next];
+    let mut rest = rest.iter().rev();
+    while remaining >= 0 {
+        let Some((level, mode, doc)) = stack.pop().or_else(|| rest.next().copied()) else {
+            return true;
+        };
+        match doc {
+            Doc::Text(text) => match text.find('\n') {
+                Some(index) => return remaining >= text[..index].chars().count() as isize,
+                None => remaining -= text.chars().count() as isize,
+            },
+            Doc::Concat(docs) => {
+                for doc in docs.iter().rev() {
+                    stack.push((level, mode, doc));
+                }
+            }
+            Doc::Group { contents, breaks } => {
+                let mode = if *breaks { Mode::Break } else { mode };
+                stack.push((level, mode, contents));
+            }
+            Doc::Indent(contents) => stack.push((level + 1, mode, contents)),
+            Doc::IfBreak { broken, flat } => {
+                stack.push((level, mode, if mode == Mode::Break { broken } else { flat }))
+            }
+            Doc::Line if mode == Mode::Flat => remaining -= 1,
+            Doc::SoftLine if mode == Mode::Flat => {}
+            Doc::Line | Doc::SoftLine | Doc::HardLine => return true,
+            Doc::LineSuffix(_) | Doc::BreakParent => {}
+        }
+    }
+    false
+}
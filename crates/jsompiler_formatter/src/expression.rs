@@ -0,0 +1,614 @@
+use crate::doc::{self, Doc};
+use crate::{Formatter, Quote, TrailingCommas};
+use jsompiler_codegen::{Precedence, is_bare_operand, number_text, quote_string};
+use jsompiler_lexer::symbol::{LiteralToken, NumberLiteral};
+use jsompiler_parser::{
+    Expression, ExpressionId, Parameter, Property, PropertyKey, Span, Statement, StatementId,
+    TemplateLiteral,
+};
+
+impl<'a> Formatter<'a> {
+    // An expression where it has to bind at least as tightly as `precedence`
+    pub fn expression(&mut self, id: ExpressionId, precedence: Precedence) -> Doc {
+        let expression = &self.ast[id];
+        let is_in = matches!(expression, Expression::Binary { op, .. } if op.text == "in");
+        let printed = if self.precedence(expression) < precedence || (is_in && self.forbid_in) {
+            self.parenthesized(id)
+        } else {
+            self.expression_unwrapped(id)
+        };
+        self.with_comments(id.into(), expression.span(), printed)
+    }
+
+    pub fn parenthesized(&mut self, id: ExpressionId) -> Doc {
+        let forbid_in = self.forbid_in;
+        self.forbid_in = false;
+        let printed = self.expression_unwrapped(id);
+        self.forbid_in = forbid_in;
+        doc::in_parentheses(printed, false)
+    }
+
+    fn precedence(&self, expression: &Expression) -> Precedence {
+        match expression {
+            Expression::Literal {
+                value: LiteralToken::Number(NumberLiteral::Value(value)),
+                ..
+            } if value.is_sign_negative() => Precedence::Prefix,
+            Expression::Unary { op_type, .. } if op_type == "Postfix" => Precedence::Postfix,
+            Expression::Unary { .. } | Expression::AwaitExpression { .. } => Precedence::Prefix,
+            Expression::Binary { op, .. } => Precedence::of_binary(&op.text),
+            Expression::MemberAccess { .. } | Expression::FunctionCall { .. } => Precedence::Call,
+            Expression::SpreadElement { .. } | Expression::ArrowFunctionExpression { .. } => {
+                Precedence::Assignment
+            }
+            _ => Precedence::Primary,
+        }
+    }
+
+    // The innermost expression at the start of `id`, the one printed first
+    fn leftmost(&self, mut id: ExpressionId) -> &'a Expression {
+        while let Some(operand) = self.left_operand(id) {
+            id = operand;
+        }
+        &self.ast[id]
+    }
+
+    // The operand printed at the start of `id` without parentheses, if any
+    pub fn left_operand(&self, id: ExpressionId) -> Option<ExpressionId> {
+        let ast = self.ast;
+        match &ast[id] {
+            Expression::Binary { left, .. } => Some(*left),
+            Expression::MemberAccess { object, .. } if is_bare_operand(&ast[*object]) => {
+                Some(*object)
+            }
+            Expression::FunctionCall { callee, .. } if is_bare_operand(&ast[*callee]) => {
+                Some(*callee)
+            }
+            Expression::Unary { op_type, expr, .. } if op_type == "Postfix" => Some(*expr),
+            _ => None,
+        }
+    }
+
+    // Whether an expression would start with `function`, `async function` or `class`
+    pub fn starts_like_declaration(&self, id: ExpressionId) -> bool {
+        matches!(
+            self.leftmost(id),
+            Expression::FunctionExpression(_)
+                | Expression::GeneratorExpression(_)
+                | Expression::AsyncFunctionExpression(_)
+                | Expression::AsyncGeneratorExpression(_)
+                | Expression::ClassExpression(_)
+        )
+    }
+
+    // Whether an expression statement starting with `id` would be read as something else: a
+    // declaration, a block, or a `let` declaration for `let[0]`
+    pub fn starts_ambiguously(&self, id: ExpressionId) -> bool {
+        self.starts_like_declaration(id)
+            || match self.leftmost(id) {
+                Expression::ObjectLiteral { .. } => true,
+                Expression::Identifier(identifier) => &self.ast[identifier.value] == "let",
+                _ => false,
+            }
+    }
+
+    fn expression_unwrapped(&mut self, id: ExpressionId) -> Doc {
+        let ast = self.ast;
+        match &ast[id] {
+            Expression::Identifier(identifier) | Expression::PrivateIdentifier(identifier) => {
+                doc::text(&self.ast[identifier.value])
+            }
+            Expression::Literal { value, span } => self.literal(value, *span),
+            Expression::TemplateLiteral(template) => self.template(template),
+            Expression::ThisExpression { .. } => doc::text("this"),
+            Expression::SpreadElement { argument, .. } => doc::concat(vec![
+                doc::text("..."),
+                self.expression(*argument, Precedence::Assignment),
+            ]),
+            Expression::MemberAccess {
+                object,
+                property,
+                computed,
+                ..
+            } => {
+                // `1.toString()` would read the dot as a decimal point
+                let object = match &ast[*object] {
+                    Expression::Literal {
+                        value: LiteralToken::Number(_),
+                        span,
+                    } if self.raw(*span).bytes().all(|byte| byte.is_ascii_digit()) => {
+                        self.parenthesized(*object)
+                    }
+                    expression if !is_bare_operand(expression) => self.parenthesized(*object),
+                    _ => self.expression(*object, Precedence::Call),
+                };
+                let property = if *computed {
+                    doc::concat(vec![
+                        doc::text("["),
+                        self.expression(*property, Precedence::Lowest),
+                        doc::text("]"),
+                    ])
+                } else {
+                    doc::concat(vec![
+                        doc::text("."),
+                        self.expression(*property, Precedence::Primary),
+                    ])
+                };
+                doc::concat(vec![object, property])
+            }
+            Expression::Unary {
+                op, op_type, expr, ..
+            } => {
+                if op_type == "Postfix" {
+                    doc::concat(vec![
+                        self.expression(*expr, Precedence::Call),
+                        doc::text(op.text.as_str()),
+                    ])
+                } else {
+                    let operand = self.expression(*expr, Precedence::Prefix);
+                    // `- -a` and `+ +a` would otherwise read as `--a` and `++a`
+                    let space = op.text.starts_with(char::is_alphabetic)
+                        || matches!(
+                            (op.text.as_str(), operand.first_char()),
+                            ("-" | "--", Some('-')) | ("+" | "++", Some('+'))
+                        );
+                    doc::concat(vec![
+                        doc::text(op.text.as_str()),
+                        doc::text(if space { " " } else { "" }),
+                        operand,
+                    ])
+                }
+            }
+            Expression::Binary {
+                left, op, right, ..
+            } if Precedence::of_binary(&op.text) == Precedence::Assignment => {
+                let target = self.expression(*left, Precedence::Call);
+                let value = self.expression(*right, Precedence::Assignment);
+                self.assigned(target, &format!(" {}", op.text), *right, value)
+            }
+            Expression::Binary { .. } => self.binary(id),
+            Expression::FunctionCall { callee, args, .. } => {
+                let callee = if is_bare_operand(&ast[*callee]) {
+                    self.expression(*callee, Precedence::Call)
+                } else {
+                    self.parenthesized(*callee)
+                };
+                let arguments = self.arguments(args);
+                doc::concat(vec![callee, arguments])
+            }
+            Expression::ArrayLiteral { elements, span } => {
+                if elements.is_empty() {
+                    return self.empty_bracketed("[", *span, "]");
+                }
+                let printed = elements
+                    .iter()
+                    .map(|&element| {
+                        if is_hole(&ast[element]) {
+                            doc::text("")
+                        } else {
+                            self.expression(element, Precedence::Assignment)
+                        }
+                    })
+                    .collect();
+                // A trailing comma is ignored, so a hole at the end needs one more
+                let trailing = if elements.last().is_some_and(|&last| is_hole(&ast[last])) {
+                    doc::text(",")
+                } else {
+                    self.trailing_comma(TrailingCommas::Es5)
+                };
+                self.bracketed("[", printed, trailing, "]")
+            }
+            Expression::ObjectLiteral { properties, span } => {
+                if properties.is_empty() {
+                    return self.empty_bracketed("{", *span, "}");
+                }
+                let mut previous_end = span.start + 1;
+                let mut printed = Vec::new();
+                for (index, property) in properties.iter().enumerate() {
+                    let property_span = property_span(Some(property), ast);
+                    let gap = Span::new(previous_end, property_span.start);
+                    let prefix = self.list_item_prefix(gap, index == 0);
+                    printed.push(doc::concat(vec![prefix, self.property(property)]));
+                    previous_end = property_span.end;
+                }
+                // An object the source had broken after the `{` stays broken
+                let first = property_span(properties.first(), ast);
+                let expanded = self.has_line_break(span.start, first.start);
+                self.object("{", printed, expanded, "}")
+            }
+            Expression::AwaitExpression { argument, .. } => doc::concat(vec![
+                doc::text("await "),
+                self.expression(*argument, Precedence::Prefix),
+            ]),
+            Expression::ClassExpression(class) => self.class(
+                class.identifier.as_ref(),
+                class.heritage,
+                &class.body,
+                class.body_span,
+            ),
+            Expression::FunctionExpression(function) => self.function(
+                function.name.as_ref(),
+                &function.parameters,
+                &function.body,
+                false,
+                false,
+                function.span,
+            ),
+            Expression::GeneratorExpression(function) => self.function(
+                function.name.as_ref(),
+                &function.parameters,
+                &function.body,
+                false,
+                true,
+                function.span,
+            ),
+            Expression::AsyncFunctionExpression(function) => self.function(
+                function.name.as_ref(),
+                &function.parameters,
+                &function.body,
+                true,
+                false,
+                function.span,
+            ),
+            Expression::AsyncGeneratorExpression(function) => self.function(
+                function.name.as_ref(),
+                &function.parameters,
+                &function.body,
+                true,
+                true,
+                function.span,
+            ),
+            Expression::RegularExpressionLiteral { pattern, flags, .. } => {
+                doc::text(format!("/{pattern}/{flags}"))
+            }
+            Expression::ArrowFunctionExpression {
+                parameters,
+                body,
+                span,
+            } => self.arrow(parameters, body, *span),
+            Expression::JSXElement(element) => self.jsx_element(element),
+            Expression::JSXFragment(fragment) => self.jsx_fragment(fragment),
+        }
+    }
+
+    // A chain of operators of the same precedence, as in `a + b - c`, broken before each
+    // operand when it doesn't fit
+    fn binary(&mut self, id: ExpressionId) -> Doc {
+        let ast = self.ast;
+        let Expression::Binary { op, .. } = &ast[id] else {
+            return self.expression_unwrapped(id);
+        };
+        let precedence = Precedence::of_binary(&op.text);
+
+        // Walk down the left operands of the same precedence, unless they need parentheses
+        let mut chain = Vec::new();
+        let mut first = id;
+        while let Expression::Binary {
+            left, op, right, ..
+        } = &ast[first]
+        {
+            if Precedence::of_binary(&op.text) != precedence {
+                break;
+            }
+            chain.push((op.text.as_str(), *right, first));
+            first = *left;
+            if precedence.is_right_associative() {
+                break;
+            }
+        }
+        chain.reverse();
+
+        let (first_op, _, _) = chain[0];
+        let mut left_precedence = if precedence.is_right_associative() {
+            precedence.next()
+        } else {
+            precedence
+        };
+        // `-a ** b` is a syntax error, the operand has to be `(-a) ** b`
+        if first_op == "**" {
+            left_precedence = Precedence::Postfix;
+        }
+        let head = if self.mixes_logical(first_op, first) {
+            self.parenthesized(first)
+        } else {
+            self.expression(first, left_precedence)
+        };
+
+        let mut rest = Vec::new();
+        for (position, &(op, right, node)) in chain.iter().enumerate() {
+            let right_precedence = if precedence.is_right_associative() {
+                precedence
+            } else {
+                precedence.next()
+            };
+            let operand = if self.mixes_logical(op, right) {
+                self.parenthesized(right)
+            } else {
+                self.expression(right, right_precedence)
+            };
+            let part = doc::concat(vec![doc::text(format!(" {op}")), Doc::Line, operand]);
+            // The inner links of the chain are nodes of their own, with their own comments
+            rest.push(if position + 1 < chain.len() {
+                self.with_comments(node.into(), ast[node].span(), part)
+            } else {
+                part
+            });
+        }
+        doc::group(doc::concat(vec![head, doc::indent(doc::concat(rest))]))
+    }
+
+    // `??` can't be mixed with `||` or `&&` without parentheses
+    fn mixes_logical(&self, op: &str, operand: ExpressionId) -> bool {
+        op == "??"
+            && matches!(&self.ast[operand], Expression::Binary { op, .. } if op.text == "||" || op.text == "&&")
+    }
+
+    // Arguments of a call. When only the last one is a function, object or array, it is
+    // printed right after the others so its body can break instead, as in
+    // `it("works", () => {` or `assign(target, {`.
+    fn arguments(&mut self, args: &[ExpressionId]) -> Doc {
+        if args.is_empty() {
+            return doc::text("()");
+        }
+        let ast = self.ast;
+        let huggable = |id: ExpressionId| {
+            matches!(
+                &ast[id],
+                Expression::FunctionExpression(_)
+                    | Expression::AsyncFunctionExpression(_)
+                    | Expression::GeneratorExpression(_)
+                    | Expression::AsyncGeneratorExpression(_)
+                    | Expression::ArrowFunctionExpression { .. }
+                    | Expression::ObjectLiteral { .. }
+                    | Expression::ArrayLiteral { .. }
+            )
+        };
+        let (last, others) = args.split_last().expect("arguments are not empty");
+        let printed: Vec<Doc> = args
+            .iter()
+            .map(|&arg| self.expression(arg, Precedence::Assignment))
+            .collect();
+        let others_are_short = !others.iter().any(|&arg| huggable(arg))
+            && printed[..others.len()].iter().all(|doc| !doc.will_break());
+        if huggable(*last) && others_are_short {
+            return doc::concat(vec![
+                doc::text("("),
+                doc::join(doc::text(", "), printed),
+                doc::text(")"),
+            ]);
+        }
+        let trailing = self.trailing_comma(TrailingCommas::All);
+        self.bracketed("(", printed, trailing, ")")
+    }
+
+    // Properties between braces, with spaces inside when on one line
+    pub fn object(&self, open: &str, items: Vec<Doc>, expanded: bool, close: &str) -> Doc {
+        let mut contents = vec![
+            doc::text(open),
+            doc::indent(doc::concat(vec![
+                Doc::Line,
+                doc::join(doc::concat(vec![doc::text(","), Doc::Line]), items),
+                self.trailing_comma(TrailingCommas::Es5),
+            ])),
+            Doc::Line,
+            doc::text(close),
+        ];
+        if expanded {
+            contents.push(Doc::BreakParent);
+        }
+        doc::group(doc::concat(contents))
+    }
+
+    fn arrow(&mut self, parameters: &[Parameter], body: &[StatementId], span: Span) -> Doc {
+        let ast = self.ast;
+        let parameters = self.parameters(parameters);
+        let body = match body {
+            [id] => match &ast[*id] {
+                Statement::ExpressionStatement { expression, .. } => {
+                    // A body starting with `{` would be read as a block, and one after a
+                    // comment ending its line is kept on the line of the arrow
+                    let statement = ((*id).into(), ast[*id].span());
+                    let breaks = self.starts_with_line_break(Some(statement), *expression);
+                    if breaks
+                        || matches!(self.leftmost(*expression), Expression::ObjectLiteral { .. })
+                    {
+                        let printed = self.expression(*expression, Precedence::Lowest);
+                        let printed = self.with_comments((*id).into(), ast[*id].span(), printed);
+                        doc::in_parentheses(printed, breaks)
+                    } else {
+                        let printed = self.expression(*expression, Precedence::Assignment);
+                        self.with_comments((*id).into(), ast[*id].span(), printed)
+                    }
+                }
+                _ => self.braced(body, span),
+            },
+            _ => self.braced(body, span),
+        };
+        doc::concat(vec![parameters, doc::text(" => "), body])
+    }
+
+    fn property(&mut self, property: &'a Property) -> Doc {
+        match property {
+            Property::KeyValue { key, value, .. } => {
+                let key = self.property_key(key);
+                doc::concat(vec![
+                    key,
+                    doc::text(": "),
+                    self.expression(*value, Precedence::Assignment),
+                ])
+            }
+            Property::Shorthand(identifier) => doc::text(&self.ast[identifier.value]),
+            Property::Method {
+                key,
+                params,
+                body,
+                span,
+                ..
+            } => doc::concat(vec![
+                self.property_key(key),
+                self.parameters(params),
+                doc::text(" "),
+                self.braced(body, *span),
+            ]),
+            Property::Getter {
+                key, body, span, ..
+            } => doc::concat(vec![
+                doc::text("get "),
+                self.property_key(key),
+                doc::text("() "),
+                self.braced(std::slice::from_ref(body), *span),
+            ]),
+            Property::Setter {
+                key,
+                param,
+                body,
+                span,
+                ..
+            } => doc::concat(vec![
+                doc::text("set "),
+                self.property_key(key),
+                self.parameters(std::slice::from_ref(param)),
+                doc::text(" "),
+                self.braced(std::slice::from_ref(body), *span),
+            ]),
+            Property::Spread(spread) => self.expression(*spread, Precedence::Assignment),
+        }
+    }
+
+    pub fn property_key(&mut self, key: &PropertyKey) -> Doc {
+        match key {
+            PropertyKey::Identifier(identifier) => doc::text(&self.ast[identifier.value]),
+            PropertyKey::StringLiteral { value, span } => self.string(value, *span),
+            PropertyKey::NumberLiteral { value, span } => self.number(value, *span),
+            PropertyKey::Computed(expression) => doc::concat(vec![
+                doc::text("["),
+                self.expression(*expression, Precedence::Assignment),
+                doc::text("]"),
+            ]),
+        }
+    }
+
+    fn literal(&self, value: &LiteralToken, span: Span) -> Doc {
+        match value {
+            LiteralToken::Number(number) => self.number(number, span),
+            LiteralToken::String(string) => self.string(string, span),
+            LiteralToken::Boolean(true) => doc::text("true"),
+            LiteralToken::Boolean(false) => doc::text("false"),
+            LiteralToken::Null => doc::text("null"),
+            LiteralToken::Undefined => doc::text("undefined"),
+        }
+    }
+
+    // Numbers keep the notation they were written in, with lowercase prefixes and exponents
+    pub fn number(&self, number: &NumberLiteral, span: Span) -> Doc {
+        let raw = self.raw(span);
+        if raw.is_empty() || raw.starts_with('-') {
+            return doc::text(number_text(number, false));
+        }
+        doc::text(raw.to_ascii_lowercase())
+    }
+
+    // A string in the preferred quote, or the other one if it needs fewer escapes. Escapes
+    // the source used are kept, apart from quotes that no longer need one.
+    pub fn string(&self, value: &str, span: Span) -> Doc {
+        let raw = self.raw(span);
+        let Some(content) = raw
+            .strip_prefix(['"', '\''])
+            .and_then(|raw| raw.strip_suffix(['"', '\'']))
+        else {
+            return doc::text(quote_string(value, self.options.quote));
+        };
+        let (preferred, other) = match self.options.quote {
+            Quote::Double => ('"', '\''),
+            Quote::Single => ('\'', '"'),
+        };
+        let count = |quote| content.chars().filter(|&c| c == quote).count();
+        let quote = if count(preferred) > count(other) {
+            other
+        } else {
+            preferred
+        };
+
+        let mut requoted = String::with_capacity(raw.len());
+        requoted.push(quote);
+        let mut chars = content.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some(escaped) if (escaped == '"' || escaped == '\'') && escaped != quote => {
+                        requoted.push(escaped)
+                    }
+                    Some(escaped) => {
+                        requoted.push('\\');
+                        requoted.push(escaped);
+                    }
+                    None => requoted.push('\\'),
+                },
+                c if c == quote => {
+                    requoted.push('\\');
+                    requoted.push(c);
+                }
+                c => requoted.push(c),
+            }
+        }
+        requoted.push(quote);
+        doc::text(requoted)
+    }
+
+    // Templates keep their text exactly as written
+    fn template(&mut self, template: &TemplateLiteral) -> Doc {
+        match template {
+            TemplateLiteral::NonSubstitutionTemplate { text, .. } => {
+                doc::text(format!("`{}`", self.quasi(*text)))
+            }
+            TemplateLiteral::SubstitutionTemplate {
+                template_head,
+                expression,
+                template_spans,
+                ..
+            } => {
+                let mut parts = vec![doc::text(format!("`{}${{", self.quasi(*template_head)))];
+                parts.push(self.expression(*expression, Precedence::Lowest));
+                let mut middle = template_spans.template_middle_list.as_ref();
+                while let Some(list) = middle {
+                    parts.push(doc::text(format!(
+                        "}}{}${{",
+                        self.quasi(list.template_middle)
+                    )));
+                    parts.push(self.expression(list.expression, Precedence::Lowest));
+                    middle = list.template_middle_list.as_deref();
+                }
+                parts.push(doc::text(format!(
+                    "}}{}`",
+                    self.quasi(template_spans.template_tail)
+                )));
+                doc::concat(parts)
+            }
+        }
+    }
+
+    fn quasi(&self, id: ExpressionId) -> String {
+        self.raw(self.ast[id].span())
+    }
+}
+
+fn is_hole(expression: &Expression) -> bool {
+    matches!(
+        expression,
+        Expression::Literal { value: LiteralToken::Undefined, span } if span.start == span.end
+    )
+}
+
+fn property_span(property: Option<&Property>, ast: &jsompiler_parser::Ast) -> Span {
+    match property {
+        Some(
+            Property::KeyValue { span, .. }
+            | Property::Method { span, .. }
+            | Property::Getter { span, .. }
+            | Property::Setter { span, .. },
+        ) => *span,
+        Some(Property::Shorthand(identifier)) => identifier.span,
+        Some(Property::Spread(spread)) => ast[*spread].span(),
+        None => Span::default(),
+    }
+}
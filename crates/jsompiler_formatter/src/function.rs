@@ -0,0 +1,376 @@
+use crate::doc::{self, Doc};
+use crate::{Formatter, TrailingCommas};
+use jsompiler_codegen::Precedence;
+use jsompiler_parser::{
+    BindingElement, BindingPattern, BindingProperty, BindingRestElement, ClassElement,
+    ClassElementName, ExpressionId, Identifier, Parameter, Span, StatementId,
+};
+
+impl<'a> Formatter<'a> {
+    pub fn function(
+        &mut self,
+        name: Option<&Identifier>,
+        parameters: &[Parameter],
+        body: &[StatementId],
+        is_async: bool,
+        is_generator: bool,
+        span: Span,
+    ) -> Doc {
+        let mut head = String::new();
+        if is_async {
+            head.push_str("async ");
+        }
+        head.push_str("function");
+        if is_generator {
+            head.push('*');
+        }
+        head.push(' ');
+        if let Some(name) = name {
+            head.push_str(&self.ast[name.value]);
+        }
+        doc::concat(vec![
+            doc::text(head),
+            self.parameters(parameters),
+            doc::text(" "),
+            self.braced(body, span),
+        ])
+    }
+
+    pub fn parameters(&mut self, parameters: &[Parameter]) -> Doc {
+        if parameters.is_empty() {
+            return doc::text("()");
+        }
+        let printed = parameters
+            .iter()
+            .map(|parameter| match parameter {
+                Parameter::Identifier(identifier) => doc::text(&self.ast[identifier.value]),
+                Parameter::Pattern(pattern) => self.binding_pattern(pattern),
+                Parameter::Default {
+                    target, default, ..
+                } => {
+                    let target = self.binding_pattern(target);
+                    doc::concat(vec![target, self.initializer(Some(*default))])
+                }
+                Parameter::RestParameter { argument, .. } => {
+                    doc::concat(vec![doc::text("..."), self.binding_pattern(argument)])
+                }
+            })
+            .collect();
+        // Nothing may follow a rest parameter, not even a comma
+        let trailing = match parameters.last() {
+            Some(Parameter::RestParameter { .. }) => doc::text(""),
+            _ => self.trailing_comma(TrailingCommas::All),
+        };
+        self.bracketed("(", printed, trailing, ")")
+    }
+
+    // ` = value` after a binding, if it has a value
+    fn initializer(&mut self, value: Option<ExpressionId>) -> Doc {
+        match value {
+            Some(value) => doc::concat(vec![
+                doc::text(" = "),
+                self.expression(value, Precedence::Assignment),
+            ]),
+            None => doc::text(""),
+        }
+    }
+
+    pub fn binding_pattern(&mut self, pattern: &BindingPattern) -> Doc {
+        match pattern {
+            BindingPattern::Identifier(identifier) => doc::text(&self.ast[identifier.value]),
+            BindingPattern::ObjectPattern {
+                properties,
+                rest,
+                span,
+            } => {
+                if properties.is_empty() && rest.is_none() {
+                    return self.empty_bracketed("{", *span, "}");
+                }
+                let mut printed: Vec<Doc> = properties
+                    .iter()
+                    .map(|property| match property {
+                        BindingProperty::KeyValue { key, value, .. } => {
+                            let key = self.property_key(key);
+                            doc::concat(vec![key, doc::text(": "), self.binding_element(value)])
+                        }
+                        BindingProperty::Shorthand { name, default, .. } => doc::concat(vec![
+                            doc::text(&self.ast[name.value]),
+                            self.initializer(*default),
+                        ]),
+                    })
+                    .collect();
+                if let Some(rest) = rest {
+                    printed.push(self.binding_rest(rest));
+                }
+                let trailing = if rest.is_some() {
+                    doc::text("")
+                } else {
+                    self.trailing_comma(TrailingCommas::Es5)
+                };
+                doc::group(doc::concat(vec![
+                    doc::text("{"),
+                    doc::indent(doc::concat(vec![
+                        Doc::Line,
+                        doc::join(doc::concat(vec![doc::text(","), Doc::Line]), printed),
+                        trailing,
+                    ])),
+                    Doc::Line,
+                    doc::text("}"),
+                ]))
+            }
+            BindingPattern::ArrayPattern {
+                elements,
+                rest,
+                span,
+            } => {
+                if elements.is_empty() && rest.is_none() {
+                    return self.empty_bracketed("[", *span, "]");
+                }
+                let mut printed: Vec<Doc> = elements
+                    .iter()
+                    .map(|element| match element {
+                        Some(element) => self.binding_element(element),
+                        None => doc::text(""),
+                    })
+                    .collect();
+                let trailing = match rest {
+                    Some(rest) => {
+                        printed.push(self.binding_rest(rest));
+                        doc::text("")
+                    }
+                    // A trailing comma is ignored, so an elision at the end needs one more
+                    None if matches!(elements.last(), Some(None)) => doc::text(","),
+                    None => self.trailing_comma(TrailingCommas::Es5),
+                };
+                self.bracketed("[", printed, trailing, "]")
+            }
+        }
+    }
+
+    fn binding_element(&mut self, element: &BindingElement) -> Doc {
+        let target = self.binding_pattern(&element.target);
+        doc::concat(vec![target, self.initializer(element.default)])
+    }
+
+    fn binding_rest(&mut self, rest: &BindingRestElement) -> Doc {
+        doc::concat(vec![doc::text("..."), self.binding_pattern(&rest.argument)])
+    }
+
+    // Class members one per line, keeping a single blank line where the source had any
+    pub fn class(
+        &mut self,
+        name: Option<&Identifier>,
+        heritage: Option<ExpressionId>,
+        body: &'a [ClassElement],
+        body_span: Span,
+    ) -> Doc {
+        let mut parts = vec![doc::text("class ")];
+        if let Some(name) = name {
+            parts.push(doc::text(format!("{} ", &self.ast[name.value])));
+        }
+        if let Some(heritage) = heritage {
+            parts.push(doc::text("extends "));
+            parts.push(self.expression(heritage, Precedence::Call));
+            parts.push(doc::text(" "));
+        }
+        if body.is_empty() {
+            parts.push(self.empty_bracketed("{", body_span, "}"));
+            return doc::concat(parts);
+        }
+
+        let mut members = Vec::new();
+        let mut previous_end = body_span.start + 1;
+        for (index, element) in body.iter().enumerate() {
+            if index > 0 {
+                members.push(Doc::HardLine);
+            }
+            let gap = Span::new(previous_end, element_span(element).start);
+            members.push(self.list_item_prefix(gap, index == 0));
+            previous_end = element_span(element).end;
+            let printed = self.class_element(element);
+            members.push(printed);
+            // Without semicolons, a field followed by `[name]` or `*name` would run into it
+            if let ClassElement::FieldDefinition { .. } | ClassElement::AccessorDefinition { .. } =
+                element
+            {
+                let next_is_hazard = match body.get(index + 1) {
+                    Some(next) => matches!(self.class_element_start(next), Some('[' | '*' | '(')),
+                    None => false,
+                };
+                members.push(if next_is_hazard {
+                    doc::text(";")
+                } else {
+                    self.semicolon()
+                });
+            }
+        }
+        parts.push(doc::concat(vec![
+            doc::text("{"),
+            doc::indent(doc::concat(vec![Doc::HardLine, doc::concat(members)])),
+            Doc::HardLine,
+            doc::text("}"),
+        ]));
+        doc::concat(parts)
+    }
+
+    // The first character of a class element when it has no modifiers in front
+    fn class_element_start(&self, element: &ClassElement) -> Option<char> {
+        match element {
+            ClassElement::GeneratorMethodDefinition {
+                is_static: false, ..
+            } => Some('*'),
+            ClassElement::MethodDefinition {
+                name: ClassElementName::Computed(_),
+                is_static: false,
+                ..
+            }
+            | ClassElement::FieldDefinition {
+                name: ClassElementName::Computed(_),
+                is_static: false,
+                ..
+            } => Some('['),
+            _ => None,
+        }
+    }
+
+    fn class_element(&mut self, element: &ClassElement) -> Doc {
+        match element {
+            ClassElement::Constructor {
+                name,
+                params,
+                body,
+                span,
+                ..
+            } => self.method("", name, params, body, *span),
+            ClassElement::MethodDefinition {
+                name,
+                params,
+                body,
+                is_static,
+                span,
+                ..
+            } => self.method(&modifiers(*is_static, ""), name, params, body, *span),
+            ClassElement::AsyncMethodDefinition {
+                name,
+                params,
+                body,
+                is_static,
+                span,
+                ..
+            } => self.method(&modifiers(*is_static, "async "), name, params, body, *span),
+            ClassElement::GeneratorMethodDefinition {
+                name,
+                params,
+                body,
+                is_static,
+                span,
+                ..
+            } => self.method(&modifiers(*is_static, "*"), name, params, body, *span),
+            ClassElement::AsyncGeneratorMethodDefinition {
+                name,
+                params,
+                body,
+                is_static,
+                span,
+                ..
+            } => self.method(&modifiers(*is_static, "async *"), name, params, body, *span),
+            ClassElement::GetterDefinition {
+                name,
+                body,
+                is_static,
+                span,
+                ..
+            } => self.method(&modifiers(*is_static, "get "), name, &[], body, *span),
+            ClassElement::SetterDefinition {
+                name,
+                param,
+                body,
+                is_static,
+                span,
+                ..
+            } => self.method(
+                &modifiers(*is_static, "set "),
+                name,
+                std::slice::from_ref(param),
+                body,
+                *span,
+            ),
+            ClassElement::FieldDefinition {
+                name,
+                value,
+                is_static,
+                ..
+            } => doc::concat(vec![
+                doc::text(modifiers(*is_static, "")),
+                self.class_element_name(name),
+                self.initializer(*value),
+            ]),
+            ClassElement::AccessorDefinition {
+                name,
+                value,
+                is_static,
+                ..
+            } => doc::concat(vec![
+                doc::text(modifiers(*is_static, "accessor ")),
+                self.class_element_name(name),
+                self.initializer(*value),
+            ]),
+            ClassElement::StaticBlock { body, span } => {
+                doc::concat(vec![doc::text("static "), self.braced(body, *span)])
+            }
+        }
+    }
+
+    fn method(
+        &mut self,
+        modifiers: &str,
+        name: &ClassElementName,
+        params: &[Parameter],
+        body: &[StatementId],
+        span: Span,
+    ) -> Doc {
+        doc::concat(vec![
+            doc::text(modifiers),
+            self.class_element_name(name),
+            self.parameters(params),
+            doc::text(" "),
+            self.braced(body, span),
+        ])
+    }
+
+    fn class_element_name(&mut self, name: &ClassElementName) -> Doc {
+        match name {
+            ClassElementName::PropertyName(identifier)
+            | ClassElementName::PrivateIdentifier(identifier) => {
+                doc::text(&self.ast[identifier.value])
+            }
+            ClassElementName::StringLiteral { value, span } => self.string(value, *span),
+            ClassElementName::NumberLiteral { value, span } => self.number(value, *span),
+            ClassElementName::Computed(expression) => doc::concat(vec![
+                doc::text("["),
+                self.expression(*expression, Precedence::Assignment),
+                doc::text("]"),
+            ]),
+        }
+    }
+}
+
+// `static` and the keywords of a method kind, such as `async ` or `get `
+fn modifiers(is_static: bool, kind: &str) -> String {
+    format!("{}{kind}", if is_static { "static " } else { "" })
+}
+
+fn element_span(element: &ClassElement) -> Span {
+    match element {
+        ClassElement::Constructor { span, .. }
+        | ClassElement::MethodDefinition { span, .. }
+        | ClassElement::AsyncMethodDefinition { span, .. }
+        | ClassElement::GeneratorMethodDefinition { span, .. }
+        | ClassElement::AsyncGeneratorMethodDefinition { span, .. }
+        | ClassElement::GetterDefinition { span, .. }
+        | ClassElement::SetterDefinition { span, .. }
+        | ClassElement::FieldDefinition { span, .. }
+        | ClassElement::AccessorDefinition { span, .. }
+        | ClassElement::StaticBlock { span, .. } => *span,
+    }
+}
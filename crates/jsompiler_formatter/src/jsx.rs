@@ -0,0 +1,169 @@
+use crate::Formatter;
+use crate::doc::{self, Doc};
+use jsompiler_codegen::Precedence;
+use jsompiler_parser::{
+    CommentKind, JSXAttribute, JSXAttributeValue, JSXChild, JSXElement, JSXExpressionContainer,
+    JSXFragment, Span,
+};
+
+impl<'a> Formatter<'a> {
+    // Attributes stay on the tag line if they fit, otherwise one per line
+    pub fn jsx_element(&mut self, element: &JSXElement) -> Doc {
+        let name = element.opening.name.text(self.ast);
+        let attributes: Vec<Doc> = element
+            .opening
+            .attributes
+            .iter()
+            .map(|attribute| doc::concat(vec![Doc::Line, self.jsx_attribute(attribute)]))
+            .collect();
+        let has_attributes = !attributes.is_empty();
+        let mut opening = vec![
+            doc::text(format!("<{name}")),
+            doc::indent(doc::concat(attributes)),
+        ];
+        if element.opening.self_closing {
+            opening.extend([Doc::Line, doc::text("/>")]);
+            return doc::group(doc::concat(opening));
+        }
+        if has_attributes {
+            opening.push(Doc::SoftLine);
+        }
+        opening.push(doc::text(">"));
+        doc::concat(vec![
+            doc::group(doc::concat(opening)),
+            self.jsx_children(&element.children),
+            doc::text(format!("</{name}>")),
+        ])
+    }
+
+    pub fn jsx_fragment(&mut self, fragment: &JSXFragment) -> Doc {
+        doc::concat(vec![
+            doc::text("<>"),
+            self.jsx_children(&fragment.children),
+            doc::text("</>"),
+        ])
+    }
+
+    fn jsx_attribute(&mut self, attribute: &JSXAttribute) -> Doc {
+        match attribute {
+            JSXAttribute::Attribute { name, value, .. } => {
+                let name = doc::text(name.text(self.ast));
+                let Some(value) = value else {
+                    return name;
+                };
+                let value = match value {
+                    // Attribute strings have no escapes, so they are kept as written
+                    JSXAttributeValue::StringLiteral { span, .. } => doc::text(self.raw(*span)),
+                    JSXAttributeValue::ExpressionContainer(container) => {
+                        self.jsx_expression_container(container)
+                    }
+                    JSXAttributeValue::Element(element) => self.jsx_element(element),
+                    JSXAttributeValue::Fragment(fragment) => self.jsx_fragment(fragment),
+                };
+                doc::concat(vec![name, doc::text("="), value])
+            }
+            JSXAttribute::SpreadAttribute { argument, .. } => doc::concat(vec![
+                doc::text("{..."),
+                self.expression(*argument, Precedence::Assignment),
+                doc::text("}"),
+            ]),
+        }
+    }
+
+    // Children keep the line structure of the source. Whitespace holding a line break is not
+    // part of the text in JSX, so lines of text can be reindented without changing it.
+    fn jsx_children(&mut self, children: &[JSXChild]) -> Doc {
+        // Comments in empty containers are taken first, before a neighbouring expression
+        // they are attached to can print them
+        let mut empty_containers: Vec<Option<Doc>> = children
+            .iter()
+            .map(|child| match child {
+                JSXChild::ExpressionContainer(JSXExpressionContainer::Empty { span }) => {
+                    Some(self.jsx_empty_container(*span))
+                }
+                _ => None,
+            })
+            .collect();
+        let mut parts = Vec::new();
+        for (index, child) in children.iter().enumerate() {
+            if let Some(empty) = empty_containers[index].take() {
+                parts.push(empty);
+                continue;
+            }
+            match child {
+                JSXChild::Text { span, .. } => {
+                    let raw = self.raw(*span);
+                    let lines: Vec<&str> = raw.split('\n').collect();
+                    let last = lines.len() - 1;
+                    for (index, line) in lines.into_iter().enumerate() {
+                        let line = match index {
+                            0 if last == 0 => line,
+                            0 => line.trim_end(),
+                            _ if index == last => line.trim_start(),
+                            _ => line.trim(),
+                        };
+                        if index > 0 && !matches!(parts.last(), Some(Doc::HardLine)) {
+                            parts.push(Doc::HardLine);
+                        }
+                        if !line.is_empty() {
+                            parts.push(doc::text(line));
+                        }
+                    }
+                }
+                JSXChild::Element(element) => parts.push(self.jsx_element(element)),
+                JSXChild::Fragment(fragment) => parts.push(self.jsx_fragment(fragment)),
+                JSXChild::ExpressionContainer(container) => {
+                    parts.push(self.jsx_expression_container(container))
+                }
+                JSXChild::Spread { expression, .. } => parts.push(doc::concat(vec![
+                    doc::text("{..."),
+                    self.expression(*expression, Precedence::Assignment),
+                    doc::text("}"),
+                ])),
+            }
+        }
+        // The closing tag lines up with the opening one
+        let closing_line = if matches!(parts.last(), Some(Doc::HardLine)) {
+            parts.pop()
+        } else {
+            None
+        };
+        doc::concat(vec![
+            doc::indent(doc::concat(parts)),
+            closing_line.unwrap_or_else(|| doc::text("")),
+        ])
+    }
+
+    fn jsx_expression_container(&mut self, container: &JSXExpressionContainer) -> Doc {
+        match container {
+            JSXExpressionContainer::Expression { expression, .. } => doc::concat(vec![
+                doc::text("{"),
+                self.expression(*expression, Precedence::Assignment),
+                doc::text("}"),
+            ]),
+            JSXExpressionContainer::Empty { span } => self.jsx_empty_container(*span),
+        }
+    }
+
+    // Only a comment, as in `{/* note */}`
+    fn jsx_empty_container(&mut self, span: Span) -> Doc {
+        let comments = self.dangling_comments(span);
+        let has_line_comment = comments
+            .iter()
+            .any(|comment| comment.kind == CommentKind::Line);
+        let printed = comments
+            .into_iter()
+            .map(|comment| self.comment(comment))
+            .collect();
+        doc::concat(vec![
+            doc::text("{"),
+            doc::join(Doc::HardLine, printed),
+            if has_line_comment {
+                Doc::HardLine
+            } else {
+                doc::text("")
+            },
+            doc::text("}"),
+        ])
+    }
+}
@@ -0,0 +1,157 @@
+mod comment;
+mod doc;
+mod expression;
+mod function;
+mod jsx;
+mod statement;
+mod test;
+
+use doc::Doc;
+use jsompiler_common::Error;
+use jsompiler_lexer::{Lexer, LexerOptions};
+use jsompiler_parser::{Ast, NodeId, Parser, ParserOptions, Span};
+use std::collections::HashMap;
+
+pub use jsompiler_codegen::Quote;
+
+// Where a comma may follow the last item of a list that is broken over several lines
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum TrailingCommas {
+    None,
+    Es5, // Arrays, objects and patterns
+    #[default]
+    All, // Also parameters and arguments
+}
+
+#[derive(Debug, Clone)]
+pub struct FormatOptions {
+    pub print_width: usize, // Lines longer than this are broken where the syntax allows
+    pub indent_width: usize, // Spaces per indentation level, or columns a tab counts as
+    pub use_tabs: bool,
+    pub quote: Quote,
+    pub trailing_commas: TrailingCommas,
+    pub semicolons: bool, // Without them, statements that would join the previous one get a leading `;`
+    pub jsx: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions {
+            print_width: 80,
+            indent_width: 2,
+            use_tabs: false,
+            quote: Quote::Double,
+            trailing_commas: TrailingCommas::All,
+            semicolons: true,
+            jsx: false,
+        }
+    }
+}
+
+// Format JavaScript source. Formatting the output again gives the same output.
+pub fn format(source: &str, options: &FormatOptions) -> Result<String, Vec<Error>> {
+    let mut lexer = Lexer::with_options(source.to_string(), LexerOptions { jsx: options.jsx });
+    lexer.scan_all_tokens();
+    let mut parser = Parser::with_lexer(lexer, ParserOptions { jsx: options.jsx });
+    parser.parse();
+    // Parse errors may only follow from the lexer errors, so those are reported alone
+    if !parser.lexer_errors.is_empty() {
+        return Err(parser.lexer_errors);
+    }
+    if !parser.errors.is_empty() {
+        return Err(parser.errors);
+    }
+    Ok(format_ast(&parser.ast, source, options))
+}
+
+// Format an AST parsed from `source`, which literals and blank lines are taken from
+pub fn format_ast(ast: &Ast, source: &str, options: &FormatOptions) -> String {
+    let mut formatter = Formatter::new(ast, source, options);
+    let doc = formatter.program();
+    let indent_unit = if options.use_tabs {
+        "\t".to_string()
+    } else {
+        " ".repeat(options.indent_width)
+    };
+    doc::print(
+        &doc,
+        options.print_width,
+        &indent_unit,
+        options.indent_width,
+    )
+}
+
+struct Formatter<'a> {
+    ast: &'a Ast,
+    source: Vec<char>,
+    options: &'a FormatOptions,
+    comments: HashMap<NodeId, Vec<usize>>, // Indices into `ast.comments` of each node
+    printed: Vec<bool>,                    // Comments already placed in the document
+    forbid_in: bool, // In a `for` initializer, where `in` would start a for-in loop
+}
+
+impl<'a> Formatter<'a> {
+    fn new(ast: &'a Ast, source: &str, options: &'a FormatOptions) -> Self {
+        let mut comments: HashMap<NodeId, Vec<usize>> = HashMap::new();
+        for (index, comment) in ast.comments.iter().enumerate() {
+            if let Some((node, _)) = comment.attachment {
+                comments.entry(node).or_default().push(index);
+            }
+        }
+        Formatter {
+            ast,
+            source: source.chars().collect(),
+            options,
+            comments,
+            printed: vec![false; ast.comments.len()],
+            forbid_in: false,
+        }
+    }
+
+    // The source text of a span, such as the raw text of a literal
+    fn raw(&self, span: Span) -> String {
+        let end = span.end.min(self.source.len());
+        self.source[span.start.min(end)..end].iter().collect()
+    }
+
+    // Whether the source has a line break between two positions
+    fn has_line_break(&self, start: usize, end: usize) -> bool {
+        self.line_breaks(start, end) > 0
+    }
+
+    fn line_breaks(&self, start: usize, end: usize) -> usize {
+        let end = end.min(self.source.len());
+        self.source[start.min(end)..end]
+            .iter()
+            .filter(|&&c| c == '\n')
+            .count()
+    }
+
+    fn semicolon(&self) -> Doc {
+        doc::text(if self.options.semicolons { ";" } else { "" })
+    }
+
+    // A comma after the last item of a list, when the list is broken and the options allow it
+    fn trailing_comma(&self, needed: TrailingCommas) -> Doc {
+        if self.options.trailing_commas >= needed {
+            doc::if_break(doc::text(","), doc::text(""))
+        } else {
+            doc::text("")
+        }
+    }
+
+    // Items between brackets, on one line if they fit or one per line otherwise, as in
+    // `[a, b]` or `(a, b)`
+    fn bracketed(&self, open: &str, items: Vec<Doc>, trailing: Doc, close: &str) -> Doc {
+        doc::group(doc::concat(vec![
+            doc::text(open),
+            doc::indent(doc::concat(vec![
+                Doc::SoftLine,
+                doc::join(doc::concat(vec![doc::text(","), Doc::Line]), items),
+                trailing,
+            ])),
+            Doc::SoftLine,
+            doc::text(close),
+        ]))
+    }
+}
@@ -0,0 +1,640 @@
+use crate::Formatter;
+use crate::doc::{self, Doc};
+use jsompiler_codegen::Precedence;
+use jsompiler_lexer::symbol::{KeywordToken, Token};
+use jsompiler_parser::{
+    ExportStatement, Expression, ExpressionId, ForInOfLeft, LetStatement, Node, NodeId, Span,
+    Statement, StatementId,
+};
+
+// What gets printed as one statement. A declaration of several variables is parsed into one
+// `LetStatement` per declarator, all sharing the span and keyword of the declaration.
+enum Group<'a> {
+    Declaration(Vec<(StatementId, &'a LetStatement)>),
+    Statement(StatementId),
+}
+
+impl<'a> Formatter<'a> {
+    pub fn program(&mut self) -> Doc {
+        let ast = self.ast;
+        let mut parts = Vec::new();
+        if let Some(hashbang) = &ast.hashbang {
+            parts.push(doc::text(format!("#!{hashbang}")));
+            parts.push(Doc::HardLine);
+            let first_line = self.source.iter().position(|&c| c == '\n');
+            let next = self
+                .groups(&self.top_level())
+                .first()
+                .map(|group| self.group_span(group));
+            if let (Some(end), Some(next)) = (first_line, next)
+                && self.line_breaks(end, next.start) > 1
+            {
+                parts.push(Doc::HardLine);
+            }
+        }
+        let statements = self.top_level();
+        if !statements.is_empty() {
+            parts.push(self.statements(&statements));
+            parts.push(Doc::HardLine);
+        }
+        for comment in self.remaining_comments() {
+            parts.push(comment);
+            parts.push(Doc::HardLine);
+        }
+        doc::concat(parts)
+    }
+
+    // Top-level statements, with expression nodes built by transforms wrapped in statements
+    fn top_level(&self) -> Vec<StatementId> {
+        let mut statements = Vec::new();
+        for node in &self.ast.body {
+            match node {
+                Node::Statement(ids) => statements.extend(ids),
+                Node::Expression(_) => {}
+            }
+        }
+        statements
+    }
+
+    fn groups(&self, statements: &[StatementId]) -> Vec<Group<'a>> {
+        let ast = self.ast;
+        let mut groups: Vec<Group<'a>> = Vec::new();
+        for &id in statements {
+            let statement = &ast[id];
+            if let Statement::EmptyStatement { .. } = statement {
+                continue;
+            }
+            let Statement::LetStatement(declarator) = statement else {
+                groups.push(Group::Statement(id));
+                continue;
+            };
+            if let Some(Group::Declaration(declarators)) = groups.last_mut()
+                && declarators[0].1.span == declarator.span
+                && declarators[0].1.token == declarator.token
+            {
+                declarators.push((id, declarator));
+                continue;
+            }
+            groups.push(Group::Declaration(vec![(id, declarator)]));
+        }
+        groups
+    }
+
+    fn group_span(&self, group: &Group) -> Span {
+        match group {
+            Group::Declaration(declarators) => {
+                let mut span = declarators[0].1.span;
+                for &(id, declarator) in declarators {
+                    let extended = self.extended_span(id.into(), declarator.span);
+                    span.start = span.start.min(extended.start);
+                    span.end = span.end.max(extended.end);
+                }
+                span
+            }
+            Group::Statement(id) => self.extended_span((*id).into(), self.ast[*id].span()),
+        }
+    }
+
+    // Statements one per line, keeping a single blank line where the source had any
+    pub fn statements(&mut self, statements: &[StatementId]) -> Doc {
+        let groups = self.groups(statements);
+        let mut parts = Vec::new();
+        let mut previous_end = None;
+        for group in &groups {
+            let span = self.group_span(group);
+            if let Some(end) = previous_end {
+                parts.push(Doc::HardLine);
+                if self.line_breaks(end, span.start) > 1 {
+                    parts.push(Doc::HardLine);
+                }
+            }
+            previous_end = Some(span.end);
+            parts.push(self.group(group));
+        }
+        doc::concat(parts)
+    }
+
+    fn group(&mut self, group: &Group<'a>) -> Doc {
+        match group {
+            Group::Declaration(declarators) => {
+                let just_declarators: Vec<&LetStatement> = declarators
+                    .iter()
+                    .map(|(_, declarator)| *declarator)
+                    .collect();
+                let mut printed =
+                    doc::concat(vec![self.declaration(&just_declarators), self.semicolon()]);
+                for &(id, declarator) in declarators {
+                    printed = self.with_comments(id.into(), declarator.span, printed);
+                }
+                printed
+            }
+            Group::Statement(id) => self.statement(*id),
+        }
+    }
+
+    // A block with the statements indented, or the comments inside an empty one
+    pub fn block(&mut self, statements: &[StatementId], span: Span) -> Doc {
+        if self.groups(statements).is_empty() {
+            return self.empty_bracketed("{", span, "}");
+        }
+        doc::concat(vec![
+            doc::text("{"),
+            doc::indent(doc::concat(vec![
+                Doc::HardLine,
+                self.statements(statements),
+            ])),
+            Doc::HardLine,
+            doc::text("}"),
+        ])
+    }
+
+    // A body that needs braces: functions, `try`, `catch`, `finally` and static blocks. The
+    // parser stores them as a single block statement.
+    pub fn braced(&mut self, statements: &[StatementId], span: Span) -> Doc {
+        match statements {
+            [id] if matches!(self.ast[*id], Statement::BlockStatement(_)) => self.statement(*id),
+            _ => self.block(statements, span),
+        }
+    }
+
+    // The body of `if`, `else`, loops, labels and `with`, normally a single statement. One
+    // that isn't a block goes on the next line when it doesn't fit.
+    fn body(&mut self, statements: &[StatementId], span: Span) -> Doc {
+        let groups = self.groups(statements);
+        match groups.as_slice() {
+            [] => doc::text(";"),
+            [Group::Statement(id)] if self.is_block(statements) => {
+                doc::concat(vec![doc::text(" "), self.statement(*id)])
+            }
+            [group] => doc::group(doc::indent(doc::concat(vec![Doc::Line, self.group(group)]))),
+            _ => doc::concat(vec![doc::text(" "), self.block(statements, span)]),
+        }
+    }
+
+    fn is_block(&self, statements: &[StatementId]) -> bool {
+        matches!(statements, [id] if matches!(self.ast[*id], Statement::BlockStatement(_)))
+    }
+
+    // Whether a body ends in an `if` without `else`, which would take an `else` that follows
+    // the body as its own
+    fn ends_with_open_if(&self, statements: &[StatementId]) -> bool {
+        let Some(&last) = statements.last() else {
+            return false;
+        };
+        match &self.ast[last] {
+            Statement::IfStatement(statement) => match &statement.alternative {
+                Some(alternative) => self.ends_with_open_if(alternative),
+                None => true,
+            },
+            Statement::WhileStatement(statement) => self.ends_with_open_if(&statement.consequence),
+            Statement::ForLoopStatement(statement) => self.ends_with_open_if(&statement.body),
+            Statement::ForInStatement(statement) => self.ends_with_open_if(&statement.body),
+            Statement::ForOfStatement(statement) => self.ends_with_open_if(&statement.body),
+            Statement::LabeledStatement(statement) => self.ends_with_open_if(&statement.body),
+            Statement::WithStatement(statement) => self.ends_with_open_if(&statement.body),
+            _ => false,
+        }
+    }
+
+    pub fn statement(&mut self, id: StatementId) -> Doc {
+        let statement = &self.ast[id];
+        let printed = self.statement_unwrapped(statement);
+        self.with_comments(id.into(), statement.span(), printed)
+    }
+
+    fn statement_unwrapped(&mut self, statement: &'a Statement) -> Doc {
+        let span = statement.span();
+        match statement {
+            Statement::LetStatement(declarator) => {
+                doc::concat(vec![self.declaration(&[declarator]), self.semicolon()])
+            }
+            Statement::ReturnStatement(statement) => {
+                let Some(value) = statement.value else {
+                    return doc::concat(vec![doc::text("return"), self.semicolon()]);
+                };
+                doc::concat(vec![
+                    doc::text("return "),
+                    self.argument(value),
+                    self.semicolon(),
+                ])
+            }
+            Statement::ExpressionStatement { expression, .. } => {
+                let printed = self.expression_statement(*expression);
+                self.guard_start(doc::concat(vec![printed, self.semicolon()]))
+            }
+            Statement::BlockStatement(block) => self.block(&block.statements, block.span),
+            Statement::IfStatement(statement) => {
+                let head = self.keyword_head("if", statement.condition);
+                let Some(alternative) = &statement.alternative else {
+                    let body = self.body(&statement.consequence, span);
+                    return doc::concat(vec![head, body]);
+                };
+                let consequence = if self.ends_with_open_if(&statement.consequence) {
+                    let block = self.block(&statement.consequence, span);
+                    doc::concat(vec![doc::text(" "), block, doc::text(" ")])
+                } else if self.is_block(&statement.consequence) {
+                    let body = self.body(&statement.consequence, span);
+                    doc::concat(vec![body, doc::text(" ")])
+                } else {
+                    let body = self.body(&statement.consequence, span);
+                    doc::concat(vec![body, Doc::HardLine])
+                };
+                let alternative = match alternative.as_slice() {
+                    // `else if` chains stay flat
+                    [id] if matches!(self.ast[*id], Statement::IfStatement(_)) => {
+                        doc::concat(vec![doc::text(" "), self.statement(*id)])
+                    }
+                    _ => self.body(alternative, span),
+                };
+                doc::concat(vec![head, consequence, doc::text("else"), alternative])
+            }
+            Statement::WhileStatement(statement) => {
+                let head = self.keyword_head("while", statement.condition);
+                let body = self.body(&statement.consequence, span);
+                doc::concat(vec![head, body])
+            }
+            Statement::AssignmentStatement(statement) => {
+                let assignment = self.assignment(statement.target, statement.value);
+                let printed = if self.starts_ambiguously(statement.target) {
+                    doc::concat(vec![doc::text("("), assignment, doc::text(")")])
+                } else {
+                    assignment
+                };
+                self.guard_start(doc::concat(vec![printed, self.semicolon()]))
+            }
+            Statement::ForLoopStatement(statement) => {
+                self.forbid_in = true;
+                let initializer = self.for_header_part(&statement.initializer);
+                self.forbid_in = false;
+                let condition = self.for_header_part(&statement.condition);
+                let increment = self.for_header_part(&statement.increment);
+                let body = self.body(&statement.body, span);
+                let header = if statement.condition.is_empty() && statement.increment.is_empty() {
+                    doc::concat(vec![initializer, doc::text(";;")])
+                } else {
+                    let part = |printed: Doc, empty: bool| {
+                        if empty {
+                            doc::text(";")
+                        } else {
+                            doc::concat(vec![doc::text(";"), Doc::Line, printed])
+                        }
+                    };
+                    doc::concat(vec![
+                        initializer,
+                        part(condition, statement.condition.is_empty()),
+                        part(increment, statement.increment.is_empty()),
+                    ])
+                };
+                doc::concat(vec![
+                    doc::group(doc::concat(vec![
+                        doc::text("for ("),
+                        doc::indent(doc::concat(vec![Doc::SoftLine, header])),
+                        Doc::SoftLine,
+                        doc::text(")"),
+                    ])),
+                    body,
+                ])
+            }
+            Statement::YieldStatement(statement) => match statement.expression.first() {
+                Some(&argument) => doc::concat(vec![
+                    doc::text("yield "),
+                    self.expression(argument, Precedence::Assignment),
+                    self.semicolon(),
+                ]),
+                None => doc::concat(vec![doc::text("yield"), self.semicolon()]),
+            },
+            Statement::FunctionDeclaration(function) => self.function(
+                function.name.as_ref(),
+                &function.parameters,
+                &function.body,
+                function.is_async,
+                function.is_generator,
+                function.span,
+            ),
+            Statement::ClassDeclaration(class) => self.class(
+                class.identifier.as_ref(),
+                class.heritage,
+                &class.body,
+                class.body_span,
+            ),
+            Statement::ExportStatement(statement) => {
+                let exported = match statement {
+                    ExportStatement::Declaration { declarations, .. } => {
+                        let groups = self.groups(declarations);
+                        let printed = groups.iter().map(|group| self.group(group)).collect();
+                        doc::concat(printed)
+                    }
+                    ExportStatement::DefaultDeclaration { declaration, .. } => {
+                        doc::concat(vec![doc::text("default "), self.statement(*declaration)])
+                    }
+                    ExportStatement::DefaultExpression { expression, .. } => {
+                        // A function or class here would be read as a declaration
+                        let printed = if self.starts_like_declaration(*expression) {
+                            self.parenthesized(*expression)
+                        } else {
+                            self.expression(*expression, Precedence::Assignment)
+                        };
+                        doc::concat(vec![doc::text("default "), printed, self.semicolon()])
+                    }
+                };
+                doc::concat(vec![doc::text("export "), exported])
+            }
+            Statement::LabeledStatement(statement) => doc::concat(vec![
+                doc::text(format!("{}:", &self.ast[statement.label.value])),
+                self.body(&statement.body, span),
+            ]),
+            Statement::DoWhileStatement(statement) => {
+                let body = self.body(&statement.body, span);
+                let separator = if self.is_block(&statement.body) {
+                    doc::text(" ")
+                } else {
+                    Doc::HardLine
+                };
+                doc::concat(vec![
+                    doc::text("do"),
+                    body,
+                    separator,
+                    self.keyword_head("while", statement.condition),
+                    self.semicolon(),
+                ])
+            }
+            Statement::ForInStatement(statement) => {
+                let left = self.for_in_of_left(&statement.left);
+                let right = self.expression(statement.right, Precedence::Lowest);
+                let body = self.body(&statement.body, span);
+                doc::concat(vec![
+                    doc::text("for ("),
+                    left,
+                    doc::text(" in "),
+                    right,
+                    doc::text(")"),
+                    body,
+                ])
+            }
+            Statement::ForOfStatement(statement) => {
+                let left = self.for_in_of_left(&statement.left);
+                let right = self.expression(statement.right, Precedence::Assignment);
+                let body = self.body(&statement.body, span);
+                doc::concat(vec![
+                    doc::text(if statement.is_await {
+                        "for await ("
+                    } else {
+                        "for ("
+                    }),
+                    left,
+                    doc::text(" of "),
+                    right,
+                    doc::text(")"),
+                    body,
+                ])
+            }
+            Statement::SwitchStatement(statement) => {
+                let head = self.keyword_head("switch", statement.discriminant);
+                if statement.cases.is_empty() {
+                    let empty = self.empty_bracketed("{", span, "}");
+                    return doc::concat(vec![head, doc::text(" "), empty]);
+                }
+                let mut cases = Vec::new();
+                let mut previous_end = self.ast[statement.discriminant].span().end;
+                for (index, case) in statement.cases.iter().enumerate() {
+                    if index > 0 {
+                        cases.push(Doc::HardLine);
+                    }
+                    let gap = Span::new(previous_end, case.span.start);
+                    cases.push(self.list_item_prefix(gap, index == 0));
+                    previous_end = case.span.end;
+                    cases.push(match case.test {
+                        Some(test) => doc::concat(vec![
+                            doc::text("case "),
+                            self.expression(test, Precedence::Lowest),
+                            doc::text(":"),
+                        ]),
+                        None => doc::text("default:"),
+                    });
+                    match case.consequent.as_slice() {
+                        [] => {}
+                        // `case a: {` keeps the block on the same line
+                        [id] if self.is_block(&case.consequent) => {
+                            cases.push(doc::text(" "));
+                            cases.push(self.statement(*id));
+                        }
+                        consequent => cases.push(doc::indent(doc::concat(vec![
+                            Doc::HardLine,
+                            self.statements(consequent),
+                        ]))),
+                    }
+                }
+                doc::concat(vec![
+                    head,
+                    doc::text(" {"),
+                    doc::indent(doc::concat(vec![Doc::HardLine, doc::concat(cases)])),
+                    Doc::HardLine,
+                    doc::text("}"),
+                ])
+            }
+            Statement::ThrowStatement(statement) => doc::concat(vec![
+                doc::text("throw "),
+                self.argument(statement.argument),
+                self.semicolon(),
+            ]),
+            Statement::TryStatement(statement) => {
+                let mut parts = vec![doc::text("try "), self.braced(&statement.block, span)];
+                if let Some(handler) = &statement.handler {
+                    parts.push(doc::text(" catch "));
+                    if let Some(param) = &handler.param {
+                        parts.push(doc::text("("));
+                        parts.push(self.binding_pattern(param));
+                        parts.push(doc::text(") "));
+                    }
+                    parts.push(self.braced(&handler.body, handler.span));
+                }
+                if let Some(finalizer) = &statement.finalizer {
+                    parts.push(doc::text(" finally "));
+                    parts.push(self.braced(finalizer, span));
+                }
+                doc::concat(parts)
+            }
+            Statement::WithStatement(statement) => {
+                let head = self.keyword_head("with", statement.object);
+                let body = self.body(&statement.body, span);
+                doc::concat(vec![head, body])
+            }
+            Statement::EmptyStatement { .. } | Statement::Error { .. } => doc::text(";"),
+            Statement::DebuggerStatement { .. } => {
+                doc::concat(vec![doc::text("debugger"), self.semicolon()])
+            }
+            Statement::BreakStatement { label, .. }
+            | Statement::ContinueStatement { label, .. } => {
+                let keyword = match statement {
+                    Statement::BreakStatement { .. } => "break",
+                    _ => "continue",
+                };
+                let label = label.map_or(String::new(), |label| {
+                    format!(" {}", &self.ast[label.value])
+                });
+                doc::concat(vec![
+                    doc::text(format!("{keyword}{label}")),
+                    self.semicolon(),
+                ])
+            }
+        }
+    }
+
+    // Without semicolons, a statement starting with one of these would continue the
+    // previous one, as `a\n(b)` is the call `a(b)`
+    fn guard_start(&self, printed: Doc) -> Doc {
+        let hazard = matches!(
+            printed.first_char(),
+            Some('(' | '[' | '`' | '+' | '-' | '/')
+        );
+        if !self.options.semicolons && hazard {
+            doc::concat(vec![doc::text(";"), printed])
+        } else {
+            printed
+        }
+    }
+
+    // `if (condition)`, `while (condition)` and the like, breaking inside the parentheses
+    // when the condition doesn't fit
+    fn keyword_head(&mut self, keyword: &str, expression: ExpressionId) -> Doc {
+        doc::group(doc::concat(vec![
+            doc::text(format!("{keyword} (")),
+            doc::indent(doc::concat(vec![
+                Doc::SoftLine,
+                self.expression(expression, Precedence::Lowest),
+            ])),
+            Doc::SoftLine,
+            doc::text(")"),
+        ]))
+    }
+
+    // The value of `return` and `throw`, in parentheses when a binary expression has to break
+    // or a comment before it ends its line, which would end the statement
+    fn argument(&mut self, value: ExpressionId) -> Doc {
+        let breaks = self.starts_with_line_break(None, value);
+        let printed = self.expression(value, Precedence::Lowest);
+        if breaks {
+            return doc::in_parentheses(printed, true);
+        }
+        if !self.is_binary(value) {
+            return printed;
+        }
+        doc::group(doc::concat(vec![
+            doc::if_break(doc::text("("), doc::text("")),
+            doc::indent(doc::concat(vec![Doc::SoftLine, printed])),
+            Doc::SoftLine,
+            doc::if_break(doc::text(")"), doc::text("")),
+        ]))
+    }
+
+    fn expression_statement(&mut self, expression: ExpressionId) -> Doc {
+        if self.starts_ambiguously(expression) {
+            self.parenthesized(expression)
+        } else {
+            self.expression(expression, Precedence::Lowest)
+        }
+    }
+
+    fn assignment(&mut self, target: ExpressionId, value: ExpressionId) -> Doc {
+        let target = self.expression(target, Precedence::Call);
+        let value_doc = self.expression(value, Precedence::Assignment);
+        self.assigned(target, " =", value, value_doc)
+    }
+
+    // `left = value`, moving a long binary value to the next line
+    pub fn assigned(&self, left: Doc, operator: &str, value: ExpressionId, value_doc: Doc) -> Doc {
+        if self.is_binary(value) {
+            doc::group(doc::concat(vec![
+                left,
+                doc::text(operator),
+                doc::group(doc::indent(doc::concat(vec![Doc::Line, value_doc]))),
+            ]))
+        } else {
+            doc::concat(vec![left, doc::text(format!("{operator} ")), value_doc])
+        }
+    }
+
+    // A binary expression other than an assignment
+    fn is_binary(&self, id: ExpressionId) -> bool {
+        matches!(&self.ast[id], Expression::Binary { op, .. }
+            if Precedence::of_binary(&op.text) > Precedence::Assignment)
+    }
+
+    // `let a = 1, b` with each declarator on its own line when any has a value
+    fn declaration(&mut self, declarators: &[&'a LetStatement]) -> Doc {
+        let keyword = declaration_keyword(&declarators[0].token);
+        let has_value = declarators.len() > 1 && declarators.iter().any(|d| d.value.is_some());
+        let mut printed = Vec::new();
+        for declarator in declarators {
+            let name = self.binding_pattern(&declarator.name);
+            printed.push(match declarator.value {
+                Some(value) => {
+                    let value_doc = self.expression(value, Precedence::Assignment);
+                    self.assigned(name, " =", value, value_doc)
+                }
+                None => name,
+            });
+        }
+        let separator = if has_value { Doc::HardLine } else { Doc::Line };
+        let mut rest = printed.split_off(1);
+        let first = printed.remove(0);
+        if rest.is_empty() {
+            return doc::concat(vec![doc::text(format!("{keyword} ")), first]);
+        }
+        for part in &mut rest {
+            *part = doc::concat(vec![doc::text(","), separator.clone(), part.clone()]);
+        }
+        doc::group(doc::concat(vec![
+            doc::text(format!("{keyword} ")),
+            first,
+            doc::indent(doc::concat(rest)),
+        ]))
+    }
+
+    // One of the three parts of a `for (;;)` header
+    fn for_header_part(&mut self, statements: &[StatementId]) -> Doc {
+        let ast = self.ast;
+        let mut parts = Vec::new();
+        for group in self.groups(statements) {
+            let (node, span, printed): (NodeId, Span, Doc) = match &group {
+                Group::Declaration(declarators) => {
+                    let just: Vec<&LetStatement> = declarators.iter().map(|(_, d)| *d).collect();
+                    let printed = self.declaration(&just);
+                    (declarators[0].0.into(), declarators[0].1.span, printed)
+                }
+                Group::Statement(id) => {
+                    let printed = match &ast[*id] {
+                        Statement::ExpressionStatement { expression, .. } => {
+                            self.expression(*expression, Precedence::Lowest)
+                        }
+                        Statement::AssignmentStatement(statement) => {
+                            self.assignment(statement.target, statement.value)
+                        }
+                        _ => doc::text(""),
+                    };
+                    ((*id).into(), ast[*id].span(), printed)
+                }
+            };
+            parts.push(self.with_comments(node, span, printed));
+        }
+        doc::concat(parts)
+    }
+
+    fn for_in_of_left(&mut self, left: &ForInOfLeft) -> Doc {
+        match left {
+            ForInOfLeft::Declaration { token, name, .. } => doc::concat(vec![
+                doc::text(format!("{} ", declaration_keyword(token))),
+                self.binding_pattern(name),
+            ]),
+            ForInOfLeft::Target(target) => self.expression(*target, Precedence::Call),
+        }
+    }
+}
+
+fn declaration_keyword(token: &Token) -> &'static str {
+    match token {
+        Token::Keyword(KeywordToken::Var) => "var",
+        Token::Keyword(KeywordToken::Const) => "const",
+        _ => "let",
+    }
+}
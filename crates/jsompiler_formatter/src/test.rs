@@ -0,0 +1,188 @@
+#[allow(unused_imports)]
+use super::{FormatOptions, Quote, TrailingCommas, format};
+
+#[allow(dead_code)]
+fn formatted(source: &str, options: &FormatOptions) -> String {
+    let output = format(source, options).unwrap_or_else(|errors| panic!("{source}: {errors:?}"));
+    // Formatting the output again must not change it
+    let again = format(&output, options).unwrap_or_else(|errors| panic!("{output}: {errors:?}"));
+    assert_eq!(output, again, "not idempotent for {source:?}");
+    output
+}
+
+#[allow(dead_code)]
+fn default(source: &str) -> String {
+    formatted(source, &FormatOptions::default())
+}
+
+#[test]
+fn test_statements_are_normalized() {
+    assert_eq!(
+        default("let a=1,b='x'\nif(a){b()}else c()\n"),
+        "let a = 1,\n  b = \"x\";\nif (a) {\n  b();\n} else c();\n"
+    );
+    assert_eq!(
+        default("function f(a,b=2,...rest){return a}\n"),
+        "function f(a, b = 2, ...rest) {\n  return a;\n}\n"
+    );
+    assert_eq!(default("x = function(){}\n"), "x = function () {};\n");
+}
+
+#[test]
+fn test_long_lines_are_broken() {
+    assert_eq!(
+        default(
+            "const value = someFunction(argumentNumberOne, argumentNumberTwo, argumentNumberThree)\n"
+        ),
+        "const value = someFunction(\n  argumentNumberOne,\n  argumentNumberTwo,\n  argumentNumberThree,\n);\n"
+    );
+    assert_eq!(
+        default(
+            "const sum = first + second + third + fourth + fifth + sixth + seventh + eighth + ninth\n"
+        ),
+        "const sum =\n  first + second + third + fourth + fifth + sixth + seventh + eighth + ninth;\n"
+    );
+    // The last argument is hugged when it is a function
+    assert_eq!(
+        default("items.forEach((item) => { process(item) })\n"),
+        "items.forEach((item) => {\n  process(item);\n});\n"
+    );
+    // An object broken after `{` in the source stays broken
+    assert_eq!(default("x = { a: 1,\n b: 2 }\n"), "x = { a: 1, b: 2 };\n");
+    assert_eq!(
+        default("x = {\n a: 1, b: 2 }\n"),
+        "x = {\n  a: 1,\n  b: 2,\n};\n"
+    );
+}
+
+#[test]
+fn test_options() {
+    let options = FormatOptions {
+        print_width: 20,
+        use_tabs: true,
+        quote: Quote::Single,
+        trailing_commas: TrailingCommas::None,
+        semicolons: false,
+        ..Default::default()
+    };
+    assert_eq!(
+        formatted("let s = \"a\"\nf(first, second, third)\n", &options),
+        "let s = 'a'\nf(\n\tfirst,\n\tsecond,\n\tthird\n)\n"
+    );
+    // A string holding the preferred quote keeps the other one
+    assert_eq!(formatted("s = \"it's\"\n", &options), "s = \"it's\"\n");
+    // Without semicolons, a statement starting with `(` gets one in front
+    assert_eq!(
+        formatted("a\n;(b + c).d()\n", &options),
+        "a\n;(b + c).d()\n"
+    );
+
+    let es5 = FormatOptions {
+        print_width: 20,
+        trailing_commas: TrailingCommas::Es5,
+        indent_width: 4,
+        ..Default::default()
+    };
+    assert_eq!(
+        formatted(
+            "x = [first, second, third]\nf(first, second, third)\n",
+            &es5
+        ),
+        "x = [\n    first,\n    second,\n    third,\n];\nf(\n    first,\n    second,\n    third\n);\n"
+    );
+}
+
+#[test]
+fn test_comments_and_blank_lines_are_kept() {
+    let source = "#!/usr/bin/env node\n/**\n   * Adds\n   */\nfunction f() {\n  // first\n\n\n  let a = 1 // one\n  return a\n}\n\nconst o = {\n  // note\n  a: 1,\n\n  b: [], /* b */\n}\nconst e = {/* empty */}\n";
+    assert_eq!(
+        default(source),
+        "#!/usr/bin/env node\n/**\n * Adds\n */\nfunction f() {\n  // first\n\n  let a = 1; // one\n  return a;\n}\n\nconst o = {\n  // note\n  a: 1,\n\n  b: [] /* b */,\n};\nconst e = { /* empty */ };\n"
+    );
+    assert_eq!(
+        default("class A {\n  // field\n  x = 1\n\n  m() {}\n}\n"),
+        "class A {\n  // field\n  x = 1;\n\n  m() {}\n}\n"
+    );
+}
+
+#[test]
+fn test_jsx() {
+    let options = FormatOptions {
+        jsx: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        formatted(
+            "x = <div className=\"a\" onClick={handleClickEventWithAVeryLongName} data-extra='v'>\n  Hi {name}\n  {/* note */}\n</div>\n",
+            &options
+        ),
+        "x = <div\n  className=\"a\"\n  onClick={handleClickEventWithAVeryLongName}\n  data-extra='v'\n>\n  Hi {name}\n  {/* note */}\n</div>;\n"
+    );
+}
+
+// Formatting the output of the formatter again gives the same text
+#[test]
+fn test_formatting_is_idempotent() {
+    let fixtures = [
+        "w = (function () { return 1; })();\n",
+        "(function () {})();\n",
+        "({}).toString();\n",
+        "x = ({}).a;\n",
+        "(class {}).name;\n",
+        "x = () => ({}).a;\n",
+        "x = 0.000001 + y;\n",
+        "let a=1,b='x'\nif(a){b()}else c()\n",
+        "items.forEach((item) => { process(item) })\n",
+        "class A {\n  // field\n  x = 1\n\n  m() {}\n}\n",
+    ];
+    let options = FormatOptions::default();
+    for fixture in fixtures {
+        let once = format(fixture, &options).unwrap_or_else(|errors| panic!("{errors:?}"));
+        let twice = format(&once, &options).unwrap_or_else(|errors| panic!("{once}: {errors:?}"));
+        assert_eq!(once, twice, "{fixture}");
+    }
+    assert_eq!(
+        default("w = (function () { return 1; })();\n"),
+        "w = (function () {\n  return 1;\n})();\n"
+    );
+    assert_eq!(default("(class {}).name\n"), "(class {}).name;\n");
+}
+
+#[test]
+fn test_syntax_errors_are_returned() {
+    assert!(format("let = ;\n", &FormatOptions::default()).is_err());
+}
+
+// A comment ending its line after `return` or `throw` would end the statement, so the
+// parentheses around the value are kept. `formatted` checks the output parses and is stable.
+#[test]
+fn test_comment_led_values_keep_parentheses() {
+    assert_eq!(
+        default("function f() { return ( // c\n a ) }\n"),
+        "function f() {\n  return (\n    // c\n    a\n  );\n}\n"
+    );
+    assert_eq!(
+        default("function f() { return (\n /* c */\n a.b ) }\n"),
+        "function f() {\n  return (\n    /* c */\n    a.b\n  );\n}\n"
+    );
+    assert_eq!(
+        default("function f() { return ( // c\n a).b }\n"),
+        "function f() {\n  return (\n    // c\n    a.b\n  );\n}\n"
+    );
+    assert_eq!(
+        default("function f() { throw ( /* c\n */ a) }\n"),
+        "function f() {\n  throw (\n    /* c\n     */ a\n  );\n}\n"
+    );
+    assert_eq!(
+        default("function f() { return /* c */ a }\n"),
+        "function f() {\n  return /* c */ a;\n}\n"
+    );
+    assert_eq!(
+        default("x = () => ( // c\n a )\n"),
+        "x = () => (\n  // c\n  a\n);\n"
+    );
+    assert_eq!(
+        default("x = () => (\n // c\n {} )\n"),
+        "x = () => (\n  // c\n  {}\n);\n"
+    );
+}
@@ -0,0 +1,1217 @@
+use super::{MAX_LENGTH, Result, argument, index_key};
+use crate::Interpreter;
+use crate::inspect::inspect;
+use crate::number::{number_to_string, to_uint32};
+use crate::object::NativeFunction;
+use jsompiler_runtime::{
+    IterationKind, Object, ObjectKind, ObjectRef, Property, PropertyDescriptor, PropertyKey, Value,
+};
+use std::cmp::Ordering;
+
+pub(super) fn install(interpreter: &mut Interpreter) {
+    let prototype = interpreter.intrinsics.array_prototype.clone();
+    let constructor = interpreter.native_constructor("Array", 1, array, &prototype);
+    let statics: [(&str, usize, NativeFunction); 3] = [
+        ("from", 1, from),
+        ("isArray", 1, is_array_method),
+        ("of", 0, of),
+    ];
+    for (name, length, function) in statics {
+        interpreter.define_method(&constructor, name, length, function);
+    }
+    let species = interpreter.intrinsics.symbols.species.clone();
+    interpreter.define_getter(&constructor, species, get_species);
+
+    let methods: [(&str, usize, NativeFunction); 38] = [
+        ("at", 1, at),
+        ("concat", 1, concat),
+        ("copyWithin", 2, copy_within),
+        ("entries", 0, entries),
+        ("every", 1, every),
+        ("fill", 1, fill),
+        ("filter", 1, filter),
+        ("find", 1, find),
+        ("findIndex", 1, find_index),
+        ("findLast", 1, find_last),
+        ("findLastIndex", 1, find_last_index),
+        ("flat", 0, flat),
+        ("flatMap", 1, flat_map),
+        ("forEach", 1, for_each),
+        ("includes", 1, includes),
+        ("indexOf", 1, index_of),
+        ("join", 1, join),
+        ("keys", 0, keys),
+        ("lastIndexOf", 1, last_index_of),
+        ("map", 1, map),
+        ("pop", 0, pop),
+        ("push", 1, push),
+        ("reduce", 1, reduce),
+        ("reduceRight", 1, reduce_right),
+        ("reverse", 0, reverse),
+        ("shift", 0, shift),
+        ("slice", 2, slice),
+        ("some", 1, some),
+        ("sort", 1, sort),
+        ("splice", 2, splice),
+        ("toLocaleString", 0, to_locale_string),
+        ("toReversed", 0, to_reversed),
+        ("toSorted", 1, to_sorted),
+        ("toSpliced", 2, to_spliced),
+        ("toString", 0, to_string),
+        ("unshift", 1, unshift),
+        ("values", 0, values),
+        ("with", 2, with),
+    ];
+    for (name, length, function) in methods {
+        interpreter.define_method(&prototype, name, length, function);
+    }
+    // `Array.prototype[Symbol.iterator]` is `Array.prototype.values`
+    let values = prototype
+        .borrow()
+        .get_own(&"values".into())
+        .map(|property| property.data_value())
+        .expect("`values` is defined above");
+    let iterator = interpreter.intrinsics.symbols.iterator.clone();
+    prototype
+        .borrow_mut()
+        .define(iterator, Property::hidden(values));
+    // The methods a `with` statement doesn't see, which were added after code used the names
+    let mut unscopables = Object::new(None, ObjectKind::Ordinary);
+    for name in [
+        "at",
+        "copyWithin",
+        "entries",
+        "fill",
+        "find",
+        "findIndex",
+        "findLast",
+        "findLastIndex",
+        "flat",
+        "flatMap",
+        "includes",
+        "keys",
+        "toReversed",
+        "toSorted",
+        "toSpliced",
+        "values",
+    ] {
+        unscopables.define(name, Property::data(Value::Boolean(true)));
+    }
+    let unscopables = Value::Object(ObjectRef::new(unscopables));
+    let key = interpreter.intrinsics.symbols.unscopables.clone();
+    let property = Property::new(unscopables, false, false, true);
+    prototype.borrow_mut().define(key, property);
+    interpreter.define_global("Array", constructor);
+}
+
+// IsArray
+fn is_array(value: &Value) -> bool {
+    matches!(value, Value::Object(object) if matches!(object.borrow().kind, ObjectKind::Array))
+}
+
+impl Interpreter {
+    // ArrayCreate
+    fn array_create(&mut self, length: u64) -> Result<ObjectRef> {
+        if length > u32::MAX as u64 {
+            return Err(self.range_error("Invalid array length"));
+        }
+        let array = self.new_array(Vec::new());
+        if length > 0 {
+            let descriptor = PropertyDescriptor::value(Value::Number(length as f64));
+            array
+                .borrow_mut()
+                .define_own_property("length".into(), descriptor);
+        }
+        Ok(array)
+    }
+
+    // ArraySpeciesCreate: an array like `original`, made by the constructor its
+    // `constructor[Symbol.species]` names, so that subclasses get instances of themselves
+    fn array_species_create(&mut self, original: &ObjectRef, length: u64) -> Result<ObjectRef> {
+        let original_value = Value::Object(original.clone());
+        if !is_array(&original_value) {
+            return self.array_create(length);
+        }
+        let mut constructor = self.get(original, &"constructor".into(), &original_value)?;
+        if let Value::Object(object) = &constructor {
+            let key = PropertyKey::Symbol(self.intrinsics.symbols.species.clone());
+            constructor = match self.get(object, &key, &constructor)? {
+                Value::Null => Value::Undefined,
+                species => species,
+            };
+        }
+        if matches!(constructor, Value::Undefined) {
+            return self.array_create(length);
+        }
+        if !constructor
+            .as_object()
+            .is_some_and(|object| self.is_constructor(object))
+        {
+            return Err(self.type_error("object.constructor[Symbol.species] is not a constructor"));
+        }
+        let array = self.construct(&constructor, &[Value::Number(length as f64)], None)?;
+        self.constructed_object(array)
+    }
+
+    // The object a constructor returned, which `Array.from` and `Array.of` add to
+    fn constructed_object(&mut self, value: Value) -> Result<ObjectRef> {
+        match value {
+            Value::Object(object) => Ok(object),
+            value => {
+                let message = format!("{} is not an object", inspect(&value));
+                Err(self.type_error(&message))
+            }
+        }
+    }
+
+    // ToObject(this) and its length, which the generic array methods start with
+    fn this_array_like(&mut self, this: &Value) -> Result<(ObjectRef, u64)> {
+        let object = self.to_object(this)?;
+        let length = self.length_of_array_like(&object)?;
+        Ok((object, length))
+    }
+
+    fn get_index(&mut self, object: &ObjectRef, index: u64) -> Result<Value> {
+        self.get(object, &index_key(index), &Value::Object(object.clone()))
+    }
+
+    fn set_index(&mut self, object: &ObjectRef, index: u64, value: Value) -> Result<()> {
+        self.put(object, &index_key(index), value)
+    }
+
+    fn set_length(&mut self, object: &ObjectRef, length: u64) -> Result<()> {
+        self.put(object, &"length".into(), Value::Number(length as f64))
+    }
+
+    // The callback argument of methods like `map`, which must be a function
+    fn callback(&mut self, args: &[Value]) -> Result<Value> {
+        let callback = argument(args, 0);
+        if callback.as_function().is_none() {
+            let message = format!("{} is not a function", inspect(&callback));
+            return Err(self.type_error(&message));
+        }
+        Ok(callback)
+    }
+
+    // Call a callback with an element, its index and the object, as `forEach` and the
+    // others like it do
+    fn call_callback(
+        &mut self,
+        callback: &Value,
+        this: &Value,
+        value: Value,
+        index: u64,
+        object: &ObjectRef,
+    ) -> Result<Value> {
+        let args = [
+            value,
+            Value::Number(index as f64),
+            Value::Object(object.clone()),
+        ];
+        self.call(callback, this.clone(), &args)
+    }
+}
+
+fn has_index(object: &ObjectRef, index: u64) -> bool {
+    object.has_property(&index_key(index))
+}
+
+// Whether adding `count` elements to `length` would pass the largest length
+fn exceeds_max_length(length: u64, count: u64) -> bool {
+    length as f64 + count as f64 > MAX_LENGTH
+}
+
+fn too_long(interpreter: &mut Interpreter) -> Value {
+    interpreter.type_error("Array length exceeds the largest possible length, 2 ** 53 - 1")
+}
+
+// Array ( ...values )
+fn array(
+    interpreter: &mut Interpreter,
+    args: &[Value],
+    new_target: Option<&ObjectRef>,
+) -> Result<Value> {
+    let default = interpreter.intrinsics.array_prototype.clone();
+    let prototype = match new_target {
+        Some(new_target) => interpreter.prototype_from_constructor(new_target, default)?,
+        None => default,
+    };
+    let array = match args {
+        [Value::Number(length)] => {
+            let valid = to_uint32(*length);
+            if valid as f64 != *length {
+                return Err(interpreter.range_error("Invalid array length"));
+            }
+            interpreter.array_create(valid as u64)?
+        }
+        args => interpreter.new_array(args.to_vec()),
+    };
+    array.borrow_mut().prototype = Some(prototype);
+    Ok(Value::Object(array))
+}
+
+// Array.from ( items [ , mapfn [ , thisArg ] ] ): an array of the values of an iterable, or
+// of the indices of an array-like object
+fn from(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let items = argument(args, 0);
+    let map = match argument(args, 1) {
+        Value::Undefined => None,
+        map if map.as_function().is_some() => Some(map),
+        map => {
+            let message = format!("{} is not a function", inspect(&map));
+            return Err(interpreter.type_error(&message));
+        }
+    };
+    let this_arg = argument(args, 2);
+    let is_constructor = this
+        .as_object()
+        .is_some_and(|object| interpreter.is_constructor(object));
+    let key = PropertyKey::Symbol(interpreter.intrinsics.symbols.iterator.clone());
+    if let Some(method) = interpreter.get_method(&items, &key)? {
+        let array = if is_constructor {
+            let array = interpreter.construct(&this, &[], None)?;
+            interpreter.constructed_object(array)?
+        } else {
+            interpreter.array_create(0)?
+        };
+        let mut record = interpreter.get_iterator_from_method(&items, &method)?;
+        let mut index = 0;
+        while let Some(value) = interpreter.iterator_step(&mut record)? {
+            let added = match &map {
+                Some(map) => {
+                    let args = [value, Value::Number(index as f64)];
+                    interpreter.call(map, this_arg.clone(), &args)
+                }
+                None => Ok(value),
+            }
+            .and_then(|value| interpreter.create_data_property(&array, index_key(index), value));
+            interpreter.iterator_close_on_error(&record, added)?;
+            index += 1;
+        }
+        interpreter.set_length(&array, index)?;
+        return Ok(Value::Object(array));
+    }
+    let (array_like, length) = interpreter.this_array_like(&items)?;
+    let array = if is_constructor {
+        let array = interpreter.construct(&this, &[Value::Number(length as f64)], None)?;
+        interpreter.constructed_object(array)?
+    } else {
+        interpreter.array_create(length)?
+    };
+    for index in 0..length {
+        let value = interpreter.get_index(&array_like, index)?;
+        let value = match &map {
+            Some(map) => {
+                let args = [value, Value::Number(index as f64)];
+                interpreter.call(map, this_arg.clone(), &args)?
+            }
+            None => value,
+        };
+        interpreter.create_data_property(&array, index_key(index), value)?;
+    }
+    interpreter.set_length(&array, length)?;
+    Ok(Value::Object(array))
+}
+
+// Array.isArray ( arg )
+fn is_array_method(_: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    Ok(Value::Boolean(is_array(&argument(args, 0))))
+}
+
+// Array.of ( ...items )
+fn of(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let length = args.len() as u64;
+    let array = if this
+        .as_object()
+        .is_some_and(|object| interpreter.is_constructor(object))
+    {
+        let array = interpreter.construct(&this, &[Value::Number(length as f64)], None)?;
+        interpreter.constructed_object(array)?
+    } else {
+        interpreter.array_create(length)?
+    };
+    for (index, item) in args.iter().enumerate() {
+        interpreter.create_data_property(&array, index_key(index as u64), item.clone())?;
+    }
+    interpreter.set_length(&array, length)?;
+    Ok(Value::Object(array))
+}
+
+// get Array [ @@species ], which Map and Set share
+pub(super) fn get_species(_: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    Ok(this)
+}
+
+// Array.prototype.at ( index )
+fn at(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let (object, length) = interpreter.this_array_like(&this)?;
+    let relative = interpreter.to_integer_or_infinity(&argument(args, 0))?;
+    let index = if relative >= 0.0 {
+        relative
+    } else {
+        length as f64 + relative
+    };
+    if index < 0.0 || index >= length as f64 {
+        return Ok(Value::Undefined);
+    }
+    interpreter.get_index(&object, index as u64)
+}
+
+// Array.prototype.concat ( ...items )
+fn concat(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let object = interpreter.to_object(&this)?;
+    let array = interpreter.array_species_create(&object, 0)?;
+    let mut length = 0;
+    let items = std::iter::once(Value::Object(object)).chain(args.iter().cloned());
+    for item in items {
+        // IsConcatSpreadable
+        let spreadable = match &item {
+            Value::Object(object) => {
+                let key = PropertyKey::Symbol(
+                    interpreter.intrinsics.symbols.is_concat_spreadable.clone(),
+                );
+                match interpreter.get(object, &key, &item)? {
+                    Value::Undefined => is_array(&item),
+                    spreadable => spreadable.to_boolean(),
+                }
+            }
+            _ => false,
+        };
+        let Some(spread) = item.as_object().filter(|_| spreadable) else {
+            if exceeds_max_length(length, 1) {
+                return Err(too_long(interpreter));
+            }
+            interpreter.create_data_property(&array, index_key(length), item)?;
+            length += 1;
+            continue;
+        };
+        let item_length = interpreter.length_of_array_like(spread)?;
+        if exceeds_max_length(length, item_length) {
+            return Err(too_long(interpreter));
+        }
+        for index in 0..item_length {
+            if has_index(spread, index) {
+                let value = interpreter.get_index(spread, index)?;
+                interpreter.create_data_property(&array, index_key(length), value)?;
+            }
+            length += 1;
+        }
+    }
+    interpreter.set_length(&array, length)?;
+    Ok(Value::Object(array))
+}
+
+// Array.prototype.copyWithin ( target, start [ , end ] )
+fn copy_within(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let (object, length) = interpreter.this_array_like(&this)?;
+    let mut to = interpreter.relative_index(&argument(args, 0), length)?;
+    let mut from = interpreter.relative_index(&argument(args, 1), length)?;
+    let end = match argument(args, 2) {
+        Value::Undefined => length,
+        end => interpreter.relative_index(&end, length)?,
+    };
+    let count = end.saturating_sub(from).min(length - to);
+    // Copy from the end when the ranges overlap with the target after the source
+    let backwards = from < to && to < from + count;
+    if backwards {
+        from += count - 1;
+        to += count - 1;
+    }
+    for _ in 0..count {
+        if has_index(&object, from) {
+            let value = interpreter.get_index(&object, from)?;
+            interpreter.set_index(&object, to, value)?;
+        } else {
+            interpreter.delete_or_throw(&object, &index_key(to))?;
+        }
+        if backwards {
+            from = from.wrapping_sub(1);
+            to = to.wrapping_sub(1);
+        } else {
+            from += 1;
+            to += 1;
+        }
+    }
+    Ok(Value::Object(object))
+}
+
+// Array.prototype.entries ( )
+fn entries(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    let object = interpreter.to_object(&this)?;
+    Ok(interpreter.array_iterator(Value::Object(object), IterationKind::Entries))
+}
+
+// Array.prototype.every ( callbackfn [ , thisArg ] )
+fn every(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let (object, length) = interpreter.this_array_like(&this)?;
+    let callback = interpreter.callback(args)?;
+    let this_arg = argument(args, 1);
+    for index in 0..length {
+        if has_index(&object, index) {
+            let value = interpreter.get_index(&object, index)?;
+            let result = interpreter.call_callback(&callback, &this_arg, value, index, &object)?;
+            if !result.to_boolean() {
+                return Ok(Value::Boolean(false));
+            }
+        }
+    }
+    Ok(Value::Boolean(true))
+}
+
+// Array.prototype.fill ( value [ , start [ , end ] ] )
+fn fill(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let (object, length) = interpreter.this_array_like(&this)?;
+    let start = interpreter.relative_index(&argument(args, 1), length)?;
+    let end = match argument(args, 2) {
+        Value::Undefined => length,
+        end => interpreter.relative_index(&end, length)?,
+    };
+    for index in start..end {
+        interpreter.set_index(&object, index, argument(args, 0))?;
+    }
+    Ok(Value::Object(object))
+}
+
+// Array.prototype.filter ( callbackfn [ , thisArg ] )
+fn filter(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let (object, length) = interpreter.this_array_like(&this)?;
+    let callback = interpreter.callback(args)?;
+    let this_arg = argument(args, 1);
+    let array = interpreter.array_species_create(&object, 0)?;
+    let mut to = 0;
+    for index in 0..length {
+        if has_index(&object, index) {
+            let value = interpreter.get_index(&object, index)?;
+            let selected =
+                interpreter.call_callback(&callback, &this_arg, value.clone(), index, &object)?;
+            if selected.to_boolean() {
+                interpreter.create_data_property(&array, index_key(to), value)?;
+                to += 1;
+            }
+        }
+    }
+    Ok(Value::Object(array))
+}
+
+// FindViaPredicate: the first element, going forward or back, the predicate accepts, and
+// its index. Holes are read as undefined.
+fn find_via_predicate(
+    interpreter: &mut Interpreter,
+    this: Value,
+    args: &[Value],
+    backwards: bool,
+) -> Result<Option<(u64, Value)>> {
+    let (object, length) = interpreter.this_array_like(&this)?;
+    let predicate = interpreter.callback(args)?;
+    let this_arg = argument(args, 1);
+    for step in 0..length {
+        let index = if backwards { length - 1 - step } else { step };
+        let value = interpreter.get_index(&object, index)?;
+        let result =
+            interpreter.call_callback(&predicate, &this_arg, value.clone(), index, &object)?;
+        if result.to_boolean() {
+            return Ok(Some((index, value)));
+        }
+    }
+    Ok(None)
+}
+
+// Array.prototype.find ( predicate [ , thisArg ] )
+fn find(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let found = find_via_predicate(interpreter, this, args, false)?;
+    Ok(found.map_or(Value::Undefined, |(_, value)| value))
+}
+
+// Array.prototype.findIndex ( predicate [ , thisArg ] )
+fn find_index(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let found = find_via_predicate(interpreter, this, args, false)?;
+    Ok(Value::Number(found.map_or(-1.0, |(index, _)| index as f64)))
+}
+
+// Array.prototype.findLast ( predicate [ , thisArg ] )
+fn find_last(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let found = find_via_predicate(interpreter, this, args, true)?;
+    Ok(found.map_or(Value::Undefined, |(_, value)| value))
+}
+
+// Array.prototype.findLastIndex ( predicate [ , thisArg ] )
+fn find_last_index(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let found = find_via_predicate(interpreter, this, args, true)?;
+    Ok(Value::Number(found.map_or(-1.0, |(index, _)| index as f64)))
+}
+
+// FlattenIntoArray: add the elements of `source` to `target` from `start`, going `depth`
+// arrays deep, and return where the next element goes
+fn flatten_into_array(
+    interpreter: &mut Interpreter,
+    target: &ObjectRef,
+    source: &ObjectRef,
+    source_length: u64,
+    start: u64,
+    depth: f64,
+    mapper: Option<(&Value, &Value)>,
+) -> Result<u64> {
+    let mut target_index = start;
+    for source_index in 0..source_length {
+        if !has_index(source, source_index) {
+            continue;
+        }
+        let mut element = interpreter.get_index(source, source_index)?;
+        if let Some((mapper, this_arg)) = mapper {
+            element = interpreter.call_callback(mapper, this_arg, element, source_index, source)?;
+        }
+        if depth > 0.0 && is_array(&element) {
+            let element = element.as_object().expect("arrays are objects").clone();
+            let length = interpreter.length_of_array_like(&element)?;
+            target_index = flatten_into_array(
+                interpreter,
+                target,
+                &element,
+                length,
+                target_index,
+                depth - 1.0,
+                None,
+            )?;
+        } else {
+            if exceeds_max_length(target_index, 1) {
+                return Err(too_long(interpreter));
+            }
+            interpreter.create_data_property(target, index_key(target_index), element)?;
+            target_index += 1;
+        }
+    }
+    Ok(target_index)
+}
+
+// Array.prototype.flat ( [ depth ] )
+fn flat(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let (object, length) = interpreter.this_array_like(&this)?;
+    let depth = match argument(args, 0) {
+        Value::Undefined => 1.0,
+        depth => interpreter.to_integer_or_infinity(&depth)?.max(0.0),
+    };
+    let array = interpreter.array_species_create(&object, 0)?;
+    flatten_into_array(interpreter, &array, &object, length, 0, depth, None)?;
+    Ok(Value::Object(array))
+}
+
+// Array.prototype.flatMap ( mapperFunction [ , thisArg ] )
+fn flat_map(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let (object, length) = interpreter.this_array_like(&this)?;
+    let mapper = interpreter.callback(args)?;
+    let this_arg = argument(args, 1);
+    let array = interpreter.array_species_create(&object, 0)?;
+    let mapper = Some((&mapper, &this_arg));
+    flatten_into_array(interpreter, &array, &object, length, 0, 1.0, mapper)?;
+    Ok(Value::Object(array))
+}
+
+// Array.prototype.forEach ( callbackfn [ , thisArg ] )
+fn for_each(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let (object, length) = interpreter.this_array_like(&this)?;
+    let callback = interpreter.callback(args)?;
+    let this_arg = argument(args, 1);
+    for index in 0..length {
+        if has_index(&object, index) {
+            let value = interpreter.get_index(&object, index)?;
+            interpreter.call_callback(&callback, &this_arg, value, index, &object)?;
+        }
+    }
+    Ok(Value::Undefined)
+}
+
+// The index `includes` and `indexOf` start searching from
+fn search_start(interpreter: &mut Interpreter, value: &Value, length: u64) -> Result<Option<u64>> {
+    let start = interpreter.to_integer_or_infinity(value)?;
+    Ok(if start == f64::INFINITY {
+        None
+    } else if start >= 0.0 {
+        Some(start as u64)
+    } else {
+        Some((length as f64 + start).max(0.0) as u64)
+    })
+}
+
+// Array.prototype.includes ( searchElement [ , fromIndex ] ), which finds NaN and reads
+// holes as undefined
+fn includes(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let (object, length) = interpreter.this_array_like(&this)?;
+    if length == 0 {
+        return Ok(Value::Boolean(false));
+    }
+    let Some(start) = search_start(interpreter, &argument(args, 1), length)? else {
+        return Ok(Value::Boolean(false));
+    };
+    let search = argument(args, 0);
+    for index in start..length {
+        if interpreter
+            .get_index(&object, index)?
+            .same_value_zero(&search)
+        {
+            return Ok(Value::Boolean(true));
+        }
+    }
+    Ok(Value::Boolean(false))
+}
+
+// Array.prototype.indexOf ( searchElement [ , fromIndex ] )
+fn index_of(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let (object, length) = interpreter.this_array_like(&this)?;
+    if length == 0 {
+        return Ok(Value::Number(-1.0));
+    }
+    let Some(start) = search_start(interpreter, &argument(args, 1), length)? else {
+        return Ok(Value::Number(-1.0));
+    };
+    let search = argument(args, 0);
+    for index in start..length {
+        if has_index(&object, index)
+            && interpreter
+                .get_index(&object, index)?
+                .strict_equals(&search)
+        {
+            return Ok(Value::Number(index as f64));
+        }
+    }
+    Ok(Value::Number(-1.0))
+}
+
+// Array.prototype.join ( separator )
+fn join(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let (object, length) = interpreter.this_array_like(&this)?;
+    let separator = match argument(args, 0) {
+        Value::Undefined => ",".into(),
+        separator => interpreter.to_string(&separator)?,
+    };
+    let mut result = String::new();
+    for index in 0..length {
+        if index > 0 {
+            result.push_str(&separator);
+        }
+        let element = interpreter.get_index(&object, index)?;
+        if !element.is_nullish() {
+            result.push_str(&interpreter.to_string(&element)?);
+        }
+    }
+    Ok(Value::from(result))
+}
+
+// Array.prototype.keys ( )
+fn keys(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    let object = interpreter.to_object(&this)?;
+    Ok(interpreter.array_iterator(Value::Object(object), IterationKind::Keys))
+}
+
+// Array.prototype.lastIndexOf ( searchElement [ , fromIndex ] )
+fn last_index_of(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let (object, length) = interpreter.this_array_like(&this)?;
+    if length == 0 {
+        return Ok(Value::Number(-1.0));
+    }
+    let start = match args.get(1) {
+        Some(start) => interpreter.to_integer_or_infinity(start)?,
+        None => length as f64 - 1.0,
+    };
+    let start = if start >= 0.0 {
+        start.min(length as f64 - 1.0)
+    } else {
+        length as f64 + start
+    };
+    if start < 0.0 {
+        return Ok(Value::Number(-1.0));
+    }
+    let search = argument(args, 0);
+    for index in (0..=start as u64).rev() {
+        if has_index(&object, index)
+            && interpreter
+                .get_index(&object, index)?
+                .strict_equals(&search)
+        {
+            return Ok(Value::Number(index as f64));
+        }
+    }
+    Ok(Value::Number(-1.0))
+}
+
+// Array.prototype.map ( callbackfn [ , thisArg ] )
+fn map(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let (object, length) = interpreter.this_array_like(&this)?;
+    let callback = interpreter.callback(args)?;
+    let this_arg = argument(args, 1);
+    let array = interpreter.array_species_create(&object, length)?;
+    for index in 0..length {
+        if has_index(&object, index) {
+            let value = interpreter.get_index(&object, index)?;
+            let mapped = interpreter.call_callback(&callback, &this_arg, value, index, &object)?;
+            interpreter.create_data_property(&array, index_key(index), mapped)?;
+        }
+    }
+    Ok(Value::Object(array))
+}
+
+// Array.prototype.pop ( )
+fn pop(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    let (object, length) = interpreter.this_array_like(&this)?;
+    if length == 0 {
+        interpreter.set_length(&object, 0)?;
+        return Ok(Value::Undefined);
+    }
+    let element = interpreter.get_index(&object, length - 1)?;
+    interpreter.delete_or_throw(&object, &index_key(length - 1))?;
+    interpreter.set_length(&object, length - 1)?;
+    Ok(element)
+}
+
+// Array.prototype.push ( ...items )
+fn push(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let (object, mut length) = interpreter.this_array_like(&this)?;
+    if exceeds_max_length(length, args.len() as u64) {
+        return Err(too_long(interpreter));
+    }
+    for item in args {
+        interpreter.set_index(&object, length, item.clone())?;
+        length += 1;
+    }
+    interpreter.set_length(&object, length)?;
+    Ok(Value::Number(length as f64))
+}
+
+// What `reduce` and `reduceRight` share. Without an initial value, the first element there
+// is starts the accumulation.
+fn reduce_in_order(
+    interpreter: &mut Interpreter,
+    this: Value,
+    args: &[Value],
+    backwards: bool,
+) -> Result<Value> {
+    let (object, length) = interpreter.this_array_like(&this)?;
+    let callback = interpreter.callback(args)?;
+    let mut indices: Box<dyn Iterator<Item = u64>> = if backwards {
+        Box::new((0..length).rev())
+    } else {
+        Box::new(0..length)
+    };
+    let mut accumulator = match args.get(1) {
+        Some(initial) => initial.clone(),
+        None => {
+            let first = indices.by_ref().find(|&index| has_index(&object, index));
+            match first {
+                Some(index) => interpreter.get_index(&object, index)?,
+                None => {
+                    return Err(
+                        interpreter.type_error("Reduce of empty array with no initial value")
+                    );
+                }
+            }
+        }
+    };
+    for index in indices {
+        if has_index(&object, index) {
+            let value = interpreter.get_index(&object, index)?;
+            let args = [
+                accumulator,
+                value,
+                Value::Number(index as f64),
+                Value::Object(object.clone()),
+            ];
+            accumulator = interpreter.call(&callback, Value::Undefined, &args)?;
+        }
+    }
+    Ok(accumulator)
+}
+
+// Array.prototype.reduce ( callbackfn [ , initialValue ] )
+fn reduce(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    reduce_in_order(interpreter, this, args, false)
+}
+
+// Array.prototype.reduceRight ( callbackfn [ , initialValue ] )
+fn reduce_right(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    reduce_in_order(interpreter, this, args, true)
+}
+
+// Array.prototype.reverse ( ), which keeps holes where they end up
+fn reverse(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    let (object, length) = interpreter.this_array_like(&this)?;
+    for lower in 0..length / 2 {
+        let upper = length - lower - 1;
+        let lower_value = match has_index(&object, lower) {
+            true => Some(interpreter.get_index(&object, lower)?),
+            false => None,
+        };
+        let upper_value = match has_index(&object, upper) {
+            true => Some(interpreter.get_index(&object, upper)?),
+            false => None,
+        };
+        match (lower_value, upper_value) {
+            (Some(lower_value), Some(upper_value)) => {
+                interpreter.set_index(&object, lower, upper_value)?;
+                interpreter.set_index(&object, upper, lower_value)?;
+            }
+            (None, Some(upper_value)) => {
+                interpreter.set_index(&object, lower, upper_value)?;
+                interpreter.delete_or_throw(&object, &index_key(upper))?;
+            }
+            (Some(lower_value), None) => {
+                interpreter.delete_or_throw(&object, &index_key(lower))?;
+                interpreter.set_index(&object, upper, lower_value)?;
+            }
+            (None, None) => {}
+        }
+    }
+    Ok(Value::Object(object))
+}
+
+// Move the elements from `from` on by `to - from` places, keeping holes, as `shift`,
+// `splice` and `unshift` do
+fn move_element(
+    interpreter: &mut Interpreter,
+    object: &ObjectRef,
+    from: u64,
+    to: u64,
+) -> Result<()> {
+    if has_index(object, from) {
+        let value = interpreter.get_index(object, from)?;
+        interpreter.set_index(object, to, value)
+    } else {
+        interpreter.delete_or_throw(object, &index_key(to))
+    }
+}
+
+// Array.prototype.shift ( )
+fn shift(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    let (object, length) = interpreter.this_array_like(&this)?;
+    if length == 0 {
+        interpreter.set_length(&object, 0)?;
+        return Ok(Value::Undefined);
+    }
+    let first = interpreter.get_index(&object, 0)?;
+    for index in 1..length {
+        move_element(interpreter, &object, index, index - 1)?;
+    }
+    interpreter.delete_or_throw(&object, &index_key(length - 1))?;
+    interpreter.set_length(&object, length - 1)?;
+    Ok(first)
+}
+
+// Array.prototype.slice ( start, end )
+fn slice(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let (object, length) = interpreter.this_array_like(&this)?;
+    let start = interpreter.relative_index(&argument(args, 0), length)?;
+    let end = match argument(args, 1) {
+        Value::Undefined => length,
+        end => interpreter.relative_index(&end, length)?,
+    };
+    let count = end.saturating_sub(start);
+    let array = interpreter.array_species_create(&object, count)?;
+    for (to, from) in (start..end).enumerate() {
+        if has_index(&object, from) {
+            let value = interpreter.get_index(&object, from)?;
+            interpreter.create_data_property(&array, index_key(to as u64), value)?;
+        }
+    }
+    interpreter.set_length(&array, count)?;
+    Ok(Value::Object(array))
+}
+
+// Array.prototype.some ( callbackfn [ , thisArg ] )
+fn some(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let (object, length) = interpreter.this_array_like(&this)?;
+    let callback = interpreter.callback(args)?;
+    let this_arg = argument(args, 1);
+    for index in 0..length {
+        if has_index(&object, index) {
+            let value = interpreter.get_index(&object, index)?;
+            let result = interpreter.call_callback(&callback, &this_arg, value, index, &object)?;
+            if result.to_boolean() {
+                return Ok(Value::Boolean(true));
+            }
+        }
+    }
+    Ok(Value::Boolean(false))
+}
+
+// The comparison function of `sort` and `toSorted`, which must be a function if it is given
+fn comparator(interpreter: &mut Interpreter, args: &[Value]) -> Result<Option<Value>> {
+    match argument(args, 0) {
+        Value::Undefined => Ok(None),
+        compare if compare.as_function().is_some() => Ok(Some(compare)),
+        _ => Err(interpreter
+            .type_error("The comparison function must be either a function or undefined")),
+    }
+}
+
+// SortCompare: undefined goes last, and without a comparison function, elements are
+// compared as strings by their code units
+fn sort_compare(
+    interpreter: &mut Interpreter,
+    compare: Option<&Value>,
+    x: &Value,
+    y: &Value,
+) -> Result<Ordering> {
+    match (x, y) {
+        (Value::Undefined, Value::Undefined) => return Ok(Ordering::Equal),
+        (Value::Undefined, _) => return Ok(Ordering::Greater),
+        (_, Value::Undefined) => return Ok(Ordering::Less),
+        _ => {}
+    }
+    if let Some(compare) = compare {
+        let result = interpreter.call(compare, Value::Undefined, &[x.clone(), y.clone()])?;
+        let result = interpreter.to_number(&result)?;
+        return Ok(result.partial_cmp(&0.0).unwrap_or(Ordering::Equal));
+    }
+    let x = interpreter.to_string(x)?;
+    let y = interpreter.to_string(y)?;
+    Ok(x.encode_utf16().cmp(y.encode_utf16()))
+}
+
+// A stable merge sort that stops at the first exception the comparison throws
+fn merge_sort(
+    interpreter: &mut Interpreter,
+    items: Vec<Value>,
+    compare: Option<&Value>,
+) -> Result<Vec<Value>> {
+    if items.len() <= 1 {
+        return Ok(items);
+    }
+    let mut left = items;
+    let right = left.split_off(left.len() / 2);
+    let left = merge_sort(interpreter, left, compare)?;
+    let right = merge_sort(interpreter, right, compare)?;
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    let mut left = left.into_iter().peekable();
+    let mut right = right.into_iter().peekable();
+    while let (Some(x), Some(y)) = (left.peek(), right.peek()) {
+        // Equal elements keep their order by taking from the left first
+        if sort_compare(interpreter, compare, y, x)? == Ordering::Less {
+            merged.extend(right.next());
+        } else {
+            merged.extend(left.next());
+        }
+    }
+    merged.extend(left);
+    merged.extend(right);
+    Ok(merged)
+}
+
+// SortIndexedProperties: the sorted elements, leaving out holes if `skip_holes` is set
+fn sort_indexed_properties(
+    interpreter: &mut Interpreter,
+    object: &ObjectRef,
+    length: u64,
+    compare: Option<&Value>,
+    skip_holes: bool,
+) -> Result<Vec<Value>> {
+    let mut items = Vec::new();
+    for index in 0..length {
+        if !skip_holes || has_index(object, index) {
+            items.push(interpreter.get_index(object, index)?);
+        }
+    }
+    merge_sort(interpreter, items, compare)
+}
+
+// Array.prototype.sort ( comparefn ), which moves holes to the end
+fn sort(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let compare = comparator(interpreter, args)?;
+    let (object, length) = interpreter.this_array_like(&this)?;
+    let items = sort_indexed_properties(interpreter, &object, length, compare.as_ref(), true)?;
+    let count = items.len() as u64;
+    for (index, item) in items.into_iter().enumerate() {
+        interpreter.set_index(&object, index as u64, item)?;
+    }
+    for index in count..length {
+        interpreter.delete_or_throw(&object, &index_key(index))?;
+    }
+    Ok(Value::Object(object))
+}
+
+// The start and the number of elements `splice` and `toSpliced` remove
+fn splice_range(interpreter: &mut Interpreter, args: &[Value], length: u64) -> Result<(u64, u64)> {
+    let start = interpreter.relative_index(&argument(args, 0), length)?;
+    let skip = match args.len() {
+        0 => 0,
+        1 => length - start,
+        _ => {
+            let count = interpreter.to_integer_or_infinity(&args[1])?;
+            count.clamp(0.0, (length - start) as f64) as u64
+        }
+    };
+    Ok((start, skip))
+}
+
+// Array.prototype.splice ( start, deleteCount, ...items )
+fn splice(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let (object, length) = interpreter.this_array_like(&this)?;
+    let (start, delete_count) = splice_range(interpreter, args, length)?;
+    let items = args.get(2..).unwrap_or_default();
+    let item_count = items.len() as u64;
+    if exceeds_max_length(length - delete_count, item_count) {
+        return Err(too_long(interpreter));
+    }
+    let removed = interpreter.array_species_create(&object, delete_count)?;
+    for index in 0..delete_count {
+        let from = start + index;
+        if has_index(&object, from) {
+            let value = interpreter.get_index(&object, from)?;
+            interpreter.create_data_property(&removed, index_key(index), value)?;
+        }
+    }
+    interpreter.set_length(&removed, delete_count)?;
+    if item_count < delete_count {
+        for index in start..length - delete_count {
+            move_element(
+                interpreter,
+                &object,
+                index + delete_count,
+                index + item_count,
+            )?;
+        }
+        for index in (length - delete_count + item_count..length).rev() {
+            interpreter.delete_or_throw(&object, &index_key(index))?;
+        }
+    } else if item_count > delete_count {
+        for index in (start..length - delete_count).rev() {
+            move_element(
+                interpreter,
+                &object,
+                index + delete_count,
+                index + item_count,
+            )?;
+        }
+    }
+    for (index, item) in items.iter().enumerate() {
+        interpreter.set_index(&object, start + index as u64, item.clone())?;
+    }
+    interpreter.set_length(&object, length - delete_count + item_count)?;
+    Ok(Value::Object(removed))
+}
+
+// Array.prototype.toLocaleString ( ), which calls `toLocaleString` on each element
+fn to_locale_string(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    let (object, length) = interpreter.this_array_like(&this)?;
+    let mut result = String::new();
+    for index in 0..length {
+        if index > 0 {
+            result.push(',');
+        }
+        let element = interpreter.get_index(&object, index)?;
+        if !element.is_nullish() {
+            let text = interpreter.invoke(&element, "toLocaleString", &[])?;
+            result.push_str(&interpreter.to_string(&text)?);
+        }
+    }
+    Ok(Value::from(result))
+}
+
+// Array.prototype.toReversed ( )
+fn to_reversed(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    let (object, length) = interpreter.this_array_like(&this)?;
+    let array = interpreter.array_create(length)?;
+    for index in 0..length {
+        let value = interpreter.get_index(&object, length - index - 1)?;
+        interpreter.create_data_property(&array, index_key(index), value)?;
+    }
+    Ok(Value::Object(array))
+}
+
+// Array.prototype.toSorted ( comparefn ), which reads holes as undefined
+fn to_sorted(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let compare = comparator(interpreter, args)?;
+    let (object, length) = interpreter.this_array_like(&this)?;
+    let array = interpreter.array_create(length)?;
+    let items = sort_indexed_properties(interpreter, &object, length, compare.as_ref(), false)?;
+    for (index, item) in items.into_iter().enumerate() {
+        interpreter.create_data_property(&array, index_key(index as u64), item)?;
+    }
+    Ok(Value::Object(array))
+}
+
+// Array.prototype.toSpliced ( start, skipCount, ...items )
+fn to_spliced(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let (object, length) = interpreter.this_array_like(&this)?;
+    let (start, skip) = splice_range(interpreter, args, length)?;
+    let items = args.get(2..).unwrap_or_default();
+    if exceeds_max_length(length - skip, items.len() as u64) {
+        return Err(too_long(interpreter));
+    }
+    let new_length = length - skip + items.len() as u64;
+    let array = interpreter.array_create(new_length)?;
+    let mut index = 0;
+    for from in 0..start {
+        let value = interpreter.get_index(&object, from)?;
+        interpreter.create_data_property(&array, index_key(index), value)?;
+        index += 1;
+    }
+    for item in items {
+        interpreter.create_data_property(&array, index_key(index), item.clone())?;
+        index += 1;
+    }
+    for from in start + skip..length {
+        let value = interpreter.get_index(&object, from)?;
+        interpreter.create_data_property(&array, index_key(index), value)?;
+        index += 1;
+    }
+    Ok(Value::Object(array))
+}
+
+// Array.prototype.toString ( ), which is `join` unless an object has no `join` method
+fn to_string(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    let object = interpreter.to_object(&this)?;
+    let this = Value::Object(object.clone());
+    let join = interpreter.get(&object, &"join".into(), &this)?;
+    if join.as_function().is_none() {
+        return super::object::to_string(interpreter, this, &[]);
+    }
+    interpreter.call(&join, this, &[])
+}
+
+// Array.prototype.unshift ( ...items )
+fn unshift(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let (object, length) = interpreter.this_array_like(&this)?;
+    let count = args.len() as u64;
+    if count > 0 {
+        if exceeds_max_length(length, count) {
+            return Err(too_long(interpreter));
+        }
+        for index in (0..length).rev() {
+            move_element(interpreter, &object, index, index + count)?;
+        }
+        for (index, item) in args.iter().enumerate() {
+            interpreter.set_index(&object, index as u64, item.clone())?;
+        }
+    }
+    interpreter.set_length(&object, length + count)?;
+    Ok(Value::Number((length + count) as f64))
+}
+
+// Array.prototype.values ( ), which is also `Array.prototype[Symbol.iterator]`
+fn values(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    let object = interpreter.to_object(&this)?;
+    Ok(interpreter.array_iterator(Value::Object(object), IterationKind::Values))
+}
+
+// Array.prototype.with ( index, value )
+fn with(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let (object, length) = interpreter.this_array_like(&this)?;
+    let relative = interpreter.to_integer_or_infinity(&argument(args, 0))?;
+    let actual = if relative >= 0.0 {
+        relative
+    } else {
+        length as f64 + relative
+    };
+    if actual < 0.0 || actual >= length as f64 {
+        let message = format!("Invalid index : {}", number_to_string(relative));
+        return Err(interpreter.range_error(&message));
+    }
+    let array = interpreter.array_create(length)?;
+    for index in 0..length {
+        let value = if index == actual as u64 {
+            argument(args, 1)
+        } else {
+            interpreter.get_index(&object, index)?
+        };
+        interpreter.create_data_property(&array, index_key(index), value)?;
+    }
+    Ok(Value::Object(array))
+}
@@ -0,0 +1,502 @@
+use super::{Result, argument};
+use crate::Interpreter;
+use crate::inspect::inspect;
+use crate::object::NativeFunction;
+use jsompiler_runtime::{
+    IterationKind, ListIterator, MapData, Object, ObjectKind, ObjectRef, Property, PropertyKey,
+    Value, WeakMapData,
+};
+
+pub(super) fn install(interpreter: &mut Interpreter) {
+    let species = interpreter.intrinsics.symbols.species.clone();
+    let iterator = interpreter.intrinsics.symbols.iterator.clone();
+
+    let prototype = interpreter.intrinsics.map_prototype.clone();
+    let constructor = interpreter.native_constructor("Map", 0, map, &prototype);
+    interpreter.define_getter(&constructor, species.clone(), super::array::get_species);
+    let methods: [(&str, usize, NativeFunction); 8] = [
+        ("clear", 0, map_clear),
+        ("delete", 1, map_delete),
+        ("entries", 0, map_entries),
+        ("forEach", 1, map_for_each),
+        ("get", 1, map_get),
+        ("has", 1, map_has),
+        ("keys", 0, map_keys),
+        ("set", 2, map_set),
+    ];
+    for (name, length, function) in methods {
+        interpreter.define_method(&prototype, name, length, function);
+    }
+    interpreter.define_getter(&prototype, "size", map_size);
+    interpreter.define_method(&prototype, "values", 0, map_values);
+    // `Map.prototype[Symbol.iterator]` is `Map.prototype.entries`
+    let entries = own_method(&prototype, "entries");
+    prototype
+        .borrow_mut()
+        .define(iterator.clone(), Property::hidden(entries));
+    interpreter.define_to_string_tag(&prototype, "Map");
+    interpreter.define_global("Map", constructor);
+
+    let prototype = interpreter.intrinsics.set_prototype.clone();
+    let constructor = interpreter.native_constructor("Set", 0, set, &prototype);
+    interpreter.define_getter(&constructor, species, super::array::get_species);
+    let methods: [(&str, usize, NativeFunction); 6] = [
+        ("add", 1, set_add),
+        ("clear", 0, set_clear),
+        ("delete", 1, set_delete),
+        ("entries", 0, set_entries),
+        ("forEach", 1, set_for_each),
+        ("has", 1, set_has),
+    ];
+    for (name, length, function) in methods {
+        interpreter.define_method(&prototype, name, length, function);
+    }
+    interpreter.define_getter(&prototype, "size", set_size);
+    // `keys` and `Symbol.iterator` are the same function as `values`
+    let values = Value::Object(interpreter.define_method(&prototype, "values", 0, set_values));
+    let mut object = prototype.borrow_mut();
+    object.define("keys", Property::hidden(values.clone()));
+    object.define(iterator, Property::hidden(values));
+    drop(object);
+    interpreter.define_to_string_tag(&prototype, "Set");
+    interpreter.define_global("Set", constructor);
+
+    let prototype = interpreter.intrinsics.weak_map_prototype.clone();
+    let constructor = interpreter.native_constructor("WeakMap", 0, weak_map, &prototype);
+    let methods: [(&str, usize, NativeFunction); 4] = [
+        ("delete", 1, weak_map_delete),
+        ("get", 1, weak_map_get),
+        ("has", 1, weak_map_has),
+        ("set", 2, weak_map_set),
+    ];
+    for (name, length, function) in methods {
+        interpreter.define_method(&prototype, name, length, function);
+    }
+    interpreter.define_to_string_tag(&prototype, "WeakMap");
+    interpreter.define_global("WeakMap", constructor);
+}
+
+fn own_method(object: &ObjectRef, name: &str) -> Value {
+    object
+        .borrow()
+        .get_own(&name.into())
+        .map(|property| property.data_value())
+        .expect("the method is defined before")
+}
+
+impl Interpreter {
+    // OrdinaryCreateFromConstructor for the collections, which must be called with `new`
+    fn create_collection(
+        &mut self,
+        name: &str,
+        new_target: Option<&ObjectRef>,
+        default: ObjectRef,
+        kind: ObjectKind,
+    ) -> Result<ObjectRef> {
+        let Some(new_target) = new_target else {
+            let message = format!("Constructor {name} requires 'new'");
+            return Err(self.type_error(&message));
+        };
+        let prototype = self.prototype_from_constructor(new_target, default)?;
+        Ok(ObjectRef::new(Object::new(Some(prototype), kind)))
+    }
+
+    // The `set` or `add` method the constructors add the values of the iterable with
+    fn adder(&mut self, collection: &ObjectRef, name: &str) -> Result<Value> {
+        let adder = self.get(collection, &name.into(), &Value::Object(collection.clone()))?;
+        if adder.as_function().is_none() {
+            let message = format!(
+                "'{}' returned for property '{name}' of object '{}' is not a function",
+                inspect(&adder),
+                inspect(&Value::Object(collection.clone())),
+            );
+            return Err(self.type_error(&message));
+        }
+        Ok(adder)
+    }
+
+    // AddEntriesFromIterable: call `adder` with the key and value of each entry
+    fn add_entries_from_iterable(
+        &mut self,
+        target: &ObjectRef,
+        iterable: &Value,
+        adder: &Value,
+    ) -> Result<()> {
+        let mut record = self.get_iterator(iterable)?;
+        while let Some(entry) = self.iterator_step(&mut record)? {
+            let added = self.add_entry_with(target, &entry, adder);
+            self.iterator_close_on_error(&record, added)?;
+        }
+        Ok(())
+    }
+
+    fn add_entry_with(&mut self, target: &ObjectRef, entry: &Value, adder: &Value) -> Result<()> {
+        if !matches!(entry, Value::Object(_)) {
+            let message = format!("Iterator value {} is not an entry object", inspect(entry));
+            return Err(self.type_error(&message));
+        }
+        let key = self.get_property(entry, &PropertyKey::Index(0))?;
+        let value = self.get_property(entry, &PropertyKey::Index(1))?;
+        self.call(adder, Value::Object(target.clone()), &[key, value])?;
+        Ok(())
+    }
+
+    // Call `adder` with each value of an iterable, as the Set constructor does
+    fn add_values_from_iterable(
+        &mut self,
+        target: &ObjectRef,
+        iterable: &Value,
+        adder: &Value,
+    ) -> Result<()> {
+        let mut record = self.get_iterator(iterable)?;
+        while let Some(value) = self.iterator_step(&mut record)? {
+            let added = self.call(adder, Value::Object(target.clone()), &[value]);
+            self.iterator_close_on_error(&record, added)?;
+        }
+        Ok(())
+    }
+}
+
+// The object `this` is, if it is a collection of the kind `matches` accepts
+fn this_collection(
+    interpreter: &mut Interpreter,
+    this: &Value,
+    method: &str,
+    matches: fn(&ObjectKind) -> bool,
+) -> Result<ObjectRef> {
+    if let Value::Object(object) = this
+        && matches(&object.borrow().kind)
+    {
+        return Ok(object.clone());
+    }
+    let message = format!(
+        "Method {method} called on incompatible receiver {}",
+        inspect(this)
+    );
+    Err(interpreter.type_error(&message))
+}
+
+fn this_map(interpreter: &mut Interpreter, this: &Value, method: &str) -> Result<ObjectRef> {
+    let is_map = |kind: &ObjectKind| matches!(kind, ObjectKind::Map(_));
+    this_collection(
+        interpreter,
+        this,
+        &format!("Map.prototype.{method}"),
+        is_map,
+    )
+}
+
+fn this_set(interpreter: &mut Interpreter, this: &Value, method: &str) -> Result<ObjectRef> {
+    let is_set = |kind: &ObjectKind| matches!(kind, ObjectKind::Set(_));
+    this_collection(
+        interpreter,
+        this,
+        &format!("Set.prototype.{method}"),
+        is_set,
+    )
+}
+
+fn this_weak_map(interpreter: &mut Interpreter, this: &Value, method: &str) -> Result<ObjectRef> {
+    let is_weak_map = |kind: &ObjectKind| matches!(kind, ObjectKind::WeakMap(_));
+    this_collection(
+        interpreter,
+        this,
+        &format!("WeakMap.prototype.{method}"),
+        is_weak_map,
+    )
+}
+
+// Work on the entries of a Map or Set
+fn with_data<T>(object: &ObjectRef, f: impl FnOnce(&mut MapData) -> T) -> T {
+    match &mut object.borrow_mut().kind {
+        ObjectKind::Map(data) | ObjectKind::Set(data) => f(data),
+        _ => unreachable!("the object is a Map or Set"),
+    }
+}
+
+fn with_weak_data<T>(object: &ObjectRef, f: impl FnOnce(&mut WeakMapData) -> T) -> T {
+    match &mut object.borrow_mut().kind {
+        ObjectKind::WeakMap(data) => f(data),
+        _ => unreachable!("the object is a WeakMap"),
+    }
+}
+
+// CreateMapIterator and CreateSetIterator. The iterator shares a cursor with the entries, so
+// it keeps its place when they are compacted.
+fn collection_iterator(
+    interpreter: &mut Interpreter,
+    object: ObjectRef,
+    kind: IterationKind,
+    iterator_kind: fn(ListIterator) -> ObjectKind,
+) -> Value {
+    let position = with_data(&object, |data| data.cursor());
+    let iterator = ListIterator {
+        list: Some(Value::Object(object)),
+        position,
+        kind,
+    };
+    interpreter.list_iterator(iterator_kind, iterator)
+}
+
+// What `forEach` of Map and Set share. Entries added while it runs are visited too.
+fn for_each_entry(
+    interpreter: &mut Interpreter,
+    object: &ObjectRef,
+    args: &[Value],
+    is_set: bool,
+) -> Result<Value> {
+    let callback = argument(args, 0);
+    if callback.as_function().is_none() {
+        let message = format!("{} is not a function", inspect(&callback));
+        return Err(interpreter.type_error(&message));
+    }
+    let this_arg = argument(args, 1);
+    let cursor = with_data(object, |data| data.cursor());
+    while let Some((position, key, value)) = with_data(object, |data| data.entry(cursor.get())) {
+        cursor.set(position + 1);
+        // Set entries are their value twice
+        let key = if is_set { value.clone() } else { key };
+        let args = [value, key, Value::Object(object.clone())];
+        interpreter.call(&callback, this_arg.clone(), &args)?;
+    }
+    Ok(Value::Undefined)
+}
+
+// Map ( [ iterable ] )
+fn map(
+    interpreter: &mut Interpreter,
+    args: &[Value],
+    new_target: Option<&ObjectRef>,
+) -> Result<Value> {
+    let default = interpreter.intrinsics.map_prototype.clone();
+    let kind = ObjectKind::Map(MapData::default());
+    let map = interpreter.create_collection("Map", new_target, default, kind)?;
+    let iterable = argument(args, 0);
+    if !iterable.is_nullish() {
+        let adder = interpreter.adder(&map, "set")?;
+        interpreter.add_entries_from_iterable(&map, &iterable, &adder)?;
+    }
+    Ok(Value::Object(map))
+}
+
+// Map.prototype.clear ( )
+fn map_clear(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    let map = this_map(interpreter, &this, "clear")?;
+    with_data(&map, MapData::clear);
+    Ok(Value::Undefined)
+}
+
+// Map.prototype.delete ( key )
+fn map_delete(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let map = this_map(interpreter, &this, "delete")?;
+    let deleted = with_data(&map, |data| data.delete(&argument(args, 0)));
+    Ok(Value::Boolean(deleted))
+}
+
+// Map.prototype.entries ( )
+fn map_entries(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    let map = this_map(interpreter, &this, "entries")?;
+    Ok(collection_iterator(
+        interpreter,
+        map,
+        IterationKind::Entries,
+        ObjectKind::MapIterator,
+    ))
+}
+
+// Map.prototype.forEach ( callbackfn [ , thisArg ] )
+fn map_for_each(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let map = this_map(interpreter, &this, "forEach")?;
+    for_each_entry(interpreter, &map, args, false)
+}
+
+// Map.prototype.get ( key )
+fn map_get(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let map = this_map(interpreter, &this, "get")?;
+    let value = with_data(&map, |data| data.get(&argument(args, 0)));
+    Ok(value.unwrap_or(Value::Undefined))
+}
+
+// Map.prototype.has ( key )
+fn map_has(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let map = this_map(interpreter, &this, "has")?;
+    let has = with_data(&map, |data| data.has(&argument(args, 0)));
+    Ok(Value::Boolean(has))
+}
+
+// Map.prototype.keys ( )
+fn map_keys(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    let map = this_map(interpreter, &this, "keys")?;
+    Ok(collection_iterator(
+        interpreter,
+        map,
+        IterationKind::Keys,
+        ObjectKind::MapIterator,
+    ))
+}
+
+// Map.prototype.set ( key, value )
+fn map_set(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let map = this_map(interpreter, &this, "set")?;
+    with_data(&map, |data| data.set(argument(args, 0), argument(args, 1)));
+    Ok(this)
+}
+
+// get Map.prototype.size
+fn map_size(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    let map = this_map(interpreter, &this, "size")?;
+    Ok(Value::Number(with_data(&map, |data| data.len()) as f64))
+}
+
+// Map.prototype.values ( )
+fn map_values(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    let map = this_map(interpreter, &this, "values")?;
+    Ok(collection_iterator(
+        interpreter,
+        map,
+        IterationKind::Values,
+        ObjectKind::MapIterator,
+    ))
+}
+
+// Set ( [ iterable ] )
+fn set(
+    interpreter: &mut Interpreter,
+    args: &[Value],
+    new_target: Option<&ObjectRef>,
+) -> Result<Value> {
+    let default = interpreter.intrinsics.set_prototype.clone();
+    let kind = ObjectKind::Set(MapData::default());
+    let set = interpreter.create_collection("Set", new_target, default, kind)?;
+    let iterable = argument(args, 0);
+    if !iterable.is_nullish() {
+        let adder = interpreter.adder(&set, "add")?;
+        interpreter.add_values_from_iterable(&set, &iterable, &adder)?;
+    }
+    Ok(Value::Object(set))
+}
+
+// Set.prototype.add ( value )
+fn set_add(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let set = this_set(interpreter, &this, "add")?;
+    let value = argument(args, 0);
+    with_data(&set, |data| {
+        if !data.has(&value) {
+            data.set(value, Value::Undefined);
+        }
+    });
+    Ok(this)
+}
+
+// Set.prototype.clear ( )
+fn set_clear(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    let set = this_set(interpreter, &this, "clear")?;
+    with_data(&set, MapData::clear);
+    Ok(Value::Undefined)
+}
+
+// Set.prototype.delete ( value )
+fn set_delete(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let set = this_set(interpreter, &this, "delete")?;
+    let deleted = with_data(&set, |data| data.delete(&argument(args, 0)));
+    Ok(Value::Boolean(deleted))
+}
+
+// Set.prototype.entries ( )
+fn set_entries(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    let set = this_set(interpreter, &this, "entries")?;
+    Ok(collection_iterator(
+        interpreter,
+        set,
+        IterationKind::Entries,
+        ObjectKind::SetIterator,
+    ))
+}
+
+// Set.prototype.forEach ( callbackfn [ , thisArg ] )
+fn set_for_each(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let set = this_set(interpreter, &this, "forEach")?;
+    for_each_entry(interpreter, &set, args, true)
+}
+
+// Set.prototype.has ( value )
+fn set_has(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let set = this_set(interpreter, &this, "has")?;
+    let has = with_data(&set, |data| data.has(&argument(args, 0)));
+    Ok(Value::Boolean(has))
+}
+
+// get Set.prototype.size
+fn set_size(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    let set = this_set(interpreter, &this, "size")?;
+    Ok(Value::Number(with_data(&set, |data| data.len()) as f64))
+}
+
+// Set.prototype.values ( ), which is also `keys` and `Symbol.iterator`
+fn set_values(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    let set = this_set(interpreter, &this, "values")?;
+    Ok(collection_iterator(
+        interpreter,
+        set,
+        IterationKind::Values,
+        ObjectKind::SetIterator,
+    ))
+}
+
+// WeakMap ( [ iterable ] )
+fn weak_map(
+    interpreter: &mut Interpreter,
+    args: &[Value],
+    new_target: Option<&ObjectRef>,
+) -> Result<Value> {
+    let default = interpreter.intrinsics.weak_map_prototype.clone();
+    let kind = ObjectKind::WeakMap(WeakMapData::default());
+    let map = interpreter.create_collection("WeakMap", new_target, default, kind)?;
+    let iterable = argument(args, 0);
+    if !iterable.is_nullish() {
+        let adder = interpreter.adder(&map, "set")?;
+        interpreter.add_entries_from_iterable(&map, &iterable, &adder)?;
+    }
+    Ok(Value::Object(map))
+}
+
+// WeakMap.prototype.delete ( key )
+fn weak_map_delete(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let map = this_weak_map(interpreter, &this, "delete")?;
+    let deleted = match argument(args, 0) {
+        Value::Object(key) => with_weak_data(&map, |data| data.delete(&key)),
+        _ => false,
+    };
+    Ok(Value::Boolean(deleted))
+}
+
+// WeakMap.prototype.get ( key )
+fn weak_map_get(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let map = this_weak_map(interpreter, &this, "get")?;
+    let value = match argument(args, 0) {
+        Value::Object(key) => with_weak_data(&map, |data| data.get(&key)),
+        _ => None,
+    };
+    Ok(value.unwrap_or(Value::Undefined))
+}
+
+// WeakMap.prototype.has ( key )
+fn weak_map_has(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let map = this_weak_map(interpreter, &this, "has")?;
+    let has = match argument(args, 0) {
+        Value::Object(key) => with_weak_data(&map, |data| data.has(&key)),
+        _ => false,
+    };
+    Ok(Value::Boolean(has))
+}
+
+// WeakMap.prototype.set ( key, value ). Only objects can be keys, since the map mustn't keep
+// its keys alive.
+fn weak_map_set(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let map = this_weak_map(interpreter, &this, "set")?;
+    let Value::Object(key) = argument(args, 0) else {
+        return Err(interpreter.type_error("Invalid value used as weak map key"));
+    };
+    with_weak_data(&map, |data| data.set(&key, argument(args, 1)));
+    Ok(this)
+}
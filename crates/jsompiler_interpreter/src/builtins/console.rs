@@ -0,0 +1,35 @@
+use super::Result;
+use crate::Interpreter;
+use crate::inspect::inspect;
+use crate::object::NativeFunction;
+use jsompiler_runtime::Value;
+
+pub(super) fn install(interpreter: &mut Interpreter) {
+    let console = interpreter.new_object();
+    // Everything is printed to the same place, so the levels only differ in name
+    let functions: [(&str, usize, NativeFunction); 5] = [
+        ("debug", 0, log),
+        ("error", 0, log),
+        ("info", 0, log),
+        ("log", 0, log),
+        ("warn", 0, log),
+    ];
+    for (name, length, function) in functions {
+        interpreter.define_method(&console, name, length, function);
+    }
+    interpreter.define_global("console", console);
+}
+
+// console.log ( ...data ): strings as they are and other values inspected, separated by spaces
+fn log(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let line = args
+        .iter()
+        .map(|value| match value {
+            Value::String(text) => text.to_string(),
+            value => inspect(value),
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    interpreter.print(&line);
+    Ok(Value::Undefined)
+}
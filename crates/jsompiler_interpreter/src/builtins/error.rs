@@ -0,0 +1,177 @@
+use super::{Result, argument};
+use crate::object::NativeConstructor;
+use crate::{ErrorType, Interpreter};
+use jsompiler_runtime::{Object, ObjectKind, ObjectRef, Property, Value};
+
+pub(super) fn install(interpreter: &mut Interpreter) {
+    let constructors: [(NativeConstructor, usize); 8] = [
+        (error, 1),
+        (eval_error, 1),
+        (range_error, 1),
+        (reference_error, 1),
+        (syntax_error, 1),
+        (type_error, 1),
+        (uri_error, 1),
+        (aggregate_error, 2),
+    ];
+    let mut error_constructor: Option<ObjectRef> = None;
+    for (kind, (constructor, length)) in ErrorType::ALL.into_iter().zip(constructors) {
+        let prototype = interpreter.error_prototype(kind);
+        let constructor =
+            interpreter.native_constructor(kind.name(), length, constructor, &prototype);
+        match &error_constructor {
+            // `TypeError.__proto__` is `Error`
+            Some(error) => constructor.borrow_mut().prototype = Some(error.clone()),
+            None => error_constructor = Some(constructor.clone()),
+        }
+        let mut prototype = prototype.borrow_mut();
+        prototype.define("message", Property::hidden(Value::string("")));
+        prototype.define("name", Property::hidden(Value::string(kind.name())));
+        drop(prototype);
+        interpreter.define_global(kind.name(), constructor);
+    }
+    let error_prototype = interpreter.error_prototype(ErrorType::Error);
+    interpreter.define_method(&error_prototype, "toString", 0, to_string);
+}
+
+impl Interpreter {
+    // The steps the error constructors share: an error object whose prototype comes from
+    // `new.target`, with the message and the `cause` of the options if there are any
+    fn construct_error(
+        &mut self,
+        kind: ErrorType,
+        new_target: Option<&ObjectRef>,
+        message: &Value,
+        options: &Value,
+    ) -> Result<ObjectRef> {
+        let default = self.error_prototype(kind);
+        let prototype = match new_target {
+            Some(new_target) => self.prototype_from_constructor(new_target, default)?,
+            None => default,
+        };
+        let error = ObjectRef::new(Object::new(Some(prototype), ObjectKind::Error));
+        if !matches!(message, Value::Undefined) {
+            let message = self.to_string(message)?;
+            let property = Property::hidden(Value::String(message));
+            error.borrow_mut().define("message", property);
+        }
+        // InstallErrorCause
+        if let Value::Object(options) = options
+            && options.has_property(&"cause".into())
+        {
+            let cause = self.get(options, &"cause".into(), &options.clone().into())?;
+            error.borrow_mut().define("cause", Property::hidden(cause));
+        }
+        Ok(error)
+    }
+
+    fn native_error(
+        &mut self,
+        kind: ErrorType,
+        args: &[Value],
+        new_target: Option<&ObjectRef>,
+    ) -> Result<Value> {
+        let error =
+            self.construct_error(kind, new_target, &argument(args, 0), &argument(args, 1))?;
+        Ok(Value::Object(error))
+    }
+}
+
+// Error ( message [ , options ] )
+fn error(
+    interpreter: &mut Interpreter,
+    args: &[Value],
+    new_target: Option<&ObjectRef>,
+) -> Result<Value> {
+    interpreter.native_error(ErrorType::Error, args, new_target)
+}
+
+fn eval_error(
+    interpreter: &mut Interpreter,
+    args: &[Value],
+    new_target: Option<&ObjectRef>,
+) -> Result<Value> {
+    interpreter.native_error(ErrorType::EvalError, args, new_target)
+}
+
+fn range_error(
+    interpreter: &mut Interpreter,
+    args: &[Value],
+    new_target: Option<&ObjectRef>,
+) -> Result<Value> {
+    interpreter.native_error(ErrorType::RangeError, args, new_target)
+}
+
+fn reference_error(
+    interpreter: &mut Interpreter,
+    args: &[Value],
+    new_target: Option<&ObjectRef>,
+) -> Result<Value> {
+    interpreter.native_error(ErrorType::ReferenceError, args, new_target)
+}
+
+fn syntax_error(
+    interpreter: &mut Interpreter,
+    args: &[Value],
+    new_target: Option<&ObjectRef>,
+) -> Result<Value> {
+    interpreter.native_error(ErrorType::SyntaxError, args, new_target)
+}
+
+fn type_error(
+    interpreter: &mut Interpreter,
+    args: &[Value],
+    new_target: Option<&ObjectRef>,
+) -> Result<Value> {
+    interpreter.native_error(ErrorType::TypeError, args, new_target)
+}
+
+fn uri_error(
+    interpreter: &mut Interpreter,
+    args: &[Value],
+    new_target: Option<&ObjectRef>,
+) -> Result<Value> {
+    interpreter.native_error(ErrorType::URIError, args, new_target)
+}
+
+// AggregateError ( errors, message [ , options ] )
+fn aggregate_error(
+    interpreter: &mut Interpreter,
+    args: &[Value],
+    new_target: Option<&ObjectRef>,
+) -> Result<Value> {
+    let error = interpreter.construct_error(
+        ErrorType::AggregateError,
+        new_target,
+        &argument(args, 1),
+        &argument(args, 2),
+    )?;
+    let errors = interpreter.iterate(&argument(args, 0))?;
+    let errors = Value::Object(interpreter.new_array(errors));
+    error
+        .borrow_mut()
+        .define("errors", Property::hidden(errors));
+    Ok(Value::Object(error))
+}
+
+// Error.prototype.toString ( )
+fn to_string(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    let Some(object) = this.as_object() else {
+        return Err(
+            interpreter.type_error("Error.prototype.toString requires that 'this' be an Object")
+        );
+    };
+    let name = match interpreter.get(object, &"name".into(), &this)? {
+        Value::Undefined => "Error".into(),
+        name => interpreter.to_string(&name)?,
+    };
+    let message = match interpreter.get(object, &"message".into(), &this)? {
+        Value::Undefined => "".into(),
+        message => interpreter.to_string(&message)?,
+    };
+    Ok(Value::from(match (name.is_empty(), message.is_empty()) {
+        (_, true) => name.to_string(),
+        (true, false) => message.to_string(),
+        (false, false) => format!("{name}: {message}"),
+    }))
+}
@@ -0,0 +1,121 @@
+use super::{Result, argument};
+use crate::Interpreter;
+use crate::object::NativeFunction;
+use jsompiler_runtime::{Object, ObjectRef, Property, PropertyKey, Value};
+
+pub(super) fn install(interpreter: &mut Interpreter) {
+    let prototype = interpreter.intrinsics.function_prototype.clone();
+    let constructor = interpreter.native_constructor("Function", 1, function, &prototype);
+    let methods: [(&str, usize, NativeFunction); 4] = [
+        ("apply", 2, apply),
+        ("bind", 1, bind),
+        ("call", 1, call),
+        ("toString", 0, to_string),
+    ];
+    for (name, length, function) in methods {
+        interpreter.define_method(&prototype, name, length, function);
+    }
+    let has_instance = interpreter.intrinsics.symbols.has_instance.clone();
+    let method = interpreter.native_function("[Symbol.hasInstance]", 1, has_instance_method);
+    let property = Property::new(Value::Object(method), false, false, false);
+    prototype.borrow_mut().define(has_instance, property);
+    interpreter.define_global("Function", constructor);
+}
+
+// Function ( ...parameterArgs, bodyArg ), which would have to parse its arguments
+fn function(interpreter: &mut Interpreter, _: &[Value], _: Option<&ObjectRef>) -> Result<Value> {
+    Err(interpreter.unsupported("Functions created from strings"))
+}
+
+// Function.prototype.apply ( thisArg, argArray )
+fn apply(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    if this.as_function().is_none() {
+        return Err(interpreter.type_error("Function.prototype.apply was called on a non-function"));
+    }
+    let arguments = match argument(args, 1) {
+        arguments if arguments.is_nullish() => Vec::new(),
+        arguments => interpreter.list_from_array_like(&arguments)?,
+    };
+    interpreter.call(&this, argument(args, 0), &arguments)
+}
+
+// Function.prototype.bind ( thisArg, ...args ): the bound function's `length` is what is
+// left of the target's after the bound arguments, and its name is "bound " and the target's
+fn bind(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let Some(target) = this.as_function() else {
+        return Err(interpreter.type_error("Bind must be called on a function"));
+    };
+    let bound_arguments = args.iter().skip(1).cloned().collect::<Vec<_>>();
+    let bound = ObjectRef::new(Object::bound_function(
+        target,
+        argument(args, 0),
+        bound_arguments.clone(),
+    ));
+    let mut length = 0.0;
+    let length_key = PropertyKey::from("length");
+    if target.borrow().has_own(&length_key)
+        && let Value::Number(target_length) = interpreter.get(target, &length_key, &this)?
+    {
+        length = if target_length == f64::INFINITY {
+            f64::INFINITY
+        } else if target_length == f64::NEG_INFINITY {
+            0.0
+        } else {
+            let target_length =
+                interpreter.to_integer_or_infinity(&Value::Number(target_length))?;
+            (target_length - bound_arguments.len() as f64).max(0.0)
+        };
+    }
+    let name = match interpreter.get(target, &"name".into(), &this)? {
+        Value::String(name) => name,
+        _ => "".into(),
+    };
+    let mut object = bound.borrow_mut();
+    object.define("length", Property::read_only(Value::Number(length)));
+    object.define(
+        "name",
+        Property::read_only(Value::from(format!("bound {name}"))),
+    );
+    drop(object);
+    Ok(Value::Object(bound))
+}
+
+// Function.prototype.call ( thisArg, ...args )
+fn call(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    if this.as_function().is_none() {
+        return Err(interpreter.type_error("Function.prototype.call was called on a non-function"));
+    }
+    let arguments = args.get(1..).unwrap_or_default();
+    interpreter.call(&this, argument(args, 0), arguments)
+}
+
+// Function.prototype.toString ( ). The source text of functions isn't kept, so all of them
+// look like built-ins.
+fn to_string(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    let Some(function) = this.as_function() else {
+        return Err(interpreter
+            .type_error("Function.prototype.toString requires that 'this' be a Function"));
+    };
+    let name = match function
+        .borrow()
+        .get_own(&"name".into())
+        .map(|property| property.data_value())
+    {
+        Some(Value::String(name)) => name,
+        _ => "".into(),
+    };
+    Ok(Value::from(format!(
+        "function {name}() {{ [native code] }}"
+    )))
+}
+
+// Function.prototype [ @@hasInstance ] ( V ), which `instanceof` calls
+fn has_instance_method(
+    interpreter: &mut Interpreter,
+    this: Value,
+    args: &[Value],
+) -> Result<Value> {
+    interpreter
+        .ordinary_has_instance(&this, &argument(args, 0))
+        .map(Value::Boolean)
+}
@@ -0,0 +1,368 @@
+use super::{Result, index_key};
+use crate::Interpreter;
+use crate::inspect::inspect;
+use jsompiler_runtime::{
+    IterationKind, ListIterator, Object, ObjectKind, ObjectRef, Property, PropertyKey, Value,
+};
+
+// An iterator and the `next` method it had when iteration started
+pub(crate) struct IteratorRecord {
+    pub iterator: Value,
+    pub next: Value,
+    pub done: bool,
+}
+
+impl IteratorRecord {
+    pub fn new(iterator: Value, next: Value) -> Self {
+        IteratorRecord {
+            iterator,
+            next,
+            done: false,
+        }
+    }
+}
+
+pub(super) fn install(interpreter: &mut Interpreter) {
+    let iterator_prototype = interpreter.intrinsics.iterator_prototype.clone();
+    let iterator = interpreter.intrinsics.symbols.iterator.clone();
+    interpreter.define_method(
+        &iterator_prototype,
+        iterator,
+        0,
+        iterator_prototype_iterator,
+    );
+
+    let prototypes: [(ObjectRef, &str, NextFunction); 4] = [
+        (
+            interpreter.intrinsics.array_iterator_prototype.clone(),
+            "Array Iterator",
+            array_iterator_next,
+        ),
+        (
+            interpreter.intrinsics.string_iterator_prototype.clone(),
+            "String Iterator",
+            string_iterator_next,
+        ),
+        (
+            interpreter.intrinsics.map_iterator_prototype.clone(),
+            "Map Iterator",
+            map_iterator_next,
+        ),
+        (
+            interpreter.intrinsics.set_iterator_prototype.clone(),
+            "Set Iterator",
+            set_iterator_next,
+        ),
+    ];
+    for (prototype, tag, next) in prototypes {
+        interpreter.define_method(&prototype, "next", 0, next);
+        interpreter.define_to_string_tag(&prototype, tag);
+    }
+}
+
+type NextFunction = fn(&mut Interpreter, Value, &[Value]) -> Result<Value>;
+
+impl Interpreter {
+    // Call the `Symbol.iterator` method of a value, which must return an object
+    pub(crate) fn iterator_object(&mut self, value: &Value) -> Result<Value> {
+        let key = PropertyKey::Symbol(self.intrinsics.symbols.iterator.clone());
+        let Some(method) = self.get_method(value, &key)? else {
+            let message = match value {
+                Value::Object(_) => "object is not iterable".to_string(),
+                value => format!("{} is not iterable", inspect(value)),
+            };
+            return Err(self.type_error(&message));
+        };
+        self.iterator_from_method(value, &method)
+    }
+
+    fn iterator_from_method(&mut self, value: &Value, method: &Value) -> Result<Value> {
+        let iterator = self.call(method, value.clone(), &[])?;
+        if !matches!(iterator, Value::Object(_)) {
+            return Err(self.type_error("Result of the Symbol.iterator method is not an object"));
+        }
+        Ok(iterator)
+    }
+
+    // GetIterator
+    pub(crate) fn get_iterator(&mut self, value: &Value) -> Result<IteratorRecord> {
+        let iterator = self.iterator_object(value)?;
+        let next = self.get_property(&iterator, &"next".into())?;
+        Ok(IteratorRecord::new(iterator, next))
+    }
+
+    // GetIteratorFromMethod, for callers that already looked the method up
+    pub(crate) fn get_iterator_from_method(
+        &mut self,
+        value: &Value,
+        method: &Value,
+    ) -> Result<IteratorRecord> {
+        let iterator = self.iterator_from_method(value, method)?;
+        let next = self.get_property(&iterator, &"next".into())?;
+        Ok(IteratorRecord::new(iterator, next))
+    }
+
+    // IteratorStepValue: the next value, or `None` once the iterator is done
+    pub(crate) fn iterator_step(&mut self, record: &mut IteratorRecord) -> Result<Option<Value>> {
+        if record.done {
+            return Ok(None);
+        }
+        let result = self.call(&record.next, record.iterator.clone(), &[]);
+        let result = match result {
+            Ok(result @ Value::Object(_)) => result,
+            Ok(result) => {
+                record.done = true;
+                let message = format!("Iterator result {} is not an object", inspect(&result));
+                return Err(self.type_error(&message));
+            }
+            Err(exception) => {
+                record.done = true;
+                return Err(exception);
+            }
+        };
+        let done = self.get_property(&result, &"done".into());
+        if done.as_ref().is_ok_and(|done| !done.to_boolean()) {
+            let value = self.get_property(&result, &"value".into());
+            record.done = value.is_err();
+            return value.map(Some);
+        }
+        record.done = true;
+        done.map(|_| None)
+    }
+
+    // IteratorClose: let the iterator clean up when iteration stops before it is done. An
+    // exception that stopped it is thrown instead of any from `return`.
+    pub(crate) fn iterator_close<T>(
+        &mut self,
+        record: &IteratorRecord,
+        completion: Result<T>,
+    ) -> Result<T> {
+        let iterator = record.iterator.clone();
+        let inner = match self.get_method(&iterator, &"return".into()) {
+            Ok(Some(method)) => self.call(&method, iterator, &[]),
+            Ok(None) => return completion,
+            Err(exception) => Err(exception),
+        };
+        let value = completion?;
+        let inner = inner?;
+        if !matches!(inner, Value::Object(_)) {
+            let message = format!("Iterator result {} is not an object", inspect(&inner));
+            return Err(self.type_error(&message));
+        }
+        Ok(value)
+    }
+
+    // IfAbruptCloseIterator: close the iterator if an exception stopped the iteration
+    pub(crate) fn iterator_close_on_error<T>(
+        &mut self,
+        record: &IteratorRecord,
+        result: Result<T>,
+    ) -> Result<T> {
+        match result {
+            Ok(value) => Ok(value),
+            Err(exception) => self.iterator_close(record, Err(exception)),
+        }
+    }
+
+    // CreateIterResultObject
+    pub(crate) fn iterator_result(&self, value: Value, done: bool) -> Value {
+        let object = self.new_object();
+        let mut result = object.borrow_mut();
+        result.define("value", Property::data(value));
+        result.define("done", Property::data(Value::Boolean(done)));
+        drop(result);
+        Value::Object(object)
+    }
+
+    // An array, string, Map or Set iterator, as `kind` says, over `iterator.list`
+    pub(crate) fn list_iterator(
+        &self,
+        kind: fn(ListIterator) -> ObjectKind,
+        iterator: ListIterator,
+    ) -> Value {
+        let kind = kind(iterator);
+        let prototype = match &kind {
+            ObjectKind::ArrayIterator(_) => &self.intrinsics.array_iterator_prototype,
+            ObjectKind::StringIterator(_) => &self.intrinsics.string_iterator_prototype,
+            ObjectKind::MapIterator(_) => &self.intrinsics.map_iterator_prototype,
+            _ => &self.intrinsics.set_iterator_prototype,
+        };
+        Value::Object(ObjectRef::new(Object::new(Some(prototype.clone()), kind)))
+    }
+
+    // CreateArrayIterator
+    pub(crate) fn array_iterator(&self, list: Value, kind: IterationKind) -> Value {
+        self.list_iterator(ObjectKind::ArrayIterator, ListIterator::new(list, kind))
+    }
+}
+
+// %IteratorPrototype% [ @@iterator ] ( ), which makes iterators iterable
+fn iterator_prototype_iterator(_: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    Ok(this)
+}
+
+// The state of the iterator `this` is, if it is an iterator of the kind `state` picks
+fn iterator_state(
+    interpreter: &mut Interpreter,
+    this: &Value,
+    state: fn(&ObjectKind) -> Option<&ListIterator>,
+    name: &str,
+) -> Result<Option<(Value, usize, IterationKind)>> {
+    let Value::Object(object) = this else {
+        return Err(incompatible(interpreter, name, this));
+    };
+    let object = object.borrow();
+    let Some(iterator) = state(&object.kind) else {
+        drop(object);
+        return Err(incompatible(interpreter, name, this));
+    };
+    Ok(iterator
+        .list
+        .clone()
+        .map(|list| (list, iterator.position.get(), iterator.kind)))
+}
+
+fn array_iterator_state(kind: &ObjectKind) -> Option<&ListIterator> {
+    match kind {
+        ObjectKind::ArrayIterator(iterator) => Some(iterator),
+        _ => None,
+    }
+}
+
+fn string_iterator_state(kind: &ObjectKind) -> Option<&ListIterator> {
+    match kind {
+        ObjectKind::StringIterator(iterator) => Some(iterator),
+        _ => None,
+    }
+}
+
+fn map_iterator_state(kind: &ObjectKind) -> Option<&ListIterator> {
+    match kind {
+        ObjectKind::MapIterator(iterator) => Some(iterator),
+        _ => None,
+    }
+}
+
+fn set_iterator_state(kind: &ObjectKind) -> Option<&ListIterator> {
+    match kind {
+        ObjectKind::SetIterator(iterator) => Some(iterator),
+        _ => None,
+    }
+}
+
+fn incompatible(interpreter: &mut Interpreter, name: &str, this: &Value) -> Value {
+    let message = format!(
+        "Method {name} called on incompatible receiver {}",
+        inspect(this)
+    );
+    interpreter.type_error(&message)
+}
+
+// Move an iterator to `position`, or mark it done if that is `None`
+fn advance(this: &Value, position: Option<usize>) {
+    let Some(object) = this.as_object() else {
+        return;
+    };
+    let mut object = object.borrow_mut();
+    if let ObjectKind::ArrayIterator(iterator)
+    | ObjectKind::StringIterator(iterator)
+    | ObjectKind::MapIterator(iterator)
+    | ObjectKind::SetIterator(iterator) = &mut object.kind
+    {
+        match position {
+            Some(position) => iterator.position.set(position),
+            None => iterator.list = None,
+        }
+    }
+}
+
+// %ArrayIteratorPrototype%.next ( )
+fn array_iterator_next(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    let name = "Array Iterator.prototype.next";
+    let Some((list, index, kind)) = iterator_state(interpreter, &this, array_iterator_state, name)?
+    else {
+        return Ok(interpreter.iterator_result(Value::Undefined, true));
+    };
+    let object = interpreter.to_object(&list)?;
+    let length = interpreter.length_of_array_like(&object)?;
+    let index = index as u64;
+    if index >= length {
+        advance(&this, None);
+        return Ok(interpreter.iterator_result(Value::Undefined, true));
+    }
+    advance(&this, Some(index as usize + 1));
+    let value = match kind {
+        IterationKind::Keys => Value::Number(index as f64),
+        IterationKind::Values => interpreter.get(&object, &index_key(index), &list)?,
+        IterationKind::Entries => {
+            let value = interpreter.get(&object, &index_key(index), &list)?;
+            let entry = vec![Value::Number(index as f64), value];
+            Value::Object(interpreter.new_array(entry))
+        }
+    };
+    Ok(interpreter.iterator_result(value, false))
+}
+
+// %StringIteratorPrototype%.next ( ), which goes through code points. The position is a
+// byte offset into the string.
+fn string_iterator_next(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    let name = "String Iterator.prototype.next";
+    let Some((Value::String(text), position, _)) =
+        iterator_state(interpreter, &this, string_iterator_state, name)?
+    else {
+        return Ok(interpreter.iterator_result(Value::Undefined, true));
+    };
+    match text[position..].chars().next() {
+        Some(c) => {
+            advance(&this, Some(position + c.len_utf8()));
+            Ok(interpreter.iterator_result(Value::from(c.to_string()), false))
+        }
+        None => {
+            advance(&this, None);
+            Ok(interpreter.iterator_result(Value::Undefined, true))
+        }
+    }
+}
+
+// %MapIteratorPrototype%.next ( )
+fn map_iterator_next(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    let name = "Map Iterator.prototype.next";
+    let state = iterator_state(interpreter, &this, map_iterator_state, name)?;
+    collection_iterator_next(interpreter, &this, state)
+}
+
+// %SetIteratorPrototype%.next ( )
+fn set_iterator_next(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    let name = "Set Iterator.prototype.next";
+    let state = iterator_state(interpreter, &this, set_iterator_state, name)?;
+    collection_iterator_next(interpreter, &this, state)
+}
+
+// The next entry of a Map or Set. Set entries are their value twice.
+fn collection_iterator_next(
+    interpreter: &mut Interpreter,
+    this: &Value,
+    state: Option<(Value, usize, IterationKind)>,
+) -> Result<Value> {
+    let Some((Value::Object(map), position, kind)) = state else {
+        return Ok(interpreter.iterator_result(Value::Undefined, true));
+    };
+    let entry = match &map.borrow().kind {
+        ObjectKind::Map(map) => map.entry(position),
+        ObjectKind::Set(set) => set
+            .entry(position)
+            .map(|(position, value, _)| (position, value.clone(), value)),
+        _ => None,
+    };
+    let Some((position, key, value)) = entry else {
+        advance(this, None);
+        return Ok(interpreter.iterator_result(Value::Undefined, true));
+    };
+    advance(this, Some(position + 1));
+    let value = match kind {
+        IterationKind::Keys => key,
+        IterationKind::Values => value,
+        IterationKind::Entries => Value::Object(interpreter.new_array(vec![key, value])),
+    };
+    Ok(interpreter.iterator_result(value, false))
+}
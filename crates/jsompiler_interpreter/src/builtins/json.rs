@@ -0,0 +1,570 @@
+use super::{Result, argument, index_key};
+use crate::number::number_to_string;
+use crate::object::NativeFunction;
+use crate::{ErrorType, Interpreter};
+use jsompiler_runtime::{ObjectKind, ObjectRef, PropertyKey, Value};
+use std::rc::Rc;
+
+// How deep arrays and objects can nest in parsed text
+const MAX_DEPTH: usize = 1000;
+
+pub(super) fn install(interpreter: &mut Interpreter) {
+    let json = interpreter.new_object();
+    let functions: [(&str, usize, NativeFunction); 2] =
+        [("parse", 2, parse), ("stringify", 3, stringify)];
+    for (name, length, function) in functions {
+        interpreter.define_method(&json, name, length, function);
+    }
+    interpreter.define_to_string_tag(&json, "JSON");
+    interpreter.define_global("JSON", json);
+}
+
+// JSON.parse ( text [ , reviver ] )
+fn parse(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let text = interpreter.to_string(&argument(args, 0))?;
+    let chars: Vec<char> = text.chars().collect();
+    let mut parser = Parser {
+        chars: &chars,
+        position: 0,
+        depth: 0,
+    };
+    let value = parser
+        .skip_whitespace()
+        .and_then(|_| parser.value(interpreter))
+        .and_then(|value| {
+            parser.skip_whitespace()?;
+            match parser.peek() {
+                Some(_) => Err(parser.unexpected()),
+                None => Ok(value),
+            }
+        });
+    let value = match value {
+        Ok(value) => value,
+        Err(ParseError::Syntax(message)) => {
+            return Err(interpreter.error(ErrorType::SyntaxError, &message));
+        }
+        Err(ParseError::TooDeep) => {
+            return Err(interpreter.range_error("Maximum call stack size exceeded"));
+        }
+    };
+    let reviver = argument(args, 1);
+    if reviver.as_function().is_none() {
+        return Ok(value);
+    }
+    let root = interpreter.new_object();
+    interpreter.create_data_property(&root, "".into(), value)?;
+    interpreter.internalize_json_property(&root, "".into(), &reviver)
+}
+
+enum ParseError {
+    Syntax(String),
+    TooDeep,
+}
+
+// A parser of JSON text, which is stricter than JavaScript literals: no single quotes,
+// trailing commas, comments or leading zeros
+struct Parser<'a> {
+    chars: &'a [char],
+    position: usize,
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn unexpected(&self) -> ParseError {
+        ParseError::Syntax(match self.peek() {
+            Some(c) => format!("Unexpected token {c} in JSON at position {}", self.position),
+            None => "Unexpected end of JSON input".to_string(),
+        })
+    }
+
+    fn skip_whitespace(&mut self) -> std::result::Result<(), ParseError> {
+        while matches!(self.peek(), Some('\t' | '\n' | '\r' | ' ')) {
+            self.position += 1;
+        }
+        Ok(())
+    }
+
+    fn expect(&mut self, expected: char) -> std::result::Result<(), ParseError> {
+        if self.peek() != Some(expected) {
+            return Err(self.unexpected());
+        }
+        self.position += 1;
+        Ok(())
+    }
+
+    fn value(&mut self, interpreter: &mut Interpreter) -> std::result::Result<Value, ParseError> {
+        match self.peek() {
+            Some('{') => self.nested(interpreter, Self::object),
+            Some('[') => self.nested(interpreter, Self::array),
+            Some('"') => self.string().map(Value::String),
+            Some('-' | '0'..='9') => self.number(),
+            Some('t') => self.keyword("true", Value::Boolean(true)),
+            Some('f') => self.keyword("false", Value::Boolean(false)),
+            Some('n') => self.keyword("null", Value::Null),
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    fn nested(
+        &mut self,
+        interpreter: &mut Interpreter,
+        parse: fn(&mut Self, &mut Interpreter) -> std::result::Result<Value, ParseError>,
+    ) -> std::result::Result<Value, ParseError> {
+        if self.depth >= MAX_DEPTH {
+            return Err(ParseError::TooDeep);
+        }
+        self.depth += 1;
+        let value = parse(self, interpreter);
+        self.depth -= 1;
+        value
+    }
+
+    fn keyword(&mut self, word: &str, value: Value) -> std::result::Result<Value, ParseError> {
+        for expected in word.chars() {
+            self.expect(expected)?;
+        }
+        Ok(value)
+    }
+
+    fn digits(&mut self) -> std::result::Result<(), ParseError> {
+        if !self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            return Err(self.unexpected());
+        }
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.position += 1;
+        }
+        Ok(())
+    }
+
+    fn number(&mut self) -> std::result::Result<Value, ParseError> {
+        let start = self.position;
+        if self.peek() == Some('-') {
+            self.position += 1;
+        }
+        if self.peek() == Some('0') {
+            self.position += 1;
+        } else {
+            self.digits()?;
+        }
+        if self.peek() == Some('.') {
+            self.position += 1;
+            self.digits()?;
+        }
+        if matches!(self.peek(), Some('e' | 'E')) {
+            self.position += 1;
+            if matches!(self.peek(), Some('+' | '-')) {
+                self.position += 1;
+            }
+            self.digits()?;
+        }
+        let text: String = self.chars[start..self.position].iter().collect();
+        Ok(Value::Number(
+            text.parse().expect("the text is a decimal number"),
+        ))
+    }
+
+    fn hex_digits(&mut self) -> std::result::Result<u16, ParseError> {
+        let mut unit = 0;
+        for _ in 0..4 {
+            let Some(digit) = self.peek().and_then(|c| c.to_digit(16)) else {
+                return Err(self.unexpected());
+            };
+            unit = unit * 16 + digit as u16;
+            self.position += 1;
+        }
+        Ok(unit)
+    }
+
+    fn string(&mut self) -> std::result::Result<Rc<str>, ParseError> {
+        self.expect('"')?;
+        let mut units: Vec<u16> = Vec::new();
+        loop {
+            match self.peek() {
+                None => return Err(self.unexpected()),
+                Some('"') => {
+                    self.position += 1;
+                    break;
+                }
+                Some('\\') => {
+                    self.position += 1;
+                    let unit = match self.peek() {
+                        Some('"') => '"' as u16,
+                        Some('\\') => '\\' as u16,
+                        Some('/') => '/' as u16,
+                        Some('b') => 0x08,
+                        Some('f') => 0x0c,
+                        Some('n') => '\n' as u16,
+                        Some('r') => '\r' as u16,
+                        Some('t') => '\t' as u16,
+                        Some('u') => {
+                            self.position += 1;
+                            units.push(self.hex_digits()?);
+                            continue;
+                        }
+                        _ => return Err(self.unexpected()),
+                    };
+                    self.position += 1;
+                    units.push(unit);
+                }
+                Some(c) if (c as u32) < 0x20 => return Err(self.unexpected()),
+                Some(c) => {
+                    let mut buffer = [0; 2];
+                    units.extend_from_slice(c.encode_utf16(&mut buffer));
+                    self.position += 1;
+                }
+            }
+        }
+        Ok(String::from_utf16_lossy(&units).into())
+    }
+
+    fn array(&mut self, interpreter: &mut Interpreter) -> std::result::Result<Value, ParseError> {
+        self.expect('[')?;
+        let mut elements = Vec::new();
+        self.skip_whitespace()?;
+        if self.peek() == Some(']') {
+            self.position += 1;
+            return Ok(Value::Object(interpreter.new_array(elements)));
+        }
+        loop {
+            self.skip_whitespace()?;
+            elements.push(self.value(interpreter)?);
+            self.skip_whitespace()?;
+            match self.peek() {
+                Some(',') => self.position += 1,
+                Some(']') => {
+                    self.position += 1;
+                    return Ok(Value::Object(interpreter.new_array(elements)));
+                }
+                _ => return Err(self.unexpected()),
+            }
+        }
+    }
+
+    fn object(&mut self, interpreter: &mut Interpreter) -> std::result::Result<Value, ParseError> {
+        self.expect('{')?;
+        let object = interpreter.new_object();
+        self.skip_whitespace()?;
+        if self.peek() == Some('}') {
+            self.position += 1;
+            return Ok(Value::Object(object));
+        }
+        loop {
+            self.skip_whitespace()?;
+            if self.peek() != Some('"') {
+                return Err(self.unexpected());
+            }
+            let key = self.string()?;
+            self.skip_whitespace()?;
+            self.expect(':')?;
+            self.skip_whitespace()?;
+            let value = self.value(interpreter)?;
+            // A repeated key replaces the earlier value, and `__proto__` is an ordinary key
+            let key = PropertyKey::from(key);
+            object
+                .borrow_mut()
+                .define_own_property(key, jsompiler_runtime::Property::data(value).into());
+            self.skip_whitespace()?;
+            match self.peek() {
+                Some(',') => self.position += 1,
+                Some('}') => {
+                    self.position += 1;
+                    return Ok(Value::Object(object));
+                }
+                _ => return Err(self.unexpected()),
+            }
+        }
+    }
+}
+
+fn is_array(value: &Value) -> bool {
+    matches!(value, Value::Object(object) if matches!(object.borrow().kind, ObjectKind::Array))
+}
+
+impl Interpreter {
+    // InternalizeJSONProperty: call the reviver on the properties of a parsed value, innermost
+    // first, replacing each with what it returns or deleting it if that is undefined
+    fn internalize_json_property(
+        &mut self,
+        holder: &ObjectRef,
+        name: PropertyKey,
+        reviver: &Value,
+    ) -> Result<Value> {
+        let value = self.get(holder, &name, &Value::Object(holder.clone()))?;
+        if let Value::Object(object) = &value {
+            let keys: Vec<PropertyKey> = if is_array(&value) {
+                let length = self.length_of_array_like(object)?;
+                (0..length).map(index_key).collect()
+            } else {
+                let keys = self.enumerable_own_properties(object, true, false)?;
+                let mut property_keys = Vec::with_capacity(keys.len());
+                for key in keys {
+                    property_keys.push(self.to_property_key(&key)?);
+                }
+                property_keys
+            };
+            for key in keys {
+                let element = self.internalize_json_property(object, key.clone(), reviver)?;
+                // Failures are ignored, as for frozen objects
+                if matches!(element, Value::Undefined) {
+                    object.borrow_mut().delete(&key);
+                } else {
+                    object.borrow_mut().define_own_property(
+                        key,
+                        jsompiler_runtime::Property::data(element).into(),
+                    );
+                }
+            }
+        }
+        let args = [name.to_value(), value];
+        self.call(reviver, Value::Object(holder.clone()), &args)
+    }
+}
+
+// What `JSON.stringify` keeps track of while it serializes
+struct Serializer {
+    replacer: Option<Value>,
+    property_list: Option<Vec<PropertyKey>>,
+    stack: Vec<ObjectRef>, // The objects being serialized, to find cycles
+    indent: String,
+    gap: String,
+}
+
+// JSON.stringify ( value [ , replacer [ , space ] ] )
+fn stringify(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let mut serializer = Serializer {
+        replacer: None,
+        property_list: None,
+        stack: Vec::new(),
+        indent: String::new(),
+        gap: String::new(),
+    };
+    let replacer = argument(args, 1);
+    if replacer.as_function().is_some() {
+        serializer.replacer = Some(replacer);
+    } else if let Some(list) = replacer.as_object().filter(|_| is_array(&replacer)) {
+        // Only the properties the list names are serialized, in its order
+        let length = interpreter.length_of_array_like(list)?;
+        let mut keys: Vec<PropertyKey> = Vec::new();
+        for index in 0..length {
+            let value = interpreter.get(list, &index_key(index), &replacer)?;
+            let item = match &value {
+                Value::String(text) => Some(text.clone()),
+                Value::Number(_) => Some(interpreter.to_string(&value)?),
+                Value::Object(object)
+                    if matches!(
+                        object.borrow().kind,
+                        ObjectKind::Primitive(Value::String(_) | Value::Number(_))
+                    ) =>
+                {
+                    Some(interpreter.to_string(&value)?)
+                }
+                _ => None,
+            };
+            if let Some(item) = item.map(PropertyKey::from)
+                && !keys.contains(&item)
+            {
+                keys.push(item);
+            }
+        }
+        serializer.property_list = Some(keys);
+    }
+    let mut space = argument(args, 2);
+    if let Value::Object(object) = &space {
+        let kind = match &object.borrow().kind {
+            ObjectKind::Primitive(Value::Number(_)) => Some(true),
+            ObjectKind::Primitive(Value::String(_)) => Some(false),
+            _ => None,
+        };
+        space = match kind {
+            Some(true) => Value::Number(interpreter.to_number(&space)?),
+            Some(false) => Value::String(interpreter.to_string(&space)?),
+            None => space,
+        };
+    }
+    serializer.gap = match &space {
+        Value::Number(_) => {
+            let count = interpreter.to_integer_or_infinity(&space)?.clamp(0.0, 10.0);
+            " ".repeat(count as usize)
+        }
+        Value::String(text) => {
+            String::from_utf16_lossy(&text.encode_utf16().take(10).collect::<Vec<_>>())
+        }
+        _ => String::new(),
+    };
+    let wrapper = interpreter.new_object();
+    interpreter.create_data_property(&wrapper, "".into(), argument(args, 0))?;
+    let result = interpreter.serialize_json_property(&mut serializer, "".into(), &wrapper)?;
+    Ok(result.map_or(Value::Undefined, Value::from))
+}
+
+// QuoteJSONString
+fn quote_json_string(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '\u{8}' => quoted.push_str("\\b"),
+            '\t' => quoted.push_str("\\t"),
+            '\n' => quoted.push_str("\\n"),
+            '\u{c}' => quoted.push_str("\\f"),
+            '\r' => quoted.push_str("\\r"),
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+impl Interpreter {
+    // SerializeJSONProperty: the JSON text of a property, or `None` for values JSON leaves
+    // out, like undefined and functions
+    fn serialize_json_property(
+        &mut self,
+        serializer: &mut Serializer,
+        key: PropertyKey,
+        holder: &ObjectRef,
+    ) -> Result<Option<String>> {
+        let mut value = self.get(holder, &key, &Value::Object(holder.clone()))?;
+        if matches!(value, Value::Object(_) | Value::BigInt(_)) {
+            let to_json = self.get_property(&value, &"toJSON".into())?;
+            if to_json.as_function().is_some() {
+                value = self.call(&to_json, value, &[key.to_value()])?;
+            }
+        }
+        if let Some(replacer) = &serializer.replacer {
+            let args = [key.to_value(), value];
+            value = self.call(replacer, Value::Object(holder.clone()), &args)?;
+        }
+        // Primitive wrappers serialize as their primitives
+        if let Value::Object(object) = &value {
+            let primitive = match &object.borrow().kind {
+                ObjectKind::Primitive(primitive) => Some(primitive.clone()),
+                _ => None,
+            };
+            value = match primitive {
+                Some(Value::Number(_)) => Value::Number(self.to_number(&value)?),
+                Some(Value::String(_)) => Value::String(self.to_string(&value)?),
+                Some(primitive @ (Value::Boolean(_) | Value::BigInt(_))) => primitive,
+                _ => value,
+            };
+        }
+        Ok(match value {
+            Value::Null => Some("null".to_string()),
+            Value::Boolean(value) => Some(value.to_string()),
+            Value::String(text) => Some(quote_json_string(&text)),
+            Value::Number(number) if number.is_finite() => Some(number_to_string(number)),
+            Value::Number(_) => Some("null".to_string()),
+            Value::BigInt(_) => {
+                return Err(self.type_error("Do not know how to serialize a BigInt"));
+            }
+            Value::Object(object) if !object.is_callable() => {
+                let text = if matches!(object.borrow().kind, ObjectKind::Array) {
+                    self.serialize_json_array(serializer, &object)?
+                } else {
+                    self.serialize_json_object(serializer, &object)?
+                };
+                Some(text)
+            }
+            _ => None,
+        })
+    }
+
+    // Start serializing an object or array, which mustn't already be on the way to it
+    fn enter_json_value(
+        &mut self,
+        serializer: &mut Serializer,
+        object: &ObjectRef,
+    ) -> Result<String> {
+        if serializer.stack.iter().any(|seen| seen.ptr_eq(object)) {
+            return Err(self.type_error("Converting circular structure to JSON"));
+        }
+        if serializer.stack.len() >= MAX_DEPTH {
+            return Err(self.range_error("Maximum call stack size exceeded"));
+        }
+        serializer.stack.push(object.clone());
+        let stepback = serializer.indent.clone();
+        serializer.indent.push_str(&serializer.gap);
+        Ok(stepback)
+    }
+
+    // The members of an object or array between its brackets, on lines of their own if
+    // there is a gap
+    fn leave_json_value(
+        &mut self,
+        serializer: &mut Serializer,
+        stepback: String,
+        parts: Vec<String>,
+        (open, close): (char, char),
+    ) -> String {
+        let text = if parts.is_empty() {
+            format!("{open}{close}")
+        } else if serializer.gap.is_empty() {
+            format!("{open}{}{close}", parts.join(","))
+        } else {
+            let separator = format!(",\n{}", serializer.indent);
+            format!(
+                "{open}\n{}{}\n{stepback}{close}",
+                serializer.indent,
+                parts.join(&separator)
+            )
+        };
+        serializer.stack.pop();
+        serializer.indent = stepback;
+        text
+    }
+
+    // SerializeJSONObject
+    fn serialize_json_object(
+        &mut self,
+        serializer: &mut Serializer,
+        object: &ObjectRef,
+    ) -> Result<String> {
+        let stepback = self.enter_json_value(serializer, object)?;
+        let keys = match &serializer.property_list {
+            Some(keys) => keys.clone(),
+            None => {
+                let keys = self.enumerable_own_properties(object, true, false)?;
+                let mut property_keys = Vec::with_capacity(keys.len());
+                for key in keys {
+                    property_keys.push(self.to_property_key(&key)?);
+                }
+                property_keys
+            }
+        };
+        let mut parts = Vec::new();
+        for key in keys {
+            let Some(text) = self.serialize_json_property(serializer, key.clone(), object)? else {
+                continue;
+            };
+            let separator = if serializer.gap.is_empty() { ":" } else { ": " };
+            parts.push(format!(
+                "{}{separator}{text}",
+                quote_json_string(&key.to_string())
+            ));
+        }
+        Ok(self.leave_json_value(serializer, stepback, parts, ('{', '}')))
+    }
+
+    // SerializeJSONArray, where values JSON leaves out become null
+    fn serialize_json_array(
+        &mut self,
+        serializer: &mut Serializer,
+        array: &ObjectRef,
+    ) -> Result<String> {
+        let stepback = self.enter_json_value(serializer, array)?;
+        let length = self.length_of_array_like(array)?;
+        let mut parts = Vec::new();
+        for index in 0..length {
+            let text = self.serialize_json_property(serializer, index_key(index), array)?;
+            parts.push(text.unwrap_or_else(|| "null".to_string()));
+        }
+        Ok(self.leave_json_value(serializer, stepback, parts, ('[', ']')))
+    }
+}
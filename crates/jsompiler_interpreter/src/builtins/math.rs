@@ -0,0 +1,321 @@
+use super::{Result, argument};
+use crate::Interpreter;
+use crate::number::{to_int32, to_uint32};
+use crate::object::NativeFunction;
+use jsompiler_runtime::{Property, Value};
+use std::cell::Cell;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub(super) fn install(interpreter: &mut Interpreter) {
+    let math = interpreter.new_object();
+    let constants = [
+        ("E", std::f64::consts::E),
+        ("LN10", std::f64::consts::LN_10),
+        ("LN2", std::f64::consts::LN_2),
+        ("LOG10E", std::f64::consts::LOG10_E),
+        ("LOG2E", std::f64::consts::LOG2_E),
+        ("PI", std::f64::consts::PI),
+        ("SQRT1_2", std::f64::consts::FRAC_1_SQRT_2),
+        ("SQRT2", std::f64::consts::SQRT_2),
+    ];
+    for (name, value) in constants {
+        let property = Property::new(Value::Number(value), false, false, false);
+        math.borrow_mut().define(name, property);
+    }
+    let functions: [(&str, usize, NativeFunction); 35] = [
+        ("abs", 1, abs),
+        ("acos", 1, acos),
+        ("acosh", 1, acosh),
+        ("asin", 1, asin),
+        ("asinh", 1, asinh),
+        ("atan", 1, atan),
+        ("atanh", 1, atanh),
+        ("atan2", 2, atan2),
+        ("cbrt", 1, cbrt),
+        ("ceil", 1, ceil),
+        ("clz32", 1, clz32),
+        ("cos", 1, cos),
+        ("cosh", 1, cosh),
+        ("exp", 1, exp),
+        ("expm1", 1, expm1),
+        ("floor", 1, floor),
+        ("fround", 1, fround),
+        ("hypot", 2, hypot),
+        ("imul", 2, imul),
+        ("log", 1, log),
+        ("log1p", 1, log1p),
+        ("log10", 1, log10),
+        ("log2", 1, log2),
+        ("max", 2, max),
+        ("min", 2, min),
+        ("pow", 2, pow),
+        ("random", 0, random),
+        ("round", 1, round),
+        ("sign", 1, sign),
+        ("sin", 1, sin),
+        ("sinh", 1, sinh),
+        ("sqrt", 1, sqrt),
+        ("tan", 1, tan),
+        ("tanh", 1, tanh),
+        ("trunc", 1, trunc),
+    ];
+    for (name, length, function) in functions {
+        interpreter.define_method(&math, name, length, function);
+    }
+    interpreter.define_to_string_tag(&math, "Math");
+    interpreter.define_global("Math", math);
+}
+
+// The first argument as a number, which most of the functions take
+fn number(interpreter: &mut Interpreter, args: &[Value]) -> Result<f64> {
+    interpreter.to_number(&argument(args, 0))
+}
+
+// A function of one number that Rust has, like `Math.sin`
+fn unary(interpreter: &mut Interpreter, args: &[Value], f: fn(f64) -> f64) -> Result<Value> {
+    number(interpreter, args).map(|x| Value::Number(f(x)))
+}
+
+fn abs(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    unary(interpreter, args, f64::abs)
+}
+
+fn acos(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    unary(interpreter, args, f64::acos)
+}
+
+fn acosh(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    unary(interpreter, args, f64::acosh)
+}
+
+fn asin(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    unary(interpreter, args, f64::asin)
+}
+
+fn asinh(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    // Rust's `asinh` loses the sign of -0
+    unary(interpreter, args, |x| if x == 0.0 { x } else { x.asinh() })
+}
+
+fn atan(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    unary(interpreter, args, f64::atan)
+}
+
+fn atanh(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    unary(interpreter, args, f64::atanh)
+}
+
+// Math.atan2 ( y, x )
+fn atan2(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let y = interpreter.to_number(&argument(args, 0))?;
+    let x = interpreter.to_number(&argument(args, 1))?;
+    Ok(Value::Number(y.atan2(x)))
+}
+
+fn cbrt(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    unary(interpreter, args, f64::cbrt)
+}
+
+fn ceil(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    unary(interpreter, args, f64::ceil)
+}
+
+// Math.clz32 ( x ): the leading zero bits of ToUint32(x)
+fn clz32(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    unary(interpreter, args, |x| to_uint32(x).leading_zeros() as f64)
+}
+
+fn cos(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    unary(interpreter, args, f64::cos)
+}
+
+fn cosh(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    unary(interpreter, args, f64::cosh)
+}
+
+fn exp(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    unary(interpreter, args, f64::exp)
+}
+
+fn expm1(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    unary(interpreter, args, f64::exp_m1)
+}
+
+fn floor(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    unary(interpreter, args, f64::floor)
+}
+
+// Math.fround ( x ): the nearest single precision number
+fn fround(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    unary(interpreter, args, |x| x as f32 as f64)
+}
+
+// Math.hypot ( ...args ): every argument is converted before any of them is looked at, and
+// an infinity wins over NaN
+fn hypot(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let mut numbers = Vec::with_capacity(args.len());
+    for arg in args {
+        numbers.push(interpreter.to_number(arg)?);
+    }
+    if numbers.iter().any(|x| x.is_infinite()) {
+        return Ok(Value::Number(f64::INFINITY));
+    }
+    if numbers.iter().any(|x| x.is_nan()) {
+        return Ok(Value::Number(f64::NAN));
+    }
+    // Scale by the largest to keep the squares from overflowing
+    let largest = numbers
+        .iter()
+        .fold(0.0, |largest: f64, x| largest.max(x.abs()));
+    if largest == 0.0 {
+        return Ok(Value::Number(0.0));
+    }
+    let sum: f64 = numbers.iter().map(|x| (x / largest).powi(2)).sum();
+    Ok(Value::Number(sum.sqrt() * largest))
+}
+
+// Math.imul ( x, y ): 32 bit integer multiplication
+fn imul(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let x = to_int32(interpreter.to_number(&argument(args, 0))?);
+    let y = to_int32(interpreter.to_number(&argument(args, 1))?);
+    Ok(Value::Number(x.wrapping_mul(y) as f64))
+}
+
+fn log(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    unary(interpreter, args, f64::ln)
+}
+
+fn log1p(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    unary(interpreter, args, f64::ln_1p)
+}
+
+fn log10(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    unary(interpreter, args, f64::log10)
+}
+
+fn log2(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    unary(interpreter, args, f64::log2)
+}
+
+// What `max` and `min` share: all arguments are converted, NaN wins, and +0 is larger than -0
+fn extreme(interpreter: &mut Interpreter, args: &[Value], largest: bool) -> Result<Value> {
+    let mut numbers = Vec::with_capacity(args.len());
+    for arg in args {
+        numbers.push(interpreter.to_number(arg)?);
+    }
+    let mut result = if largest {
+        f64::NEG_INFINITY
+    } else {
+        f64::INFINITY
+    };
+    for x in numbers {
+        if x.is_nan() {
+            return Ok(Value::Number(f64::NAN));
+        }
+        let zeros = x == 0.0 && result == 0.0;
+        let replaces = if largest {
+            x > result || (zeros && result.is_sign_negative())
+        } else {
+            x < result || (zeros && x.is_sign_negative())
+        };
+        if replaces {
+            result = x;
+        }
+    }
+    Ok(Value::Number(result))
+}
+
+// Math.max ( ...args )
+fn max(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    extreme(interpreter, args, true)
+}
+
+// Math.min ( ...args )
+fn min(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    extreme(interpreter, args, false)
+}
+
+// Math.pow ( base, exponent ), which is what `**` does
+fn pow(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let base = interpreter.to_number(&argument(args, 0))?;
+    let exponent = interpreter.to_number(&argument(args, 1))?;
+    // Unlike C, 1 ** NaN and (-1) ** ±Infinity are NaN
+    if exponent.is_nan() || (base.abs() == 1.0 && exponent.is_infinite()) {
+        return Ok(Value::Number(f64::NAN));
+    }
+    Ok(Value::Number(base.powf(exponent)))
+}
+
+thread_local! {
+    static RANDOM_STATE: Cell<u64> = Cell::new(random_seed());
+}
+
+fn random_seed() -> u64 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_nanos() as u64);
+    // xorshift can't start from zero
+    nanos | 1
+}
+
+// Math.random ( ): xorshift64*, which is plenty for scripts but not for cryptography
+fn random(_: &mut Interpreter, _: Value, _: &[Value]) -> Result<Value> {
+    let bits = RANDOM_STATE.with(|state| {
+        let mut x = state.get();
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        state.set(x);
+        x.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    });
+    // The top 53 bits as a fraction in [0, 1)
+    Ok(Value::Number((bits >> 11) as f64 / (1u64 << 53) as f64))
+}
+
+// Math.round ( x ): halves round up, toward +Infinity, and the sign of zero is kept
+fn round(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    unary(interpreter, args, |x| {
+        if !x.is_finite() || x.fract() == 0.0 {
+            return x;
+        }
+        if (-0.5..0.0).contains(&x) {
+            return -0.0;
+        }
+        let floor = x.floor();
+        if x - floor >= 0.5 { floor + 1.0 } else { floor }
+    })
+}
+
+fn sign(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    unary(interpreter, args, |x| {
+        if x.is_nan() || x == 0.0 {
+            x
+        } else {
+            x.signum()
+        }
+    })
+}
+
+fn sin(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    unary(interpreter, args, f64::sin)
+}
+
+fn sinh(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    unary(interpreter, args, f64::sinh)
+}
+
+fn sqrt(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    unary(interpreter, args, f64::sqrt)
+}
+
+fn tan(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    unary(interpreter, args, f64::tan)
+}
+
+fn tanh(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    unary(interpreter, args, f64::tanh)
+}
+
+fn trunc(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    unary(interpreter, args, f64::trunc)
+}
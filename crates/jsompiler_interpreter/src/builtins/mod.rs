@@ -0,0 +1,221 @@
+mod array;
+mod collections;
+mod console;
+mod error;
+mod function;
+mod iterator;
+mod json;
+mod math;
+mod number;
+mod object;
+mod reflect;
+mod string;
+mod symbol;
+
+pub(crate) use iterator::IteratorRecord;
+
+use crate::Interpreter;
+use crate::inspect::inspect;
+use jsompiler_runtime::{Object, ObjectKind, ObjectRef, Property, PropertyKey, Value};
+
+type Result<T> = std::result::Result<T, Value>;
+
+// Largest length of an array-like object, 2 ** 53 - 1
+const MAX_LENGTH: f64 = 9007199254740991.0;
+
+// Define the global constructors, namespaces and functions of the standard library, in the
+// order the specification lists them
+pub(crate) fn install(interpreter: &mut Interpreter) {
+    number::install_globals(interpreter);
+    object::install(interpreter);
+    function::install(interpreter);
+    number::install_boolean(interpreter);
+    symbol::install(interpreter);
+    error::install(interpreter);
+    number::install(interpreter);
+    math::install(interpreter);
+    string::install(interpreter);
+    array::install(interpreter);
+    iterator::install(interpreter);
+    collections::install(interpreter);
+    json::install(interpreter);
+    reflect::install(interpreter);
+    console::install(interpreter);
+}
+
+pub(crate) fn argument(args: &[Value], index: usize) -> Value {
+    args.get(index).cloned().unwrap_or(Value::Undefined)
+}
+
+// The key of an index of an array-like object, which can be past the largest array index
+fn index_key(index: u64) -> PropertyKey {
+    PropertyKey::from(index as usize)
+}
+
+impl Interpreter {
+    // ToObject, which wraps primitives in an object of their type
+    pub fn to_object(&mut self, value: &Value) -> Result<ObjectRef> {
+        let prototype = match value {
+            Value::Object(object) => return Ok(object.clone()),
+            Value::Undefined | Value::Null => {
+                return Err(self.type_error("Cannot convert undefined or null to object"));
+            }
+            Value::Boolean(_) => &self.intrinsics.boolean_prototype,
+            Value::Number(_) => &self.intrinsics.number_prototype,
+            Value::BigInt(_) => &self.intrinsics.bigint_prototype,
+            Value::String(_) => &self.intrinsics.string_prototype,
+            Value::Symbol(_) => &self.intrinsics.symbol_prototype,
+        };
+        Ok(self.primitive_object(value.clone(), prototype.clone()))
+    }
+
+    // A Boolean, Number, String, Symbol or BigInt object. String objects have the code units
+    // of the string as read-only indices.
+    pub(crate) fn primitive_object(&self, value: Value, prototype: ObjectRef) -> ObjectRef {
+        let mut object = Object::new(Some(prototype), ObjectKind::Primitive(value.clone()));
+        if let Value::String(text) = &value {
+            let units: Vec<u16> = text.encode_utf16().collect();
+            for (index, unit) in units.iter().enumerate() {
+                let unit = Value::from(String::from_utf16_lossy(&[*unit]));
+                object.define(index, Property::new(unit, false, true, false));
+            }
+            let length = Value::Number(units.len() as f64);
+            object.define("length", Property::new(length, false, false, false));
+        }
+        ObjectRef::new(object)
+    }
+
+    // ToIntegerOrInfinity: NaN is 0 and fractions are truncated
+    pub fn to_integer_or_infinity(&mut self, value: &Value) -> Result<f64> {
+        let number = self.to_number(value)?;
+        Ok(if number.is_nan() {
+            0.0
+        } else {
+            number.trunc() + 0.0
+        })
+    }
+
+    // ToLength: an integer from 0 to 2 ** 53 - 1
+    pub fn to_length(&mut self, value: &Value) -> Result<u64> {
+        let length = self.to_integer_or_infinity(value)?;
+        Ok(length.clamp(0.0, MAX_LENGTH) as u64)
+    }
+
+    // LengthOfArrayLike
+    pub(crate) fn length_of_array_like(&mut self, object: &ObjectRef) -> Result<u64> {
+        let length = self.get(object, &"length".into(), &Value::Object(object.clone()))?;
+        self.to_length(&length)
+    }
+
+    // A position relative to the start of a list, or to its end if it is negative, clamped to
+    // the list, as `slice` and `splice` take them
+    pub(crate) fn relative_index(&mut self, value: &Value, length: u64) -> Result<u64> {
+        let relative = self.to_integer_or_infinity(value)?;
+        Ok(if relative < 0.0 {
+            (length as f64 + relative).max(0.0) as u64
+        } else {
+            relative.min(length as f64) as u64
+        })
+    }
+
+    // CreateDataPropertyOrThrow
+    pub(crate) fn create_data_property(
+        &mut self,
+        object: &ObjectRef,
+        key: PropertyKey,
+        value: Value,
+    ) -> Result<()> {
+        if object
+            .borrow_mut()
+            .define_own_property(key.clone(), Property::data(value).into())
+        {
+            return Ok(());
+        }
+        let message = if object.borrow().extensible {
+            format!("Cannot redefine property: {key}")
+        } else {
+            format!("Cannot add property {key}, object is not extensible")
+        };
+        Err(self.type_error(&message))
+    }
+
+    // Set(O, P, V, true), which built-ins use and which throws whether or not the code that
+    // called them is strict
+    pub(crate) fn put(
+        &mut self,
+        object: &ObjectRef,
+        key: &PropertyKey,
+        value: Value,
+    ) -> Result<()> {
+        match self.try_set(object, key, value, &Value::Object(object.clone()))? {
+            Some(message) => Err(self.type_error(&message)),
+            None => Ok(()),
+        }
+    }
+
+    // DeletePropertyOrThrow
+    pub(crate) fn delete_or_throw(&mut self, object: &ObjectRef, key: &PropertyKey) -> Result<()> {
+        if object.borrow_mut().delete(key) {
+            return Ok(());
+        }
+        let message = format!(
+            "Cannot delete property '{key}' of {}",
+            inspect(&Value::Object(object.clone()))
+        );
+        Err(self.type_error(&message))
+    }
+
+    // GetMethod: the function a value has under `key`, or `None` if it is undefined or null
+    pub(crate) fn get_method(&mut self, value: &Value, key: &PropertyKey) -> Result<Option<Value>> {
+        let function = self.get_property(value, key)?;
+        if function.is_nullish() {
+            return Ok(None);
+        }
+        if function.as_function().is_none() {
+            let message = format!("{} is not a function", inspect(&function));
+            return Err(self.type_error(&message));
+        }
+        Ok(Some(function))
+    }
+
+    // Invoke: call the method of a value under `key`
+    pub(crate) fn invoke(&mut self, value: &Value, key: &str, args: &[Value]) -> Result<Value> {
+        let function = self.get_property(value, &key.into())?;
+        self.call(&function, value.clone(), args)
+    }
+
+    // CreateListFromArrayLike, the arguments `apply` and `Reflect.construct` take
+    pub(crate) fn list_from_array_like(&mut self, value: &Value) -> Result<Vec<Value>> {
+        let Value::Object(object) = value else {
+            return Err(self.type_error("CreateListFromArrayLike called on non-object"));
+        };
+        let length = self.length_of_array_like(object)?;
+        let mut list = Vec::with_capacity(length.min(1 << 16) as usize);
+        for index in 0..length {
+            list.push(self.get(object, &index_key(index), value)?);
+        }
+        Ok(list)
+    }
+
+    // A built-in method that only works on values of one type, like `Number.prototype.toFixed`
+    // on numbers and Number objects
+    pub(crate) fn this_primitive(
+        &mut self,
+        this: &Value,
+        matches: fn(&Value) -> bool,
+        method: &str,
+        type_name: &str,
+    ) -> Result<Value> {
+        if matches(this) {
+            return Ok(this.clone());
+        }
+        if let Value::Object(object) = this
+            && let ObjectKind::Primitive(value) = &object.borrow().kind
+            && matches(value)
+        {
+            return Ok(value.clone());
+        }
+        let message = format!("{method} requires that 'this' be a {type_name}");
+        Err(self.type_error(&message))
+    }
+}
@@ -0,0 +1,556 @@
+use super::{Result, argument};
+use crate::Interpreter;
+use crate::number::{
+    bigint_to_number, is_whitespace, number_to_bigint, number_to_radix_string, number_to_string,
+    round_scaled, significant_digits, string_to_bigint, to_int32,
+};
+use crate::object::NativeFunction;
+use crate::operations::{Hint, Numeric};
+use jsompiler_runtime::{ObjectRef, Property, Value};
+use num_bigint::BigInt;
+
+// isFinite, isNaN, parseFloat and parseInt, which `Number` shares
+pub(super) fn install_globals(interpreter: &mut Interpreter) {
+    let functions: [(&str, usize, NativeFunction); 4] = [
+        ("isFinite", 1, is_finite),
+        ("isNaN", 1, is_nan),
+        ("parseFloat", 1, parse_float),
+        ("parseInt", 2, parse_int),
+    ];
+    let global = interpreter.global.clone();
+    for (name, length, function) in functions {
+        interpreter.define_method(&global, name, length, function);
+    }
+}
+
+pub(super) fn install_boolean(interpreter: &mut Interpreter) {
+    let prototype = interpreter.intrinsics.boolean_prototype.clone();
+    let constructor = interpreter.native_constructor("Boolean", 1, boolean, &prototype);
+    interpreter.define_method(&prototype, "toString", 0, boolean_to_string);
+    interpreter.define_method(&prototype, "valueOf", 0, boolean_value_of);
+    interpreter.define_global("Boolean", constructor);
+}
+
+pub(super) fn install(interpreter: &mut Interpreter) {
+    let prototype = interpreter.intrinsics.number_prototype.clone();
+    let constructor = interpreter.native_constructor("Number", 1, number, &prototype);
+    let constants = [
+        ("EPSILON", f64::EPSILON),
+        ("MAX_SAFE_INTEGER", 9007199254740991.0),
+        ("MAX_VALUE", f64::MAX),
+        ("MIN_SAFE_INTEGER", -9007199254740991.0),
+        ("MIN_VALUE", f64::from_bits(1)),
+        ("NaN", f64::NAN),
+        ("NEGATIVE_INFINITY", f64::NEG_INFINITY),
+        ("POSITIVE_INFINITY", f64::INFINITY),
+    ];
+    for (name, value) in constants {
+        let property = Property::new(Value::Number(value), false, false, false);
+        constructor.borrow_mut().define(name, property);
+    }
+    let statics: [(&str, usize, NativeFunction); 4] = [
+        ("isFinite", 1, number_is_finite),
+        ("isInteger", 1, is_integer),
+        ("isNaN", 1, number_is_nan),
+        ("isSafeInteger", 1, is_safe_integer),
+    ];
+    for (name, length, function) in statics {
+        interpreter.define_method(&constructor, name, length, function);
+    }
+    // `Number.parseFloat` and `Number.parseInt` are the global functions
+    for name in ["parseFloat", "parseInt"] {
+        let function = interpreter
+            .global
+            .borrow()
+            .get_own(&name.into())
+            .map(|property| property.data_value())
+            .expect("the global functions are installed first");
+        constructor
+            .borrow_mut()
+            .define(name, Property::hidden(function));
+    }
+    let methods: [(&str, usize, NativeFunction); 6] = [
+        ("toExponential", 1, to_exponential),
+        ("toFixed", 1, to_fixed),
+        ("toLocaleString", 0, number_to_locale_string),
+        ("toPrecision", 1, to_precision),
+        ("toString", 1, number_to_string_method),
+        ("valueOf", 0, number_value_of),
+    ];
+    for (name, length, function) in methods {
+        interpreter.define_method(&prototype, name, length, function);
+    }
+    interpreter.define_global("Number", constructor);
+
+    let prototype = interpreter.intrinsics.bigint_prototype.clone();
+    let constructor = interpreter.native_constructor("BigInt", 1, bigint, &prototype);
+    interpreter.define_method(&constructor, "asIntN", 2, as_int_n);
+    interpreter.define_method(&constructor, "asUintN", 2, as_uint_n);
+    let methods: [(&str, usize, NativeFunction); 3] = [
+        ("toLocaleString", 0, bigint_to_locale_string),
+        ("toString", 0, bigint_to_string),
+        ("valueOf", 0, bigint_value_of),
+    ];
+    for (name, length, function) in methods {
+        interpreter.define_method(&prototype, name, length, function);
+    }
+    interpreter.define_to_string_tag(&prototype, "BigInt");
+    interpreter.define_global("BigInt", constructor);
+}
+
+// isFinite ( number )
+fn is_finite(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let number = interpreter.to_number(&argument(args, 0))?;
+    Ok(Value::Boolean(number.is_finite()))
+}
+
+// isNaN ( number )
+fn is_nan(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let number = interpreter.to_number(&argument(args, 0))?;
+    Ok(Value::Boolean(number.is_nan()))
+}
+
+// parseFloat ( string ): the longest prefix that is a decimal literal, after whitespace
+fn parse_float(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let text = interpreter.to_string(&argument(args, 0))?;
+    let text = text.trim_start_matches(is_whitespace);
+    let bytes = text.as_bytes();
+    let mut end = usize::from(
+        bytes
+            .first()
+            .is_some_and(|byte| matches!(byte, b'+' | b'-')),
+    );
+    if text[end..].starts_with("Infinity") {
+        let infinity = if bytes.first() == Some(&b'-') {
+            f64::NEG_INFINITY
+        } else {
+            f64::INFINITY
+        };
+        return Ok(Value::Number(infinity));
+    }
+    let digits = |from: usize| {
+        from + bytes[from..]
+            .iter()
+            .take_while(|byte| byte.is_ascii_digit())
+            .count()
+    };
+    let integer_end = digits(end);
+    let mut has_digits = integer_end > end;
+    end = integer_end;
+    if bytes.get(end) == Some(&b'.') {
+        let fraction_end = digits(end + 1);
+        if has_digits || fraction_end > end + 1 {
+            has_digits = true;
+            end = fraction_end;
+        }
+    }
+    if !has_digits {
+        return Ok(Value::Number(f64::NAN));
+    }
+    if matches!(bytes.get(end), Some(b'e' | b'E')) {
+        let sign = usize::from(matches!(bytes.get(end + 1), Some(b'+' | b'-')));
+        let exponent_end = digits(end + 1 + sign);
+        if exponent_end > end + 1 + sign {
+            end = exponent_end;
+        }
+    }
+    Ok(Value::Number(text[..end].parse().unwrap_or(f64::NAN)))
+}
+
+// parseInt ( string, radix ): the digits at the start of a string, in base 10 unless the
+// radix says otherwise or the digits start with "0x"
+fn parse_int(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let text = interpreter.to_string(&argument(args, 0))?;
+    let mut text = text.trim_start_matches(is_whitespace);
+    let negative = text.starts_with('-');
+    text = text.strip_prefix(['+', '-']).unwrap_or(text);
+    let mut radix = to_int32(interpreter.to_number(&argument(args, 1))?);
+    let mut strip_prefix = true;
+    if radix != 0 {
+        if !(2..=36).contains(&radix) {
+            return Ok(Value::Number(f64::NAN));
+        }
+        strip_prefix = radix == 16;
+    } else {
+        radix = 10;
+    }
+    if strip_prefix && text.len() >= 2 && text[..2].eq_ignore_ascii_case("0x") {
+        text = &text[2..];
+        radix = 16;
+    }
+    let end = text
+        .find(|c: char| !c.is_digit(radix as u32))
+        .unwrap_or(text.len());
+    let Some(value) = BigInt::parse_bytes(&text.as_bytes()[..end], radix as u32) else {
+        return Ok(Value::Number(f64::NAN));
+    };
+    let value = bigint_to_number(&value);
+    Ok(Value::Number(if negative { -value } else { value }))
+}
+
+// Boolean ( value )
+fn boolean(
+    interpreter: &mut Interpreter,
+    args: &[Value],
+    new_target: Option<&ObjectRef>,
+) -> Result<Value> {
+    let value = Value::Boolean(argument(args, 0).to_boolean());
+    let Some(new_target) = new_target else {
+        return Ok(value);
+    };
+    let default = interpreter.intrinsics.boolean_prototype.clone();
+    let prototype = interpreter.prototype_from_constructor(new_target, default)?;
+    Ok(Value::Object(
+        interpreter.primitive_object(value, prototype),
+    ))
+}
+
+fn this_boolean(interpreter: &mut Interpreter, this: &Value, method: &str) -> Result<bool> {
+    let is_boolean = |value: &Value| matches!(value, Value::Boolean(_));
+    match interpreter.this_primitive(this, is_boolean, method, "Boolean")? {
+        Value::Boolean(value) => Ok(value),
+        _ => unreachable!("the value is a boolean"),
+    }
+}
+
+// Boolean.prototype.toString ( )
+fn boolean_to_string(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    let value = this_boolean(interpreter, &this, "Boolean.prototype.toString")?;
+    Ok(Value::from(value.to_string()))
+}
+
+// Boolean.prototype.valueOf ( )
+fn boolean_value_of(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    this_boolean(interpreter, &this, "Boolean.prototype.valueOf").map(Value::Boolean)
+}
+
+// Number ( value ), which also converts BigInts
+fn number(
+    interpreter: &mut Interpreter,
+    args: &[Value],
+    new_target: Option<&ObjectRef>,
+) -> Result<Value> {
+    let value = match args.first() {
+        Some(value) => match interpreter.to_numeric(value)? {
+            Numeric::Number(number) => number,
+            Numeric::BigInt(bigint) => bigint_to_number(&bigint),
+        },
+        None => 0.0,
+    };
+    let Some(new_target) = new_target else {
+        return Ok(Value::Number(value));
+    };
+    let default = interpreter.intrinsics.number_prototype.clone();
+    let prototype = interpreter.prototype_from_constructor(new_target, default)?;
+    Ok(Value::Object(
+        interpreter.primitive_object(Value::Number(value), prototype),
+    ))
+}
+
+// Number.isFinite ( number ), which doesn't convert its argument
+fn number_is_finite(_: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let finite = matches!(argument(args, 0), Value::Number(number) if number.is_finite());
+    Ok(Value::Boolean(finite))
+}
+
+// Number.isInteger ( number )
+fn is_integer(_: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let integer = matches!(
+        argument(args, 0),
+        Value::Number(number) if number.is_finite() && number.trunc() == number
+    );
+    Ok(Value::Boolean(integer))
+}
+
+// Number.isNaN ( number )
+fn number_is_nan(_: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let nan = matches!(argument(args, 0), Value::Number(number) if number.is_nan());
+    Ok(Value::Boolean(nan))
+}
+
+// Number.isSafeInteger ( number )
+fn is_safe_integer(_: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let safe = matches!(
+        argument(args, 0),
+        Value::Number(number)
+            if number.trunc() == number && number.abs() <= 9007199254740991.0
+    );
+    Ok(Value::Boolean(safe))
+}
+
+fn this_number(interpreter: &mut Interpreter, this: &Value, method: &str) -> Result<f64> {
+    let is_number = |value: &Value| matches!(value, Value::Number(_));
+    match interpreter.this_primitive(this, is_number, method, "Number")? {
+        Value::Number(value) => Ok(value),
+        _ => unreachable!("the value is a number"),
+    }
+}
+
+// The fraction digits `toFixed` and `toExponential` take, which can be 0 to 100
+fn fraction_digits(interpreter: &mut Interpreter, value: &Value, method: &str) -> Result<f64> {
+    let digits = interpreter.to_integer_or_infinity(value)?;
+    if !(0.0..=100.0).contains(&digits) {
+        let message = format!("{method}() argument must be between 0 and 100");
+        return Err(interpreter.range_error(&message));
+    }
+    Ok(digits)
+}
+
+// "d.ddde+x", the exponent form of digits whose first one is at `exponent`
+fn exponential(digits: &str, exponent: i32) -> String {
+    let (first, rest) = digits.split_at(1);
+    let sign = if exponent < 0 { '-' } else { '+' };
+    let fraction = if rest.is_empty() {
+        String::new()
+    } else {
+        format!(".{rest}")
+    };
+    format!("{first}{fraction}e{sign}{}", exponent.abs())
+}
+
+// Number.prototype.toExponential ( fractionDigits )
+fn to_exponential(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let value = this_number(interpreter, &this, "Number.prototype.toExponential")?;
+    let digits = interpreter.to_integer_or_infinity(&argument(args, 0))?;
+    if !value.is_finite() {
+        return Ok(Value::from(number_to_string(value)));
+    }
+    let digits = match argument(args, 0) {
+        Value::Undefined => None,
+        _ => Some(fraction_digits(
+            interpreter,
+            &Value::Number(digits),
+            "toExponential",
+        )?),
+    };
+    let sign = if value < 0.0 { "-" } else { "" };
+    let value = value.abs();
+    let (digits, exponent) = match digits {
+        _ if value == 0.0 => ("0".repeat(digits.unwrap_or(0.0) as usize + 1), 0),
+        // As many digits as it takes to tell the number apart from its neighbors
+        None => {
+            let shortest = format!("{value:e}");
+            let (mantissa, exponent) = shortest
+                .split_once('e')
+                .expect("exponent formatting has an exponent");
+            let digits = mantissa.replace('.', "");
+            (digits, exponent.parse().expect("the exponent is a number"))
+        }
+        Some(digits) => significant_digits(value, digits as u32 + 1),
+    };
+    Ok(Value::from(format!(
+        "{sign}{}",
+        exponential(&digits, exponent)
+    )))
+}
+
+// Number.prototype.toFixed ( fractionDigits ), which rounds halves up rather than to even
+fn to_fixed(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let value = this_number(interpreter, &this, "Number.prototype.toFixed")?;
+    let digits = fraction_digits(interpreter, &argument(args, 0), "toFixed")? as usize;
+    if !value.is_finite() || value.abs() >= 1e21 {
+        return Ok(Value::from(number_to_string(value)));
+    }
+    let sign = if value < 0.0 { "-" } else { "" };
+    let rounded = round_scaled(value.abs(), digits as i32).to_string();
+    let rounded = format!("{rounded:0>width$}", width = digits + 1);
+    let (whole, fraction) = rounded.split_at(rounded.len() - digits);
+    let text = if fraction.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{whole}.{fraction}")
+    };
+    // (-0.0001).toFixed(2) is "-0.00", but (-0).toFixed(2) is "0.00"
+    let sign = if value == 0.0 { "" } else { sign };
+    Ok(Value::from(format!("{sign}{text}")))
+}
+
+// Number.prototype.toLocaleString ( ), which ignores locales
+fn number_to_locale_string(
+    interpreter: &mut Interpreter,
+    this: Value,
+    _: &[Value],
+) -> Result<Value> {
+    let value = this_number(interpreter, &this, "Number.prototype.toLocaleString")?;
+    Ok(Value::from(number_to_string(value)))
+}
+
+// Number.prototype.toPrecision ( precision )
+fn to_precision(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let value = this_number(interpreter, &this, "Number.prototype.toPrecision")?;
+    if matches!(argument(args, 0), Value::Undefined) {
+        return Ok(Value::from(number_to_string(value)));
+    }
+    let precision = interpreter.to_integer_or_infinity(&argument(args, 0))?;
+    if !value.is_finite() {
+        return Ok(Value::from(number_to_string(value)));
+    }
+    if !(1.0..=100.0).contains(&precision) {
+        return Err(interpreter.range_error("toPrecision() argument must be between 1 and 100"));
+    }
+    let precision = precision as i32;
+    let sign = if value < 0.0 { "-" } else { "" };
+    let value = value.abs();
+    let (digits, exponent) = if value == 0.0 {
+        ("0".repeat(precision as usize), 0)
+    } else {
+        significant_digits(value, precision as u32)
+    };
+    let text = if exponent < -6 || exponent >= precision {
+        exponential(&digits, exponent)
+    } else if exponent == precision - 1 {
+        digits
+    } else if exponent >= 0 {
+        let (whole, fraction) = digits.split_at(exponent as usize + 1);
+        format!("{whole}.{fraction}")
+    } else {
+        format!("0.{}{digits}", "0".repeat((-exponent - 1) as usize))
+    };
+    Ok(Value::from(format!("{sign}{text}")))
+}
+
+// The radix `toString` takes, 10 unless it is given
+fn radix(interpreter: &mut Interpreter, value: &Value) -> Result<u32> {
+    if matches!(value, Value::Undefined) {
+        return Ok(10);
+    }
+    let radix = interpreter.to_integer_or_infinity(value)?;
+    if !(2.0..=36.0).contains(&radix) {
+        return Err(interpreter.range_error("toString() radix must be between 2 and 36"));
+    }
+    Ok(radix as u32)
+}
+
+// Number.prototype.toString ( [ radix ] )
+fn number_to_string_method(
+    interpreter: &mut Interpreter,
+    this: Value,
+    args: &[Value],
+) -> Result<Value> {
+    let value = this_number(interpreter, &this, "Number.prototype.toString")?;
+    Ok(Value::from(match radix(interpreter, &argument(args, 0))? {
+        10 => number_to_string(value),
+        radix => number_to_radix_string(value, radix),
+    }))
+}
+
+// Number.prototype.valueOf ( )
+fn number_value_of(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    this_number(interpreter, &this, "Number.prototype.valueOf").map(Value::Number)
+}
+
+// BigInt ( value ), which can't be called with `new`
+fn bigint(
+    interpreter: &mut Interpreter,
+    args: &[Value],
+    new_target: Option<&ObjectRef>,
+) -> Result<Value> {
+    if new_target.is_some() {
+        return Err(interpreter.type_error("BigInt is not a constructor"));
+    }
+    match interpreter.to_primitive(&argument(args, 0), Hint::Number)? {
+        Value::Number(number) => match number_to_bigint(number) {
+            Some(value) => Ok(Value::bigint(value)),
+            None => {
+                let message = format!(
+                    "The number {} cannot be converted to a BigInt because it is not an integer",
+                    number_to_string(number)
+                );
+                Err(interpreter.range_error(&message))
+            }
+        },
+        primitive => interpreter.to_bigint(&primitive).map(Value::BigInt),
+    }
+}
+
+impl Interpreter {
+    // ToBigInt, which only converts booleans and strings
+    pub fn to_bigint(&mut self, value: &Value) -> Result<std::rc::Rc<BigInt>> {
+        match self.to_primitive(value, Hint::Number)? {
+            Value::BigInt(value) => Ok(value),
+            Value::Boolean(value) => Ok(BigInt::from(value as u8).into()),
+            Value::String(text) => match string_to_bigint(&text) {
+                Some(value) => Ok(value.into()),
+                None => {
+                    let message = format!("Cannot convert {text} to a BigInt");
+                    Err(self.error(crate::ErrorType::SyntaxError, &message))
+                }
+            },
+            primitive => {
+                let text = match &primitive {
+                    Value::Symbol(symbol) => symbol.to_string(),
+                    Value::Number(number) => number_to_string(*number),
+                    _ => primitive.type_of().to_string(),
+                };
+                let message = format!("Cannot convert {text} to a BigInt");
+                Err(self.type_error(&message))
+            }
+        }
+    }
+}
+
+// The bits and the BigInt `asIntN` and `asUintN` take, and 2 ** bits
+fn bits_and_bigint(interpreter: &mut Interpreter, args: &[Value]) -> Result<(BigInt, BigInt)> {
+    let bits = interpreter.to_integer_or_infinity(&argument(args, 0))?;
+    if !(0.0..=9007199254740991.0).contains(&bits) {
+        return Err(interpreter.range_error("Invalid value: not (convertible to) a safe integer"));
+    }
+    let value = interpreter.to_bigint(&argument(args, 1))?;
+    if bits > u32::MAX as f64 {
+        return Err(interpreter.range_error("Maximum BigInt size exceeded"));
+    }
+    let modulus = BigInt::from(1) << bits as usize;
+    let remainder = ((&*value % &modulus) + &modulus) % &modulus;
+    Ok((remainder, modulus))
+}
+
+// BigInt.asIntN ( bits, bigint )
+fn as_int_n(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let (remainder, modulus) = bits_and_bigint(interpreter, args)?;
+    let value = if remainder.clone() * 2 >= modulus {
+        remainder - modulus
+    } else {
+        remainder
+    };
+    Ok(Value::bigint(value))
+}
+
+// BigInt.asUintN ( bits, bigint )
+fn as_uint_n(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let (remainder, _) = bits_and_bigint(interpreter, args)?;
+    Ok(Value::bigint(remainder))
+}
+
+fn this_bigint(
+    interpreter: &mut Interpreter,
+    this: &Value,
+    method: &str,
+) -> Result<std::rc::Rc<BigInt>> {
+    let is_bigint = |value: &Value| matches!(value, Value::BigInt(_));
+    match interpreter.this_primitive(this, is_bigint, method, "BigInt")? {
+        Value::BigInt(value) => Ok(value),
+        _ => unreachable!("the value is a BigInt"),
+    }
+}
+
+// BigInt.prototype.toLocaleString ( ), which ignores locales
+fn bigint_to_locale_string(
+    interpreter: &mut Interpreter,
+    this: Value,
+    _: &[Value],
+) -> Result<Value> {
+    let value = this_bigint(interpreter, &this, "BigInt.prototype.toLocaleString")?;
+    Ok(Value::from(value.to_string()))
+}
+
+// BigInt.prototype.toString ( [ radix ] )
+fn bigint_to_string(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let value = this_bigint(interpreter, &this, "BigInt.prototype.toString")?;
+    let radix = radix(interpreter, &argument(args, 0))?;
+    Ok(Value::from(value.to_str_radix(radix)))
+}
+
+// BigInt.prototype.valueOf ( )
+fn bigint_value_of(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    this_bigint(interpreter, &this, "BigInt.prototype.valueOf").map(Value::BigInt)
+}
@@ -0,0 +1,689 @@
+use super::{Result, argument};
+use crate::Interpreter;
+use crate::inspect::inspect;
+use crate::object::NativeFunction;
+use jsompiler_runtime::{
+    Object, ObjectKind, ObjectRef, Property, PropertyDescriptor, PropertyKey, PropertyValue, Value,
+};
+
+pub(super) fn install(interpreter: &mut Interpreter) {
+    let prototype = interpreter.intrinsics.object_prototype.clone();
+    let constructor = interpreter.native_constructor("Object", 1, object, &prototype);
+    let functions: [(&str, usize, NativeFunction); 22] = [
+        ("assign", 2, assign),
+        ("create", 2, create),
+        ("defineProperties", 2, define_properties),
+        ("defineProperty", 3, define_property),
+        ("entries", 1, entries),
+        ("freeze", 1, freeze),
+        ("fromEntries", 1, from_entries),
+        ("getOwnPropertyDescriptor", 2, get_own_property_descriptor),
+        ("getOwnPropertyDescriptors", 1, get_own_property_descriptors),
+        ("getOwnPropertyNames", 1, get_own_property_names),
+        ("getOwnPropertySymbols", 1, get_own_property_symbols),
+        ("getPrototypeOf", 1, get_prototype_of),
+        ("hasOwn", 2, has_own),
+        ("is", 2, is),
+        ("isExtensible", 1, is_extensible),
+        ("isFrozen", 1, is_frozen),
+        ("isSealed", 1, is_sealed),
+        ("keys", 1, keys),
+        ("preventExtensions", 1, prevent_extensions),
+        ("seal", 1, seal),
+        ("setPrototypeOf", 2, set_prototype_of),
+        ("values", 1, values),
+    ];
+    for (name, length, function) in functions {
+        interpreter.define_method(&constructor, name, length, function);
+    }
+
+    let methods: [(&str, usize, NativeFunction); 6] = [
+        ("hasOwnProperty", 1, has_own_property),
+        ("isPrototypeOf", 1, is_prototype_of),
+        ("propertyIsEnumerable", 1, property_is_enumerable),
+        ("toLocaleString", 0, to_locale_string),
+        ("toString", 0, to_string),
+        ("valueOf", 0, value_of),
+    ];
+    for (name, length, function) in methods {
+        interpreter.define_method(&prototype, name, length, function);
+    }
+    let get = interpreter.native_function("get __proto__", 0, get_proto);
+    let set = interpreter.native_function("set __proto__", 1, set_proto);
+    let mut proto = Property::accessor(Some(get), Some(set), false);
+    proto.configurable = true;
+    prototype.borrow_mut().define("__proto__", proto);
+    interpreter.define_global("Object", constructor);
+}
+
+// The object an `Object.*` function works on, which must be an object
+fn object_argument<'a>(
+    interpreter: &mut Interpreter,
+    function: &str,
+    args: &'a [Value],
+) -> Result<&'a ObjectRef> {
+    match args.first() {
+        Some(Value::Object(object)) => Ok(object),
+        _ => Err(interpreter.type_error(&format!("Object.{function} called on non-object"))),
+    }
+}
+
+// Object ( [ value ] )
+fn object(
+    interpreter: &mut Interpreter,
+    args: &[Value],
+    new_target: Option<&ObjectRef>,
+) -> Result<Value> {
+    let object_prototype = interpreter.intrinsics.object_prototype.clone();
+    // Subclasses get an object that inherits from their prototype
+    if let Some(new_target) = new_target {
+        let prototype =
+            interpreter.prototype_from_constructor(new_target, object_prototype.clone())?;
+        if !prototype.ptr_eq(&object_prototype) {
+            return Ok(Value::Object(ObjectRef::new(Object::new(
+                Some(prototype),
+                ObjectKind::Ordinary,
+            ))));
+        }
+    }
+    match argument(args, 0) {
+        value if value.is_nullish() => Ok(Value::Object(interpreter.new_object())),
+        value => interpreter.to_object(&value).map(Value::Object),
+    }
+}
+
+impl Interpreter {
+    // ToPropertyDescriptor: the fields an object passed to `Object.defineProperty` has
+    pub fn to_property_descriptor(&mut self, value: &Value) -> Result<PropertyDescriptor> {
+        let Value::Object(object) = value else {
+            let message = format!("Property description must be an object: {}", inspect(value));
+            return Err(self.type_error(&message));
+        };
+        let mut descriptor = PropertyDescriptor::default();
+        let field = |interpreter: &mut Interpreter, name: &str| -> Result<Option<Value>> {
+            let key = PropertyKey::from(name);
+            if !object.has_property(&key) {
+                return Ok(None);
+            }
+            interpreter.get(object, &key, value).map(Some)
+        };
+        descriptor.enumerable = field(self, "enumerable")?.map(|value| value.to_boolean());
+        descriptor.configurable = field(self, "configurable")?.map(|value| value.to_boolean());
+        descriptor.value = field(self, "value")?;
+        descriptor.writable = field(self, "writable")?.map(|value| value.to_boolean());
+        for (name, is_getter) in [("get", true), ("set", false)] {
+            let function = match field(self, name)? {
+                None => continue,
+                Some(Value::Undefined) => None,
+                Some(Value::Object(function)) if function.is_callable() => Some(function),
+                Some(value) => {
+                    let message = format!(
+                        "{} function: {}",
+                        name_of_accessor(is_getter),
+                        inspect(&value)
+                    );
+                    return Err(self.type_error(&message));
+                }
+            };
+            if is_getter {
+                descriptor.get = Some(function);
+            } else {
+                descriptor.set = Some(function);
+            }
+        }
+        if descriptor.is_accessor() && descriptor.is_data() {
+            return Err(self.type_error(
+                "Invalid property descriptor. Cannot both specify accessors and a value or writable attribute",
+            ));
+        }
+        Ok(descriptor)
+    }
+
+    // FromPropertyDescriptor, the object `Object.getOwnPropertyDescriptor` returns
+    pub fn from_property(&mut self, property: Property) -> Value {
+        let object = self.new_object();
+        let mut fields = Vec::new();
+        match property.value {
+            PropertyValue::Data { value, writable } => {
+                fields.push(("value", value));
+                fields.push(("writable", Value::Boolean(writable)));
+            }
+            PropertyValue::Accessor { get, set } => {
+                fields.push(("get", get.map_or(Value::Undefined, Value::Object)));
+                fields.push(("set", set.map_or(Value::Undefined, Value::Object)));
+            }
+        }
+        fields.push(("enumerable", Value::Boolean(property.enumerable)));
+        fields.push(("configurable", Value::Boolean(property.configurable)));
+        for (name, value) in fields {
+            object.borrow_mut().define(name, Property::data(value));
+        }
+        Value::Object(object)
+    }
+
+    // [[DefineOwnProperty]]. An array's `length` is converted to a valid length first.
+    pub(crate) fn define_own_property(
+        &mut self,
+        object: &ObjectRef,
+        key: PropertyKey,
+        mut descriptor: PropertyDescriptor,
+    ) -> Result<bool> {
+        if let Some(value) = descriptor.value.take() {
+            descriptor.value = Some(self.array_length_value(object, &key, value)?);
+        }
+        Ok(object.borrow_mut().define_own_property(key, descriptor))
+    }
+
+    // The value for `key` of `object`, which for the `length` of an array is the number it
+    // converts to, as long as that is a valid length
+    pub(crate) fn array_length_value(
+        &mut self,
+        object: &ObjectRef,
+        key: &PropertyKey,
+        value: Value,
+    ) -> Result<Value> {
+        if *key != "length" || object.borrow().array_length().is_none() {
+            return Ok(value);
+        }
+        let length = self.to_number(&value)?;
+        if length.fract() != 0.0 || !(0.0..=u32::MAX as f64).contains(&length) {
+            return Err(self.range_error("Invalid array length"));
+        }
+        Ok(Value::Number(length))
+    }
+
+    // DefinePropertyOrThrow
+    pub(crate) fn define_property_or_throw(
+        &mut self,
+        object: &ObjectRef,
+        key: PropertyKey,
+        descriptor: PropertyDescriptor,
+    ) -> Result<()> {
+        if !self.define_own_property(object, key.clone(), descriptor)? {
+            let message = format!("Cannot redefine property: {key}");
+            return Err(self.type_error(&message));
+        }
+        Ok(())
+    }
+
+    // ObjectDefineProperties, where all descriptors are read before any property is defined
+    pub(crate) fn define_properties(
+        &mut self,
+        object: &ObjectRef,
+        properties: &Value,
+    ) -> Result<()> {
+        let source = self.to_object(properties)?;
+        let mut descriptors = Vec::new();
+        for key in source.borrow().own_keys() {
+            let enumerable = source
+                .borrow()
+                .get_own(&key)
+                .is_some_and(|property| property.enumerable);
+            if enumerable {
+                descriptors.push(key);
+            }
+        }
+        let mut resolved = Vec::with_capacity(descriptors.len());
+        for key in descriptors {
+            let value = self.get(&source, &key, properties)?;
+            resolved.push((key, self.to_property_descriptor(&value)?));
+        }
+        for (key, descriptor) in resolved {
+            self.define_property_or_throw(object, key, descriptor)?;
+        }
+        Ok(())
+    }
+
+    // EnumerableOwnProperties: the keys, values or `[key, value]` entries of the own
+    // enumerable string properties of an object
+    pub(crate) fn enumerable_own_properties(
+        &mut self,
+        object: &ObjectRef,
+        keys: bool,
+        values: bool,
+    ) -> Result<Vec<Value>> {
+        let receiver = Value::Object(object.clone());
+        let own_keys = object.borrow().own_keys();
+        let mut properties = Vec::new();
+        for key in own_keys.into_iter().filter(|key| !key.is_symbol()) {
+            let enumerable = object
+                .borrow()
+                .get_own(&key)
+                .is_some_and(|property| property.enumerable);
+            if !enumerable {
+                continue;
+            }
+            if !values {
+                properties.push(key.to_value());
+                continue;
+            }
+            let value = self.get(object, &key, &receiver)?;
+            properties.push(if keys {
+                Value::Object(self.new_array(vec![key.to_value(), value]))
+            } else {
+                value
+            });
+        }
+        Ok(properties)
+    }
+
+    // SetIntegrityLevel: make every property non-configurable, and also read-only when
+    // freezing
+    fn set_integrity_level(&mut self, object: &ObjectRef, frozen: bool) -> Result<()> {
+        object.borrow_mut().extensible = false;
+        let keys = object.borrow().own_keys();
+        for key in keys {
+            let mut descriptor = PropertyDescriptor {
+                configurable: Some(false),
+                ..PropertyDescriptor::default()
+            };
+            let current = object.borrow().get_own(&key);
+            if frozen && current.is_some_and(|property| !property.is_accessor()) {
+                descriptor.writable = Some(false);
+            }
+            self.define_property_or_throw(object, key, descriptor)?;
+        }
+        Ok(())
+    }
+}
+
+// TestIntegrityLevel
+fn test_integrity_level(object: &ObjectRef, frozen: bool) -> bool {
+    let object = object.borrow();
+    if object.extensible {
+        return false;
+    }
+    object
+        .own_keys()
+        .iter()
+        .all(|key| match object.get_own(key) {
+            Some(property) => {
+                let writable = matches!(property.value, PropertyValue::Data { writable: true, .. });
+                // Frozen objects also have no writable data properties
+                !property.configurable && (!frozen || !writable)
+            }
+            None => true,
+        })
+}
+
+fn name_of_accessor(is_getter: bool) -> &'static str {
+    if is_getter {
+        "Getter must be a"
+    } else {
+        "Setter must be a"
+    }
+}
+
+// Object.assign ( target, ...sources )
+fn assign(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let target = interpreter.to_object(&argument(args, 0))?;
+    for source in args.iter().skip(1).filter(|source| !source.is_nullish()) {
+        let from = interpreter.to_object(source)?;
+        let from_value = Value::Object(from.clone());
+        let keys = from.borrow().own_keys();
+        for key in keys {
+            let enumerable = from
+                .borrow()
+                .get_own(&key)
+                .is_some_and(|property| property.enumerable);
+            if enumerable {
+                let value = interpreter.get(&from, &key, &from_value)?;
+                interpreter.put(&target, &key, value)?;
+            }
+        }
+    }
+    Ok(Value::Object(target))
+}
+
+// Object.create ( O, Properties )
+fn create(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let prototype = match argument(args, 0) {
+        Value::Object(prototype) => Some(prototype),
+        Value::Null => None,
+        value => {
+            let message = format!(
+                "Object prototype may only be an Object or null: {}",
+                inspect(&value)
+            );
+            return Err(interpreter.type_error(&message));
+        }
+    };
+    let object = ObjectRef::new(Object::new(prototype, ObjectKind::Ordinary));
+    let properties = argument(args, 1);
+    if !matches!(properties, Value::Undefined) {
+        interpreter.define_properties(&object, &properties)?;
+    }
+    Ok(Value::Object(object))
+}
+
+// Object.defineProperties ( O, Properties )
+fn define_properties(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let object = object_argument(interpreter, "defineProperties", args)?;
+    interpreter.define_properties(object, &argument(args, 1))?;
+    Ok(Value::Object(object.clone()))
+}
+
+// Object.defineProperty ( O, P, Attributes )
+fn define_property(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let object = object_argument(interpreter, "defineProperty", args)?;
+    let key = interpreter.to_property_key(&argument(args, 1))?;
+    let descriptor = interpreter.to_property_descriptor(&argument(args, 2))?;
+    interpreter.define_property_or_throw(object, key, descriptor)?;
+    Ok(Value::Object(object.clone()))
+}
+
+// Object.entries ( O )
+fn entries(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let object = interpreter.to_object(&argument(args, 0))?;
+    let entries = interpreter.enumerable_own_properties(&object, true, true)?;
+    Ok(Value::Object(interpreter.new_array(entries)))
+}
+
+// Object.freeze ( O )
+fn freeze(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let value = argument(args, 0);
+    if let Value::Object(object) = &value {
+        interpreter.set_integrity_level(object, true)?;
+    }
+    Ok(value)
+}
+
+// Object.fromEntries ( iterable )
+fn from_entries(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let iterable = argument(args, 0);
+    if iterable.is_nullish() {
+        let message = format!("{} is not iterable", inspect(&iterable));
+        return Err(interpreter.type_error(&message));
+    }
+    let object = interpreter.new_object();
+    let mut iterator = interpreter.get_iterator(&iterable)?;
+    while let Some(entry) = interpreter.iterator_step(&mut iterator)? {
+        let result = add_entry(interpreter, &object, &entry);
+        if result.is_err() {
+            return interpreter.iterator_close(&iterator, result.map(|_| Value::Undefined));
+        }
+    }
+    Ok(Value::Object(object))
+}
+
+fn add_entry(interpreter: &mut Interpreter, object: &ObjectRef, entry: &Value) -> Result<()> {
+    if !matches!(entry, Value::Object(_)) {
+        let message = format!("Iterator value {} is not an entry object", inspect(entry));
+        return Err(interpreter.type_error(&message));
+    }
+    let key = interpreter.get_property(entry, &PropertyKey::Index(0))?;
+    let value = interpreter.get_property(entry, &PropertyKey::Index(1))?;
+    let key = interpreter.to_property_key(&key)?;
+    interpreter.create_data_property(object, key, value)
+}
+
+// Object.getOwnPropertyDescriptor ( O, P )
+fn get_own_property_descriptor(
+    interpreter: &mut Interpreter,
+    _: Value,
+    args: &[Value],
+) -> Result<Value> {
+    let object = interpreter.to_object(&argument(args, 0))?;
+    let key = interpreter.to_property_key(&argument(args, 1))?;
+    let property = object.borrow().get_own(&key);
+    Ok(match property {
+        Some(property) => interpreter.from_property(property),
+        None => Value::Undefined,
+    })
+}
+
+// Object.getOwnPropertyDescriptors ( O )
+fn get_own_property_descriptors(
+    interpreter: &mut Interpreter,
+    _: Value,
+    args: &[Value],
+) -> Result<Value> {
+    let object = interpreter.to_object(&argument(args, 0))?;
+    let descriptors = interpreter.new_object();
+    let keys = object.borrow().own_keys();
+    for key in keys {
+        let property = object.borrow().get_own(&key);
+        if let Some(property) = property {
+            let descriptor = interpreter.from_property(property);
+            interpreter.create_data_property(&descriptors, key, descriptor)?;
+        }
+    }
+    Ok(Value::Object(descriptors))
+}
+
+// Object.getOwnPropertyNames ( O )
+fn get_own_property_names(
+    interpreter: &mut Interpreter,
+    _: Value,
+    args: &[Value],
+) -> Result<Value> {
+    let object = interpreter.to_object(&argument(args, 0))?;
+    let keys = object.borrow().own_keys();
+    let names = keys
+        .iter()
+        .filter(|key| !key.is_symbol())
+        .map(|key| key.to_value())
+        .collect();
+    Ok(Value::Object(interpreter.new_array(names)))
+}
+
+// Object.getOwnPropertySymbols ( O )
+fn get_own_property_symbols(
+    interpreter: &mut Interpreter,
+    _: Value,
+    args: &[Value],
+) -> Result<Value> {
+    let object = interpreter.to_object(&argument(args, 0))?;
+    let keys = object.borrow().own_keys();
+    let symbols = keys
+        .iter()
+        .filter(|key| key.is_symbol())
+        .map(|key| key.to_value())
+        .collect();
+    Ok(Value::Object(interpreter.new_array(symbols)))
+}
+
+// Object.getPrototypeOf ( O )
+fn get_prototype_of(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let object = interpreter.to_object(&argument(args, 0))?;
+    Ok(object.prototype().map_or(Value::Null, Value::Object))
+}
+
+// Object.hasOwn ( O, P )
+fn has_own(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let object = interpreter.to_object(&argument(args, 0))?;
+    let key = interpreter.to_property_key(&argument(args, 1))?;
+    Ok(Value::Boolean(object.borrow().has_own(&key)))
+}
+
+// Object.is ( value1, value2 )
+fn is(_: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    Ok(Value::Boolean(
+        argument(args, 0).same_value(&argument(args, 1)),
+    ))
+}
+
+// Object.isExtensible ( O )
+fn is_extensible(_: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    Ok(Value::Boolean(match args.first() {
+        Some(Value::Object(object)) => object.borrow().extensible,
+        _ => false,
+    }))
+}
+
+// Object.isFrozen ( O )
+fn is_frozen(_: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    Ok(Value::Boolean(match args.first() {
+        Some(Value::Object(object)) => test_integrity_level(object, true),
+        _ => true,
+    }))
+}
+
+// Object.isSealed ( O )
+fn is_sealed(_: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    Ok(Value::Boolean(match args.first() {
+        Some(Value::Object(object)) => test_integrity_level(object, false),
+        _ => true,
+    }))
+}
+
+// Object.keys ( O )
+fn keys(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let object = interpreter.to_object(&argument(args, 0))?;
+    let keys = interpreter.enumerable_own_properties(&object, true, false)?;
+    Ok(Value::Object(interpreter.new_array(keys)))
+}
+
+// Object.preventExtensions ( O )
+fn prevent_extensions(_: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let value = argument(args, 0);
+    if let Value::Object(object) = &value {
+        object.borrow_mut().extensible = false;
+    }
+    Ok(value)
+}
+
+// Object.seal ( O )
+fn seal(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let value = argument(args, 0);
+    if let Value::Object(object) = &value {
+        interpreter.set_integrity_level(object, false)?;
+    }
+    Ok(value)
+}
+
+// Object.setPrototypeOf ( O, proto )
+fn set_prototype_of(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let value = argument(args, 0);
+    if value.is_nullish() {
+        return Err(interpreter.type_error("Object.setPrototypeOf called on null or undefined"));
+    }
+    let prototype = match argument(args, 1) {
+        Value::Object(prototype) => Some(prototype),
+        Value::Null => None,
+        prototype => {
+            let message = format!(
+                "Object prototype may only be an Object or null: {}",
+                inspect(&prototype)
+            );
+            return Err(interpreter.type_error(&message));
+        }
+    };
+    let Value::Object(object) = &value else {
+        return Ok(value);
+    };
+    if !object.set_prototype_of(prototype) {
+        let message = format!(
+            "{} is not extensible or the prototype chain would be cyclic",
+            inspect(&value)
+        );
+        return Err(interpreter.type_error(&message));
+    }
+    Ok(value)
+}
+
+// Object.values ( O )
+fn values(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let object = interpreter.to_object(&argument(args, 0))?;
+    let values = interpreter.enumerable_own_properties(&object, false, true)?;
+    Ok(Value::Object(interpreter.new_array(values)))
+}
+
+// Object.prototype.hasOwnProperty ( V )
+fn has_own_property(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let key = interpreter.to_property_key(&argument(args, 0))?;
+    let object = interpreter.to_object(&this)?;
+    Ok(Value::Boolean(object.borrow().has_own(&key)))
+}
+
+// Object.prototype.isPrototypeOf ( V )
+fn is_prototype_of(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let Value::Object(value) = argument(args, 0) else {
+        return Ok(Value::Boolean(false));
+    };
+    let object = interpreter.to_object(&this)?;
+    let mut current = value.prototype();
+    while let Some(prototype) = current {
+        if prototype.ptr_eq(&object) {
+            return Ok(Value::Boolean(true));
+        }
+        current = prototype.prototype();
+    }
+    Ok(Value::Boolean(false))
+}
+
+// Object.prototype.propertyIsEnumerable ( V )
+fn property_is_enumerable(
+    interpreter: &mut Interpreter,
+    this: Value,
+    args: &[Value],
+) -> Result<Value> {
+    let key = interpreter.to_property_key(&argument(args, 0))?;
+    let object = interpreter.to_object(&this)?;
+    let property = object.borrow().get_own(&key);
+    Ok(Value::Boolean(
+        property.is_some_and(|property| property.enumerable),
+    ))
+}
+
+// Object.prototype.toLocaleString ( )
+fn to_locale_string(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    interpreter.invoke(&this, "toString", &[])
+}
+
+// Object.prototype.toString ( ): "[object Tag]", where a `Symbol.toStringTag` string
+// replaces the tag of the kind of object
+pub(super) fn to_string(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    let object = match &this {
+        Value::Undefined => return Ok(Value::string("[object Undefined]")),
+        Value::Null => return Ok(Value::string("[object Null]")),
+        value => interpreter.to_object(value)?,
+    };
+    let builtin_tag = match &object.borrow().kind {
+        ObjectKind::Array => "Array",
+        ObjectKind::Arguments(_) => "Arguments",
+        ObjectKind::Function(_) | ObjectKind::BoundFunction(_) => "Function",
+        ObjectKind::Error => "Error",
+        ObjectKind::Primitive(Value::Boolean(_)) => "Boolean",
+        ObjectKind::Primitive(Value::Number(_)) => "Number",
+        ObjectKind::Primitive(Value::String(_)) => "String",
+        _ => "Object",
+    };
+    let key = PropertyKey::Symbol(interpreter.intrinsics.symbols.to_string_tag.clone());
+    let tag = match interpreter.get(&object, &key, &Value::Object(object.clone()))? {
+        Value::String(tag) => tag.to_string(),
+        _ => builtin_tag.to_string(),
+    };
+    Ok(Value::from(format!("[object {tag}]")))
+}
+
+// Object.prototype.valueOf ( )
+fn value_of(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    interpreter.to_object(&this).map(Value::Object)
+}
+
+// get Object.prototype.__proto__
+fn get_proto(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    let object = interpreter.to_object(&this)?;
+    Ok(object.prototype().map_or(Value::Null, Value::Object))
+}
+
+// set Object.prototype.__proto__, which ignores values that aren't objects or null
+fn set_proto(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    if this.is_nullish() {
+        let message = "Object.prototype.__proto__ called on null or undefined";
+        return Err(interpreter.type_error(message));
+    }
+    let prototype = match argument(args, 0) {
+        Value::Object(prototype) => Some(prototype),
+        Value::Null => None,
+        _ => return Ok(Value::Undefined),
+    };
+    let Value::Object(object) = &this else {
+        return Ok(Value::Undefined);
+    };
+    if !object.set_prototype_of(prototype) {
+        return Err(interpreter.type_error("Cyclic __proto__ value"));
+    }
+    Ok(Value::Undefined)
+}
@@ -0,0 +1,173 @@
+use super::{Result, argument};
+use crate::Interpreter;
+use crate::inspect::inspect;
+use crate::object::NativeFunction;
+use jsompiler_runtime::{ObjectRef, Value};
+
+pub(super) fn install(interpreter: &mut Interpreter) {
+    let reflect = interpreter.new_object();
+    let functions: [(&str, usize, NativeFunction); 13] = [
+        ("apply", 3, apply),
+        ("construct", 2, construct),
+        ("defineProperty", 3, define_property),
+        ("deleteProperty", 2, delete_property),
+        ("get", 2, get),
+        ("getOwnPropertyDescriptor", 2, get_own_property_descriptor),
+        ("getPrototypeOf", 1, get_prototype_of),
+        ("has", 2, has),
+        ("isExtensible", 1, is_extensible),
+        ("ownKeys", 1, own_keys),
+        ("preventExtensions", 1, prevent_extensions),
+        ("set", 3, set),
+        ("setPrototypeOf", 2, set_prototype_of),
+    ];
+    for (name, length, function) in functions {
+        interpreter.define_method(&reflect, name, length, function);
+    }
+    interpreter.define_to_string_tag(&reflect, "Reflect");
+    interpreter.define_global("Reflect", reflect);
+}
+
+// The object a `Reflect.*` function works on, which unlike for `Object.*` is never converted
+fn target<'a>(
+    interpreter: &mut Interpreter,
+    function: &str,
+    args: &'a [Value],
+) -> Result<&'a ObjectRef> {
+    match args.first() {
+        Some(Value::Object(object)) => Ok(object),
+        _ => Err(interpreter.type_error(&format!("Reflect.{function} called on non-object"))),
+    }
+}
+
+// Reflect.apply ( target, thisArgument, argumentsList )
+fn apply(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let target = argument(args, 0);
+    if target.as_function().is_none() {
+        let message = format!("{} is not a function", inspect(&target));
+        return Err(interpreter.type_error(&message));
+    }
+    let list = interpreter.list_from_array_like(&argument(args, 2))?;
+    interpreter.call(&target, argument(args, 1), &list)
+}
+
+// Reflect.construct ( target, argumentsList [ , newTarget ] )
+fn construct(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let target = argument(args, 0);
+    let new_target = args.get(2).cloned().unwrap_or_else(|| target.clone());
+    for value in [&target, &new_target] {
+        let constructor = value
+            .as_object()
+            .is_some_and(|object| interpreter.is_constructor(object));
+        if !constructor {
+            let message = format!("{} is not a constructor", inspect(value));
+            return Err(interpreter.type_error(&message));
+        }
+    }
+    let list = interpreter.list_from_array_like(&argument(args, 1))?;
+    interpreter.construct(&target, &list, new_target.as_object())
+}
+
+// Reflect.defineProperty ( target, propertyKey, attributes ): whether it was defined
+fn define_property(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let object = target(interpreter, "defineProperty", args)?;
+    let key = interpreter.to_property_key(&argument(args, 1))?;
+    let descriptor = interpreter.to_property_descriptor(&argument(args, 2))?;
+    interpreter
+        .define_own_property(object, key, descriptor)
+        .map(Value::Boolean)
+}
+
+// Reflect.deleteProperty ( target, propertyKey )
+fn delete_property(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let object = target(interpreter, "deleteProperty", args)?;
+    let key = interpreter.to_property_key(&argument(args, 1))?;
+    Ok(Value::Boolean(object.borrow_mut().delete(&key)))
+}
+
+// Reflect.get ( target, propertyKey [ , receiver ] )
+fn get(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let object = target(interpreter, "get", args)?;
+    let key = interpreter.to_property_key(&argument(args, 1))?;
+    let receiver = args.get(2).cloned().unwrap_or_else(|| argument(args, 0));
+    interpreter.get(object, &key, &receiver)
+}
+
+// Reflect.getOwnPropertyDescriptor ( target, propertyKey )
+fn get_own_property_descriptor(
+    interpreter: &mut Interpreter,
+    _: Value,
+    args: &[Value],
+) -> Result<Value> {
+    let object = target(interpreter, "getOwnPropertyDescriptor", args)?;
+    let key = interpreter.to_property_key(&argument(args, 1))?;
+    let property = object.borrow().get_own(&key);
+    Ok(property.map_or(Value::Undefined, |property| {
+        interpreter.from_property(property)
+    }))
+}
+
+// Reflect.getPrototypeOf ( target )
+fn get_prototype_of(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let object = target(interpreter, "getPrototypeOf", args)?;
+    Ok(object.prototype().map_or(Value::Null, Value::Object))
+}
+
+// Reflect.has ( target, propertyKey )
+fn has(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let object = target(interpreter, "has", args)?;
+    let key = interpreter.to_property_key(&argument(args, 1))?;
+    Ok(Value::Boolean(object.has_property(&key)))
+}
+
+// Reflect.isExtensible ( target )
+fn is_extensible(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let object = target(interpreter, "isExtensible", args)?;
+    Ok(Value::Boolean(object.borrow().extensible))
+}
+
+// Reflect.ownKeys ( target ): the string keys and then the symbols
+fn own_keys(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let object = target(interpreter, "ownKeys", args)?;
+    let keys = object
+        .borrow()
+        .own_keys()
+        .iter()
+        .map(|key| key.to_value())
+        .collect();
+    Ok(Value::Object(interpreter.new_array(keys)))
+}
+
+// Reflect.preventExtensions ( target )
+fn prevent_extensions(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let object = target(interpreter, "preventExtensions", args)?;
+    object.borrow_mut().extensible = false;
+    Ok(Value::Boolean(true))
+}
+
+// Reflect.set ( target, propertyKey, V [ , receiver ] ): whether it was set, even in strict
+// mode code
+fn set(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let object = target(interpreter, "set", args)?;
+    let key = interpreter.to_property_key(&argument(args, 1))?;
+    let receiver = args.get(3).cloned().unwrap_or_else(|| argument(args, 0));
+    let failure = interpreter.try_set(object, &key, argument(args, 2), &receiver)?;
+    Ok(Value::Boolean(failure.is_none()))
+}
+
+// Reflect.setPrototypeOf ( target, proto )
+fn set_prototype_of(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let object = target(interpreter, "setPrototypeOf", args)?;
+    let prototype = match argument(args, 1) {
+        Value::Object(prototype) => Some(prototype),
+        Value::Null => None,
+        prototype => {
+            let message = format!(
+                "Object prototype may only be an Object or null: {}",
+                inspect(&prototype)
+            );
+            return Err(interpreter.type_error(&message));
+        }
+    };
+    Ok(Value::Boolean(object.set_prototype_of(prototype)))
+}
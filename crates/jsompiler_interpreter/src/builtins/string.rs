@@ -0,0 +1,660 @@
+use super::{Result, argument, index_key};
+use crate::Interpreter;
+use crate::number::{is_whitespace, number_to_string, to_uint32};
+use crate::object::NativeFunction;
+use jsompiler_runtime::{IterationKind, ListIterator, ObjectKind, ObjectRef, PropertyKey, Value};
+use std::rc::Rc;
+
+// The longest string that can be built, which is V8's limit
+const MAX_STRING_LENGTH: usize = (1 << 29) - 24;
+
+pub(super) fn install(interpreter: &mut Interpreter) {
+    let prototype = interpreter.intrinsics.string_prototype.clone();
+    let constructor = interpreter.native_constructor("String", 1, string, &prototype);
+    let statics: [(&str, usize, NativeFunction); 3] = [
+        ("fromCharCode", 1, from_char_code),
+        ("fromCodePoint", 1, from_code_point),
+        ("raw", 1, raw),
+    ];
+    for (name, length, function) in statics {
+        interpreter.define_method(&constructor, name, length, function);
+    }
+    let methods: [(&str, usize, NativeFunction); 30] = [
+        ("at", 1, at),
+        ("charAt", 1, char_at),
+        ("charCodeAt", 1, char_code_at),
+        ("codePointAt", 1, code_point_at),
+        ("concat", 1, concat),
+        ("endsWith", 1, ends_with),
+        ("includes", 1, includes),
+        ("indexOf", 1, index_of),
+        ("isWellFormed", 0, is_well_formed),
+        ("lastIndexOf", 1, last_index_of),
+        ("localeCompare", 1, locale_compare),
+        ("padEnd", 1, pad_end),
+        ("padStart", 1, pad_start),
+        ("repeat", 1, repeat),
+        ("replace", 2, replace),
+        ("replaceAll", 2, replace_all),
+        ("slice", 2, slice),
+        ("split", 2, split),
+        ("startsWith", 1, starts_with),
+        ("substring", 2, substring),
+        ("toLocaleLowerCase", 0, to_lower_case),
+        ("toLocaleUpperCase", 0, to_upper_case),
+        ("toLowerCase", 0, to_lower_case),
+        ("toString", 0, to_string),
+        ("toUpperCase", 0, to_upper_case),
+        ("toWellFormed", 0, to_well_formed),
+        ("trim", 0, trim),
+        ("trimEnd", 0, trim_end),
+        ("trimStart", 0, trim_start),
+        ("valueOf", 0, value_of),
+    ];
+    for (name, length, function) in methods {
+        interpreter.define_method(&prototype, name, length, function);
+    }
+    let iterator = interpreter.intrinsics.symbols.iterator.clone();
+    interpreter.define_method(&prototype, iterator, 0, string_iterator);
+    interpreter.define_global("String", constructor);
+}
+
+// The UTF-16 code units of a string, which indices count
+fn units(text: &str) -> Vec<u16> {
+    text.encode_utf16().collect()
+}
+
+fn from_units(units: &[u16]) -> Value {
+    Value::from(String::from_utf16_lossy(units))
+}
+
+// StringIndexOf: where `search` first appears at or after `from`, if it does
+fn find(text: &[u16], search: &[u16], from: usize) -> Option<usize> {
+    if search.is_empty() {
+        return (from <= text.len()).then_some(from);
+    }
+    if search.len() > text.len() {
+        return None;
+    }
+    (from..=text.len() - search.len()).find(|&index| text[index..].starts_with(search))
+}
+
+// A position argument clamped to the string, like `includes` and `startsWith` take
+fn clamped_position(interpreter: &mut Interpreter, value: &Value, length: usize) -> Result<usize> {
+    let position = interpreter.to_integer_or_infinity(value)?;
+    Ok(position.clamp(0.0, length as f64) as usize)
+}
+
+impl Interpreter {
+    // RequireObjectCoercible(this) and ToString, which the String methods start with
+    fn this_string(&mut self, this: &Value, method: &str) -> Result<Rc<str>> {
+        if this.is_nullish() {
+            let message = format!("String.prototype.{method} called on null or undefined");
+            return Err(self.type_error(&message));
+        }
+        self.to_string(this)
+    }
+
+    // IsRegExp: whether a value asks to be treated as a regular expression with a
+    // `Symbol.match` property. There are no RegExp objects otherwise.
+    fn is_regexp(&mut self, value: &Value) -> Result<bool> {
+        let Value::Object(object) = value else {
+            return Ok(false);
+        };
+        let key = PropertyKey::Symbol(self.intrinsics.symbols.match_.clone());
+        let matcher = self.get(object, &key, value)?;
+        Ok(!matches!(matcher, Value::Undefined) && matcher.to_boolean())
+    }
+
+    // The search string of `includes`, `startsWith` and `endsWith`, which can't be a regular
+    // expression
+    fn search_string(&mut self, value: &Value, method: &str) -> Result<Vec<u16>> {
+        if self.is_regexp(value)? {
+            let message = format!(
+                "First argument to String.prototype.{method} must not be a regular expression"
+            );
+            return Err(self.type_error(&message));
+        }
+        Ok(units(&self.to_string(value)?))
+    }
+
+    // Call the `Symbol.replace` or `Symbol.split` method of the first argument, if it has one,
+    // which is how regular expressions take over these methods
+    fn delegate(
+        &mut self,
+        symbol: PropertyKey,
+        args: &[Value],
+        this: &Value,
+    ) -> Result<Option<Value>> {
+        let target = argument(args, 0);
+        if target.is_nullish() {
+            return Ok(None);
+        }
+        let Some(method) = self.get_method(&target, &symbol)? else {
+            return Ok(None);
+        };
+        let args = [this.clone(), argument(args, 1)];
+        self.call(&method, target, &args).map(Some)
+    }
+}
+
+// String ( value ): symbols become their description when `String` is called as a function
+fn string(
+    interpreter: &mut Interpreter,
+    args: &[Value],
+    new_target: Option<&ObjectRef>,
+) -> Result<Value> {
+    let text = match args.first() {
+        None => Rc::from(""),
+        Some(Value::Symbol(symbol)) if new_target.is_none() => Rc::from(symbol.to_string()),
+        Some(value) => interpreter.to_string(value)?,
+    };
+    let Some(new_target) = new_target else {
+        return Ok(Value::String(text));
+    };
+    let default = interpreter.intrinsics.string_prototype.clone();
+    let prototype = interpreter.prototype_from_constructor(new_target, default)?;
+    Ok(Value::Object(
+        interpreter.primitive_object(Value::String(text), prototype),
+    ))
+}
+
+// String.fromCharCode ( ...codeUnits )
+fn from_char_code(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let mut units = Vec::with_capacity(args.len());
+    for arg in args {
+        units.push(to_uint32(interpreter.to_number(arg)?) as u16);
+    }
+    Ok(from_units(&units))
+}
+
+// String.fromCodePoint ( ...codePoints )
+fn from_code_point(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let mut units = Vec::with_capacity(args.len());
+    for arg in args {
+        let code_point = interpreter.to_number(arg)?;
+        if code_point.trunc() != code_point || !(0.0..=1114111.0).contains(&code_point) {
+            let message = format!("Invalid code point {}", number_to_string(code_point));
+            return Err(interpreter.range_error(&message));
+        }
+        let mut buffer = [0; 2];
+        match char::from_u32(code_point as u32) {
+            Some(c) => units.extend_from_slice(c.encode_utf16(&mut buffer)),
+            // A lone surrogate
+            None => units.push(code_point as u16),
+        }
+    }
+    Ok(from_units(&units))
+}
+
+// String.raw ( template, ...substitutions ): the raw strings of a template, with the
+// substitutions between them
+fn raw(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let cooked = interpreter.to_object(&argument(args, 0))?;
+    let raw = interpreter.get(&cooked, &"raw".into(), &Value::Object(cooked.clone()))?;
+    let raw = interpreter.to_object(&raw)?;
+    let length = interpreter.length_of_array_like(&raw)?;
+    let mut result = String::new();
+    for index in 0..length {
+        let segment = interpreter.get(&raw, &index_key(index), &Value::Object(raw.clone()))?;
+        result.push_str(&interpreter.to_string(&segment)?);
+        if index + 1 == length {
+            break;
+        }
+        if let Some(substitution) = args.get(index as usize + 1) {
+            result.push_str(&interpreter.to_string(substitution)?);
+        }
+    }
+    Ok(Value::from(result))
+}
+
+// String.prototype.at ( index )
+fn at(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let text = units(&interpreter.this_string(&this, "at")?);
+    let relative = interpreter.to_integer_or_infinity(&argument(args, 0))?;
+    let index = if relative >= 0.0 {
+        relative
+    } else {
+        text.len() as f64 + relative
+    };
+    if index < 0.0 || index >= text.len() as f64 {
+        return Ok(Value::Undefined);
+    }
+    Ok(from_units(&[text[index as usize]]))
+}
+
+// String.prototype.charAt ( pos )
+fn char_at(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let text = units(&interpreter.this_string(&this, "charAt")?);
+    let position = interpreter.to_integer_or_infinity(&argument(args, 0))?;
+    if position < 0.0 || position >= text.len() as f64 {
+        return Ok(Value::string(""));
+    }
+    Ok(from_units(&[text[position as usize]]))
+}
+
+// String.prototype.charCodeAt ( pos )
+fn char_code_at(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let text = units(&interpreter.this_string(&this, "charCodeAt")?);
+    let position = interpreter.to_integer_or_infinity(&argument(args, 0))?;
+    if position < 0.0 || position >= text.len() as f64 {
+        return Ok(Value::Number(f64::NAN));
+    }
+    Ok(Value::Number(text[position as usize] as f64))
+}
+
+// String.prototype.codePointAt ( pos )
+fn code_point_at(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let text = units(&interpreter.this_string(&this, "codePointAt")?);
+    let position = interpreter.to_integer_or_infinity(&argument(args, 0))?;
+    if position < 0.0 || position >= text.len() as f64 {
+        return Ok(Value::Undefined);
+    }
+    let position = position as usize;
+    let code_point = char::decode_utf16(text[position..].iter().copied())
+        .next()
+        .and_then(|c| c.ok())
+        .map_or(text[position] as u32, u32::from);
+    Ok(Value::Number(code_point as f64))
+}
+
+// String.prototype.concat ( ...args )
+fn concat(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let mut result = interpreter.this_string(&this, "concat")?.to_string();
+    for arg in args {
+        result.push_str(&interpreter.to_string(arg)?);
+    }
+    Ok(Value::from(result))
+}
+
+// String.prototype.endsWith ( searchString [ , endPosition ] )
+fn ends_with(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let text = units(&interpreter.this_string(&this, "endsWith")?);
+    let search = interpreter.search_string(&argument(args, 0), "endsWith")?;
+    let end = match argument(args, 1) {
+        Value::Undefined => text.len(),
+        end => clamped_position(interpreter, &end, text.len())?,
+    };
+    Ok(Value::Boolean(text[..end].ends_with(&search)))
+}
+
+// String.prototype.includes ( searchString [ , position ] )
+fn includes(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let text = units(&interpreter.this_string(&this, "includes")?);
+    let search = interpreter.search_string(&argument(args, 0), "includes")?;
+    let start = clamped_position(interpreter, &argument(args, 1), text.len())?;
+    Ok(Value::Boolean(find(&text, &search, start).is_some()))
+}
+
+// String.prototype.indexOf ( searchString [ , position ] )
+fn index_of(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let text = units(&interpreter.this_string(&this, "indexOf")?);
+    let search = units(&interpreter.to_string(&argument(args, 0))?);
+    let start = clamped_position(interpreter, &argument(args, 1), text.len())?;
+    let index = find(&text, &search, start).map_or(-1.0, |index| index as f64);
+    Ok(Value::Number(index))
+}
+
+// String.prototype.lastIndexOf ( searchString [ , position ] )
+fn last_index_of(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let text = units(&interpreter.this_string(&this, "lastIndexOf")?);
+    let search = units(&interpreter.to_string(&argument(args, 0))?);
+    let position = interpreter.to_number(&argument(args, 1))?;
+    let start = if position.is_nan() {
+        text.len()
+    } else {
+        clamped_position(interpreter, &Value::Number(position), text.len())?
+    };
+    if search.len() > text.len() {
+        return Ok(Value::Number(-1.0));
+    }
+    let index = (0..=start.min(text.len() - search.len()))
+        .rev()
+        .find(|&index| text[index..].starts_with(&search))
+        .map_or(-1.0, |index| index as f64);
+    Ok(Value::Number(index))
+}
+
+// String.prototype.localeCompare ( that ), which compares code points rather than following
+// a locale
+fn locale_compare(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let text = interpreter.this_string(&this, "localeCompare")?;
+    let that = interpreter.to_string(&argument(args, 0))?;
+    Ok(Value::Number(text.cmp(&that) as i8 as f64))
+}
+
+// StringPad: fill a string up to `maxLength` with repeats of `fillString`
+fn pad(
+    interpreter: &mut Interpreter,
+    this: Value,
+    args: &[Value],
+    at_start: bool,
+) -> Result<Value> {
+    let method = if at_start { "padStart" } else { "padEnd" };
+    let text = interpreter.this_string(&this, method)?;
+    let max_length = interpreter.to_length(&argument(args, 0))? as usize;
+    let length = text.encode_utf16().count();
+    if max_length <= length {
+        return Ok(Value::String(text));
+    }
+    let fill = match argument(args, 1) {
+        Value::Undefined => vec![b' ' as u16],
+        fill => units(&interpreter.to_string(&fill)?),
+    };
+    if fill.is_empty() {
+        return Ok(Value::String(text));
+    }
+    if max_length > MAX_STRING_LENGTH {
+        return Err(interpreter.range_error("Invalid string length"));
+    }
+    let filler: Vec<u16> = fill
+        .iter()
+        .copied()
+        .cycle()
+        .take(max_length - length)
+        .collect();
+    let text = units(&text);
+    let padded = if at_start {
+        [filler, text].concat()
+    } else {
+        [text, filler].concat()
+    };
+    Ok(from_units(&padded))
+}
+
+// String.prototype.padEnd ( maxLength [ , fillString ] )
+fn pad_end(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    pad(interpreter, this, args, false)
+}
+
+// String.prototype.padStart ( maxLength [ , fillString ] )
+fn pad_start(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    pad(interpreter, this, args, true)
+}
+
+// String.prototype.repeat ( count )
+fn repeat(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let text = interpreter.this_string(&this, "repeat")?;
+    let count = interpreter.to_integer_or_infinity(&argument(args, 0))?;
+    if count < 0.0 || count.is_infinite() {
+        let message = format!("Invalid count value: {}", number_to_string(count));
+        return Err(interpreter.range_error(&message));
+    }
+    if count == 0.0 || text.is_empty() {
+        return Ok(Value::string(""));
+    }
+    if text.encode_utf16().count() as f64 * count > MAX_STRING_LENGTH as f64 {
+        return Err(interpreter.range_error("Invalid string length"));
+    }
+    Ok(Value::from(text.repeat(count as usize)))
+}
+
+// GetSubstitution without captures: `$$`, `$&`, `` $` `` and `$'` in a replacement template
+fn substitution(matched: &[u16], text: &[u16], position: usize, template: &[u16]) -> Vec<u16> {
+    let mut result = Vec::with_capacity(template.len());
+    let mut index = 0;
+    while index < template.len() {
+        let next = template.get(index + 1).copied();
+        if template[index] == b'$' as u16
+            && let Some(next) = next
+        {
+            let replacement = match u8::try_from(next).unwrap_or(0) {
+                b'$' => Some(&[b'$' as u16][..]),
+                b'&' => Some(matched),
+                b'`' => Some(&text[..position]),
+                b'\'' => Some(&text[(position + matched.len()).min(text.len())..]),
+                _ => None,
+            };
+            if let Some(replacement) = replacement {
+                result.extend_from_slice(replacement);
+                index += 2;
+                continue;
+            }
+        }
+        result.push(template[index]);
+        index += 1;
+    }
+    result
+}
+
+// What `replace` and `replaceAll` share once they know where the matches are
+fn replace_matches(
+    interpreter: &mut Interpreter,
+    text: &[u16],
+    search: &[u16],
+    positions: &[usize],
+    replace_value: &Value,
+) -> Result<Value> {
+    let template = match replace_value.as_function() {
+        Some(_) => None,
+        None => Some(units(&interpreter.to_string(replace_value)?)),
+    };
+    let mut result = Vec::with_capacity(text.len());
+    let mut end_of_last_match = 0;
+    for &position in positions {
+        result.extend_from_slice(&text[end_of_last_match..position]);
+        let replacement = match &template {
+            Some(template) => substitution(search, text, position, template),
+            None => {
+                let args = [
+                    from_units(search),
+                    Value::Number(position as f64),
+                    from_units(text),
+                ];
+                let replacement = interpreter.call(replace_value, Value::Undefined, &args)?;
+                units(&interpreter.to_string(&replacement)?)
+            }
+        };
+        result.extend_from_slice(&replacement);
+        end_of_last_match = position + search.len();
+    }
+    result.extend_from_slice(&text[end_of_last_match..]);
+    Ok(from_units(&result))
+}
+
+// String.prototype.replace ( searchValue, replaceValue )
+fn replace(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    if this.is_nullish() {
+        return Err(interpreter.type_error("String.prototype.replace called on null or undefined"));
+    }
+    let symbol = PropertyKey::Symbol(interpreter.intrinsics.symbols.replace.clone());
+    if let Some(result) = interpreter.delegate(symbol, args, &this)? {
+        return Ok(result);
+    }
+    let text = units(&interpreter.to_string(&this)?);
+    let search = units(&interpreter.to_string(&argument(args, 0))?);
+    let replace_value = argument(args, 1);
+    // The replacement is converted even if nothing matches
+    let replace_value = match replace_value.as_function() {
+        Some(_) => replace_value,
+        None => Value::String(interpreter.to_string(&replace_value)?),
+    };
+    let positions: Vec<usize> = find(&text, &search, 0).into_iter().collect();
+    replace_matches(interpreter, &text, &search, &positions, &replace_value)
+}
+
+// String.prototype.replaceAll ( searchValue, replaceValue )
+fn replace_all(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    if this.is_nullish() {
+        return Err(
+            interpreter.type_error("String.prototype.replaceAll called on null or undefined")
+        );
+    }
+    let search_value = argument(args, 0);
+    if !search_value.is_nullish() && interpreter.is_regexp(&search_value)? {
+        let flags = interpreter.get_property(&search_value, &"flags".into())?;
+        if flags.is_nullish() {
+            return Err(interpreter.type_error(
+                "String.prototype.replaceAll called with a non-global RegExp argument",
+            ));
+        }
+        if !interpreter.to_string(&flags)?.contains('g') {
+            return Err(interpreter.type_error("replaceAll must be called with a global RegExp"));
+        }
+    }
+    let symbol = PropertyKey::Symbol(interpreter.intrinsics.symbols.replace.clone());
+    if let Some(result) = interpreter.delegate(symbol, args, &this)? {
+        return Ok(result);
+    }
+    let text = units(&interpreter.to_string(&this)?);
+    let search = units(&interpreter.to_string(&search_value)?);
+    let replace_value = argument(args, 1);
+    let replace_value = match replace_value.as_function() {
+        Some(_) => replace_value,
+        None => Value::String(interpreter.to_string(&replace_value)?),
+    };
+    let advance_by = search.len().max(1);
+    let mut positions = Vec::new();
+    let mut position = find(&text, &search, 0);
+    while let Some(found) = position {
+        positions.push(found);
+        position = find(&text, &search, found + advance_by);
+    }
+    replace_matches(interpreter, &text, &search, &positions, &replace_value)
+}
+
+// String.prototype.slice ( start, end )
+fn slice(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let text = units(&interpreter.this_string(&this, "slice")?);
+    let length = text.len() as u64;
+    let from = interpreter.relative_index(&argument(args, 0), length)? as usize;
+    let to = match argument(args, 1) {
+        Value::Undefined => text.len(),
+        end => interpreter.relative_index(&end, length)? as usize,
+    };
+    if from >= to {
+        return Ok(Value::string(""));
+    }
+    Ok(from_units(&text[from..to]))
+}
+
+// String.prototype.split ( separator, limit )
+fn split(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    if this.is_nullish() {
+        return Err(interpreter.type_error("String.prototype.split called on null or undefined"));
+    }
+    let symbol = PropertyKey::Symbol(interpreter.intrinsics.symbols.split.clone());
+    if let Some(result) = interpreter.delegate(symbol, args, &this)? {
+        return Ok(result);
+    }
+    let text = units(&interpreter.to_string(&this)?);
+    let limit = match argument(args, 1) {
+        Value::Undefined => u32::MAX,
+        limit => to_uint32(interpreter.to_number(&limit)?),
+    } as usize;
+    let separator = argument(args, 0);
+    let separator_units = units(&interpreter.to_string(&separator)?);
+    let parts = if limit == 0 {
+        Vec::new()
+    } else if matches!(separator, Value::Undefined) {
+        vec![from_units(&text)]
+    } else if separator_units.is_empty() {
+        text.iter()
+            .take(limit)
+            .map(|unit| from_units(&[*unit]))
+            .collect()
+    } else if text.is_empty() {
+        vec![from_units(&text)]
+    } else {
+        let mut parts = Vec::new();
+        let mut start = 0;
+        let mut next = find(&text, &separator_units, 0);
+        while let Some(found) = next {
+            parts.push(from_units(&text[start..found]));
+            if parts.len() == limit {
+                return Ok(Value::Object(interpreter.new_array(parts)));
+            }
+            start = found + separator_units.len();
+            next = find(&text, &separator_units, start);
+        }
+        parts.push(from_units(&text[start..]));
+        parts
+    };
+    Ok(Value::Object(interpreter.new_array(parts)))
+}
+
+// String.prototype.startsWith ( searchString [ , position ] )
+fn starts_with(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let text = units(&interpreter.this_string(&this, "startsWith")?);
+    let search = interpreter.search_string(&argument(args, 0), "startsWith")?;
+    let start = clamped_position(interpreter, &argument(args, 1), text.len())?;
+    Ok(Value::Boolean(text[start..].starts_with(&search)))
+}
+
+// String.prototype.substring ( start, end ), which swaps the ends if they are the wrong way
+// around
+fn substring(interpreter: &mut Interpreter, this: Value, args: &[Value]) -> Result<Value> {
+    let text = units(&interpreter.this_string(&this, "substring")?);
+    let start = clamped_position(interpreter, &argument(args, 0), text.len())?;
+    let end = match argument(args, 1) {
+        Value::Undefined => text.len(),
+        end => clamped_position(interpreter, &end, text.len())?,
+    };
+    Ok(from_units(&text[start.min(end)..start.max(end)]))
+}
+
+// String.prototype.toLowerCase ( ), which is also `toLocaleLowerCase`
+fn to_lower_case(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    let text = interpreter.this_string(&this, "toLowerCase")?;
+    Ok(Value::from(text.to_lowercase()))
+}
+
+// String.prototype.toUpperCase ( ), which is also `toLocaleUpperCase`
+fn to_upper_case(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    let text = interpreter.this_string(&this, "toUpperCase")?;
+    Ok(Value::from(text.to_uppercase()))
+}
+
+fn this_string_value(interpreter: &mut Interpreter, this: &Value, method: &str) -> Result<Value> {
+    let is_string = |value: &Value| matches!(value, Value::String(_));
+    interpreter.this_primitive(this, is_string, method, "String")
+}
+
+// String.prototype.toString ( )
+fn to_string(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    this_string_value(interpreter, &this, "String.prototype.toString")
+}
+
+// String.prototype.trim ( )
+fn trim(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    let text = interpreter.this_string(&this, "trim")?;
+    Ok(Value::string(text.trim_matches(is_whitespace)))
+}
+
+// String.prototype.trimEnd ( )
+fn trim_end(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    let text = interpreter.this_string(&this, "trimEnd")?;
+    Ok(Value::string(text.trim_end_matches(is_whitespace)))
+}
+
+// String.prototype.trimStart ( )
+fn trim_start(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    let text = interpreter.this_string(&this, "trimStart")?;
+    Ok(Value::string(text.trim_start_matches(is_whitespace)))
+}
+
+// String.prototype.isWellFormed ( ). Strings are kept as UTF-8, so they never have lone
+// surrogates.
+fn is_well_formed(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    interpreter.this_string(&this, "isWellFormed")?;
+    Ok(Value::Boolean(true))
+}
+
+// String.prototype.toWellFormed ( )
+fn to_well_formed(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    interpreter
+        .this_string(&this, "toWellFormed")
+        .map(Value::String)
+}
+
+// String.prototype.valueOf ( )
+fn value_of(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    this_string_value(interpreter, &this, "String.prototype.valueOf")
+}
+
+// String.prototype [ @@iterator ] ( ), which goes through code points
+fn string_iterator(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    let text = interpreter.this_string(&this, "[Symbol.iterator]")?;
+    let iterator = ListIterator::new(Value::String(text), IterationKind::Values);
+    Ok(interpreter.list_iterator(ObjectKind::StringIterator, iterator))
+}
@@ -0,0 +1,110 @@
+use super::{Result, argument};
+use crate::Interpreter;
+use crate::inspect::inspect;
+use crate::object::NativeFunction;
+use jsompiler_runtime::{ObjectRef, Property, Symbol, Value};
+
+pub(super) fn install(interpreter: &mut Interpreter) {
+    let prototype = interpreter.intrinsics.symbol_prototype.clone();
+    let constructor = interpreter.native_constructor("Symbol", 0, symbol, &prototype);
+    let symbols = &interpreter.intrinsics.symbols;
+    let well_known = [
+        ("asyncIterator", symbols.async_iterator.clone()),
+        ("hasInstance", symbols.has_instance.clone()),
+        ("isConcatSpreadable", symbols.is_concat_spreadable.clone()),
+        ("iterator", symbols.iterator.clone()),
+        ("match", symbols.match_.clone()),
+        ("matchAll", symbols.match_all.clone()),
+        ("replace", symbols.replace.clone()),
+        ("search", symbols.search.clone()),
+        ("species", symbols.species.clone()),
+        ("split", symbols.split.clone()),
+        ("toPrimitive", symbols.to_primitive.clone()),
+        ("toStringTag", symbols.to_string_tag.clone()),
+        ("unscopables", symbols.unscopables.clone()),
+    ];
+    for (name, symbol) in well_known {
+        let property = Property::new(Value::Symbol(symbol), false, false, false);
+        constructor.borrow_mut().define(name, property);
+    }
+    interpreter.define_method(&constructor, "for", 1, symbol_for);
+    interpreter.define_method(&constructor, "keyFor", 1, key_for);
+
+    interpreter.define_getter(&prototype, "description", description);
+    let methods: [(&str, usize, NativeFunction); 2] =
+        [("toString", 0, to_string), ("valueOf", 0, value_of)];
+    for (name, length, function) in methods {
+        interpreter.define_method(&prototype, name, length, function);
+    }
+    let to_primitive = interpreter.intrinsics.symbols.to_primitive.clone();
+    let method = interpreter.native_function("[Symbol.toPrimitive]", 1, value_of);
+    let property = Property::new(Value::Object(method), false, false, true);
+    prototype.borrow_mut().define(to_primitive, property);
+    interpreter.define_to_string_tag(&prototype, "Symbol");
+    interpreter.define_global("Symbol", constructor);
+}
+
+// Symbol ( [ description ] ), which can't be called with `new`
+fn symbol(
+    interpreter: &mut Interpreter,
+    args: &[Value],
+    new_target: Option<&ObjectRef>,
+) -> Result<Value> {
+    if new_target.is_some() {
+        return Err(interpreter.type_error("Symbol is not a constructor"));
+    }
+    let description = match argument(args, 0) {
+        Value::Undefined => None,
+        description => Some(interpreter.to_string(&description)?),
+    };
+    Ok(Value::Symbol(Symbol::new(description.as_deref())))
+}
+
+// Symbol.for ( key ): the symbol registered under a key, which is the same for every call
+fn symbol_for(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let key = interpreter.to_string(&argument(args, 0))?;
+    let symbol = interpreter
+        .symbol_registry
+        .entry(key.clone())
+        .or_insert_with(|| Symbol::new(Some(&key)))
+        .clone();
+    Ok(Value::Symbol(symbol))
+}
+
+// Symbol.keyFor ( sym )
+fn key_for(interpreter: &mut Interpreter, _: Value, args: &[Value]) -> Result<Value> {
+    let Value::Symbol(symbol) = argument(args, 0) else {
+        let message = format!("{} is not a symbol", inspect(&argument(args, 0)));
+        return Err(interpreter.type_error(&message));
+    };
+    Ok(interpreter
+        .symbol_registry
+        .iter()
+        .find(|(_, registered)| **registered == symbol)
+        .map_or(Value::Undefined, |(key, _)| Value::String(key.clone())))
+}
+
+fn this_symbol(interpreter: &mut Interpreter, this: &Value, method: &str) -> Result<Symbol> {
+    let is_symbol = |value: &Value| matches!(value, Value::Symbol(_));
+    match interpreter.this_primitive(this, is_symbol, method, "Symbol")? {
+        Value::Symbol(symbol) => Ok(symbol),
+        _ => unreachable!("the value is a symbol"),
+    }
+}
+
+// get Symbol.prototype.description
+fn description(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    let symbol = this_symbol(interpreter, &this, "Symbol.prototype.description")?;
+    Ok(symbol.description().map_or(Value::Undefined, Value::string))
+}
+
+// Symbol.prototype.toString ( )
+fn to_string(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    let symbol = this_symbol(interpreter, &this, "Symbol.prototype.toString")?;
+    Ok(Value::from(symbol.to_string()))
+}
+
+// Symbol.prototype.valueOf ( ), which is also its `Symbol.toPrimitive`
+fn value_of(interpreter: &mut Interpreter, this: Value, _: &[Value]) -> Result<Value> {
+    this_symbol(interpreter, &this, "Symbol.prototype.valueOf").map(Value::Symbol)
+}
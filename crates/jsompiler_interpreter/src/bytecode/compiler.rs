@@ -0,0 +1,1788 @@
+use super::{Capture, CaptureFrom, Code, Local, Opcode};
+use crate::function::expected_arguments;
+use crate::number::number_to_string;
+use crate::object::FunctionKind;
+use jsompiler_common::{Atom, Error, ErrorKind};
+use jsompiler_lexer::symbol::{KeywordToken, LiteralToken, NumberLiteral, Token};
+use jsompiler_parser::{
+    Ast, BindingPattern, Expression, ExpressionId, ForInOfLeft, Identifier, Node, Parameter,
+    Property, PropertyKey, Span, Statement, StatementId, TemplateLiteral, TryStatement,
+};
+use jsompiler_runtime::Value;
+use jsompiler_semantic::{BindingKind, ScopeId, ScopeKind, Semantic, SymbolId, body_statements};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+type Result<T> = std::result::Result<T, Error>;
+
+// Compile a script that was checked by `jsompiler_semantic`, whose scope tree tells which
+// bindings closures capture. Features the compiler can't handle yet are a `SyntaxError`.
+pub fn compile(ast: &Ast, semantic: &Semantic, source: &str) -> Result<Rc<Code>> {
+    let statements: Vec<StatementId> = ast
+        .body
+        .iter()
+        .flat_map(|node| match node {
+            Node::Statement(statements) => statements.clone(),
+            Node::Expression(_) => Vec::new(),
+        })
+        .collect();
+    let global = semantic.global_scope();
+    let mut compiler = Compiler {
+        ast,
+        semantic,
+        source,
+        scope: global,
+        functions: Vec::new(),
+        captured: HashMap::new(),
+    };
+    compiler.functions.push(FunctionState::new(
+        global,
+        Code {
+            name: "<script>".into(),
+            strict: semantic.scope(global).strict,
+            ..Default::default()
+        },
+    ));
+
+    // Top level `var` bindings are properties of the global object
+    for symbol in &semantic.scope(global).bindings {
+        let symbol = semantic.symbol(*symbol);
+        if symbol.kind() == BindingKind::Var {
+            let name = compiler.name(symbol.name);
+            compiler.emit1(Opcode::DeclareGlobal, name);
+        }
+    }
+    compiler.statements(&statements)?;
+    compiler.emit(Opcode::Undefined);
+    compiler.emit(Opcode::Return);
+    compiler.finish()
+}
+
+struct Compiler<'a> {
+    ast: &'a Ast,
+    semantic: &'a Semantic,
+    source: &'a str,
+    scope: ScopeId,                    // The scope being compiled
+    functions: Vec<FunctionState>,     // The function being compiled and those around it
+    captured: HashMap<SymbolId, bool>, // Whether a closure refers to the binding
+}
+
+struct FunctionState {
+    scope: ScopeId,
+    code: Code,
+    slots: HashMap<SymbolId, usize>,
+    captures: HashMap<SymbolId, usize>,
+    controls: Vec<Control>,
+    too_large: bool, // An operand didn't fit in a `u16`
+}
+
+impl FunctionState {
+    fn new(scope: ScopeId, code: Code) -> Self {
+        FunctionState {
+            scope,
+            code,
+            slots: HashMap::new(),
+            captures: HashMap::new(),
+            controls: Vec::new(),
+            too_large: false,
+        }
+    }
+}
+
+// What `break`, `continue` and `return` leave on their way out
+enum Control {
+    Breakable {
+        kind: BreakableKind,
+        labels: Vec<Atom>,
+        breaks: Vec<usize>,    // Operands of the jumps to the end
+        continues: Vec<usize>, // Operands of the jumps to the next iteration
+    },
+    Handler,                            // A `try` block, whose handler must be removed
+    Finally(Vec<StatementId>, ScopeId), // A `finally` block that must run, and its scope
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BreakableKind {
+    Loop,
+    Switch,
+    Label,
+}
+
+// Where a binding lives at run time
+#[derive(Debug, Clone, Copy)]
+enum Storage {
+    Global(Atom), // A property of the global object, or a name nothing declares
+    Local(usize),
+    Cell(usize),
+    Capture(usize),
+}
+
+impl<'a> Compiler<'a> {
+    fn function(&mut self) -> &mut FunctionState {
+        self.functions
+            .last_mut()
+            .expect("a function is always being compiled")
+    }
+
+    fn finish(&mut self) -> Result<Rc<Code>> {
+        let state = self
+            .functions
+            .pop()
+            .expect("a function is always being compiled");
+        if state.too_large {
+            let message = format!("{} is too large for the bytecode compiler", state.code.name);
+            return Err(Error::new(ErrorKind::SyntaxError, message, 1, 0));
+        }
+        Ok(Rc::new(state.code))
+    }
+
+    fn unsupported(&self, feature: &str, span: Span) -> Error {
+        let line_number = self
+            .source
+            .chars()
+            .take(span.start)
+            .filter(|c| *c == '\n')
+            .count()
+            + 1;
+        let message = format!("{feature} are not supported by the bytecode compiler yet");
+        Error::new(ErrorKind::SyntaxError, message, line_number, span.start)
+    }
+
+    // Emitting code
+
+    fn offset(&mut self) -> usize {
+        self.function().code.bytes.len()
+    }
+
+    fn emit(&mut self, opcode: Opcode) {
+        self.function().code.bytes.push(opcode as u8);
+    }
+
+    fn operand(&mut self, operand: usize) {
+        let function = self.function();
+        let Ok(operand) = u16::try_from(operand) else {
+            function.too_large = true;
+            function.code.bytes.extend([0xff, 0xff]);
+            return;
+        };
+        function.code.bytes.extend(operand.to_le_bytes());
+    }
+
+    fn emit1(&mut self, opcode: Opcode, operand: usize) {
+        self.emit(opcode);
+        self.operand(operand);
+    }
+
+    fn emit2(&mut self, opcode: Opcode, first: usize, second: usize) {
+        self.emit(opcode);
+        self.operand(first);
+        self.operand(second);
+    }
+
+    // A jump to a target that isn't known yet, returning the operand to patch
+    fn jump(&mut self, opcode: Opcode) -> usize {
+        self.emit(opcode);
+        let operand = self.offset();
+        self.operand(0xffff);
+        operand
+    }
+
+    // Point a jump at the current offset
+    fn patch(&mut self, operand: usize) {
+        let target = self.offset();
+        self.patch_to(operand, target);
+    }
+
+    fn patch_to(&mut self, operand: usize, target: usize) {
+        let function = self.function();
+        match u16::try_from(target) {
+            Ok(target) => {
+                let bytes = target.to_le_bytes();
+                function.code.bytes[operand] = bytes[0];
+                function.code.bytes[operand + 1] = bytes[1];
+            }
+            Err(_) => function.too_large = true,
+        }
+    }
+
+    fn constant(&mut self, value: Value) -> usize {
+        let constants = &mut self.function().code.constants;
+        let same = |other: &Value| match (&value, other) {
+            (Value::Number(a), Value::Number(b)) => a.to_bits() == b.to_bits(),
+            (Value::String(a), Value::String(b)) => a == b,
+            (Value::BigInt(a), Value::BigInt(b)) => a == b,
+            _ => false,
+        };
+        match constants.iter().position(same) {
+            Some(index) => index,
+            None => {
+                constants.push(value);
+                constants.len() - 1
+            }
+        }
+    }
+
+    fn name(&mut self, name: Atom) -> usize {
+        let ast = self.ast;
+        self.name_text(&ast[name])
+    }
+
+    fn name_text(&mut self, name: &str) -> usize {
+        let names = &mut self.function().code.names;
+        match names.iter().position(|other| **other == *name) {
+            Some(index) => index,
+            None => {
+                names.push(name.into());
+                names.len() - 1
+            }
+        }
+    }
+
+    // Remember where the source of the code emitted next starts
+    fn mark(&mut self, span: Span) {
+        let offset = self.offset();
+        let positions = &mut self.function().code.positions;
+        match positions.last_mut() {
+            Some(last) if last.0 == offset => last.1 = span.start,
+            _ => positions.push((offset, span.start)),
+        }
+    }
+
+    // Scopes and bindings
+
+    // The scope the builder made for a node, a child of the current one
+    fn child_scope(&self, kind: ScopeKind, span: Span) -> ScopeId {
+        *self
+            .semantic
+            .scope(self.scope)
+            .children
+            .iter()
+            .find(|child| {
+                let scope = self.semantic.scope(**child);
+                scope.kind == kind && scope.span == span
+            })
+            .expect("the semantic model has a scope for every scope in the AST")
+    }
+
+    // The function or script a scope belongs to
+    fn function_scope(&self, scope: ScopeId) -> ScopeId {
+        self.semantic
+            .ancestors(scope)
+            .find(|scope| {
+                matches!(
+                    self.semantic.scope(*scope).kind,
+                    ScopeKind::Function | ScopeKind::Global | ScopeKind::Module
+                )
+            })
+            .expect("the global scope is an ancestor of every scope")
+    }
+
+    // Closure capture analysis: a binding is captured if it is referred to from a function
+    // other than the one declaring it
+    fn is_captured(&mut self, symbol: SymbolId) -> bool {
+        if let Some(captured) = self.captured.get(&symbol) {
+            return *captured;
+        }
+        let data = self.semantic.symbol(symbol);
+        let owner = self.function_scope(data.scope);
+        let captured = data.references.iter().any(|reference| {
+            self.function_scope(self.semantic.reference(*reference).scope) != owner
+        });
+        self.captured.insert(symbol, captured);
+        captured
+    }
+
+    // The local slot of a binding in the function at `index`
+    fn slot(&mut self, index: usize, symbol: SymbolId) -> usize {
+        if let Some(slot) = self.functions[index].slots.get(&symbol) {
+            return *slot;
+        }
+        let data = self.semantic.symbol(symbol);
+        let local = Local {
+            name: self.ast[data.name].into(),
+            cell: self.is_captured(symbol),
+            hoisted: matches!(
+                data.kind(),
+                BindingKind::Var | BindingKind::Function | BindingKind::Parameter
+            ),
+        };
+        let function = &mut self.functions[index];
+        function.code.locals.push(local);
+        let slot = function.code.locals.len() - 1;
+        function.slots.insert(symbol, slot);
+        slot
+    }
+
+    // A slot no binding has, for state a statement keeps while it runs
+    fn hidden_slot(&mut self, name: &str) -> usize {
+        let locals = &mut self.function().code.locals;
+        locals.push(Local {
+            name: name.into(),
+            cell: false,
+            hoisted: false,
+        });
+        locals.len() - 1
+    }
+
+    // The capture of a binding in the function at `index`, added to the functions between
+    // it and the one declaring the binding
+    fn capture(&mut self, index: usize, symbol: SymbolId, owner: ScopeId) -> usize {
+        if let Some(capture) = self.functions[index].captures.get(&symbol) {
+            return *capture;
+        }
+        let parent = index - 1;
+        let from = if self.functions[parent].scope == owner {
+            CaptureFrom::Local(self.slot(parent, symbol))
+        } else {
+            CaptureFrom::Capture(self.capture(parent, symbol, owner))
+        };
+        let function = &mut self.functions[index];
+        function.code.captures.push(Capture {
+            name: self.ast[self.semantic.symbol(symbol).name].into(),
+            from,
+        });
+        let capture = function.code.captures.len() - 1;
+        function.captures.insert(symbol, capture);
+        capture
+    }
+
+    fn storage(&mut self, symbol: Option<SymbolId>, name: Atom) -> Storage {
+        let Some(symbol) = symbol else {
+            return Storage::Global(name);
+        };
+        let data = self.semantic.symbol(symbol);
+        let scope = self.semantic.scope(data.scope);
+        if scope.kind == ScopeKind::Global && !data.kind().is_lexical() {
+            return Storage::Global(name);
+        }
+        let owner = self.function_scope(data.scope);
+        let current = self.functions.len() - 1;
+        if self.functions[current].scope == owner {
+            let slot = self.slot(current, symbol);
+            if self.is_captured(symbol) {
+                Storage::Cell(slot)
+            } else {
+                Storage::Local(slot)
+            }
+        } else {
+            Storage::Capture(self.capture(current, symbol, owner))
+        }
+    }
+
+    // The binding a declaration in the current scope makes
+    fn declared(&self, name: &Identifier) -> Option<SymbolId> {
+        self.semantic.find_binding(self.scope, name.value)
+    }
+
+    fn check_arguments(&self, symbol: Option<SymbolId>, span: Span) -> Result<()> {
+        match symbol {
+            Some(symbol) if self.semantic.symbol(symbol).kind() == BindingKind::Arguments => {
+                Err(self.unsupported("'arguments' objects", span))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn load(&mut self, symbol: Option<SymbolId>, name: Atom) {
+        match self.storage(symbol, name) {
+            Storage::Global(name) => {
+                let name = self.name(name);
+                self.emit1(Opcode::GetGlobal, name);
+            }
+            Storage::Local(slot) => self.emit1(Opcode::GetLocal, slot),
+            Storage::Cell(slot) => self.emit1(Opcode::GetCell, slot),
+            Storage::Capture(index) => self.emit1(Opcode::GetCapture, index),
+        }
+    }
+
+    // Assign the value on top of the stack, leaving it there
+    fn store(&mut self, symbol: Option<SymbolId>, name: Atom) {
+        if let Some(symbol) = symbol
+            && self.semantic.symbol(symbol).kind() == BindingKind::Const
+        {
+            let name = self.name(name);
+            self.emit1(Opcode::ConstAssignment, name);
+            return;
+        }
+        match self.storage(symbol, name) {
+            Storage::Global(name) => {
+                let name = self.name(name);
+                self.emit1(Opcode::SetGlobal, name);
+            }
+            Storage::Local(slot) => self.emit1(Opcode::SetLocal, slot),
+            Storage::Cell(slot) => self.emit1(Opcode::SetCell, slot),
+            Storage::Capture(index) => self.emit1(Opcode::SetCapture, index),
+        }
+    }
+
+    // Initialize a binding with the value on top of the stack, which is popped
+    fn initialize(&mut self, symbol: Option<SymbolId>, name: Atom) {
+        match self.storage(symbol, name) {
+            Storage::Global(name) => {
+                let name = self.name(name);
+                self.emit1(Opcode::InitGlobal, name);
+            }
+            Storage::Local(slot) => self.emit1(Opcode::InitLocal, slot),
+            Storage::Cell(slot) => self.emit1(Opcode::InitCell, slot),
+            Storage::Capture(_) => unreachable!("bindings are initialized where they are declared"),
+        }
+    }
+
+    // Enter the scope of a block, giving captured bindings new cells each time it runs
+    fn enter_scope(&mut self, kind: ScopeKind, span: Span) -> ScopeId {
+        let outer = self.scope;
+        self.scope = self.child_scope(kind, span);
+        self.fresh_cells(self.scope);
+        outer
+    }
+
+    fn fresh_cells(&mut self, scope: ScopeId) {
+        for symbol in self.semantic.scope(scope).bindings.clone() {
+            let name = self.semantic.symbol(symbol).name;
+            if let Storage::Cell(slot) = self.storage(Some(symbol), name) {
+                self.emit1(Opcode::NewCell, slot);
+            }
+        }
+    }
+
+    // Statements
+
+    // Function declarations are initialized before the statements run
+    fn statements(&mut self, statements: &[StatementId]) -> Result<()> {
+        for statement in statements {
+            if let Statement::FunctionDeclaration(function) = &self.ast[*statement] {
+                if function.is_async || function.is_generator {
+                    return Err(self.unsupported("Generator and async functions", function.span));
+                }
+                let Some(name) = &function.name else { continue };
+                let index = self.compile_function(
+                    &self.ast[name.value],
+                    FunctionKind::Normal,
+                    &function.parameters,
+                    &function.body,
+                    function.span,
+                    None,
+                )?;
+                self.emit1(Opcode::Closure, index);
+                let symbol = self.declared(name);
+                self.initialize(symbol, name.value);
+            }
+        }
+        for statement in statements {
+            self.statement(*statement, &[])?;
+        }
+        Ok(())
+    }
+
+    fn statement(&mut self, id: StatementId, labels: &[Atom]) -> Result<()> {
+        let ast = self.ast;
+        let statement = &ast[id];
+        self.mark(statement.span());
+        match statement {
+            Statement::ExpressionStatement { expression, .. } => {
+                self.expression(*expression)?;
+                self.completion();
+            }
+            Statement::AssignmentStatement(assignment) => {
+                self.assign(assignment.target, assignment.value)?;
+                self.completion();
+            }
+            Statement::LetStatement(declaration) => {
+                let BindingPattern::Identifier(name) = &declaration.name else {
+                    return Err(self.unsupported("Destructuring patterns", declaration.span));
+                };
+                let symbol = self.declared(name);
+                if declaration.token == Token::Keyword(KeywordToken::Var) {
+                    if let Some(value) = declaration.value {
+                        self.named_expression(value, &self.ast[name.value])?;
+                        self.store(symbol, name.value);
+                        self.emit(Opcode::Pop);
+                    }
+                } else {
+                    match declaration.value {
+                        Some(value) => self.named_expression(value, &self.ast[name.value])?,
+                        None => self.emit(Opcode::Undefined),
+                    }
+                    self.initialize(symbol, name.value);
+                }
+            }
+            Statement::BlockStatement(block) => {
+                let outer = self.enter_scope(ScopeKind::Block, block.span);
+                self.statements(&block.statements)?;
+                self.scope = outer;
+            }
+            Statement::IfStatement(statement) => {
+                self.expression(statement.condition)?;
+                let otherwise = self.jump(Opcode::JumpIfFalse);
+                self.statements(&statement.consequence)?;
+                match &statement.alternative {
+                    Some(alternative) => {
+                        let end = self.jump(Opcode::Jump);
+                        self.patch(otherwise);
+                        self.statements(alternative)?;
+                        self.patch(end);
+                    }
+                    None => self.patch(otherwise),
+                }
+            }
+            Statement::WhileStatement(statement) => {
+                let start = self.offset();
+                self.expression(statement.condition)?;
+                let exit = self.jump(Opcode::JumpIfFalse);
+                self.loop_body(&statement.consequence, labels)?;
+                self.end_loop(start, start);
+                self.patch(exit);
+                self.end_breakable();
+            }
+            Statement::DoWhileStatement(statement) => {
+                let start = self.offset();
+                self.loop_body(&statement.body, labels)?;
+                let condition = self.offset();
+                self.patch_continues(condition);
+                self.expression(statement.condition)?;
+                self.emit1(Opcode::JumpIfTrue, start);
+                self.end_breakable();
+            }
+            Statement::ForLoopStatement(statement) => {
+                let outer = self.enter_scope(ScopeKind::Block, statement.span);
+                let scope = self.scope;
+                for initializer in &statement.initializer {
+                    match &ast[*initializer] {
+                        Statement::LetStatement(_) => self.statement(*initializer, &[])?,
+                        _ => self.discarded(*initializer)?,
+                    }
+                }
+                let start = self.offset();
+                let mut exit = None;
+                if let Some(condition) = statement.condition.first() {
+                    self.head_expression(*condition)?;
+                    exit = Some(self.jump(Opcode::JumpIfFalse));
+                }
+                self.loop_body(&statement.body, labels)?;
+                // Each iteration gets its own copy of the captured `let` bindings
+                let next = self.offset();
+                for symbol in self.semantic.scope(scope).bindings.clone() {
+                    let name = self.semantic.symbol(symbol).name;
+                    if let Storage::Cell(slot) = self.storage(Some(symbol), name) {
+                        self.emit1(Opcode::CopyCell, slot);
+                    }
+                }
+                for increment in &statement.increment {
+                    self.discarded(*increment)?;
+                }
+                self.end_loop(start, next);
+                if let Some(exit) = exit {
+                    self.patch(exit);
+                }
+                self.end_breakable();
+                self.scope = outer;
+            }
+            Statement::ForInStatement(statement) => {
+                self.for_in_of(
+                    &statement.left,
+                    statement.right,
+                    &statement.body,
+                    statement.span,
+                    Opcode::EnumerateKeys,
+                    labels,
+                )?;
+            }
+            Statement::ForOfStatement(statement) => {
+                if statement.is_await {
+                    return Err(self.unsupported("'for await' loops", statement.span));
+                }
+                self.for_in_of(
+                    &statement.left,
+                    statement.right,
+                    &statement.body,
+                    statement.span,
+                    Opcode::IterateValues,
+                    labels,
+                )?;
+            }
+            Statement::SwitchStatement(statement) => {
+                self.expression(statement.discriminant)?;
+                let discriminant = self.hidden_slot("%discriminant");
+                self.emit1(Opcode::InitLocal, discriminant);
+                let outer = self.enter_scope(ScopeKind::Block, statement.span);
+                self.begin_breakable(BreakableKind::Switch, labels);
+                // Function declarations of all cases are initialized before any case runs
+                let consequents: Vec<StatementId> = statement
+                    .cases
+                    .iter()
+                    .flat_map(|case| case.consequent.iter().copied())
+                    .collect();
+                self.hoist_functions(&consequents)?;
+                let mut bodies = Vec::new();
+                for case in &statement.cases {
+                    if let Some(test) = case.test {
+                        self.emit1(Opcode::GetLocal, discriminant);
+                        self.expression(test)?;
+                        self.emit(Opcode::StrictEqual);
+                        bodies.push(Some(self.jump(Opcode::JumpIfTrue)));
+                    } else {
+                        bodies.push(None);
+                    }
+                }
+                let otherwise = self.jump(Opcode::Jump);
+                let mut default = None;
+                for (case, body) in statement.cases.iter().zip(bodies) {
+                    match body {
+                        Some(jump) => self.patch(jump),
+                        None => default = Some(self.offset()),
+                    }
+                    for statement in &case.consequent {
+                        self.statement(*statement, &[])?;
+                    }
+                }
+                match default {
+                    Some(default) => self.patch_to(otherwise, default),
+                    None => self.patch(otherwise),
+                }
+                self.end_breakable();
+                self.scope = outer;
+            }
+            Statement::LabeledStatement(statement) => {
+                let mut labels = labels.to_vec();
+                labels.push(statement.label.value);
+                match statement.body.as_slice() {
+                    [body]
+                        if matches!(
+                            ast[*body],
+                            Statement::WhileStatement(_)
+                                | Statement::DoWhileStatement(_)
+                                | Statement::ForLoopStatement(_)
+                                | Statement::ForInStatement(_)
+                                | Statement::ForOfStatement(_)
+                                | Statement::LabeledStatement(_)
+                        ) =>
+                    {
+                        self.statement(*body, &labels)?
+                    }
+                    body => {
+                        self.begin_breakable(BreakableKind::Label, &labels);
+                        for statement in body {
+                            self.statement(*statement, &[])?;
+                        }
+                        self.end_breakable();
+                    }
+                }
+            }
+            Statement::BreakStatement { label, span } => {
+                let target = self.find_breakable(label.map(|label| label.value), false);
+                let Some(target) = target else {
+                    return Err(self.unsupported("'break' without a target", *span));
+                };
+                self.leave(target)?;
+                let jump = self.jump(Opcode::Jump);
+                if let Control::Breakable { breaks, .. } = &mut self.function().controls[target] {
+                    breaks.push(jump);
+                }
+            }
+            Statement::ContinueStatement { label, span } => {
+                let target = self.find_breakable(label.map(|label| label.value), true);
+                let Some(target) = target else {
+                    return Err(self.unsupported("'continue' without a loop", *span));
+                };
+                self.leave(target + 1)?;
+                let jump = self.jump(Opcode::Jump);
+                if let Control::Breakable { continues, .. } = &mut self.function().controls[target]
+                {
+                    continues.push(jump);
+                }
+            }
+            Statement::ReturnStatement(statement) => {
+                match statement.value {
+                    Some(value) => self.expression(value)?,
+                    None => self.emit(Opcode::Undefined),
+                }
+                self.leave(0)?;
+                self.emit(Opcode::Return);
+            }
+            Statement::ThrowStatement(statement) => {
+                self.expression(statement.argument)?;
+                self.emit(Opcode::Throw);
+            }
+            Statement::TryStatement(statement) => self.try_statement(statement)?,
+            Statement::FunctionDeclaration(_)
+            | Statement::EmptyStatement { .. }
+            | Statement::DebuggerStatement { .. } => {}
+            Statement::ClassDeclaration(class) => {
+                return Err(self.unsupported("Classes", class.span));
+            }
+            Statement::WithStatement(statement) => {
+                return Err(self.unsupported("'with' statements", statement.span));
+            }
+            Statement::YieldStatement(statement) => {
+                return Err(self.unsupported("'yield' expressions", statement.span));
+            }
+            Statement::ExportStatement(statement) => {
+                return Err(self.unsupported("Modules", statement.span()));
+            }
+            Statement::Error { span } => {
+                return Err(self.unsupported("Statements that failed to parse", *span));
+            }
+        }
+        Ok(())
+    }
+
+    // The value of an expression statement is the completion value of a script
+    fn completion(&mut self) {
+        if self.functions.len() == 1 {
+            self.emit(Opcode::Completion);
+        } else {
+            self.emit(Opcode::Pop);
+        }
+    }
+
+    // The value of a statement in a loop head
+    fn head_expression(&mut self, id: StatementId) -> Result<()> {
+        match &self.ast[id] {
+            Statement::ExpressionStatement { expression, .. } => self.expression(*expression),
+            Statement::AssignmentStatement(assignment) => {
+                self.assign(assignment.target, assignment.value)
+            }
+            _ => {
+                self.statement(id, &[])?;
+                self.emit(Opcode::Undefined);
+                Ok(())
+            }
+        }
+    }
+
+    fn discarded(&mut self, id: StatementId) -> Result<()> {
+        self.head_expression(id)?;
+        self.emit(Opcode::Pop);
+        Ok(())
+    }
+
+    fn hoist_functions(&mut self, statements: &[StatementId]) -> Result<()> {
+        let functions: Vec<StatementId> = statements
+            .iter()
+            .copied()
+            .filter(|id| matches!(self.ast[*id], Statement::FunctionDeclaration(_)))
+            .collect();
+        if functions.is_empty() {
+            return Ok(());
+        }
+        self.statements(&functions)
+    }
+
+    fn loop_body(&mut self, body: &[StatementId], labels: &[Atom]) -> Result<()> {
+        self.begin_breakable(BreakableKind::Loop, labels);
+        self.statements(body)
+    }
+
+    // Jump back to the start of a loop, and point its `continue` jumps at `next`
+    fn end_loop(&mut self, start: usize, next: usize) {
+        self.patch_continues(next);
+        self.emit1(Opcode::Jump, start);
+    }
+
+    fn patch_continues(&mut self, next: usize) {
+        if let Some(Control::Breakable { continues, .. }) = self.function().controls.last_mut() {
+            for jump in std::mem::take(continues) {
+                self.patch_to(jump, next);
+            }
+        }
+    }
+
+    fn begin_breakable(&mut self, kind: BreakableKind, labels: &[Atom]) {
+        self.function().controls.push(Control::Breakable {
+            kind,
+            labels: labels.to_vec(),
+            breaks: Vec::new(),
+            continues: Vec::new(),
+        });
+    }
+
+    fn end_breakable(&mut self) {
+        if let Some(Control::Breakable { breaks, .. }) = self.function().controls.pop() {
+            for jump in breaks {
+                self.patch(jump);
+            }
+        }
+    }
+
+    // The control a `break` or `continue` goes to
+    fn find_breakable(&mut self, label: Option<Atom>, is_continue: bool) -> Option<usize> {
+        self.function()
+            .controls
+            .iter()
+            .rposition(|control| match control {
+                Control::Breakable { kind, labels, .. } => {
+                    let loop_only = is_continue && *kind != BreakableKind::Loop;
+                    match label {
+                        Some(label) => labels.contains(&label) && !loop_only,
+                        None => *kind != BreakableKind::Label && !loop_only,
+                    }
+                }
+                _ => false,
+            })
+    }
+
+    // Remove the handlers and run the `finally` blocks between here and the control at
+    // `target`, before jumping out to it
+    fn leave(&mut self, target: usize) -> Result<()> {
+        let mut index = self.function().controls.len();
+        while index > target {
+            index -= 1;
+            match &self.function().controls[index] {
+                Control::Handler => self.emit(Opcode::PopHandler),
+                Control::Finally(finalizer, scope) => {
+                    let (finalizer, scope) = (finalizer.clone(), *scope);
+                    // The `finally` block is compiled as if it were outside the `try`
+                    let inner = self.function().controls.split_off(index);
+                    let outer = std::mem::replace(&mut self.scope, scope);
+                    let result = self.statements(&finalizer);
+                    self.scope = outer;
+                    self.function().controls.extend(inner);
+                    result?;
+                }
+                Control::Breakable { .. } => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn for_in_of(
+        &mut self,
+        left: &ForInOfLeft,
+        right: ExpressionId,
+        body: &[StatementId],
+        span: Span,
+        opcode: Opcode,
+        labels: &[Atom],
+    ) -> Result<()> {
+        let outer = self.scope;
+        self.scope = self.child_scope(ScopeKind::Block, span);
+        let scope = self.scope;
+        self.expression(right)?;
+        self.emit(opcode);
+        let iteration = self.hidden_slot("%iteration");
+        let index = self.hidden_slot("%index");
+        self.emit1(Opcode::InitLocal, iteration);
+        let zero = self.constant(Value::Number(0.0));
+        self.emit1(Opcode::Constant, zero);
+        self.emit1(Opcode::InitLocal, index);
+
+        let start = self.offset();
+        self.fresh_cells(scope);
+        self.emit(Opcode::Next);
+        self.operand(iteration);
+        let exit = self.offset();
+        self.operand(0xffff);
+        match left {
+            ForInOfLeft::Declaration { token, name, span } => {
+                let BindingPattern::Identifier(name) = name else {
+                    return Err(self.unsupported("Destructuring patterns", *span));
+                };
+                let symbol = self.declared(name);
+                if *token == Token::Keyword(KeywordToken::Var) {
+                    self.store(symbol, name.value);
+                    self.emit(Opcode::Pop);
+                } else {
+                    self.initialize(symbol, name.value);
+                }
+            }
+            ForInOfLeft::Target(target) => self.store_target(*target)?,
+        }
+        self.loop_body(body, labels)?;
+        self.end_loop(start, start);
+        self.patch(exit);
+        self.end_breakable();
+        self.scope = outer;
+        Ok(())
+    }
+
+    // try { block } catch (param) { handler } finally { finalizer }
+    fn try_statement(&mut self, statement: &TryStatement) -> Result<()> {
+        let finally_handler = match &statement.finalizer {
+            Some(finalizer) => {
+                let handler = self.jump(Opcode::PushHandler);
+                let scope = self.scope;
+                self.function()
+                    .controls
+                    .push(Control::Finally(finalizer.clone(), scope));
+                self.function().controls.push(Control::Handler);
+                Some(handler)
+            }
+            None => None,
+        };
+
+        match &statement.handler {
+            Some(clause) => {
+                let catch = self.jump(Opcode::PushHandler);
+                self.function().controls.push(Control::Handler);
+                self.statements(&statement.block)?;
+                self.function().controls.pop();
+                self.emit(Opcode::PopHandler);
+                let end = self.jump(Opcode::Jump);
+
+                // The exception is on the stack when the handler runs
+                self.patch(catch);
+                let outer = self.enter_scope(ScopeKind::Catch, clause.span);
+                match &clause.param {
+                    Some(BindingPattern::Identifier(name)) => {
+                        let symbol = self.declared(name);
+                        self.initialize(symbol, name.value);
+                    }
+                    Some(_) => return Err(self.unsupported("Destructuring patterns", clause.span)),
+                    None => self.emit(Opcode::Pop),
+                }
+                let body = body_statements(self.ast, &clause.body);
+                self.statements(&body)?;
+                self.scope = outer;
+                self.patch(end);
+            }
+            None => self.statements(&statement.block)?,
+        }
+
+        if let (Some(handler), Some(finalizer)) = (finally_handler, &statement.finalizer) {
+            self.function().controls.pop();
+            self.function().controls.pop();
+            self.emit(Opcode::PopHandler);
+            self.statements(finalizer)?;
+            let end = self.jump(Opcode::Jump);
+
+            // An exception runs the `finally` block and is thrown again
+            self.patch(handler);
+            let exception = self.hidden_slot("%exception");
+            self.emit1(Opcode::InitLocal, exception);
+            self.statements(finalizer)?;
+            self.emit1(Opcode::GetLocal, exception);
+            self.emit(Opcode::Rethrow);
+            self.patch(end);
+        }
+        Ok(())
+    }
+
+    // Functions
+
+    // Compile a function into the functions of the current one, returning its index. The
+    // name of a function expression is a binding inside it.
+    fn compile_function(
+        &mut self,
+        name: &str,
+        kind: FunctionKind,
+        parameters: &[Parameter],
+        body: &[StatementId],
+        span: Span,
+        own_name: Option<&Identifier>,
+    ) -> Result<usize> {
+        let scope = self.child_scope(ScopeKind::Function, span);
+        let outer = std::mem::replace(&mut self.scope, scope);
+        self.functions.push(FunctionState::new(
+            scope,
+            Code {
+                name: name.into(),
+                kind,
+                strict: self.semantic.scope(scope).strict,
+                length: expected_arguments(parameters),
+                ..Default::default()
+            },
+        ));
+        self.mark(span);
+
+        for (index, parameter) in parameters.iter().enumerate() {
+            let (name, default) = match parameter {
+                Parameter::Identifier(name) => {
+                    self.emit1(Opcode::Argument, index);
+                    (name, None)
+                }
+                Parameter::Default {
+                    target: BindingPattern::Identifier(name),
+                    default,
+                    ..
+                } => {
+                    self.emit1(Opcode::Argument, index);
+                    (name, Some(*default))
+                }
+                Parameter::RestParameter {
+                    argument: BindingPattern::Identifier(name),
+                    ..
+                } => {
+                    self.emit1(Opcode::Arguments, index);
+                    (name, None)
+                }
+                _ => return Err(self.unsupported("Destructuring patterns", span)),
+            };
+            if let Some(default) = default {
+                self.emit(Opcode::Dup);
+                self.emit(Opcode::Undefined);
+                self.emit(Opcode::StrictEqual);
+                let given = self.jump(Opcode::JumpIfFalse);
+                self.emit(Opcode::Pop);
+                self.named_expression(default, &self.ast[name.value])?;
+                self.patch(given);
+            }
+            let symbol = self.semantic.binding(scope, name.value);
+            self.initialize(symbol, name.value);
+        }
+        if let Some(own_name) = own_name
+            && let Some(symbol) = self.semantic.binding(scope, own_name.value)
+            && self.semantic.symbol(symbol).kind() == BindingKind::FunctionName
+        {
+            self.emit(Opcode::Callee);
+            self.initialize(Some(symbol), own_name.value);
+        }
+
+        // An arrow with an expression body returns it
+        match body {
+            [statement] if kind == FunctionKind::Arrow => match &self.ast[*statement] {
+                Statement::ExpressionStatement { expression, .. } => {
+                    self.expression(*expression)?;
+                    self.emit(Opcode::Return);
+                }
+                _ => self.function_body(body)?,
+            },
+            _ => self.function_body(body)?,
+        }
+
+        let code = self.finish()?;
+        self.scope = outer;
+        let functions = &mut self.function().code.functions;
+        functions.push(code);
+        Ok(functions.len() - 1)
+    }
+
+    fn function_body(&mut self, body: &[StatementId]) -> Result<()> {
+        let body = body_statements(self.ast, body);
+        self.statements(&body)?;
+        self.emit(Opcode::Undefined);
+        self.emit(Opcode::Return);
+        Ok(())
+    }
+
+    // Expressions
+
+    fn expression(&mut self, id: ExpressionId) -> Result<()> {
+        let ast = self.ast;
+        match &ast[id] {
+            Expression::Identifier(name) => {
+                let symbol = self.semantic.symbol_of(id);
+                self.check_arguments(symbol, name.span)?;
+                self.load(symbol, name.value);
+            }
+            Expression::Literal { value, .. } => match value {
+                LiteralToken::Number(number) => {
+                    let value = match number {
+                        NumberLiteral::Value(value) => Value::Number(*value),
+                        NumberLiteral::NaN => Value::Number(f64::NAN),
+                        NumberLiteral::Infinity => Value::Number(f64::INFINITY),
+                        NumberLiteral::BigInt(value) => Value::bigint(value.clone()),
+                    };
+                    let constant = self.constant(value);
+                    self.emit1(Opcode::Constant, constant);
+                }
+                LiteralToken::String(text) => {
+                    let constant = self.constant(Value::string(text));
+                    self.emit1(Opcode::Constant, constant);
+                }
+                LiteralToken::Boolean(true) => self.emit(Opcode::True),
+                LiteralToken::Boolean(false) => self.emit(Opcode::False),
+                LiteralToken::Null => self.emit(Opcode::Null),
+                LiteralToken::Undefined => self.emit(Opcode::Undefined),
+            },
+            Expression::TemplateLiteral(template) => self.template(template)?,
+            Expression::ThisExpression { .. } => self.emit(Opcode::This),
+            Expression::MemberAccess {
+                object,
+                property,
+                computed,
+                ..
+            } => {
+                self.expression(*object)?;
+                self.member(*property, *computed, Opcode::GetNamed, Opcode::GetProperty)?;
+            }
+            Expression::Unary {
+                op,
+                op_type,
+                expr,
+                span,
+            } => self.unary(&op.text, op_type == "Postfix", *expr, *span)?,
+            Expression::Binary {
+                left,
+                op,
+                right,
+                span,
+            } => self.binary(&op.text, *left, *right, *span)?,
+            Expression::FunctionCall { callee, args, span } => {
+                match &ast[*callee] {
+                    Expression::MemberAccess {
+                        object,
+                        property,
+                        computed,
+                        ..
+                    } => {
+                        self.expression(*object)?;
+                        self.emit(Opcode::Dup);
+                        self.member(*property, *computed, Opcode::GetNamed, Opcode::GetProperty)?;
+                        self.emit(Opcode::Swap);
+                    }
+                    _ => {
+                        self.expression(*callee)?;
+                        self.emit(Opcode::Undefined);
+                    }
+                }
+                for argument in args {
+                    if matches!(ast[*argument], Expression::SpreadElement { .. }) {
+                        return Err(self.unsupported("Spread arguments", *span));
+                    }
+                    self.expression(*argument)?;
+                }
+                let description = self.name_text(&describe_callee(ast, *callee));
+                self.emit2(Opcode::Call, args.len(), description);
+            }
+            Expression::ArrayLiteral { elements, .. } => {
+                let spread_or_hole = elements.iter().any(|element| {
+                    matches!(ast[*element], Expression::SpreadElement { .. })
+                        || is_hole(&ast[*element])
+                });
+                if !spread_or_hole {
+                    for element in elements {
+                        self.expression(*element)?;
+                    }
+                    self.emit1(Opcode::Array, elements.len());
+                } else {
+                    self.emit1(Opcode::Array, 0);
+                    for element in elements {
+                        match &ast[*element] {
+                            Expression::SpreadElement { argument, .. } => {
+                                self.expression(*argument)?;
+                                self.emit(Opcode::ArraySpread);
+                            }
+                            expression if is_hole(expression) => self.emit(Opcode::ArrayHole),
+                            _ => {
+                                self.expression(*element)?;
+                                self.emit(Opcode::ArrayAppend);
+                            }
+                        }
+                    }
+                }
+            }
+            Expression::ObjectLiteral { properties, span } => self.object(properties, *span)?,
+            Expression::FunctionExpression(function) => {
+                let name = function.name.map(|name| &self.ast[name.value]);
+                let index = self.compile_function(
+                    name.unwrap_or(""),
+                    FunctionKind::Normal,
+                    &function.parameters,
+                    &function.body,
+                    function.span,
+                    function.name.as_ref(),
+                )?;
+                self.emit1(Opcode::Closure, index);
+            }
+            Expression::ArrowFunctionExpression {
+                parameters,
+                body,
+                span,
+            } => {
+                let index =
+                    self.compile_function("", FunctionKind::Arrow, parameters, body, *span, None)?;
+                self.emit1(Opcode::Closure, index);
+            }
+            Expression::GeneratorExpression(function)
+            | Expression::AsyncFunctionExpression(function)
+            | Expression::AsyncGeneratorExpression(function) => {
+                return Err(self.unsupported("Generator and async functions", function.span));
+            }
+            Expression::ClassExpression(class) => {
+                return Err(self.unsupported("Classes", class.span));
+            }
+            Expression::AwaitExpression { span, .. } => {
+                return Err(self.unsupported("'await' expressions", *span));
+            }
+            Expression::RegularExpressionLiteral { span, .. } => {
+                return Err(self.unsupported("Regular expressions", *span));
+            }
+            Expression::JSXElement(_) | Expression::JSXFragment(_) => {
+                return Err(self.unsupported("JSX elements", ast[id].span()));
+            }
+            Expression::SpreadElement { span, .. } => {
+                return Err(self.unsupported("Spread elements here", *span));
+            }
+            Expression::PrivateIdentifier(name) => {
+                return Err(self.unsupported("Private names", name.span));
+            }
+        }
+        Ok(())
+    }
+
+    // An expression that may be an anonymous function, which then gets `name`
+    fn named_expression(&mut self, id: ExpressionId, name: &str) -> Result<()> {
+        let index = match &self.ast[id] {
+            Expression::FunctionExpression(function) if function.name.is_none() => self
+                .compile_function(
+                    name,
+                    FunctionKind::Normal,
+                    &function.parameters,
+                    &function.body,
+                    function.span,
+                    None,
+                )?,
+            Expression::ArrowFunctionExpression {
+                parameters,
+                body,
+                span,
+            } => self.compile_function(name, FunctionKind::Arrow, parameters, body, *span, None)?,
+            _ => return self.expression(id),
+        };
+        self.emit1(Opcode::Closure, index);
+        Ok(())
+    }
+
+    // Read or write the property of the object on the stack
+    fn member(
+        &mut self,
+        property: ExpressionId,
+        computed: bool,
+        named: Opcode,
+        keyed: Opcode,
+    ) -> Result<()> {
+        match &self.ast[property] {
+            Expression::Identifier(name) if !computed => {
+                let name = self.name(name.value);
+                self.emit1(named, name);
+            }
+            _ => {
+                self.expression(property)?;
+                self.emit(keyed);
+            }
+        }
+        Ok(())
+    }
+
+    fn template(&mut self, template: &TemplateLiteral) -> Result<()> {
+        let ast = self.ast;
+        let quasi = |compiler: &mut Self, id: ExpressionId| {
+            let text = match &ast[id] {
+                Expression::Literal {
+                    value: LiteralToken::String(text),
+                    ..
+                } => text.as_str(),
+                _ => "",
+            };
+            let constant = compiler.constant(Value::string(text));
+            compiler.emit1(Opcode::Constant, constant);
+        };
+        match template {
+            TemplateLiteral::NonSubstitutionTemplate { text, .. } => quasi(self, *text),
+            TemplateLiteral::SubstitutionTemplate {
+                template_head,
+                expression,
+                template_spans,
+                ..
+            } => {
+                quasi(self, *template_head);
+                self.expression(*expression)?;
+                self.emit(Opcode::ToString);
+                self.emit(Opcode::Add);
+                let mut middle = template_spans.template_middle_list.as_ref();
+                while let Some(list) = middle {
+                    quasi(self, list.template_middle);
+                    self.emit(Opcode::Add);
+                    self.expression(list.expression)?;
+                    self.emit(Opcode::ToString);
+                    self.emit(Opcode::Add);
+                    middle = list.template_middle_list.as_deref();
+                }
+                quasi(self, template_spans.template_tail);
+                self.emit(Opcode::Add);
+            }
+        }
+        Ok(())
+    }
+
+    fn object(&mut self, properties: &[Property], span: Span) -> Result<()> {
+        self.emit(Opcode::Object);
+        for property in properties {
+            match property {
+                Property::KeyValue { key, value, .. } => match static_key(self.ast, key) {
+                    Some(key) => {
+                        self.named_expression(*value, &key)?;
+                        let name = self.name_text(&key);
+                        self.emit1(Opcode::DefineNamed, name);
+                    }
+                    None => {
+                        self.property_key(key)?;
+                        self.expression(*value)?;
+                        self.emit(Opcode::DefineProperty);
+                    }
+                },
+                Property::Shorthand(name) => {
+                    let symbol = self.semantic.find_binding(self.scope, name.value);
+                    self.check_arguments(symbol, name.span)?;
+                    self.load(symbol, name.value);
+                    let name = self.name(name.value);
+                    self.emit1(Opcode::DefineNamed, name);
+                }
+                Property::Method {
+                    key,
+                    params,
+                    body,
+                    function_span,
+                    ..
+                } => {
+                    let name = static_key(self.ast, key);
+                    if name.is_none() {
+                        self.property_key(key)?;
+                    }
+                    let index = self.compile_function(
+                        name.as_deref().unwrap_or(""),
+                        FunctionKind::Method,
+                        params,
+                        body,
+                        *function_span,
+                        None,
+                    )?;
+                    self.emit1(Opcode::Closure, index);
+                    match name {
+                        Some(name) => {
+                            let name = self.name_text(&name);
+                            self.emit1(Opcode::DefineNamed, name);
+                        }
+                        None => self.emit(Opcode::DefineProperty),
+                    }
+                }
+                Property::Getter {
+                    key,
+                    body,
+                    function_span,
+                    span,
+                } => {
+                    let Some(name) = static_key(self.ast, key) else {
+                        return Err(self.unsupported("Computed accessor names", *span));
+                    };
+                    let body = std::slice::from_ref(body);
+                    let index = self.compile_function(
+                        &format!("get {name}"),
+                        FunctionKind::Method,
+                        &[],
+                        body,
+                        *function_span,
+                        None,
+                    )?;
+                    self.emit1(Opcode::Closure, index);
+                    let name = self.name_text(&name);
+                    self.emit1(Opcode::DefineGetter, name);
+                }
+                Property::Setter {
+                    key,
+                    param,
+                    body,
+                    function_span,
+                    span,
+                } => {
+                    let Some(name) = static_key(self.ast, key) else {
+                        return Err(self.unsupported("Computed accessor names", *span));
+                    };
+                    let (params, body) = (std::slice::from_ref(param), std::slice::from_ref(body));
+                    let index = self.compile_function(
+                        &format!("set {name}"),
+                        FunctionKind::Method,
+                        params,
+                        body,
+                        *function_span,
+                        None,
+                    )?;
+                    self.emit1(Opcode::Closure, index);
+                    let name = self.name_text(&name);
+                    self.emit1(Opcode::DefineSetter, name);
+                }
+                Property::Spread(_) => return Err(self.unsupported("Object spread", span)),
+            }
+        }
+        Ok(())
+    }
+
+    fn property_key(&mut self, key: &PropertyKey) -> Result<()> {
+        match key {
+            PropertyKey::Computed(expression) => self.expression(*expression),
+            key => {
+                let key = static_key(self.ast, key).unwrap_or_default();
+                let constant = self.constant(Value::string(&key));
+                self.emit1(Opcode::Constant, constant);
+                Ok(())
+            }
+        }
+    }
+
+    fn unary(
+        &mut self,
+        operator: &str,
+        postfix: bool,
+        operand: ExpressionId,
+        span: Span,
+    ) -> Result<()> {
+        let ast = self.ast;
+        match operator {
+            "++" | "--" => self.update(operator, postfix, operand, span)?,
+            "typeof" => match &ast[operand] {
+                Expression::Identifier(name) if self.semantic.symbol_of(operand).is_none() => {
+                    let name = self.name(name.value);
+                    self.emit1(Opcode::TypeofGlobal, name);
+                }
+                _ => {
+                    self.expression(operand)?;
+                    self.emit(Opcode::Typeof);
+                }
+            },
+            "delete" => match &ast[operand] {
+                Expression::MemberAccess {
+                    object,
+                    property,
+                    computed,
+                    ..
+                } => {
+                    self.expression(*object)?;
+                    match &ast[*property] {
+                        Expression::Identifier(name) if !computed => {
+                            let constant = self.constant(Value::string(&self.ast[name.value]));
+                            self.emit1(Opcode::Constant, constant);
+                        }
+                        _ => self.expression(*property)?,
+                    }
+                    self.emit(Opcode::DeleteProperty);
+                }
+                // Declared names can't be deleted, globals created by assignment can
+                Expression::Identifier(name) => match self.semantic.symbol_of(operand) {
+                    Some(_) => self.emit(Opcode::False),
+                    None => {
+                        let global = self.name_text("globalThis");
+                        self.emit1(Opcode::GetGlobal, global);
+                        let constant = self.constant(Value::string(&self.ast[name.value]));
+                        self.emit1(Opcode::Constant, constant);
+                        self.emit(Opcode::DeleteProperty);
+                    }
+                },
+                _ => {
+                    self.expression(operand)?;
+                    self.emit(Opcode::Pop);
+                    self.emit(Opcode::True);
+                }
+            },
+            _ => {
+                self.expression(operand)?;
+                match operator {
+                    "-" => self.emit(Opcode::Negate),
+                    "+" => self.emit(Opcode::Plus),
+                    "!" => self.emit(Opcode::Not),
+                    "~" => self.emit(Opcode::BitNot),
+                    "void" => {
+                        self.emit(Opcode::Pop);
+                        self.emit(Opcode::Undefined);
+                    }
+                    _ => unreachable!("{operator} is not a unary operator"),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // `++x`, `x--`, `a.b++` and `a[b]--`
+    fn update(
+        &mut self,
+        operator: &str,
+        postfix: bool,
+        operand: ExpressionId,
+        span: Span,
+    ) -> Result<()> {
+        let opcode = if operator == "++" {
+            Opcode::Increment
+        } else {
+            Opcode::Decrement
+        };
+        match &self.ast[operand] {
+            Expression::Identifier(name) => {
+                let symbol = self.semantic.symbol_of(operand);
+                self.check_arguments(symbol, name.span)?;
+                self.load(symbol, name.value);
+                self.emit(Opcode::ToNumeric);
+                if postfix {
+                    self.emit(Opcode::Dup);
+                }
+                self.emit(opcode);
+                self.store(symbol, name.value);
+                if postfix {
+                    self.emit(Opcode::Pop);
+                }
+            }
+            Expression::MemberAccess {
+                object,
+                property,
+                computed,
+                ..
+            } => {
+                self.expression(*object)?;
+                let named = match &self.ast[*property] {
+                    Expression::Identifier(name) if !computed => Some(name.value),
+                    _ => None,
+                };
+                match named {
+                    Some(name) => {
+                        let name = self.name(name);
+                        self.emit(Opcode::Dup);
+                        self.emit1(Opcode::GetNamed, name);
+                        self.emit(Opcode::ToNumeric);
+                        if postfix {
+                            // object old -> old object old
+                            self.emit(Opcode::Dup);
+                            self.emit(Opcode::Rot3);
+                        }
+                        self.emit(opcode);
+                        self.emit1(Opcode::SetNamed, name);
+                    }
+                    None => {
+                        self.expression(*property)?;
+                        self.emit(Opcode::Dup2);
+                        self.emit(Opcode::GetProperty);
+                        self.emit(Opcode::ToNumeric);
+                        if postfix {
+                            // object key old -> old object key old
+                            self.emit(Opcode::Dup);
+                            self.emit(Opcode::Rot4);
+                        }
+                        self.emit(opcode);
+                        self.emit(Opcode::SetProperty);
+                    }
+                }
+                if postfix {
+                    self.emit(Opcode::Pop);
+                }
+            }
+            _ => return Err(self.unsupported("Updates of this kind of target", span)),
+        }
+        Ok(())
+    }
+
+    fn binary(
+        &mut self,
+        operator: &str,
+        left: ExpressionId,
+        right: ExpressionId,
+        span: Span,
+    ) -> Result<()> {
+        let ast = self.ast;
+        let short_circuit = match operator {
+            "&&" => Some(Opcode::JumpIfFalseOrPop),
+            "||" => Some(Opcode::JumpIfTrueOrPop),
+            "??" => Some(Opcode::JumpIfNotNullishOrPop),
+            _ => None,
+        };
+        if let Some(opcode) = short_circuit {
+            self.expression(left)?;
+            let end = self.jump(opcode);
+            self.expression(right)?;
+            self.patch(end);
+            return Ok(());
+        }
+        if operator == "=" {
+            return self.assign(left, right);
+        }
+        if let Some(logical) = operator
+            .strip_suffix('=')
+            .filter(|operator| matches!(*operator, "&&" | "||" | "??"))
+        {
+            let Expression::Identifier(name) = &ast[left] else {
+                return Err(self.unsupported("Logical assignments to properties", span));
+            };
+            let symbol = self.semantic.symbol_of(left);
+            self.check_arguments(symbol, name.span)?;
+            self.load(symbol, name.value);
+            let opcode = match logical {
+                "&&" => Opcode::JumpIfFalseOrPop,
+                "||" => Opcode::JumpIfTrueOrPop,
+                _ => Opcode::JumpIfNotNullishOrPop,
+            };
+            let end = self.jump(opcode);
+            self.named_expression(right, &self.ast[name.value])?;
+            self.store(symbol, name.value);
+            self.patch(end);
+            return Ok(());
+        }
+        if let Some(opcode) = operator
+            .strip_suffix('=')
+            .and_then(binary_opcode)
+            .filter(|_| !matches!(operator, "==" | "!=" | "===" | "!==" | "<=" | ">="))
+        {
+            match &ast[left] {
+                Expression::Identifier(name) => {
+                    let symbol = self.semantic.symbol_of(left);
+                    self.check_arguments(symbol, name.span)?;
+                    self.load(symbol, name.value);
+                    self.expression(right)?;
+                    self.emit(opcode);
+                    self.store(symbol, name.value);
+                }
+                Expression::MemberAccess {
+                    object,
+                    property,
+                    computed,
+                    ..
+                } => {
+                    self.expression(*object)?;
+                    match &ast[*property] {
+                        Expression::Identifier(name) if !computed => {
+                            let name = self.name(name.value);
+                            self.emit(Opcode::Dup);
+                            self.emit1(Opcode::GetNamed, name);
+                            self.expression(right)?;
+                            self.emit(opcode);
+                            self.emit1(Opcode::SetNamed, name);
+                        }
+                        _ => {
+                            self.expression(*property)?;
+                            self.emit(Opcode::Dup2);
+                            self.emit(Opcode::GetProperty);
+                            self.expression(right)?;
+                            self.emit(opcode);
+                            self.emit(Opcode::SetProperty);
+                        }
+                    }
+                }
+                _ => return Err(self.unsupported("Assignments to this kind of target", span)),
+            }
+            return Ok(());
+        }
+        if matches!(ast[left], Expression::PrivateIdentifier(_)) {
+            return Err(self.unsupported("Private names", span));
+        }
+        let Some(opcode) = binary_opcode(operator) else {
+            return Err(self.unsupported(&format!("'{operator}' operators"), span));
+        };
+        self.expression(left)?;
+        self.expression(right)?;
+        self.emit(opcode);
+        Ok(())
+    }
+
+    // `target = value`, leaving the value on the stack
+    fn assign(&mut self, target: ExpressionId, value: ExpressionId) -> Result<()> {
+        let ast = self.ast;
+        match &ast[target] {
+            Expression::Identifier(name) => {
+                let symbol = self.semantic.symbol_of(target);
+                self.check_arguments(symbol, name.span)?;
+                self.named_expression(value, &self.ast[name.value])?;
+                self.store(symbol, name.value);
+            }
+            Expression::MemberAccess {
+                object,
+                property,
+                computed,
+                ..
+            } => {
+                self.expression(*object)?;
+                match &ast[*property] {
+                    Expression::Identifier(name) if !computed => {
+                        self.expression(value)?;
+                        let name = self.name(name.value);
+                        self.emit1(Opcode::SetNamed, name);
+                    }
+                    _ => {
+                        self.expression(*property)?;
+                        self.expression(value)?;
+                        self.emit(Opcode::SetProperty);
+                    }
+                }
+            }
+            expression => {
+                return Err(self.unsupported("Destructuring assignments", expression.span()));
+            }
+        }
+        Ok(())
+    }
+
+    // Assign the value on the stack to a `for-in` or `for-of` target and pop it
+    fn store_target(&mut self, target: ExpressionId) -> Result<()> {
+        let ast = self.ast;
+        match &ast[target] {
+            Expression::Identifier(name) => {
+                let symbol = self.semantic.symbol_of(target);
+                self.check_arguments(symbol, name.span)?;
+                self.store(symbol, name.value);
+            }
+            Expression::MemberAccess {
+                object,
+                property,
+                computed,
+                ..
+            } => {
+                self.expression(*object)?;
+                match &ast[*property] {
+                    Expression::Identifier(name) if !computed => {
+                        self.emit(Opcode::Swap);
+                        let name = self.name(name.value);
+                        self.emit1(Opcode::SetNamed, name);
+                    }
+                    _ => {
+                        // value object key -> object key value
+                        self.expression(*property)?;
+                        self.emit(Opcode::Rot3);
+                        self.emit(Opcode::Rot3);
+                        self.emit(Opcode::SetProperty);
+                    }
+                }
+            }
+            expression => {
+                return Err(self.unsupported("Destructuring assignments", expression.span()));
+            }
+        }
+        self.emit(Opcode::Pop);
+        Ok(())
+    }
+}
+
+fn is_hole(expression: &Expression) -> bool {
+    matches!(
+        expression,
+        Expression::Literal { value: LiteralToken::Undefined, span } if span.start == span.end
+    )
+}
+
+fn binary_opcode(operator: &str) -> Option<Opcode> {
+    Opcode::ALL
+        .into_iter()
+        .find(|opcode| opcode.operator() == Some(operator))
+}
+
+// The name of a property key that isn't computed
+fn static_key(ast: &Ast, key: &PropertyKey) -> Option<String> {
+    match key {
+        PropertyKey::Identifier(name) => Some(ast[name.value].to_string()),
+        PropertyKey::StringLiteral { value, .. } => Some(value.clone()),
+        PropertyKey::NumberLiteral { value, .. } => Some(match value {
+            NumberLiteral::Value(value) => number_to_string(*value),
+            NumberLiteral::NaN => "NaN".to_string(),
+            NumberLiteral::Infinity => "Infinity".to_string(),
+            NumberLiteral::BigInt(value) => value.to_string(),
+        }),
+        PropertyKey::Computed(_) => None,
+    }
+}
+
+// How an error message refers to a callee: `a.b` for a member, else the name
+fn describe_callee(ast: &Ast, id: ExpressionId) -> String {
+    match &ast[id] {
+        Expression::Identifier(name) | Expression::PrivateIdentifier(name) => {
+            ast[name.value].to_string()
+        }
+        Expression::MemberAccess {
+            object,
+            property,
+            computed: false,
+            ..
+        } => format!(
+            "{}.{}",
+            describe_callee(ast, *object),
+            describe_callee(ast, *property)
+        ),
+        Expression::MemberAccess { object, .. } => {
+            format!("{}[...]", describe_callee(ast, *object))
+        }
+        Expression::ThisExpression { .. } => "this".to_string(),
+        _ => "expression".to_string(),
+    }
+}
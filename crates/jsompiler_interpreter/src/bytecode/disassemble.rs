@@ -0,0 +1,103 @@
+use super::{CaptureFrom, Code, Opcode};
+use crate::inspect;
+use std::fmt::Write;
+
+// A listing of compiled code and the functions in it, one instruction per line:
+// its offset, opcode and operands, and what the operands refer to.
+pub fn disassemble(code: &Code) -> String {
+    let mut text = String::new();
+    function(&mut text, code, &code.name);
+    text
+}
+
+fn function(text: &mut String, code: &Code, path: &str) {
+    let mode = if code.strict { "strict" } else { "sloppy" };
+    let name = if path.is_empty() { "(anonymous)" } else { path };
+    writeln!(text, "function {name} (length {}, {mode})", code.length).unwrap();
+    if !code.locals.is_empty() {
+        let locals: Vec<String> = code
+            .locals
+            .iter()
+            .enumerate()
+            .map(|(slot, local)| match local.cell {
+                true => format!("{slot} {} (cell)", local.name),
+                false => format!("{slot} {}", local.name),
+            })
+            .collect();
+        writeln!(text, "  locals: {}", locals.join(", ")).unwrap();
+    }
+    if !code.captures.is_empty() {
+        let captures: Vec<String> = code
+            .captures
+            .iter()
+            .enumerate()
+            .map(|(index, capture)| match capture.from {
+                CaptureFrom::Local(slot) => format!("{index} {} <- local {slot}", capture.name),
+                CaptureFrom::Capture(outer) => {
+                    format!("{index} {} <- capture {outer}", capture.name)
+                }
+            })
+            .collect();
+        writeln!(text, "  captures: {}", captures.join(", ")).unwrap();
+    }
+
+    let mut offset = 0;
+    while offset < code.bytes.len() {
+        let opcode = Opcode::from_byte(code.bytes[offset]);
+        let operands: Vec<usize> = (0..opcode.operands())
+            .map(|index| code.operand(offset + 1 + index * 2))
+            .collect();
+        let listed: Vec<String> = operands.iter().map(usize::to_string).collect();
+        let line = format!(
+            "{offset:04}  {:<22}{}",
+            format!("{opcode:?}"),
+            listed.join(" ")
+        );
+        match annotation(code, opcode, &operands) {
+            Some(note) => writeln!(text, "{:<34}; {note}", line).unwrap(),
+            None => writeln!(text, "{}", line.trim_end()).unwrap(),
+        }
+        offset += 1 + operands.len() * 2;
+    }
+
+    for (index, nested) in code.functions.iter().enumerate() {
+        text.push('\n');
+        let nested_name = if nested.name.is_empty() {
+            format!("{path}/{index}")
+        } else {
+            format!("{path}/{}", nested.name)
+        };
+        function(text, nested, &nested_name);
+    }
+}
+
+// What the operands of an instruction refer to
+fn annotation(code: &Code, opcode: Opcode, operands: &[usize]) -> Option<String> {
+    use Opcode::*;
+    let first = *operands.first()?;
+    Some(match opcode {
+        Constant => inspect(&code.constants[first]),
+        GetLocal | SetLocal | InitLocal | GetCell | SetCell | InitCell | NewCell | CopyCell => {
+            code.locals[first].name.to_string()
+        }
+        GetCapture | SetCapture => code.captures[first].name.to_string(),
+        GetGlobal | SetGlobal | InitGlobal | DeclareGlobal | TypeofGlobal | ConstAssignment
+        | GetNamed | SetNamed | DefineNamed | DefineGetter | DefineSetter => {
+            code.names[first].to_string()
+        }
+        Jump
+        | JumpIfFalse
+        | JumpIfTrue
+        | JumpIfFalseOrPop
+        | JumpIfTrueOrPop
+        | JumpIfNotNullishOrPop
+        | PushHandler => format!("to {first:04}"),
+        Next => format!("{}, done to {:04}", code.locals[first].name, operands[1]),
+        Closure => match &*code.functions[first].name {
+            "" => "(anonymous)".to_string(),
+            name => name.to_string(),
+        },
+        Call => code.names[operands[1]].to_string(),
+        _ => return None,
+    })
+}
@@ -0,0 +1,357 @@
+mod compiler;
+mod disassemble;
+mod vm;
+
+pub use compiler::compile;
+pub use disassemble::disassemble;
+
+use crate::object::FunctionKind;
+use jsompiler_runtime::Value;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+// One instruction is an opcode byte followed by its operands, each a little-endian `u16`.
+// Jump targets are offsets into the code of the same function, and a name is an index into
+// its names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Opcode {
+    Constant, // constant
+    Undefined,
+    Null,
+    True,
+    False,
+    This,
+    Callee, // The function being run, for the name of a function expression
+    Pop,
+    Dup,
+    Dup2,
+    Swap,
+    Rot3, // a b c -> c a b
+    Rot4, // a b c d -> d a b c
+
+    GetLocal,        // slot
+    SetLocal,        // slot
+    InitLocal,       // slot
+    GetCell,         // slot
+    SetCell,         // slot
+    InitCell,        // slot
+    NewCell,         // slot, an uninitialized cell for a scope that is entered again
+    CopyCell,        // slot, a cell with the same value for the next iteration of a loop
+    GetCapture,      // capture
+    SetCapture,      // capture
+    GetGlobal,       // name
+    SetGlobal,       // name
+    InitGlobal,      // name
+    DeclareGlobal,   // name, for `var`
+    TypeofGlobal,    // name, which is "undefined" for names nothing declares
+    ConstAssignment, // name
+
+    GetNamed, // name
+    SetNamed, // name
+    GetProperty,
+    SetProperty,
+    DeleteProperty,
+    Array, // count
+    ArrayAppend,
+    ArraySpread,
+    ArrayHole, // Adds one to the length without an element
+    Object,
+    DefineNamed,  // name
+    DefineGetter, // name
+    DefineSetter, // name
+    DefineProperty,
+
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+    Exponent,
+    BitAnd,
+    BitOr,
+    BitXor,
+    ShiftLeft,
+    ShiftRight,
+    UnsignedShiftRight,
+    Equal,
+    NotEqual,
+    StrictEqual,
+    StrictNotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    In,
+    InstanceOf,
+    Negate,
+    Plus,
+    Not,
+    BitNot,
+    Typeof,
+    ToNumeric,
+    ToString,
+    Increment,
+    Decrement,
+
+    Jump,                  // target
+    JumpIfFalse,           // target
+    JumpIfTrue,            // target
+    JumpIfFalseOrPop,      // target, keeping the value if it jumps
+    JumpIfTrueOrPop,       // target, keeping the value if it jumps
+    JumpIfNotNullishOrPop, // target, keeping the value if it jumps
+    EnumerateKeys,
+    IterateValues,
+    Next, // slot, target: the next value of the iteration in the slot and the one after it
+
+    Closure,   // function
+    Call,      // argument count, name describing the callee for errors
+    Argument,  // index
+    Arguments, // index, the rest of the arguments from it as an array
+    Return,
+    Throw,
+    Rethrow,     // Throw again from a `finally` block, keeping where it was first thrown
+    PushHandler, // target
+    PopHandler,
+    Completion,
+}
+
+impl Opcode {
+    // Every opcode, in the order of their bytes
+    pub const ALL: [Opcode; 93] = {
+        use Opcode::*;
+        [
+            Constant,
+            Undefined,
+            Null,
+            True,
+            False,
+            This,
+            Callee,
+            Pop,
+            Dup,
+            Dup2,
+            Swap,
+            Rot3,
+            Rot4,
+            GetLocal,
+            SetLocal,
+            InitLocal,
+            GetCell,
+            SetCell,
+            InitCell,
+            NewCell,
+            CopyCell,
+            GetCapture,
+            SetCapture,
+            GetGlobal,
+            SetGlobal,
+            InitGlobal,
+            DeclareGlobal,
+            TypeofGlobal,
+            ConstAssignment,
+            GetNamed,
+            SetNamed,
+            GetProperty,
+            SetProperty,
+            DeleteProperty,
+            Array,
+            ArrayAppend,
+            ArraySpread,
+            ArrayHole,
+            Object,
+            DefineNamed,
+            DefineGetter,
+            DefineSetter,
+            DefineProperty,
+            Add,
+            Subtract,
+            Multiply,
+            Divide,
+            Remainder,
+            Exponent,
+            BitAnd,
+            BitOr,
+            BitXor,
+            ShiftLeft,
+            ShiftRight,
+            UnsignedShiftRight,
+            Equal,
+            NotEqual,
+            StrictEqual,
+            StrictNotEqual,
+            Less,
+            LessEqual,
+            Greater,
+            GreaterEqual,
+            In,
+            InstanceOf,
+            Negate,
+            Plus,
+            Not,
+            BitNot,
+            Typeof,
+            ToNumeric,
+            ToString,
+            Increment,
+            Decrement,
+            Jump,
+            JumpIfFalse,
+            JumpIfTrue,
+            JumpIfFalseOrPop,
+            JumpIfTrueOrPop,
+            JumpIfNotNullishOrPop,
+            EnumerateKeys,
+            IterateValues,
+            Next,
+            Closure,
+            Call,
+            Argument,
+            Arguments,
+            Return,
+            Throw,
+            Rethrow,
+            PushHandler,
+            PopHandler,
+            Completion,
+        ]
+    };
+
+    pub fn from_byte(byte: u8) -> Opcode {
+        Opcode::ALL[byte as usize]
+    }
+
+    pub fn operands(self) -> usize {
+        use Opcode::*;
+        match self {
+            Constant
+            | GetLocal
+            | SetLocal
+            | InitLocal
+            | GetCell
+            | SetCell
+            | InitCell
+            | NewCell
+            | CopyCell
+            | GetCapture
+            | SetCapture
+            | GetGlobal
+            | SetGlobal
+            | InitGlobal
+            | DeclareGlobal
+            | TypeofGlobal
+            | ConstAssignment
+            | GetNamed
+            | SetNamed
+            | Array
+            | DefineNamed
+            | DefineGetter
+            | DefineSetter
+            | Jump
+            | JumpIfFalse
+            | JumpIfTrue
+            | JumpIfFalseOrPop
+            | JumpIfTrueOrPop
+            | JumpIfNotNullishOrPop
+            | Closure
+            | Argument
+            | Arguments
+            | PushHandler => 1,
+            Next | Call => 2,
+            _ => 0,
+        }
+    }
+
+    // The operator of the binary operation this opcode does
+    pub fn operator(self) -> Option<&'static str> {
+        use Opcode::*;
+        Some(match self {
+            Add => "+",
+            Subtract => "-",
+            Multiply => "*",
+            Divide => "/",
+            Remainder => "%",
+            Exponent => "**",
+            BitAnd => "&",
+            BitOr => "|",
+            BitXor => "^",
+            ShiftLeft => "<<",
+            ShiftRight => ">>",
+            UnsignedShiftRight => ">>>",
+            Equal => "==",
+            NotEqual => "!=",
+            StrictEqual => "===",
+            StrictNotEqual => "!==",
+            Less => "<",
+            LessEqual => "<=",
+            Greater => ">",
+            GreaterEqual => ">=",
+            In => "in",
+            InstanceOf => "instanceof",
+            _ => return None,
+        })
+    }
+}
+
+// A compiled function, or the top level of a script
+#[derive(Debug, Default)]
+pub struct Code {
+    pub name: Rc<str>,
+    pub kind: FunctionKind,
+    pub strict: bool,
+    pub length: usize, // Parameters before the first default or rest parameter
+    pub bytes: Vec<u8>,
+    pub constants: Vec<Value>,
+    pub names: Vec<Rc<str>>, // Names of variables and properties
+    pub functions: Vec<Rc<Code>>,
+    pub locals: Vec<Local>,
+    pub captures: Vec<Capture>,
+    pub positions: Vec<(usize, usize)>, // Source position of the statement starting at an offset
+}
+
+impl Code {
+    pub fn operand(&self, offset: usize) -> usize {
+        u16::from_le_bytes([self.bytes[offset], self.bytes[offset + 1]]) as usize
+    }
+
+    // Where in the source the instruction at `offset` comes from
+    pub fn position(&self, offset: usize) -> usize {
+        match self
+            .positions
+            .partition_point(|(start, _)| *start <= offset)
+        {
+            0 => 0,
+            index => self.positions[index - 1].1,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Local {
+    pub name: Rc<str>,
+    pub cell: bool, // Captured by a closure, so the slot holds a cell the closure shares
+    pub hoisted: bool, // `var`, functions and parameters, which start out `undefined`
+}
+
+// A binding of an enclosing function that a closure keeps alive
+#[derive(Debug, Clone)]
+pub struct Capture {
+    pub name: Rc<str>,
+    pub from: CaptureFrom,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureFrom {
+    Local(usize),   // A cell slot of the enclosing function
+    Capture(usize), // A capture of the enclosing function
+}
+
+// A binding shared between a function and its closures. `None` until it is initialized.
+pub type Cell = Rc<RefCell<Option<Value>>>;
+
+// A closure over compiled code
+pub struct CompiledFunction {
+    pub code: Rc<Code>,
+    pub captures: Rc<[Cell]>,
+    pub this: Option<Value>, // The `this` an arrow function was created with
+}
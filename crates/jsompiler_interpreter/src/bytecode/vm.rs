@@ -0,0 +1,768 @@
+use super::{CaptureFrom, Cell, Code, CompiledFunction, Opcode};
+use crate::builtins::IteratorRecord;
+use crate::object::{Function, FunctionKind, function_of};
+use crate::operations::Numeric;
+use crate::{Interpreter, MAX_CALL_DEPTH};
+use jsompiler_runtime::{
+    Object, ObjectKind, ObjectRef, Property, PropertyKey, Value, allocate, collect_if_needed,
+};
+use num_bigint::BigInt;
+use std::rc::Rc;
+
+type Result<T> = std::result::Result<T, Value>;
+
+// A call of compiled code being run
+struct Frame {
+    function: Value, // The function object, `undefined` for a script
+    code: Rc<Code>,
+    captures: Rc<[Cell]>,
+    this: Value,
+    arguments: Vec<Value>,
+    slots: Vec<Slot>,
+    ip: usize,
+    mark: usize, // The next entry of `code.positions`, to track the statement being run
+    base: usize, // Height of the stack when the call started
+    handlers: Vec<(usize, usize)>, // Target and stack height of the `try` blocks entered
+}
+
+enum Slot {
+    Value(Option<Value>), // `None` until a `let`, `const` or `class` is initialized
+    Cell(Cell),
+}
+
+// The frames and operand stack of one entry into the VM. Calls between compiled functions
+// push frames instead of recursing, other calls go through `Interpreter::call`.
+#[derive(Default)]
+struct Machine {
+    stack: Vec<Value>,
+    frames: Vec<Frame>,
+}
+
+fn pop(stack: &mut Vec<Value>) -> Value {
+    stack.pop().expect("the compiler balances the stack")
+}
+
+fn peek(stack: &[Value]) -> &Value {
+    stack.last().expect("the compiler balances the stack")
+}
+
+fn new_cell(value: Option<Value>) -> Cell {
+    allocate(value)
+}
+
+impl Frame {
+    fn new(
+        function: Value,
+        code: Rc<Code>,
+        captures: Rc<[Cell]>,
+        this: Value,
+        arguments: Vec<Value>,
+        base: usize,
+    ) -> Self {
+        let slots = code
+            .locals
+            .iter()
+            .map(|local| {
+                let value = local.hoisted.then_some(Value::Undefined);
+                if local.cell {
+                    Slot::Cell(new_cell(value))
+                } else {
+                    Slot::Value(value)
+                }
+            })
+            .collect();
+        Frame {
+            function,
+            code,
+            captures,
+            this,
+            arguments,
+            slots,
+            ip: 0,
+            mark: 0,
+            base,
+            handlers: Vec::new(),
+        }
+    }
+
+    fn operand(&mut self) -> usize {
+        let operand = self.code.operand(self.ip);
+        self.ip += 2;
+        operand
+    }
+
+    fn name(&mut self) -> Rc<str> {
+        let index = self.operand();
+        self.code.names[index].clone()
+    }
+
+    fn cell(&self, slot: usize) -> &Cell {
+        match &self.slots[slot] {
+            Slot::Cell(cell) => cell,
+            Slot::Value(_) => unreachable!("cell instructions are used on cell slots"),
+        }
+    }
+}
+
+impl Interpreter {
+    // Run a compiled script. An exception nothing catches is returned as the thrown value.
+    pub fn execute(&mut self, code: Rc<Code>) -> Result<Value> {
+        self.strict = code.strict;
+        self.depth = 0;
+        self.completion = Value::Undefined;
+        let this = Value::Object(self.global.clone());
+        let frame = Frame::new(Value::Undefined, code, Rc::from([]), this, Vec::new(), 0);
+        self.run_machine(frame)?;
+        Ok(std::mem::replace(&mut self.completion, Value::Undefined))
+    }
+
+    // A function object for compiled code
+    pub(crate) fn compiled_function(&mut self, function: CompiledFunction) -> ObjectRef {
+        let (name, length, kind) = (
+            function.code.name.clone(),
+            function.code.length,
+            function.code.kind,
+        );
+        let mut object = Object::new(
+            Some(self.intrinsics.function_prototype.clone()),
+            ObjectKind::Function(Box::new(Function::Compiled(function))),
+        );
+        object.define("length", Property::read_only(Value::Number(length as f64)));
+        object.define("name", Property::read_only(Value::String(name)));
+        let function = ObjectRef::new(object);
+        if kind == FunctionKind::Normal {
+            let prototype = self.new_object();
+            prototype
+                .borrow_mut()
+                .define("constructor", Property::hidden(function.clone().into()));
+            let prototype = Property::new(prototype.into(), true, false, false);
+            function.borrow_mut().define("prototype", prototype);
+        }
+        function
+    }
+
+    // Call a compiled function from outside the VM, as built-ins and getters do
+    pub(crate) fn call_compiled(
+        &mut self,
+        function: &ObjectRef,
+        this: Value,
+        args: &[Value],
+    ) -> Result<Value> {
+        let (code, captures, this) = match function_of(function).as_deref() {
+            Some(Function::Compiled(compiled)) => (
+                compiled.code.clone(),
+                compiled.captures.clone(),
+                compiled.this.clone().unwrap_or(this),
+            ),
+            _ => unreachable!("only compiled functions are run by the VM"),
+        };
+        if self.depth >= MAX_CALL_DEPTH {
+            return Err(self.range_error("Maximum call stack size exceeded"));
+        }
+        let this = self.function_this(&code, this);
+        let function = Value::Object(function.clone());
+        let frame = Frame::new(function, code, captures, this, args.to_vec(), 0);
+        self.depth += 1;
+        let result = self.run_machine(frame);
+        self.depth -= 1;
+        result
+    }
+
+    // Sloppy mode functions called without a `this` get the global object
+    fn function_this(&self, code: &Code, this: Value) -> Value {
+        match this {
+            Value::Undefined | Value::Null if !code.strict => Value::Object(self.global.clone()),
+            this => this,
+        }
+    }
+
+    fn run_machine(&mut self, frame: Frame) -> Result<Value> {
+        let outer_environment =
+            std::mem::replace(&mut self.environment, self.global_environment.clone());
+        let outer_strict = self.strict;
+        let position = self.position;
+        self.strict = frame.code.strict;
+        let mut machine = Machine::default();
+        machine.frames.push(frame);
+        let result = self.run_frames(&mut machine);
+        self.environment = outer_environment;
+        self.strict = outer_strict;
+        self.position = position;
+        result
+    }
+
+    // Run until the first frame returns, unwinding to the closest handler on exceptions
+    fn run_frames(&mut self, machine: &mut Machine) -> Result<Value> {
+        loop {
+            let exception = match self.run_instructions(machine) {
+                Ok(value) => return Ok(value),
+                Err(exception) => exception,
+            };
+            loop {
+                let frame = machine.frames.last_mut().expect("code runs in a frame");
+                if let Some((target, height)) = frame.handlers.pop() {
+                    frame.ip = target;
+                    machine.stack.truncate(height);
+                    machine.stack.push(exception);
+                    self.seek(frame);
+                    break;
+                }
+                if machine.frames.len() == 1 {
+                    return Err(exception);
+                }
+                machine.frames.pop();
+                self.depth -= 1;
+                let frame = machine.frames.last_mut().expect("code runs in a frame");
+                self.strict = frame.code.strict;
+                let call = frame.ip - 1;
+                self.position = frame.code.position(call);
+            }
+        }
+    }
+
+    // Point the statement tracking at the instruction a frame goes to next
+    fn seek(&mut self, frame: &mut Frame) {
+        let positions = &frame.code.positions;
+        frame.mark = positions.partition_point(|(start, _)| *start <= frame.ip);
+        self.position = frame.code.position(frame.ip);
+    }
+
+    fn jump(&mut self, frame: &mut Frame, target: usize) {
+        // Jumping back is a loop, which can make garbage without calling anything
+        if target < frame.ip {
+            collect_if_needed();
+        }
+        frame.ip = target;
+        self.seek(frame);
+    }
+
+    fn uninitialized(&mut self, name: &str) -> Value {
+        let message = format!("Cannot access '{name}' before initialization");
+        self.reference_error(&message)
+    }
+
+    fn run_instructions(&mut self, machine: &mut Machine) -> Result<Value> {
+        let Machine { stack, frames } = machine;
+        loop {
+            let frame = frames.last_mut().expect("code runs in a frame");
+            if let Some(&(start, position)) = frame.code.positions.get(frame.mark)
+                && start == frame.ip
+            {
+                self.position = position;
+                frame.mark += 1;
+            }
+            let opcode = Opcode::from_byte(frame.code.bytes[frame.ip]);
+            frame.ip += 1;
+            match opcode {
+                Opcode::Constant => {
+                    let index = frame.operand();
+                    let value = frame.code.constants[index].clone();
+                    stack.push(value);
+                }
+                Opcode::Undefined => stack.push(Value::Undefined),
+                Opcode::Null => stack.push(Value::Null),
+                Opcode::True => stack.push(Value::Boolean(true)),
+                Opcode::False => stack.push(Value::Boolean(false)),
+                Opcode::This => {
+                    let this = frame.this.clone();
+                    stack.push(this);
+                }
+                Opcode::Callee => {
+                    let function = frame.function.clone();
+                    stack.push(function);
+                }
+                Opcode::Pop => {
+                    pop(stack);
+                }
+                Opcode::Dup => stack.push(peek(stack).clone()),
+                Opcode::Dup2 => {
+                    let length = stack.len();
+                    stack.extend_from_within(length - 2..);
+                }
+                Opcode::Swap => {
+                    let length = stack.len();
+                    stack.swap(length - 1, length - 2);
+                }
+                Opcode::Rot3 => {
+                    let length = stack.len();
+                    stack[length - 3..].rotate_right(1);
+                }
+                Opcode::Rot4 => {
+                    let length = stack.len();
+                    stack[length - 4..].rotate_right(1);
+                }
+
+                Opcode::GetLocal => {
+                    let slot = frame.operand();
+                    let value = match &frame.slots[slot] {
+                        Slot::Value(value) => value.clone(),
+                        Slot::Cell(cell) => cell.borrow().clone(),
+                    };
+                    match value {
+                        Some(value) => stack.push(value),
+                        None => return Err(self.uninitialized(&frame.code.locals[slot].name)),
+                    }
+                }
+                Opcode::SetLocal => {
+                    let slot = frame.operand();
+                    match &mut frame.slots[slot] {
+                        Slot::Value(value @ Some(_)) => *value = Some(peek(stack).clone()),
+                        _ => return Err(self.uninitialized(&frame.code.locals[slot].name)),
+                    }
+                }
+                Opcode::InitLocal => {
+                    let slot = frame.operand();
+                    let value = stack.pop();
+                    frame.slots[slot] = Slot::Value(value);
+                }
+                Opcode::GetCell => {
+                    let slot = frame.operand();
+                    let value = frame.cell(slot).borrow().clone();
+                    match value {
+                        Some(value) => stack.push(value),
+                        None => return Err(self.uninitialized(&frame.code.locals[slot].name)),
+                    }
+                }
+                Opcode::SetCell => {
+                    let slot = frame.operand();
+                    let mut cell = frame.cell(slot).borrow_mut();
+                    match &mut *cell {
+                        Some(value) => *value = stack.last().cloned().unwrap_or(Value::Undefined),
+                        None => {
+                            drop(cell);
+                            return Err(self.uninitialized(&frame.code.locals[slot].name));
+                        }
+                    }
+                }
+                Opcode::InitCell => {
+                    let slot = frame.operand();
+                    let value = stack.pop();
+                    *frame.cell(slot).borrow_mut() = value;
+                }
+                Opcode::NewCell => {
+                    let slot = frame.operand();
+                    let value = frame.code.locals[slot].hoisted.then_some(Value::Undefined);
+                    frame.slots[slot] = Slot::Cell(new_cell(value));
+                }
+                Opcode::CopyCell => {
+                    let slot = frame.operand();
+                    let value = frame.cell(slot).borrow().clone();
+                    frame.slots[slot] = Slot::Cell(new_cell(value));
+                }
+                Opcode::GetCapture => {
+                    let index = frame.operand();
+                    let value = frame.captures[index].borrow().clone();
+                    match value {
+                        Some(value) => stack.push(value),
+                        None => return Err(self.uninitialized(&frame.code.captures[index].name)),
+                    }
+                }
+                Opcode::SetCapture => {
+                    let index = frame.operand();
+                    let mut cell = frame.captures[index].borrow_mut();
+                    match &mut *cell {
+                        Some(value) => *value = stack.last().cloned().unwrap_or(Value::Undefined),
+                        None => {
+                            drop(cell);
+                            return Err(self.uninitialized(&frame.code.captures[index].name));
+                        }
+                    }
+                }
+                Opcode::GetGlobal => {
+                    let name = self.atoms.intern(&frame.name());
+                    let value = self.lookup(name)?;
+                    stack.push(value);
+                }
+                Opcode::SetGlobal => {
+                    let name = self.atoms.intern(&frame.name());
+                    self.assign(name, peek(stack).clone())?;
+                }
+                Opcode::InitGlobal => {
+                    let name = frame.name();
+                    let value = pop(stack);
+                    let key = PropertyKey::from(&*name);
+                    self.global.borrow_mut().set_own(key, value);
+                }
+                Opcode::DeclareGlobal => {
+                    let name = frame.name();
+                    let key = PropertyKey::from(&*name);
+                    if !self.global.borrow().has_own(&key) {
+                        let property = Property::new(Value::Undefined, true, true, false);
+                        self.global.borrow_mut().define(key, property);
+                    }
+                }
+                Opcode::TypeofGlobal => {
+                    // A name nothing declares is "undefined" rather than an error
+                    let name = self.atoms.intern(&frame.name());
+                    let type_of = match self.resolve(name) {
+                        Some(_) => self.lookup(name)?.type_of(),
+                        None => "undefined",
+                    };
+                    stack.push(Value::string(type_of));
+                }
+                Opcode::ConstAssignment => {
+                    frame.operand();
+                    return Err(self.type_error("Assignment to constant variable."));
+                }
+
+                Opcode::GetNamed => {
+                    let name = frame.name();
+                    let object = pop(stack);
+                    let value = self.get_property(&object, &PropertyKey::from(&*name))?;
+                    stack.push(value);
+                }
+                Opcode::SetNamed => {
+                    let name = frame.name();
+                    let value = pop(stack);
+                    let object = pop(stack);
+                    let key = PropertyKey::from(&*name);
+                    self.set_property(&object, &key, value.clone())?;
+                    stack.push(value);
+                }
+                Opcode::GetProperty => {
+                    let key = pop(stack);
+                    let object = pop(stack);
+                    let key = self.to_property_key(&key)?;
+                    let value = self.get_property(&object, &key)?;
+                    stack.push(value);
+                }
+                Opcode::SetProperty => {
+                    let value = pop(stack);
+                    let key = pop(stack);
+                    let object = pop(stack);
+                    let key = self.to_property_key(&key)?;
+                    self.set_property(&object, &key, value.clone())?;
+                    stack.push(value);
+                }
+                Opcode::DeleteProperty => {
+                    let key = pop(stack);
+                    let object = pop(stack);
+                    let key = self.to_property_key(&key)?;
+                    let deleted = self.delete_property(&object, &key)?;
+                    stack.push(Value::Boolean(deleted));
+                }
+                Opcode::Array => {
+                    let count = frame.operand();
+                    let elements = stack.split_off(stack.len() - count);
+                    stack.push(Value::Object(self.new_array(elements)));
+                }
+                Opcode::ArrayAppend => {
+                    let value = pop(stack);
+                    if let Value::Object(array) = peek(stack) {
+                        push_element(array, value);
+                    }
+                }
+                Opcode::ArraySpread => {
+                    let iterable = pop(stack);
+                    let values = self.iterate(&iterable)?;
+                    if let Value::Object(array) = peek(stack) {
+                        for value in values {
+                            push_element(array, value);
+                        }
+                    }
+                }
+                Opcode::ArrayHole => {
+                    if let Value::Object(array) = peek(stack) {
+                        let mut array = array.borrow_mut();
+                        let length = array.array_length().unwrap_or_default();
+                        array.set_own("length".into(), Value::Number(length as f64 + 1.0));
+                    }
+                }
+                Opcode::Object => stack.push(Value::Object(self.new_object())),
+                Opcode::DefineNamed => {
+                    let name = frame.name();
+                    let value = pop(stack);
+                    let Value::Object(object) = peek(stack) else {
+                        unreachable!("properties are defined on object literals")
+                    };
+                    // `__proto__: value` sets the prototype
+                    if &*name == "__proto__" {
+                        match value {
+                            Value::Object(prototype) => {
+                                object.borrow_mut().prototype = Some(prototype)
+                            }
+                            Value::Null => object.borrow_mut().prototype = None,
+                            _ => {}
+                        }
+                    } else {
+                        let key = PropertyKey::from(&*name);
+                        object.borrow_mut().define(key, Property::data(value));
+                    }
+                }
+                Opcode::DefineGetter | Opcode::DefineSetter => {
+                    let name = frame.name();
+                    let Value::Object(function) = pop(stack) else {
+                        unreachable!("accessors are functions")
+                    };
+                    let Value::Object(object) = peek(stack).clone() else {
+                        unreachable!("properties are defined on object literals")
+                    };
+                    let is_getter = opcode == Opcode::DefineGetter;
+                    let key = PropertyKey::from(&*name);
+                    self.define_accessor(&object, &key, function, is_getter, true);
+                }
+                Opcode::DefineProperty => {
+                    let value = pop(stack);
+                    let key = pop(stack);
+                    let key = self.to_property_key(&key)?;
+                    let Value::Object(object) = peek(stack) else {
+                        unreachable!("properties are defined on object literals")
+                    };
+                    object.borrow_mut().define(key, Property::data(value));
+                }
+
+                Opcode::Negate | Opcode::BitNot | Opcode::Increment | Opcode::Decrement => {
+                    let value = pop(stack);
+                    let value = match self.to_numeric(&value)? {
+                        Numeric::Number(value) => Value::Number(match opcode {
+                            Opcode::Negate => -value,
+                            Opcode::BitNot => !crate::number::to_int32(value) as f64,
+                            Opcode::Increment => value + 1.0,
+                            _ => value - 1.0,
+                        }),
+                        Numeric::BigInt(value) => Value::bigint(match opcode {
+                            Opcode::Negate => -&*value,
+                            Opcode::BitNot => !&*value,
+                            Opcode::Increment => &*value + BigInt::from(1),
+                            _ => &*value - BigInt::from(1),
+                        }),
+                    };
+                    stack.push(value);
+                }
+                Opcode::Plus => {
+                    let value = pop(stack);
+                    let number = self.to_number(&value)?;
+                    stack.push(Value::Number(number));
+                }
+                Opcode::Not => {
+                    let value = pop(stack);
+                    stack.push(Value::Boolean(!value.to_boolean()));
+                }
+                Opcode::Typeof => {
+                    let value = pop(stack);
+                    stack.push(Value::string(value.type_of()));
+                }
+                Opcode::ToNumeric => {
+                    let value = pop(stack);
+                    let value = match self.to_numeric(&value)? {
+                        Numeric::Number(value) => Value::Number(value),
+                        Numeric::BigInt(value) => Value::BigInt(value),
+                    };
+                    stack.push(value);
+                }
+                Opcode::ToString => {
+                    let value = pop(stack);
+                    let text = self.to_string(&value)?;
+                    stack.push(Value::String(text));
+                }
+
+                Opcode::Jump => {
+                    let target = frame.operand();
+                    self.jump(frame, target);
+                }
+                Opcode::JumpIfFalse | Opcode::JumpIfTrue => {
+                    let target = frame.operand();
+                    let value = pop(stack);
+                    if value.to_boolean() == (opcode == Opcode::JumpIfTrue) {
+                        self.jump(frame, target);
+                    }
+                }
+                Opcode::JumpIfFalseOrPop
+                | Opcode::JumpIfTrueOrPop
+                | Opcode::JumpIfNotNullishOrPop => {
+                    let target = frame.operand();
+                    let value = stack.last().expect("the compiler balances the stack");
+                    let jumps = match opcode {
+                        Opcode::JumpIfFalseOrPop => !value.to_boolean(),
+                        Opcode::JumpIfTrueOrPop => value.to_boolean(),
+                        _ => !value.is_nullish(),
+                    };
+                    if jumps {
+                        self.jump(frame, target);
+                    } else {
+                        stack.pop();
+                    }
+                }
+                Opcode::EnumerateKeys => {
+                    let value = pop(stack);
+                    let keys = self.enumerate_keys(&value);
+                    let keys = keys.iter().map(|key| key.to_value()).collect();
+                    stack.push(Value::Object(self.new_array(keys)));
+                }
+                Opcode::IterateValues => {
+                    // Arrays are read as the loop goes, so it sees elements added to them, and
+                    // other objects are stepped through their iterator. Strings are read first.
+                    let value = pop(stack);
+                    let values = if self.list_length(&value)?.is_some() {
+                        value
+                    } else if matches!(value, Value::Object(_)) {
+                        self.iterator_object(&value)?
+                    } else {
+                        let values = self.iterate(&value)?;
+                        Value::Object(self.new_array(values))
+                    };
+                    stack.push(values);
+                }
+                Opcode::Next => {
+                    let slot = frame.operand();
+                    let target = frame.operand();
+                    let (Slot::Value(Some(Value::Object(values))), Slot::Value(Some(state))) =
+                        (&frame.slots[slot], &frame.slots[slot + 1])
+                    else {
+                        unreachable!("iterations keep their values and index in two slots")
+                    };
+                    let (values, state) = (values.clone(), state.clone());
+                    let list = Value::Object(values.clone());
+                    match (self.list_length(&list)?, state) {
+                        (Some(length), Value::Number(index)) => {
+                            let index = index as u32;
+                            if index < length {
+                                let value = self.get(&values, &PropertyKey::Index(index), &list)?;
+                                frame.slots[slot + 1] =
+                                    Slot::Value(Some(Value::Number((index + 1) as f64)));
+                                stack.push(value);
+                            } else {
+                                self.jump(frame, target);
+                            }
+                        }
+                        // An iterator keeps its `next` method in place of the index
+                        (_, state) => {
+                            let next = match state {
+                                Value::Number(_) => self.get_property(&list, &"next".into())?,
+                                next => next,
+                            };
+                            frame.slots[slot + 1] = Slot::Value(Some(next.clone()));
+                            let mut iterator = IteratorRecord::new(list, next);
+                            match self.iterator_step(&mut iterator)? {
+                                Some(value) => stack.push(value),
+                                None => self.jump(frame, target),
+                            }
+                        }
+                    }
+                }
+
+                Opcode::Closure => {
+                    let index = frame.operand();
+                    let code = frame.code.functions[index].clone();
+                    let captures = code
+                        .captures
+                        .iter()
+                        .map(|capture| match capture.from {
+                            CaptureFrom::Local(slot) => frame.cell(slot).clone(),
+                            CaptureFrom::Capture(index) => frame.captures[index].clone(),
+                        })
+                        .collect();
+                    let this = (code.kind == FunctionKind::Arrow).then(|| frame.this.clone());
+                    let function = self.compiled_function(CompiledFunction {
+                        code,
+                        captures,
+                        this,
+                    });
+                    stack.push(Value::Object(function));
+                }
+                Opcode::Call => {
+                    let count = frame.operand();
+                    let description = frame.name();
+                    let args = stack.split_off(stack.len() - count);
+                    let this = pop(stack);
+                    let callee = pop(stack);
+                    let compiled = match &callee {
+                        Value::Object(object) => match function_of(object).as_deref() {
+                            Some(Function::Compiled(function)) => Some((
+                                function.code.clone(),
+                                function.captures.clone(),
+                                function.this.clone(),
+                            )),
+                            _ => None,
+                        },
+                        _ => None,
+                    };
+                    match compiled {
+                        Some((code, captures, arrow_this)) => {
+                            collect_if_needed();
+                            if self.depth >= MAX_CALL_DEPTH {
+                                return Err(self.range_error("Maximum call stack size exceeded"));
+                            }
+                            let this = match arrow_this {
+                                Some(this) => this,
+                                None => self.function_this(&code, this),
+                            };
+                            self.strict = code.strict;
+                            let base = stack.len();
+                            let mut frame = Frame::new(callee, code, captures, this, args, base);
+                            self.seek(&mut frame);
+                            frames.push(frame);
+                            self.depth += 1;
+                        }
+                        None if callee.as_function().is_none() => {
+                            let message = format!("{description} is not a function");
+                            return Err(self.type_error(&message));
+                        }
+                        None => {
+                            let value = self.call(&callee, this, &args)?;
+                            stack.push(value);
+                        }
+                    }
+                }
+                Opcode::Argument => {
+                    let index = frame.operand();
+                    let value = frame
+                        .arguments
+                        .get(index)
+                        .cloned()
+                        .unwrap_or(Value::Undefined);
+                    stack.push(value);
+                }
+                Opcode::Arguments => {
+                    let index = frame.operand();
+                    let rest = frame.arguments.get(index..).unwrap_or_default().to_vec();
+                    stack.push(Value::Object(self.new_array(rest)));
+                }
+                Opcode::Return => {
+                    let value = pop(stack);
+                    if frames.len() == 1 {
+                        return Ok(value);
+                    }
+                    let frame = frames.pop().expect("code runs in a frame");
+                    stack.truncate(frame.base);
+                    stack.push(value);
+                    self.depth -= 1;
+                    let frame = frames.last_mut().expect("code runs in a frame");
+                    self.strict = frame.code.strict;
+                    let call = frame.ip - 1;
+                    self.position = frame.code.position(call);
+                }
+                Opcode::Throw => {
+                    let exception = pop(stack);
+                    self.thrown_at = self.position;
+                    return Err(exception);
+                }
+                Opcode::Rethrow => return Err(pop(stack)),
+                Opcode::PushHandler => {
+                    let target = frame.operand();
+                    frame.handlers.push((target, stack.len()));
+                }
+                Opcode::PopHandler => {
+                    frame.handlers.pop();
+                }
+                Opcode::Completion => {
+                    self.completion = pop(stack);
+                }
+                opcode => {
+                    let operator = opcode.operator().expect("the rest are binary operators");
+                    let right = pop(stack);
+                    let left = pop(stack);
+                    let value = self.binary_operation(operator, &left, &right)?;
+                    stack.push(value);
+                }
+            }
+        }
+    }
+}
+
+// Add an element at the end of an array literal being built
+fn push_element(array: &ObjectRef, value: Value) {
+    let mut array = array.borrow_mut();
+    let length = array.array_length().unwrap_or_default();
+    array.define(PropertyKey::Index(length), Property::data(value));
+}
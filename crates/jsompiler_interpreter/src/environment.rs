@@ -0,0 +1,84 @@
+use jsompiler_common::Atom;
+use jsompiler_runtime::{ObjectRef, Trace, Tracer, Value, allocate};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+pub type EnvRef = Rc<RefCell<Environment>>;
+
+// A scope at run time. Names are looked up in `bindings` first, then as properties of
+// `object`, which is the global object at the top level and the object of a `with` statement.
+#[derive(Debug, Default)]
+pub struct Environment {
+    bindings: HashMap<Atom, Binding>,
+    pub parent: Option<EnvRef>,
+    pub object: Option<ObjectRef>,
+    pub this: Option<Value>, // Set for functions other than arrows, and the top level
+    pub var_scope: bool,     // Where `var` declarations go: a function or the top level
+}
+
+#[derive(Debug, Clone)]
+pub struct Binding {
+    pub value: Option<Value>, // `None` until a `let`, `const` or `class` is initialized
+    pub mutable: bool,
+}
+
+impl Environment {
+    pub fn new(parent: Option<EnvRef>) -> EnvRef {
+        allocate(Environment {
+            parent,
+            ..Default::default()
+        })
+    }
+
+    pub fn get(&self, name: Atom) -> Option<&Binding> {
+        self.bindings.get(&name)
+    }
+
+    pub fn has(&self, name: Atom) -> bool {
+        self.bindings.contains_key(&name)
+    }
+
+    pub fn declare(&mut self, name: Atom, value: Option<Value>, mutable: bool) {
+        self.bindings.insert(name, Binding { value, mutable });
+    }
+
+    // Set the value of a binding, which initializes it if it wasn't yet
+    pub fn set(&mut self, name: Atom, value: Value) {
+        if let Some(binding) = self.bindings.get_mut(&name) {
+            binding.value = Some(value);
+        }
+    }
+
+    // Copy the bindings into a new environment with the same parent, so closures created
+    // in one iteration of a `for (let ...)` loop don't see the next iteration's values
+    pub fn copy(&self) -> EnvRef {
+        allocate(Environment {
+            bindings: self.bindings.clone(),
+            parent: self.parent.clone(),
+            object: self.object.clone(),
+            this: self.this.clone(),
+            var_scope: self.var_scope,
+        })
+    }
+}
+
+impl Trace for Environment {
+    fn trace(&self, tracer: &mut Tracer) {
+        for binding in self.bindings.values() {
+            binding.value.trace(tracer);
+        }
+        if let Some(parent) = &self.parent {
+            tracer.edge(parent);
+        }
+        self.object.trace(tracer);
+        self.this.trace(tracer);
+    }
+
+    fn clear(&mut self) {
+        self.bindings.clear();
+        self.parent = None;
+        self.object = None;
+        self.this = None;
+    }
+}
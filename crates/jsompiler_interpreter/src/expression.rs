@@ -0,0 +1,681 @@
+use crate::Interpreter;
+use crate::environment::EnvRef;
+use crate::function::function_name;
+use crate::object::FunctionKind;
+use crate::operations::Numeric;
+use jsompiler_common::Atom;
+use jsompiler_lexer::symbol::{LiteralToken, NumberLiteral};
+use jsompiler_parser::{
+    Expression, ExpressionId, Property as LiteralProperty, PropertyKey as LiteralKey,
+    TemplateLiteral,
+};
+use jsompiler_runtime::{ObjectRef, Property, PropertyKey, Value};
+use num_bigint::BigInt;
+
+type Result<T> = std::result::Result<T, Value>;
+
+// Where a name resolved to
+pub(crate) enum Reference {
+    Binding(EnvRef),
+    Property(ObjectRef), // The global object or the object of a `with`
+}
+
+// Something that can be assigned to
+pub(crate) enum Target {
+    Name(Atom),
+    Property(Value, PropertyKey),
+}
+
+impl Interpreter {
+    pub(crate) fn resolve(&self, name: Atom) -> Option<Reference> {
+        let mut environment = Some(self.environment.clone());
+        while let Some(current) = environment {
+            {
+                let scope = current.borrow();
+                if scope.has(name) {
+                    return Some(Reference::Binding(current.clone()));
+                }
+                if let Some(object) = &scope.object
+                    && object.has_property(&PropertyKey::from(&self.atoms[name]))
+                {
+                    return Some(Reference::Property(object.clone()));
+                }
+            }
+            environment = current.borrow().parent.clone();
+        }
+        None
+    }
+
+    // The value of a name
+    pub(crate) fn lookup(&mut self, name: Atom) -> Result<Value> {
+        match self.resolve(name) {
+            Some(Reference::Binding(environment)) => {
+                let value = environment
+                    .borrow()
+                    .get(name)
+                    .and_then(|binding| binding.value.clone());
+                match value {
+                    Some(value) => Ok(value),
+                    None => {
+                        let message = format!(
+                            "Cannot access '{}' before initialization",
+                            &self.atoms[name]
+                        );
+                        Err(self.reference_error(&message))
+                    }
+                }
+            }
+            Some(Reference::Property(object)) => {
+                let key = PropertyKey::from(&self.atoms[name]);
+                self.get(&object, &key, &Value::Object(object.clone()))
+            }
+            None => {
+                let message = format!("{} is not defined", &self.atoms[name]);
+                Err(self.reference_error(&message))
+            }
+        }
+    }
+
+    // Assign to a name. Sloppy mode code creates a global for a name nothing declares.
+    pub(crate) fn assign(&mut self, name: Atom, value: Value) -> Result<()> {
+        match self.resolve(name) {
+            Some(Reference::Binding(environment)) => {
+                let binding = environment.borrow().get(name).cloned();
+                match binding {
+                    Some(binding) if binding.value.is_none() => {
+                        let message = format!(
+                            "Cannot access '{}' before initialization",
+                            &self.atoms[name]
+                        );
+                        Err(self.reference_error(&message))
+                    }
+                    Some(binding) if !binding.mutable => {
+                        Err(self.type_error("Assignment to constant variable."))
+                    }
+                    _ => {
+                        environment.borrow_mut().set(name, value);
+                        Ok(())
+                    }
+                }
+            }
+            Some(Reference::Property(object)) => {
+                let key = PropertyKey::from(&self.atoms[name]);
+                self.set(&object, &key, value, &Value::Object(object.clone()))
+            }
+            None if self.strict => {
+                let message = format!("{} is not defined", &self.atoms[name]);
+                Err(self.reference_error(&message))
+            }
+            None => {
+                let key = PropertyKey::from(&self.atoms[name]);
+                self.global.borrow_mut().set_own(key, value);
+                Ok(())
+            }
+        }
+    }
+
+    fn this(&self) -> Value {
+        let mut environment = Some(self.environment.clone());
+        while let Some(current) = environment {
+            if let Some(this) = &current.borrow().this {
+                return this.clone();
+            }
+            environment = current.borrow().parent.clone();
+        }
+        Value::Undefined
+    }
+
+    // The key of `object.key` or `object[key]`
+    fn member_key(&mut self, property: ExpressionId, computed: bool) -> Result<PropertyKey> {
+        if !computed
+            && let Expression::Identifier(name) | Expression::PrivateIdentifier(name) =
+                &self.ast[property]
+        {
+            return Ok(PropertyKey::from(&self.ast[name.value]));
+        }
+        let key = self.expression(property)?;
+        self.to_property_key(&key)
+    }
+
+    pub(crate) fn target(&mut self, id: ExpressionId) -> Result<Target> {
+        let ast = self.ast.clone();
+        match &ast[id] {
+            Expression::Identifier(name) => Ok(Target::Name(name.value)),
+            Expression::MemberAccess {
+                object,
+                property,
+                computed,
+                ..
+            } => {
+                let object = self.expression(*object)?;
+                let key = self.member_key(*property, *computed)?;
+                Ok(Target::Property(object, key))
+            }
+            _ => {
+                let error = self.error(
+                    crate::ErrorType::SyntaxError,
+                    "Invalid left-hand side in assignment",
+                );
+                Err(error)
+            }
+        }
+    }
+
+    fn get_target(&mut self, target: &Target) -> Result<Value> {
+        match target {
+            Target::Name(name) => self.lookup(*name),
+            Target::Property(object, key) => self.get_property(object, key),
+        }
+    }
+
+    pub(crate) fn put_target(&mut self, target: &Target, value: Value) -> Result<()> {
+        match target {
+            Target::Name(name) => self.assign(*name, value),
+            Target::Property(object, key) => self.set_property(object, key, value),
+        }
+    }
+
+    pub(crate) fn literal_number(&self, number: &NumberLiteral) -> Value {
+        match number {
+            NumberLiteral::Value(value) => Value::Number(*value),
+            NumberLiteral::NaN => Value::Number(f64::NAN),
+            NumberLiteral::Infinity => Value::Number(f64::INFINITY),
+            NumberLiteral::BigInt(value) => Value::bigint(value.clone()),
+        }
+    }
+
+    pub(crate) fn expression(&mut self, id: ExpressionId) -> Result<Value> {
+        let ast = self.ast.clone();
+        match &ast[id] {
+            Expression::Identifier(name) => self.lookup(name.value),
+            Expression::Literal { value, .. } => Ok(match value {
+                LiteralToken::Number(number) => self.literal_number(number),
+                LiteralToken::String(text) => Value::string(text),
+                LiteralToken::Boolean(value) => Value::Boolean(*value),
+                LiteralToken::Null => Value::Null,
+                LiteralToken::Undefined => Value::Undefined,
+            }),
+            Expression::TemplateLiteral(template) => self.template(template),
+            Expression::ThisExpression { .. } => Ok(self.this()),
+            Expression::MemberAccess {
+                object,
+                property,
+                computed,
+                ..
+            } => {
+                let object = self.expression(*object)?;
+                let key = self.member_key(*property, *computed)?;
+                self.get_property(&object, &key)
+            }
+            Expression::Unary {
+                op, op_type, expr, ..
+            } => self.unary(&op.text, op_type == "Postfix", *expr),
+            Expression::Binary {
+                left, op, right, ..
+            } => self.binary(&op.text, *left, *right),
+            Expression::FunctionCall { callee, args, .. } => {
+                let (function, this) = match &ast[*callee] {
+                    Expression::MemberAccess {
+                        object,
+                        property,
+                        computed,
+                        ..
+                    } => {
+                        let object = self.expression(*object)?;
+                        let key = self.member_key(*property, *computed)?;
+                        (self.get_property(&object, &key)?, object)
+                    }
+                    _ => (self.expression(*callee)?, Value::Undefined),
+                };
+                let args = self.arguments(args)?;
+                if function.as_function().is_none() {
+                    let message = format!("{} is not a function", self.describe_callee(*callee));
+                    return Err(self.type_error(&message));
+                }
+                self.call(&function, this, &args)
+            }
+            Expression::ArrayLiteral { elements, .. } => self.array_literal(elements),
+            Expression::ObjectLiteral { properties, .. } => self.object_literal(properties),
+            Expression::ClassExpression(class) => {
+                let name = class.identifier.map(|name| &ast[name.value]);
+                let class = self.class(
+                    class.identifier,
+                    class.heritage,
+                    &class.body,
+                    name.unwrap_or(""),
+                )?;
+                Ok(Value::Object(class))
+            }
+            Expression::FunctionExpression(function)
+            | Expression::GeneratorExpression(function)
+            | Expression::AsyncFunctionExpression(function)
+            | Expression::AsyncGeneratorExpression(function) => {
+                let kind = match &ast[id] {
+                    Expression::FunctionExpression(_) => FunctionKind::Normal,
+                    Expression::GeneratorExpression(_) => FunctionKind::Generator,
+                    _ => FunctionKind::Async,
+                };
+                let Some(name) = function.name else {
+                    let function =
+                        self.create_function("", kind, &function.parameters, &function.body);
+                    return Ok(Value::Object(function));
+                };
+                // The name of a function expression is only visible inside it
+                let scope = crate::Environment::new(Some(self.environment.clone()));
+                scope.borrow_mut().declare(name.value, None, false);
+                self.in_environment(scope, |interpreter| {
+                    let function = interpreter.create_function(
+                        &ast[name.value],
+                        kind,
+                        &function.parameters,
+                        &function.body,
+                    );
+                    interpreter
+                        .environment
+                        .borrow_mut()
+                        .set(name.value, Value::Object(function.clone()));
+                    Ok(Value::Object(function))
+                })
+            }
+            Expression::ArrowFunctionExpression {
+                parameters, body, ..
+            } => {
+                let function = self.create_function("", FunctionKind::Arrow, parameters, body);
+                Ok(Value::Object(function))
+            }
+            Expression::AwaitExpression { .. } => Err(self.unsupported("'await' expressions")),
+            Expression::RegularExpressionLiteral { .. } => {
+                Err(self.unsupported("Regular expressions"))
+            }
+            Expression::JSXElement(_) | Expression::JSXFragment(_) => {
+                Err(self.unsupported("JSX elements"))
+            }
+            Expression::SpreadElement { .. } | Expression::PrivateIdentifier(_) => {
+                Err(self.error(crate::ErrorType::SyntaxError, "Unexpected token"))
+            }
+        }
+    }
+
+    // Arguments and array elements, with spread elements expanded
+    fn arguments(&mut self, expressions: &[ExpressionId]) -> Result<Vec<Value>> {
+        let ast = self.ast.clone();
+        let mut values = Vec::with_capacity(expressions.len());
+        for id in expressions {
+            match &ast[*id] {
+                Expression::SpreadElement { argument, .. } => {
+                    let iterable = self.expression(*argument)?;
+                    values.extend(self.iterate(&iterable)?);
+                }
+                _ => values.push(self.expression(*id)?),
+            }
+        }
+        Ok(values)
+    }
+
+    // Holes count towards the length of the array but leave their index without a property
+    fn array_literal(&mut self, elements: &[ExpressionId]) -> Result<Value> {
+        let ast = self.ast.clone();
+        let array = self.new_array(Vec::new());
+        let mut length = 0;
+        for id in elements {
+            let values = match &ast[*id] {
+                Expression::SpreadElement { argument, .. } => {
+                    let iterable = self.expression(*argument)?;
+                    self.iterate(&iterable)?
+                }
+                expression if is_hole(expression) => {
+                    length += 1;
+                    continue;
+                }
+                _ => vec![self.expression(*id)?],
+            };
+            for value in values {
+                array.borrow_mut().define(length, Property::data(value));
+                length += 1;
+            }
+        }
+        let length = Value::Number(length as f64);
+        array.borrow_mut().set_own("length".into(), length);
+        Ok(Value::Object(array))
+    }
+
+    // How an error message refers to a callee: `a.b` for a member, else the name
+    fn describe_callee(&self, id: ExpressionId) -> String {
+        match &self.ast[id] {
+            Expression::Identifier(name) => self.ast[name.value].to_string(),
+            Expression::MemberAccess {
+                object,
+                property,
+                computed: false,
+                ..
+            } => format!(
+                "{}.{}",
+                self.describe_callee(*object),
+                self.describe_callee(*property)
+            ),
+            Expression::MemberAccess { object, .. } => {
+                format!("{}[...]", self.describe_callee(*object))
+            }
+            Expression::ThisExpression { .. } => "this".to_string(),
+            Expression::PrivateIdentifier(name) => self.ast[name.value].to_string(),
+            _ => "expression".to_string(),
+        }
+    }
+
+    fn template(&mut self, template: &TemplateLiteral) -> Result<Value> {
+        let ast = self.ast.clone();
+        let quasi = |id: ExpressionId| match &ast[id] {
+            Expression::Literal {
+                value: LiteralToken::String(text),
+                ..
+            } => text.clone(),
+            _ => String::new(),
+        };
+        let mut text = String::new();
+        match template {
+            TemplateLiteral::NonSubstitutionTemplate { text: quasi_id, .. } => {
+                text.push_str(&quasi(*quasi_id))
+            }
+            TemplateLiteral::SubstitutionTemplate {
+                template_head,
+                expression,
+                template_spans,
+                ..
+            } => {
+                text.push_str(&quasi(*template_head));
+                let value = self.expression(*expression)?;
+                text.push_str(&self.to_string(&value)?);
+                let mut middle = template_spans.template_middle_list.as_ref();
+                while let Some(list) = middle {
+                    text.push_str(&quasi(list.template_middle));
+                    let value = self.expression(list.expression)?;
+                    text.push_str(&self.to_string(&value)?);
+                    middle = list.template_middle_list.as_deref();
+                }
+                text.push_str(&quasi(template_spans.template_tail));
+            }
+        }
+        Ok(Value::from(text))
+    }
+
+    fn object_literal(&mut self, properties: &[LiteralProperty]) -> Result<Value> {
+        let ast = self.ast.clone();
+        let object = self.new_object();
+        for property in properties {
+            match property {
+                LiteralProperty::KeyValue { key, value, .. } => {
+                    // `__proto__: value` sets the prototype, unless the key is computed
+                    let is_proto = match key {
+                        LiteralKey::Identifier(name) => &self.ast[name.value] == "__proto__",
+                        LiteralKey::StringLiteral { value, .. } => value == "__proto__",
+                        _ => false,
+                    };
+                    if is_proto {
+                        match self.expression(*value)? {
+                            Value::Object(prototype) => {
+                                object.borrow_mut().prototype = Some(prototype)
+                            }
+                            Value::Null => object.borrow_mut().prototype = None,
+                            _ => {}
+                        }
+                        continue;
+                    }
+                    let key = self.property_key(key)?;
+                    let value = self.named_expression(*value, &function_name(&key))?;
+                    object.borrow_mut().define(key, Property::data(value));
+                }
+                LiteralProperty::Shorthand(name) => {
+                    let value = self.lookup(name.value)?;
+                    object
+                        .borrow_mut()
+                        .define(&self.ast[name.value], Property::data(value));
+                }
+                LiteralProperty::Method {
+                    key, params, body, ..
+                } => {
+                    let key = self.property_key(key)?;
+                    let name = function_name(&key);
+                    let method = self.create_function(&name, FunctionKind::Method, params, body);
+                    object
+                        .borrow_mut()
+                        .define(key, Property::data(method.into()));
+                }
+                LiteralProperty::Getter { key, body, .. } => {
+                    let key = self.property_key(key)?;
+                    let name = format!("get {}", function_name(&key));
+                    let getter = self.create_function(
+                        &name,
+                        FunctionKind::Method,
+                        &[],
+                        std::slice::from_ref(body),
+                    );
+                    self.define_accessor(&object, &key, getter, true, true);
+                }
+                LiteralProperty::Setter {
+                    key, param, body, ..
+                } => {
+                    let key = self.property_key(key)?;
+                    let name = format!("set {}", function_name(&key));
+                    let parameters = std::slice::from_ref(param);
+                    let setter = self.create_function(
+                        &name,
+                        FunctionKind::Method,
+                        parameters,
+                        std::slice::from_ref(body),
+                    );
+                    self.define_accessor(&object, &key, setter, false, true);
+                }
+                LiteralProperty::Spread(spread) => {
+                    let argument = match &ast[*spread] {
+                        Expression::SpreadElement { argument, .. } => *argument,
+                        _ => *spread,
+                    };
+                    let source = self.expression(argument)?;
+                    self.copy_properties(&object, &source)?;
+                }
+            }
+        }
+        Ok(Value::Object(object))
+    }
+
+    // `{ ...source }`: own enumerable properties, read through getters
+    fn copy_properties(&mut self, target: &ObjectRef, source: &Value) -> Result<()> {
+        self.copy_properties_except(target, source, &[])
+    }
+
+    // CopyDataProperties, which object spread and rest patterns use. Symbol keys are copied
+    // too, unlike what `for-in` sees.
+    pub(crate) fn copy_properties_except(
+        &mut self,
+        target: &ObjectRef,
+        source: &Value,
+        excluded: &[PropertyKey],
+    ) -> Result<()> {
+        match source {
+            Value::Object(object) => {
+                let keys = object.borrow().own_keys();
+                for key in keys.into_iter().filter(|key| !excluded.contains(key)) {
+                    let property = object.borrow().get_own(&key);
+                    if property.is_some_and(|property| property.enumerable) {
+                        let value = self.get(object, &key, source)?;
+                        target.borrow_mut().define(key, Property::data(value));
+                    }
+                }
+            }
+            Value::String(text) => {
+                for (index, unit) in text.encode_utf16().enumerate() {
+                    let key = PropertyKey::from(index);
+                    if !excluded.contains(&key) {
+                        let value = Value::from(String::from_utf16_lossy(&[unit]));
+                        target.borrow_mut().define(key, Property::data(value));
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn unary(&mut self, operator: &str, postfix: bool, operand: ExpressionId) -> Result<Value> {
+        let ast = self.ast.clone();
+        match operator {
+            "++" | "--" => {
+                let target = self.target(operand)?;
+                let old = self.get_target(&target)?;
+                let old = self.to_numeric(&old)?;
+                let delta = if operator == "++" { 1 } else { -1 };
+                let (old, new) = match old {
+                    Numeric::Number(value) => {
+                        (Value::Number(value), Value::Number(value + delta as f64))
+                    }
+                    Numeric::BigInt(value) => {
+                        let new = &*value + BigInt::from(delta);
+                        (Value::BigInt(value), Value::bigint(new))
+                    }
+                };
+                self.put_target(&target, new.clone())?;
+                Ok(if postfix { old } else { new })
+            }
+            "typeof" => {
+                // A name nothing declares is "undefined" rather than an error
+                if let Expression::Identifier(name) = &ast[operand]
+                    && self.resolve(name.value).is_none()
+                {
+                    return Ok(Value::string("undefined"));
+                }
+                let value = self.expression(operand)?;
+                Ok(Value::string(value.type_of()))
+            }
+            "delete" => self.delete(operand),
+            _ => {
+                let value = self.expression(operand)?;
+                Ok(match operator {
+                    "void" => Value::Undefined,
+                    "!" => Value::Boolean(!value.to_boolean()),
+                    "+" => Value::Number(self.to_number(&value)?),
+                    "-" => match self.to_numeric(&value)? {
+                        Numeric::Number(value) => Value::Number(-value),
+                        Numeric::BigInt(value) => Value::bigint(-&*value),
+                    },
+                    "~" => match self.to_numeric(&value)? {
+                        Numeric::Number(value) => {
+                            Value::Number(!crate::number::to_int32(value) as f64)
+                        }
+                        Numeric::BigInt(value) => Value::bigint(!&*value),
+                    },
+                    _ => unreachable!("{operator} is not a unary operator"),
+                })
+            }
+        }
+    }
+
+    fn delete(&mut self, operand: ExpressionId) -> Result<Value> {
+        let ast = self.ast.clone();
+        match &ast[operand] {
+            Expression::MemberAccess {
+                object,
+                property,
+                computed,
+                ..
+            } => {
+                let object = self.expression(*object)?;
+                let key = self.member_key(*property, *computed)?;
+                Ok(Value::Boolean(self.delete_property(&object, &key)?))
+            }
+            // Declared names can't be deleted, globals created by assignment can
+            Expression::Identifier(name) => Ok(Value::Boolean(match self.resolve(name.value) {
+                Some(Reference::Binding(_)) => false,
+                Some(Reference::Property(object)) => object
+                    .borrow_mut()
+                    .delete(&PropertyKey::from(&self.ast[name.value])),
+                None => true,
+            })),
+            _ => {
+                self.expression(operand)?;
+                Ok(Value::Boolean(true))
+            }
+        }
+    }
+
+    fn binary(&mut self, operator: &str, left: ExpressionId, right: ExpressionId) -> Result<Value> {
+        let ast = self.ast.clone();
+        match operator {
+            "&&" | "||" | "??" => {
+                let value = self.expression(left)?;
+                let short_circuits = match operator {
+                    "&&" => !value.to_boolean(),
+                    "||" => value.to_boolean(),
+                    _ => !value.is_nullish(),
+                };
+                if short_circuits {
+                    Ok(value)
+                } else {
+                    self.expression(right)
+                }
+            }
+            "=" => {
+                let target = self.target(left)?;
+                let value = match &ast[left] {
+                    Expression::Identifier(name) => {
+                        self.named_expression(right, &ast[name.value])?
+                    }
+                    _ => self.expression(right)?,
+                };
+                self.put_target(&target, value.clone())?;
+                Ok(value)
+            }
+            "&&=" | "||=" | "??=" => {
+                let target = self.target(left)?;
+                let value = self.get_target(&target)?;
+                let short_circuits = match operator {
+                    "&&=" => !value.to_boolean(),
+                    "||=" => value.to_boolean(),
+                    _ => !value.is_nullish(),
+                };
+                if short_circuits {
+                    return Ok(value);
+                }
+                let value = self.expression(right)?;
+                self.put_target(&target, value.clone())?;
+                Ok(value)
+            }
+            "+=" | "-=" | "*=" | "/=" | "%=" | "**=" | "<<=" | ">>=" | ">>>=" | "&=" | "|="
+            | "^=" => {
+                let target = self.target(left)?;
+                let old = self.get_target(&target)?;
+                let right = self.expression(right)?;
+                let value = self.binary_operation(&operator[..operator.len() - 1], &old, &right)?;
+                self.put_target(&target, value.clone())?;
+                Ok(value)
+            }
+            // `#x in object` checks for a private name
+            "in" if matches!(ast[left], Expression::PrivateIdentifier(_)) => {
+                let Expression::PrivateIdentifier(name) = &ast[left] else {
+                    unreachable!()
+                };
+                let object = self.expression(right)?;
+                match object {
+                    Value::Object(object) => {
+                        let key = PropertyKey::from(&self.ast[name.value]);
+                        Ok(Value::Boolean(object.has_property(&key)))
+                    }
+                    _ => Err(self.type_error(
+                        "Cannot use 'in' operator to search for a private field in a primitive",
+                    )),
+                }
+            }
+            _ => {
+                let left = self.expression(left)?;
+                let right = self.expression(right)?;
+                self.binary_operation(operator, &left, &right)
+            }
+        }
+    }
+}
+
+fn is_hole(expression: &Expression) -> bool {
+    matches!(
+        expression,
+        Expression::Literal { value: LiteralToken::Undefined, span } if span.start == span.end
+    )
+}
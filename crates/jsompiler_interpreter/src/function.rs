@@ -0,0 +1,1036 @@
+use crate::environment::{EnvRef, Environment};
+use crate::object::{
+    Class, Closure, Function, FunctionCode, FunctionKind, NativeConstructor, NativeFunction,
+    closure_of_mut, function_of,
+};
+use crate::statement::Completion;
+use crate::{Interpreter, MAX_CALL_DEPTH};
+use jsompiler_common::Atom;
+use jsompiler_lexer::symbol::{KeywordToken, Token};
+use jsompiler_parser::visit::{AstNode, Visit, Walk};
+use jsompiler_parser::{
+    Ast, BindingPattern, BindingProperty, ClassElement, ClassElementName, Expression, ExpressionId,
+    ForInOfLeft, ForInStatement, ForOfStatement, FunctionDeclaration, Identifier, Parameter,
+    PropertyKey as LiteralKey, Statement, StatementId,
+};
+use jsompiler_runtime::{
+    Object, ObjectKind, ObjectRef, ParameterMap, Property, PropertyKey, PropertyValue, Trace,
+    Tracer, Value, collect_if_needed,
+};
+use jsompiler_semantic::{body_statements, has_use_strict};
+use std::collections::HashSet;
+use std::rc::Rc;
+
+type Result<T> = std::result::Result<T, Value>;
+
+// Where the names of a binding pattern go
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum BindingTarget {
+    Declare { mutable: bool }, // `let`, `const`, parameters and `catch`, in the current scope
+    Assign,                    // `var` and assignment targets, wherever the name resolves
+}
+
+// Names a function or script declares with `var`, including functions declared in blocks,
+// which sloppy mode code can call after the block
+pub(crate) fn var_names(ast: &Ast, statements: &[StatementId]) -> Vec<Atom> {
+    let mut names = VarNames::default();
+    for statement in statements {
+        // Functions at the top level are declared with their value instead
+        if let Statement::FunctionDeclaration(_) = &ast[*statement] {
+            continue;
+        }
+        names.visit_statement(ast, *statement);
+    }
+    names.names
+}
+
+#[derive(Default)]
+struct VarNames {
+    names: Vec<Atom>,
+    seen: HashSet<Atom>,
+}
+
+impl VarNames {
+    fn add(&mut self, names: Vec<Identifier>) {
+        for name in names {
+            if self.seen.insert(name.value) {
+                self.names.push(name.value);
+            }
+        }
+    }
+}
+
+impl Visit for VarNames {
+    fn enter(&mut self, node: AstNode) -> Walk {
+        let var = Token::Keyword(KeywordToken::Var);
+        match node {
+            AstNode::Statement(_, Statement::LetStatement(declaration)) => {
+                if declaration.token == var {
+                    self.add(declaration.name.bound_names());
+                }
+                Walk::Continue
+            }
+            AstNode::Statement(
+                _,
+                Statement::ForInStatement(ForInStatement {
+                    left: ForInOfLeft::Declaration { token, name, .. },
+                    ..
+                })
+                | Statement::ForOfStatement(ForOfStatement {
+                    left: ForInOfLeft::Declaration { token, name, .. },
+                    ..
+                }),
+            ) => {
+                if *token == var {
+                    self.add(name.bound_names());
+                }
+                Walk::Continue
+            }
+            AstNode::Statement(_, Statement::FunctionDeclaration(function)) => {
+                self.add(function.name.into_iter().collect());
+                Walk::Skip
+            }
+            // Nothing inside a function or class is hoisted out of it
+            AstNode::Statement(_, Statement::ClassDeclaration(_)) | AstNode::Expression(_, _) => {
+                Walk::Skip
+            }
+            _ => Walk::Continue,
+        }
+    }
+}
+
+// The name a function defined under a property key gets, which is `[description]` for a
+// symbol
+pub(crate) fn function_name(key: &PropertyKey) -> String {
+    match key {
+        PropertyKey::Symbol(symbol) => symbol
+            .description()
+            .map(|description| format!("[{description}]"))
+            .unwrap_or_default(),
+        key => key.to_string(),
+    }
+}
+
+// What `call` runs, copied out of the function object
+enum Callee {
+    Closure(Rc<FunctionCode>, EnvRef),
+    Native(NativeFunction),
+    Constructor(NativeConstructor),
+    Compiled,
+}
+
+// The parameters of a sloppy mode function call, which its `arguments` object shares
+struct ParameterBindings {
+    environment: EnvRef,
+    names: Vec<Atom>,
+}
+
+impl Trace for ParameterBindings {
+    fn trace(&self, tracer: &mut Tracer) {
+        tracer.edge(&self.environment);
+    }
+}
+
+impl ParameterMap for ParameterBindings {
+    fn get(&self, index: u32) -> Value {
+        let environment = self.environment.borrow();
+        let binding = environment.get(self.names[index as usize]);
+        binding
+            .and_then(|binding| binding.value.clone())
+            .unwrap_or(Value::Undefined)
+    }
+
+    fn set(&self, index: u32, value: Value) {
+        let name = self.names[index as usize];
+        self.environment.borrow_mut().set(name, value);
+    }
+}
+
+// The number of parameters before the first default or rest parameter
+pub(crate) fn expected_arguments(parameters: &[Parameter]) -> usize {
+    parameters
+        .iter()
+        .take_while(|parameter| {
+            matches!(parameter, Parameter::Identifier(_) | Parameter::Pattern(_))
+        })
+        .count()
+}
+
+impl Interpreter {
+    // A function object for code in the running program, closing over the current scope
+    pub(crate) fn create_function(
+        &mut self,
+        name: &str,
+        kind: FunctionKind,
+        parameters: &[Parameter],
+        body: &[StatementId],
+    ) -> ObjectRef {
+        let strict = self.strict
+            || kind == FunctionKind::ClassConstructor
+            || has_use_strict(&self.ast, &body_statements(&self.ast, body));
+        let code = FunctionCode {
+            ast: self.ast.clone(),
+            name: name.into(),
+            kind,
+            parameters: parameters.to_vec(),
+            body: body.to_vec(),
+            strict,
+        };
+        let closure = Closure {
+            code: Rc::new(code),
+            environment: self.environment.clone(),
+            class: None,
+        };
+        let mut object = Object::new(
+            Some(self.intrinsics.function_prototype.clone()),
+            ObjectKind::Function(Box::new(Function::Closure(closure))),
+        );
+        let length = expected_arguments(parameters) as f64;
+        object.define("length", Property::read_only(Value::Number(length)));
+        object.define("name", Property::read_only(Value::string(name)));
+        let function = ObjectRef::new(object);
+
+        // Functions that can be constructed get a prototype for their instances
+        if matches!(kind, FunctionKind::Normal | FunctionKind::ClassConstructor) {
+            let prototype = self.new_object();
+            prototype
+                .borrow_mut()
+                .define("constructor", Property::hidden(function.clone().into()));
+            // Class prototypes can't be assigned, function prototypes can
+            let writable = kind == FunctionKind::Normal;
+            let prototype = Property::new(prototype.into(), writable, false, false);
+            function.borrow_mut().define("prototype", prototype);
+        }
+        function
+    }
+
+    pub(crate) fn function_declaration(&mut self, function: &FunctionDeclaration) -> ObjectRef {
+        let kind = match (function.is_async, function.is_generator) {
+            (false, false) => FunctionKind::Normal,
+            (_, true) => FunctionKind::Generator,
+            (true, false) => FunctionKind::Async,
+        };
+        let ast = self.ast.clone();
+        let name = function.name.map(|name| &ast[name.value]);
+        self.create_function(
+            name.unwrap_or("default"),
+            kind,
+            &function.parameters,
+            &function.body,
+        )
+    }
+
+    // Call a function with a `this` value
+    pub fn call(&mut self, function: &Value, this: Value, args: &[Value]) -> Result<Value> {
+        let Some(object) = function.as_function() else {
+            let message = format!("{} is not a function", crate::inspect(function));
+            return Err(self.type_error(&message));
+        };
+        collect_if_needed();
+        let bound = match &object.borrow().kind {
+            ObjectKind::BoundFunction(bound) => Some((
+                bound.target.clone(),
+                bound.this.clone(),
+                bound.arguments.clone(),
+            )),
+            _ => None,
+        };
+        if let Some((target, this, mut arguments)) = bound {
+            arguments.extend_from_slice(args);
+            return self.call(&Value::Object(target), this, &arguments);
+        }
+        // The function is no longer borrowed when it runs, since it may change itself
+        let callee = match function_of(object).as_deref() {
+            Some(Function::Closure(closure)) => {
+                Callee::Closure(closure.code.clone(), closure.environment.clone())
+            }
+            Some(Function::Native(native)) => Callee::Native(*native),
+            Some(Function::Constructor(constructor)) => Callee::Constructor(*constructor),
+            Some(Function::Compiled(_)) => Callee::Compiled,
+            None => unreachable!("callable objects are functions"),
+        };
+        let (code, environment) = match callee {
+            Callee::Closure(code, environment) => (code, environment),
+            Callee::Native(native) => {
+                return self.call_native(|interpreter| native(interpreter, this, args));
+            }
+            Callee::Constructor(constructor) => {
+                return self.call_native(|interpreter| constructor(interpreter, args, None));
+            }
+            Callee::Compiled => return self.call_compiled(object, this, args),
+        };
+        match code.kind {
+            FunctionKind::ClassConstructor => {
+                let message = format!(
+                    "Class constructor {} cannot be invoked without 'new'",
+                    code.name
+                );
+                return Err(self.type_error(&message));
+            }
+            FunctionKind::Generator => return Err(self.unsupported("Generator functions")),
+            FunctionKind::Async => return Err(self.unsupported("Async functions")),
+            FunctionKind::Normal | FunctionKind::Arrow | FunctionKind::Method => {}
+        }
+        self.call_closure(object, code, environment, this, args)
+    }
+
+    // Run the body of a closure in a new scope of the environment it was created in
+    fn call_closure(
+        &mut self,
+        object: &ObjectRef,
+        code: Rc<FunctionCode>,
+        environment: EnvRef,
+        this: Value,
+        args: &[Value],
+    ) -> Result<Value> {
+        if self.depth >= MAX_CALL_DEPTH {
+            return Err(self.range_error("Maximum call stack size exceeded"));
+        }
+
+        let scope = Environment::new(Some(environment));
+        {
+            let mut scope = scope.borrow_mut();
+            scope.var_scope = true;
+            if code.kind != FunctionKind::Arrow {
+                // Sloppy mode functions called without a `this` get the global object
+                let this = match this {
+                    Value::Undefined | Value::Null if !code.strict => {
+                        Value::Object(self.global.clone())
+                    }
+                    this => this,
+                };
+                scope.this = Some(this);
+            }
+        }
+        if code.kind != FunctionKind::Arrow {
+            let arguments = self.arguments_object(object, &code, &scope, args);
+            let name = self.atoms.intern("arguments");
+            scope
+                .borrow_mut()
+                .declare(name, Some(Value::Object(arguments)), true);
+        }
+
+        let outer_ast = std::mem::replace(&mut self.ast, code.ast.clone());
+        let outer_strict = std::mem::replace(&mut self.strict, code.strict);
+        let position = self.position;
+        self.depth += 1;
+        let result =
+            self.in_environment(scope, |interpreter| interpreter.function_body(&code, args));
+        self.depth -= 1;
+        self.position = position;
+        self.strict = outer_strict;
+        self.ast = outer_ast;
+        result
+    }
+
+    // Run a built-in, which counts toward the call depth like other functions, so that
+    // built-ins calling each other without end throw rather than overflow the stack
+    fn call_native(&mut self, native: impl FnOnce(&mut Self) -> Result<Value>) -> Result<Value> {
+        if self.depth >= MAX_CALL_DEPTH {
+            return Err(self.range_error("Maximum call stack size exceeded"));
+        }
+        self.depth += 1;
+        let result = native(self);
+        self.depth -= 1;
+        result
+    }
+
+    // Whether a function has [[Construct]]: built-in constructors, functions that aren't
+    // arrows, methods, generators or async, classes, and functions bound to those
+    pub fn is_constructor(&self, object: &ObjectRef) -> bool {
+        if let ObjectKind::BoundFunction(bound) = &object.borrow().kind {
+            return self.is_constructor(&bound.target);
+        }
+        match function_of(object).as_deref() {
+            Some(Function::Constructor(_)) => true,
+            Some(Function::Closure(closure)) => matches!(
+                closure.code.kind,
+                FunctionKind::Normal | FunctionKind::ClassConstructor
+            ),
+            Some(Function::Compiled(function)) => function.code.kind == FunctionKind::Normal,
+            Some(Function::Native(_)) | None => false,
+        }
+    }
+
+    // [[Construct]], which `new` and `Reflect.construct` call. `new_target` is the
+    // constructor itself unless a subclass is being constructed.
+    pub fn construct(
+        &mut self,
+        constructor: &Value,
+        args: &[Value],
+        new_target: Option<&ObjectRef>,
+    ) -> Result<Value> {
+        let Some(object) = constructor
+            .as_object()
+            .filter(|object| self.is_constructor(object))
+        else {
+            let message = format!("{} is not a constructor", crate::inspect(constructor));
+            return Err(self.type_error(&message));
+        };
+        let new_target = new_target.unwrap_or(object).clone();
+        let bound = match &object.borrow().kind {
+            ObjectKind::BoundFunction(bound) => {
+                Some((bound.target.clone(), bound.arguments.clone()))
+            }
+            _ => None,
+        };
+        if let Some((target, mut arguments)) = bound {
+            arguments.extend_from_slice(args);
+            let new_target = if new_target.ptr_eq(object) {
+                &target
+            } else {
+                &new_target
+            };
+            return self.construct(&Value::Object(target.clone()), &arguments, Some(new_target));
+        }
+        let (native, class) = match function_of(object).as_deref() {
+            Some(Function::Constructor(constructor)) => (Some(*constructor), None),
+            Some(Function::Closure(closure)) => (None, closure.class.clone()),
+            _ => (None, None),
+        };
+        if let Some(constructor) = native {
+            return self
+                .call_native(|interpreter| constructor(interpreter, args, Some(&new_target)));
+        }
+        if let Some(class) = class {
+            return self.construct_class(object, &class, args, &new_target);
+        }
+        let prototype = self.intrinsics.object_prototype.clone();
+        let prototype = self.prototype_from_constructor(&new_target, prototype)?;
+        let this = Value::Object(ObjectRef::new(Object::new(
+            Some(prototype),
+            ObjectKind::Ordinary,
+        )));
+        match self.call(constructor, this.clone(), args)? {
+            result @ Value::Object(_) => Ok(result),
+            _ => Ok(this),
+        }
+    }
+
+    // A base class makes `this` from `new.target` and defines its fields before the body
+    // runs. A derived class without a constructor of its own has its parent make `this`, as
+    // the default `constructor(...args) { super(...args) }` would.
+    fn construct_class(
+        &mut self,
+        constructor: &ObjectRef,
+        class: &Class,
+        args: &[Value],
+        new_target: &ObjectRef,
+    ) -> Result<Value> {
+        let (code, environment) = match function_of(constructor).as_deref() {
+            Some(Function::Closure(closure)) => (closure.code.clone(), closure.environment.clone()),
+            _ => unreachable!("classes are closures"),
+        };
+        if class.derived {
+            if class.has_constructor {
+                return Err(self.unsupported("Constructors of derived classes"));
+            }
+            let parent = match constructor.prototype() {
+                Some(parent) if self.is_constructor(&parent) => parent,
+                _ => {
+                    let name = match &*code.name {
+                        "" => "anonymous class",
+                        name => name,
+                    };
+                    let message = format!("Super constructor of {name} is not a constructor");
+                    return Err(self.type_error(&message));
+                }
+            };
+            let this = self.construct(&Value::Object(parent), args, Some(new_target))?;
+            self.initialize_fields(&code, &environment, class, &this)?;
+            return Ok(this);
+        }
+        let prototype = self.intrinsics.object_prototype.clone();
+        let prototype = self.prototype_from_constructor(new_target, prototype)?;
+        let this = Value::Object(ObjectRef::new(Object::new(
+            Some(prototype),
+            ObjectKind::Ordinary,
+        )));
+        self.initialize_fields(&code, &environment, class, &this)?;
+        match self.call_closure(constructor, code, environment, this.clone(), args)? {
+            result @ Value::Object(_) => Ok(result),
+            _ => Ok(this),
+        }
+    }
+
+    // Define the instance fields of a class on `this`, in order. Initializers run in the
+    // class scope with `this` bound, like methods do.
+    fn initialize_fields(
+        &mut self,
+        code: &FunctionCode,
+        environment: &EnvRef,
+        class: &Class,
+        this: &Value,
+    ) -> Result<()> {
+        let Value::Object(object) = this else {
+            unreachable!("constructors make objects")
+        };
+        for (key, value) in &class.fields {
+            let value = match value {
+                Some(value) => {
+                    let scope = Environment::new(Some(environment.clone()));
+                    scope.borrow_mut().this = Some(this.clone());
+                    let outer_ast = std::mem::replace(&mut self.ast, code.ast.clone());
+                    let outer_strict = std::mem::replace(&mut self.strict, true);
+                    let name = function_name(key);
+                    let value = self.in_environment(scope, |interpreter| {
+                        interpreter.named_expression(*value, &name)
+                    });
+                    self.strict = outer_strict;
+                    self.ast = outer_ast;
+                    value?
+                }
+                None => Value::Undefined,
+            };
+            object
+                .borrow_mut()
+                .define(key.clone(), Property::data(value));
+        }
+        Ok(())
+    }
+
+    // GetPrototypeFromConstructor: the `prototype` of `new.target` if it is an object, else
+    // the intrinsic the constructor uses by default
+    pub(crate) fn prototype_from_constructor(
+        &mut self,
+        new_target: &ObjectRef,
+        default: ObjectRef,
+    ) -> Result<ObjectRef> {
+        let target = Value::Object(new_target.clone());
+        match self.get(new_target, &"prototype".into(), &target)? {
+            Value::Object(prototype) => Ok(prototype),
+            _ => Ok(default),
+        }
+    }
+
+    // The `arguments` of a call. Sloppy mode functions with only plain parameters get one
+    // that shares its indices with them, other functions get a copy of the values.
+    fn arguments_object(
+        &mut self,
+        function: &ObjectRef,
+        code: &FunctionCode,
+        scope: &EnvRef,
+        args: &[Value],
+    ) -> ObjectRef {
+        let prototype = Some(self.intrinsics.object_prototype.clone());
+        let names: Option<Vec<Atom>> = code
+            .parameters
+            .iter()
+            .map(|parameter| match parameter {
+                Parameter::Identifier(name) => Some(name.value),
+                _ => None,
+            })
+            .collect();
+        let Some(names) = names.filter(|_| !code.strict) else {
+            let arguments = ObjectRef::new(Object::arguments(prototype, args, None));
+            let thrower = Some(self.intrinsics.throw_type_error.clone());
+            let callee = Property {
+                configurable: false,
+                ..Property::accessor(thrower.clone(), thrower, false)
+            };
+            arguments.borrow_mut().define("callee", callee);
+            return arguments;
+        };
+        // With a repeated name, the last parameter of that name is the one that is shared
+        let mapped = (0..args.len())
+            .map(|index| {
+                names
+                    .get(index)
+                    .is_some_and(|name| !names[index + 1..].contains(name))
+            })
+            .collect();
+        let parameters = ParameterBindings {
+            environment: scope.clone(),
+            names,
+        };
+        let parameters: Rc<dyn ParameterMap> = Rc::new(parameters);
+        let object = Object::arguments(prototype, args, Some((parameters, mapped)));
+        let arguments = ObjectRef::new(object);
+        let callee = Property::hidden(Value::Object(function.clone()));
+        arguments.borrow_mut().define("callee", callee);
+        arguments
+    }
+
+    fn function_body(&mut self, code: &FunctionCode, args: &[Value]) -> Result<Value> {
+        let ast = self.ast.clone();
+        for (index, parameter) in code.parameters.iter().enumerate() {
+            let argument = args.get(index).cloned().unwrap_or(Value::Undefined);
+            let mutable = BindingTarget::Declare { mutable: true };
+            match parameter {
+                Parameter::Identifier(name) => {
+                    self.environment
+                        .borrow_mut()
+                        .declare(name.value, Some(argument), true)
+                }
+                Parameter::Pattern(pattern) => self.bind_pattern(pattern, argument, mutable)?,
+                Parameter::Default {
+                    target, default, ..
+                } => {
+                    let argument = match (argument, target) {
+                        (Value::Undefined, BindingPattern::Identifier(name)) => {
+                            self.named_expression(*default, &ast[name.value])?
+                        }
+                        (Value::Undefined, _) => self.expression(*default)?,
+                        (argument, _) => argument,
+                    };
+                    self.bind_pattern(target, argument, mutable)?;
+                }
+                Parameter::RestParameter { argument, .. } => {
+                    let rest = args.get(index..).unwrap_or_default().to_vec();
+                    let rest = Value::Object(self.new_array(rest));
+                    self.bind_pattern(argument, rest, mutable)?;
+                }
+            }
+        }
+
+        // An arrow with an expression body returns it
+        if code.kind == FunctionKind::Arrow
+            && let [statement] = code.body.as_slice()
+            && let Statement::ExpressionStatement { expression, .. } = &self.ast[*statement]
+        {
+            return self.expression(*expression);
+        }
+        let statements = body_statements(&self.ast, &code.body);
+        self.hoist_declarations(&statements, true)?;
+        match self.statements(&statements)? {
+            Completion::Return(value) => Ok(value),
+            _ => Ok(Value::Undefined),
+        }
+    }
+
+    // Evaluate an expression that may be an anonymous function or class, which then gets
+    // `name`, as in `let add = (a, b) => a + b`
+    pub(crate) fn named_expression(&mut self, id: ExpressionId, name: &str) -> Result<Value> {
+        let ast = self.ast.clone();
+        let function = match &ast[id] {
+            Expression::FunctionExpression(function) if function.name.is_none() => self
+                .create_function(
+                    name,
+                    FunctionKind::Normal,
+                    &function.parameters,
+                    &function.body,
+                ),
+            Expression::GeneratorExpression(function) if function.name.is_none() => self
+                .create_function(
+                    name,
+                    FunctionKind::Generator,
+                    &function.parameters,
+                    &function.body,
+                ),
+            Expression::AsyncFunctionExpression(function)
+            | Expression::AsyncGeneratorExpression(function)
+                if function.name.is_none() =>
+            {
+                self.create_function(
+                    name,
+                    FunctionKind::Async,
+                    &function.parameters,
+                    &function.body,
+                )
+            }
+            Expression::ArrowFunctionExpression {
+                parameters, body, ..
+            } => self.create_function(name, FunctionKind::Arrow, parameters, body),
+            Expression::ClassExpression(class) if class.identifier.is_none() => {
+                self.class(None, class.heritage, &class.body, name)?
+            }
+            _ => return self.expression(id),
+        };
+        Ok(Value::Object(function))
+    }
+
+    // Bind the names of a pattern to the parts of `value` they take apart
+    pub(crate) fn bind_pattern(
+        &mut self,
+        pattern: &BindingPattern,
+        value: Value,
+        target: BindingTarget,
+    ) -> Result<()> {
+        let ast = self.ast.clone();
+        match pattern {
+            BindingPattern::Identifier(name) => self.bind_name(name.value, value, target),
+            BindingPattern::ObjectPattern {
+                properties, rest, ..
+            } => {
+                if value.is_nullish() {
+                    let message = format!(
+                        "Cannot destructure '{}' as it is {}.",
+                        crate::inspect(&value),
+                        crate::inspect(&value)
+                    );
+                    return Err(self.type_error(&message));
+                }
+                let mut used = Vec::new();
+                for property in properties {
+                    match property {
+                        BindingProperty::KeyValue {
+                            key,
+                            value: element,
+                            ..
+                        } => {
+                            let key = self.property_key(key)?;
+                            let mut property = self.get_property(&value, &key)?;
+                            if let (Value::Undefined, Some(default)) = (&property, element.default)
+                            {
+                                property = match &element.target {
+                                    BindingPattern::Identifier(name) => {
+                                        self.named_expression(default, &ast[name.value])?
+                                    }
+                                    _ => self.expression(default)?,
+                                };
+                            }
+                            self.bind_pattern(&element.target, property, target)?;
+                            used.push(key);
+                        }
+                        BindingProperty::Shorthand { name, default, .. } => {
+                            let key = PropertyKey::from(&self.ast[name.value]);
+                            let mut property = self.get_property(&value, &key)?;
+                            if let (Value::Undefined, Some(default)) = (&property, default) {
+                                property = self.named_expression(*default, &ast[name.value])?;
+                            }
+                            self.bind_name(name.value, property, target)?;
+                            used.push(key);
+                        }
+                    }
+                }
+                if let Some(rest) = rest {
+                    let copy = self.new_object();
+                    self.copy_properties_except(&copy, &value, &used)?;
+                    self.bind_pattern(&rest.argument, Value::Object(copy), target)?;
+                }
+                Ok(())
+            }
+            BindingPattern::ArrayPattern { elements, rest, .. } => {
+                let values = self.iterate(&value)?;
+                for (index, element) in elements.iter().enumerate() {
+                    let Some(element) = element else { continue };
+                    let mut item = values.get(index).cloned().unwrap_or(Value::Undefined);
+                    if let (Value::Undefined, Some(default)) = (&item, element.default) {
+                        item = match &element.target {
+                            BindingPattern::Identifier(name) => {
+                                self.named_expression(default, &ast[name.value])?
+                            }
+                            _ => self.expression(default)?,
+                        };
+                    }
+                    self.bind_pattern(&element.target, item, target)?;
+                }
+                if let Some(rest) = rest {
+                    let remaining = values.get(elements.len()..).unwrap_or_default().to_vec();
+                    let remaining = Value::Object(self.new_array(remaining));
+                    self.bind_pattern(&rest.argument, remaining, target)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn bind_name(&mut self, name: Atom, value: Value, target: BindingTarget) -> Result<()> {
+        match target {
+            BindingTarget::Declare { mutable } => {
+                self.environment
+                    .borrow_mut()
+                    .declare(name, Some(value), mutable);
+                Ok(())
+            }
+            BindingTarget::Assign => self.assign(name, value),
+        }
+    }
+
+    // The values spread and array patterns go through, which are all read first. Arrays and
+    // strings are read directly, anything else through its iterator.
+    pub(crate) fn iterate(&mut self, value: &Value) -> Result<Vec<Value>> {
+        if let Some(length) = self.list_length(value)?
+            && let Value::Object(object) = value
+        {
+            let mut values = Vec::with_capacity(length as usize);
+            for index in 0..length {
+                values.push(self.get(object, &PropertyKey::Index(index), value)?);
+            }
+            return Ok(values);
+        }
+        if let Value::String(text) = value {
+            return Ok(text.chars().map(|c| Value::from(c.to_string())).collect());
+        }
+        let mut iterator = self.get_iterator(value)?;
+        let mut values = Vec::new();
+        while let Some(value) = self.iterator_step(&mut iterator)? {
+            values.push(value);
+        }
+        Ok(values)
+    }
+
+    pub(crate) fn property_key(&mut self, key: &LiteralKey) -> Result<PropertyKey> {
+        Ok(match key {
+            LiteralKey::Identifier(name) => PropertyKey::from(&self.ast[name.value]),
+            LiteralKey::StringLiteral { value, .. } => PropertyKey::from(value.as_str()),
+            LiteralKey::NumberLiteral { value, .. } => {
+                let value = self.literal_number(value);
+                self.to_property_key(&value)?
+            }
+            LiteralKey::Computed(expression) => {
+                let value = self.expression(*expression)?;
+                self.to_property_key(&value)?
+            }
+        })
+    }
+
+    fn class_element_key(&mut self, name: &ClassElementName) -> Result<PropertyKey> {
+        Ok(match name {
+            ClassElementName::PropertyName(name) | ClassElementName::PrivateIdentifier(name) => {
+                PropertyKey::from(&self.ast[name.value])
+            }
+            ClassElementName::StringLiteral { value, .. } => PropertyKey::from(value.as_str()),
+            ClassElementName::NumberLiteral { value, .. } => {
+                let value = self.literal_number(value);
+                self.to_property_key(&value)?
+            }
+            ClassElementName::Computed(expression) => {
+                let value = self.expression(*expression)?;
+                self.to_property_key(&value)?
+            }
+        })
+    }
+
+    // Add a getter or setter to an object, keeping the other half if it has one
+    pub(crate) fn define_accessor(
+        &mut self,
+        object: &ObjectRef,
+        key: &PropertyKey,
+        function: ObjectRef,
+        is_getter: bool,
+        enumerable: bool,
+    ) {
+        let existing = object.borrow().get_own(key).map(|property| property.value);
+        let (mut get, mut set) = match existing {
+            Some(PropertyValue::Accessor { get, set }) => (get, set),
+            _ => (None, None),
+        };
+        if is_getter {
+            get = Some(function);
+        } else {
+            set = Some(function);
+        }
+        let accessor = Property::accessor(get, set, enumerable);
+        object.borrow_mut().define(key.clone(), accessor);
+    }
+
+    // Evaluate a class to its constructor. Instances can't be created without `new`, so
+    // instance fields are never initialized.
+    pub(crate) fn class(
+        &mut self,
+        identifier: Option<Identifier>,
+        heritage: Option<ExpressionId>,
+        body: &[ClassElement],
+        name: &str,
+    ) -> Result<ObjectRef> {
+        let outer_strict = std::mem::replace(&mut self.strict, true);
+        let scope = Environment::new(Some(self.environment.clone()));
+        if let Some(identifier) = identifier {
+            scope.borrow_mut().declare(identifier.value, None, false);
+        }
+        let result = self.in_environment(scope, |interpreter| {
+            let constructor = interpreter.class_body(heritage, body, name)?;
+            if let Some(identifier) = identifier {
+                interpreter
+                    .environment
+                    .borrow_mut()
+                    .set(identifier.value, Value::Object(constructor.clone()));
+            }
+            Ok(constructor)
+        });
+        self.strict = outer_strict;
+        result
+    }
+
+    fn class_body(
+        &mut self,
+        heritage: Option<ExpressionId>,
+        body: &[ClassElement],
+        name: &str,
+    ) -> Result<ObjectRef> {
+        let (prototype_parent, constructor_parent) = match heritage {
+            None => (
+                Some(self.intrinsics.object_prototype.clone()),
+                self.intrinsics.function_prototype.clone(),
+            ),
+            Some(heritage) => match self.expression(heritage)? {
+                Value::Null => (None, self.intrinsics.function_prototype.clone()),
+                Value::Object(parent) if parent.is_callable() => {
+                    let key = PropertyKey::from("prototype");
+                    match self.get(&parent, &key, &Value::Object(parent.clone()))? {
+                        Value::Object(prototype) => (Some(prototype), parent),
+                        Value::Null => (None, parent),
+                        _ => {
+                            return Err(self.type_error(
+                                "Class extends value does not have valid prototype property",
+                            ));
+                        }
+                    }
+                }
+                value => {
+                    let message = format!(
+                        "Class extends value {} is not a constructor or null",
+                        crate::inspect(&value)
+                    );
+                    return Err(self.type_error(&message));
+                }
+            },
+        };
+
+        let (parameters, constructor_body) = body
+            .iter()
+            .find_map(|element| match element {
+                ClassElement::Constructor { params, body, .. } => {
+                    Some((params.clone(), body.clone()))
+                }
+                _ => None,
+            })
+            .unwrap_or_default();
+        let constructor = self.create_function(
+            name,
+            FunctionKind::ClassConstructor,
+            &parameters,
+            &constructor_body,
+        );
+        constructor.borrow_mut().prototype = Some(constructor_parent);
+        let prototype = match constructor
+            .borrow()
+            .get_own(&"prototype".into())
+            .map(|property| property.data_value())
+        {
+            Some(Value::Object(prototype)) => prototype,
+            _ => unreachable!("constructors have a prototype"),
+        };
+        prototype.borrow_mut().prototype = prototype_parent;
+
+        let mut class = Class {
+            derived: heritage.is_some(),
+            has_constructor: body
+                .iter()
+                .any(|element| matches!(element, ClassElement::Constructor { .. })),
+            fields: Vec::new(),
+        };
+        for element in body {
+            let (name, is_static) = match element {
+                ClassElement::Constructor { .. } => continue,
+                ClassElement::MethodDefinition {
+                    name, is_static, ..
+                }
+                | ClassElement::AsyncMethodDefinition {
+                    name, is_static, ..
+                }
+                | ClassElement::GeneratorMethodDefinition {
+                    name, is_static, ..
+                }
+                | ClassElement::AsyncGeneratorMethodDefinition {
+                    name, is_static, ..
+                }
+                | ClassElement::GetterDefinition {
+                    name, is_static, ..
+                }
+                | ClassElement::SetterDefinition {
+                    name, is_static, ..
+                }
+                | ClassElement::FieldDefinition {
+                    name, is_static, ..
+                }
+                | ClassElement::AccessorDefinition {
+                    name, is_static, ..
+                } => (name, *is_static),
+                ClassElement::StaticBlock { body, .. } => {
+                    self.static_block(&constructor, body)?;
+                    continue;
+                }
+            };
+            let target = if is_static { &constructor } else { &prototype };
+            let key = self.class_element_key(name)?;
+            let name = function_name(&key);
+            match element {
+                ClassElement::MethodDefinition { params, body, .. } => {
+                    let method = self.create_function(&name, FunctionKind::Method, params, body);
+                    target
+                        .borrow_mut()
+                        .define(key, Property::hidden(method.into()));
+                }
+                ClassElement::AsyncMethodDefinition { params, body, .. }
+                | ClassElement::AsyncGeneratorMethodDefinition { params, body, .. } => {
+                    let method = self.create_function(&name, FunctionKind::Async, params, body);
+                    target
+                        .borrow_mut()
+                        .define(key, Property::hidden(method.into()));
+                }
+                ClassElement::GeneratorMethodDefinition { params, body, .. } => {
+                    let method = self.create_function(&name, FunctionKind::Generator, params, body);
+                    target
+                        .borrow_mut()
+                        .define(key, Property::hidden(method.into()));
+                }
+                ClassElement::GetterDefinition { body, .. } => {
+                    let getter = self.create_function(
+                        &format!("get {name}"),
+                        FunctionKind::Method,
+                        &[],
+                        body,
+                    );
+                    self.define_accessor(target, &key, getter, true, false);
+                }
+                ClassElement::SetterDefinition { param, body, .. } => {
+                    let parameters = std::slice::from_ref(param);
+                    let setter = self.create_function(
+                        &format!("set {name}"),
+                        FunctionKind::Method,
+                        parameters,
+                        body,
+                    );
+                    self.define_accessor(target, &key, setter, false, false);
+                }
+                ClassElement::FieldDefinition { value, .. }
+                | ClassElement::AccessorDefinition { value, .. } => {
+                    if is_static {
+                        let value = self.static_initializer(&constructor, *value, &name)?;
+                        target.borrow_mut().define(key, Property::data(value));
+                    } else {
+                        class.fields.push((key, *value));
+                    }
+                }
+                ClassElement::Constructor { .. } | ClassElement::StaticBlock { .. } => {}
+            }
+        }
+        if let Some(mut closure) = closure_of_mut(&constructor) {
+            closure.class = Some(Rc::new(class));
+        }
+        Ok(constructor)
+    }
+
+    // Static fields are evaluated with the class as `this`
+    fn static_initializer(
+        &mut self,
+        class: &ObjectRef,
+        value: Option<ExpressionId>,
+        key: &str,
+    ) -> Result<Value> {
+        let Some(value) = value else {
+            return Ok(Value::Undefined);
+        };
+        let scope = Environment::new(Some(self.environment.clone()));
+        scope.borrow_mut().this = Some(Value::Object(class.clone()));
+        self.in_environment(scope, |interpreter| {
+            interpreter.named_expression(value, key)
+        })
+    }
+
+    fn static_block(&mut self, class: &ObjectRef, body: &[StatementId]) -> Result<()> {
+        let scope = Environment::new(Some(self.environment.clone()));
+        {
+            let mut scope = scope.borrow_mut();
+            scope.this = Some(Value::Object(class.clone()));
+            scope.var_scope = true;
+        }
+        let statements = body_statements(&self.ast, body);
+        self.in_environment(scope, |interpreter| {
+            interpreter.hoist_declarations(&statements, true)?;
+            interpreter.statements(&statements).map(|_| ())
+        })
+    }
+}
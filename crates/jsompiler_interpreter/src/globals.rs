@@ -0,0 +1,266 @@
+use crate::builtins;
+use crate::environment::Environment;
+use crate::function::function_name;
+use crate::object::{Function, NativeConstructor, NativeFunction};
+use crate::{ErrorType, Interpreter, Intrinsics, WellKnownSymbols};
+use jsompiler_common::Atoms;
+use jsompiler_parser::Ast;
+use jsompiler_runtime::{
+    Object, ObjectKind, ObjectRef, Property, PropertyKey, Symbol, Value, collect,
+};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+type Result<T> = std::result::Result<T, Value>;
+
+// An interpreter with the global object and the standard library
+pub(crate) fn create() -> Interpreter {
+    let object_prototype = ObjectRef::new(Object::new(None, ObjectKind::Ordinary));
+    let ordinary = || {
+        ObjectRef::new(Object::new(
+            Some(object_prototype.clone()),
+            ObjectKind::Ordinary,
+        ))
+    };
+    // `Function.prototype` is a function that accepts anything and returns undefined
+    let mut function_prototype = Object::new(
+        Some(object_prototype.clone()),
+        ObjectKind::Function(Box::new(Function::Native(empty))),
+    );
+    function_prototype.define("length", Property::read_only(Value::Number(0.0)));
+    function_prototype.define("name", Property::read_only(Value::string("")));
+    let function_prototype = ObjectRef::new(function_prototype);
+    let array_prototype = ObjectRef::new(Object::array(Some(object_prototype.clone()), Vec::new()));
+    let error_prototype = ordinary();
+    let error_prototypes = ErrorType::ALL
+        .iter()
+        .map(|kind| match kind {
+            ErrorType::Error => error_prototype.clone(),
+            _ => ObjectRef::new(Object::new(
+                Some(error_prototype.clone()),
+                ObjectKind::Ordinary,
+            )),
+        })
+        .collect();
+    // Prototypes of the primitive wrappers are wrappers themselves
+    let primitive = |value: Value| {
+        ObjectRef::new(Object::new(
+            Some(object_prototype.clone()),
+            ObjectKind::Primitive(value),
+        ))
+    };
+    let iterator_prototype = ordinary();
+    let iterator = || {
+        ObjectRef::new(Object::new(
+            Some(iterator_prototype.clone()),
+            ObjectKind::Ordinary,
+        ))
+    };
+    let global = ordinary();
+    let mut throw_type_error = Object::new(
+        Some(function_prototype.clone()),
+        ObjectKind::Function(Box::new(Function::Native(throw_type_error))),
+    );
+    throw_type_error.define(
+        "length",
+        Property::new(Value::Number(0.0), false, false, false),
+    );
+    throw_type_error.define(
+        "name",
+        Property::new(Value::string(""), false, false, false),
+    );
+    throw_type_error.extensible = false;
+    let throw_type_error = ObjectRef::new(throw_type_error);
+    let symbol = |name: &str| Symbol::new(Some(&format!("Symbol.{name}")));
+    let symbols = WellKnownSymbols {
+        async_iterator: symbol("asyncIterator"),
+        has_instance: symbol("hasInstance"),
+        is_concat_spreadable: symbol("isConcatSpreadable"),
+        iterator: symbol("iterator"),
+        match_: symbol("match"),
+        match_all: symbol("matchAll"),
+        replace: symbol("replace"),
+        search: symbol("search"),
+        species: symbol("species"),
+        split: symbol("split"),
+        to_primitive: symbol("toPrimitive"),
+        to_string_tag: symbol("toStringTag"),
+        unscopables: symbol("unscopables"),
+    };
+
+    let global_environment = Environment::new(None);
+    {
+        let mut environment = global_environment.borrow_mut();
+        environment.object = Some(global.clone());
+        environment.this = Some(Value::Object(global.clone()));
+        environment.var_scope = true;
+    }
+    let mut interpreter = Interpreter {
+        global,
+        intrinsics: Intrinsics {
+            function_prototype,
+            array_prototype,
+            error_prototypes,
+            throw_type_error,
+            boolean_prototype: primitive(Value::Boolean(false)),
+            number_prototype: primitive(Value::Number(0.0)),
+            bigint_prototype: ordinary(),
+            string_prototype: primitive(Value::string("")),
+            symbol_prototype: ordinary(),
+            array_iterator_prototype: iterator(),
+            string_iterator_prototype: iterator(),
+            map_prototype: ordinary(),
+            map_iterator_prototype: iterator(),
+            set_prototype: ordinary(),
+            set_iterator_prototype: iterator(),
+            weak_map_prototype: ordinary(),
+            iterator_prototype,
+            object_prototype,
+            symbols,
+        },
+        environment: global_environment.clone(),
+        global_environment,
+        ast: Rc::new(Ast::new()),
+        atoms: Atoms::new(),
+        strict: false,
+        depth: 0,
+        position: 0,
+        thrown_at: 0,
+        completion: Value::Undefined,
+        symbol_registry: HashMap::new(),
+        output: None,
+    };
+    interpreter.install();
+    interpreter
+}
+
+impl Interpreter {
+    // A built-in function object
+    pub fn native_function(
+        &self,
+        name: &str,
+        length: usize,
+        function: NativeFunction,
+    ) -> ObjectRef {
+        self.builtin_function(name, length, Function::Native(function))
+    }
+
+    // A built-in constructor, whose instances inherit from `prototype`
+    pub fn native_constructor(
+        &self,
+        name: &str,
+        length: usize,
+        constructor: NativeConstructor,
+        prototype: &ObjectRef,
+    ) -> ObjectRef {
+        let constructor = self.builtin_function(name, length, Function::Constructor(constructor));
+        let property = Property::new(prototype.clone().into(), false, false, false);
+        constructor.borrow_mut().define("prototype", property);
+        prototype
+            .borrow_mut()
+            .define("constructor", Property::hidden(constructor.clone().into()));
+        constructor
+    }
+
+    fn builtin_function(&self, name: &str, length: usize, function: Function) -> ObjectRef {
+        let mut object = Object::new(
+            Some(self.intrinsics.function_prototype.clone()),
+            ObjectKind::Function(Box::new(function)),
+        );
+        object.define("length", Property::read_only(Value::Number(length as f64)));
+        object.define("name", Property::read_only(Value::string(name)));
+        ObjectRef::new(object)
+    }
+
+    // Define a built-in method, which is writable and configurable but not enumerable
+    pub(crate) fn define_method(
+        &self,
+        object: &ObjectRef,
+        key: impl Into<PropertyKey>,
+        length: usize,
+        function: NativeFunction,
+    ) -> ObjectRef {
+        let key = key.into();
+        let function = self.native_function(&function_name(&key), length, function);
+        object
+            .borrow_mut()
+            .define(key, Property::hidden(Value::Object(function.clone())));
+        function
+    }
+
+    // Define a built-in accessor with only a getter, like `Map.prototype.size`
+    pub(crate) fn define_getter(
+        &self,
+        object: &ObjectRef,
+        key: impl Into<PropertyKey>,
+        getter: NativeFunction,
+    ) {
+        let key = key.into();
+        let name = format!("get {}", function_name(&key));
+        let getter = self.native_function(&name, 0, getter);
+        let mut property = Property::accessor(Some(getter), None, false);
+        property.configurable = true;
+        object.borrow_mut().define(key, property);
+    }
+
+    // The `Symbol.toStringTag` of a prototype, which `Object.prototype.toString` reports
+    pub(crate) fn define_to_string_tag(&self, object: &ObjectRef, tag: &str) {
+        let key = self.intrinsics.symbols.to_string_tag.clone();
+        let property = Property::new(Value::string(tag), false, false, true);
+        object.borrow_mut().define(key, property);
+    }
+
+    // Define a global binding, like the constructors and namespaces of the standard library
+    pub(crate) fn define_global(&self, name: &str, value: impl Into<Value>) {
+        self.global
+            .borrow_mut()
+            .define(name, Property::hidden(value.into()));
+    }
+
+    fn install(&mut self) {
+        self.define_global("globalThis", self.global.clone());
+        let constant = |name: &str, value: Value| {
+            let property = Property::new(value, false, false, false);
+            self.global.borrow_mut().define(name, property);
+        };
+        constant("Infinity", Value::Number(f64::INFINITY));
+        constant("NaN", Value::Number(f64::NAN));
+        constant("undefined", Value::Undefined);
+        builtins::install(self);
+    }
+
+    // Define a global `gc()` that collects garbage when scripts ask for it, like Node.js
+    // does with `--expose-gc`
+    pub fn expose_gc(&mut self) {
+        let gc = self.native_function("gc", 0, gc);
+        self.define_global("gc", gc);
+    }
+
+    // Free the objects, closures and environments nothing can reach any more, instead of
+    // waiting until enough were allocated. Returns how many were freed.
+    pub fn collect_garbage(&mut self) -> usize {
+        collect()
+    }
+
+    pub(crate) fn error_prototype(&self, kind: ErrorType) -> ObjectRef {
+        let index = ErrorType::ALL
+            .iter()
+            .position(|&other| other == kind)
+            .expect("every error type has a prototype");
+        self.intrinsics.error_prototypes[index].clone()
+    }
+}
+
+fn gc(interpreter: &mut Interpreter, _: Value, _: &[Value]) -> Result<Value> {
+    Ok(Value::Number(interpreter.collect_garbage() as f64))
+}
+
+fn empty(_: &mut Interpreter, _: Value, _: &[Value]) -> Result<Value> {
+    Ok(Value::Undefined)
+}
+
+fn throw_type_error(interpreter: &mut Interpreter, _: Value, _: &[Value]) -> Result<Value> {
+    Err(interpreter.type_error(
+        "'caller', 'callee', and 'arguments' properties may not be accessed on strict mode functions or the arguments objects for calls to them",
+    ))
+}
@@ -0,0 +1,338 @@
+use crate::number::number_to_string;
+use crate::object::{Function, FunctionKind, function_of};
+use jsompiler_runtime::{ObjectKind, ObjectRef, PropertyKey, PropertyValue, Value};
+
+// Objects nested deeper than this are abbreviated to `[Object]` and `[Array]`
+const MAX_DEPTH: usize = 2;
+// Lines longer than this are broken with one entry per line
+const BREAK_LENGTH: usize = 80;
+
+// A readable description of a value, the way Node.js's `console.log` prints it
+pub fn inspect(value: &Value) -> String {
+    let mut inspector = Inspector {
+        seen: Vec::new(),
+        indentation: 0,
+        current_depth: 0,
+    };
+    inspector.value(value, 0)
+}
+
+struct Inspector {
+    seen: Vec<ObjectRef>, // Objects being printed, to find cycles
+    indentation: usize,
+    current_depth: usize, // Depth of the object printed last
+}
+
+impl Inspector {
+    fn value(&mut self, value: &Value, depth: usize) -> String {
+        match value {
+            Value::Undefined => "undefined".to_string(),
+            Value::Null => "null".to_string(),
+            Value::Boolean(value) => value.to_string(),
+            Value::Number(value) if *value == 0.0 && value.is_sign_negative() => "-0".to_string(),
+            Value::Number(value) => number_to_string(*value),
+            Value::BigInt(value) => format!("{value}n"),
+            Value::String(text) => quote(text),
+            Value::Symbol(symbol) => symbol.to_string(),
+            Value::Object(object) => self.object(object, depth),
+        }
+    }
+
+    fn object(&mut self, object: &ObjectRef, depth: usize) -> String {
+        if self.seen.iter().any(|seen| seen.ptr_eq(object)) {
+            return "[Circular]".to_string();
+        }
+        let length = list_length(object);
+        let is_array = length.is_some();
+        let (base, prefix) = match &object.borrow().kind {
+            ObjectKind::Function(_) | ObjectKind::BoundFunction(_) => {
+                (function_base(object), String::new())
+            }
+            ObjectKind::Error => (error_base(object), String::new()),
+            ObjectKind::Arguments(_) => (String::new(), "[Arguments] ".to_string()),
+            ObjectKind::Primitive(value) => (primitive_base(value), String::new()),
+            ObjectKind::Map(map) => (String::new(), format!("Map({}) ", map.len())),
+            ObjectKind::Set(set) => (String::new(), format!("Set({}) ", set.len())),
+            ObjectKind::WeakMap(_) => return "WeakMap { <items unknown> }".to_string(),
+            ObjectKind::ArrayIterator(_) => (String::new(), "Object [Array Iterator] ".to_string()),
+            ObjectKind::StringIterator(_) => {
+                (String::new(), "Object [String Iterator] ".to_string())
+            }
+            ObjectKind::MapIterator(_) => (String::new(), "Object [Map Iterator] ".to_string()),
+            ObjectKind::SetIterator(_) => (String::new(), "Object [Set Iterator] ".to_string()),
+            _ => (String::new(), constructor_prefix(object)),
+        };
+        // The indices of a String object are its characters, which the base already shows
+        let is_string = matches!(
+            object.borrow().kind,
+            ObjectKind::Primitive(Value::String(_))
+        );
+        // Array elements are listed by index, and the other properties after them
+        let keys: Vec<PropertyKey> = object
+            .borrow()
+            .own_keys()
+            .into_iter()
+            .filter(|key| {
+                object
+                    .borrow()
+                    .get_own(key)
+                    .is_some_and(|property| property.enumerable)
+                    && !(is_string && key.as_index().is_some())
+            })
+            .collect();
+        let (indices, keys): (Vec<PropertyKey>, Vec<PropertyKey>) = keys
+            .into_iter()
+            .partition(|key| is_array && key.as_index().is_some());
+        let collection = collection_entries(object);
+        let has_entries = length.unwrap_or(0) > 0 || !keys.is_empty() || !collection.is_empty();
+        if !base.is_empty() && !has_entries {
+            return base;
+        }
+        let (open, close) = if is_array {
+            (format!("{prefix}["), "]")
+        } else {
+            (format!("{prefix}{{"), "}")
+        };
+        if !has_entries {
+            return format!("{open}{close}");
+        }
+        if depth > MAX_DEPTH {
+            return if is_array {
+                "[Array]".to_string()
+            } else if prefix.is_empty() {
+                "[Object]".to_string()
+            } else {
+                format!("[{}]", prefix.trim_end())
+            };
+        }
+
+        self.seen.push(object.clone());
+        self.current_depth = depth;
+        self.indentation += 2;
+        let mut entries = Vec::new();
+        let mut next = 0;
+        for key in &indices {
+            let index = key.as_index().unwrap_or_default();
+            if index > next {
+                entries.push(empty_items(index - next));
+            }
+            next = index + 1;
+            if let Some(text) = self.property(object, key, depth) {
+                entries.push(text);
+            }
+        }
+        if let Some(length) = length
+            && length > next
+        {
+            entries.push(empty_items(length - next));
+        }
+        // Map entries are `key => value`, and Set entries their value
+        for (key, value) in &collection {
+            let value = value.as_ref().map(|value| self.value(value, depth + 1));
+            let key = self.value(key, depth + 1);
+            entries.push(match value {
+                Some(value) => format!("{key} => {value}"),
+                None => key,
+            });
+        }
+        for key in &keys {
+            if let Some(text) = self.property(object, key, depth) {
+                entries.push(format!("{}: {text}", property_key(key)));
+            }
+        }
+        self.indentation -= 2;
+        self.seen.pop();
+        self.join(entries, &base, &open, close, depth)
+    }
+
+    fn property(&mut self, object: &ObjectRef, key: &PropertyKey, depth: usize) -> Option<String> {
+        let property = object.borrow().get_own(key)?;
+        Some(match property.value {
+            PropertyValue::Data { value, .. } => self.value(&value, depth + 1),
+            PropertyValue::Accessor { get, set } => match (get, set) {
+                (Some(_), Some(_)) => "[Getter/Setter]".to_string(),
+                (Some(_), None) => "[Getter]".to_string(),
+                _ => "[Setter]".to_string(),
+            },
+        })
+    }
+
+    // One line if it fits and nothing nested is too deep, else one entry per line
+    fn join(
+        &self,
+        entries: Vec<String>,
+        base: &str,
+        open: &str,
+        close: &str,
+        depth: usize,
+    ) -> String {
+        let base = if base.is_empty() {
+            String::new()
+        } else {
+            format!("{base} ")
+        };
+        if self.current_depth - depth < 3 {
+            let start = entries.len() + self.indentation + open.len() + base.len() + 10;
+            let total: usize = entries.iter().map(|entry| entry.len()).sum::<usize>() + start;
+            let joined = entries.join(", ");
+            if total + entries.len() <= BREAK_LENGTH && !joined.contains('\n') {
+                return format!("{base}{open} {joined} {close}");
+            }
+        }
+        let indentation = format!("\n{}", " ".repeat(self.indentation));
+        format!(
+            "{base}{open}{indentation}  {}{indentation}{close}",
+            entries.join(&format!(",{indentation}  "))
+        )
+    }
+}
+
+// The data property `key` of an object or its prototypes, if it is a string
+fn string_property(object: &ObjectRef, key: &str) -> Option<String> {
+    match object.find_property(&key.into())?.data_value() {
+        Value::String(text) => Some(text.to_string()),
+        _ => None,
+    }
+}
+
+// The length of an array, or of an `arguments` object whose `length` is still a number
+fn list_length(object: &ObjectRef) -> Option<u32> {
+    let object = object.borrow();
+    match object.kind {
+        ObjectKind::Arguments(_) => match object.get_own(&"length".into())?.data_value() {
+            Value::Number(length) => Some(length.clamp(0.0, u32::MAX as f64) as u32),
+            _ => None,
+        },
+        _ => object.array_length(),
+    }
+}
+
+// The entries of a Map, or the values of a Set without a value of their own
+fn collection_entries(object: &ObjectRef) -> Vec<(Value, Option<Value>)> {
+    let object = object.borrow();
+    let (map, is_set) = match &object.kind {
+        ObjectKind::Map(map) => (map, false),
+        ObjectKind::Set(set) => (set, true),
+        _ => return Vec::new(),
+    };
+    let mut entries = Vec::new();
+    let mut position = 0;
+    while let Some((found, key, value)) = map.entry(position) {
+        entries.push((key, (!is_set).then_some(value)));
+        position = found + 1;
+    }
+    entries
+}
+
+// `[Number: 1]` for a Number object, and likewise for the other primitive wrappers
+fn primitive_base(value: &Value) -> String {
+    let kind = match value {
+        Value::Boolean(_) => "Boolean",
+        Value::Number(_) => "Number",
+        Value::BigInt(_) => "BigInt",
+        Value::String(_) => "String",
+        Value::Symbol(_) => "Symbol",
+        _ => "Object",
+    };
+    format!("[{kind}: {}]", inspect(value))
+}
+
+// Holes in an array, like `<2 empty items>`
+fn empty_items(count: u32) -> String {
+    match count {
+        1 => "<1 empty item>".to_string(),
+        count => format!("<{count} empty items>"),
+    }
+}
+
+fn function_base(object: &ObjectRef) -> String {
+    let name = object
+        .borrow()
+        .get_own(&"name".into())
+        .and_then(|property| match property.data_value() {
+            Value::String(name) if !name.is_empty() => Some(name),
+            _ => None,
+        });
+    let is_class = matches!(function_of(object).as_deref(), Some(Function::Closure(closure)) if closure.code.kind == FunctionKind::ClassConstructor);
+    match (is_class, name) {
+        (true, Some(name)) => format!("[class {name}]"),
+        (true, None) => "[class (anonymous)]".to_string(),
+        (false, Some(name)) => format!("[Function: {name}]"),
+        (false, None) => "[Function (anonymous)]".to_string(),
+    }
+}
+
+fn error_base(object: &ObjectRef) -> String {
+    let name = string_property(object, "name").unwrap_or_else(|| "Error".to_string());
+    match string_property(object, "message") {
+        Some(message) if !message.is_empty() => format!("{name}: {message}"),
+        _ => name,
+    }
+}
+
+// "Shape " for instances of a class, nothing for plain objects and arrays
+fn constructor_prefix(object: &ObjectRef) -> String {
+    let Some(mut prototype) = object.prototype() else {
+        return "[Object: null prototype] ".to_string();
+    };
+    loop {
+        let constructor = prototype.borrow().get_own(&"constructor".into());
+        if let Some(constructor) = constructor {
+            if let Value::Object(constructor) = constructor.data_value()
+                && let Some(name) = string_property(&constructor, "name")
+                && name != "Object"
+                && name != "Array"
+                && !name.is_empty()
+            {
+                return format!("{name} ");
+            }
+            return String::new();
+        }
+        match prototype.prototype() {
+            Some(next) => prototype = next,
+            None => return String::new(),
+        }
+    }
+}
+
+fn is_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
+fn property_key(key: &PropertyKey) -> String {
+    match key {
+        PropertyKey::Symbol(symbol) => format!("[{symbol}]"),
+        PropertyKey::String(key) if is_identifier(key) => key.to_string(),
+        key => quote(&key.to_string()),
+    }
+}
+
+// Single quotes unless the text has some and no double quotes
+fn quote(text: &str) -> String {
+    let quote = if text.contains('\'') && !text.contains('"') {
+        '"'
+    } else {
+        '\''
+    };
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push(quote);
+    for c in text.chars() {
+        match c {
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            '\r' => quoted.push_str("\\r"),
+            '\\' => quoted.push_str("\\\\"),
+            c if c == quote => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push(quote);
+    quoted
+}
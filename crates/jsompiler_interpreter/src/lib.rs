@@ -0,0 +1,317 @@
+mod builtins;
+mod bytecode;
+mod environment;
+mod expression;
+mod function;
+mod globals;
+mod inspect;
+mod number;
+mod object;
+mod operations;
+mod statement;
+mod test;
+
+pub use bytecode::{Code, CompiledFunction, Opcode, compile, disassemble};
+pub use environment::{Binding, EnvRef, Environment};
+pub use inspect::inspect;
+pub use jsompiler_runtime::{
+    HeapStats, Object, ObjectKind, ObjectRef, Property, PropertyDescriptor, PropertyKey,
+    PropertyValue, Symbol, Value, heap_stats,
+};
+pub use number::number_to_string;
+pub use object::{
+    Class, Closure, Function, FunctionCode, FunctionKind, NativeConstructor, NativeFunction,
+    function_of,
+};
+
+use jsompiler_common::{Atoms, Error, ErrorKind};
+use jsompiler_lexer::Lexer;
+use jsompiler_parser::{Ast, Node, Parser, StatementId};
+use jsompiler_semantic::{Semantic, SemanticOptions, analyze, check, has_use_strict};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+// Calls nested deeper than this throw a RangeError instead of overflowing the native stack.
+// Each call takes a few kilobytes of it, so scripts should run on a thread with a stack of
+// at least `STACK_SIZE` bytes.
+const MAX_CALL_DEPTH: usize = 1000;
+pub const STACK_SIZE: usize = 64 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorType {
+    Error,
+    EvalError,
+    RangeError,
+    ReferenceError,
+    SyntaxError,
+    TypeError,
+    URIError,
+    AggregateError,
+}
+
+impl ErrorType {
+    pub const ALL: [ErrorType; 8] = [
+        ErrorType::Error,
+        ErrorType::EvalError,
+        ErrorType::RangeError,
+        ErrorType::ReferenceError,
+        ErrorType::SyntaxError,
+        ErrorType::TypeError,
+        ErrorType::URIError,
+        ErrorType::AggregateError,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ErrorType::Error => "Error",
+            ErrorType::EvalError => "EvalError",
+            ErrorType::RangeError => "RangeError",
+            ErrorType::ReferenceError => "ReferenceError",
+            ErrorType::SyntaxError => "SyntaxError",
+            ErrorType::TypeError => "TypeError",
+            ErrorType::URIError => "URIError",
+            ErrorType::AggregateError => "AggregateError",
+        }
+    }
+}
+
+// Parse a script and check it for early errors, which are returned instead of the AST
+pub fn parse(source: &str) -> Result<(Ast, Semantic), Vec<Error>> {
+    parse_with_atoms(source, Atoms::new())
+}
+
+// Parse a script whose names are added to `atoms`, so its atoms agree with those of the
+// scripts parsed into the table before
+fn parse_with_atoms(source: &str, atoms: Atoms) -> Result<(Ast, Semantic), Vec<Error>> {
+    let mut lexer = Lexer::new(source.to_string());
+    lexer.scan_all_tokens();
+    // The lexer is built against the published `jsompiler_common`, so its errors are converted
+    if !lexer.errors.is_empty() {
+        return Err(lexer
+            .errors
+            .into_iter()
+            .map(|error| {
+                Error::new(
+                    ErrorKind::LexerError,
+                    error.message,
+                    error.line_number,
+                    error.pos,
+                )
+            })
+            .collect());
+    }
+    let mut parser = Parser::new(lexer.tokens);
+    parser.ast.atoms = atoms;
+    parser.parse();
+    if !parser.errors.is_empty() {
+        return Err(parser.errors);
+    }
+    let semantic = analyze(&parser.ast, &SemanticOptions::default());
+    let errors = check(&parser.ast, &semantic, source);
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    Ok((parser.ast, semantic))
+}
+
+// Objects the interpreter needs to create values, made once per interpreter
+pub(crate) struct Intrinsics {
+    pub object_prototype: ObjectRef,
+    pub function_prototype: ObjectRef,
+    pub array_prototype: ObjectRef,
+    pub error_prototypes: Vec<ObjectRef>, // In the order of `ErrorType::ALL`
+    pub throw_type_error: ObjectRef,      // The getter of `callee` on strict `arguments`
+    pub boolean_prototype: ObjectRef,
+    pub number_prototype: ObjectRef,
+    pub bigint_prototype: ObjectRef,
+    pub string_prototype: ObjectRef,
+    pub symbol_prototype: ObjectRef,
+    pub iterator_prototype: ObjectRef,
+    pub array_iterator_prototype: ObjectRef,
+    pub string_iterator_prototype: ObjectRef,
+    pub map_prototype: ObjectRef,
+    pub map_iterator_prototype: ObjectRef,
+    pub set_prototype: ObjectRef,
+    pub set_iterator_prototype: ObjectRef,
+    pub weak_map_prototype: ObjectRef,
+    pub symbols: WellKnownSymbols,
+}
+
+// The symbols the language itself looks up, which `Symbol` has as properties
+pub(crate) struct WellKnownSymbols {
+    pub async_iterator: Symbol,
+    pub has_instance: Symbol,
+    pub is_concat_spreadable: Symbol,
+    pub iterator: Symbol,
+    pub match_: Symbol,
+    pub match_all: Symbol,
+    pub replace: Symbol,
+    pub search: Symbol,
+    pub species: Symbol,
+    pub split: Symbol,
+    pub to_primitive: Symbol,
+    pub to_string_tag: Symbol,
+    pub unscopables: Symbol,
+}
+
+// Evaluates programs against one global object, so later programs see what earlier ones
+// declared. `console.log` prints to stdout, or to a buffer when output is captured.
+pub struct Interpreter {
+    pub global: ObjectRef,
+    pub(crate) intrinsics: Intrinsics,
+    pub(crate) global_environment: EnvRef,
+    pub(crate) environment: EnvRef, // The scope being evaluated
+    pub(crate) ast: Rc<Ast>,        // The program the running code belongs to
+    pub(crate) atoms: Atoms,        // Names of every program run, as environments are keyed by atom
+    pub(crate) strict: bool,
+    pub(crate) depth: usize,
+    pub(crate) position: usize,   // Start of the statement being evaluated
+    pub(crate) thrown_at: usize,  // Where the last exception was thrown
+    pub(crate) completion: Value, // Value of the last expression statement
+    pub(crate) symbol_registry: HashMap<Rc<str>, Symbol>, // For `Symbol.for`
+    output: Option<String>,
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Interpreter {
+    pub fn new() -> Self {
+        globals::create()
+    }
+
+    // An interpreter whose `console.log` output is kept for `output` instead of printed
+    pub fn capturing() -> Self {
+        let mut interpreter = Self::new();
+        interpreter.output = Some(String::new());
+        interpreter
+    }
+
+    // What `console.log` printed so far, if output is captured
+    pub fn output(&self) -> &str {
+        self.output.as_deref().unwrap_or_default()
+    }
+
+    pub(crate) fn print(&mut self, line: &str) {
+        match &mut self.output {
+            Some(output) => {
+                output.push_str(line);
+                output.push('\n');
+            }
+            None => println!("{line}"),
+        }
+    }
+
+    // Run a script. Syntax and early errors are returned without running anything, and an
+    // exception nothing catches is returned as a `RuntimeError`. Otherwise the result is the
+    // value of the last expression statement, as `eval` would return.
+    pub fn run(&mut self, source: &str) -> Result<Value, Vec<Error>> {
+        let (ast, _) = self.parse(source)?;
+        self.evaluate(ast)
+            .map_err(|exception| self.uncaught(&exception, source))
+    }
+
+    // Run a script like `run`, compiled to bytecode for the VM
+    pub fn run_bytecode(&mut self, source: &str) -> Result<Value, Vec<Error>> {
+        let (ast, semantic) = parse(source)?;
+        let code = compile(&ast, &semantic, source).map_err(|error| vec![error])?;
+        self.execute(code)
+            .map_err(|exception| self.uncaught(&exception, source))
+    }
+
+    // Parse a script like `parse`, with its names interned in the table of this interpreter.
+    // Only scripts parsed this way can be passed to `evaluate`.
+    pub fn parse(&mut self, source: &str) -> Result<(Ast, Semantic), Vec<Error>> {
+        parse_with_atoms(source, self.atoms.clone())
+    }
+
+    fn uncaught(&self, exception: &Value, source: &str) -> Vec<Error> {
+        let message = format!("Uncaught {}", self.describe_exception(exception));
+        let line_number = source
+            .chars()
+            .take(self.thrown_at)
+            .filter(|c| *c == '\n')
+            .count()
+            + 1;
+        vec![Error::new(
+            ErrorKind::RuntimeError,
+            message,
+            line_number,
+            self.thrown_at,
+        )]
+    }
+
+    // Evaluate a parsed script. An exception nothing catches is returned as the thrown value.
+    pub fn evaluate(&mut self, ast: Ast) -> Result<Value, Value> {
+        let statements: Vec<StatementId> = ast
+            .body
+            .iter()
+            .flat_map(|node| match node {
+                Node::Statement(statements) => statements.clone(),
+                Node::Expression(_) => Vec::new(),
+            })
+            .collect();
+        self.strict = has_use_strict(&ast, &statements);
+        // The table only grows, so the script's holds every name interned before it
+        self.atoms = ast.atoms.clone();
+        self.ast = Rc::new(ast);
+        self.environment = self.global_environment.clone();
+        self.depth = 0;
+        self.completion = Value::Undefined;
+
+        self.hoist_declarations(&statements, true)?;
+        self.statements(&statements)?;
+        Ok(std::mem::replace(&mut self.completion, Value::Undefined))
+    }
+
+    // "TypeError: message" for errors, like an uncaught exception is reported
+    pub fn describe_exception(&self, exception: &Value) -> String {
+        let Some(object) = exception.as_object().filter(|_| exception.is_error()) else {
+            return inspect(exception);
+        };
+        let text = |key: &str| match object.find_property(&key.into()) {
+            Some(property) => match property.data_value() {
+                Value::String(text) => text.to_string(),
+                _ => String::new(),
+            },
+            None => String::new(),
+        };
+        let (name, message) = (text("name"), text("message"));
+        if message.is_empty() {
+            name
+        } else {
+            format!("{name}: {message}")
+        }
+    }
+
+    // A new error object, as `TypeError(message)` creates
+    pub fn error(&mut self, kind: ErrorType, message: &str) -> Value {
+        let mut object = Object::new(Some(self.error_prototype(kind)), ObjectKind::Error);
+        object.define("message", Property::hidden(Value::string(message)));
+        self.thrown_at = self.position;
+        Value::Object(ObjectRef::new(object))
+    }
+
+    pub(crate) fn type_error(&mut self, message: &str) -> Value {
+        self.error(ErrorType::TypeError, message)
+    }
+
+    pub(crate) fn reference_error(&mut self, message: &str) -> Value {
+        self.error(ErrorType::ReferenceError, message)
+    }
+
+    pub(crate) fn range_error(&mut self, message: &str) -> Value {
+        self.error(ErrorType::RangeError, message)
+    }
+
+    // Features the parser accepts that the interpreter can't run yet
+    pub(crate) fn unsupported(&mut self, feature: &str) -> Value {
+        self.error(
+            ErrorType::SyntaxError,
+            &format!("{feature} are not supported by the interpreter yet"),
+        )
+    }
+}
@@ -0,0 +1,238 @@
+use num_bigint::BigInt;
+
+// Number::toString: the shortest digits that round trip, in exponent form below 1e-6 and
+// from 1e21 on
+pub fn number_to_string(value: f64) -> String {
+    if value.is_nan() {
+        return "NaN".to_string();
+    }
+    if value == 0.0 {
+        return "0".to_string();
+    }
+    if value.is_infinite() {
+        return if value > 0.0 { "Infinity" } else { "-Infinity" }.to_string();
+    }
+    if value < 0.0 {
+        return format!("-{}", number_to_string(-value));
+    }
+
+    // Rust prints the shortest round trip digits as `d.ddde±x`
+    let exponent_form = format!("{value:e}");
+    let (mantissa, exponent) = exponent_form
+        .split_once('e')
+        .expect("exponent formatting has an exponent");
+    let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    let k = digits.len() as i32;
+    let n = exponent.parse::<i32>().expect("the exponent is a number") + 1;
+
+    if k <= n && n <= 21 {
+        format!("{digits}{}", "0".repeat((n - k) as usize))
+    } else if 0 < n && n <= 21 {
+        let (whole, fraction) = digits.split_at(n as usize);
+        format!("{whole}.{fraction}")
+    } else if -6 < n && n <= 0 {
+        format!("0.{}{digits}", "0".repeat(-n as usize))
+    } else {
+        let sign = if n - 1 < 0 { '-' } else { '+' };
+        let (first, rest) = digits.split_at(1);
+        let fraction = if rest.is_empty() {
+            String::new()
+        } else {
+            format!(".{rest}")
+        };
+        format!("{first}{fraction}e{sign}{}", (n - 1).abs())
+    }
+}
+
+// WhiteSpace and LineTerminator, which `trim` and number parsing skip
+pub fn is_whitespace(c: char) -> bool {
+    (c.is_whitespace() && c != '\u{85}') || c == '\u{feff}'
+}
+
+// StringToNumber: decimal, hex, octal and binary literals with surrounding whitespace.
+// Anything else is NaN.
+pub fn string_to_number(text: &str) -> f64 {
+    let text = text.trim_matches(is_whitespace);
+    if text.is_empty() {
+        return 0.0;
+    }
+    for (prefix, radix) in [("0x", 16), ("0o", 8), ("0b", 2)] {
+        if text.len() > 2 && text[..2].eq_ignore_ascii_case(prefix) {
+            return match BigInt::parse_bytes(&text.as_bytes()[2..], radix) {
+                Some(value) => bigint_to_number(&value),
+                None => f64::NAN,
+            };
+        }
+    }
+    let unsigned = text.strip_prefix(['+', '-']).unwrap_or(text);
+    if unsigned == "Infinity" {
+        return if text.starts_with('-') {
+            f64::NEG_INFINITY
+        } else {
+            f64::INFINITY
+        };
+    }
+    // Rust also reads "inf", "nan" and "1_000" style text, which JavaScript doesn't
+    let is_decimal = unsigned
+        .bytes()
+        .all(|byte| byte.is_ascii_digit() || matches!(byte, b'.' | b'e' | b'E' | b'+' | b'-'));
+    if !is_decimal || unsigned.starts_with(['e', 'E']) || unsigned == "." {
+        return f64::NAN;
+    }
+    text.parse().unwrap_or(f64::NAN)
+}
+
+// StringToBigInt, used when a string is compared with a BigInt
+pub fn string_to_bigint(text: &str) -> Option<BigInt> {
+    let text = text.trim_matches(is_whitespace);
+    if text.is_empty() {
+        return Some(BigInt::default());
+    }
+    for (prefix, radix) in [("0x", 16), ("0o", 8), ("0b", 2)] {
+        if text.len() > 2 && text[..2].eq_ignore_ascii_case(prefix) {
+            return BigInt::parse_bytes(&text.as_bytes()[2..], radix);
+        }
+    }
+    let digits = text.strip_prefix(['+', '-']).unwrap_or(text);
+    if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    text.parse().ok()
+}
+
+pub fn bigint_to_number(value: &BigInt) -> f64 {
+    value.to_string().parse().unwrap_or(f64::NAN)
+}
+
+// The BigInt a number is equal to, if it is an integer
+pub fn number_to_bigint(value: f64) -> Option<BigInt> {
+    if !value.is_finite() || value.fract() != 0.0 {
+        return None;
+    }
+    format!("{value:.0}").parse().ok()
+}
+
+// ToInt32, for bitwise operators
+pub fn to_int32(value: f64) -> i32 {
+    to_uint32(value) as i32
+}
+
+// ToUint32, for `>>>` and shift counts
+pub fn to_uint32(value: f64) -> u32 {
+    if !value.is_finite() {
+        return 0;
+    }
+    value.trunc().rem_euclid(4294967296.0) as u32
+}
+
+// Number::toString with a radix other than 10. Fractions get as many digits as it takes to
+// tell the number apart from its neighbors, rounding the last one like V8 does.
+pub fn number_to_radix_string(value: f64, radix: u32) -> String {
+    if value.is_nan() {
+        return "NaN".to_string();
+    }
+    if value.is_infinite() {
+        return if value > 0.0 { "Infinity" } else { "-Infinity" }.to_string();
+    }
+    if value < 0.0 {
+        return format!("-{}", number_to_radix_string(-value, radix));
+    }
+    let digit = |value: u32| char::from_digit(value, radix).expect("digits are below the radix");
+    let mut integer = value.floor();
+    let mut fraction = value - integer;
+    // Half the distance to the next number, which is how precise the digits need to be
+    let mut delta = (0.5 * (f64::from_bits(value.to_bits() + 1) - value)).max(f64::from_bits(1));
+    let mut fraction_digits = String::new();
+    if fraction >= delta {
+        loop {
+            fraction *= radix as f64;
+            delta *= radix as f64;
+            let next = fraction as u32;
+            fraction_digits.push(digit(next));
+            fraction -= next as f64;
+            if (fraction > 0.5 || (fraction == 0.5 && next & 1 == 1)) && fraction + delta > 1.0 {
+                // Round up, carrying into the digits before
+                loop {
+                    let Some(last) = fraction_digits.pop() else {
+                        integer += 1.0;
+                        break;
+                    };
+                    let last = last.to_digit(radix).expect("the digit is in the radix");
+                    if last + 1 < radix {
+                        fraction_digits.push(digit(last + 1));
+                        break;
+                    }
+                }
+                break;
+            }
+            if fraction < delta {
+                break;
+            }
+        }
+    }
+    let radix_f64 = radix as f64;
+    let mut integer_digits = Vec::new();
+    // Digits beyond the precision of a double are zeros
+    while integer / radix_f64 >= 9007199254740992.0 {
+        integer = (integer / radix_f64).floor();
+        integer_digits.push('0');
+    }
+    loop {
+        let remainder = integer % radix_f64;
+        integer_digits.push(digit(remainder as u32));
+        integer = (integer - remainder) / radix_f64;
+        if integer <= 0.0 {
+            break;
+        }
+    }
+    let integer_digits: String = integer_digits.into_iter().rev().collect();
+    if fraction_digits.is_empty() {
+        integer_digits
+    } else {
+        format!("{integer_digits}.{fraction_digits}")
+    }
+}
+
+// The integer closest to `value * 10 ** scale` for a positive finite value, rounding halves up.
+// The value is exact in binary, so this is computed exactly.
+pub fn round_scaled(value: f64, scale: i32) -> BigInt {
+    let bits = value.to_bits();
+    let exponent = ((bits >> 52) & 0x7ff) as i32;
+    let fraction = bits & ((1 << 52) - 1);
+    let (mantissa, exponent) = match exponent {
+        0 => (fraction, -1074),
+        _ => (fraction | (1 << 52), exponent - 1075),
+    };
+    let mut numerator = BigInt::from(mantissa);
+    let mut denominator = BigInt::from(1);
+    if exponent > 0 {
+        numerator <<= exponent as usize;
+    } else {
+        denominator <<= (-exponent) as usize;
+    }
+    let power = BigInt::from(10).pow(scale.unsigned_abs());
+    if scale > 0 {
+        numerator *= power;
+    } else {
+        denominator *= power;
+    }
+    (numerator * 2 + &denominator) / (denominator * 2)
+}
+
+// The digits of a positive finite value rounded to `precision` significant digits, and the
+// exponent of the first one
+pub fn significant_digits(value: f64, precision: u32) -> (String, i32) {
+    let shortest = format!("{value:e}");
+    let (_, exponent) = shortest
+        .split_once('e')
+        .expect("exponent formatting has an exponent");
+    let mut exponent: i32 = exponent.parse().expect("the exponent is a number");
+    loop {
+        let digits = round_scaled(value, precision as i32 - 1 - exponent).to_string();
+        match digits.len().cmp(&(precision as usize)) {
+            std::cmp::Ordering::Greater => exponent += 1,
+            std::cmp::Ordering::Less if digits != "0" => exponent -= 1,
+            _ => return (digits, exponent),
+        }
+    }
+}
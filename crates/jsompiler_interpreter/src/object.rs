@@ -0,0 +1,100 @@
+use crate::Interpreter;
+use crate::bytecode::CompiledFunction;
+use crate::environment::EnvRef;
+use jsompiler_parser::{Ast, ExpressionId, Parameter, StatementId};
+use jsompiler_runtime::{ObjectKind, ObjectRef, PropertyKey, Trace, Tracer, Value};
+use std::any::Any;
+use std::cell::{Ref, RefMut};
+use std::rc::Rc;
+
+// What the interpreter keeps in the objects of the functions it creates
+pub enum Function {
+    Closure(Closure),
+    Native(NativeFunction),
+    Constructor(NativeConstructor),
+    Compiled(CompiledFunction),
+}
+
+// A built-in function, called with `this` and the arguments
+pub type NativeFunction = fn(&mut Interpreter, Value, &[Value]) -> Result<Value, Value>;
+
+// A built-in constructor, called with the arguments and `new.target`, which is `None` when
+// it is called as a function
+pub type NativeConstructor =
+    fn(&mut Interpreter, &[Value], Option<&ObjectRef>) -> Result<Value, Value>;
+
+// A function written in JavaScript and the environment it was created in
+pub struct Closure {
+    pub code: Rc<FunctionCode>,
+    pub environment: EnvRef,
+    pub class: Option<Rc<Class>>, // What a class constructor does besides running its body
+}
+
+// The parts of a class that make its instances
+#[derive(Debug, Default)]
+pub struct Class {
+    pub derived: bool, // Has an `extends` clause, so the parent constructor makes `this`
+    pub has_constructor: bool, // Declares a constructor instead of using the default one
+    pub fields: Vec<(PropertyKey, Option<ExpressionId>)>, // Instance fields with their keys evaluated
+}
+
+#[derive(Debug)]
+pub struct FunctionCode {
+    pub ast: Rc<Ast>,
+    pub name: Rc<str>,
+    pub kind: FunctionKind,
+    pub parameters: Vec<Parameter>,
+    pub body: Vec<StatementId>,
+    pub strict: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FunctionKind {
+    #[default]
+    Normal,
+    Arrow,            // Takes `this` and `arguments` from where it was created
+    Method,           // Object and class methods and accessors, which can't be constructed
+    ClassConstructor, // Throws unless called with `new`
+    Generator,
+    Async,
+}
+
+impl Trace for Function {
+    fn trace(&self, tracer: &mut Tracer) {
+        match self {
+            Function::Closure(closure) => tracer.edge(&closure.environment),
+            Function::Native(_) | Function::Constructor(_) => {}
+            Function::Compiled(function) => {
+                if tracer.through(&function.captures) {
+                    for cell in function.captures.iter() {
+                        tracer.edge(cell);
+                    }
+                }
+                function.this.trace(tracer);
+            }
+        }
+    }
+}
+
+// The function a callable object runs, unless it is a bound function
+pub fn function_of(object: &ObjectRef) -> Option<Ref<'_, Function>> {
+    Ref::filter_map(object.borrow(), |object| match &object.kind {
+        ObjectKind::Function(function) => (function.as_ref() as &dyn Any).downcast_ref(),
+        _ => None,
+    })
+    .ok()
+}
+
+// The closure a function object runs, to change it while the function is being made
+pub(crate) fn closure_of_mut(object: &ObjectRef) -> Option<RefMut<'_, Closure>> {
+    RefMut::filter_map(object.borrow_mut(), |object| match &mut object.kind {
+        ObjectKind::Function(function) => {
+            match (function.as_mut() as &mut dyn Any).downcast_mut() {
+                Some(Function::Closure(closure)) => Some(closure),
+                _ => None,
+            }
+        }
+        _ => None,
+    })
+    .ok()
+}
@@ -0,0 +1,571 @@
+use crate::Interpreter;
+use crate::number::{
+    bigint_to_number, number_to_bigint, number_to_string, string_to_bigint, string_to_number,
+    to_int32, to_uint32,
+};
+use jsompiler_runtime::{
+    Object, ObjectKind, ObjectRef, Property, PropertyKey, PropertyValue, Value,
+};
+use num_bigint::BigInt;
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+type Result<T> = std::result::Result<T, Value>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hint {
+    Default,
+    Number,
+    String,
+}
+
+// The result of ToNumeric, which arithmetic works on
+pub enum Numeric {
+    Number(f64),
+    BigInt(Rc<BigInt>),
+}
+
+impl Interpreter {
+    pub fn new_object(&self) -> ObjectRef {
+        ObjectRef::new(Object::new(
+            Some(self.intrinsics.object_prototype.clone()),
+            ObjectKind::Ordinary,
+        ))
+    }
+
+    pub fn new_array(&self, elements: Vec<Value>) -> ObjectRef {
+        ObjectRef::new(Object::array(
+            Some(self.intrinsics.array_prototype.clone()),
+            elements,
+        ))
+    }
+
+    // ToPrimitive: call the `Symbol.toPrimitive` method of an object with the hint, or else
+    // `valueOf` and `toString`, in the order the hint asks for, until one returns a primitive
+    pub fn to_primitive(&mut self, value: &Value, hint: Hint) -> Result<Value> {
+        let Value::Object(object) = value else {
+            return Ok(value.clone());
+        };
+        let key = PropertyKey::Symbol(self.intrinsics.symbols.to_primitive.clone());
+        if let Some(exotic) = self.get_method(value, &key)? {
+            let hint = match hint {
+                Hint::Default => "default",
+                Hint::Number => "number",
+                Hint::String => "string",
+            };
+            return match self.call(&exotic, value.clone(), &[Value::string(hint)])? {
+                Value::Object(_) => {
+                    Err(self.type_error("Cannot convert object to primitive value"))
+                }
+                result => Ok(result),
+            };
+        }
+        let methods = match hint {
+            Hint::String => ["toString", "valueOf"],
+            Hint::Default | Hint::Number => ["valueOf", "toString"],
+        };
+        for method in methods {
+            let function = self.get(object, &method.into(), value)?;
+            if function.as_function().is_some() {
+                let result = self.call(&function, value.clone(), &[])?;
+                if !matches!(result, Value::Object(_)) {
+                    return Ok(result);
+                }
+            }
+        }
+        Err(self.type_error("Cannot convert object to primitive value"))
+    }
+
+    pub fn to_numeric(&mut self, value: &Value) -> Result<Numeric> {
+        match self.to_primitive(value, Hint::Number)? {
+            Value::BigInt(value) => Ok(Numeric::BigInt(value)),
+            primitive => self.to_number(&primitive).map(Numeric::Number),
+        }
+    }
+
+    pub fn to_number(&mut self, value: &Value) -> Result<f64> {
+        Ok(match value {
+            Value::Undefined => f64::NAN,
+            Value::Null => 0.0,
+            Value::Boolean(value) => *value as u8 as f64,
+            Value::Number(value) => *value,
+            Value::BigInt(_) => {
+                return Err(self.type_error("Cannot convert a BigInt value to a number"));
+            }
+            Value::String(text) => string_to_number(text),
+            Value::Symbol(_) => {
+                return Err(self.type_error("Cannot convert a Symbol value to a number"));
+            }
+            Value::Object(_) => {
+                let primitive = self.to_primitive(value, Hint::Number)?;
+                return self.to_number(&primitive);
+            }
+        })
+    }
+
+    pub fn to_string(&mut self, value: &Value) -> Result<Rc<str>> {
+        Ok(match value {
+            Value::Undefined => "undefined".into(),
+            Value::Null => "null".into(),
+            Value::Boolean(value) => value.to_string().into(),
+            Value::Number(value) => number_to_string(*value).into(),
+            Value::BigInt(value) => value.to_string().into(),
+            Value::String(text) => text.clone(),
+            Value::Symbol(_) => {
+                return Err(self.type_error("Cannot convert a Symbol value to a string"));
+            }
+            Value::Object(_) => {
+                let primitive = self.to_primitive(value, Hint::String)?;
+                return self.to_string(&primitive);
+            }
+        })
+    }
+
+    pub fn to_property_key(&mut self, value: &Value) -> Result<PropertyKey> {
+        Ok(match self.to_primitive(value, Hint::String)? {
+            Value::Symbol(symbol) => PropertyKey::Symbol(symbol),
+            primitive => PropertyKey::from(self.to_string(&primitive)?),
+        })
+    }
+
+    // The length of an array or `arguments` object, which `for-of`, spread and array
+    // patterns go through
+    pub(crate) fn list_length(&mut self, value: &Value) -> Result<Option<u32>> {
+        let Some(object) = value.as_object() else {
+            return Ok(None);
+        };
+        if let Some(length) = object.borrow().array_length() {
+            return Ok(Some(length));
+        }
+        if !matches!(object.borrow().kind, ObjectKind::Arguments(_)) {
+            return Ok(None);
+        }
+        let length = self.get(object, &"length".into(), value)?;
+        let length = self.to_number(&length)?;
+        Ok(Some(length.clamp(0.0, u32::MAX as f64) as u32))
+    }
+
+    // The object properties of a value are looked up on. Primitives other than `null` and
+    // `undefined` use the prototype of their type.
+    fn property_holder(
+        &mut self,
+        value: &Value,
+        key: &PropertyKey,
+        reading: bool,
+    ) -> Result<ObjectRef> {
+        match value {
+            Value::Object(object) => Ok(object.clone()),
+            Value::Undefined | Value::Null => {
+                let (action, gerund) = if reading {
+                    ("read", "reading")
+                } else {
+                    ("set", "setting")
+                };
+                let message = format!(
+                    "Cannot {action} properties of {} ({gerund} '{key}')",
+                    inspect_nullish(value)
+                );
+                Err(self.type_error(&message))
+            }
+            Value::Boolean(_) => Ok(self.intrinsics.boolean_prototype.clone()),
+            Value::Number(_) => Ok(self.intrinsics.number_prototype.clone()),
+            Value::BigInt(_) => Ok(self.intrinsics.bigint_prototype.clone()),
+            Value::String(_) => Ok(self.intrinsics.string_prototype.clone()),
+            Value::Symbol(_) => Ok(self.intrinsics.symbol_prototype.clone()),
+        }
+    }
+
+    // [[Get]] along the prototype chain, calling getters with `receiver` as `this`
+    pub fn get(
+        &mut self,
+        object: &ObjectRef,
+        key: &PropertyKey,
+        receiver: &Value,
+    ) -> Result<Value> {
+        match object.find_property(key).map(|property| property.value) {
+            None => Ok(Value::Undefined),
+            Some(PropertyValue::Data { value, .. }) => Ok(value),
+            Some(PropertyValue::Accessor { get: None, .. }) => Ok(Value::Undefined),
+            Some(PropertyValue::Accessor {
+                get: Some(getter), ..
+            }) => self.call(&Value::Object(getter), receiver.clone(), &[]),
+        }
+    }
+
+    // `value[key]` for any value
+    pub fn get_property(&mut self, value: &Value, key: &PropertyKey) -> Result<Value> {
+        if let Value::String(text) = value {
+            if *key == "length" {
+                return Ok(Value::Number(text.encode_utf16().count() as f64));
+            }
+            if let Some(index) = key.as_index() {
+                return Ok(match text.encode_utf16().nth(index as usize) {
+                    Some(unit) => Value::from(String::from_utf16_lossy(&[unit])),
+                    None => Value::Undefined,
+                });
+            }
+        }
+        let object = self.property_holder(value, key, true)?;
+        self.get(&object, key, value)
+    }
+
+    // [[Set]]: call a setter found on the prototype chain, or set an own data property.
+    // Assignments that fail are ignored, except in strict mode code which throws.
+    pub fn set(
+        &mut self,
+        object: &ObjectRef,
+        key: &PropertyKey,
+        value: Value,
+        receiver: &Value,
+    ) -> Result<()> {
+        match self.try_set(object, key, value, receiver)? {
+            Some(message) if self.strict => Err(self.type_error(&message)),
+            _ => Ok(()),
+        }
+    }
+
+    // [[Set]], returning why it failed if it did
+    pub(crate) fn try_set(
+        &mut self,
+        object: &ObjectRef,
+        key: &PropertyKey,
+        value: Value,
+        receiver: &Value,
+    ) -> Result<Option<String>> {
+        let message = match object.find_property(key).map(|property| property.value) {
+            Some(PropertyValue::Accessor {
+                set: Some(setter), ..
+            }) => {
+                self.call(&Value::Object(setter), receiver.clone(), &[value])?;
+                return Ok(None);
+            }
+            Some(PropertyValue::Accessor { set: None, .. }) => {
+                format!("Cannot set property {key} which has only a getter")
+            }
+            Some(PropertyValue::Data {
+                writable: false, ..
+            }) => read_only(key, receiver),
+            _ => match receiver {
+                Value::Object(object) => {
+                    let value = self.array_length_value(object, key, value)?;
+                    if object.borrow_mut().set_own(key.clone(), value) {
+                        return Ok(None);
+                    }
+                    if object.borrow().has_own(key) {
+                        read_only(key, receiver)
+                    } else {
+                        format!("Cannot add property {key}, object is not extensible")
+                    }
+                }
+                // Properties set on primitives are lost
+                _ => format!("Cannot create property '{key}' on {}", receiver.type_of()),
+            },
+        };
+        Ok(Some(message))
+    }
+
+    // `target[key] = value` for any value
+    pub fn set_property(&mut self, target: &Value, key: &PropertyKey, value: Value) -> Result<()> {
+        let object = self.property_holder(target, key, false)?;
+        self.set(&object, key, value, target)
+    }
+
+    // `delete value[key]`. Strict mode code throws for properties that can't be deleted.
+    pub fn delete_property(&mut self, value: &Value, key: &PropertyKey) -> Result<bool> {
+        let deleted = match value {
+            Value::Object(object) => object.borrow_mut().delete(key),
+            value if value.is_nullish() => {
+                let message = format!("Cannot convert {} to object", crate::inspect(value));
+                return Err(self.type_error(&message));
+            }
+            _ => true,
+        };
+        if !deleted && self.strict {
+            let message = format!(
+                "Cannot delete property '{key}' of {}",
+                crate::inspect(value)
+            );
+            return Err(self.type_error(&message));
+        }
+        Ok(deleted)
+    }
+
+    // Define an own property the way object literals and classes do, without calling setters
+    pub fn define_property(&mut self, object: &ObjectRef, key: PropertyKey, property: Property) {
+        object.borrow_mut().define(key, property);
+    }
+
+    // `==`
+    pub fn loose_equals(&mut self, left: &Value, right: &Value) -> Result<bool> {
+        Ok(match (left, right) {
+            (Value::Undefined | Value::Null, Value::Undefined | Value::Null) => true,
+            (Value::Undefined | Value::Null, _) | (_, Value::Undefined | Value::Null) => false,
+            (Value::Number(_), Value::String(text)) => {
+                left.strict_equals(&Value::Number(string_to_number(text)))
+            }
+            (Value::String(text), Value::Number(_)) => {
+                Value::Number(string_to_number(text)).strict_equals(right)
+            }
+            (Value::BigInt(value), Value::String(text))
+            | (Value::String(text), Value::BigInt(value)) => {
+                string_to_bigint(text).is_some_and(|other| other == **value)
+            }
+            (Value::Boolean(value), _) => {
+                return self.loose_equals(&Value::Number(*value as u8 as f64), right);
+            }
+            (_, Value::Boolean(value)) => {
+                return self.loose_equals(left, &Value::Number(*value as u8 as f64));
+            }
+            (Value::Object(_), Value::Object(_)) => left.strict_equals(right),
+            (Value::Object(_), _) => {
+                let left = self.to_primitive(left, Hint::Default)?;
+                return self.loose_equals(&left, right);
+            }
+            (_, Value::Object(_)) => {
+                let right = self.to_primitive(right, Hint::Default)?;
+                return self.loose_equals(left, &right);
+            }
+            (Value::BigInt(value), Value::Number(number))
+            | (Value::Number(number), Value::BigInt(value)) => {
+                number_to_bigint(*number).is_some_and(|number| number == **value)
+            }
+            _ => left.strict_equals(right),
+        })
+    }
+
+    // IsLessThan: `None` when either side is NaN
+    fn less_than(&mut self, left: &Value, right: &Value, left_first: bool) -> Result<Option<bool>> {
+        let (left, right) = if left_first {
+            let left = self.to_primitive(left, Hint::Number)?;
+            (left, self.to_primitive(right, Hint::Number)?)
+        } else {
+            let right = self.to_primitive(right, Hint::Number)?;
+            (self.to_primitive(left, Hint::Number)?, right)
+        };
+        if let (Value::String(left), Value::String(right)) = (&left, &right) {
+            // Strings compare by UTF-16 code units
+            return Ok(Some(left.encode_utf16().lt(right.encode_utf16())));
+        }
+        let bigint_of = |value: &Value| match value {
+            Value::BigInt(value) => Some((**value).clone()),
+            Value::String(text) => string_to_bigint(text),
+            _ => None,
+        };
+        match (&left, &right) {
+            (Value::BigInt(left), Value::String(_)) => {
+                return Ok(bigint_of(&right).map(|right| **left < right));
+            }
+            (Value::String(_), Value::BigInt(right)) => {
+                return Ok(bigint_of(&left).map(|left| left < **right));
+            }
+            _ => {}
+        }
+        let left = self.to_numeric(&left)?;
+        let right = self.to_numeric(&right)?;
+        Ok(match (left, right) {
+            (Numeric::BigInt(left), Numeric::BigInt(right)) => Some(left < right),
+            (Numeric::Number(left), Numeric::Number(right)) => left
+                .partial_cmp(&right)
+                .map(|order| order == Ordering::Less),
+            (Numeric::BigInt(left), Numeric::Number(right)) => bigint_to_number(&left)
+                .partial_cmp(&right)
+                .map(|order| order == Ordering::Less),
+            (Numeric::Number(left), Numeric::BigInt(right)) => left
+                .partial_cmp(&bigint_to_number(&right))
+                .map(|order| order == Ordering::Less),
+        })
+    }
+
+    // Operators that take two evaluated operands, by their source text
+    pub fn binary_operation(
+        &mut self,
+        operator: &str,
+        left: &Value,
+        right: &Value,
+    ) -> Result<Value> {
+        let value = match operator {
+            "==" => self.loose_equals(left, right)?.into(),
+            "!=" => (!self.loose_equals(left, right)?).into(),
+            "===" => left.strict_equals(right).into(),
+            "!==" => (!left.strict_equals(right)).into(),
+            "<" => (self.less_than(left, right, true)? == Some(true)).into(),
+            ">" => (self.less_than(right, left, false)? == Some(true)).into(),
+            "<=" => (self.less_than(right, left, false)? == Some(false)).into(),
+            ">=" => (self.less_than(left, right, true)? == Some(false)).into(),
+            "in" => {
+                let Value::Object(object) = right else {
+                    let key = self.to_string(left)?;
+                    let message = format!(
+                        "Cannot use 'in' operator to search for '{key}' in {}",
+                        crate::inspect(right)
+                    );
+                    return Err(self.type_error(&message));
+                };
+                let key = self.to_property_key(left)?;
+                object.has_property(&key).into()
+            }
+            "instanceof" => self.instance_of(left, right)?.into(),
+            "+" => {
+                let left = self.to_primitive(left, Hint::Default)?;
+                let right = self.to_primitive(right, Hint::Default)?;
+                if matches!(left, Value::String(_)) || matches!(right, Value::String(_)) {
+                    let left = self.to_string(&left)?;
+                    let right = self.to_string(&right)?;
+                    Value::from(format!("{left}{right}"))
+                } else {
+                    self.arithmetic(operator, &left, &right)?
+                }
+            }
+            _ => self.arithmetic(operator, left, right)?,
+        };
+        Ok(value)
+    }
+
+    fn arithmetic(&mut self, operator: &str, left: &Value, right: &Value) -> Result<Value> {
+        let left = self.to_numeric(left)?;
+        let right = self.to_numeric(right)?;
+        match (left, right) {
+            (Numeric::Number(left), Numeric::Number(right)) => Ok(Value::Number(match operator {
+                "+" => left + right,
+                "-" => left - right,
+                "*" => left * right,
+                "/" => left / right,
+                "%" => left % right,
+                "**" => power(left, right),
+                "&" => (to_int32(left) & to_int32(right)) as f64,
+                "|" => (to_int32(left) | to_int32(right)) as f64,
+                "^" => (to_int32(left) ^ to_int32(right)) as f64,
+                "<<" => to_int32(left).wrapping_shl(to_uint32(right) & 31) as f64,
+                ">>" => (to_int32(left) >> (to_uint32(right) & 31)) as f64,
+                ">>>" => (to_uint32(left) >> (to_uint32(right) & 31)) as f64,
+                _ => unreachable!("{operator} is not an arithmetic operator"),
+            })),
+            (Numeric::BigInt(left), Numeric::BigInt(right)) => {
+                self.bigint_arithmetic(operator, &left, &right)
+            }
+            _ => {
+                Err(self.type_error("Cannot mix BigInt and other types, use explicit conversions"))
+            }
+        }
+    }
+
+    fn bigint_arithmetic(
+        &mut self,
+        operator: &str,
+        left: &BigInt,
+        right: &BigInt,
+    ) -> Result<Value> {
+        let zero = BigInt::default();
+        let value = match operator {
+            "+" => left + right,
+            "-" => left - right,
+            "*" => left * right,
+            "/" | "%" if *right == zero => return Err(self.range_error("Division by zero")),
+            "/" => left / right,
+            "%" => left % right,
+            "**" => {
+                if *right < zero {
+                    return Err(self.range_error("Exponent must be non-negative"));
+                }
+                match u32::try_from(right) {
+                    Ok(exponent) => left.pow(exponent),
+                    Err(_) => return Err(self.range_error("Maximum BigInt size exceeded")),
+                }
+            }
+            "&" => left & right,
+            "|" => left | right,
+            "^" => left ^ right,
+            "<<" | ">>" => {
+                let Ok(shift) = i64::try_from(right) else {
+                    return Err(self.range_error("Maximum BigInt size exceeded"));
+                };
+                let shift = if operator == ">>" { -shift } else { shift };
+                if shift >= 0 {
+                    left << shift.unsigned_abs()
+                } else {
+                    left >> shift.unsigned_abs()
+                }
+            }
+            ">>>" => {
+                return Err(self.type_error("BigInts have no unsigned right shift, use >> instead"));
+            }
+            _ => unreachable!("{operator} is not an arithmetic operator"),
+        };
+        Ok(Value::bigint(value))
+    }
+
+    // `value instanceof target`, which asks `Symbol.hasInstance` of the target
+    pub fn instance_of(&mut self, value: &Value, target: &Value) -> Result<bool> {
+        if !matches!(target, Value::Object(_)) {
+            return Err(self.type_error("Right-hand side of 'instanceof' is not an object"));
+        }
+        let key = PropertyKey::Symbol(self.intrinsics.symbols.has_instance.clone());
+        if let Some(handler) = self.get_method(target, &key)? {
+            let result = self.call(&handler, target.clone(), std::slice::from_ref(value))?;
+            return Ok(result.to_boolean());
+        }
+        if target.as_function().is_none() {
+            return Err(self.type_error("Right-hand side of 'instanceof' is not callable"));
+        }
+        self.ordinary_has_instance(target, value)
+    }
+
+    // OrdinaryHasInstance: whether the `prototype` of a constructor is on the prototype
+    // chain of a value
+    pub(crate) fn ordinary_has_instance(&mut self, target: &Value, value: &Value) -> Result<bool> {
+        let Some(target) = target.as_function() else {
+            return Ok(false);
+        };
+        let bound_target = match &target.borrow().kind {
+            ObjectKind::BoundFunction(bound) => Some(bound.target.clone()),
+            _ => None,
+        };
+        if let Some(bound_target) = bound_target {
+            return self.instance_of(value, &Value::Object(bound_target));
+        }
+        let Value::Object(object) = value else {
+            return Ok(false);
+        };
+        let prototype = self.get(target, &"prototype".into(), &Value::Object(target.clone()))?;
+        let Value::Object(prototype) = prototype else {
+            return Err(self.type_error("Function has non-object prototype in instanceof check"));
+        };
+        let mut current = object.prototype();
+        while let Some(object) = current {
+            if object.ptr_eq(&prototype) {
+                return Ok(true);
+            }
+            current = object.prototype();
+        }
+        Ok(false)
+    }
+}
+
+// "Cannot assign to read only property 'x' of object '#<Object>'"
+fn read_only(key: &PropertyKey, receiver: &Value) -> String {
+    let description = match receiver {
+        Value::Object(object) if object.is_callable() => "function".to_string(),
+        Value::Object(object) => match &object.borrow().kind {
+            ObjectKind::Array => "object '[object Array]'".to_string(),
+            _ => "object '#<Object>'".to_string(),
+        },
+        value => value.type_of().to_string(),
+    };
+    format!("Cannot assign to read only property '{key}' of {description}")
+}
+
+fn inspect_nullish(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        _ => "undefined",
+    }
+}
+
+// `**`, where a NaN exponent and 1 to an infinite power are NaN, unlike `powf`
+fn power(base: f64, exponent: f64) -> f64 {
+    if exponent.is_nan() || (base.abs() == 1.0 && exponent.is_infinite()) {
+        f64::NAN
+    } else {
+        base.powf(exponent)
+    }
+}
@@ -0,0 +1,562 @@
+use crate::Interpreter;
+use crate::environment::{EnvRef, Environment};
+use crate::function::BindingTarget;
+use jsompiler_common::Atom;
+use jsompiler_lexer::symbol::{KeywordToken, Token};
+use jsompiler_parser::{
+    ExportStatement, ForInOfLeft, ForInStatement, ForOfStatement, Statement, StatementId,
+};
+use jsompiler_runtime::{Property, PropertyKey, Value, collect_if_needed};
+use std::collections::HashSet;
+
+type Result<T> = std::result::Result<T, Value>;
+
+// How a statement finished, other than by throwing
+#[derive(Debug, Clone)]
+pub(crate) enum Completion {
+    Normal,
+    Return(Value),
+    Break(Option<Atom>),
+    Continue(Option<Atom>),
+}
+
+impl Interpreter {
+    pub(crate) fn statements(&mut self, statements: &[StatementId]) -> Result<Completion> {
+        for statement in statements {
+            let completion = self.statement(*statement)?;
+            if !matches!(completion, Completion::Normal) {
+                return Ok(completion);
+            }
+        }
+        Ok(Completion::Normal)
+    }
+
+    // Run `f` in `environment`, then go back to the current one
+    pub(crate) fn in_environment<T>(
+        &mut self,
+        environment: EnvRef,
+        f: impl FnOnce(&mut Self) -> Result<T>,
+    ) -> Result<T> {
+        let outer = std::mem::replace(&mut self.environment, environment);
+        let result = f(self);
+        self.environment = outer;
+        result
+    }
+
+    // A scope of its own for the lexical declarations among `statements`
+    pub(crate) fn block(&mut self, statements: &[StatementId]) -> Result<Completion> {
+        let environment = Environment::new(Some(self.environment.clone()));
+        self.in_environment(environment, |interpreter| {
+            interpreter.hoist_declarations(statements, false)?;
+            interpreter.statements(statements)
+        })
+    }
+
+    pub(crate) fn statement(&mut self, id: StatementId) -> Result<Completion> {
+        self.labeled(id, &[])
+    }
+
+    // A statement with the labels in front of it, which `continue` may name if it is a loop
+    fn labeled(&mut self, id: StatementId, labels: &[Atom]) -> Result<Completion> {
+        let ast = self.ast.clone();
+        let statement = &ast[id];
+        self.position = statement.span().start;
+        match statement {
+            Statement::LetStatement(declaration) => {
+                let name = declaration.name.bound_names();
+                let value = match declaration.value {
+                    Some(value) => match name.as_slice() {
+                        [name] => self.named_expression(value, &ast[name.value])?,
+                        _ => self.expression(value)?,
+                    },
+                    // `var a` alone doesn't change `a`
+                    None if declaration.token == Token::Keyword(KeywordToken::Var) => {
+                        return Ok(Completion::Normal);
+                    }
+                    None => Value::Undefined,
+                };
+                let target = match declaration.token {
+                    Token::Keyword(KeywordToken::Var) => BindingTarget::Assign,
+                    Token::Keyword(KeywordToken::Const) => {
+                        BindingTarget::Declare { mutable: false }
+                    }
+                    _ => BindingTarget::Declare { mutable: true },
+                };
+                self.bind_pattern(&declaration.name, value, target)?;
+            }
+            Statement::ReturnStatement(statement) => {
+                let value = match statement.value {
+                    Some(value) => self.expression(value)?,
+                    None => Value::Undefined,
+                };
+                return Ok(Completion::Return(value));
+            }
+            Statement::ExpressionStatement { expression, .. } => {
+                self.completion = self.expression(*expression)?;
+            }
+            Statement::BlockStatement(block) => return self.block(&block.statements),
+            Statement::IfStatement(statement) => {
+                if self.expression(statement.condition)?.to_boolean() {
+                    return self.statements(&statement.consequence);
+                } else if let Some(alternative) = &statement.alternative {
+                    return self.statements(alternative);
+                }
+            }
+            Statement::WhileStatement(statement) => {
+                while self.expression(statement.condition)?.to_boolean() {
+                    if let Some(completion) = self.iteration(&statement.consequence, labels)? {
+                        return Ok(completion);
+                    }
+                }
+            }
+            Statement::DoWhileStatement(statement) => loop {
+                if let Some(completion) = self.iteration(&statement.body, labels)? {
+                    return Ok(completion);
+                }
+                if !self.expression(statement.condition)?.to_boolean() {
+                    break;
+                }
+            },
+            Statement::ForLoopStatement(statement) => {
+                let environment = Environment::new(Some(self.environment.clone()));
+                return self.in_environment(environment, |interpreter| {
+                    interpreter.hoist_declarations(&statement.initializer, false)?;
+                    interpreter.statements(&statement.initializer)?;
+                    // Each iteration of `for (let ...)` gets its own copy of the bindings
+                    let per_iteration = statement.initializer.iter().any(|id| {
+                        matches!(&ast[*id], Statement::LetStatement(declaration) if declaration.token != Token::Keyword(KeywordToken::Var))
+                    });
+                    if per_iteration {
+                        let copy = interpreter.environment.borrow().copy();
+                        interpreter.environment = copy;
+                    }
+                    loop {
+                        for condition in &statement.condition {
+                            if let Statement::ExpressionStatement { expression, .. } = &ast[*condition]
+                                && !interpreter.expression(*expression)?.to_boolean()
+                            {
+                                return Ok(Completion::Normal);
+                            }
+                        }
+                        if let Some(completion) = interpreter.iteration(&statement.body, labels)? {
+                            return Ok(completion);
+                        }
+                        if per_iteration {
+                            let copy = interpreter.environment.borrow().copy();
+                            interpreter.environment = copy;
+                        }
+                        interpreter.statements(&statement.increment)?;
+                    }
+                });
+            }
+            Statement::ForInStatement(ForInStatement {
+                left, right, body, ..
+            }) => {
+                let object = self.expression(*right)?;
+                for key in self.enumerate_keys(&object) {
+                    let key = key.to_value();
+                    if let Some(completion) = self.for_in_of_iteration(left, key, body, labels)? {
+                        return Ok(completion);
+                    }
+                }
+            }
+            Statement::ForOfStatement(ForOfStatement {
+                left,
+                right,
+                body,
+                is_await,
+                ..
+            }) => {
+                if *is_await {
+                    return Err(self.unsupported("'for await' loops"));
+                }
+                let iterable = self.expression(*right)?;
+                let (Some(_), Value::Object(object)) = (self.list_length(&iterable)?, &iterable)
+                else {
+                    return self.for_of_values(left, &iterable, body, labels);
+                };
+                // Arrays are read as the loop goes, so elements pushed in the body are visited
+                let mut index = 0;
+                while self
+                    .list_length(&iterable)?
+                    .is_some_and(|length| index < length)
+                {
+                    let value = self.get(object, &PropertyKey::Index(index), &iterable)?;
+                    index += 1;
+                    if let Some(completion) = self.for_in_of_iteration(left, value, body, labels)? {
+                        return Ok(completion);
+                    }
+                }
+            }
+            Statement::SwitchStatement(statement) => {
+                let discriminant = self.expression(statement.discriminant)?;
+                let statements: Vec<StatementId> = statement
+                    .cases
+                    .iter()
+                    .flat_map(|case| case.consequent.clone())
+                    .collect();
+                let environment = Environment::new(Some(self.environment.clone()));
+                return self.in_environment(environment, |interpreter| {
+                    interpreter.hoist_declarations(&statements, false)?;
+                    let mut start = None;
+                    for (index, case) in statement.cases.iter().enumerate() {
+                        if let Some(test) = case.test
+                            && interpreter.expression(test)?.strict_equals(&discriminant)
+                        {
+                            start = Some(index);
+                            break;
+                        }
+                    }
+                    let start = start
+                        .or_else(|| statement.cases.iter().position(|case| case.test.is_none()));
+                    let Some(start) = start else {
+                        return Ok(Completion::Normal);
+                    };
+                    for case in &statement.cases[start..] {
+                        match interpreter.statements(&case.consequent)? {
+                            Completion::Normal => {}
+                            Completion::Break(None) => return Ok(Completion::Normal),
+                            completion => return Ok(completion),
+                        }
+                    }
+                    Ok(Completion::Normal)
+                });
+            }
+            Statement::ThrowStatement(statement) => {
+                let exception = self.expression(statement.argument)?;
+                self.thrown_at = statement.span.start;
+                return Err(exception);
+            }
+            Statement::TryStatement(statement) => {
+                let mut result = self.statements(&statement.block);
+                if let Err(exception) = &result
+                    && let Some(handler) = &statement.handler
+                {
+                    let exception = exception.clone();
+                    let environment = Environment::new(Some(self.environment.clone()));
+                    result = self.in_environment(environment, |interpreter| {
+                        if let Some(param) = &handler.param {
+                            interpreter.bind_pattern(
+                                param,
+                                exception,
+                                BindingTarget::Declare { mutable: true },
+                            )?;
+                        }
+                        interpreter.statements(&handler.body)
+                    });
+                }
+                if let Some(finalizer) = &statement.finalizer {
+                    // A `finally` that returns, breaks or throws replaces the result
+                    let thrown_at = self.thrown_at;
+                    match self.statements(finalizer)? {
+                        Completion::Normal => self.thrown_at = thrown_at,
+                        completion => return Ok(completion),
+                    }
+                }
+                return result;
+            }
+            Statement::WithStatement(statement) => {
+                let value = self.expression(statement.object)?;
+                let Value::Object(object) = value else {
+                    return Err(self.type_error("Cannot convert undefined or null to object"));
+                };
+                let environment = Environment::new(Some(self.environment.clone()));
+                environment.borrow_mut().object = Some(object);
+                return self.in_environment(environment, |interpreter| {
+                    interpreter.statements(&statement.body)
+                });
+            }
+            Statement::LabeledStatement(statement) => {
+                let mut labels = labels.to_vec();
+                labels.push(statement.label.value);
+                let completion = match statement.body.as_slice() {
+                    [body] => self.labeled(*body, &labels)?,
+                    body => self.statements(body)?,
+                };
+                return Ok(match completion {
+                    Completion::Break(Some(label)) if label == statement.label.value => {
+                        Completion::Normal
+                    }
+                    completion => completion,
+                });
+            }
+            Statement::BreakStatement { label, .. } => {
+                return Ok(Completion::Break(label.map(|label| label.value)));
+            }
+            Statement::ContinueStatement { label, .. } => {
+                return Ok(Completion::Continue(label.map(|label| label.value)));
+            }
+            Statement::AssignmentStatement(statement) => {
+                let target = self.target(statement.target)?;
+                let value = match &ast[statement.target] {
+                    jsompiler_parser::Expression::Identifier(name) => {
+                        self.named_expression(statement.value, &ast[name.value])?
+                    }
+                    _ => self.expression(statement.value)?,
+                };
+                self.put_target(&target, value.clone())?;
+                self.completion = value;
+            }
+            Statement::FunctionDeclaration(function) => {
+                // Sloppy mode code can also call a function declared in a block after the block
+                if let Some(name) = function.name
+                    && !self.strict
+                    && !self.environment.borrow().var_scope
+                {
+                    let value = self.lookup(name.value)?;
+                    self.assign_var(name.value, value);
+                }
+            }
+            Statement::ClassDeclaration(class) => {
+                let name = class.identifier.map(|name| name.value);
+                let constructor = self.class(
+                    class.identifier,
+                    class.heritage,
+                    &class.body,
+                    name.map_or("default", |name| &ast[name]),
+                )?;
+                if let Some(name) = name {
+                    self.environment
+                        .borrow_mut()
+                        .set(name, Value::Object(constructor));
+                }
+            }
+            Statement::ExportStatement(statement) => match statement {
+                ExportStatement::Declaration { declarations, .. } => {
+                    return self.statements(declarations);
+                }
+                ExportStatement::DefaultDeclaration { declaration, .. } => {
+                    return self.statement(*declaration);
+                }
+                ExportStatement::DefaultExpression { expression, .. } => {
+                    self.expression(*expression)?;
+                }
+            },
+            Statement::YieldStatement(_) => return Err(self.unsupported("Generators")),
+            Statement::EmptyStatement { .. }
+            | Statement::DebuggerStatement { .. }
+            | Statement::Error { .. } => {}
+        }
+        Ok(Completion::Normal)
+    }
+
+    // Run a loop body. `Some` ends the loop with that completion.
+    fn iteration(&mut self, body: &[StatementId], labels: &[Atom]) -> Result<Option<Completion>> {
+        // Loops make garbage without calling anything, so they are a place to collect it too
+        collect_if_needed();
+        Ok(match self.statements(body)? {
+            Completion::Normal | Completion::Continue(None) => None,
+            Completion::Continue(Some(label)) if labels.contains(&label) => None,
+            Completion::Break(None) => Some(Completion::Normal),
+            completion => Some(completion),
+        })
+    }
+
+    fn for_in_of_iteration(
+        &mut self,
+        left: &ForInOfLeft,
+        value: Value,
+        body: &[StatementId],
+        labels: &[Atom],
+    ) -> Result<Option<Completion>> {
+        match left {
+            ForInOfLeft::Declaration { token, name, .. }
+                if *token != Token::Keyword(KeywordToken::Var) =>
+            {
+                let mutable = *token != Token::Keyword(KeywordToken::Const);
+                let environment = Environment::new(Some(self.environment.clone()));
+                self.in_environment(environment, |interpreter| {
+                    interpreter.bind_pattern(name, value, BindingTarget::Declare { mutable })?;
+                    interpreter.iteration(body, labels)
+                })
+            }
+            ForInOfLeft::Declaration { name, .. } => {
+                self.bind_pattern(name, value, BindingTarget::Assign)?;
+                self.iteration(body, labels)
+            }
+            ForInOfLeft::Target(target) => {
+                let target = self.target(*target)?;
+                self.put_target(&target, value)?;
+                self.iteration(body, labels)
+            }
+        }
+    }
+
+    // `for-of` over anything but an array. Objects are stepped through their iterator as the
+    // loop goes, which is closed if the loop is left early; strings are read first.
+    fn for_of_values(
+        &mut self,
+        left: &ForInOfLeft,
+        iterable: &Value,
+        body: &[StatementId],
+        labels: &[Atom],
+    ) -> Result<Completion> {
+        if !matches!(iterable, Value::Object(_)) {
+            for value in self.iterate(iterable)? {
+                if let Some(completion) = self.for_in_of_iteration(left, value, body, labels)? {
+                    return Ok(completion);
+                }
+            }
+            return Ok(Completion::Normal);
+        }
+        let mut iterator = self.get_iterator(iterable)?;
+        while let Some(value) = self.iterator_step(&mut iterator)? {
+            match self.for_in_of_iteration(left, value, body, labels) {
+                Ok(None) => {}
+                Ok(Some(completion)) => return self.iterator_close(&iterator, Ok(completion)),
+                Err(exception) => return self.iterator_close(&iterator, Err(exception)),
+            }
+        }
+        Ok(Completion::Normal)
+    }
+
+    // The keys `for-in` visits: enumerable string keys of the object and its prototypes,
+    // skipping those a closer object already has
+    pub(crate) fn enumerate_keys(&self, value: &Value) -> Vec<PropertyKey> {
+        let mut object = match value {
+            Value::Object(object) => Some(object.clone()),
+            Value::String(text) => {
+                return (0..text.encode_utf16().count())
+                    .map(PropertyKey::from)
+                    .collect();
+            }
+            _ => return Vec::new(),
+        };
+        let mut seen = HashSet::new();
+        let mut keys = Vec::new();
+        while let Some(current) = object {
+            let current_object = current.borrow();
+            for key in current_object.own_keys() {
+                if key.is_symbol() {
+                    continue;
+                }
+                let enumerable = current_object
+                    .get_own(&key)
+                    .is_some_and(|property| property.enumerable);
+                if seen.insert(key.clone()) && enumerable {
+                    keys.push(key);
+                }
+            }
+            object = current_object.prototype.clone();
+        }
+        keys
+    }
+
+    // Declare the bindings of a statement list before running it. At the top of a function
+    // or script that is every `var` in it, and its functions. Any statement list also
+    // declares its `let`, `const` and `class`, which can't be used until they run.
+    pub(crate) fn hoist_declarations(
+        &mut self,
+        statements: &[StatementId],
+        var_scope: bool,
+    ) -> Result<()> {
+        let ast = self.ast.clone();
+        if var_scope {
+            for name in crate::function::var_names(&ast, statements) {
+                self.declare_var(name);
+            }
+        }
+        let mut declarations = Vec::new();
+        for id in statements {
+            match &ast[*id] {
+                Statement::ExportStatement(ExportStatement::Declaration {
+                    declarations: exported,
+                    ..
+                }) => declarations.extend(exported.iter().copied()),
+                Statement::ExportStatement(ExportStatement::DefaultDeclaration {
+                    declaration,
+                    ..
+                }) => declarations.push(*declaration),
+                _ => declarations.push(*id),
+            }
+        }
+        for id in declarations {
+            match &ast[id] {
+                Statement::LetStatement(declaration)
+                    if declaration.token != Token::Keyword(KeywordToken::Var) =>
+                {
+                    let mutable = declaration.token != Token::Keyword(KeywordToken::Const);
+                    for name in declaration.name.bound_names() {
+                        self.environment
+                            .borrow_mut()
+                            .declare(name.value, None, mutable);
+                    }
+                }
+                Statement::ClassDeclaration(class) => {
+                    if let Some(name) = class.identifier {
+                        self.environment
+                            .borrow_mut()
+                            .declare(name.value, None, true);
+                    }
+                }
+                Statement::FunctionDeclaration(function) => {
+                    let Some(name) = function.name else { continue };
+                    let closure = self.function_declaration(function);
+                    if var_scope {
+                        self.assign_var(name.value, Value::Object(closure));
+                    } else {
+                        self.environment.borrow_mut().declare(
+                            name.value,
+                            Some(Value::Object(closure)),
+                            true,
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    // The closest environment `var` declarations go to
+    fn var_environment(&self) -> EnvRef {
+        let mut environment = self.environment.clone();
+        loop {
+            let parent = {
+                let current = environment.borrow();
+                if current.var_scope {
+                    break;
+                }
+                current.parent.clone()
+            };
+            match parent {
+                Some(parent) => environment = parent,
+                None => break,
+            }
+        }
+        environment
+    }
+
+    fn declare_var(&mut self, name: Atom) {
+        let environment = self.var_environment();
+        let mut environment = environment.borrow_mut();
+        match environment.object.clone() {
+            // Top level `var`s are properties of the global object
+            Some(global) => {
+                let key = PropertyKey::from(&self.atoms[name]);
+                if !global.borrow().has_own(&key) {
+                    let property = Property::new(Value::Undefined, true, true, false);
+                    global.borrow_mut().define(key, property);
+                }
+            }
+            None => {
+                if !environment.has(name) {
+                    environment.declare(name, Some(Value::Undefined), true);
+                }
+            }
+        }
+    }
+
+    fn assign_var(&mut self, name: Atom, value: Value) {
+        let environment = self.var_environment();
+        let mut environment = environment.borrow_mut();
+        match environment.object.clone() {
+            Some(global) => {
+                global
+                    .borrow_mut()
+                    .set_own(PropertyKey::from(&self.atoms[name]), value);
+            }
+            None => environment.declare(name, Some(value), true),
+        }
+    }
+}
@@ -0,0 +1,1021 @@
+#[allow(unused_imports)]
+use super::{Interpreter, Opcode, Value, compile, disassemble, heap_stats, inspect, parse};
+#[allow(unused_imports)]
+use jsompiler_common::ErrorKind;
+
+// What the script printed with `console.log`
+#[allow(dead_code)]
+fn output(source: &str) -> String {
+    let mut interpreter = Interpreter::capturing();
+    if let Err(errors) = interpreter.run(source) {
+        panic!("{source}: {errors:?}");
+    }
+    interpreter.output().to_string()
+}
+
+// The completion value of the script, as `console.log` would print it
+#[allow(dead_code)]
+fn evaluated(source: &str) -> String {
+    let mut interpreter = Interpreter::capturing();
+    match interpreter.run(source) {
+        Ok(value) => inspect(&value),
+        Err(errors) => panic!("{source}: {errors:?}"),
+    }
+}
+
+// The message of the uncaught exception the script ends with
+#[allow(dead_code)]
+fn uncaught(source: &str) -> String {
+    let mut interpreter = Interpreter::capturing();
+    match interpreter.run(source) {
+        Ok(value) => panic!("{source}: completed with {}", inspect(&value)),
+        Err(errors) => {
+            assert_eq!(
+                errors[0].error_kind,
+                ErrorKind::RuntimeError,
+                "{source}: {errors:?}"
+            );
+            errors[0].message.clone()
+        }
+    }
+}
+
+// What the script printed when compiled to bytecode, which must be what the tree-walking
+// interpreter printed too
+#[allow(dead_code)]
+fn bytecode_output(source: &str) -> String {
+    let mut interpreter = Interpreter::capturing();
+    if let Err(errors) = interpreter.run_bytecode(source) {
+        panic!("{source}: {errors:?}");
+    }
+    assert_eq!(interpreter.output(), output(source), "{source}");
+    interpreter.output().to_string()
+}
+
+// The error running the script with the VM ends with, and its line
+#[allow(dead_code)]
+fn bytecode_error(source: &str) -> (String, usize) {
+    match Interpreter::capturing().run_bytecode(source) {
+        Ok(value) => panic!("{source}: completed with {}", inspect(&value)),
+        Err(errors) => (errors[0].message.clone(), errors[0].line_number),
+    }
+}
+
+#[test]
+fn test_primitives() {
+    assert_eq!(evaluated("1 + 2 * 3;"), "7");
+    assert_eq!(evaluated("'a' + 1;"), "'a1'");
+    assert_eq!(evaluated("0.1 + 0.2;"), "0.30000000000000004");
+    assert_eq!(evaluated("1 / 3;"), "0.3333333333333333");
+    assert_eq!(evaluated("1e21;"), "1e+21");
+    assert_eq!(evaluated("-0;"), "-0");
+    assert_eq!(evaluated("1 / 0;"), "Infinity");
+    assert_eq!(evaluated("'3' * '4';"), "12");
+    assert_eq!(evaluated("null == undefined;"), "true");
+    assert_eq!(evaluated("null === undefined;"), "false");
+    assert_eq!(evaluated("'10' < '9';"), "true");
+    assert_eq!(evaluated("10 < '9';"), "false");
+    assert_eq!(evaluated("typeof null;"), "'object'");
+    assert_eq!(evaluated("typeof nothing;"), "'undefined'");
+    assert_eq!(
+        evaluated("let two = 2; let c = 'c'; `a${two}b${c}d`;"),
+        "'a2bcd'"
+    );
+}
+
+#[test]
+fn test_bigint() {
+    assert_eq!(evaluated("2n * 21n;"), "42n");
+    assert_eq!(
+        evaluated("123456789012345678901234567890n + 1n;"),
+        "123456789012345678901234567891n"
+    );
+    assert_eq!(evaluated("7n / 2n;"), "3n");
+    assert_eq!(evaluated("typeof 1n;"), "'bigint'");
+    assert_eq!(evaluated("1n == 1;"), "true");
+    assert_eq!(evaluated("2n > 1;"), "true");
+    assert_eq!(
+        uncaught("1n + 1;"),
+        "Uncaught TypeError: Cannot mix BigInt and other types, use explicit conversions"
+    );
+}
+
+#[test]
+fn test_closures() {
+    let source = "
+        function counter() {
+            let count = 0;
+            return () => ++count;
+        }
+        const next = counter();
+        next();
+        next();
+        console.log(next());
+    ";
+    assert_eq!(output(source), "3\n");
+
+    // Each iteration of a `for (let ...)` loop has its own binding
+    let source = "
+        const functions = [];
+        for (let i = 0; i < 3; i++) functions[i] = () => i;
+        console.log(functions[0](), functions[2]());
+    ";
+    assert_eq!(output(source), "0 2\n");
+}
+
+// Each script is parsed into its own AST, and names still refer to the same bindings
+#[test]
+fn test_scripts_share_globals() {
+    let mut interpreter = Interpreter::capturing();
+    let scripts = [
+        "let a = 1; function f() { return a + b; } function g() { return typeof later; }",
+        "let b = 2; let later = 'declared';",
+        "console.log(f(), g(), a + b);",
+    ];
+    for source in scripts {
+        assert!(interpreter.run(source).is_ok(), "{source}");
+    }
+    assert_eq!(interpreter.output(), "3 string 3\n");
+
+    // The VM looks globals up by name in the same environment
+    let result = interpreter.run_bytecode("f() + b");
+    assert_eq!(result.map(|value| inspect(&value)), Ok("5".to_string()));
+}
+
+#[test]
+fn test_this_binding() {
+    let source = "
+        const point = {
+            x: 1,
+            getX() { return this.x; },
+            arrow() { return (() => this.x)(); },
+        };
+        const getX = point.getX;
+        console.log(point.getX(), point.arrow(), typeof getX());
+    ";
+    assert_eq!(output(source), "1 1 undefined\n");
+    assert_eq!(
+        uncaught("'use strict'; function f() { return this.x; } f();"),
+        "Uncaught TypeError: Cannot read properties of undefined (reading 'x')"
+    );
+}
+
+#[test]
+fn test_exceptions() {
+    let source = "
+        function check(value) {
+            if (value < 0) throw new_error('negative');
+            return value;
+        }
+        function new_error(message) { return { message: message }; }
+        let log = [];
+        try {
+            log[0] = check(1);
+            log[1] = check(-1);
+        } catch (error) {
+            log[1] = error.message;
+        } finally {
+            log[2] = 'done';
+        }
+        console.log(log);
+    ";
+    assert_eq!(output(source), "[ 1, 'negative', 'done' ]\n");
+    assert_eq!(
+        evaluated("function f() { try { return 1; } finally { return 2; } } f();"),
+        "2"
+    );
+    assert_eq!(
+        uncaught("undefinedName;"),
+        "Uncaught ReferenceError: undefinedName is not defined"
+    );
+    assert_eq!(
+        uncaught("let x = 1; x();"),
+        "Uncaught TypeError: x is not a function"
+    );
+    assert_eq!(
+        uncaught("x; let x;"),
+        "Uncaught ReferenceError: Cannot access 'x' before initialization"
+    );
+    assert_eq!(uncaught("throw 'oops';"), "Uncaught 'oops'");
+    assert_eq!(
+        uncaught("throw TypeError('bad');"),
+        "Uncaught TypeError: bad"
+    );
+}
+
+#[test]
+fn test_uncaught_line() {
+    let mut interpreter = Interpreter::capturing();
+    let errors = interpreter
+        .run("let a = 1;\nlet b = 2;\na.b.c;")
+        .unwrap_err();
+    assert_eq!(errors[0].line_number, 3);
+}
+
+#[test]
+fn test_statements() {
+    let source = "
+        let total = 0;
+        outer: for (let i = 0; i < 5; i++) {
+            for (let j = 0; j < 5; j++) {
+                if (j === 3) continue outer;
+                if (i === 3) break outer;
+                total = total + j;
+            }
+        }
+        console.log(total);
+    ";
+    assert_eq!(output(source), "9\n");
+
+    let source = "
+        function name(n) {
+            switch (n) {
+                case 1: return 'one';
+                case 2:
+                case 3: return 'few';
+                default: return 'many';
+            }
+        }
+        console.log(name(1), name(3), name(9));
+    ";
+    assert_eq!(output(source), "one few many\n");
+
+    let source = "
+        let keys = [];
+        for (const key in { a: 1, b: 2 }) keys[keys.length] = key;
+        for (const value of [3, 4]) keys[keys.length] = value;
+        let i = 0;
+        do { i++; } while (i < 10);
+        while (i > 5) i--;
+        console.log(keys, i);
+    ";
+    assert_eq!(output(source), "[ 'a', 'b', 3, 4 ] 5\n");
+}
+
+#[test]
+fn test_hoisting() {
+    assert_eq!(
+        output("console.log(f(), v); function f() { return 1; } var v = 2;"),
+        "1 undefined\n"
+    );
+    assert_eq!(output("var g = 1; console.log(globalThis.g);"), "1\n");
+}
+
+#[test]
+fn test_destructuring() {
+    let source = "
+        const { a, b: [c, d = 4], ...rest } = { a: 1, b: [3], e: 5, f: 6 };
+        function f({ x } = { x: 7 }, ...others) { return x + others.length; }
+        console.log(a, c, d, rest, f(), f({ x: 1 }, 2, 3));
+    ";
+    assert_eq!(output(source), "1 3 4 { e: 5, f: 6 } 7 3\n");
+}
+
+#[test]
+fn test_objects() {
+    let source = "
+        const base = { greet() { return 'hi ' + this.name; } };
+        const object = { __proto__: base, name: 'x', get upper() { return this.name + '!'; } };
+        console.log(object.greet(), object.upper, 'name' in object, 'greet' in object);
+        delete object.name;
+        console.log(object, [1, [2, [3, [4]]]], { ...{ a: 1 }, b: 2 });
+    ";
+    assert_eq!(
+        output(source),
+        "hi x x! true true\n{ upper: [Getter] } [ 1, [ 2, [ 3, [Array] ] ] ] { a: 1, b: 2 }\n"
+    );
+}
+
+#[test]
+fn test_classes() {
+    let source = "
+        class Shape {
+            constructor(name) { this.name = name; }
+            describe() { return 'a ' + this.name; }
+            static create() { return 'static'; }
+        }
+        console.log(Shape.create(), typeof Shape, Shape.prototype.describe.call === Function.prototype.call);
+        console.log(Shape.prototype.describe.call({ name: 'plain object' }));
+    ";
+    assert_eq!(output(source), "static function true\na plain object\n");
+    assert_eq!(
+        uncaught("class A {} A();"),
+        "Uncaught TypeError: Class constructor A cannot be invoked without 'new'"
+    );
+}
+
+// The parser doesn't read `new` yet, so instances are made with `Reflect.construct`
+#[test]
+fn test_class_instances() {
+    let source = "
+        let count = 0;
+        class Point {
+            x = 1;
+            y;
+            ['com' + 'puted'] = this.x + 1;
+            id = ++count;
+            constructor(y) { this.y = y; }
+            sum() { return this.x + this.y; }
+        }
+        const p = Reflect.construct(Point, [2]);
+        console.log(p, p.sum(), Object.getPrototypeOf(p) === Point.prototype);
+        class Point3 extends Point { z = this.x + 2; }
+        const q = Reflect.construct(Point3, [5]);
+        console.log(q.sum(), q.z, q.id, q instanceof Point, q instanceof Point3);
+        const r = Reflect.construct(Point, [0], Point3);
+        console.log(Object.getPrototypeOf(r) === Point3.prototype, r.z);
+        class Replaced { constructor() { return [1]; } }
+        class Named { f = function () {}; g = () => this; }
+        const named = Reflect.construct(Named, []);
+        console.log(Reflect.construct(Replaced, []), named.f.name, named.g() === named);
+    ";
+    assert_eq!(
+        output(source),
+        "Point { x: 1, y: 2, computed: 2, id: 1 } 3 true\n6 3 2 true true\ntrue undefined\n[ 1 ] f true\n"
+    );
+    assert_eq!(
+        uncaught("class A extends null {} Reflect.construct(A, []);"),
+        "Uncaught TypeError: Super constructor of A is not a constructor"
+    );
+    assert_eq!(
+        uncaught("class A {} class B extends A { constructor() {} } Reflect.construct(B, []);"),
+        "Uncaught SyntaxError: Constructors of derived classes are not supported by the interpreter yet"
+    );
+}
+
+#[test]
+fn test_functions() {
+    let source = "
+        function f() {}
+        console.log(f, () => {}, f.name, f.length, (function (a, b) {}).length);
+    ";
+    assert_eq!(
+        output(source),
+        "[Function: f] [Function (anonymous)] f 0 2\n"
+    );
+    let recursion = std::thread::Builder::new()
+        .stack_size(super::STACK_SIZE)
+        .spawn(|| uncaught("function f() { f(); } f();"))
+        .unwrap();
+    assert_eq!(
+        recursion.join().unwrap(),
+        "Uncaught RangeError: Maximum call stack size exceeded"
+    );
+}
+
+#[test]
+fn test_property_descriptors() {
+    let source = "
+        'use strict';
+        const object = {};
+        Object.defineProperty(object, 'fixed', { value: 1, enumerable: true });
+        Object.defineProperty(object, 'hidden', { value: 2, writable: true });
+        console.log(object, object.hidden, Object.getOwnPropertyDescriptor(object, 'fixed'));
+        try { object.fixed = 3; } catch (error) { console.log(error.message); }
+        try { delete object.fixed; } catch (error) { console.log(error.message); }
+        try {
+            Object.defineProperty(object, 'fixed', { value: 4 });
+        } catch (error) {
+            console.log(error.message);
+        }
+        const twice = function () { return object.hidden * 2; };
+        Object.defineProperty(object, 'twice', { 'get': twice });
+        console.log(object.twice, Object.getOwnPropertyNames(object));
+        Object.preventExtensions(object);
+        try { object.extra = 1; } catch (error) { console.log(error.message); }
+    ";
+    assert_eq!(
+        output(source),
+        "{ fixed: 1 } 2 { value: 1, writable: false, enumerable: true, configurable: false }\n\
+         Cannot assign to read only property 'fixed' of object '#<Object>'\n\
+         Cannot delete property 'fixed' of { fixed: 1 }\n\
+         Cannot redefine property: fixed\n\
+         4 [ 'fixed', 'hidden', 'twice' ]\n\
+         Cannot add property extra, object is not extensible\n"
+    );
+    // Sloppy mode ignores the same assignments
+    assert_eq!(
+        evaluated("const o = Object.defineProperty({}, 'x', { value: 1 }); o.x = 2; o.x;"),
+        "1"
+    );
+    assert_eq!(
+        evaluated("Object.getPrototypeOf(Object.setPrototypeOf({}, null));"),
+        "null"
+    );
+}
+
+#[test]
+fn test_property_order() {
+    let source = "
+        const object = { b: 1, 2: 1, a: 1, 1: 1, '-1': 1 };
+        object[0] = 1;
+        delete object.b;
+        object.b = 1;
+        const keys = [];
+        for (const key in object) { keys[keys.length] = key; }
+        console.log(Object.keys(object), keys);
+    ";
+    assert_eq!(
+        output(source),
+        "[ '0', '1', '2', 'a', '-1', 'b' ] [ '0', '1', '2', 'a', '-1', 'b' ]\n"
+    );
+}
+
+#[test]
+fn test_arrays() {
+    let source = "
+        const array = [1, 2, 3];
+        array[5] = 6;
+        console.log(array, array.length);
+        array.length = 2;
+        console.log(array, array[2]);
+        Object.defineProperty(array, 'length', { writable: false });
+        array[4] = 1;
+        console.log(array);
+    ";
+    assert_eq!(
+        output(source),
+        "[ 1, 2, 3, <2 empty items>, 6 ] 6\n[ 1, 2 ] undefined\n[ 1, 2 ]\n"
+    );
+    // Holes in a literal have no property, but count towards the length
+    assert_eq!(
+        bytecode_output(
+            "const a = [1, , 3]; console.log(a, 1 in a, a.length); console.log([, 1, ...[2], ,]);"
+        ),
+        "[ 1, <1 empty item>, 3 ] false 3\n[ <1 empty item>, 1, 2, <1 empty item> ]\n"
+    );
+    assert_eq!(
+        uncaught("Object.defineProperty([], 'length', { value: -1 });"),
+        "Uncaught RangeError: Invalid array length"
+    );
+    // Assigning `length` converts the value to a number, which has to be a valid length
+    assert_eq!(
+        bytecode_output(
+            "const a = [1, 2, 3]; a.length = '1'; console.log(a, a.length);
+            a.length = { valueOf() { return 2; } }; console.log(a, a.length);"
+        ),
+        "[ 1 ] 1\n[ 1, <1 empty item> ] 2\n"
+    );
+    for length in ["-1", "1.5", "'two'", "4294967296"] {
+        assert_eq!(
+            uncaught(&format!("const a = [1]; a.length = {length};")),
+            "Uncaught RangeError: Invalid array length",
+            "{length}"
+        );
+    }
+}
+
+#[test]
+fn test_arguments() {
+    let source = "
+        function sloppy(a, b) {
+            const list = arguments;
+            list[0] = 10;
+            b = 20;
+            return [a, list[1], list.length, list.callee === sloppy];
+        }
+        function strict(a) {
+            'use strict';
+            const list = arguments;
+            list[0] = 10;
+            return [a, list.length];
+        }
+        function spread() { return [...arguments]; }
+        function list() { return arguments; }
+        console.log(sloppy(1, 2), strict(1, 2), spread(1, 2), list(1, 'a'));
+    ";
+    assert_eq!(
+        output(source),
+        "[ 10, 20, 2, true ] [ 1, 2 ] [ 1, 2 ] [Arguments] [ 1, 'a' ]\n"
+    );
+    assert_eq!(
+        uncaught("function f() { 'use strict'; return arguments.callee; } f();"),
+        "Uncaught TypeError: 'caller', 'callee', and 'arguments' properties may not be accessed on strict mode functions or the arguments objects for calls to them"
+    );
+}
+
+#[test]
+fn test_garbage_collection() {
+    let source = "
+        const kept = { name: 'kept' };
+        kept.self = kept;
+        function cycles() {
+            const object = {};
+            object.self = object;
+            function closure() { return closure; }
+            return 0;
+        }
+        for (let i = 0; i < 100; i = i + 1) { cycles(); }
+        console.log(gc() > 100, kept.self.name, gc());
+    ";
+    for bytecode in [false, true] {
+        let before = heap_stats();
+        let mut interpreter = Interpreter::capturing();
+        interpreter.expose_gc();
+        let result = if bytecode {
+            interpreter.run_bytecode(source)
+        } else {
+            interpreter.run(source)
+        };
+        assert!(result.is_ok(), "{result:?}");
+        assert_eq!(interpreter.output(), "true kept 0\n");
+
+        // Built-ins and what scripts left on the global object are cycles too
+        drop(interpreter);
+        assert!(heap_stats().live > before.live);
+        jsompiler_runtime::collect();
+        assert_eq!(heap_stats().live, before.live);
+    }
+}
+
+#[test]
+fn test_opcodes() {
+    for (byte, opcode) in Opcode::ALL.iter().enumerate() {
+        assert_eq!(*opcode as usize, byte);
+        assert_eq!(Opcode::from_byte(byte as u8), *opcode);
+    }
+}
+
+#[test]
+fn test_bytecode_closures() {
+    let source = "
+        function makeCounter() {
+            let count = 0;
+            return function () {
+                count = count + 1;
+                return count;
+            };
+        }
+        const counter = makeCounter();
+        counter();
+        console.log(counter(), makeCounter()());
+        function outer(a) {
+            function middle(b) {
+                return (c) => a + b + c;
+            }
+            return middle(2);
+        }
+        console.log(outer(1)(3));
+        const fns = [];
+        for (let i = 0; i < 3; i++) {
+            fns[i] = () => i;
+        }
+        for (const key in { a: 1, b: 2 }) {
+            fns[fns.length] = () => key;
+        }
+        console.log(fns[0](), fns[2](), fns[3](), fns[4]());
+        const fact = function f(n) {
+            if (n < 2) { return 1; }
+            return n * f(n - 1);
+        };
+        function defaults(a, b = a * 2, ...rest) { return [a, b, rest]; }
+        console.log(fact(5), fact.name, defaults(1), defaults(1, 5, 6, 7), defaults.length);
+        const point = { x: 1, set y(v) { this.x = v; }, get y() { return this.x * 10; } };
+        point.y = 4;
+        point.x++;
+        console.log(point.y, typeof point, typeof nothing);
+    ";
+    assert_eq!(
+        bytecode_output(source),
+        "2 1\n6\n0 2 a b\n120 f [ 1, 2, [] ] [ 1, 5, [ 6, 7 ] ] 1\n50 object undefined\n"
+    );
+}
+
+#[test]
+fn test_bytecode_control_flow() {
+    let source = "
+        function loop() {
+            for (let i = 0; i < 4; i++) {
+                try {
+                    if (i === 1) { continue; }
+                    if (i === 3) { break; }
+                    console.log('body', i);
+                } finally {
+                    console.log('finally', i);
+                }
+            }
+            try { return 'returned'; } finally { console.log('cleanup'); }
+        }
+        console.log(loop());
+        let total = 0;
+        outer: for (const x of [1, 2, 3]) {
+            let j = 0;
+            while (true) {
+                j++;
+                if (x === 2) { continue outer; }
+                if (j > 2) { break; }
+                total = total + x;
+            }
+        }
+        block: {
+            if (total) { break block; }
+            console.log('never');
+        }
+        do { total--; } while (total > 5);
+        switch (total) {
+            case 4: console.log('four');
+            case 5: console.log('five');
+            case 6: console.log('six'); break;
+            default: console.log('default');
+        }
+        try { null.x; } catch (error) { console.log(error.message); }
+    ";
+    assert_eq!(
+        bytecode_output(source),
+        "body 0\nfinally 0\nfinally 1\nbody 2\nfinally 2\nfinally 3\ncleanup\nreturned\n\
+         five\nsix\nCannot read properties of null (reading 'x')\n"
+    );
+}
+
+#[test]
+fn test_bytecode_errors() {
+    let source =
+        "function a() {\n  return b();\n}\nfunction b() {\n  let x = null;\n  return x.y;\n}\na();";
+    assert_eq!(
+        bytecode_error(source),
+        (
+            "Uncaught TypeError: Cannot read properties of null (reading 'y')".to_string(),
+            6
+        )
+    );
+    assert_eq!(
+        bytecode_error("function f() {\n  throw 1;\n}\ntry { f(); } finally { 2; }"),
+        ("Uncaught 1".to_string(), 2)
+    );
+    assert_eq!(
+        bytecode_error("let f = () => later;\nf();\nlet later = 1;"),
+        (
+            "Uncaught ReferenceError: Cannot access 'later' before initialization".to_string(),
+            1
+        )
+    );
+    assert_eq!(
+        bytecode_error("let x = 1;\nclass A {}"),
+        (
+            "Classes are not supported by the bytecode compiler yet".to_string(),
+            2
+        )
+    );
+    // Calls between compiled functions don't use the native stack
+    assert_eq!(
+        bytecode_error("function f() { f(); } f();").0,
+        "Uncaught RangeError: Maximum call stack size exceeded"
+    );
+}
+
+#[test]
+fn test_disassembly() {
+    let source = "let a = 1;\nconst add = (b) => a + b;\nadd(2);";
+    let (ast, semantic) = parse(source).unwrap();
+    let code = compile(&ast, &semantic, source).unwrap();
+    assert_eq!(
+        disassemble(&code),
+        "\
+function <script> (length 0, sloppy)
+  locals: 0 a (cell), 1 add
+0000  Constant              0     ; 1
+0003  InitCell              0     ; a
+0006  Closure               0     ; add
+0009  InitLocal             1     ; add
+0012  GetLocal              1     ; add
+0015  Undefined
+0016  Constant              1     ; 2
+0019  Call                  1 0   ; add
+0024  Completion
+0025  Undefined
+0026  Return
+
+function <script>/add (length 1, sloppy)
+  locals: 0 b
+  captures: 0 a <- local 0
+0000  Argument              0
+0003  InitLocal             0     ; b
+0006  GetCapture            0     ; a
+0009  GetLocal              0     ; b
+0012  Add
+0013  Return
+"
+    );
+
+    // A construct the compiler doesn't support fails the whole compilation with an error
+    // naming it, so `disasm` prints no partial bytecode
+    for (source, message, line) in [
+        ("let a = 1;\nclass A {}", "Classes", 2),
+        (
+            "function f(o) {\n  const { x } = o;\n}",
+            "Destructuring patterns",
+            2,
+        ),
+        ("f(...items);", "Spread arguments", 1),
+        (
+            "function f() {\n  g();\n  return arguments;\n}",
+            "'arguments' objects",
+            3,
+        ),
+        ("with (o) {}", "'with' statements", 1),
+    ] {
+        let (ast, semantic) = parse(source).unwrap();
+        let error = compile(&ast, &semantic, source).unwrap_err();
+        assert_eq!(
+            (error.message, error.line_number),
+            (
+                format!("{message} are not supported by the bytecode compiler yet"),
+                line
+            ),
+            "{source}"
+        );
+    }
+}
+
+#[test]
+fn test_object_builtins() {
+    let source = "
+        const object = Object.create({ inherited: 1 }, { own: { value: 2, enumerable: true } });
+        console.log(Object.keys(object), Object.entries({ a: 1, b: 2 }), object.inherited);
+        console.log(Object.assign({}, { x: 1 }, null, { y: 2 }), Object.fromEntries([['k', 1]]));
+        const frozen = Object.freeze({ a: 1 });
+        console.log(Object.isFrozen(frozen), Object.isSealed(frozen), Object.is(NaN, NaN));
+        console.log(Object.keys('ab'), Object(1), Object('ab'), Object(true), Object(1n));
+        console.log(Object.prototype.toString.call([]), Object.prototype.toString.call(null));
+    ";
+    assert_eq!(
+        output(source),
+        "[ 'own' ] [ [ 'a', 1 ], [ 'b', 2 ] ] 1\n\
+         { x: 1, y: 2 } { k: 1 }\n\
+         true true true\n\
+         [ '0', '1' ] [Number: 1] [String: 'ab'] [Boolean: true] [BigInt: 1n]\n\
+         [object Array] [object Null]\n"
+    );
+    assert_eq!(
+        uncaught("Object.defineProperty(1, 'x', {});"),
+        "Uncaught TypeError: Object.defineProperty called on non-object"
+    );
+    assert_eq!(
+        evaluated("Reflect.ownKeys({ b: 1, 0: 1, [Symbol.iterator]: 1 });"),
+        "[ '0', 'b', Symbol(Symbol.iterator) ]"
+    );
+}
+
+#[test]
+fn test_function_methods() {
+    let source = "
+        function add(a, b) { return this.base + a + b; }
+        const bound = add.bind({ base: 10 }, 1);
+        console.log(add.call({ base: 1 }, 2, 3), add.apply({ base: 1 }, [2, 3]), bound(2));
+        console.log(bound.name, bound.length, typeof Function.prototype, add.toString === Function.prototype.toString);
+    ";
+    assert_eq!(output(source), "6 6 13\nbound add 1 function true\n");
+    assert_eq!(
+        uncaught("Function.prototype.call.call(1);"),
+        "Uncaught TypeError: Function.prototype.call was called on a non-function"
+    );
+}
+
+#[test]
+fn test_array_builtins() {
+    let source = "
+        const numbers = [5, 1, 10, 2];
+        console.log(numbers.map(x => x * 2), numbers.filter(x => x > 2), numbers.reduce((a, b) => a + b));
+        console.log(numbers.sort().join(), numbers.sort((a, b) => a - b), numbers.indexOf(10), numbers.includes(NaN));
+        const nested = [1, [2, [3, [4]]]];
+        console.log(nested.flat(), nested.flat(Infinity), numbers.join('-'), numbers.slice(-2));
+        const spliced = [1, 2, 3, 4];
+        console.log(spliced.splice(1, 2, 'a'), spliced, spliced.reverse(), spliced.at(-1));
+        console.log(Array['from']('abc'), Array['of'](7), Array.isArray(numbers), Array['from']({ length: 2 }));
+        console.log(numbers.findLast(x => x < 10), numbers.some(x => x > 9), numbers.every(x => x > 0));
+        const holes = [1];
+        holes[2] = 3;
+        console.log(holes.map(x => x), Object.keys(holes), holes.toString());
+    ";
+    assert_eq!(
+        output(source),
+        "[ 10, 2, 20, 4 ] [ 5, 10 ] 18\n\
+         1,10,2,5 [ 1, 2, 5, 10 ] 3 false\n\
+         [ 1, 2, [ 3, [ 4 ] ] ] [ 1, 2, 3, 4 ] 1-2-5-10 [ 5, 10 ]\n\
+         [ 2, 3 ] [ 4, 'a', 1 ] [ 4, 'a', 1 ] 1\n\
+         [ 'a', 'b', 'c' ] [ 7 ] true [ undefined, undefined ]\n\
+         5 true true\n\
+         [ 1, <1 empty item>, 3 ] [ '0', '2' ] 1,,3\n"
+    );
+    assert_eq!(
+        uncaught("const a = []; a.reduce((x, y) => x);"),
+        "Uncaught TypeError: Reduce of empty array with no initial value"
+    );
+}
+
+#[test]
+fn test_string_builtins() {
+    let source = "
+        const text = 'Hello, world';
+        console.log(text.toUpperCase(), text.slice(-5), text.substring(7, 5), text.indexOf('o', 5));
+        console.log(text.split(', '), text.replace('o', '0'), text.replaceAll('o', '0'), text.at(-1));
+        console.log('  x '.trim(), 'ab'.padStart(4, '-'), 'ab'.repeat(2), 'a'.concat(1, null));
+        console.log('😀'.length, '😀'.codePointAt(0), String.fromCodePoint(128512) === '😀', 'abc'.localeCompare('abd'));
+        console.log(String.fromCharCode(72, 105), String(Symbol('s')), 'a-b-c'.split('-', 2), 'x$y'.replace('$', '$$'));
+    ";
+    assert_eq!(
+        output(source),
+        "HELLO, WORLD world ,  8\n\
+         [ 'Hello', 'world' ] Hell0, world Hell0, w0rld d\n\
+         x --ab abab a1null\n\
+         2 128512 true -1\n\
+         Hi Symbol(s) [ 'a', 'b' ] x$y\n"
+    );
+    assert_eq!(
+        uncaught("'a'.repeat(-1);"),
+        "Uncaught RangeError: Invalid count value: -1"
+    );
+}
+
+#[test]
+fn test_number_builtins() {
+    let source = "
+        console.log((1.005).toFixed(2), (1e21).toFixed(2), (123.456).toPrecision(4), (0.00001).toPrecision(1));
+        console.log((255).toString(16), (-255).toString(2), (0.5).toString(2), (25).toExponential(), (12345).toExponential(2));
+        console.log(Number('  0x1F '), Number(''), Number('1_0'), parseInt('42px'), parseInt('ff', 16), parseFloat('.5e1x'));
+        console.log(Number.isInteger(5), Number.isSafeInteger(9007199254740992), Number.MAX_SAFE_INTEGER, Number.EPSILON > 0);
+        console.log(Math.max(), Math.min(0, -0), Math.round(-2.5), Math.round(2.5), Math.hypot(3, 4), Math.sign(-3));
+        console.log(Math.trunc(-4.7), Math.cbrt(27), Math.clz32(1), Math.imul(2147483648, 2), Math.fround(5.05) === 5.05);
+        console.log(BigInt(10) === 10n, BigInt.asIntN(8, 255n), (255n).toString(16), Boolean(''), typeof Math.random());
+    ";
+    assert_eq!(
+        output(source),
+        "1.00 1e+21 123.5 0.00001\n\
+         ff -11111111 0.1 2.5e+1 1.23e+4\n\
+         31 0 NaN 42 255 5\n\
+         true false 9007199254740991 true\n\
+         -Infinity -0 -2 3 5 -1\n\
+         -4 3 31 0 false\n\
+         true -1n ff false number\n"
+    );
+    assert_eq!(
+        uncaught("(1).toExponential(101);"),
+        "Uncaught RangeError: toExponential() argument must be between 0 and 100"
+    );
+}
+
+#[test]
+fn test_json() {
+    let source = "
+        const value = { a: [1, 'x', null, true], b: { c: undefined, d: () => 1 }, e: 'q\"\\n' };
+        console.log(JSON.stringify(value));
+        console.log(JSON.stringify([1, { a: 2 }], null, 2));
+        console.log(JSON.stringify({ a: 1, b: 2, c: 3 }, ['c', 'a']), JSON.stringify({ toJSON: () => 7 }));
+        console.log(JSON.stringify(undefined), JSON.stringify(Object('s')), JSON.stringify(NaN));
+        console.log(JSON.parse(' {\"a\": [1, -2.5e1, {\"b\": null}], \"c\": \"\\\\u0041\"} '));
+        function reviver(key, value) {
+            if (typeof value === 'number') return value * 10;
+            return value;
+        }
+        console.log(JSON.parse('[1, [2]]', reviver));
+        try { JSON.parse('{\"a\": 1,}'); } catch (error) { console.log(error.name, error.message); }
+        try { JSON.parse('[1'); } catch (error) { console.log(error.message); }
+        const cycle = {};
+        cycle.self = cycle;
+        try { JSON.stringify(cycle); } catch (error) { console.log(error.message); }
+        try { JSON.stringify(1n); } catch (error) { console.log(error.message); }
+    ";
+    assert_eq!(
+        output(source),
+        "{\"a\":[1,\"x\",null,true],\"b\":{},\"e\":\"q\\\"\\n\"}\n\
+         [\n  1,\n  {\n    \"a\": 2\n  }\n]\n\
+         {\"c\":3,\"a\":1} 7\n\
+         undefined \"s\" null\n\
+         { a: [ 1, -25, { b: null } ], c: 'A' }\n\
+         [ 10, [ 20 ] ]\n\
+         SyntaxError Unexpected token } in JSON at position 8\n\
+         Unexpected end of JSON input\n\
+         Converting circular structure to JSON\n\
+         Do not know how to serialize a BigInt\n"
+    );
+}
+
+#[test]
+fn test_error_builtins() {
+    let source = "
+        const error = Reflect.construct(RangeError, ['bad', { cause: 'why' }]);
+        console.log(error.message, error.cause, error instanceof RangeError, error instanceof Error);
+        console.log(String(error), Object.prototype.toString.call(error), RangeError.prototype.name);
+        console.log(Error('plain').message, TypeError.prototype instanceof Error, EvalError.name);
+        console.log(Error.prototype.toString.call({ name: 'Custom', message: '' }));
+        const aggregate = AggregateError([1, 2], 'many');
+        console.log(aggregate.errors, aggregate.message);
+    ";
+    assert_eq!(
+        output(source),
+        "bad why true true\n\
+         RangeError: bad [object Error] RangeError\n\
+         plain true EvalError\n\
+         Custom\n\
+         [ 1, 2 ] many\n"
+    );
+}
+
+#[test]
+fn test_keyed_collections() {
+    let source = "
+        const map = Reflect.construct(Map, [[['a', 1], ['b', 2]]]);
+        map['set']('c', 3)['set'](NaN, 4);
+        map['delete']('a');
+        console.log(map, map.size, map['get']('b'), map.has(NaN), [...map.keys()]);
+        const seen = [];
+        function visit(value, key) {
+            seen.push(key);
+            if (key === 'b') map['delete']('c');
+        }
+        map.forEach(visit);
+        console.log(seen);
+        const letters = Reflect.construct(Set, ['hello']);
+        letters.add(-0);
+        const entry = letters.entries().next().value;
+        console.log(letters, letters.has(0), entry, Set.prototype.keys === Set.prototype.values);
+        const weak = Reflect.construct(WeakMap, []);
+        const key = {};
+        weak['set'](key, 'value');
+        console.log(weak, weak['get'](key), weak.has({}), weak['delete'](key), weak.has(key));
+    ";
+    assert_eq!(
+        output(source),
+        "Map(3) { 'b' => 2, 'c' => 3, NaN => 4 } 3 2 true [ 'b', 'c', NaN ]\n\
+         [ 'b', NaN ]\n\
+         Set(5) { 'h', 'e', 'l', 'o', 0 } true [ 'h', 'h' ] true\n\
+         WeakMap { <items unknown> } value false true false\n"
+    );
+    assert_eq!(
+        uncaught("Map();"),
+        "Uncaught TypeError: Constructor Map requires 'new'"
+    );
+    assert_eq!(
+        uncaught("Reflect.construct(WeakMap, [])['set'](1, 1);"),
+        "Uncaught TypeError: Invalid value used as weak map key"
+    );
+}
+
+#[test]
+fn test_symbols() {
+    let source = "
+        const symbol = Symbol('desc');
+        console.log(symbol, symbol.description, symbol.toString(), Object(symbol));
+        console.log(Symbol['for']('k') === Symbol['for']('k'), Symbol.keyFor(Symbol['for']('k')), Symbol.keyFor(symbol));
+        const object = { [symbol]: 1, [Symbol.toStringTag]: 'Tagged' };
+        console.log(object[symbol], String(object), Object.getOwnPropertySymbols(object));
+    ";
+    assert_eq!(
+        output(source),
+        "Symbol(desc) desc Symbol(desc) [Symbol: Symbol(desc)]\n\
+         true k undefined\n\
+         1 [object Tagged] [ Symbol(desc), Symbol(Symbol.toStringTag) ]\n"
+    );
+    assert_eq!(
+        uncaught("Symbol() + '';"),
+        "Uncaught TypeError: Cannot convert a Symbol value to a string"
+    );
+}
+
+#[test]
+fn test_iteration() {
+    // `for-of` steps through iterators as it goes, and closes them when it leaves early
+    let source = "
+        function counter(limit) {
+            let count = 0;
+            return {
+                [Symbol.iterator]() { return this; },
+                next() { count = count + 1; console.log('next', count); return { value: count, done: count > limit }; },
+                'return'() { console.log('closed'); return {}; },
+            };
+        }
+        for (const value of counter(5)) { if (value === 2) break; }
+        try { for (const value of counter(5)) { throw 'out'; } } catch (error) { console.log(error); }
+        for (const value of counter(1)) {}
+        const pair = [1, 2];
+        console.log(pair[Symbol.iterator](), [...'ab'], Array['from'](counter(0)));
+    ";
+    assert_eq!(
+        output(source),
+        "next 1\nnext 2\nclosed\nnext 1\nclosed\nout\nnext 1\nnext 2\nnext 1\n\
+         Object [Array Iterator] {} [ 'a', 'b' ] []\n"
+    );
+    let source = "
+        const map = Reflect.construct(Map, []);
+        map['set']('a', 1);
+        map['set']('b', 2);
+        for (const entry of map) { console.log(entry); map['delete']('b'); }
+        const list = [1];
+        for (const value of list) { if (value < 3) list.push(value + 1); console.log(value); }
+        for (const c of 'hé') console.log(c);
+    ";
+    assert_eq!(bytecode_output(source), "[ 'a', 1 ]\n1\n2\n3\nh\né\n");
+}
+
+#[test]
+fn test_console_methods() {
+    let source = "
+        console.log('text', 1, 'two words', [1]);
+        console.error('error'); console.warn('warn'); console.info('info'); console.debug('debug');
+        console.log();
+    ";
+    assert_eq!(
+        output(source),
+        "text 1 two words [ 1 ]\nerror\nwarn\ninfo\ndebug\n\n"
+    );
+}
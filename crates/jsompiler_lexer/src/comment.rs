@@ -67,7 +67,7 @@ impl Lexer {
                 }
             }
         }
-        return self.lex_operator_punctuation('/');
+        self.lex_operator_punctuation('/')
     }
 
     pub fn lex_hashbang(&mut self) -> Result<Option<Lexeme>, Error> {
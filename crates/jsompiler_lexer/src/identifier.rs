@@ -10,7 +10,7 @@ impl Lexer {
         let is_private = self.get_current_char() == '#';
         if is_private {
             // Check if # is the first token, if yes it can't be private field
-            if self.tokens.len() == 0 {
+            if self.tokens.is_empty() {
                 return self.lex_hashbang();
             }
 
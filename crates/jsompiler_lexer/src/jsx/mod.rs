@@ -2,88 +2,193 @@ pub mod symbol;
 pub mod test;
 
 use jsompiler_common::{Error, ErrorKind};
-use symbol::{JSX_SYMBOLS, JSXToken};
+use symbol::{JSX_ENTITIES, JSX_SYMBOLS, JSXToken};
 
 use crate::{
     Lexer,
-    symbol::{Lexeme, Token, lexeme},
+    symbol::{Lexeme, Span, Token, lexeme},
 };
 
+// Where the lexer is inside a JSX tree. The innermost context is the last one on
+// `Lexer::jsx_modes`; with an empty stack the lexer produces plain JavaScript tokens.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JSXMode {
+    OpeningTag,        // <div a="b" {...c}
+    ClosingTag,        // </div
+    Children,          // text, elements and `{expr}` between tags
+    Expression(usize), // JavaScript inside `{...}`, with the depth of nested braces
+}
+
+// The state of the lexer at a `<` it lexed as an operator, to lex it again as JSX from there
+#[derive(Debug, Clone)]
+pub(crate) struct JSXCheckpoint {
+    position: usize,
+    line_number: usize,
+    errors: usize,
+    modes: Vec<JSXMode>,
+}
+
 impl Lexer {
-    pub fn lex_jsx(&mut self) -> Result<Option<Lexeme>, Error> {
-        let mut is_jsx = false;
-        // if let Some(last_token) = self.tokens.last() {
-        match self.tokens.last() {
-            Some(last_token) => match last_token.token {
-                Token::Operator(_) => is_jsx = true,
-                Token::Delimiter(_) => is_jsx = true,
-                _ => {}
-            },
-            None => is_jsx = true,
+    // Lex a `<` in JavaScript context. Whether it starts a JSX element depends on whether an
+    // expression may start there, which only the parser knows, so it is lexed as an
+    // operator and the parser calls `relex_jsx` when it expects an expression instead.
+    pub fn lex_less_than(&mut self) -> Result<Option<Lexeme>, Error> {
+        self.jsx_checkpoints.push(JSXCheckpoint {
+            position: self.current,
+            line_number: self.line_number,
+            errors: self.errors.len(),
+            modes: self.jsx_modes.clone(),
+        });
+        let c = self.advance();
+        self.lex_operator_punctuation(c)
+    }
+
+    // Lex the source again from the `<` at `position` as the start of a JSX element. Tokens
+    // and errors from there on are dropped, and the rest of the source is lexed again, so
+    // `tokens` then ends with the element and whatever follows it. Returns false if there
+    // is no `<` lexed as an operator at `position`.
+    pub fn relex_jsx(&mut self, position: usize) -> bool {
+        let Ok(index) = self
+            .jsx_checkpoints
+            .binary_search_by_key(&position, |checkpoint| checkpoint.position)
+        else {
+            return false;
+        };
+        let checkpoint = self.jsx_checkpoints[index].clone();
+        self.jsx_checkpoints.truncate(index);
+        let kept = self
+            .tokens
+            .partition_point(|lexeme| lexeme.span.start < position);
+        self.tokens.truncate(kept);
+        self.errors.truncate(checkpoint.errors);
+        self.jsx_modes = checkpoint.modes;
+        self.line_number = checkpoint.line_number;
+        self.start = position;
+        self.current = position;
+
+        match self.lex_jsx_tag_start() {
+            Ok(Some(token)) => self.push_token_with_span(token, position),
+            Ok(None) => {}
+            Err(error) => self.errors.push(error),
         }
-        if !is_jsx {
-            return self.lex_operator_punctuation('<');
+        self.scan_all_tokens();
+        true
+    }
+
+    // Lex the next token inside a JSX tag or between JSX tags
+    pub fn lex_jsx_token(&mut self, mode: JSXMode) -> Result<Option<Lexeme>, Error> {
+        if matches!(mode, JSXMode::OpeningTag | JSXMode::ClosingTag) {
+            while self.get_current_char().is_whitespace() {
+                self.advance();
+            }
         }
+        self.start = self.current;
 
-        let mut element_count = -1;
+        if self.is_at_end() {
+            // Leave JSX so the rest of the input still ends with EOF
+            self.jsx_modes.clear();
+            self.current = self.source.len() + 1;
+            self.errors.push(Error {
+                error_kind: ErrorKind::LexerError,
+                message: "Unterminated JSX contents".to_string(),
+                line_number: self.line_number,
+                pos: self.start,
+            });
+            return Ok(Some(lexeme("EOF".to_string(), Token::EOF)));
+        }
 
-        loop {
-            let op: Result<Option<Lexeme>, Error> = match self.get_current_char() {
+        match mode {
+            JSXMode::Children => match self.get_current_char() {
+                '<' => self.lex_jsx_tag_start(),
+                '{' => self.lex_jsx_left_brace(),
+                _ => self.lex_jsx_text(),
+            },
+            _ => match self.get_current_char() {
                 'A'..='Z' | 'a'..='z' | '_' | '$' => self.lex_jsx_identifier(),
-                _ => {
-                    let res = self.lex_jsx_operator_punctuation();
-
-                    if let Ok(Some(token)) = &res {
-                        let token = &token.token;
-                        if token == &Token::JSX(JSXToken::LessThanSlash)
-                            || token == &Token::JSX(JSXToken::SlashGreaterThan)
-                        {
-                            element_count -= 1;
-                        }
-
-                        if token == &Token::JSX(JSXToken::LessThan) {
-                            if element_count < 0 {
-                                element_count = 1;
-                            } else {
-                                element_count += 1;
-                            }
-                        }
-                    }
-
-                    res
-                }
-            };
-
-            let mut token = None;
-            if let Ok(Some(op)) = op {
-                token = Some(op.clone().token);
-                self.tokens.push(op);
+                '"' | '\'' => self.lex_jsx_string(),
+                '{' if mode == JSXMode::OpeningTag => self.lex_jsx_left_brace(),
+                // An element as an attribute value, as in `a=<b/>`
+                '<' if mode == JSXMode::OpeningTag => self.lex_jsx_tag_start(),
+                _ => self.lex_jsx_tag_punctuation(mode),
+            },
+        }
+    }
+
+    // Inside `{...}`, braces are counted so only the matching `}` returns to JSX
+    pub fn lex_jsx_expression_brace(&mut self, depth: usize) -> Option<Lexeme> {
+        match self.get_current_char() {
+            '{' => {
+                self.jsx_modes.pop();
+                self.jsx_modes.push(JSXMode::Expression(depth + 1));
+                None
+            }
+            '}' if depth > 0 => {
+                self.jsx_modes.pop();
+                self.jsx_modes.push(JSXMode::Expression(depth - 1));
+                None
             }
+            '}' => {
+                self.jsx_modes.pop();
+                self.advance();
+                Some(JSX_SYMBOLS["}"].clone())
+            }
+            _ => None,
+        }
+    }
 
-            if (element_count == 0
-                && (token == Some(Token::JSX(JSXToken::GreaterThan))
-                    || token == Some(Token::JSX(JSXToken::SlashGreaterThan))))
-                || token == Some(Token::EOF)
-            {
-                break;
+    // `<`, `<>`, `</` or `</>`
+    fn lex_jsx_tag_start(&mut self) -> Result<Option<Lexeme>, Error> {
+        let token = self.lex_jsx_punctuation()?;
+        match token.token {
+            Token::JSX(JSXToken::LessThan) => self.jsx_modes.push(JSXMode::OpeningTag),
+            Token::JSX(JSXToken::FragmentStart) => self.jsx_modes.push(JSXMode::Children),
+            Token::JSX(JSXToken::LessThanSlash) => {
+                self.jsx_modes.pop(); // Leave the children
+                self.jsx_modes.push(JSXMode::ClosingTag);
+            }
+            Token::JSX(JSXToken::FragmentEnd) => {
+                self.jsx_modes.pop();
             }
+            _ => {}
+        }
+        Ok(Some(token))
+    }
 
-            self.advance();
+    fn lex_jsx_tag_punctuation(&mut self, mode: JSXMode) -> Result<Option<Lexeme>, Error> {
+        let token = self.lex_jsx_punctuation()?;
+        match token.token {
+            // The opening tag is done; its children follow
+            Token::JSX(JSXToken::GreaterThan) if mode == JSXMode::OpeningTag => {
+                self.jsx_modes.pop();
+                self.jsx_modes.push(JSXMode::Children);
+            }
+            Token::JSX(JSXToken::GreaterThan) | Token::JSX(JSXToken::SlashGreaterThan) => {
+                self.jsx_modes.pop();
+            }
+            _ => {}
         }
+        Ok(Some(token))
+    }
 
+    // `{` starts JavaScript; a leading `...` is kept as JSX so spreads are easy to spot
+    fn lex_jsx_left_brace(&mut self) -> Result<Option<Lexeme>, Error> {
         self.advance();
+        let mut brace = JSX_SYMBOLS["{"].clone();
+        brace.span = Span::new(self.current - 1, self.current);
+        self.tokens.push(brace);
+        self.jsx_modes.push(JSXMode::Expression(0));
+
+        self.skip_whitespaces();
+        if self.source[self.current..].starts_with(&['.', '.', '.']) {
+            self.current += 3;
+            let mut ellipsis = JSX_SYMBOLS["..."].clone();
+            ellipsis.span = Span::new(self.current - 3, self.current);
+            self.tokens.push(ellipsis);
+        }
         Ok(None)
     }
 
-    fn lex_jsx_operator_punctuation(&mut self) -> Result<Option<Lexeme>, Error> {
-        if self.is_beyond_end() {
-            return Ok(Some(Lexeme {
-                text: "EOF".to_string(),
-                len: 0,
-                token: Token::EOF,
-            }));
-        }
-        let mut longest_match = None;
+    fn lex_jsx_punctuation(&mut self) -> Result<Lexeme, Error> {
         let start = self.current;
 
         for len in (1..=3).rev() {
@@ -94,35 +199,30 @@ impl Lexer {
             let lexeme_slice: String = self.source[start..end_index].iter().collect();
 
             if let Some(symbol) = JSX_SYMBOLS.get(lexeme_slice.as_str()) {
-                longest_match = Some(symbol.clone());
-                self.current += len - 1;
-                break;
+                self.current += len;
+                return Ok(symbol.clone());
             }
         }
 
-        if longest_match.is_none() {
-            return Err(Error {
-                error_kind: ErrorKind::LexerError,
-                message: format!("Unexpected character: {}", self.get_current_char()).to_string(),
-                line_number: self.line_number,
-                pos: start,
-            });
-        } else {
-            return Ok(longest_match);
-        }
+        let c = self.advance();
+        Err(Error {
+            error_kind: ErrorKind::LexerError,
+            message: format!("Unexpected character: {}", c),
+            line_number: self.line_number,
+            pos: start,
+        })
     }
 
+    // JSX names may contain dashes, as in `data-id` or `aria-label`
     fn lex_jsx_identifier(&mut self) -> Result<Option<Lexeme>, Error> {
         let start_index = self.current;
-        loop {
+        while {
             let ch = self.get_current_char();
-            if !ch.is_alphanumeric() && ch != '_' && ch != '$' {
-                self.current -= 1;
-                break;
-            }
+            ch.is_alphanumeric() || ch == '_' || ch == '$' || ch == '-'
+        } {
             self.advance();
         }
-        let token_string = self.source[start_index..=self.current]
+        let token_string = self.source[start_index..self.current]
             .iter()
             .collect::<String>();
 
@@ -131,4 +231,80 @@ impl Lexer {
             Token::JSX(JSXToken::Identifier(token_string)),
         )))
     }
+
+    // Attribute strings have no escape sequences, only character references
+    fn lex_jsx_string(&mut self) -> Result<Option<Lexeme>, Error> {
+        let quote = self.advance();
+        let content_start = self.current;
+        while self.get_current_char() != quote {
+            if self.is_at_end() {
+                return Err(Error {
+                    error_kind: ErrorKind::LexerError,
+                    message: format!("String ({quote}) not closed."),
+                    line_number: self.line_number,
+                    pos: self.current,
+                });
+            }
+            self.advance();
+        }
+        let raw: String = self.source[content_start..self.current].iter().collect();
+        self.advance(); // Consume the closing quote
+
+        Ok(Some(lexeme(
+            self.source[self.start..self.current].iter().collect(),
+            Token::JSX(JSXToken::StringLiteral(decode_jsx_entities(&raw))),
+        )))
+    }
+
+    fn lex_jsx_text(&mut self) -> Result<Option<Lexeme>, Error> {
+        while !self.is_at_end() && !matches!(self.get_current_char(), '<' | '{') {
+            self.advance();
+        }
+        let raw: String = self.source[self.start..self.current].iter().collect();
+
+        Ok(Some(lexeme(
+            raw.clone(),
+            Token::JSX(JSXToken::Text(decode_jsx_entities(&raw))),
+        )))
+    }
+}
+
+// Decode HTML character references like `&amp;`, `&#123;` and `&#x7B;`. Unknown or
+// malformed references are kept as written.
+pub fn decode_jsx_entities(raw: &str) -> String {
+    let mut decoded = String::with_capacity(raw.len());
+    let mut rest = raw;
+
+    while let Some(index) = rest.find('&') {
+        decoded.push_str(&rest[..index]);
+        rest = &rest[index..];
+
+        let entity = rest[1..]
+            .find(';')
+            .filter(|&end| end <= 10)
+            .map(|end| &rest[1..=end]);
+        let character = entity.and_then(|entity| {
+            if let Some(hex) = entity.strip_prefix("#x") {
+                u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+            } else if let Some(decimal) = entity.strip_prefix('#') {
+                decimal.parse().ok().and_then(char::from_u32)
+            } else {
+                JSX_ENTITIES.get(entity).copied()
+            }
+        });
+
+        match (entity, character) {
+            (Some(entity), Some(character)) => {
+                decoded.push(character);
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    decoded.push_str(rest);
+    decoded
 }
@@ -4,6 +4,7 @@ use lazy_static::lazy_static;
 
 use crate::symbol::{Lexeme, Token, lexeme};
 
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Debug, PartialEq, Clone)]
 pub enum JSXToken {
     // Tag delimiters
@@ -71,3 +72,41 @@ lazy_static! {
         m
     };
 }
+
+lazy_static! {
+    // Named character references decoded in JSX text and attribute strings
+    pub static ref JSX_ENTITIES: HashMap<&'static str, char> = {
+        let mut m = HashMap::new();
+
+        m.insert("amp", '&');
+        m.insert("lt", '<');
+        m.insert("gt", '>');
+        m.insert("quot", '"');
+        m.insert("apos", '\'');
+        m.insert("nbsp", '\u{a0}');
+        m.insert("copy", '©');
+        m.insert("reg", '®');
+        m.insert("trade", '™');
+        m.insert("hellip", '…');
+        m.insert("mdash", '—');
+        m.insert("ndash", '–');
+        m.insert("lsquo", '‘');
+        m.insert("rsquo", '’');
+        m.insert("ldquo", '“');
+        m.insert("rdquo", '”');
+        m.insert("laquo", '«');
+        m.insert("raquo", '»');
+        m.insert("middot", '·');
+        m.insert("bull", '•');
+        m.insert("times", '×');
+        m.insert("divide", '÷');
+        m.insert("deg", '°');
+        m.insert("euro", '€');
+        m.insert("larr", '←');
+        m.insert("rarr", '→');
+        m.insert("uarr", '↑');
+        m.insert("darr", '↓');
+
+        m
+    };
+}
@@ -1,13 +1,30 @@
 #[allow(unused_imports)]
 use super::symbol::JSXToken;
 #[allow(unused_imports)]
-use crate::{Lexer, symbol::Token};
+use crate::{
+    Lexer, LexerOptions,
+    symbol::{DelimiterToken, LiteralToken, NumberLiteral, OperatorToken, Token},
+};
+
+#[allow(dead_code)]
+const JSX_OPTIONS: LexerOptions = LexerOptions { jsx: true };
+
+// Lex `input` and then lex it again as JSX from each `<` at `positions`, like the parser does
+// when it finds one where an expression starts
+#[allow(dead_code)]
+fn lex_jsx(input: &str, positions: &[usize]) -> Lexer {
+    let mut lexer = Lexer::with_options(input.to_string(), JSX_OPTIONS);
+    lexer.scan_all_tokens();
+    for &position in positions {
+        assert!(lexer.relex_jsx(position), "{input}: no `<` at {position}");
+    }
+    lexer
+}
 
 #[test]
 fn test_jsx_identifier_and_punctuation() {
     let input = "<$div_test></$div_test>";
-    let mut lexer = Lexer::new(input.to_string());
-    lexer.scan_all_tokens();
+    let lexer = lex_jsx(input, &[0]);
     assert_eq!(lexer.errors, vec![]);
     assert_eq!(
         lexer
@@ -26,3 +43,163 @@ fn test_jsx_identifier_and_punctuation() {
         ]
     );
 }
+
+#[test]
+fn test_jsx_text_and_entities() {
+    let input = "<p>a &amp; b&nbsp;&#65;&#x42;&unknown;</p>";
+    let lexer = lex_jsx(input, &[0]);
+    assert_eq!(lexer.errors, vec![]);
+    assert_eq!(
+        lexer.tokens[3].token,
+        Token::JSX(JSXToken::Text("a & b\u{a0}AB&unknown;".to_string()))
+    );
+}
+
+#[test]
+fn test_jsx_attributes() {
+    let input = "<input data-id=\"a &quot;b&quot;\" disabled {...rest} />";
+    let lexer = lex_jsx(input, &[0]);
+    assert_eq!(lexer.errors, vec![]);
+    assert_eq!(
+        lexer
+            .tokens
+            .iter()
+            .map(|l| l.token.clone())
+            .collect::<Vec<_>>(),
+        vec![
+            Token::JSX(JSXToken::LessThan),
+            Token::JSX(JSXToken::Identifier("input".to_string())),
+            Token::JSX(JSXToken::Identifier("data-id".to_string())),
+            Token::JSX(JSXToken::Equals),
+            Token::JSX(JSXToken::StringLiteral("a \"b\"".to_string())),
+            Token::JSX(JSXToken::Identifier("disabled".to_string())),
+            Token::JSX(JSXToken::LeftBrace),
+            Token::JSX(JSXToken::Ellipsis),
+            Token::Identifier("rest".to_string()),
+            Token::JSX(JSXToken::RightBrace),
+            Token::JSX(JSXToken::SlashGreaterThan),
+            Token::EOF
+        ]
+    );
+}
+
+#[test]
+fn test_jsx_nested_expression_containers() {
+    let input = "<a>{ {b: <c>{d}</c>} }</a>";
+    let lexer = lex_jsx(input, &[0, 9]);
+    assert_eq!(lexer.errors, vec![]);
+    assert_eq!(
+        lexer
+            .tokens
+            .iter()
+            .map(|l| l.token.clone())
+            .collect::<Vec<_>>(),
+        vec![
+            Token::JSX(JSXToken::LessThan),
+            Token::JSX(JSXToken::Identifier("a".to_string())),
+            Token::JSX(JSXToken::GreaterThan),
+            Token::JSX(JSXToken::LeftBrace),
+            Token::Delimiter(DelimiterToken::OpenBrace),
+            Token::Identifier("b".to_string()),
+            Token::Operator(OperatorToken::Colon),
+            Token::JSX(JSXToken::LessThan),
+            Token::JSX(JSXToken::Identifier("c".to_string())),
+            Token::JSX(JSXToken::GreaterThan),
+            Token::JSX(JSXToken::LeftBrace),
+            Token::Identifier("d".to_string()),
+            Token::JSX(JSXToken::RightBrace),
+            Token::JSX(JSXToken::LessThanSlash),
+            Token::JSX(JSXToken::Identifier("c".to_string())),
+            Token::JSX(JSXToken::GreaterThan),
+            Token::Delimiter(DelimiterToken::CloseBrace),
+            Token::JSX(JSXToken::RightBrace),
+            Token::JSX(JSXToken::LessThanSlash),
+            Token::JSX(JSXToken::Identifier("a".to_string())),
+            Token::JSX(JSXToken::GreaterThan),
+            Token::EOF
+        ]
+    );
+}
+
+#[test]
+fn test_jsx_unterminated_contents() {
+    let lexer = lex_jsx("<a>text", &[0]);
+    assert_eq!(lexer.errors.len(), 1);
+    assert_eq!(
+        lexer.tokens.last().map(|l| l.token.clone()),
+        Some(Token::EOF)
+    );
+}
+
+#[test]
+fn test_less_than_after_operand_with_jsx() {
+    let input = "(a)<b; c[0]<d";
+    let mut lexer = Lexer::with_options(input.to_string(), JSX_OPTIONS);
+    lexer.scan_all_tokens();
+    assert_eq!(lexer.errors, vec![]);
+    assert!(
+        !lexer
+            .tokens
+            .iter()
+            .any(|l| matches!(l.token, Token::JSX(_)))
+    );
+}
+
+#[test]
+fn test_jsx_replaces_what_was_lexed_as_javascript() {
+    // As JavaScript, the apostrophe starts a string that is never closed
+    let input = "x = <p>it's</p>;\ny = 1";
+    let mut lexer = Lexer::with_options(input.to_string(), JSX_OPTIONS);
+    lexer.scan_all_tokens();
+    assert_eq!(lexer.errors.len(), 1);
+    assert_eq!(lexer.tokens[2].token, Token::Operator(OperatorToken::Less));
+
+    assert!(lexer.relex_jsx(4));
+    assert_eq!(lexer.errors, vec![]);
+    assert_eq!(
+        lexer.tokens[5].token,
+        Token::JSX(JSXToken::Text("it's".to_string()))
+    );
+    // Lexing goes back to JavaScript after the element
+    assert_eq!(
+        lexer.tokens[9..]
+            .iter()
+            .map(|l| l.token.clone())
+            .collect::<Vec<_>>(),
+        vec![
+            Token::Delimiter(DelimiterToken::Semicolon),
+            Token::Delimiter(DelimiterToken::NewLine),
+            Token::Identifier("y".to_string()),
+            Token::Operator(OperatorToken::EqualTo),
+            Token::Literal(LiteralToken::Number(NumberLiteral::Value(1.0))),
+            Token::EOF
+        ]
+    );
+    assert_eq!(lexer.tokens[11].span.start, input.find('y').unwrap());
+}
+
+#[test]
+fn test_relex_jsx_needs_a_less_than() {
+    let mut lexer = Lexer::with_options("a + b; c < d".to_string(), JSX_OPTIONS);
+    lexer.scan_all_tokens();
+    assert!(!lexer.relex_jsx(2));
+    assert!(!lexer.relex_jsx(11));
+    assert!(lexer.relex_jsx(9));
+}
+
+#[test]
+fn test_jsx_after_keyword() {
+    let lexer = lex_jsx("return <a/>", &[7]);
+    assert_eq!(lexer.errors, vec![]);
+    assert_eq!(lexer.tokens[1].token, Token::JSX(JSXToken::LessThan));
+}
+
+#[test]
+fn test_jsx_disabled() {
+    let input = "x = <a";
+    let mut lexer = Lexer::new(input.to_string());
+    lexer.scan_all_tokens();
+    assert_eq!(lexer.errors, vec![]);
+    assert_eq!(lexer.tokens[2].token, Token::Operator(OperatorToken::Less));
+    assert!(!lexer.relex_jsx(4));
+}
@@ -8,26 +8,43 @@ pub mod symbol;
 mod test;
 
 use jsompiler_common::{Error, ErrorKind};
-use symbol::{DelimiterToken, Lexeme, Token, lexeme};
+use jsx::{JSXCheckpoint, JSXMode};
+use symbol::{DelimiterToken, Lexeme, Span, Token, lexeme};
+
+// Syntax extensions the lexer recognizes on top of ECMAScript
+#[derive(Debug, Clone, Default)]
+pub struct LexerOptions {
+    pub jsx: bool, // Keep what `relex_jsx` needs to lex a `<` again as the start of JSX
+}
 
 pub struct Lexer {
+    pub options: LexerOptions,
     pub source: Vec<char>, // Code to be scanned
     pub tokens: Vec<Lexeme>,
     pub errors: Vec<crate::Error>,
     pub start: usize,
     pub current: usize,
     pub line_number: usize,
+    jsx_modes: Vec<JSXMode>, // Nested JSX contexts, innermost last
+    jsx_checkpoints: Vec<JSXCheckpoint>, // Every `<` lexed as an operator, in source order
 }
 
 impl Lexer {
     pub fn new(source: String) -> Self {
+        Self::with_options(source, LexerOptions::default())
+    }
+
+    pub fn with_options(source: String, options: LexerOptions) -> Self {
         Self {
+            options,
             source: source.chars().collect(),
             start: 0,
             current: 0,
             tokens: vec![],
             errors: vec![],
             line_number: 1,
+            jsx_modes: vec![],
+            jsx_checkpoints: vec![],
         }
     }
 
@@ -70,9 +87,22 @@ impl Lexer {
                 text: "EOF".to_string(),
                 len: 0,
                 token: symbol::Token::EOF,
+                span: symbol::Span::new(self.source.len(), self.source.len()),
             }));
         }
 
+        match self.jsx_modes.last().cloned() {
+            Some(JSXMode::Expression(depth)) => {
+                self.skip_whitespaces();
+                self.start = self.current;
+                if let Some(token) = self.lex_jsx_expression_brace(depth) {
+                    return Ok(Some(token));
+                }
+            }
+            Some(mode) => return self.lex_jsx_token(mode),
+            None => {}
+        }
+
         self.skip_whitespaces();
         self.start = self.current;
 
@@ -115,7 +145,7 @@ impl Lexer {
                 }
             }
             '/' => self.lex_comment(), // lex_comment handles advancing
-            '<' => self.lex_jsx(),
+            '<' if self.options.jsx => self.lex_less_than(),
             _ => {
                 let c = self.advance();
                 self.lex_operator_punctuation(c)
@@ -126,7 +156,11 @@ impl Lexer {
     pub fn scan_all_tokens(&mut self) {
         loop {
             match self.scan_token() {
-                Ok(Some(token)) => {
+                Ok(Some(mut token)) => {
+                    if token.span == Span::default() {
+                        let end = self.current.min(self.source.len());
+                        token.span = Span::new(self.start.min(end), end);
+                    }
                     self.tokens.push(token.clone());
 
                     if token.token == Token::EOF {
@@ -137,6 +171,10 @@ impl Lexer {
                 Err(error) => {
                     self.errors.push(error);
                     if !self.go_to_new_line() {
+                        // The token stream always ends with EOF, even after an error
+                        let mut eof = lexeme("EOF".to_string(), Token::EOF);
+                        eof.span = Span::new(self.source.len(), self.source.len());
+                        self.tokens.push(eof);
                         break;
                     }
                 }
@@ -162,6 +200,14 @@ impl Lexer {
         }
     }
 
+    // Push a token lexed outside `scan_token`, spanning from `start` to the current position
+    fn push_token_with_span(&mut self, mut token: Lexeme, start: usize) {
+        if token.span == Span::default() {
+            token.span = Span::new(start, self.current.min(self.source.len()));
+        }
+        self.tokens.push(token);
+    }
+
     pub fn skip_whitespaces(&mut self) {
         while self.get_current_char() == ' ' || self.get_current_char() == '\t' {
             self.advance();
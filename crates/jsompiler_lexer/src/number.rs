@@ -63,7 +63,7 @@ impl Lexer {
                     break;
                 }
             }
-            if is_octal && !lexeme_slice.starts_with('.') && lexeme_slice.len() != 0 {
+            if is_octal && !lexeme_slice.starts_with('.') && !lexeme_slice.is_empty() {
                 return self.output_base_value(lexeme_slice, ('o', &octal_allowed_chars));
             }
             self.current = current_index;
@@ -95,9 +95,7 @@ impl Lexer {
             });
         }
         let omitted = omit_underscores_from_numbers(
-            &lexeme_slice
-                .trim_start_matches(format!("0{}", base.0).as_str())
-                .to_string(),
+            lexeme_slice.trim_start_matches(format!("0{}", base.0).as_str()),
             false,
         );
         if omitted.is_none() {
@@ -121,7 +119,7 @@ impl Lexer {
         }
         let omitted = omitted.unwrap();
         let lexeme_f64 = u64::from_str_radix(omitted.as_str(), base.1.len() as u32);
-        if !lexeme_f64.is_ok() {
+        if lexeme_f64.is_err() {
             return Err(Error {
                 pos: self.current,
                 message: format!(
@@ -139,12 +137,12 @@ impl Lexer {
             });
         }
         let lexeme_u64 = lexeme_f64.unwrap();
-        return Ok(Some(lexeme(
+        Ok(Some(lexeme(
             lexeme_slice,
             Token::Literal(LiteralToken::Number(symbol::NumberLiteral::Value(
                 lexeme_u64 as f64,
             ))),
-        )));
+        )))
     }
 
     fn lex_nobase_numbers(&mut self) -> Result<Option<Lexeme>, crate::Error> {
@@ -153,6 +151,11 @@ impl Lexer {
             if ch == ' ' || ch == '\t' || ch == '\0' || ch == '\n' {
                 break;
             }
+            // The sign of an exponent, as in `1e-6`
+            if matches!(ch, '+' | '-') && matches!(self.source[self.current - 1], 'e' | 'E') {
+                self.advance();
+                continue;
+            }
             if let Some(lexeme) = SYMBOLS.get(ch.to_string().as_str()) {
                 match lexeme.token {
                     Token::Operator(_) => break,
@@ -165,7 +168,7 @@ impl Lexer {
             self.advance();
         }
         let token_string: String = self.source[self.start..self.current].iter().collect();
-        if token_string.chars().last() == Some('e') {
+        if token_string.ends_with('e') {
             return Err(Error::new(
                 ErrorKind::LexerError,
                 format!("Invalid number {token_string}"),
@@ -185,6 +188,7 @@ impl Lexer {
             {
                 self.advance();
             }
+            self.skip_exponent();
             let token_string: String = self.source[self.start..self.current].iter().collect();
             let omitted = omit_underscores_from_numbers(&token_string, true);
             if omitted.is_none() {
@@ -197,7 +201,7 @@ impl Lexer {
             }
             let omitted = omitted.unwrap();
             let token_num = omitted.parse::<f64>();
-            if !token_num.is_ok() {
+            if token_num.is_err() {
                 return Err(Error::new(
                     ErrorKind::LexerError,
                     format!("Invalid number {token_string}"),
@@ -226,13 +230,31 @@ impl Lexer {
         }
         let mut omitted = omitted.unwrap();
         let mut is_bigint = false;
-        if omitted.chars().last() == Some('n') {
+        if omitted.ends_with('n') {
             is_bigint = true;
             omitted.pop();
         }
 
+        // BigInts are parsed from the digits so they keep their precision
+        if is_bigint {
+            let Ok(token_num) = omitted.parse::<BigInt>() else {
+                return Err(Error::new(
+                    ErrorKind::LexerError,
+                    format!("Invalid number {token_string}"),
+                    self.line_number,
+                    self.current,
+                ));
+            };
+            return Ok(Some(lexeme(
+                token_string,
+                Token::Literal(LiteralToken::Number(symbol::NumberLiteral::BigInt(
+                    token_num,
+                ))),
+            )));
+        }
+
         let token_num = omitted.parse::<f64>();
-        if !token_num.is_ok() {
+        if token_num.is_err() {
             return Err(Error::new(
                 ErrorKind::LexerError,
                 format!("Invalid number {token_string}"),
@@ -241,16 +263,6 @@ impl Lexer {
             ));
         }
         let token_num = token_num.unwrap();
-
-        if is_bigint {
-            let token_num = token_num as i32;
-            return Ok(Some(lexeme(
-                token_string,
-                Token::Literal(LiteralToken::Number(symbol::NumberLiteral::BigInt(
-                    BigInt::from(token_num),
-                ))),
-            )));
-        }
         Ok(Some(lexeme(
             token_string,
             Token::Literal(LiteralToken::Number(symbol::NumberLiteral::Value(
@@ -258,9 +270,23 @@ impl Lexer {
             ))),
         )))
     }
+
+    // The exponent after a fraction, as in `1.5e-6`
+    fn skip_exponent(&mut self) {
+        if !matches!(self.get_current_char(), 'e' | 'E') {
+            return;
+        }
+        self.advance();
+        if matches!(self.get_current_char(), '+' | '-') {
+            self.advance();
+        }
+        while self.get_current_char().is_ascii_digit() {
+            self.advance();
+        }
+    }
 }
 
-fn omit_underscores_from_numbers(number_string: &String, is_decimal: bool) -> Option<String> {
+fn omit_underscores_from_numbers(number_string: &str, is_decimal: bool) -> Option<String> {
     let mut new_str = "".to_string();
     let mut collect_chars = vec![];
     for (i, ch) in number_string.char_indices() {
@@ -9,115 +9,114 @@ impl Lexer {
     pub fn lex_operator_punctuation(&mut self, c: char) -> Result<Option<Lexeme>, Error> {
         if c == '.' {
             // Don't allow lexing of '.' after floating point number
-            if let Some(lexeme) = self.tokens.last() {
-                if lexeme.text.contains(".") {
-                    return Err(Error {
-                        pos: self.current,
-                        line_number: self.line_number,
-                        message: format!("Invalid number: \"{}.\"", lexeme.text),
-                        error_kind: ErrorKind::LexerError,
-                    });
-                }
+            if let Some(lexeme) = self.tokens.last()
+                && lexeme.text.contains(".")
+            {
+                return Err(Error {
+                    pos: self.current,
+                    line_number: self.line_number,
+                    message: format!("Invalid number: \"{}.\"", lexeme.text),
+                    error_kind: ErrorKind::LexerError,
+                });
             }
 
             // For floating point numbers like .123
-            if self.peek_next_char().is_some_and(|ch| ch.is_ascii_digit()) {
+            if self.get_current_char().is_ascii_digit() {
                 return self.lex_number();
             }
         }
 
-        // Handle regex
-        if let Some(last_token) = self.tokens.last() {
-            if let Token::Operator(_) = last_token.token {
-                if self.get_current_char() == '/' {
-                    // Check for empty regex
-                    if self.peek_next_char() == Some('/') {
-                        return Err(Error {
-                            error_kind: ErrorKind::LexerError,
-                            message:
-                                "Empty regular expression literals are not allowed. Use /(?:)/ instead."
-                                    .to_string(),
-                            line_number: self.line_number,
-                            pos: self.current,
-                        });
-                    }
+        // Handle regex. Other punctuation is already consumed, so the current character is
+        // the one after it.
+        if c == '/'
+            && let Some(last_token) = self.tokens.last()
+            && let Token::Operator(_) = last_token.token
+            && self.get_current_char() == '/'
+        {
+            // Check for empty regex
+            if self.peek_next_char() == Some('/') {
+                return Err(Error {
+                    error_kind: ErrorKind::LexerError,
+                    message:
+                        "Empty regular expression literals are not allowed. Use /(?:)/ instead."
+                            .to_string(),
+                    line_number: self.line_number,
+                    pos: self.current,
+                });
+            }
 
-                    let mut in_class: bool = false;
-                    let mut escaped = false;
+            let mut in_class: bool = false;
+            let mut escaped = false;
 
-                    // Parse regex body
-                    loop {
-                        self.advance();
-                        let c = self.get_current_char();
-
-                        if c == '\n' || c == '\0' {
-                            return Err(Error {
-                                error_kind: ErrorKind::LexerError,
-                                message: "Regex not closed.".to_string(),
-                                line_number: self.line_number,
-                                pos: self.current,
-                            });
-                        }
-
-                        // Handle first character restrictions
-                        if self.current == self.start + 1 {
-                            if c == '*' || c == '/' {
-                                return Err(Error {
-                                    error_kind: ErrorKind::LexerError,
-                                    message: format!("Invalid first character in regex: '{}'", c),
-                                    line_number: self.line_number,
-                                    pos: self.current,
-                                });
-                            }
-                        }
-
-                        if escaped {
-                            escaped = false;
-                        } else {
-                            match c {
-                                '\\' => escaped = true,
-                                '[' if !in_class => in_class = true,
-                                ']' if in_class => in_class = false,
-                                '/' if !in_class => break,
-                                _ => {}
-                            }
-                        }
-                    }
+            // Parse regex body
+            loop {
+                self.advance();
+                let c = self.get_current_char();
 
-                    let pattern = self.source[self.start + 1..self.current]
-                        .iter()
-                        .collect::<String>();
-
-                    // Parse flags
-                    let flags_start = self.current;
-                    self.advance();
-
-                    loop {
-                        let c = self.get_current_char();
-                        if !c.is_alphabetic() {
-                            break;
-                        }
-                        if self.is_at_end() {
-                            return Err(Error {
-                                error_kind: ErrorKind::LexerError,
-                                message: "Regex not closed.".to_string(),
-                                line_number: self.line_number,
-                                pos: self.current,
-                            });
-                        }
-                        self.advance();
+                if c == '\n' || c == '\0' {
+                    return Err(Error {
+                        error_kind: ErrorKind::LexerError,
+                        message: "Regex not closed.".to_string(),
+                        line_number: self.line_number,
+                        pos: self.current,
+                    });
+                }
+
+                // Handle first character restrictions
+                if self.current == self.start + 1 && (c == '*' || c == '/') {
+                    return Err(Error {
+                        error_kind: ErrorKind::LexerError,
+                        message: format!("Invalid first character in regex: '{}'", c),
+                        line_number: self.line_number,
+                        pos: self.current,
+                    });
+                }
+
+                if escaped {
+                    escaped = false;
+                } else {
+                    match c {
+                        '\\' => escaped = true,
+                        '[' if !in_class => in_class = true,
+                        ']' if in_class => in_class = false,
+                        '/' if !in_class => break,
+                        _ => {}
                     }
+                }
+            }
+
+            let pattern = self.source[self.start + 1..self.current]
+                .iter()
+                .collect::<String>();
 
-                    let flags = self.source[flags_start + 1..self.current]
-                        .iter()
-                        .collect::<String>();
+            // Parse flags
+            let flags_start = self.current;
+            self.advance();
 
-                    return Ok(Some(lexeme(
-                        self.source[self.start..self.current].iter().collect(),
-                        Token::RegExp { pattern, flags },
-                    )));
+            loop {
+                let c = self.get_current_char();
+                if !c.is_alphabetic() {
+                    break;
                 }
+                if self.is_at_end() {
+                    return Err(Error {
+                        error_kind: ErrorKind::LexerError,
+                        message: "Regex not closed.".to_string(),
+                        line_number: self.line_number,
+                        pos: self.current,
+                    });
+                }
+                self.advance();
             }
+
+            let flags = self.source[flags_start + 1..self.current]
+                .iter()
+                .collect::<String>();
+
+            return Ok(Some(lexeme(
+                self.source[self.start..self.current].iter().collect(),
+                Token::RegExp { pattern, flags },
+            )));
         }
 
         if !self.is_beyond_end() {
@@ -157,6 +156,7 @@ impl Lexer {
             text: "EOF".to_string(),
             len: 0,
             token: symbol::Token::EOF,
+            span: symbol::Span::new(self.source.len(), self.source.len()),
         }))
     }
 }
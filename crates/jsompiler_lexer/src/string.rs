@@ -8,23 +8,25 @@ use crate::{
 impl Lexer {
     pub fn lex_template_string(&mut self) -> Result<Option<Lexeme>, Error> {
         let mut processed_string = "".to_string();
-        self.tokens.push(lexeme(
-            "`".to_string(),
-            Token::Delimiter(DelimiterToken::Tilde),
-        ));
+        self.push_token_with_span(
+            lexeme("`".to_string(), Token::Delimiter(DelimiterToken::Tilde)),
+            self.current - 1,
+        );
+        let mut part_start = self.current;
 
         while self.get_current_char() != '`' {
-            if let Err(e) = self.process_char_in_string('`', &mut processed_string) {
-                return Err(e);
-            }
+            self.process_char_in_string('`', &mut processed_string)?;
 
             if self.get_current_char() == '$'
                 && (self.source.len() > self.current + 1 && self.source[self.current + 1] == '{')
             {
-                self.tokens.push(lexeme(
-                    processed_string.clone(),
-                    Token::Literal(LiteralToken::String(processed_string.clone())),
-                ));
+                self.push_token_with_span(
+                    lexeme(
+                        processed_string.clone(),
+                        Token::Literal(LiteralToken::String(processed_string.clone())),
+                    ),
+                    part_start,
+                );
                 processed_string = "".to_string();
 
                 let mut open_brace_count = -1;
@@ -58,7 +60,7 @@ impl Lexer {
                     };
 
                     match op {
-                        Ok(Some(lexeme)) => self.tokens.push(lexeme),
+                        Ok(Some(lexeme)) => self.push_token_with_span(lexeme, self.start),
                         Err(e) => return Err(e),
                         _ => {}
                     }
@@ -67,6 +69,7 @@ impl Lexer {
                         break;
                     }
                 }
+                part_start = self.current;
             }
         }
 
@@ -80,24 +83,26 @@ impl Lexer {
                 self.advance(); // Skip `\`
             }
         }
-        self.tokens.push(lexeme(
-            processed_string.clone(),
-            Token::Literal(LiteralToken::String(processed_string)),
-        ));
+        self.push_token_with_span(
+            lexeme(
+                processed_string.clone(),
+                Token::Literal(LiteralToken::String(processed_string)),
+            ),
+            part_start,
+        );
+        self.start = self.current;
         self.advance(); // consume the closing quote
 
-        return Ok(Some(lexeme(
+        Ok(Some(lexeme(
             "`".to_string(),
             Token::Delimiter(DelimiterToken::Tilde),
-        )));
+        )))
     }
 
     pub fn lex_string(&mut self, ch: char) -> Result<Option<Lexeme>, Error> {
         let mut processed_string = "".to_string();
         while self.get_current_char() != ch {
-            if let Err(e) = self.process_char_in_string(ch, &mut processed_string) {
-                return Err(e);
-            }
+            self.process_char_in_string(ch, &mut processed_string)?
         }
         self.advance(); // consume the closing quote
         Ok(Some(lexeme(
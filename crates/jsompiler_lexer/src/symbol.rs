@@ -1,13 +1,32 @@
 use lazy_static::lazy_static;
 use std::collections::HashMap;
 
-use crate::jsx::symbol::JSXToken;
+pub use crate::jsx::symbol::JSXToken;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Lexeme {
     pub text: String,
     pub len: usize,
     pub token: Token,
+    pub span: Span,
+}
+
+// Character offsets of a piece of source code, `end` being exclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    // The smallest span covering both `self` and `other`
+    pub fn to(self, other: Span) -> Span {
+        Span::new(self.start.min(other.start), self.end.max(other.end))
+    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -193,6 +212,7 @@ pub fn lexeme(text: String, token: Token) -> Lexeme {
         text: text.clone(),
         len: text.len(),
         token,
+        span: Span::default(),
     }
 }
 
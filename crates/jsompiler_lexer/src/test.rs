@@ -512,9 +512,41 @@ fn test_exponential_number() {
     );
 }
 
+#[test]
+fn test_signed_exponential_number() {
+    let inputs = [
+        ("1e-6", 0.000001),
+        ("1E+6", 1000000.0),
+        ("1.5e-6", 0.0000015),
+        (".5e2", 50.0),
+    ];
+    for (input, value) in inputs {
+        let mut lexer = Lexer::new(input.to_string());
+        lexer.scan_all_tokens();
+        assert_eq!(lexer.errors, vec![], "{input}");
+        assert_eq!(
+            lexer
+                .tokens
+                .iter()
+                .map(|l| l.token.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                Token::Literal(LiteralToken::Number(NumberLiteral::Value(value))),
+                Token::EOF
+            ]
+        );
+    }
+
+    // A sign only belongs to the number right after its `e`
+    let mut lexer = Lexer::new("1-6".to_string());
+    lexer.scan_all_tokens();
+    assert_eq!(lexer.errors, vec![]);
+    assert_eq!(lexer.tokens.len(), 4);
+}
+
 #[test]
 fn test_invalid_exponential_number() {
-    let inputs = ["12_e2", "12e", "12e_2", "12e2e2"];
+    let inputs = ["12_e2", "12e", "12e_2", "12e2e2", "12e-", "1.5e"];
     for input in inputs {
         let mut lexer = Lexer::new(input.to_string());
         lexer.scan_all_tokens();
@@ -541,6 +573,16 @@ fn test_bigint() {
             Token::EOF
         ]
     );
+
+    // Digits past the precision of a float are kept
+    let mut lexer = Lexer::new("123456789012345678901234567890n".to_string());
+    lexer.scan_all_tokens();
+    assert_eq!(
+        lexer.tokens[0].token,
+        Token::Literal(LiteralToken::Number(NumberLiteral::BigInt(
+            "123456789012345678901234567890".parse().unwrap()
+        )))
+    );
 }
 
 #[test]
@@ -665,3 +707,74 @@ fn test_escaped_backslash_unicode_like() {
         ]
     );
 }
+
+#[test]
+fn test_less_than_after_identifier() {
+    let input = "i < 3; i <= 4";
+    let mut lexer = Lexer::new(input.to_string());
+    lexer.scan_all_tokens();
+    assert_eq!(lexer.errors, vec![]);
+    assert_eq!(
+        lexer
+            .tokens
+            .iter()
+            .map(|l| l.token.clone())
+            .collect::<Vec<_>>(),
+        vec![
+            Token::Identifier("i".to_string()),
+            Token::Operator(OperatorToken::Less),
+            Token::Literal(LiteralToken::Number(NumberLiteral::Value(3_f64))),
+            Token::Delimiter(DelimiterToken::Semicolon),
+            Token::Identifier("i".to_string()),
+            Token::Operator(OperatorToken::LessEqual),
+            Token::Literal(LiteralToken::Number(NumberLiteral::Value(4_f64))),
+            Token::EOF
+        ]
+    );
+}
+
+#[test]
+fn test_eof_after_error_on_last_line() {
+    let input = "x = 'unterminated";
+    let mut lexer = Lexer::new(input.to_string());
+    lexer.scan_all_tokens();
+    assert_eq!(lexer.errors.len(), 1);
+    assert_eq!(
+        lexer.tokens.last().map(|l| l.token.clone()),
+        Some(Token::EOF)
+    );
+}
+
+#[test]
+fn test_token_spans() {
+    let input = "let s = `a${b}c` + 'd' // e\nf";
+    let mut lexer = Lexer::new(input.to_string());
+    lexer.scan_all_tokens();
+    assert_eq!(lexer.errors, vec![]);
+    let chars: Vec<char> = input.chars().collect();
+    assert_eq!(
+        lexer
+            .tokens
+            .iter()
+            .map(|l| chars[l.span.start..l.span.end].iter().collect::<String>())
+            .collect::<Vec<_>>(),
+        vec![
+            "let", "s", "=", "`", "a", "$", "{", "b", "}", "c", "`", "+", "'d'", "// e", "\n", "f",
+            ""
+        ]
+    );
+}
+
+#[test]
+fn test_comment_after_punctuation_is_not_a_regex() {
+    let mut lexer = Lexer::new("x = {/* a */}; y = /b/".to_string());
+    lexer.scan_all_tokens();
+    assert_eq!(lexer.errors, vec![]);
+    let tokens: Vec<&Token> = lexer.tokens.iter().map(|l| &l.token).collect();
+    assert!(tokens.contains(&&Token::Comment(CommentToken::Block(" a ".to_string()))));
+    assert!(
+        tokens
+            .iter()
+            .any(|token| matches!(token, Token::RegExp { pattern, .. } if pattern == "b"))
+    );
+}
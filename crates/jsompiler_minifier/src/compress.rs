@@ -0,0 +1,422 @@
+use jsompiler_codegen::number_text;
+use jsompiler_lexer::symbol::{
+    DelimiterToken, KeywordToken, LiteralToken, NumberLiteral, SYMBOLS, Token,
+};
+use jsompiler_parser::visit::{
+    AstNode, Visit, VisitMut, Walk, walk_expression_mut, walk_statement_mut,
+};
+use jsompiler_parser::{
+    Ast, BindingPattern, BlockStatement, Expression, ExpressionId, ForInOfLeft, ForInStatement,
+    ForOfStatement, Identifier, LetStatement, Span, Statement, StatementId,
+};
+
+pub fn compress(ast: &mut Ast) {
+    Folder.visit_program(ast);
+    // Booleans are shortened last, so folding only ever sees them as literals
+    BooleanShortener.visit_program(ast);
+}
+
+// A literal operand. `undefined`, `NaN` and `Infinity` are left out, as they are ordinary
+// bindings a function may shadow.
+#[derive(Debug, Clone, PartialEq)]
+enum Constant {
+    Number(f64),
+    String(String),
+    Boolean(bool),
+    Null,
+}
+
+impl Constant {
+    fn of(expression: &Expression) -> Option<Constant> {
+        let Expression::Literal { value, .. } = expression else {
+            return None;
+        };
+        match value {
+            LiteralToken::Number(NumberLiteral::Value(value)) => Some(Constant::Number(*value)),
+            LiteralToken::String(value) => Some(Constant::String(value.clone())),
+            LiteralToken::Boolean(value) => Some(Constant::Boolean(*value)),
+            LiteralToken::Null => Some(Constant::Null),
+            LiteralToken::Number(_) | LiteralToken::Undefined => None,
+        }
+    }
+
+    fn is_truthy(&self) -> bool {
+        match self {
+            Constant::Number(value) => *value != 0.0 && !value.is_nan(),
+            Constant::String(value) => !value.is_empty(),
+            Constant::Boolean(value) => *value,
+            Constant::Null => false,
+        }
+    }
+
+    // Strings are not converted, as that needs the whole numeric literal grammar
+    fn to_number(&self) -> Option<f64> {
+        match self {
+            Constant::Number(value) => Some(*value),
+            Constant::Boolean(value) => Some(if *value { 1.0 } else { 0.0 }),
+            Constant::Null => Some(0.0),
+            Constant::String(_) => None,
+        }
+    }
+
+    // Only numbers JavaScript prints in plain decimal are converted
+    fn to_js_string(&self) -> Option<String> {
+        match self {
+            Constant::Number(value) if *value == 0.0 => Some("0".to_string()),
+            Constant::Number(value) if (1e-6..1e21).contains(&value.abs()) => {
+                Some(format!("{value}"))
+            }
+            Constant::Number(_) => None,
+            Constant::String(value) => Some(value.clone()),
+            Constant::Boolean(value) => Some(value.to_string()),
+            Constant::Null => Some("null".to_string()),
+        }
+    }
+
+    // Length of the constant in minified output
+    fn printed_len(&self) -> usize {
+        match self {
+            Constant::Number(value) => number_text(&NumberLiteral::Value(*value), true).len(),
+            Constant::String(value) => value.len() + 2,
+            Constant::Boolean(_) => 2, // `!0` or `!1`
+            Constant::Null => 4,
+        }
+    }
+
+    fn into_expression(self, span: Span) -> Option<Expression> {
+        let value = match self {
+            Constant::Number(value) if value.is_finite() => {
+                LiteralToken::Number(NumberLiteral::Value(value))
+            }
+            Constant::Number(_) => return None,
+            Constant::String(value) => LiteralToken::String(value),
+            Constant::Boolean(value) => LiteralToken::Boolean(value),
+            Constant::Null => LiteralToken::Null,
+        };
+        Some(Expression::Literal { value, span })
+    }
+}
+
+// ToInt32 and ToUint32 of the bitwise operators
+fn to_uint32(value: f64) -> u32 {
+    if !value.is_finite() {
+        return 0;
+    }
+    value.trunc().rem_euclid(4294967296.0) as u32
+}
+
+fn to_int32(value: f64) -> i32 {
+    to_uint32(value) as i32
+}
+
+fn strict_equals(left: &Constant, right: &Constant) -> bool {
+    match (left, right) {
+        (Constant::Number(left), Constant::Number(right)) => left == right,
+        (Constant::String(left), Constant::String(right)) => left == right,
+        (Constant::Boolean(left), Constant::Boolean(right)) => left == right,
+        (Constant::Null, Constant::Null) => true,
+        _ => false,
+    }
+}
+
+fn loose_equals(left: &Constant, right: &Constant) -> Option<bool> {
+    match (left, right) {
+        (Constant::String(_), Constant::String(_)) => Some(strict_equals(left, right)),
+        (Constant::String(_), _) | (_, Constant::String(_)) => None,
+        (Constant::Null, Constant::Null) => Some(true),
+        (Constant::Null, _) | (_, Constant::Null) => Some(false),
+        _ => Some(left.to_number()? == right.to_number()?),
+    }
+}
+
+fn binary(op: &str, left: &Constant, right: &Constant) -> Option<Constant> {
+    if op == "+" && (matches!(left, Constant::String(_)) || matches!(right, Constant::String(_))) {
+        return Some(Constant::String(
+            left.to_js_string()? + &right.to_js_string()?,
+        ));
+    }
+    match op {
+        "===" => return Some(Constant::Boolean(strict_equals(left, right))),
+        "!==" => return Some(Constant::Boolean(!strict_equals(left, right))),
+        "==" => return Some(Constant::Boolean(loose_equals(left, right)?)),
+        "!=" => return Some(Constant::Boolean(!loose_equals(left, right)?)),
+        _ => {}
+    }
+    // Strings compare by UTF-16 code units
+    if let (Constant::String(left), Constant::String(right)) = (left, right) {
+        let ordering = left.encode_utf16().cmp(right.encode_utf16());
+        return match op {
+            "<" => Some(Constant::Boolean(ordering.is_lt())),
+            ">" => Some(Constant::Boolean(ordering.is_gt())),
+            "<=" => Some(Constant::Boolean(ordering.is_le())),
+            ">=" => Some(Constant::Boolean(ordering.is_ge())),
+            _ => None,
+        };
+    }
+    let (left, right) = (left.to_number()?, right.to_number()?);
+    let result = match op {
+        "+" => Constant::Number(left + right),
+        "-" => Constant::Number(left - right),
+        "*" => Constant::Number(left * right),
+        "/" => Constant::Number(left / right),
+        "%" => Constant::Number(left % right),
+        // Comparisons with NaN are all false
+        "<" => Constant::Boolean(left < right),
+        ">" => Constant::Boolean(left > right),
+        "<=" => Constant::Boolean(left <= right),
+        ">=" => Constant::Boolean(left >= right),
+        "&" => Constant::Number((to_int32(left) & to_int32(right)) as f64),
+        "|" => Constant::Number((to_int32(left) | to_int32(right)) as f64),
+        "^" => Constant::Number((to_int32(left) ^ to_int32(right)) as f64),
+        "<<" => Constant::Number(to_int32(left).wrapping_shl(to_uint32(right) & 31) as f64),
+        ">>" => Constant::Number((to_int32(left) >> (to_uint32(right) & 31)) as f64),
+        ">>>" => Constant::Number((to_uint32(left) >> (to_uint32(right) & 31)) as f64),
+        _ => return None,
+    };
+    Some(result)
+}
+
+fn take_expression(ast: &mut Ast, id: ExpressionId) -> Expression {
+    std::mem::replace(
+        &mut ast[id],
+        Expression::ThisExpression {
+            span: Span::default(),
+        },
+    )
+}
+
+fn take_statement(ast: &mut Ast, id: StatementId) -> Statement {
+    std::mem::replace(
+        &mut ast[id],
+        Statement::Error {
+            span: Span::default(),
+        },
+    )
+}
+
+// The expression an operator on constant operands evaluates to, if it is no longer
+fn fold(ast: &mut Ast, expression: &Expression) -> Option<Expression> {
+    let span = expression.span();
+    match expression {
+        Expression::Unary {
+            op, op_type, expr, ..
+        } if op_type == "Prefix" => {
+            let operand = Constant::of(&ast[*expr])?;
+            let result = match op.text.as_str() {
+                "!" => Constant::Boolean(!operand.is_truthy()),
+                "-" => Constant::Number(-operand.to_number()?),
+                "+" => Constant::Number(operand.to_number()?),
+                "~" => Constant::Number(!to_int32(operand.to_number()?) as f64),
+                _ => return None,
+            };
+            result.into_expression(span)
+        }
+        Expression::Binary {
+            left, op, right, ..
+        } => {
+            let left_constant = Constant::of(&ast[*left])?;
+            // A constant left operand decides which operand a logical operator gives. Taking
+            // a member access out of `(true && a.b)()` would change `this`, so it stays.
+            let pick_right = match op.text.as_str() {
+                "&&" => Some(left_constant.is_truthy()),
+                "||" => Some(!left_constant.is_truthy()),
+                "??" => Some(left_constant == Constant::Null),
+                _ => None,
+            };
+            match pick_right {
+                Some(true) if matches!(ast[*right], Expression::MemberAccess { .. }) => {
+                    return None;
+                }
+                Some(true) => return Some(take_expression(ast, *right)),
+                Some(false) => return left_constant.into_expression(span),
+                None => {}
+            }
+            let right_constant = Constant::of(&ast[*right])?;
+            let result = binary(&op.text, &left_constant, &right_constant)?;
+            // `1/3` is shorter than its value
+            let unfolded =
+                left_constant.printed_len() + op.text.len() + right_constant.printed_len();
+            if result.printed_len() > unfolded {
+                return None;
+            }
+            result.into_expression(span)
+        }
+        _ => None,
+    }
+}
+
+// Whether a statement needs a block around it to keep its scope
+fn is_lexical_declaration(statement: &Statement) -> bool {
+    match statement {
+        Statement::LetStatement(declarator) => {
+            declarator.token != Token::Keyword(KeywordToken::Var)
+        }
+        Statement::FunctionDeclaration(_) | Statement::ClassDeclaration(_) => true,
+        _ => false,
+    }
+}
+
+// What an `if` with a constant test is left as: the branch taken, plus `var` declarations
+// for the bindings the other branch hoisted
+fn taken_branch(
+    ast: &mut Ast,
+    taken: Option<Vec<StatementId>>,
+    dropped: Option<Vec<StatementId>>,
+    span: Span,
+) -> Statement {
+    let mut statements = taken.unwrap_or_default();
+    if let Some(dropped) = dropped {
+        let mut hoisted = HoistedNames::default();
+        for id in &dropped {
+            hoisted.visit_statement(ast, *id);
+        }
+        let declarators = hoisted
+            .names
+            .into_iter()
+            .map(|name| {
+                Statement::LetStatement(LetStatement {
+                    token: Token::Keyword(KeywordToken::Var),
+                    name: BindingPattern::Identifier(name),
+                    value: None,
+                    span,
+                    declarator_span: name.span,
+                })
+            })
+            .collect();
+        statements.extend(ast.alloc_statements(declarators));
+    }
+    match statements.as_slice() {
+        [] => Statement::EmptyStatement { span },
+        [only] if !is_lexical_declaration(&ast[*only]) => take_statement(ast, *only),
+        _ => Statement::BlockStatement(BlockStatement {
+            token: Token::Delimiter(DelimiterToken::OpenBrace),
+            statements,
+            span,
+        }),
+    }
+}
+
+// Folds constant expressions bottom up and drops the dead branch of constant `if`s
+struct Folder;
+
+impl VisitMut for Folder {
+    fn visit_statement(&mut self, ast: &mut Ast, id: StatementId) {
+        let mut statement = take_statement(ast, id);
+        walk_statement_mut(self, ast, &mut statement);
+        if let Statement::IfStatement(if_statement) = &statement
+            && let Some(test) = Constant::of(&ast[if_statement.condition])
+        {
+            let consequence = Some(if_statement.consequence.clone());
+            let alternative = if_statement.alternative.clone();
+            statement = if test.is_truthy() {
+                taken_branch(ast, consequence, alternative, if_statement.span)
+            } else {
+                taken_branch(ast, alternative, consequence, if_statement.span)
+            };
+        }
+        ast[id] = statement;
+    }
+
+    fn visit_expression(&mut self, ast: &mut Ast, id: ExpressionId) {
+        let mut expression = take_expression(ast, id);
+        walk_expression_mut(self, ast, &mut expression);
+        if let Some(folded) = fold(ast, &expression) {
+            expression = folded;
+        }
+        ast[id] = expression;
+    }
+}
+
+// `true` and `false` as `!0` and `!1`
+struct BooleanShortener;
+
+impl VisitMut for BooleanShortener {
+    fn visit_expression(&mut self, ast: &mut Ast, id: ExpressionId) {
+        let mut expression = take_expression(ast, id);
+        walk_expression_mut(self, ast, &mut expression);
+        if let Expression::Literal {
+            value: LiteralToken::Boolean(value),
+            span,
+        } = expression
+        {
+            let operand = ast.alloc_expression(Expression::Literal {
+                value: LiteralToken::Number(NumberLiteral::Value(if value { 0.0 } else { 1.0 })),
+                span,
+            });
+            expression = Expression::Unary {
+                op: SYMBOLS["!"].clone(),
+                op_type: "Prefix".to_string(),
+                expr: operand,
+                span,
+            };
+        }
+        ast[id] = expression;
+    }
+}
+
+// Names a dropped branch declares for the whole function: `var` bindings, and functions
+// declared in blocks, which sloppy mode also hoists
+#[derive(Default)]
+struct HoistedNames {
+    names: Vec<Identifier>,
+}
+
+impl HoistedNames {
+    fn add(&mut self, names: Vec<Identifier>) {
+        for name in names {
+            if !self.names.iter().any(|known| known.value == name.value) {
+                self.names.push(name);
+            }
+        }
+    }
+}
+
+impl Visit for HoistedNames {
+    fn enter(&mut self, node: AstNode) -> Walk {
+        let var = Token::Keyword(KeywordToken::Var);
+        let mut names = Vec::new();
+        let walk = match node {
+            AstNode::Statement(_, Statement::LetStatement(declarator)) => {
+                if declarator.token == var {
+                    names = declarator.name.bound_names();
+                }
+                Walk::Continue
+            }
+            AstNode::Statement(
+                _,
+                Statement::ForInStatement(ForInStatement {
+                    left: ForInOfLeft::Declaration { token, name, .. },
+                    ..
+                })
+                | Statement::ForOfStatement(ForOfStatement {
+                    left: ForInOfLeft::Declaration { token, name, .. },
+                    ..
+                }),
+            ) => {
+                if *token == var {
+                    names = name.bound_names();
+                }
+                Walk::Continue
+            }
+            AstNode::Statement(_, Statement::FunctionDeclaration(function)) => {
+                names.extend(function.name);
+                Walk::Skip
+            }
+            AstNode::Statement(_, Statement::ClassDeclaration(_)) => Walk::Skip,
+            // Nothing inside a function or class is hoisted out of it
+            AstNode::Expression(
+                _,
+                Expression::FunctionExpression(_)
+                | Expression::GeneratorExpression(_)
+                | Expression::AsyncFunctionExpression(_)
+                | Expression::AsyncGeneratorExpression(_)
+                | Expression::ArrowFunctionExpression { .. }
+                | Expression::ClassExpression(_)
+                | Expression::ObjectLiteral { .. },
+            ) => Walk::Skip,
+            _ => Walk::Continue,
+        };
+        self.add(names);
+        walk
+    }
+}
@@ -0,0 +1,59 @@
+mod compress;
+mod mangle;
+mod test;
+
+use jsompiler_codegen::{CodegenOptions, Quote, generate};
+use jsompiler_common::Error;
+use jsompiler_lexer::{Lexer, LexerOptions};
+use jsompiler_parser::{Ast, Parser, ParserOptions};
+
+#[derive(Debug, Clone)]
+pub struct MinifyOptions {
+    pub compress: bool, // Fold constants, shorten booleans and drop dead branches
+    pub mangle: bool,   // Rename local bindings to the shortest free names
+    pub jsx: bool,
+}
+
+impl Default for MinifyOptions {
+    fn default() -> Self {
+        MinifyOptions {
+            compress: true,
+            mangle: true,
+            jsx: false,
+        }
+    }
+}
+
+// Minify JavaScript source. The output behaves the same as the source, except for the names
+// of local bindings and the text of functions.
+pub fn minify(source: &str, options: &MinifyOptions) -> Result<String, Vec<Error>> {
+    let mut lexer = Lexer::with_options(source.to_string(), LexerOptions { jsx: options.jsx });
+    lexer.scan_all_tokens();
+    let mut parser = Parser::with_lexer(lexer, ParserOptions { jsx: options.jsx });
+    parser.parse();
+    // Parse errors may only follow from the lexer errors, so those are reported alone
+    if !parser.lexer_errors.is_empty() {
+        return Err(parser.lexer_errors);
+    }
+    if !parser.errors.is_empty() {
+        return Err(parser.errors);
+    }
+    Ok(minify_ast(&mut parser.ast, options))
+}
+
+// Minify an AST in place and print it without optional whitespace or comments
+pub fn minify_ast(ast: &mut Ast, options: &MinifyOptions) -> String {
+    if options.compress {
+        compress::compress(ast);
+    }
+    if options.mangle {
+        mangle::mangle(ast);
+    }
+    generate(
+        ast,
+        CodegenOptions {
+            compact: true,
+            quote: Quote::Double,
+        },
+    )
+}
@@ -0,0 +1,698 @@
+use jsompiler_common::Atom;
+use jsompiler_lexer::symbol::{KeywordToken, Token};
+use jsompiler_parser::visit::{
+    VisitMut, walk_binding_pattern_mut, walk_class_element_mut, walk_expression_mut,
+    walk_parameter_mut, walk_program_mut, walk_property_mut, walk_statement_mut,
+};
+use jsompiler_parser::{
+    Ast, BindingElement, BindingPattern, BindingProperty, CatchClause, ClassElement, Expression,
+    ExpressionId, ForInOfLeft, ForInStatement, ForOfStatement, Identifier, JSXAttribute,
+    JSXAttributeValue, JSXElementName, Parameter, Property, PropertyKey, Span, Statement,
+    StatementId,
+};
+use std::cmp::Reverse;
+use std::collections::HashSet;
+
+// Rename the bindings of functions and blocks to the shortest names that don't clash.
+// Top level bindings are globals other scripts can see, so they keep their names, and so
+// does every binding of a function that holds `eval` or `with`, or of a function around it.
+pub fn mangle(ast: &mut Ast) {
+    let mut mangler = Mangler {
+        eval: ast.atoms.find("eval"),
+        ..Mangler::default()
+    };
+    mangler.visit_program(ast);
+    mangler.assign_names(ast);
+    mangler.renaming = true;
+    mangler.visit_program(ast);
+}
+
+const FIRST_CHARACTERS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ$_";
+const CHARACTERS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ$_0123456789";
+
+// Words a binding can't be named, or shouldn't be as they mean something to readers
+const RESERVED_WORDS: &[&str] = &[
+    "do",
+    "if",
+    "in",
+    "as",
+    "of",
+    "for",
+    "let",
+    "new",
+    "try",
+    "var",
+    "NaN",
+    "case",
+    "else",
+    "enum",
+    "eval",
+    "null",
+    "this",
+    "true",
+    "void",
+    "with",
+    "async",
+    "await",
+    "break",
+    "catch",
+    "class",
+    "const",
+    "false",
+    "super",
+    "throw",
+    "while",
+    "yield",
+    "delete",
+    "export",
+    "import",
+    "public",
+    "return",
+    "static",
+    "switch",
+    "typeof",
+    "default",
+    "extends",
+    "finally",
+    "package",
+    "private",
+    "continue",
+    "debugger",
+    "function",
+    "arguments",
+    "Infinity",
+    "interface",
+    "protected",
+    "undefined",
+    "implements",
+    "instanceof",
+];
+
+// The `index`th shortest identifier: `a` to `_`, then `aa`, `ba` and so on
+fn short_name(mut index: usize) -> String {
+    let mut name = String::new();
+    name.push(FIRST_CHARACTERS[index % FIRST_CHARACTERS.len()] as char);
+    index /= FIRST_CHARACTERS.len();
+    while index > 0 {
+        index -= 1;
+        name.push(CHARACTERS[index % CHARACTERS.len()] as char);
+        index /= CHARACTERS.len();
+    }
+    name
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ScopeKind {
+    Program,
+    Function, // Where `var` declarations go, also for arrows, methods and static blocks
+    Block,
+}
+
+#[derive(Debug)]
+struct Binding {
+    name: Atom,
+    fixed: bool, // Keeps its name
+    references: usize,
+    renamed: Option<Atom>,
+}
+
+#[derive(Debug)]
+struct Scope {
+    kind: ScopeKind,
+    parent: Option<usize>,
+    bindings: Vec<Binding>,
+    dynamic: bool, // Holds `eval` or `with`, directly or in a nested scope
+}
+
+#[derive(Debug)]
+struct Reference {
+    scope: usize,
+    name: Atom,
+    fixed: bool, // JSX tag names can't be renamed, as a lowercase one would become a tag
+}
+
+// Runs twice over the same tree: first to collect scopes, bindings and references, then to
+// rename. Scopes are numbered in the order they are entered, which is the same both times.
+#[derive(Default)]
+struct Mangler {
+    renaming: bool,
+    scopes: Vec<Scope>,
+    stack: Vec<usize>,
+    entered: usize,
+    references: Vec<Reference>,
+    // Sloppy mode also hoists functions declared in blocks, so their names are left alone
+    block_functions: HashSet<Atom>,
+    eval: Option<Atom>,
+}
+
+impl Mangler {
+    fn enter_scope(&mut self, kind: ScopeKind) {
+        let id = self.entered;
+        self.entered += 1;
+        if !self.renaming {
+            self.scopes.push(Scope {
+                kind,
+                parent: self.stack.last().copied(),
+                bindings: Vec::new(),
+                dynamic: false,
+            });
+        }
+        self.stack.push(id);
+    }
+
+    fn exit_scope(&mut self) {
+        self.stack.pop();
+    }
+
+    fn current(&self) -> usize {
+        *self
+            .stack
+            .last()
+            .expect("the program scope is always entered")
+    }
+
+    // The scope `var` declarations in the current one go to
+    fn var_scope(&self) -> usize {
+        *self
+            .stack
+            .iter()
+            .rev()
+            .find(|&&id| self.scopes[id].kind != ScopeKind::Block)
+            .expect("the program scope is always entered")
+    }
+
+    fn declare(&mut self, scope: usize, name: &Identifier, fixed: bool) {
+        if self.renaming {
+            return;
+        }
+        let bindings = &mut self.scopes[scope].bindings;
+        match bindings
+            .iter_mut()
+            .find(|binding| binding.name == name.value)
+        {
+            Some(binding) => binding.fixed |= fixed,
+            None => bindings.push(Binding {
+                name: name.value,
+                fixed,
+                references: 0,
+                renamed: None,
+            }),
+        }
+    }
+
+    fn declare_pattern(&mut self, token: &Token, pattern: &BindingPattern) {
+        let scope = if *token == Token::Keyword(KeywordToken::Var) {
+            self.var_scope()
+        } else {
+            self.current()
+        };
+        for name in pattern.bound_names() {
+            self.declare(scope, &name, false);
+        }
+    }
+
+    fn resolve(&self, mut scope: usize, name: Atom) -> Option<(usize, usize)> {
+        loop {
+            let bindings = &self.scopes[scope].bindings;
+            if let Some(index) = bindings.iter().position(|binding| binding.name == name) {
+                return Some((scope, index));
+            }
+            scope = self.scopes[scope].parent?;
+        }
+    }
+
+    // The new name of whatever `name` refers to from the current scope, once names are assigned
+    fn renamed(&self, name: Atom) -> Option<Atom> {
+        let (scope, index) = self.resolve(self.current(), name)?;
+        self.scopes[scope].bindings[index].renamed
+    }
+
+    fn reference(&mut self, identifier: &mut Identifier) {
+        if !self.renaming {
+            // A direct `eval` can read any binding in scope by name
+            if Some(identifier.value) == self.eval {
+                self.mark_dynamic();
+            }
+            self.references.push(Reference {
+                scope: self.current(),
+                name: identifier.value,
+                fixed: false,
+            });
+        } else if let Some(renamed) = self.renamed(identifier.value) {
+            identifier.value = renamed;
+        }
+    }
+
+    fn binding(&mut self, identifier: &mut Identifier) {
+        if self.renaming
+            && let Some(renamed) = self.renamed(identifier.value)
+        {
+            identifier.value = renamed;
+        }
+    }
+
+    fn mark_dynamic(&mut self) {
+        for &id in &self.stack {
+            self.scopes[id].dynamic = true;
+        }
+    }
+
+    // Give each binding that may be renamed the shortest name not taken by a binding that
+    // keeps its name, a global, or a binding of an enclosing scope. Bindings referenced most
+    // get the shortest names, and sibling scopes reuse the same ones.
+    fn assign_names(&mut self, ast: &mut Ast) {
+        let mut taken: HashSet<Atom> = HashSet::new();
+        for reference in &self.references {
+            match self.resolve(reference.scope, reference.name) {
+                Some((scope, index)) => {
+                    let binding = &mut self.scopes[scope].bindings[index];
+                    binding.references += 1;
+                    binding.fixed |= reference.fixed;
+                }
+                None => {
+                    taken.insert(reference.name);
+                }
+            }
+        }
+        for scope in &mut self.scopes {
+            let keeps_names = scope.kind == ScopeKind::Program || scope.dynamic;
+            for binding in &mut scope.bindings {
+                if keeps_names || self.block_functions.contains(&binding.name) {
+                    binding.fixed = true;
+                }
+                if binding.fixed {
+                    taken.insert(binding.name);
+                }
+            }
+        }
+
+        // Scopes come before the scopes nested in them, so a scope's names are assigned
+        // before its children start counting after them
+        let mut next_name = vec![0; self.scopes.len()];
+        for id in 0..self.scopes.len() {
+            let mut index = self.scopes[id].parent.map_or(0, |parent| next_name[parent]);
+            let bindings = &mut self.scopes[id].bindings;
+            let mut order: Vec<usize> = (0..bindings.len())
+                .filter(|&binding| !bindings[binding].fixed)
+                .collect();
+            order.sort_by_key(|&binding| Reverse(bindings[binding].references));
+            for binding in order {
+                let name = loop {
+                    let name = short_name(index);
+                    index += 1;
+                    let free = !RESERVED_WORDS.contains(&name.as_str())
+                        && ast
+                            .atoms
+                            .find(&name)
+                            .is_none_or(|atom| !taken.contains(&atom));
+                    if free {
+                        break name;
+                    }
+                };
+                bindings[binding].renamed = Some(ast.atoms.intern(&name));
+            }
+            next_name[id] = index;
+        }
+    }
+
+    fn statements(&mut self, ast: &mut Ast, statements: &[StatementId]) {
+        for statement in statements {
+            self.visit_statement(ast, *statement);
+        }
+    }
+
+    fn block(&mut self, ast: &mut Ast, statements: &[StatementId]) {
+        self.enter_scope(ScopeKind::Block);
+        self.statements(ast, statements);
+        self.exit_scope();
+    }
+
+    // The name of a function expression is only visible inside it, and is kept
+    fn function(
+        &mut self,
+        ast: &mut Ast,
+        name: Option<&Identifier>,
+        parameters: &mut [Parameter],
+        body: &[StatementId],
+    ) {
+        self.enter_scope(ScopeKind::Function);
+        let scope = self.current();
+        for parameter in parameters.iter() {
+            for name in parameter.bound_names() {
+                self.declare(scope, &name, false);
+            }
+        }
+        if let Some(name) = name {
+            self.declare(scope, name, true);
+        }
+        for parameter in parameters {
+            self.visit_parameter(ast, parameter);
+        }
+        // The body is parsed as one block, whose declarations belong to the function
+        let body = match body {
+            [id] => match &ast[*id] {
+                Statement::BlockStatement(block) => block.statements.clone(),
+                _ => body.to_vec(),
+            },
+            _ => body.to_vec(),
+        };
+        self.statements(ast, &body);
+        self.exit_scope();
+    }
+
+    // The name of a class is also visible inside it, where it is kept
+    fn class(
+        &mut self,
+        ast: &mut Ast,
+        name: Option<&Identifier>,
+        heritage: Option<ExpressionId>,
+        body: &mut [ClassElement],
+    ) {
+        self.enter_scope(ScopeKind::Block);
+        if let Some(name) = name {
+            self.declare(self.current(), name, true);
+        }
+        if let Some(heritage) = heritage {
+            self.visit_expression(ast, heritage);
+        }
+        for element in body {
+            self.visit_class_element(ast, element);
+        }
+        self.exit_scope();
+    }
+
+    fn statement(&mut self, ast: &mut Ast, statement: &mut Statement) {
+        match statement {
+            Statement::LetStatement(declarator) => {
+                self.declare_pattern(&declarator.token, &declarator.name);
+                walk_statement_mut(self, ast, statement);
+            }
+            Statement::FunctionDeclaration(function) => {
+                if let Some(name) = &mut function.name {
+                    let scope = self.current();
+                    if self.scopes[scope].kind == ScopeKind::Block && !self.renaming {
+                        self.block_functions.insert(name.value);
+                    }
+                    self.declare(scope, name, false);
+                    self.binding(name);
+                }
+                self.function(ast, None, &mut function.parameters, &function.body);
+            }
+            Statement::ClassDeclaration(class) => {
+                if let Some(name) = &mut class.identifier {
+                    self.declare(self.current(), name, false);
+                    self.binding(name);
+                }
+                // Inside the class, the name refers to the same binding
+                self.class(ast, None, class.heritage, &mut class.body);
+            }
+            Statement::BlockStatement(block) => self.block(ast, &block.statements),
+            Statement::ForLoopStatement(_) => {
+                self.enter_scope(ScopeKind::Block);
+                walk_statement_mut(self, ast, statement);
+                self.exit_scope();
+            }
+            Statement::ForInStatement(ForInStatement { left, .. })
+            | Statement::ForOfStatement(ForOfStatement { left, .. }) => {
+                self.enter_scope(ScopeKind::Block);
+                if let ForInOfLeft::Declaration { token, name, .. } = left {
+                    self.declare_pattern(token, name);
+                }
+                walk_statement_mut(self, ast, statement);
+                self.exit_scope();
+            }
+            Statement::SwitchStatement(switch) => {
+                self.visit_expression(ast, switch.discriminant);
+                self.enter_scope(ScopeKind::Block);
+                for case in &mut switch.cases {
+                    self.visit_switch_case(ast, case);
+                }
+                self.exit_scope();
+            }
+            Statement::TryStatement(try_statement) => {
+                self.block(ast, &try_statement.block);
+                if let Some(handler) = &mut try_statement.handler {
+                    self.visit_catch_clause(ast, handler);
+                }
+                if let Some(finalizer) = &try_statement.finalizer {
+                    self.block(ast, finalizer);
+                }
+            }
+            // Names in the body may be properties of the object
+            Statement::WithStatement(_) => {
+                if !self.renaming {
+                    self.mark_dynamic();
+                }
+                walk_statement_mut(self, ast, statement);
+            }
+            _ => walk_statement_mut(self, ast, statement),
+        }
+    }
+}
+
+impl VisitMut for Mangler {
+    fn visit_program(&mut self, ast: &mut Ast) {
+        self.entered = 0;
+        self.enter_scope(ScopeKind::Program);
+        walk_program_mut(self, ast);
+        self.exit_scope();
+    }
+
+    fn visit_statement(&mut self, ast: &mut Ast, id: StatementId) {
+        let placeholder = Statement::Error {
+            span: Span::default(),
+        };
+        let mut statement = std::mem::replace(&mut ast[id], placeholder);
+        self.statement(ast, &mut statement);
+        ast[id] = statement;
+    }
+
+    fn visit_expression(&mut self, ast: &mut Ast, id: ExpressionId) {
+        let placeholder = Expression::ThisExpression {
+            span: Span::default(),
+        };
+        let mut expression = std::mem::replace(&mut ast[id], placeholder);
+        match &mut expression {
+            Expression::Identifier(identifier) => self.reference(identifier),
+            // `a.b` only refers to `a`
+            Expression::MemberAccess {
+                object,
+                property,
+                computed,
+                ..
+            } => {
+                self.visit_expression(ast, *object);
+                if *computed {
+                    self.visit_expression(ast, *property);
+                }
+            }
+            Expression::FunctionExpression(function)
+            | Expression::GeneratorExpression(function)
+            | Expression::AsyncFunctionExpression(function)
+            | Expression::AsyncGeneratorExpression(function) => {
+                self.function(
+                    ast,
+                    function.name.as_ref(),
+                    &mut function.parameters,
+                    &function.body,
+                );
+            }
+            Expression::ArrowFunctionExpression {
+                parameters, body, ..
+            } => self.function(ast, None, parameters, body),
+            Expression::ClassExpression(class) => {
+                self.class(
+                    ast,
+                    class.identifier.as_ref(),
+                    class.heritage,
+                    &mut class.body,
+                );
+            }
+            _ => walk_expression_mut(self, ast, &mut expression),
+        }
+        ast[id] = expression;
+    }
+
+    fn visit_parameter(&mut self, ast: &mut Ast, parameter: &mut Parameter) {
+        match parameter {
+            Parameter::Identifier(identifier) => self.binding(identifier),
+            _ => walk_parameter_mut(self, ast, parameter),
+        }
+    }
+
+    // A renamed shorthand `{ a }` becomes `{ a: b }`
+    fn visit_binding_pattern(&mut self, ast: &mut Ast, pattern: &mut BindingPattern) {
+        match pattern {
+            BindingPattern::Identifier(identifier) => self.binding(identifier),
+            BindingPattern::ObjectPattern {
+                properties, rest, ..
+            } => {
+                for property in properties.iter_mut() {
+                    match property {
+                        BindingProperty::KeyValue { key, value, .. } => {
+                            self.visit_property_key(ast, key);
+                            self.visit_binding_pattern(ast, &mut value.target);
+                            if let Some(default) = value.default {
+                                self.visit_expression(ast, default);
+                            }
+                        }
+                        BindingProperty::Shorthand {
+                            name,
+                            default,
+                            span,
+                        } => {
+                            let (name, default, span) = (*name, *default, *span);
+                            if let Some(default) = default {
+                                self.visit_expression(ast, default);
+                            }
+                            if self.renaming
+                                && let Some(renamed) = self.renamed(name.value)
+                                && renamed != name.value
+                            {
+                                *property = BindingProperty::KeyValue {
+                                    key: PropertyKey::Identifier(name),
+                                    value: BindingElement {
+                                        target: BindingPattern::Identifier(Identifier {
+                                            value: renamed,
+                                            span: name.span,
+                                        }),
+                                        default,
+                                        span,
+                                    },
+                                    span,
+                                };
+                            }
+                        }
+                    }
+                }
+                if let Some(rest) = rest {
+                    self.visit_binding_pattern(ast, &mut rest.argument);
+                }
+            }
+            BindingPattern::ArrayPattern { .. } => walk_binding_pattern_mut(self, ast, pattern),
+        }
+    }
+
+    fn visit_property(&mut self, ast: &mut Ast, property: &mut Property) {
+        match property {
+            // `{ a }` becomes `{ a: b }` when `a` is renamed
+            Property::Shorthand(identifier) => {
+                let mut reference = *identifier;
+                self.reference(&mut reference);
+                if reference.value != identifier.value {
+                    let span = identifier.span;
+                    *property = Property::KeyValue {
+                        key: PropertyKey::Identifier(*identifier),
+                        value: ast.alloc_expression(Expression::Identifier(reference)),
+                        span,
+                    };
+                }
+            }
+            Property::Method {
+                key, params, body, ..
+            } => {
+                self.visit_property_key(ast, key);
+                self.function(ast, None, params, body);
+            }
+            Property::Getter { key, body, .. } => {
+                self.visit_property_key(ast, key);
+                self.function(ast, None, &mut [], std::slice::from_ref(body));
+            }
+            Property::Setter {
+                key, param, body, ..
+            } => {
+                self.visit_property_key(ast, key);
+                self.function(
+                    ast,
+                    None,
+                    std::slice::from_mut(param),
+                    std::slice::from_ref(body),
+                );
+            }
+            _ => walk_property_mut(self, ast, property),
+        }
+    }
+
+    fn visit_class_element(&mut self, ast: &mut Ast, element: &mut ClassElement) {
+        match element {
+            ClassElement::Constructor {
+                name, params, body, ..
+            }
+            | ClassElement::MethodDefinition {
+                name, params, body, ..
+            }
+            | ClassElement::AsyncMethodDefinition {
+                name, params, body, ..
+            }
+            | ClassElement::GeneratorMethodDefinition {
+                name, params, body, ..
+            }
+            | ClassElement::AsyncGeneratorMethodDefinition {
+                name, params, body, ..
+            } => {
+                self.visit_class_element_name(ast, name);
+                self.function(ast, None, params, body);
+            }
+            ClassElement::GetterDefinition { name, body, .. } => {
+                self.visit_class_element_name(ast, name);
+                self.function(ast, None, &mut [], body);
+            }
+            ClassElement::SetterDefinition {
+                name, param, body, ..
+            } => {
+                self.visit_class_element_name(ast, name);
+                self.function(ast, None, std::slice::from_mut(param), body);
+            }
+            ClassElement::StaticBlock { body, .. } => self.function(ast, None, &mut [], body),
+            ClassElement::FieldDefinition { .. } | ClassElement::AccessorDefinition { .. } => {
+                walk_class_element_mut(self, ast, element)
+            }
+        }
+    }
+
+    fn visit_catch_clause(&mut self, ast: &mut Ast, clause: &mut CatchClause) {
+        self.enter_scope(ScopeKind::Block);
+        if let Some(param) = &mut clause.param {
+            for name in param.bound_names() {
+                self.declare(self.current(), &name, false);
+            }
+            self.visit_binding_pattern(ast, param);
+        }
+        self.statements(ast, &clause.body);
+        self.exit_scope();
+    }
+
+    // Tag names refer to bindings, which keep their names
+    fn visit_jsx_element_name(&mut self, _ast: &mut Ast, name: &mut JSXElementName) {
+        let mut name = &*name;
+        while let JSXElementName::Member { object, .. } = name {
+            name = object;
+        }
+        if let JSXElementName::Identifier(identifier) = name
+            && !self.renaming
+        {
+            self.references.push(Reference {
+                scope: self.current(),
+                name: identifier.value,
+                fixed: true,
+            });
+        }
+    }
+
+    // Attribute names are not references
+    fn visit_jsx_attribute(&mut self, ast: &mut Ast, attribute: &mut JSXAttribute) {
+        match attribute {
+            JSXAttribute::Attribute { value, .. } => match value {
+                Some(JSXAttributeValue::ExpressionContainer(container)) => {
+                    self.visit_jsx_expression_container(ast, container)
+                }
+                Some(JSXAttributeValue::Element(element)) => self.visit_jsx_element(ast, element),
+                Some(JSXAttributeValue::Fragment(fragment)) => {
+                    self.visit_jsx_fragment(ast, fragment)
+                }
+                Some(JSXAttributeValue::StringLiteral { .. }) | None => {}
+            },
+            JSXAttribute::SpreadAttribute { argument, .. } => self.visit_expression(ast, *argument),
+        }
+    }
+}
@@ -0,0 +1,165 @@
+#[allow(unused_imports)]
+use super::{MinifyOptions, minify};
+
+#[allow(dead_code)]
+fn minified(source: &str, options: &MinifyOptions) -> String {
+    let output = minify(source, options).unwrap_or_else(|errors| panic!("{source}: {errors:?}"));
+    // The output must parse again
+    minify(&output, options).unwrap_or_else(|errors| panic!("{output}: {errors:?}"));
+    output
+}
+
+#[allow(dead_code)]
+fn compressed(source: &str) -> String {
+    let options = MinifyOptions {
+        mangle: false,
+        ..Default::default()
+    };
+    minified(source, &options)
+}
+
+#[allow(dead_code)]
+fn mangled(source: &str) -> String {
+    let options = MinifyOptions {
+        compress: false,
+        ..Default::default()
+    };
+    minified(source, &options)
+}
+
+// What a program prints when our interpreter runs it, which is only a dev-dependency
+#[cfg(test)]
+fn run(source: &str) -> String {
+    let mut interpreter = jsompiler_interpreter::Interpreter::capturing();
+    if let Err(errors) = interpreter.run(source) {
+        panic!("{source}: {errors:?}");
+    }
+    interpreter.output().to_string()
+}
+
+// Differential check: the minified program prints the same as the original
+#[cfg(test)]
+fn assert_same_behavior(source: &str) {
+    let output = minified(source, &MinifyOptions::default());
+    assert!(output.len() < source.len(), "{output}");
+    assert_eq!(run(&output), run(source), "{output}");
+}
+
+#[test]
+fn test_constants_are_folded() {
+    assert_eq!(compressed("x = 1 + 2 * 3 - 4\n"), "x=3");
+    assert_eq!(
+        compressed("x = \"a\" + \"b\" + 1 + null\n"),
+        "x=\"ab1null\""
+    );
+    assert_eq!(compressed("x = -(4) / 2\n"), "x=-2");
+    // Folding that would make the output longer is left out
+    assert_eq!(compressed("x = 1 / 3\n"), "x=1/3");
+    assert_eq!(compressed("x = y + (1 + 2)\n"), "x=y+3");
+}
+
+#[test]
+fn test_booleans_are_shortened() {
+    assert_eq!(compressed("x = true\ny = false\n"), "x=!0;y=!1");
+    assert_eq!(compressed("x = 1 < 2\ny = \"b\" < \"a\"\n"), "x=!0;y=!1");
+    assert_eq!(compressed("x = 1 === \"1\"\ny = null == 0\n"), "x=!1;y=!1");
+    assert_eq!(compressed("x = !\"\"\n"), "x=!0");
+}
+
+#[test]
+fn test_dead_branches_are_removed() {
+    assert_eq!(compressed("if (true) { a() } else { b() }\n"), "{a()}");
+    assert_eq!(compressed("if (0) a(); else b()\n"), "b()");
+    assert_eq!(compressed("if (1 > 2) { a() }\nc()\n"), ";c()");
+    // `var` bindings of the dropped branch are still declared
+    assert_eq!(compressed("if (false) { var x = 1; f() }\n"), "var x");
+    // A declaration on its own gets a block, so its binding stays scoped to it
+    assert_eq!(
+        compressed("if (\"s\") function f() {}\n"),
+        "{function f(){}}"
+    );
+}
+
+#[test]
+fn test_locals_are_mangled() {
+    assert_eq!(
+        mangled("function add(first, second) { let sum = first + second; return sum }\n"),
+        "function add(a,b){let c=a+b;return c}"
+    );
+    assert_eq!(
+        mangled("function f(x) { return function (y) { return x + y } }\n"),
+        "function f(a){return function(b){return a+b}}"
+    );
+    // Top level bindings and globals keep their names, and no local is renamed to one
+    assert_eq!(
+        mangled("var g = 1\nfunction f(value) { return a + g + value }\n"),
+        "var g=1;function f(b){return a+g+b}"
+    );
+    // Shorthand patterns are expanded, properties are not renamed
+    assert_eq!(
+        mangled("function f(object) { const { x } = object; return x + object.x }\n"),
+        "function f(a){const{x:b}=a;return b+a.x}"
+    );
+    // Sibling scopes reuse names
+    assert_eq!(
+        mangled("function f() { { let one = 1; g(one) } { let two = 2; g(two) } }\n"),
+        "function f(){{let a=1;g(a)}{let a=2;g(a)}}"
+    );
+}
+
+#[test]
+fn test_eval_and_with_keep_names() {
+    assert_eq!(
+        mangled("function f(value) { return eval(\"value\") }\n"),
+        "function f(value){return eval(\"value\")}"
+    );
+    assert_eq!(
+        mangled("function f(value, object) { with (object) { return value } }\n"),
+        "function f(value,object){with(object){return value}}"
+    );
+    // Scopes inside are still mangled
+    assert_eq!(
+        mangled("function f(value) { eval(\"\"); return function (inner) { return inner } }\n"),
+        "function f(value){eval(\"\");return function(a){return a}}"
+    );
+}
+
+// Output that once didn't parse again: literal callees lost their parentheses and compact
+// numbers had a signed exponent
+#[test]
+fn test_output_parses_back() {
+    assert_eq!(
+        minified(
+            "w = (function () { return 1 })()\n",
+            &MinifyOptions::default()
+        ),
+        "w=(function(){return 1})()"
+    );
+    assert_eq!(compressed("x = 0.000001 + y\n"), "x=1e-6+y");
+    assert_eq!(compressed("x = ({}).a\n"), "x=({}).a");
+    assert_eq!(compressed("(class {}).name\n"), "(class{}).name");
+}
+
+#[test]
+fn test_minified_code_behaves_the_same() {
+    assert_same_behavior(
+        "const small = 0.000001;\nconst doubled = (function (value) {\n  return value * 2;\n})(small);\nconsole.log(doubled, small + 1, (function () { return 1; })());\n",
+    );
+    assert_same_behavior(
+        "function compute(first, second) {\n  let result = first * second;\n  if (true) {\n    result = result + 1;\n  } else {\n    var unused = 5;\n  }\n  console.log(unused);\n  return result;\n}\nconsole.log(compute(6, 7), 1 + 2 * 3, \"a\" + \"b\" + 1);\n",
+    );
+    assert_same_behavior(
+        "function counter(start) {\n  let count = start;\n  const step = (amount) => {\n    count = count + amount;\n    return count;\n  };\n  return { step: step, peek: function () { return count } };\n}\nconst c = counter(10);\nc.step(5);\nconsole.log(c.peek(), c.step(1 - 3));\n",
+    );
+    assert_same_behavior(
+        "class Shape {\n  constructor(width, height) {\n    this.width = width;\n    this.height = height;\n  }\n  area() {\n    const { width, height } = this;\n    return width * height;\n  }\n}\nfunction describe(shape) {\n  try {\n    throw { message: \"x\" };\n  } catch (error) {\n    return shape.area.call({ width: 3, height: 4 }) + error.message;\n  }\n}\nconsole.log(describe(Shape.prototype), 10 - 4 > 5, null == 0);\n",
+    );
+    assert_same_behavior(
+        "function outer(value) {\n  function inner(other) {\n    return value + other;\n  }\n  for (let index = 0; index < 3; index++) {\n    value = inner(index);\n  }\n  if (false) {\n    function hoisted() {}\n  }\n  return [value, hoisted];\n}\nconsole.log(outer(1));\n",
+    );
+}
+
+#[test]
+fn test_syntax_errors_are_returned() {
+    assert!(minify("let = ;\n", &MinifyOptions::default()).is_err());
+}
@@ -1,55 +1,69 @@
-use super::{expression::Expression, Parser, Statement};
+use super::{expression::Expression, ExpressionId, Parser, Span, Statement};
 use jsompiler_common::{Error, ErrorKind};
-use jsompiler_lexer::symbol::{OperatorToken, Token};
+use jsompiler_lexer::symbol::{DelimiterToken, OperatorToken, Token};
 
 #[derive(Debug, Clone)]
 pub struct AssignmentStatement {
-    pub target: Expression,
-    pub value: Expression,
+    pub target: ExpressionId,
+    pub value: ExpressionId,
+    pub span: Span,
 }
 
 impl Parser {
     pub fn parse_assignment_statement(&mut self) -> Result<Vec<Statement>, Vec<Error>> {
+        let start = self.current;
+        let mut statement = self.parse_assignment_or_expression()?;
+
+        if !self.consume_semicolon() {
+            return Err(vec![self.error(
+                ErrorKind::UnexpectedToken,
+                "Expected ';' after expression",
+            )]);
+        }
+
+        *statement.span_mut() = self.span_from(start);
+        Ok(vec![statement])
+    }
+
+    // An assignment or expression without its terminator, as used by `for` headers
+    pub fn parse_assignment_or_expression(&mut self) -> Result<Statement, Vec<Error>> {
+        let start = self.current;
         let target = self.expression()?;
 
         // check for an assignment
         if self.match_token(&Token::Operator(OperatorToken::EqualTo)) {
             match &target {
-                Expression::Identifier(_)
-                | Expression::MemberAccess {
-                    object: _,
-                    property: _,
-                } => {
+                Expression::Identifier(_) | Expression::MemberAccess { .. } => {
                     let value = self.expression()?;
-                    return Ok(vec![Statement::AssignmentStatement(AssignmentStatement {
-                        target,
-                        value,
-                    })]);
+                    return Ok(Statement::AssignmentStatement(AssignmentStatement {
+                        target: self.ast.alloc_expression(target),
+                        value: self.ast.alloc_expression(value),
+                        span: self.span_from(start),
+                    }));
                 }
                 _ => {
-                    return Err(vec![Error {
-                        error_kind: ErrorKind::UnexpectedToken,
-                        message: "Invalid left-hand side in assignment".to_string(),
-                        line_number: 1,
-                        pos: 2,
-                    }]);
+                    return Err(vec![self.error(
+                        ErrorKind::UnexpectedToken,
+                        "Invalid left-hand side in assignment",
+                    )]);
                 }
             }
         }
 
-        // if we don't have an assignment, we can treat it as an expression statement
+        // if we don't have an assignment, we can treat it as an expression statement. A name
+        // or literal on the next line starts the next statement instead.
         match self.peek().token {
-            Token::Identifier(_) | Token::Literal(_) => {
-                return Err(vec![Error {
-                    error_kind: ErrorKind::UnexpectedToken,
-                    message: "Missing =".to_string(),
-                    line_number: 1,
-                    pos: 2,
-                }]);
+            Token::Identifier(_) | Token::Literal(_)
+                if self.previous().token != Token::Delimiter(DelimiterToken::NewLine) =>
+            {
+                return Err(vec![self.error(ErrorKind::UnexpectedToken, "Missing =")]);
             }
             _ => {}
         }
 
-        Ok(vec![Statement::ExpressionStatement(target)])
+        Ok(Statement::ExpressionStatement {
+            expression: self.ast.alloc_expression(target),
+            span: self.span_from(start),
+        })
     }
 }
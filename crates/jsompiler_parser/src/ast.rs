@@ -0,0 +1,148 @@
+use crate::{comment::Comment, expression::Expression, Node, Statement};
+use jsompiler_common::{Atom, Atoms};
+use std::ops::{Index, IndexMut};
+
+// Position of a node in its `Ast`. Statements and expressions are numbered together, so
+// side tables such as scopes or comments can be vectors indexed by `NodeId`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(u32);
+
+impl NodeId {
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct StatementId(NodeId);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ExpressionId(NodeId);
+
+impl StatementId {
+    pub fn node_id(self) -> NodeId {
+        self.0
+    }
+}
+
+impl ExpressionId {
+    pub fn node_id(self) -> NodeId {
+        self.0
+    }
+}
+
+impl From<StatementId> for NodeId {
+    fn from(id: StatementId) -> Self {
+        id.0
+    }
+}
+
+impl From<ExpressionId> for NodeId {
+    fn from(id: ExpressionId) -> Self {
+        id.0
+    }
+}
+
+#[derive(Debug, Clone)]
+enum NodeKind {
+    Statement(Statement),
+    Expression(Expression),
+}
+
+// Arena owning every statement and expression of a program. Nodes refer to their children
+// by ID instead of boxing them, so a program is one flat vector and IDs stay valid for as
+// long as the `Ast` lives. Nodes left behind when the parser backtracks are never referenced.
+#[derive(Debug, Clone, Default)]
+pub struct Ast {
+    nodes: Vec<NodeKind>,
+    pub body: Vec<Node>,          // Top-level statements in source order
+    pub comments: Vec<Comment>,   // Every comment in source order, attached to the nodes around it
+    pub hashbang: Option<String>, // Text after the `#!` on the first line
+    pub atoms: Atoms,             // Text of the identifiers and names in the program
+}
+
+impl Ast {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn next_id(&self) -> NodeId {
+        NodeId(u32::try_from(self.nodes.len()).expect("too many AST nodes"))
+    }
+
+    pub fn alloc_statement(&mut self, statement: Statement) -> StatementId {
+        let id = StatementId(self.next_id());
+        self.nodes.push(NodeKind::Statement(statement));
+        id
+    }
+
+    pub fn alloc_statements(&mut self, statements: Vec<Statement>) -> Vec<StatementId> {
+        statements
+            .into_iter()
+            .map(|statement| self.alloc_statement(statement))
+            .collect()
+    }
+
+    pub fn alloc_expression(&mut self, expression: Expression) -> ExpressionId {
+        let id = ExpressionId(self.next_id());
+        self.nodes.push(NodeKind::Expression(expression));
+        id
+    }
+
+    // Number of nodes allocated so far, the length a side table needs
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+impl Index<StatementId> for Ast {
+    type Output = Statement;
+
+    fn index(&self, id: StatementId) -> &Statement {
+        match &self.nodes[id.0.index()] {
+            NodeKind::Statement(statement) => statement,
+            NodeKind::Expression(_) => unreachable!("statement ID of an expression"),
+        }
+    }
+}
+
+impl IndexMut<StatementId> for Ast {
+    fn index_mut(&mut self, id: StatementId) -> &mut Statement {
+        match &mut self.nodes[id.0.index()] {
+            NodeKind::Statement(statement) => statement,
+            NodeKind::Expression(_) => unreachable!("statement ID of an expression"),
+        }
+    }
+}
+
+impl Index<ExpressionId> for Ast {
+    type Output = Expression;
+
+    fn index(&self, id: ExpressionId) -> &Expression {
+        match &self.nodes[id.0.index()] {
+            NodeKind::Expression(expression) => expression,
+            NodeKind::Statement(_) => unreachable!("expression ID of a statement"),
+        }
+    }
+}
+
+impl IndexMut<ExpressionId> for Ast {
+    fn index_mut(&mut self, id: ExpressionId) -> &mut Expression {
+        match &mut self.nodes[id.0.index()] {
+            NodeKind::Expression(expression) => expression,
+            NodeKind::Statement(_) => unreachable!("expression ID of a statement"),
+        }
+    }
+}
+
+impl Index<Atom> for Ast {
+    type Output = str;
+
+    fn index(&self, atom: Atom) -> &str {
+        &self.atoms[atom]
+    }
+}
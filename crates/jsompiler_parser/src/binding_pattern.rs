@@ -0,0 +1,267 @@
+use crate::object_expression::PropertyKey;
+use crate::{Error, ErrorKind};
+use crate::{ExpressionId, Identifier, Parser, Span};
+use jsompiler_lexer::symbol::{DelimiterToken, OperatorToken, Token};
+
+#[derive(Debug, Clone)]
+pub enum BindingPattern {
+    Identifier(Identifier),
+    ObjectPattern {
+        properties: Vec<BindingProperty>,
+        rest: Option<BindingRestElement>, // Always an identifier in object patterns
+        span: Span,
+    },
+    ArrayPattern {
+        elements: Vec<Option<BindingElement>>, // `None` for elisions like [a, , b]
+        rest: Option<BindingRestElement>,
+        span: Span,
+    },
+}
+
+// A binding target with an optional default value, e.g. `a = 1` or `{ b } = {}`
+#[derive(Debug, Clone)]
+pub struct BindingElement {
+    pub target: BindingPattern,
+    pub default: Option<ExpressionId>,
+    pub span: Span,
+}
+
+// The `...rest` at the end of a pattern
+#[derive(Debug, Clone)]
+pub struct BindingRestElement {
+    pub argument: Box<BindingPattern>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub enum BindingProperty {
+    KeyValue {
+        key: PropertyKey,
+        value: BindingElement,
+        span: Span,
+    },
+    Shorthand {
+        name: Identifier,
+        default: Option<ExpressionId>,
+        span: Span,
+    }, // For shorthand bindings like { x } or { x = 1 }
+}
+
+impl BindingPattern {
+    pub fn span(&self) -> Span {
+        match self {
+            BindingPattern::Identifier(identifier) => identifier.span(),
+            BindingPattern::ObjectPattern { span, .. }
+            | BindingPattern::ArrayPattern { span, .. } => *span,
+        }
+    }
+}
+
+impl BindingPattern {
+    pub fn bound_names(&self) -> Vec<Identifier> {
+        let mut names = Vec::new();
+        self.collect_bound_names(&mut names);
+        names
+    }
+
+    fn collect_bound_names(&self, names: &mut Vec<Identifier>) {
+        match self {
+            BindingPattern::Identifier(identifier) => names.push(*identifier),
+            BindingPattern::ObjectPattern {
+                properties, rest, ..
+            } => {
+                for property in properties {
+                    match property {
+                        BindingProperty::KeyValue { value, .. } => {
+                            value.target.collect_bound_names(names)
+                        }
+                        BindingProperty::Shorthand { name, .. } => names.push(*name),
+                    }
+                }
+                if let Some(rest) = rest {
+                    rest.argument.collect_bound_names(names);
+                }
+            }
+            BindingPattern::ArrayPattern { elements, rest, .. } => {
+                for element in elements.iter().flatten() {
+                    element.target.collect_bound_names(names);
+                }
+                if let Some(rest) = rest {
+                    rest.argument.collect_bound_names(names);
+                }
+            }
+        }
+    }
+}
+
+impl Parser {
+    pub fn parse_binding_pattern(&mut self) -> Result<BindingPattern, Vec<Error>> {
+        match self.peek().token {
+            Token::Identifier(_) => {
+                self.advance();
+                Ok(BindingPattern::Identifier(self.previous_identifier()))
+            }
+            Token::Delimiter(DelimiterToken::OpenBrace) => self.parse_object_binding_pattern(),
+            Token::Delimiter(DelimiterToken::OpenBracket) => self.parse_array_binding_pattern(),
+            _ => Err(vec![self.error(
+                ErrorKind::UnexpectedToken,
+                "Expected identifier or binding pattern",
+            )]),
+        }
+    }
+
+    pub fn parse_binding_element(&mut self) -> Result<BindingElement, Vec<Error>> {
+        let start = self.current;
+        let target = self.parse_binding_pattern()?;
+        let default = self.parse_binding_initializer()?;
+        Ok(BindingElement {
+            target,
+            default,
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_binding_initializer(&mut self) -> Result<Option<ExpressionId>, Vec<Error>> {
+        if self.match_token(&Token::Operator(OperatorToken::EqualTo)) {
+            let default = self.expression()?;
+            Ok(Some(self.ast.alloc_expression(default)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn parse_object_binding_pattern(&mut self) -> Result<BindingPattern, Vec<Error>> {
+        let start = self.current;
+        self.advance(); // Consume '{'
+
+        let mut properties = Vec::new();
+        let mut rest = None;
+
+        loop {
+            self.skip_newlines();
+
+            if self.match_token(&Token::Delimiter(DelimiterToken::CloseBrace)) {
+                break;
+            }
+
+            let property_start = self.current;
+            if self.match_token(&Token::Operator(OperatorToken::Spread)) {
+                // Rest element must be a plain identifier and the last property
+                let Token::Identifier(_) = self.peek().token else {
+                    return Err(vec![self.error(
+                        ErrorKind::SyntaxError,
+                        "Expected identifier after '...' in object pattern",
+                    )]);
+                };
+                self.advance();
+                rest = Some(BindingRestElement {
+                    argument: Box::new(BindingPattern::Identifier(self.previous_identifier())),
+                    span: self.span_from(property_start),
+                });
+                self.skip_newlines();
+                if !self.match_token(&Token::Delimiter(DelimiterToken::CloseBrace)) {
+                    return Err(vec![self.error(
+                        ErrorKind::SyntaxError,
+                        "Rest element must be last element",
+                    )]);
+                }
+                break;
+            }
+
+            let key = self.parse_property_key()?;
+
+            let property = if self.match_token(&Token::Operator(OperatorToken::Colon)) {
+                BindingProperty::KeyValue {
+                    key,
+                    value: self.parse_binding_element()?,
+                    span: self.span_from(property_start),
+                }
+            } else if let PropertyKey::Identifier(name) = key {
+                BindingProperty::Shorthand {
+                    name,
+                    default: self.parse_binding_initializer()?,
+                    span: self.span_from(property_start),
+                }
+            } else {
+                return Err(vec![self.error(
+                    ErrorKind::SyntaxError,
+                    "Expected ':' after property name in object pattern",
+                )]);
+            };
+            properties.push(property);
+
+            self.skip_newlines();
+            if !self.match_token(&Token::Delimiter(DelimiterToken::Comma))
+                && self.peek().token != Token::Delimiter(DelimiterToken::CloseBrace)
+            {
+                return Err(vec![self.error(
+                    ErrorKind::SyntaxError,
+                    "Expected ',' or '}' in object pattern",
+                )]);
+            }
+        }
+
+        Ok(BindingPattern::ObjectPattern {
+            properties,
+            rest,
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_array_binding_pattern(&mut self) -> Result<BindingPattern, Vec<Error>> {
+        let start = self.current;
+        self.advance(); // Consume '['
+
+        let mut elements = Vec::new();
+        let mut rest = None;
+
+        loop {
+            self.skip_newlines();
+
+            if self.match_token(&Token::Delimiter(DelimiterToken::CloseBracket)) {
+                break;
+            }
+
+            // Elision
+            if self.match_token(&Token::Delimiter(DelimiterToken::Comma)) {
+                elements.push(None);
+                continue;
+            }
+
+            let rest_start = self.current;
+            if self.match_token(&Token::Operator(OperatorToken::Spread)) {
+                let argument = Box::new(self.parse_binding_pattern()?);
+                rest = Some(BindingRestElement {
+                    argument,
+                    span: self.span_from(rest_start),
+                });
+                self.skip_newlines();
+                if !self.match_token(&Token::Delimiter(DelimiterToken::CloseBracket)) {
+                    return Err(vec![self.error(
+                        ErrorKind::SyntaxError,
+                        "Rest element must be last element",
+                    )]);
+                }
+                break;
+            }
+
+            elements.push(Some(self.parse_binding_element()?));
+
+            self.skip_newlines();
+            if !self.match_token(&Token::Delimiter(DelimiterToken::Comma))
+                && self.peek().token != Token::Delimiter(DelimiterToken::CloseBracket)
+            {
+                return Err(vec![self.error(
+                    ErrorKind::SyntaxError,
+                    "Expected ',' or ']' in array pattern",
+                )]);
+            }
+        }
+
+        Ok(BindingPattern::ArrayPattern {
+            elements,
+            rest,
+            span: self.span_from(start),
+        })
+    }
+}
@@ -1,15 +1,17 @@
-use super::{Parser, Statement};
+use super::{Parser, Span, Statement, StatementId};
 use jsompiler_common::{Error, ErrorKind};
 use jsompiler_lexer::symbol::{DelimiterToken, Token};
 
 #[derive(Debug, Clone)]
 pub struct BlockStatement {
     pub token: Token,
-    pub statements: Vec<Statement>,
+    pub statements: Vec<StatementId>,
+    pub span: Span,
 }
 
 impl Parser {
     pub fn parse_block_statement(&mut self) -> Result<Vec<Statement>, Vec<Error>> {
+        let start = self.current;
         self.advance(); // consume '{'
         let mut statements = Vec::new();
 
@@ -19,21 +21,25 @@ impl Parser {
                     self.advance(); // consume '}'
                     break;
                 }
-                Token::Delimiter(DelimiterToken::Semicolon)
-                | Token::Delimiter(DelimiterToken::NewLine) => {
+                Token::Delimiter(DelimiterToken::NewLine) => {
                     self.advance();
                 }
                 Token::EOF => {
-                    return Err(vec![Error {
-                        error_kind: ErrorKind::UnexpectedToken,
-                        message: "Expected '}'".to_string(),
-                        line_number: 1,
-                        pos: 2,
-                    }]);
+                    return Err(vec![self.error(ErrorKind::UnexpectedToken, "Expected '}'")]);
                 }
                 _ => {
-                    let mut stmt = self.parse_statement()?;
-                    statements.append(&mut stmt);
+                    let start = self.current;
+                    match self.parse_statement() {
+                        Ok(stmt) => statements.extend(self.ast.alloc_statements(stmt)),
+                        Err(errors) => {
+                            // Recover inside the block so the rest of it is still checked
+                            self.errors.extend(errors);
+                            self.synchronize(start);
+                            statements.push(self.ast.alloc_statement(Statement::Error {
+                                span: self.span_from(start),
+                            }));
+                        }
+                    }
                 }
             }
         }
@@ -41,6 +47,7 @@ impl Parser {
         Ok(vec![Statement::BlockStatement(BlockStatement {
             token: Token::Delimiter(DelimiterToken::OpenBrace),
             statements,
+            span: self.span_from(start),
         })])
     }
 }
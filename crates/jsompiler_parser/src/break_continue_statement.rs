@@ -1,6 +1,6 @@
 use super::{Parser, Statement};
 use crate::{Error, ErrorKind};
-use jsompiler_lexer::symbol::{DelimiterToken, KeywordToken, Token};
+use jsompiler_lexer::symbol::{KeywordToken, Token};
 
 impl Parser {
     pub fn parse_break_or_continue_statement(&mut self) -> Result<Vec<Statement>, Vec<Error>> {
@@ -9,56 +9,64 @@ impl Parser {
         } else if self.peek().token == Token::Keyword(KeywordToken::Continue) {
             self.parse_continue_statement()
         } else {
-            Err(vec![Error {
-                error_kind: ErrorKind::UnexpectedToken,
-                message: "Expected 'break' or 'continue'".to_string(),
-                line_number: 1,
-                pos: 2,
-            }])
+            Err(vec![self.error(
+                ErrorKind::UnexpectedToken,
+                "Expected 'break' or 'continue'",
+            )])
         }
     }
 
     fn parse_break_statement(&mut self) -> Result<Vec<Statement>, Vec<Error>> {
+        let start = self.current;
         self.advance(); // Consume 'break' keyword
 
+        // The label must be on the same line as `break`
         let label = if let Token::Identifier(_) = self.peek().token {
-            Some(self.expression()?)
+            self.advance();
+            Some(self.previous_identifier())
         } else {
             None
         };
+        self.check_jump_target(&label, false)?;
 
         // Expect semicolon
-        if !self.match_token(&Token::Delimiter(DelimiterToken::Semicolon)) {
-            return Err(vec![Error {
-                error_kind: ErrorKind::UnexpectedToken,
-                message: "Expected ';' after break statement".to_string(),
-                line_number: 1,
-                pos: 2,
-            }]);
+        if !self.consume_semicolon() {
+            return Err(vec![self.error(
+                ErrorKind::UnexpectedToken,
+                "Expected ';' after break statement",
+            )]);
         }
 
-        Ok(vec![Statement::BreakStatement { label }])
+        Ok(vec![Statement::BreakStatement {
+            label,
+            span: self.span_from(start),
+        }])
     }
 
     fn parse_continue_statement(&mut self) -> Result<Vec<Statement>, Vec<Error>> {
+        let start = self.current;
         self.advance(); // Consume 'continue' keyword
 
+        // The label must be on the same line as `continue`
         let label = if let Token::Identifier(_) = self.peek().token {
-            Some(self.expression()?)
+            self.advance();
+            Some(self.previous_identifier())
         } else {
             None
         };
+        self.check_jump_target(&label, true)?;
 
         // Expect semicolon
-        if !self.match_token(&Token::Delimiter(DelimiterToken::Semicolon)) {
-            return Err(vec![Error {
-                error_kind: ErrorKind::UnexpectedToken,
-                message: "Expected ';' after continue statement".to_string(),
-                line_number: 1,
-                pos: 2,
-            }]);
+        if !self.consume_semicolon() {
+            return Err(vec![self.error(
+                ErrorKind::UnexpectedToken,
+                "Expected ';' after continue statement",
+            )]);
         }
 
-        Ok(vec![Statement::ContinueStatement { label }])
+        Ok(vec![Statement::ContinueStatement {
+            label,
+            span: self.span_from(start),
+        }])
     }
 }
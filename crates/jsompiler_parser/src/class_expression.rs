@@ -1,110 +1,267 @@
 use crate::function_expression::Parameter;
-use crate::{expression::Expression, Identifier, Parser, Statement};
-use crate::{Error, ErrorKind};
+use crate::{
+    expression::Expression, Ast, ExpressionId, Identifier, Parser, Span, Statement, StatementId,
+};
+use crate::{Atom, Error, ErrorKind};
 use jsompiler_lexer::symbol::{
-    ContextualKeywordToken, DelimiterToken, KeywordToken, LiteralToken, OperatorToken, Token,
+    ContextualKeywordToken, DelimiterToken, KeywordToken, LiteralToken, NumberLiteral,
+    OperatorToken, Token,
 };
 
 #[derive(Debug, Clone)]
 pub struct ClassExpression {
     pub identifier: Option<Identifier>,
-    pub heritage: Box<Option<Expression>>,
+    pub heritage: Option<ExpressionId>,
+    pub body: Vec<ClassElement>,
+    pub span: Span,
+    pub body_span: Span, // From `{` to `}`
+}
+
+#[derive(Debug, Clone)]
+pub struct ClassDeclaration {
+    pub identifier: Option<Identifier>, // Only `None` for `export default class {}`
+    pub heritage: Option<ExpressionId>,
     pub body: Vec<ClassElement>,
+    pub span: Span,
+    pub body_span: Span, // From `{` to `}`
 }
 
+// Methods also keep the span of their parameters and body, which ESTree treats as a function
 #[derive(Debug, Clone)]
 pub enum ClassElement {
+    Constructor {
+        name: ClassElementName, // `constructor` or `'constructor'`
+        params: Vec<Parameter>,
+        body: Vec<StatementId>,
+        span: Span,
+        function_span: Span,
+    },
     MethodDefinition {
         name: ClassElementName,
         params: Vec<Parameter>,
-        body: Vec<Statement>,
+        body: Vec<StatementId>,
         is_static: bool,
+        span: Span,
+        function_span: Span,
     },
     AsyncMethodDefinition {
         name: ClassElementName,
         params: Vec<Parameter>,
-        body: Vec<Statement>,
+        body: Vec<StatementId>,
+        is_static: bool,
+        span: Span,
+        function_span: Span,
+    },
+    GeneratorMethodDefinition {
+        name: ClassElementName,
+        params: Vec<Parameter>,
+        body: Vec<StatementId>,
+        is_static: bool,
+        span: Span,
+        function_span: Span,
+    },
+    AsyncGeneratorMethodDefinition {
+        name: ClassElementName,
+        params: Vec<Parameter>,
+        body: Vec<StatementId>,
+        is_static: bool,
+        span: Span,
+        function_span: Span,
+    },
+    GetterDefinition {
+        name: ClassElementName,
+        body: Vec<StatementId>,
+        is_static: bool,
+        span: Span,
+        function_span: Span,
+    },
+    SetterDefinition {
+        name: ClassElementName,
+        param: Parameter,
+        body: Vec<StatementId>,
         is_static: bool,
+        span: Span,
+        function_span: Span,
     },
     FieldDefinition {
         name: ClassElementName,
-        value: Option<Expression>,
+        value: Option<ExpressionId>,
         is_static: bool,
+        span: Span,
     },
+    AccessorDefinition {
+        name: ClassElementName,
+        value: Option<ExpressionId>,
+        is_static: bool,
+        span: Span,
+    }, // Auto-accessor fields like `accessor x = 1`
     StaticBlock {
-        body: Vec<Statement>,
+        body: Vec<StatementId>,
+        span: Span,
     },
 }
 
 #[derive(Debug, Clone)]
 pub enum ClassElementName {
-    PropertyName(String),
-    PrivateIdentifier(String),
+    PropertyName(Identifier), // Identifiers and keywords
+    StringLiteral { value: String, span: Span },
+    NumberLiteral { value: NumberLiteral, span: Span },
+    PrivateIdentifier(Identifier),
+    Computed(ExpressionId), // For computed names like [Symbol.iterator]() {}
+}
+
+impl ClassElementName {
+    // The name as written, unless it is computed. `'constructor'` names the constructor
+    // just like `constructor` does.
+    pub fn static_name<'a>(&'a self, ast: &'a Ast) -> Option<&'a str> {
+        match self {
+            ClassElementName::PropertyName(name) | ClassElementName::PrivateIdentifier(name) => {
+                Some(&ast[name.value])
+            }
+            ClassElementName::StringLiteral { value, .. } => Some(value),
+            ClassElementName::NumberLiteral { .. } | ClassElementName::Computed(_) => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum MethodKind {
+    Method,
+    Async,
+    Generator,
+    AsyncGenerator,
+    Getter,
+    Setter,
+    Accessor,
+}
+
+// The `bool` records whether the accessor is static; a getter and setter pair may share a name
+#[derive(Debug, Clone, PartialEq)]
+enum PrivateNameKind {
+    Getter(bool),
+    Setter(bool),
+    Other,
+}
+
+// Private names declared and referenced within one class body
+#[derive(Debug, Default)]
+pub struct PrivateNameScope {
+    declared: Vec<(Atom, PrivateNameKind)>,
+    referenced: Vec<Identifier>,
 }
 
 impl Parser {
     pub fn parse_class_expression(&mut self) -> Result<Expression, Vec<Error>> {
-        println!("Parsing class expression");
+        Ok(Expression::ClassExpression(self.parse_class()?))
+    }
+
+    pub fn parse_class_declaration(
+        &mut self,
+        name_required: bool,
+    ) -> Result<Vec<Statement>, Vec<Error>> {
+        let class = self.parse_class()?;
+
+        // Only `export default class {}` may omit the name
+        if name_required && class.identifier.is_none() {
+            return Err(vec![self.error(
+                ErrorKind::SyntaxError,
+                "Class statements require a class name",
+            )]);
+        }
+
+        Ok(vec![Statement::ClassDeclaration(ClassDeclaration {
+            identifier: class.identifier,
+            heritage: class.heritage,
+            body: class.body,
+            span: class.span,
+            body_span: class.body_span,
+        })])
+    }
+
+    fn parse_class(&mut self) -> Result<ClassExpression, Vec<Error>> {
+        let start = self.current;
         if !self.match_token(&Token::Keyword(KeywordToken::Class)) {
-            return Err(vec![Error {
-                error_kind: ErrorKind::UnexpectedToken,
-                message: "Expected 'class' keyword".to_string(),
-                line_number: 1,
-                pos: 2,
-            }]);
+            return Err(vec![
+                self.error(ErrorKind::UnexpectedToken, "Expected 'class' keyword")
+            ]);
         }
 
-        let identifier: Option<Identifier>;
-        if let Token::Identifier(_) = self.peek().token {
+        let identifier = if let Token::Identifier(_) = self.peek().token {
             self.advance();
-            identifier = Some(Identifier {
-                token: self.previous().clone(),
-                value: self.previous().text.clone(),
-            });
+            Some(self.previous_identifier())
         } else {
-            identifier = None;
-        }
+            None
+        };
 
         // Check for heritage (extends)
-        let heritage: Option<Expression>;
-        if self.match_token(&Token::Keyword(KeywordToken::Extends)) {
-            heritage = Some(self.expression()?);
+        let heritage = if self.match_token(&Token::Keyword(KeywordToken::Extends)) {
+            let heritage = self.expression()?;
+            Some(self.ast.alloc_expression(heritage))
         } else {
-            heritage = None;
-        }
+            None
+        };
 
         if self.peek().token != Token::Delimiter(DelimiterToken::OpenBrace) {
-            return Err(vec![Error {
-                error_kind: ErrorKind::UnexpectedToken,
-                message: "Expected '{' after class name".to_string(),
-                line_number: 1,
-                pos: 2,
-            }]);
+            return Err(vec![self.error(
+                ErrorKind::UnexpectedToken,
+                "Expected '{' after class name",
+            )]);
         }
 
+        // All parts of a class body are strict mode code
+        let outer_strict_mode = self.strict_mode;
+        self.strict_mode = true;
+        let body_start = self.current;
         let body = self.parse_class_body();
-        match body {
-            Ok(body) => Ok(Expression::ClassExpression(ClassExpression {
-                identifier,
-                heritage: Box::new(heritage),
-                body,
-            })),
-            Err(err) => {
-                return Err(err);
-            }
-        }
+        self.strict_mode = outer_strict_mode;
+
+        Ok(ClassExpression {
+            identifier,
+            heritage,
+            body: body?,
+            span: self.span_from(start),
+            body_span: self.span_from(body_start),
+        })
     }
 
     fn parse_class_body(&mut self) -> Result<Vec<ClassElement>, Vec<Error>> {
         self.advance(); // Consume '{'
 
-        while self.peek().token == Token::Delimiter(DelimiterToken::NewLine)
-            || self.peek().token == Token::Delimiter(DelimiterToken::Semicolon)
-        {
-            self.advance();
+        self.private_name_scopes.push(PrivateNameScope::default());
+        let elements = self.parse_class_elements();
+        let scope = self.private_name_scopes.pop().unwrap_or_default();
+        let elements = elements?;
+
+        // Private names used in this class must be declared here or in an enclosing class
+        for name in scope.referenced {
+            if scope
+                .declared
+                .iter()
+                .any(|(declared, _)| *declared == name.value)
+            {
+                continue;
+            }
+            match self.private_name_scopes.last_mut() {
+                Some(outer) => outer.referenced.push(name),
+                None => {
+                    return Err(vec![self.error_at(
+                        name.span,
+                        ErrorKind::SyntaxError,
+                        format!(
+                            "Private field '{}' must be declared in an enclosing class",
+                            &self.ast[name.value]
+                        ),
+                    )]);
+                }
+            }
         }
 
+        Ok(elements)
+    }
+
+    fn parse_class_elements(&mut self) -> Result<Vec<ClassElement>, Vec<Error>> {
         let mut elements = Vec::new();
+        let mut has_constructor = false;
 
         while self.peek().token != Token::Delimiter(DelimiterToken::CloseBrace) && !self.is_at_end()
         {
@@ -119,139 +276,418 @@ impl Parser {
             }
 
             // Parse a class element
-            let element = self.parse_class_element();
-            match element {
-                Ok(e) => elements.push(e),
-                Err(err) => return Err(err),
+            let element = self.parse_class_element()?;
+            if let ClassElement::Constructor { .. } = element {
+                if has_constructor {
+                    return Err(vec![self.error(
+                        ErrorKind::SyntaxError,
+                        "A class may only have one constructor",
+                    )]);
+                }
+                has_constructor = true;
             }
+            self.declare_private_name(&element)?;
+            elements.push(element);
         }
 
         if self.peek().token != Token::Delimiter(DelimiterToken::CloseBrace) {
-            return Err(vec![Error {
-                error_kind: ErrorKind::UnexpectedToken,
-                message: "Expected '}' to end class body".to_string(),
-                line_number: 1,
-                pos: 2,
-            }]);
+            return Err(vec![self.error(
+                ErrorKind::UnexpectedToken,
+                "Expected '}' to end class body",
+            )]);
         }
         self.advance(); // Consume '}'
 
         Ok(elements)
     }
 
-    // Parse a single class element (method, field, or static block)
+    // Parse a single class element (method, accessor, field, or static block)
     fn parse_class_element(&mut self) -> Result<ClassElement, Vec<Error>> {
-        let is_static =
-            if self.peek().token == Token::ContextualKeyword(ContextualKeywordToken::Static) {
-                self.advance();
-                true
-            } else {
-                false
-            };
+        let start = self.current;
+        let is_static = self.match_class_element_modifier(
+            |token| *token == Token::ContextualKeyword(ContextualKeywordToken::Static),
+            false,
+        );
 
         if is_static && self.peek().token == Token::Delimiter(DelimiterToken::OpenBrace) {
-            return self.parse_static_block();
+            return self.parse_static_block(start);
+        }
+
+        let kind = if self.match_class_element_modifier(
+            |token| *token == Token::ContextualKeyword(ContextualKeywordToken::Async),
+            true,
+        ) {
+            if self.match_token(&Token::Operator(OperatorToken::Asterisk)) {
+                MethodKind::AsyncGenerator
+            } else {
+                MethodKind::Async
+            }
+        } else if self.match_token(&Token::Operator(OperatorToken::Asterisk)) {
+            MethodKind::Generator
+        } else if self.match_class_element_modifier(
+            |token| *token == Token::ContextualKeyword(ContextualKeywordToken::Get),
+            false,
+        ) {
+            MethodKind::Getter
+        } else if self.match_class_element_modifier(
+            |token| *token == Token::ContextualKeyword(ContextualKeywordToken::Set),
+            false,
+        ) {
+            MethodKind::Setter
+        } else if self.match_class_element_modifier(
+            |token| matches!(token, Token::Identifier(name) if name == "accessor"),
+            true,
+        ) {
+            MethodKind::Accessor
+        } else {
+            MethodKind::Method
+        };
+
+        let name = self.parse_class_element_name()?;
+        self.check_class_element_name(&name, is_static, &kind)?;
+
+        if kind != MethodKind::Accessor
+            && self.peek().token == Token::Delimiter(DelimiterToken::OpenParen)
+        {
+            return self.parse_method(start, name, is_static, kind);
+        }
+
+        // Field definition
+        if kind != MethodKind::Method && kind != MethodKind::Accessor {
+            return Err(vec![self.error(
+                ErrorKind::UnexpectedToken,
+                "Expected '(' after method name",
+            )]);
+        }
+        if is_constructor_name(&name, &self.ast) {
+            return Err(vec![self.error(
+                ErrorKind::SyntaxError,
+                "Classes may not have a field named 'constructor'",
+            )]);
+        }
+
+        let value = if self.peek().token == Token::Operator(OperatorToken::EqualTo) {
+            self.advance(); // Consume '='
+            let value = self.expression()?;
+            Some(self.ast.alloc_expression(value))
+        } else {
+            None
+        };
+
+        if !self.consume_semicolon() {
+            return Err(vec![self.error(
+                ErrorKind::SyntaxError,
+                "Expected ';' after field definition",
+            )]);
+        }
+
+        if kind == MethodKind::Accessor {
+            Ok(ClassElement::AccessorDefinition {
+                name,
+                value,
+                is_static,
+                span: self.span_from(start),
+            })
+        } else {
+            Ok(ClassElement::FieldDefinition {
+                name,
+                value,
+                is_static,
+                span: self.span_from(start),
+            })
         }
+    }
 
-        let is_async =
-            if self.peek().token == Token::ContextualKeyword(ContextualKeywordToken::Async) {
+    // Modifiers like `static`, `get` or `async` are also valid element names, e.g. `static() {}`
+    // or `get = 1`, so they only act as modifiers when a name follows. Only `async` and
+    // `accessor` need it on the same line; the others may have line breaks after them.
+    fn match_class_element_modifier(
+        &mut self,
+        is_modifier: impl Fn(&Token) -> bool,
+        same_line: bool,
+    ) -> bool {
+        if !is_modifier(&self.peek().token) {
+            return false;
+        }
+        let mut index = self.peek_index() + 1;
+        while !same_line && self.tokens[index].token == Token::Delimiter(DelimiterToken::NewLine) {
+            index += 1;
+        }
+        match self.tokens[index].token {
+            Token::Delimiter(DelimiterToken::OpenParen)
+            | Token::Delimiter(DelimiterToken::Semicolon)
+            | Token::Delimiter(DelimiterToken::CloseBrace)
+            | Token::Delimiter(DelimiterToken::NewLine)
+            | Token::Operator(OperatorToken::EqualTo)
+            | Token::EOF => false,
+            _ => {
                 self.advance();
+                self.skip_newlines();
                 true
-            } else {
-                false
-            };
+            }
+        }
+    }
 
-        let name = match self.peek().token {
-            Token::Identifier(_) => ClassElementName::PropertyName(self.peek().text.clone()),
+    fn parse_class_element_name(&mut self) -> Result<ClassElementName, Vec<Error>> {
+        let name = match &self.peek().token {
+            Token::Identifier(_) | Token::Keyword(_) | Token::ContextualKeyword(_) => {
+                ClassElementName::PropertyName(self.peek_identifier())
+            }
             Token::PrivateIdentifier(_) => {
-                ClassElementName::PrivateIdentifier(self.peek().text.clone())
+                ClassElementName::PrivateIdentifier(self.peek_identifier())
             }
-            Token::Literal(LiteralToken::String(_)) => {
-                ClassElementName::PropertyName(self.peek().text.clone())
+            Token::Literal(LiteralToken::String(value)) => ClassElementName::StringLiteral {
+                value: value.clone(),
+                span: self.peek().span,
+            },
+            Token::Literal(LiteralToken::Number(value)) => ClassElementName::NumberLiteral {
+                value: value.clone(),
+                span: self.peek().span,
+            },
+            Token::Delimiter(DelimiterToken::OpenBracket) => {
+                self.advance(); // Consume '['
+                let expr = self.expression()?;
+                if !self.match_token(&Token::Delimiter(DelimiterToken::CloseBracket)) {
+                    return Err(vec![self.error(
+                        ErrorKind::SyntaxError,
+                        "Expected ']' after computed class element name",
+                    )]);
+                }
+                return Ok(ClassElementName::Computed(self.ast.alloc_expression(expr)));
             }
             _ => {
-                return Err(vec![Error {
-                    error_kind: ErrorKind::UnexpectedToken,
-                    message: "Expected class element name".to_string(),
-                    line_number: 1,
-                    pos: 2,
-                }])
+                return Err(vec![
+                    self.error(ErrorKind::UnexpectedToken, "Expected class element name")
+                ])
             }
         };
 
         self.advance(); // Consume the name
+        Ok(name)
+    }
 
-        if self.peek().token == Token::Delimiter(DelimiterToken::OpenParen) {
-            return self.parse_method(name, is_static, is_async);
-        } else {
-            // Field definition
-            if is_async {
-                return Err(vec![Error {
-                    error_kind: ErrorKind::UnexpectedToken,
-                    message: "Async fields are not allowed ".to_string(),
-                    line_number: 1,
-                    pos: 2,
-                }]);
-            }
-            let value = if self.peek().token == Token::Operator(OperatorToken::EqualTo) {
-                self.advance(); // Consume '='
-                Some(self.expression()?)
-            } else {
-                None
-            };
-
-            if self.peek().token != Token::Delimiter(DelimiterToken::Semicolon)
-                && self.peek().token != Token::Delimiter(DelimiterToken::NewLine)
-            {
-                return Err(vec![Error {
-                    error_kind: ErrorKind::SyntaxError,
-                    message: "Expected ';' after field definition".to_string(),
-                    line_number: 1,
-                    pos: 2,
-                }]);
+    fn check_class_element_name(
+        &self,
+        name: &ClassElementName,
+        is_static: bool,
+        kind: &MethodKind,
+    ) -> Result<(), Vec<Error>> {
+        let message = match (name, name.static_name(&self.ast)) {
+            (ClassElementName::PrivateIdentifier(_), Some("#constructor")) => {
+                "Classes may not have a private element named '#constructor'"
             }
-
-            if self.peek().token == Token::Delimiter(DelimiterToken::Semicolon) {
-                self.advance();
+            (ClassElementName::PrivateIdentifier(_), _) => return Ok(()),
+            (_, Some("prototype")) if is_static => {
+                "Classes may not have a static property named 'prototype'"
             }
+            (_, Some("constructor")) if !is_static => match kind {
+                MethodKind::Getter | MethodKind::Setter => {
+                    "Class constructor may not be an accessor"
+                }
+                MethodKind::Generator | MethodKind::AsyncGenerator => {
+                    "Class constructor may not be a generator"
+                }
+                MethodKind::Async => "Class constructor may not be an async method",
+                MethodKind::Method | MethodKind::Accessor => return Ok(()),
+            },
+            _ => return Ok(()),
+        };
 
-            Ok(ClassElement::FieldDefinition {
-                name,
-                value,
-                is_static,
-            })
-        }
+        Err(vec![self.error(ErrorKind::SyntaxError, message.to_string())])
     }
 
-    fn parse_static_block(&mut self) -> Result<ClassElement, Vec<Error>> {
-        let body = self.parse_block_statement()?;
-        Ok(ClassElement::StaticBlock { body })
+    fn parse_static_block(&mut self, start: usize) -> Result<ClassElement, Vec<Error>> {
+        let outer_label_set = std::mem::take(&mut self.label_set);
+        let body = self.parse_block_statement();
+        self.label_set = outer_label_set;
+        let body = body?;
+        Ok(ClassElement::StaticBlock {
+            body: self.ast.alloc_statements(body),
+            span: self.span_from(start),
+        })
     }
 
     fn parse_method(
         &mut self,
+        start: usize,
         name: ClassElementName,
         is_static: bool,
-        is_async: bool,
+        kind: MethodKind,
     ) -> Result<ClassElement, Vec<Error>> {
+        let function_start = self.current;
         let params = self.parse_function_parameters()?;
 
-        let body = self.parse_block_statement()?;
+        match kind {
+            MethodKind::Getter if !params.is_empty() => {
+                return Err(vec![self.error(
+                    ErrorKind::SyntaxError,
+                    "Getter must not have any formal parameters",
+                )]);
+            }
+            MethodKind::Setter
+                if params.len() != 1 || matches!(params[0], Parameter::RestParameter { .. }) =>
+            {
+                return Err(vec![self.error(
+                    ErrorKind::SyntaxError,
+                    "Setter must have exactly one formal parameter",
+                )]);
+            }
+            _ => {}
+        }
+
+        let body = self.parse_function_body_block(&params, true)?;
+        let body = self.ast.alloc_statements(body);
+        let span = self.span_from(start);
+        let function_span = self.span_from(function_start);
 
-        if is_async {
-            Ok(ClassElement::AsyncMethodDefinition {
+        if !is_static && is_constructor_name(&name, &self.ast) {
+            return Ok(ClassElement::Constructor {
+                name,
+                params,
+                body,
+                span,
+                function_span,
+            });
+        }
+
+        Ok(match kind {
+            MethodKind::Async => ClassElement::AsyncMethodDefinition {
                 name,
                 params,
                 body,
                 is_static,
-            })
-        } else {
-            Ok(ClassElement::MethodDefinition {
+                span,
+                function_span,
+            },
+            MethodKind::Generator => ClassElement::GeneratorMethodDefinition {
                 name,
                 params,
                 body,
                 is_static,
-            })
+                span,
+                function_span,
+            },
+            MethodKind::AsyncGenerator => ClassElement::AsyncGeneratorMethodDefinition {
+                name,
+                params,
+                body,
+                is_static,
+                span,
+                function_span,
+            },
+            MethodKind::Getter => ClassElement::GetterDefinition {
+                name,
+                body,
+                is_static,
+                span,
+                function_span,
+            },
+            MethodKind::Setter => ClassElement::SetterDefinition {
+                name,
+                param: params.into_iter().next().expect("setter has one parameter"),
+                body,
+                is_static,
+                span,
+                function_span,
+            },
+            MethodKind::Method | MethodKind::Accessor => ClassElement::MethodDefinition {
+                name,
+                params,
+                body,
+                is_static,
+                span,
+                function_span,
+            },
+        })
+    }
+
+    // Private names may only be declared once per class, except for a getter/setter pair
+    fn declare_private_name(&mut self, element: &ClassElement) -> Result<(), Vec<Error>> {
+        let (name, kind) = match element {
+            ClassElement::GetterDefinition {
+                name: ClassElementName::PrivateIdentifier(name),
+                is_static,
+                ..
+            } => (name, PrivateNameKind::Getter(*is_static)),
+            ClassElement::SetterDefinition {
+                name: ClassElementName::PrivateIdentifier(name),
+                is_static,
+                ..
+            } => (name, PrivateNameKind::Setter(*is_static)),
+            ClassElement::MethodDefinition {
+                name: ClassElementName::PrivateIdentifier(name),
+                ..
+            }
+            | ClassElement::AsyncMethodDefinition {
+                name: ClassElementName::PrivateIdentifier(name),
+                ..
+            }
+            | ClassElement::GeneratorMethodDefinition {
+                name: ClassElementName::PrivateIdentifier(name),
+                ..
+            }
+            | ClassElement::AsyncGeneratorMethodDefinition {
+                name: ClassElementName::PrivateIdentifier(name),
+                ..
+            }
+            | ClassElement::FieldDefinition {
+                name: ClassElementName::PrivateIdentifier(name),
+                ..
+            }
+            | ClassElement::AccessorDefinition {
+                name: ClassElementName::PrivateIdentifier(name),
+                ..
+            } => (name, PrivateNameKind::Other),
+            _ => return Ok(()),
+        };
+
+        let Some(scope) = self.private_name_scopes.last_mut() else {
+            return Ok(());
+        };
+
+        let conflict = scope.declared.iter().any(|(declared, declared_kind)| {
+            *declared == name.value
+                && !matches!(
+                    (declared_kind, &kind),
+                    (PrivateNameKind::Getter(s), PrivateNameKind::Setter(t))
+                    | (PrivateNameKind::Setter(s), PrivateNameKind::Getter(t)) if s == t
+                )
+        });
+
+        if conflict {
+            return Err(vec![self.error_at(
+                name.span,
+                ErrorKind::SyntaxError,
+                format!(
+                    "Identifier '{}' has already been declared",
+                    &self.ast[name.value]
+                ),
+            )]);
+        }
+        scope.declared.push((name.value, kind));
+        Ok(())
+    }
+
+    // Record a use of a private name, e.g. `this.#x` or `#x in obj`
+    pub fn reference_private_name(&mut self, name: Identifier) -> Result<(), Vec<Error>> {
+        match self.private_name_scopes.last_mut() {
+            Some(scope) => {
+                scope.referenced.push(name);
+                Ok(())
+            }
+            None => Err(vec![self.error_at(
+                name.span,
+                ErrorKind::SyntaxError,
+                format!(
+                    "Private field '{}' must be declared in an enclosing class",
+                    &self.ast[name.value]
+                ),
+            )]),
         }
     }
 }
+
+fn is_constructor_name(name: &ClassElementName, ast: &Ast) -> bool {
+    !matches!(name, ClassElementName::PrivateIdentifier(_))
+        && name.static_name(ast) == Some("constructor")
+}
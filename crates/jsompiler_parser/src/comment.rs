@@ -0,0 +1,161 @@
+use crate::visit::{AstNode, Visit, Walk};
+use crate::{Ast, NodeId, Span};
+use jsompiler_lexer::symbol::{CommentToken, DelimiterToken, Lexeme, Token};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommentKind {
+    Line,  // `// text`
+    Block, // `/* text */`
+}
+
+// Where a comment sits relative to the node it is attached to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommentPlacement {
+    Leading,  // Before the node, as in `/* a */ a` or a comment on the line above
+    Trailing, // After the node on the same line, as in `a; // a`
+    Dangling, // Inside a node with nothing else to attach to, as in `{ /* empty */ }`
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comment {
+    pub kind: CommentKind,
+    pub text: String, // Without the `//`, `/*` and `*/`
+    pub span: Span,
+    pub attachment: Option<(NodeId, CommentPlacement)>, // None in a program with no nodes
+}
+
+// Comments and the hashbang taken out of a token stream, which the parser never sees
+#[derive(Debug, Default)]
+pub(crate) struct Trivia {
+    pub comments: Vec<Comment>,
+    pub hashbang: Option<String>,
+    pub line_breaks: Vec<usize>, // Start of every line break, in source order
+}
+
+// Split comments off `tokens`. A block comment spanning lines still ends the line for
+// automatic semicolon insertion, so it is replaced by a line break token.
+pub(crate) fn split_trivia(tokens: Vec<Lexeme>) -> (Vec<Lexeme>, Trivia) {
+    let mut trivia = Trivia::default();
+    let mut kept = Vec::with_capacity(tokens.len());
+    for lexeme in tokens {
+        let (kind, text) = match lexeme.token {
+            Token::Comment(CommentToken::HashBang(text)) => {
+                trivia.hashbang = Some(text);
+                continue;
+            }
+            Token::Comment(CommentToken::Line(ref text)) => (CommentKind::Line, text.clone()),
+            Token::Comment(CommentToken::Block(ref text)) => (CommentKind::Block, text.clone()),
+            Token::Delimiter(DelimiterToken::NewLine) => {
+                trivia.line_breaks.push(lexeme.span.start);
+                kept.push(lexeme);
+                continue;
+            }
+            _ => {
+                kept.push(lexeme);
+                continue;
+            }
+        };
+        if kind == CommentKind::Block && text.contains(['\n', '\r', '\u{2028}', '\u{2029}']) {
+            trivia.line_breaks.push(lexeme.span.start);
+            kept.push(Lexeme {
+                text: "\n".to_string(),
+                len: 1,
+                token: Token::Delimiter(DelimiterToken::NewLine),
+                span: lexeme.span,
+            });
+        }
+        trivia.comments.push(Comment {
+            kind,
+            text,
+            span: lexeme.span,
+            attachment: None,
+        });
+    }
+    (kept, trivia)
+}
+
+// Every statement and expression with a position in the source
+#[derive(Default)]
+struct Spans(Vec<(NodeId, Span)>);
+
+impl Visit for Spans {
+    fn enter(&mut self, node: AstNode) -> Walk {
+        let (id, span) = match node {
+            AstNode::Statement(id, statement) => (id.node_id(), statement.span()),
+            AstNode::Expression(id, expression) => (id.node_id(), expression.span()),
+            _ => return Walk::Continue,
+        };
+        if span.end > span.start {
+            self.0.push((id, span));
+        }
+        Walk::Continue
+    }
+}
+
+// Attach each comment to a node of `ast`:
+//  - the node before it on the same line gets it as trailing, unless another node follows
+//    on that line too, as in `f(a, /* b */ b)`
+//  - otherwise the node after it gets it as leading
+//  - otherwise the node before it gets it as trailing, as at the end of a block
+//  - otherwise the smallest node around it gets it as dangling
+// Only nodes inside the smallest node around the comment are considered, so a comment at
+// the end of a function body stays in the function.
+pub(crate) fn attach_comments(ast: &mut Ast, line_breaks: &[usize]) {
+    let mut spans = Spans::default();
+    spans.visit_program(ast);
+    let nodes = spans.0;
+    let same_line = |from: usize, to: usize| {
+        let first = line_breaks.partition_point(|&start| start < from);
+        line_breaks.get(first).is_none_or(|&start| start >= to)
+    };
+
+    for comment in &mut ast.comments {
+        let span = comment.span;
+        let enclosing = nodes
+            .iter()
+            .filter(|(_, node)| node.start <= span.start && span.end <= node.end)
+            .min_by_key(|(_, node)| node.end - node.start)
+            .copied();
+        let inside = |node: &Span| match enclosing {
+            Some((_, outer)) => {
+                outer.start <= node.start && node.end <= outer.end && *node != outer
+            }
+            None => true,
+        };
+
+        // Of nodes ending at the same place, the outermost is the one before the comment
+        let preceding = nodes
+            .iter()
+            .filter(|(_, node)| node.end <= span.start && inside(node))
+            .max_by_key(|(_, node)| (node.end, usize::MAX - node.start))
+            .copied();
+        let following = nodes
+            .iter()
+            .filter(|(_, node)| node.start >= span.end && inside(node))
+            .min_by_key(|(_, node)| (node.start, usize::MAX - node.end))
+            .copied();
+
+        comment.attachment = match (preceding, following) {
+            (Some((id, before)), following) if same_line(before.end, span.start) => match following
+            {
+                Some((next, after)) if same_line(span.end, after.start) => {
+                    Some((next, CommentPlacement::Leading))
+                }
+                _ => Some((id, CommentPlacement::Trailing)),
+            },
+            (_, Some((id, _))) => Some((id, CommentPlacement::Leading)),
+            (Some((id, _)), None) => Some((id, CommentPlacement::Trailing)),
+            (None, None) => enclosing.map(|(id, _)| (id, CommentPlacement::Dangling)),
+        };
+    }
+}
+
+impl Ast {
+    // Comments attached to `node`, in source order
+    pub fn comments_of(&self, node: impl Into<NodeId>) -> impl Iterator<Item = &Comment> {
+        let node = node.into();
+        self.comments
+            .iter()
+            .filter(move |comment| comment.attachment.is_some_and(|(id, _)| id == node))
+    }
+}
@@ -0,0 +1,20 @@
+use super::{Parser, Statement};
+use crate::{Error, ErrorKind};
+
+impl Parser {
+    pub fn parse_debugger_statement(&mut self) -> Result<Vec<Statement>, Vec<Error>> {
+        let start = self.current;
+        self.advance(); // Consume 'debugger' keyword
+
+        if !self.consume_semicolon() {
+            return Err(vec![self.error(
+                ErrorKind::UnexpectedToken,
+                "Expected ';' after debugger statement",
+            )]);
+        }
+
+        Ok(vec![Statement::DebuggerStatement {
+            span: self.span_from(start),
+        }])
+    }
+}
@@ -0,0 +1,42 @@
+use super::{ExpressionId, Parser, Span, Statement, StatementId};
+use crate::{Error, ErrorKind};
+use jsompiler_lexer::symbol::{DelimiterToken, KeywordToken, Token};
+
+#[derive(Debug, Clone)]
+pub struct DoWhileStatement {
+    pub body: Vec<StatementId>,
+    pub condition: ExpressionId,
+    pub span: Span,
+}
+
+impl Parser {
+    pub fn parse_do_while_statement(&mut self) -> Result<Vec<Statement>, Vec<Error>> {
+        let start = self.current;
+        if !self.match_token(&Token::Keyword(KeywordToken::Do)) {
+            return Err(vec![self.error(ErrorKind::UnexpectedToken, "Expected 'do'")]);
+        }
+
+        let body = self.parse_iteration_body()?;
+        let body = self.ast.alloc_statements(body);
+
+        self.skip_newlines();
+        if !self.match_token(&Token::Keyword(KeywordToken::While)) {
+            return Err(vec![self.error(
+                ErrorKind::UnexpectedToken,
+                "Expected 'while' after do-while body",
+            )]);
+        }
+
+        let condition = self.parenthesis_expression()?;
+        let condition = self.ast.alloc_expression(condition);
+
+        // A semicolon is always inserted after a do-while, even on the same line
+        self.match_token(&Token::Delimiter(DelimiterToken::Semicolon));
+
+        Ok(vec![Statement::DoWhileStatement(DoWhileStatement {
+            body,
+            condition,
+            span: self.span_from(start),
+        })])
+    }
+}
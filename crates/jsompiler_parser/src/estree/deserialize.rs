@@ -0,0 +1,1158 @@
+use super::SourceText;
+use crate::assignment_statement::AssignmentStatement;
+use crate::binding_pattern::{BindingElement, BindingPattern, BindingProperty, BindingRestElement};
+use crate::block_statement::BlockStatement;
+use crate::class_expression::{ClassDeclaration, ClassElement, ClassElementName, ClassExpression};
+use crate::do_while_statement::DoWhileStatement;
+use crate::export_statement::ExportStatement;
+use crate::expression::Expression;
+use crate::for_in_of_statement::{ForInOfLeft, ForInStatement, ForOfStatement};
+use crate::for_loop_statement::ForLoopStatement;
+use crate::function_expression::{FunctionDeclaration, FunctionExpression, Parameter};
+use crate::if_statement::IfStatement;
+use crate::jsx_expression::{
+    JSXAttribute, JSXAttributeValue, JSXChild, JSXClosingElement, JSXElement, JSXElementName,
+    JSXExpressionContainer, JSXFragment, JSXOpeningElement,
+};
+use crate::labeled_statement::LabeledStatement;
+use crate::let_statement::LetStatement;
+use crate::object_expression::{Property, PropertyKey};
+use crate::return_statement::ReturnStatement;
+use crate::switch_statement::{SwitchCase, SwitchStatement};
+use crate::template_literal::{TemplateLiteral, TemplateMiddleList, TemplateSpan};
+use crate::throw_statement::ThrowStatement;
+use crate::try_statement::{CatchClause, TryStatement};
+use crate::while_statement::WhileStatement;
+use crate::with_statement::WithStatement;
+use crate::yield_statement::YieldStatement;
+use crate::{Ast, ExpressionId, Identifier, Node, Span, Statement, StatementId};
+use jsompiler_common::{Error, ErrorKind};
+use jsompiler_lexer::symbol::{
+    DelimiterToken, KeywordToken, Lexeme, LiteralToken, NumberLiteral, Token, SYMBOLS,
+};
+use serde_json::Value;
+
+type Result<T> = std::result::Result<T, Error>;
+
+pub(super) struct ESTreeDeserializer {
+    text: SourceText,
+    ast: Ast,
+}
+
+// The `type` of an ESTree node
+fn kind(node: &Value) -> &str {
+    node["type"].as_str().unwrap_or_default()
+}
+
+impl ESTreeDeserializer {
+    pub(super) fn new(source: &str) -> Self {
+        Self {
+            text: SourceText::new(source),
+            ast: Ast::new(),
+        }
+    }
+
+    // Errors point at the node through its `loc` when it has one
+    fn error(&self, node: &Value, message: String) -> Error {
+        Error {
+            error_kind: ErrorKind::SyntaxError,
+            message,
+            line_number: node["loc"]["start"]["line"].as_u64().unwrap_or(1) as usize,
+            pos: node["loc"]["start"]["column"].as_u64().unwrap_or(0) as usize,
+        }
+    }
+
+    fn unsupported(&self, node: &Value) -> Error {
+        match kind(node) {
+            "" => self.error(node, "Expected an ESTree node".to_string()),
+            kind => self.error(node, format!("Unsupported ESTree node `{kind}`")),
+        }
+    }
+
+    fn span(&self, node: &Value) -> Result<Span> {
+        let index = |field: &str| {
+            node[field]
+                .as_u64()
+                .and_then(|offset| self.text.index(offset as usize))
+                .ok_or_else(|| {
+                    self.error(
+                        node,
+                        format!("Invalid `{field}` offset in `{}`", kind(node)),
+                    )
+                })
+        };
+        Ok(Span::new(index("start")?, index("end")?))
+    }
+
+    fn string(&self, node: &Value, field: &str) -> Result<String> {
+        node[field]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| self.error(node, format!("Expected `{field}` in `{}`", kind(node))))
+    }
+
+    fn array<'a>(&self, node: &'a Value, field: &str) -> Result<&'a Vec<Value>> {
+        node[field]
+            .as_array()
+            .ok_or_else(|| self.error(node, format!("Expected `{field}` in `{}`", kind(node))))
+    }
+
+    pub(super) fn program(mut self, program: &Value) -> Result<Ast> {
+        if kind(program) != "Program" {
+            return Err(self.error(program, "Expected a `Program` node".to_string()));
+        }
+        for statement in self.array(program, "body")? {
+            let statements = self.statement(statement)?;
+            self.ast.body.push(Node::Statement(statements));
+        }
+        Ok(self.ast)
+    }
+
+    fn statement_list(&mut self, statements: &[Value]) -> Result<Vec<StatementId>> {
+        let mut list = Vec::new();
+        for statement in statements {
+            list.extend(self.statement(statement)?);
+        }
+        Ok(list)
+    }
+
+    fn optional_statement(&mut self, node: &Value) -> Result<Option<Vec<StatementId>>> {
+        match node {
+            Value::Null => Ok(None),
+            node => Ok(Some(self.statement(node)?)),
+        }
+    }
+
+    // A statement becomes several of ours only for declarations with many declarators
+    fn statement(&mut self, node: &Value) -> Result<Vec<StatementId>> {
+        let statements = self.build_statement(node)?;
+        Ok(self.ast.alloc_statements(statements))
+    }
+
+    fn build_statement(&mut self, node: &Value) -> Result<Vec<Statement>> {
+        let span = self.span(node)?;
+        let statement = match kind(node) {
+            "VariableDeclaration" => return self.variable_declaration(node),
+            "ExpressionStatement" => {
+                let expression = &node["expression"];
+                match kind(expression) {
+                    "AssignmentExpression" if expression["operator"] == "=" => {
+                        Statement::AssignmentStatement(AssignmentStatement {
+                            target: self.expression(&expression["left"])?,
+                            value: self.expression(&expression["right"])?,
+                            span,
+                        })
+                    }
+                    "YieldExpression" if expression["delegate"] != true => {
+                        Statement::YieldStatement(YieldStatement {
+                            expression: self
+                                .optional_expression(&expression["argument"])?
+                                .into_iter()
+                                .collect(),
+                            span,
+                        })
+                    }
+                    _ => Statement::ExpressionStatement {
+                        expression: self.expression(expression)?,
+                        span,
+                    },
+                }
+            }
+            "ReturnStatement" => Statement::ReturnStatement(ReturnStatement {
+                token: Token::Keyword(KeywordToken::Return),
+                value: self.optional_expression(&node["argument"])?,
+                span,
+            }),
+            "BlockStatement" => Statement::BlockStatement(self.block(node)?),
+            "IfStatement" => Statement::IfStatement(IfStatement {
+                condition: self.expression(&node["test"])?,
+                consequence: self.statement(&node["consequent"])?,
+                alternative: self.optional_statement(&node["alternate"])?,
+                span,
+            }),
+            "WhileStatement" => Statement::WhileStatement(WhileStatement {
+                condition: self.expression(&node["test"])?,
+                consequence: self.statement(&node["body"])?,
+                span,
+            }),
+            "ForStatement" => Statement::ForLoopStatement(ForLoopStatement {
+                initializer: self.for_header_part(&node["init"])?,
+                condition: self.for_header_part(&node["test"])?,
+                increment: self.for_header_part(&node["update"])?,
+                body: self.statement(&node["body"])?,
+                span,
+            }),
+            "FunctionDeclaration" => Statement::FunctionDeclaration(FunctionDeclaration {
+                name: self.optional_identifier(&node["id"])?,
+                parameters: self.parameters(&node["params"])?,
+                body: self.function_body(&node["body"])?,
+                is_async: node["async"] == true,
+                is_generator: node["generator"] == true,
+                span,
+            }),
+            "ClassDeclaration" => {
+                let class = self.class(node)?;
+                Statement::ClassDeclaration(ClassDeclaration {
+                    identifier: class.identifier,
+                    heritage: class.heritage,
+                    body: class.body,
+                    span: class.span,
+                    body_span: class.body_span,
+                })
+            }
+            "ExportNamedDeclaration" if !node["declaration"].is_null() => {
+                Statement::ExportStatement(ExportStatement::Declaration {
+                    declarations: self.statement(&node["declaration"])?,
+                    span,
+                })
+            }
+            "ExportDefaultDeclaration" => {
+                let declaration = &node["declaration"];
+                match kind(declaration) {
+                    "FunctionDeclaration" | "ClassDeclaration" => {
+                        let statements = self.statement(declaration)?;
+                        Statement::ExportStatement(ExportStatement::DefaultDeclaration {
+                            declaration: statements[0],
+                            span,
+                        })
+                    }
+                    _ => Statement::ExportStatement(ExportStatement::DefaultExpression {
+                        expression: self.expression(declaration)?,
+                        span,
+                    }),
+                }
+            }
+            "LabeledStatement" => Statement::LabeledStatement(LabeledStatement {
+                label: self.identifier(&node["label"])?,
+                body: self.statement(&node["body"])?,
+                span,
+            }),
+            "DoWhileStatement" => Statement::DoWhileStatement(DoWhileStatement {
+                body: self.statement(&node["body"])?,
+                condition: self.expression(&node["test"])?,
+                span,
+            }),
+            "ForInStatement" => Statement::ForInStatement(ForInStatement {
+                left: self.for_in_of_left(&node["left"])?,
+                right: self.expression(&node["right"])?,
+                body: self.statement(&node["body"])?,
+                span,
+            }),
+            "ForOfStatement" => Statement::ForOfStatement(ForOfStatement {
+                left: self.for_in_of_left(&node["left"])?,
+                right: self.expression(&node["right"])?,
+                body: self.statement(&node["body"])?,
+                is_await: node["await"] == true,
+                span,
+            }),
+            "SwitchStatement" => Statement::SwitchStatement(SwitchStatement {
+                discriminant: self.expression(&node["discriminant"])?,
+                cases: self
+                    .array(node, "cases")?
+                    .iter()
+                    .map(|case| {
+                        Ok(SwitchCase {
+                            test: self.optional_expression(&case["test"])?,
+                            consequent: self.statement_list(self.array(case, "consequent")?)?,
+                            span: self.span(case)?,
+                        })
+                    })
+                    .collect::<Result<_>>()?,
+                span,
+            }),
+            "ThrowStatement" => Statement::ThrowStatement(ThrowStatement {
+                argument: self.expression(&node["argument"])?,
+                span,
+            }),
+            "TryStatement" => {
+                let handler = &node["handler"];
+                Statement::TryStatement(TryStatement {
+                    block: self.statement(&node["block"])?,
+                    handler: match handler {
+                        Value::Null => None,
+                        handler => Some(CatchClause {
+                            param: match &handler["param"] {
+                                Value::Null => None,
+                                param => Some(self.binding_pattern(param)?),
+                            },
+                            body: self.statement(&handler["body"])?,
+                            span: self.span(handler)?,
+                        }),
+                    },
+                    finalizer: self.optional_statement(&node["finalizer"])?,
+                    span,
+                })
+            }
+            "WithStatement" => Statement::WithStatement(WithStatement {
+                object: self.expression(&node["object"])?,
+                body: self.statement(&node["body"])?,
+                span,
+            }),
+            "EmptyStatement" => Statement::EmptyStatement { span },
+            "DebuggerStatement" => Statement::DebuggerStatement { span },
+            "BreakStatement" => Statement::BreakStatement {
+                label: self.optional_identifier(&node["label"])?,
+                span,
+            },
+            "ContinueStatement" => Statement::ContinueStatement {
+                label: self.optional_identifier(&node["label"])?,
+                span,
+            },
+            // Written by `to_estree` for statements that failed to parse
+            "ErrorStatement" => Statement::Error { span },
+            _ => return Err(self.unsupported(node)),
+        };
+        Ok(vec![statement])
+    }
+
+    fn block(&mut self, node: &Value) -> Result<BlockStatement> {
+        if kind(node) != "BlockStatement" {
+            return Err(self.unsupported(node));
+        }
+        Ok(BlockStatement {
+            token: Token::Delimiter(DelimiterToken::OpenBrace),
+            statements: self.statement_list(self.array(node, "body")?)?,
+            span: self.span(node)?,
+        })
+    }
+
+    fn block_statement(&mut self, node: &Value) -> Result<StatementId> {
+        let block = self.block(node)?;
+        Ok(self.ast.alloc_statement(Statement::BlockStatement(block)))
+    }
+
+    fn function_body(&mut self, node: &Value) -> Result<Vec<StatementId>> {
+        Ok(vec![self.block_statement(node)?])
+    }
+
+    fn declaration_token(&self, node: &Value) -> Result<Token> {
+        let kind = self.string(node, "kind")?;
+        match SYMBOLS.get(kind.as_str()) {
+            Some(keyword) if matches!(kind.as_str(), "var" | "let" | "const") => {
+                Ok(keyword.token.clone())
+            }
+            _ => Err(self.error(node, format!("Unsupported declaration kind `{kind}`"))),
+        }
+    }
+
+    // Every declarator is its own statement, sharing the span of the declaration
+    fn variable_declaration(&mut self, node: &Value) -> Result<Vec<Statement>> {
+        let token = self.declaration_token(node)?;
+        let span = self.span(node)?;
+        self.array(node, "declarations")?
+            .iter()
+            .map(|declarator| {
+                Ok(Statement::LetStatement(LetStatement {
+                    token: token.clone(),
+                    name: self.binding_pattern(&declarator["id"])?,
+                    value: self.optional_expression(&declarator["init"])?,
+                    span,
+                    declarator_span: self.span(declarator)?,
+                }))
+            })
+            .collect()
+    }
+
+    fn for_header_part(&mut self, node: &Value) -> Result<Vec<StatementId>> {
+        let span = match node {
+            Value::Null => return Ok(vec![]),
+            node => self.span(node)?,
+        };
+        let statements = match kind(node) {
+            "VariableDeclaration" => self.variable_declaration(node)?,
+            "AssignmentExpression" if node["operator"] == "=" => {
+                vec![Statement::AssignmentStatement(AssignmentStatement {
+                    target: self.expression(&node["left"])?,
+                    value: self.expression(&node["right"])?,
+                    span,
+                })]
+            }
+            _ => vec![Statement::ExpressionStatement {
+                expression: self.expression(node)?,
+                span,
+            }],
+        };
+        Ok(self.ast.alloc_statements(statements))
+    }
+
+    fn for_in_of_left(&mut self, node: &Value) -> Result<ForInOfLeft> {
+        if kind(node) != "VariableDeclaration" {
+            return Ok(ForInOfLeft::Target(self.expression(node)?));
+        }
+        let declarations = self.array(node, "declarations")?;
+        let [declarator] = declarations.as_slice() else {
+            return Err(self.error(node, "Expected a single declarator".to_string()));
+        };
+        Ok(ForInOfLeft::Declaration {
+            token: self.declaration_token(node)?,
+            name: self.binding_pattern(&declarator["id"])?,
+            span: self.span(node)?,
+        })
+    }
+
+    fn identifier(&mut self, node: &Value) -> Result<Identifier> {
+        if kind(node) != "Identifier" {
+            return Err(self.unsupported(node));
+        }
+        let name = self.string(node, "name")?;
+        Ok(Identifier::new(
+            self.ast.atoms.intern(&name),
+            self.span(node)?,
+        ))
+    }
+
+    fn optional_identifier(&mut self, node: &Value) -> Result<Option<Identifier>> {
+        match node {
+            Value::Null => Ok(None),
+            node => Ok(Some(self.identifier(node)?)),
+        }
+    }
+
+    // We keep the `#` that ESTree leaves out of private names
+    fn private_identifier(&mut self, node: &Value) -> Result<Identifier> {
+        let name = format!("#{}", self.string(node, "name")?);
+        Ok(Identifier::new(
+            self.ast.atoms.intern(&name),
+            self.span(node)?,
+        ))
+    }
+
+    fn literal_token(&self, node: &Value) -> Result<LiteralToken> {
+        if let Some(bigint) = node["bigint"].as_str() {
+            return bigint
+                .parse()
+                .map(|value| LiteralToken::Number(NumberLiteral::BigInt(value)))
+                .map_err(|_| self.error(node, format!("Invalid BigInt `{bigint}`")));
+        }
+        match &node["value"] {
+            Value::String(value) => Ok(LiteralToken::String(value.clone())),
+            Value::Bool(value) => Ok(LiteralToken::Boolean(*value)),
+            Value::Number(value) => Ok(LiteralToken::Number(NumberLiteral::Value(
+                value.as_f64().unwrap_or_default(),
+            ))),
+            // Numbers too large for JSON, like 1e400, are written as null
+            Value::Null if node["raw"] != "null" && node["raw"].is_string() => {
+                Ok(LiteralToken::Number(NumberLiteral::Infinity))
+            }
+            Value::Null => Ok(LiteralToken::Null),
+            _ => Err(self.unsupported(node)),
+        }
+    }
+
+    fn optional_expression(&mut self, node: &Value) -> Result<Option<ExpressionId>> {
+        match node {
+            Value::Null => Ok(None),
+            node => Ok(Some(self.expression(node)?)),
+        }
+    }
+
+    fn expressions(&mut self, node: &Value, field: &str) -> Result<Vec<ExpressionId>> {
+        self.array(node, field)?
+            .iter()
+            .map(|expression| self.expression(expression))
+            .collect()
+    }
+
+    // The operator of a unary or binary expression, found in the source between its operands
+    fn operator(&self, node: &Value, start: usize, end: usize) -> Result<Lexeme> {
+        let text = self.string(node, "operator")?;
+        let Some(symbol) = SYMBOLS.get(text.as_str()) else {
+            return Err(self.error(node, format!("Unsupported operator `{text}`")));
+        };
+        let mut op = symbol.clone();
+        let position = self.text.find(&text, start, end).unwrap_or(start);
+        op.span = Span::new(position, position + text.chars().count());
+        Ok(op)
+    }
+
+    fn expression(&mut self, node: &Value) -> Result<ExpressionId> {
+        let expression = self.build_expression(node)?;
+        Ok(self.ast.alloc_expression(expression))
+    }
+
+    fn build_expression(&mut self, node: &Value) -> Result<Expression> {
+        let span = self.span(node)?;
+        Ok(match kind(node) {
+            "Identifier" => match node["name"].as_str() {
+                // The lexer reads these globals as literals
+                Some("undefined") => Expression::Literal {
+                    value: LiteralToken::Undefined,
+                    span,
+                },
+                Some("NaN") => Expression::Literal {
+                    value: LiteralToken::Number(NumberLiteral::NaN),
+                    span,
+                },
+                Some("Infinity") => Expression::Literal {
+                    value: LiteralToken::Number(NumberLiteral::Infinity),
+                    span,
+                },
+                _ => Expression::Identifier(self.identifier(node)?),
+            },
+            "PrivateIdentifier" => Expression::PrivateIdentifier(self.private_identifier(node)?),
+            "Literal" if node["regex"].is_object() => Expression::RegularExpressionLiteral {
+                pattern: self.string(&node["regex"], "pattern")?,
+                flags: self.string(&node["regex"], "flags")?,
+                span,
+            },
+            "Literal" => Expression::Literal {
+                value: self.literal_token(node)?,
+                span,
+            },
+            "TemplateLiteral" => {
+                Expression::TemplateLiteral(Box::new(self.template_literal(node)?))
+            }
+            "ThisExpression" => Expression::ThisExpression { span },
+            "SpreadElement" => Expression::SpreadElement {
+                argument: self.expression(&node["argument"])?,
+                span,
+            },
+            "MemberExpression" if node["optional"] != true => {
+                let property = &node["property"];
+                Expression::MemberAccess {
+                    object: self.expression(&node["object"])?,
+                    property: match kind(property) {
+                        "PrivateIdentifier" => {
+                            let name = self.private_identifier(property)?;
+                            self.ast.alloc_expression(Expression::Identifier(name))
+                        }
+                        _ => self.expression(property)?,
+                    },
+                    computed: node["computed"] == true,
+                    span,
+                }
+            }
+            "UnaryExpression" | "UpdateExpression" => {
+                let argument = self.expression(&node["argument"])?;
+                let argument_span = self.ast[argument].span();
+                let is_prefix = node["prefix"] == true;
+                let op = if is_prefix {
+                    self.operator(node, span.start, argument_span.start)?
+                } else {
+                    self.operator(node, argument_span.end, span.end)?
+                };
+                Expression::Unary {
+                    op,
+                    op_type: if is_prefix { "Prefix" } else { "Postfix" }.to_string(),
+                    expr: argument,
+                    span,
+                }
+            }
+            "BinaryExpression" => {
+                let left = self.expression(&node["left"])?;
+                let right = self.expression(&node["right"])?;
+                Expression::Binary {
+                    op: self.operator(
+                        node,
+                        self.ast[left].span().end,
+                        self.ast[right].span().start,
+                    )?,
+                    left,
+                    right,
+                    span,
+                }
+            }
+            "CallExpression" if node["optional"] != true => Expression::FunctionCall {
+                callee: self.expression(&node["callee"])?,
+                args: self.expressions(node, "arguments")?,
+                span,
+            },
+            "ArrayExpression" => {
+                let mut elements = Vec::new();
+                for element in self.array(node, "elements")? {
+                    elements.push(match element {
+                        // Holes are empty undefined literals where the element would start
+                        Value::Null => {
+                            let position = elements
+                                .last()
+                                .map_or(span.start + 1, |element: &ExpressionId| {
+                                    self.ast[*element].span().end
+                                });
+                            self.ast.alloc_expression(Expression::Literal {
+                                value: LiteralToken::Undefined,
+                                span: Span::new(position, position),
+                            })
+                        }
+                        element => self.expression(element)?,
+                    });
+                }
+                Expression::ArrayLiteral { elements, span }
+            }
+            "ObjectExpression" => Expression::ObjectLiteral {
+                properties: self
+                    .array(node, "properties")?
+                    .iter()
+                    .map(|property| self.property(property))
+                    .collect::<Result<_>>()?,
+                span,
+            },
+            "AwaitExpression" => Expression::AwaitExpression {
+                argument: self.expression(&node["argument"])?,
+                span,
+            },
+            "ClassExpression" => {
+                let class = self.class(node)?;
+                Expression::ClassExpression(ClassExpression {
+                    identifier: class.identifier,
+                    heritage: class.heritage,
+                    body: class.body,
+                    span: class.span,
+                    body_span: class.body_span,
+                })
+            }
+            "FunctionExpression" => {
+                let function = FunctionExpression {
+                    name: self.optional_identifier(&node["id"])?,
+                    parameters: self.parameters(&node["params"])?,
+                    body: self.function_body(&node["body"])?,
+                    span,
+                };
+                match (node["async"] == true, node["generator"] == true) {
+                    (false, false) => Expression::FunctionExpression(function),
+                    (false, true) => Expression::GeneratorExpression(function),
+                    (true, false) => Expression::AsyncFunctionExpression(function),
+                    (true, true) => Expression::AsyncGeneratorExpression(function),
+                }
+            }
+            "ArrowFunctionExpression" if node["async"] != true => {
+                let body = &node["body"];
+                Expression::ArrowFunctionExpression {
+                    parameters: self.parameters(&node["params"])?,
+                    // A concise body is kept as a single expression statement
+                    body: match kind(body) {
+                        "BlockStatement" => self.function_body(body)?,
+                        _ => {
+                            let statement = Statement::ExpressionStatement {
+                                expression: self.expression(body)?,
+                                span: self.span(body)?,
+                            };
+                            vec![self.ast.alloc_statement(statement)]
+                        }
+                    },
+                    span,
+                }
+            }
+            "JSXElement" => Expression::JSXElement(Box::new(self.jsx_element(node)?)),
+            "JSXFragment" => Expression::JSXFragment(self.jsx_fragment(node)?),
+            _ => return Err(self.unsupported(node)),
+        })
+    }
+
+    fn template_literal(&mut self, node: &Value) -> Result<TemplateLiteral> {
+        let span = self.span(node)?;
+        let mut quasis = self
+            .array(node, "quasis")?
+            .iter()
+            .map(|quasi| {
+                let cooked = match quasi["value"]["cooked"].as_str() {
+                    Some(cooked) => cooked.to_string(),
+                    None => self.string(&quasi["value"], "raw")?,
+                };
+                let span = self.span(quasi)?;
+                Ok(self.ast.alloc_expression(Expression::Literal {
+                    value: LiteralToken::String(cooked),
+                    span,
+                }))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let mut expressions = self.expressions(node, "expressions")?;
+
+        if quasis.len() != expressions.len() + 1 {
+            return Err(self.error(
+                node,
+                "Expected one more quasi than expressions in `TemplateLiteral`".to_string(),
+            ));
+        }
+        if expressions.is_empty() {
+            return Ok(TemplateLiteral::NonSubstitutionTemplate {
+                text: quasis.remove(0),
+                span,
+            });
+        }
+
+        // Pair every middle quasi with the expression after it, from the back
+        let template_tail = quasis.pop().unwrap();
+        let mut template_middle_list = None;
+        while expressions.len() > 1 {
+            template_middle_list = Some(TemplateMiddleList {
+                expression: expressions.pop().unwrap(),
+                template_middle: quasis.pop().unwrap(),
+                template_middle_list: template_middle_list.map(Box::new),
+            });
+        }
+
+        Ok(TemplateLiteral::SubstitutionTemplate {
+            template_head: quasis.remove(0),
+            expression: expressions.remove(0),
+            template_spans: TemplateSpan {
+                template_tail,
+                template_middle_list,
+            },
+            span,
+        })
+    }
+
+    fn parameters(&mut self, node: &Value) -> Result<Vec<Parameter>> {
+        let Some(parameters) = node.as_array() else {
+            return Err(self.error(node, "Expected function parameters".to_string()));
+        };
+        parameters
+            .iter()
+            .map(|parameter| self.parameter(parameter))
+            .collect()
+    }
+
+    fn parameter(&mut self, node: &Value) -> Result<Parameter> {
+        Ok(match kind(node) {
+            "Identifier" => Parameter::Identifier(self.identifier(node)?),
+            "AssignmentPattern" => Parameter::Default {
+                target: self.binding_pattern(&node["left"])?,
+                default: self.expression(&node["right"])?,
+                span: self.span(node)?,
+            },
+            "RestElement" => Parameter::RestParameter {
+                argument: self.binding_pattern(&node["argument"])?,
+                span: self.span(node)?,
+            },
+            _ => Parameter::Pattern(self.binding_pattern(node)?),
+        })
+    }
+
+    fn binding_pattern(&mut self, node: &Value) -> Result<BindingPattern> {
+        let span = self.span(node)?;
+        Ok(match kind(node) {
+            "Identifier" => BindingPattern::Identifier(self.identifier(node)?),
+            "ObjectPattern" => {
+                let mut properties = Vec::new();
+                let mut rest = None;
+                for property in self.array(node, "properties")? {
+                    match kind(property) {
+                        "RestElement" => rest = Some(self.rest_element(property)?),
+                        _ => properties.push(self.binding_property(property)?),
+                    }
+                }
+                BindingPattern::ObjectPattern {
+                    properties,
+                    rest,
+                    span,
+                }
+            }
+            "ArrayPattern" => {
+                let mut elements = Vec::new();
+                let mut rest = None;
+                for element in self.array(node, "elements")? {
+                    match element {
+                        Value::Null => elements.push(None),
+                        element if kind(element) == "RestElement" => {
+                            rest = Some(self.rest_element(element)?)
+                        }
+                        element => elements.push(Some(self.binding_element(element)?)),
+                    }
+                }
+                BindingPattern::ArrayPattern {
+                    elements,
+                    rest,
+                    span,
+                }
+            }
+            _ => return Err(self.unsupported(node)),
+        })
+    }
+
+    fn binding_element(&mut self, node: &Value) -> Result<BindingElement> {
+        let span = self.span(node)?;
+        match kind(node) {
+            "AssignmentPattern" => Ok(BindingElement {
+                target: self.binding_pattern(&node["left"])?,
+                default: Some(self.expression(&node["right"])?),
+                span,
+            }),
+            _ => Ok(BindingElement {
+                target: self.binding_pattern(node)?,
+                default: None,
+                span,
+            }),
+        }
+    }
+
+    fn binding_property(&mut self, node: &Value) -> Result<BindingProperty> {
+        if kind(node) != "Property" {
+            return Err(self.unsupported(node));
+        }
+        let span = self.span(node)?;
+        let value = &node["value"];
+        if node["shorthand"] == true {
+            return Ok(BindingProperty::Shorthand {
+                name: self.identifier(&node["key"])?,
+                default: match kind(value) {
+                    "AssignmentPattern" => Some(self.expression(&value["right"])?),
+                    _ => None,
+                },
+                span,
+            });
+        }
+        Ok(BindingProperty::KeyValue {
+            key: self.property_key(node)?,
+            value: self.binding_element(value)?,
+            span,
+        })
+    }
+
+    fn rest_element(&mut self, node: &Value) -> Result<BindingRestElement> {
+        Ok(BindingRestElement {
+            argument: Box::new(self.binding_pattern(&node["argument"])?),
+            span: self.span(node)?,
+        })
+    }
+
+    // The key of a property or of a property pattern
+    fn property_key(&mut self, property: &Value) -> Result<PropertyKey> {
+        let key = &property["key"];
+        if property["computed"] == true {
+            return Ok(PropertyKey::Computed(self.expression(key)?));
+        }
+        let span = self.span(key)?;
+        match kind(key) {
+            "Identifier" => Ok(PropertyKey::Identifier(self.identifier(key)?)),
+            "Literal" => match self.literal_token(key)? {
+                LiteralToken::String(value) => Ok(PropertyKey::StringLiteral { value, span }),
+                LiteralToken::Number(value) => Ok(PropertyKey::NumberLiteral { value, span }),
+                _ => Err(self.unsupported(key)),
+            },
+            _ => Err(self.unsupported(key)),
+        }
+    }
+
+    fn property(&mut self, node: &Value) -> Result<Property> {
+        if kind(node) == "SpreadElement" {
+            return Ok(Property::Spread(self.expression(node)?));
+        }
+        if kind(node) != "Property" {
+            return Err(self.unsupported(node));
+        }
+
+        let span = self.span(node)?;
+        let value = &node["value"];
+        if node["shorthand"] == true {
+            return Ok(Property::Shorthand(self.identifier(&node["key"])?));
+        }
+
+        let key = self.property_key(node)?;
+        Ok(match (node["kind"].as_str(), node["method"] == true) {
+            (Some("get"), _) => Property::Getter {
+                key,
+                body: self.block_statement(&value["body"])?,
+                span,
+                function_span: self.span(value)?,
+            },
+            (Some("set"), _) => Property::Setter {
+                key,
+                param: self.single_parameter(value)?,
+                body: self.block_statement(&value["body"])?,
+                span,
+                function_span: self.span(value)?,
+            },
+            (_, true) if value["async"] != true && value["generator"] != true => Property::Method {
+                key,
+                params: self.parameters(&value["params"])?,
+                body: self.function_body(&value["body"])?,
+                span,
+                function_span: self.span(value)?,
+            },
+            (Some("init"), false) => Property::KeyValue {
+                key,
+                value: self.expression(value)?,
+                span,
+            },
+            _ => return Err(self.unsupported(value)),
+        })
+    }
+
+    // The parameter of a setter
+    fn single_parameter(&mut self, function: &Value) -> Result<Parameter> {
+        match self.parameters(&function["params"])?.pop() {
+            Some(parameter) => Ok(parameter),
+            None => Err(self.error(function, "Expected a setter parameter".to_string())),
+        }
+    }
+
+    fn class(&mut self, node: &Value) -> Result<ClassExpression> {
+        let body = &node["body"];
+        Ok(ClassExpression {
+            identifier: self.optional_identifier(&node["id"])?,
+            heritage: self.optional_expression(&node["superClass"])?,
+            body: self
+                .array(body, "body")?
+                .iter()
+                .map(|element| self.class_element(element))
+                .collect::<Result<_>>()?,
+            span: self.span(node)?,
+            body_span: self.span(body)?,
+        })
+    }
+
+    fn class_element(&mut self, node: &Value) -> Result<ClassElement> {
+        let span = self.span(node)?;
+        let is_static = node["static"] == true;
+        match kind(node) {
+            "MethodDefinition" => {}
+            "PropertyDefinition" => {
+                return Ok(ClassElement::FieldDefinition {
+                    name: self.class_element_name(node)?,
+                    value: self.optional_expression(&node["value"])?,
+                    is_static,
+                    span,
+                })
+            }
+            "AccessorProperty" => {
+                return Ok(ClassElement::AccessorDefinition {
+                    name: self.class_element_name(node)?,
+                    value: self.optional_expression(&node["value"])?,
+                    is_static,
+                    span,
+                })
+            }
+            "StaticBlock" => {
+                // The block itself starts at the `{` after `static`
+                let start = self
+                    .text
+                    .find("{", span.start, span.end)
+                    .unwrap_or(span.start);
+                let block = Statement::BlockStatement(BlockStatement {
+                    token: Token::Delimiter(DelimiterToken::OpenBrace),
+                    statements: self.statement_list(self.array(node, "body")?)?,
+                    span: Span::new(start, span.end),
+                });
+                return Ok(ClassElement::StaticBlock {
+                    body: vec![self.ast.alloc_statement(block)],
+                    span,
+                });
+            }
+            _ => return Err(self.unsupported(node)),
+        }
+
+        let name = self.class_element_name(node)?;
+        let value = &node["value"];
+        let function_span = self.span(value)?;
+        let body = self.function_body(&value["body"])?;
+        Ok(match node["kind"].as_str() {
+            Some("constructor") => ClassElement::Constructor {
+                name,
+                params: self.parameters(&value["params"])?,
+                body,
+                span,
+                function_span,
+            },
+            Some("get") => ClassElement::GetterDefinition {
+                name,
+                body,
+                is_static,
+                span,
+                function_span,
+            },
+            Some("set") => ClassElement::SetterDefinition {
+                name,
+                param: self.single_parameter(value)?,
+                body,
+                is_static,
+                span,
+                function_span,
+            },
+            Some("method") => {
+                let params = self.parameters(&value["params"])?;
+                match (value["async"] == true, value["generator"] == true) {
+                    (false, false) => ClassElement::MethodDefinition {
+                        name,
+                        params,
+                        body,
+                        is_static,
+                        span,
+                        function_span,
+                    },
+                    (true, false) => ClassElement::AsyncMethodDefinition {
+                        name,
+                        params,
+                        body,
+                        is_static,
+                        span,
+                        function_span,
+                    },
+                    (false, true) => ClassElement::GeneratorMethodDefinition {
+                        name,
+                        params,
+                        body,
+                        is_static,
+                        span,
+                        function_span,
+                    },
+                    (true, true) => ClassElement::AsyncGeneratorMethodDefinition {
+                        name,
+                        params,
+                        body,
+                        is_static,
+                        span,
+                        function_span,
+                    },
+                }
+            }
+            _ => return Err(self.unsupported(node)),
+        })
+    }
+
+    fn class_element_name(&mut self, element: &Value) -> Result<ClassElementName> {
+        let key = &element["key"];
+        if element["computed"] == true {
+            return Ok(ClassElementName::Computed(self.expression(key)?));
+        }
+        match kind(key) {
+            "Identifier" => Ok(ClassElementName::PropertyName(self.identifier(key)?)),
+            "PrivateIdentifier" => Ok(ClassElementName::PrivateIdentifier(
+                self.private_identifier(key)?,
+            )),
+            "Literal" => {
+                let span = self.span(key)?;
+                match self.literal_token(key)? {
+                    LiteralToken::String(value) => {
+                        Ok(ClassElementName::StringLiteral { value, span })
+                    }
+                    LiteralToken::Number(value) => {
+                        Ok(ClassElementName::NumberLiteral { value, span })
+                    }
+                    _ => Err(self.unsupported(key)),
+                }
+            }
+            _ => Err(self.unsupported(key)),
+        }
+    }
+
+    fn jsx_element(&mut self, node: &Value) -> Result<JSXElement> {
+        let opening = &node["openingElement"];
+        let closing = &node["closingElement"];
+        Ok(JSXElement {
+            opening: JSXOpeningElement {
+                name: self.jsx_element_name(&opening["name"])?,
+                attributes: self
+                    .array(opening, "attributes")?
+                    .iter()
+                    .map(|attribute| self.jsx_attribute(attribute))
+                    .collect::<Result<_>>()?,
+                self_closing: opening["selfClosing"] == true,
+                span: self.span(opening)?,
+            },
+            children: self.jsx_children(node)?,
+            closing: match closing {
+                Value::Null => None,
+                closing => Some(JSXClosingElement {
+                    name: self.jsx_element_name(&closing["name"])?,
+                    span: self.span(closing)?,
+                }),
+            },
+            span: self.span(node)?,
+        })
+    }
+
+    fn jsx_fragment(&mut self, node: &Value) -> Result<JSXFragment> {
+        Ok(JSXFragment {
+            children: self.jsx_children(node)?,
+            span: self.span(node)?,
+            opening_span: self.span(&node["openingFragment"])?,
+            closing_span: self.span(&node["closingFragment"])?,
+        })
+    }
+
+    fn jsx_identifier(&mut self, node: &Value) -> Result<Identifier> {
+        if kind(node) != "JSXIdentifier" {
+            return Err(self.unsupported(node));
+        }
+        let name = self.string(node, "name")?;
+        Ok(Identifier::new(
+            self.ast.atoms.intern(&name),
+            self.span(node)?,
+        ))
+    }
+
+    fn jsx_element_name(&mut self, node: &Value) -> Result<JSXElementName> {
+        match kind(node) {
+            "JSXIdentifier" => Ok(JSXElementName::Identifier(self.jsx_identifier(node)?)),
+            "JSXNamespacedName" => Ok(JSXElementName::Namespaced {
+                namespace: self.jsx_identifier(&node["namespace"])?,
+                name: self.jsx_identifier(&node["name"])?,
+            }),
+            "JSXMemberExpression" => Ok(JSXElementName::Member {
+                object: Box::new(self.jsx_element_name(&node["object"])?),
+                property: self.jsx_identifier(&node["property"])?,
+            }),
+            _ => Err(self.unsupported(node)),
+        }
+    }
+
+    fn jsx_attribute(&mut self, node: &Value) -> Result<JSXAttribute> {
+        let span = self.span(node)?;
+        match kind(node) {
+            "JSXAttribute" => {
+                let value = &node["value"];
+                Ok(JSXAttribute::Attribute {
+                    name: self.jsx_element_name(&node["name"])?,
+                    value: match kind(value) {
+                        _ if value.is_null() => None,
+                        "Literal" => Some(JSXAttributeValue::StringLiteral {
+                            value: self.string(value, "value")?,
+                            span: self.span(value)?,
+                        }),
+                        "JSXExpressionContainer" => Some(JSXAttributeValue::ExpressionContainer(
+                            self.jsx_expression_container(value)?,
+                        )),
+                        "JSXElement" => Some(JSXAttributeValue::Element(Box::new(
+                            self.jsx_element(value)?,
+                        ))),
+                        "JSXFragment" => Some(JSXAttributeValue::Fragment(Box::new(
+                            self.jsx_fragment(value)?,
+                        ))),
+                        _ => return Err(self.unsupported(value)),
+                    },
+                    span,
+                })
+            }
+            "JSXSpreadAttribute" => Ok(JSXAttribute::SpreadAttribute {
+                argument: self.expression(&node["argument"])?,
+                span,
+            }),
+            _ => Err(self.unsupported(node)),
+        }
+    }
+
+    fn jsx_expression_container(&mut self, node: &Value) -> Result<JSXExpressionContainer> {
+        let span = self.span(node)?;
+        match kind(&node["expression"]) {
+            "JSXEmptyExpression" => Ok(JSXExpressionContainer::Empty { span }),
+            _ => Ok(JSXExpressionContainer::Expression {
+                expression: self.expression(&node["expression"])?,
+                span,
+            }),
+        }
+    }
+
+    fn jsx_children(&mut self, node: &Value) -> Result<Vec<JSXChild>> {
+        self.array(node, "children")?
+            .iter()
+            .map(|child| {
+                Ok(match kind(child) {
+                    "JSXText" => JSXChild::Text {
+                        value: self.string(child, "value")?,
+                        span: self.span(child)?,
+                    },
+                    "JSXElement" => JSXChild::Element(Box::new(self.jsx_element(child)?)),
+                    "JSXFragment" => JSXChild::Fragment(Box::new(self.jsx_fragment(child)?)),
+                    "JSXExpressionContainer" => {
+                        JSXChild::ExpressionContainer(self.jsx_expression_container(child)?)
+                    }
+                    "JSXSpreadChild" => JSXChild::Spread {
+                        expression: self.expression(&child["expression"])?,
+                        span: self.span(child)?,
+                    },
+                    _ => return Err(self.unsupported(child)),
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,107 @@
+mod deserialize;
+mod serialize;
+
+use super::Ast;
+use deserialize::ESTreeDeserializer;
+use jsompiler_common::{Error, ErrorKind};
+use jsompiler_lexer::symbol::Span;
+use serde_json::{json, Value};
+use serialize::ESTreeSerializer;
+
+// Serialize a parsed program to ESTree JSON, in the shape acorn and espree produce with
+// `locations` and `ranges` enabled. Offsets and columns count UTF-16 code units like
+// JavaScript strings do, so the output can be diffed against their fixtures directly.
+pub fn to_estree(ast: &Ast, source: &str) -> Value {
+    ESTreeSerializer::new(source, ast).program()
+}
+
+pub fn to_estree_string(ast: &Ast, source: &str) -> String {
+    serde_json::to_string_pretty(&to_estree(ast, source)).unwrap_or_default()
+}
+
+// Build our AST back from an ESTree `Program` of the given source. Nodes we have no
+// representation for, like `NewExpression`, are reported as errors.
+pub fn from_estree(program: &Value, source: &str) -> Result<Ast, Error> {
+    ESTreeDeserializer::new(source).program(program)
+}
+
+pub fn from_estree_str(json: &str, source: &str) -> Result<Ast, Error> {
+    let program = serde_json::from_str(json).map_err(|error| Error {
+        error_kind: ErrorKind::SyntaxError,
+        message: format!("Invalid ESTree JSON: {error}"),
+        line_number: error.line(),
+        pos: error.column(),
+    })?;
+    from_estree(&program, source)
+}
+
+// Converts between our character offsets and the UTF-16 offsets, lines and columns of ESTree
+struct SourceText {
+    source: Vec<char>,
+    offsets: Vec<usize>,     // UTF-16 offset of every character, plus the end
+    line_starts: Vec<usize>, // Character index where each line starts
+}
+
+impl SourceText {
+    fn new(source: &str) -> Self {
+        let source: Vec<char> = source.chars().collect();
+
+        let mut offsets = Vec::with_capacity(source.len() + 1);
+        let mut offset = 0;
+        for c in &source {
+            offsets.push(offset);
+            offset += c.len_utf16();
+        }
+        offsets.push(offset);
+
+        let mut line_starts = vec![0];
+        for (index, c) in source.iter().enumerate() {
+            let is_line_break = match c {
+                '\n' | '\u{2028}' | '\u{2029}' => true,
+                '\r' => source.get(index + 1) != Some(&'\n'),
+                _ => false,
+            };
+            if is_line_break {
+                line_starts.push(index + 1);
+            }
+        }
+
+        Self {
+            source,
+            offsets,
+            line_starts,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.source.len()
+    }
+
+    fn offset(&self, index: usize) -> usize {
+        self.offsets[index.min(self.source.len())]
+    }
+
+    // The character at a UTF-16 offset, `None` in the middle of a surrogate pair
+    fn index(&self, offset: usize) -> Option<usize> {
+        self.offsets.binary_search(&offset).ok()
+    }
+
+    fn position(&self, index: usize) -> Value {
+        let line = self.line_starts.partition_point(|&start| start <= index);
+        let line_start = self.line_starts[line - 1];
+        json!({ "line": line, "column": self.offset(index) - self.offset(line_start) })
+    }
+
+    fn raw(&self, span: Span) -> String {
+        let end = span.end.min(self.source.len());
+        self.source[span.start.min(end)..end].iter().collect()
+    }
+
+    // Where `text` first occurs in `start..end`
+    fn find(&self, text: &str, start: usize, end: usize) -> Option<usize> {
+        let text: Vec<char> = text.chars().collect();
+        let end = end.min(self.source.len());
+        (start..end.saturating_sub(text.len()) + 1)
+            .find(|&index| self.source[index..].starts_with(&text))
+    }
+}
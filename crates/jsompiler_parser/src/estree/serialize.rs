@@ -0,0 +1,1390 @@
+use super::SourceText;
+use crate::binding_pattern::{BindingElement, BindingPattern, BindingProperty, BindingRestElement};
+use crate::class_expression::{ClassElement, ClassElementName};
+use crate::export_statement::ExportStatement;
+use crate::expression::Expression;
+use crate::for_in_of_statement::ForInOfLeft;
+use crate::function_expression::Parameter;
+use crate::jsx_expression::{
+    JSXAttribute, JSXAttributeValue, JSXChild, JSXElement, JSXElementName, JSXExpressionContainer,
+    JSXFragment,
+};
+use crate::let_statement::LetStatement;
+use crate::object_expression::{Property, PropertyKey};
+use crate::template_literal::TemplateLiteral;
+use crate::{Ast, ExpressionId, Identifier, Node, Span, Statement, StatementId};
+use jsompiler_lexer::symbol::{KeywordToken, LiteralToken, NumberLiteral, Token};
+use serde_json::{json, Map, Value};
+
+pub(super) struct ESTreeSerializer<'a> {
+    text: SourceText,
+    ast: &'a Ast,
+}
+
+impl<'a> ESTreeSerializer<'a> {
+    pub(super) fn new(source: &str, ast: &'a Ast) -> Self {
+        Self {
+            text: SourceText::new(source),
+            ast,
+        }
+    }
+
+    // A node with its type, position fields and the given properties
+    fn node(&self, kind: &str, span: Span, properties: Value) -> Value {
+        let mut node = Map::new();
+        node.insert("type".to_string(), json!(kind));
+        node.insert("start".to_string(), json!(self.text.offset(span.start)));
+        node.insert("end".to_string(), json!(self.text.offset(span.end)));
+        node.insert(
+            "loc".to_string(),
+            json!({ "start": self.text.position(span.start), "end": self.text.position(span.end) }),
+        );
+        node.insert(
+            "range".to_string(),
+            json!([self.text.offset(span.start), self.text.offset(span.end)]),
+        );
+        if let Value::Object(properties) = properties {
+            node.extend(properties);
+        }
+        Value::Object(node)
+    }
+
+    pub(super) fn program(&self) -> Value {
+        let mut body = Vec::new();
+        let mut statements = Vec::new();
+        for node in &self.ast.body {
+            match node {
+                Node::Statement(list) => statements.extend_from_slice(list),
+                Node::Expression(expression) => {
+                    body.extend(self.statement_list(&statements, body.is_empty()));
+                    statements.clear();
+                    let span = self.ast[*expression].span();
+                    body.push(self.node(
+                        "ExpressionStatement",
+                        span,
+                        json!({ "expression": self.expression(*expression) }),
+                    ));
+                }
+            }
+        }
+        body.extend(self.statement_list(&statements, body.is_empty()));
+
+        let is_module = self.ast.body.iter().any(|node| match node {
+            Node::Statement(list) => list
+                .iter()
+                .any(|id| matches!(self.ast[*id], Statement::ExportStatement(_))),
+            Node::Expression(_) => false,
+        });
+
+        self.node(
+            "Program",
+            Span::new(0, self.text.len()),
+            json!({
+                "body": body,
+                "sourceType": if is_module { "module" } else { "script" },
+            }),
+        )
+    }
+
+    // Serialize a statement list. Declarators of one `let a, b` are separate statements
+    // in our tree but share the span of their declaration, so they are grouped back into
+    // one `VariableDeclaration`. Leading string statements become directives.
+    fn statement_list(&self, statements: &[StatementId], has_directives: bool) -> Vec<Value> {
+        let mut body = Vec::new();
+        let mut declarators: Vec<&LetStatement> = Vec::new();
+        let mut in_prologue = has_directives;
+
+        for &id in statements {
+            let statement = &self.ast[id];
+            if let Statement::LetStatement(declarator) = statement {
+                if declarators
+                    .last()
+                    .is_some_and(|last| last.span != declarator.span)
+                {
+                    body.push(self.variable_declaration(&declarators));
+                    declarators.clear();
+                }
+                declarators.push(declarator);
+                in_prologue = false;
+                continue;
+            }
+            if !declarators.is_empty() {
+                body.push(self.variable_declaration(&declarators));
+                declarators.clear();
+            }
+
+            let mut value = self.statement(id);
+            if in_prologue {
+                match statement {
+                    Statement::ExpressionStatement { expression, .. } => {
+                        match &self.ast[*expression] {
+                            Expression::Literal {
+                                value: LiteralToken::String(_),
+                                span,
+                            } => {
+                                let raw = self.text.raw(*span);
+                                value["directive"] = json!(raw[1..raw.len() - 1]);
+                            }
+                            _ => in_prologue = false,
+                        }
+                    }
+                    _ => in_prologue = false,
+                }
+            }
+            body.push(value);
+        }
+
+        if !declarators.is_empty() {
+            body.push(self.variable_declaration(&declarators));
+        }
+        body
+    }
+
+    // The single statement in the body of `if`, loops, labels and `with`
+    fn sub_statement(&self, statements: &[StatementId]) -> Value {
+        self.statement_list(statements, false)
+            .into_iter()
+            .next()
+            .unwrap_or(Value::Null)
+    }
+
+    fn variable_declaration(&self, declarators: &[&LetStatement]) -> Value {
+        let kind = match declarators[0].token {
+            Token::Keyword(KeywordToken::Var) => "var",
+            Token::Keyword(KeywordToken::Const) => "const",
+            _ => "let",
+        };
+        let declarations: Vec<Value> = declarators
+            .iter()
+            .map(|declarator| {
+                self.node(
+                    "VariableDeclarator",
+                    declarator.declarator_span,
+                    json!({
+                        "id": self.binding_pattern(&declarator.name),
+                        "init": self.optional_expression(declarator.value),
+                    }),
+                )
+            })
+            .collect();
+
+        self.node(
+            "VariableDeclaration",
+            declarators[0].span,
+            json!({ "declarations": declarations, "kind": kind }),
+        )
+    }
+
+    fn statement(&self, id: StatementId) -> Value {
+        match &self.ast[id] {
+            Statement::LetStatement(declarator) => self.variable_declaration(&[declarator]),
+            Statement::ReturnStatement(statement) => self.node(
+                "ReturnStatement",
+                statement.span,
+                json!({ "argument": self.optional_expression(statement.value) }),
+            ),
+            Statement::ExpressionStatement { expression, span } => self.node(
+                "ExpressionStatement",
+                *span,
+                json!({ "expression": self.expression(*expression) }),
+            ),
+            Statement::BlockStatement(block) => self.node(
+                "BlockStatement",
+                block.span,
+                json!({ "body": self.statement_list(&block.statements, false) }),
+            ),
+            Statement::IfStatement(statement) => self.node(
+                "IfStatement",
+                statement.span,
+                json!({
+                    "test": self.expression(statement.condition),
+                    "consequent": self.sub_statement(&statement.consequence),
+                    "alternate": match &statement.alternative {
+                        Some(alternative) => self.sub_statement(alternative),
+                        None => Value::Null,
+                    },
+                }),
+            ),
+            Statement::WhileStatement(statement) => self.node(
+                "WhileStatement",
+                statement.span,
+                json!({
+                    "test": self.expression(statement.condition),
+                    "body": self.sub_statement(&statement.consequence),
+                }),
+            ),
+            Statement::AssignmentStatement(statement) => self.node(
+                "ExpressionStatement",
+                statement.span,
+                json!({ "expression": self.assignment_expression(statement.target, statement.value) }),
+            ),
+            Statement::ForLoopStatement(statement) => self.node(
+                "ForStatement",
+                statement.span,
+                json!({
+                    "init": self.for_header_part(&statement.initializer),
+                    "test": self.for_header_part(&statement.condition),
+                    "update": self.for_header_part(&statement.increment),
+                    "body": self.sub_statement(&statement.body),
+                }),
+            ),
+            Statement::YieldStatement(statement) => {
+                let end = match statement.expression.first() {
+                    Some(&argument) => self.ast[argument].span().end,
+                    None => statement.span.start + "yield".len(),
+                };
+                self.node(
+                    "ExpressionStatement",
+                    statement.span,
+                    json!({
+                        "expression": self.node(
+                            "YieldExpression",
+                            Span::new(statement.span.start, end),
+                            json!({
+                                "delegate": false,
+                                "argument": self.optional_expression(statement.expression.first().copied()),
+                            }),
+                        ),
+                    }),
+                )
+            }
+            Statement::FunctionDeclaration(function) => self.function(
+                "FunctionDeclaration",
+                function.span,
+                function.name.as_ref(),
+                &function.parameters,
+                &function.body,
+                function.is_async,
+                function.is_generator,
+            ),
+            Statement::ClassDeclaration(class) => self.class(
+                "ClassDeclaration",
+                class.span,
+                class.identifier.as_ref(),
+                class.heritage,
+                &class.body,
+                class.body_span,
+            ),
+            Statement::ExportStatement(ExportStatement::Declaration {
+                declarations,
+                span,
+            }) => self.node(
+                "ExportNamedDeclaration",
+                *span,
+                json!({
+                    "declaration": self.sub_statement(declarations),
+                    "specifiers": [],
+                    "source": null,
+                }),
+            ),
+            Statement::ExportStatement(ExportStatement::DefaultDeclaration {
+                declaration,
+                span,
+            }) => self.node(
+                "ExportDefaultDeclaration",
+                *span,
+                json!({ "declaration": self.statement(*declaration) }),
+            ),
+            Statement::ExportStatement(ExportStatement::DefaultExpression { expression, span }) => {
+                self.node(
+                    "ExportDefaultDeclaration",
+                    *span,
+                    json!({ "declaration": self.expression(*expression) }),
+                )
+            }
+            Statement::LabeledStatement(statement) => self.node(
+                "LabeledStatement",
+                statement.span,
+                json!({
+                    "body": self.sub_statement(&statement.body),
+                    "label": self.identifier(&statement.label),
+                }),
+            ),
+            Statement::DoWhileStatement(statement) => self.node(
+                "DoWhileStatement",
+                statement.span,
+                json!({
+                    "body": self.sub_statement(&statement.body),
+                    "test": self.expression(statement.condition),
+                }),
+            ),
+            Statement::ForInStatement(statement) => self.node(
+                "ForInStatement",
+                statement.span,
+                json!({
+                    "left": self.for_in_of_left(&statement.left),
+                    "right": self.expression(statement.right),
+                    "body": self.sub_statement(&statement.body),
+                }),
+            ),
+            Statement::ForOfStatement(statement) => self.node(
+                "ForOfStatement",
+                statement.span,
+                json!({
+                    "await": statement.is_await,
+                    "left": self.for_in_of_left(&statement.left),
+                    "right": self.expression(statement.right),
+                    "body": self.sub_statement(&statement.body),
+                }),
+            ),
+            Statement::SwitchStatement(statement) => self.node(
+                "SwitchStatement",
+                statement.span,
+                json!({
+                    "discriminant": self.expression(statement.discriminant),
+                    "cases": statement.cases.iter().map(|case| self.node(
+                        "SwitchCase",
+                        case.span,
+                        json!({
+                            "consequent": self.statement_list(&case.consequent, false),
+                            "test": self.optional_expression(case.test),
+                        }),
+                    )).collect::<Vec<_>>(),
+                }),
+            ),
+            Statement::ThrowStatement(statement) => self.node(
+                "ThrowStatement",
+                statement.span,
+                json!({ "argument": self.expression(statement.argument) }),
+            ),
+            Statement::TryStatement(statement) => self.node(
+                "TryStatement",
+                statement.span,
+                json!({
+                    "block": self.sub_statement(&statement.block),
+                    "handler": match &statement.handler {
+                        Some(handler) => self.node(
+                            "CatchClause",
+                            handler.span,
+                            json!({
+                                "param": match &handler.param {
+                                    Some(param) => self.binding_pattern(param),
+                                    None => Value::Null,
+                                },
+                                "body": self.sub_statement(&handler.body),
+                            }),
+                        ),
+                        None => Value::Null,
+                    },
+                    "finalizer": match &statement.finalizer {
+                        Some(finalizer) => self.sub_statement(finalizer),
+                        None => Value::Null,
+                    },
+                }),
+            ),
+            Statement::WithStatement(statement) => self.node(
+                "WithStatement",
+                statement.span,
+                json!({
+                    "object": self.expression(statement.object),
+                    "body": self.sub_statement(&statement.body),
+                }),
+            ),
+            Statement::EmptyStatement { span } => self.node("EmptyStatement", *span, json!({})),
+            Statement::DebuggerStatement { span } => {
+                self.node("DebuggerStatement", *span, json!({}))
+            }
+            Statement::BreakStatement { label, span } => self.node(
+                "BreakStatement",
+                *span,
+                json!({ "label": label.as_ref().map(|label| self.identifier(label)) }),
+            ),
+            Statement::ContinueStatement { label, span } => self.node(
+                "ContinueStatement",
+                *span,
+                json!({ "label": label.as_ref().map(|label| self.identifier(label)) }),
+            ),
+            // Not part of ESTree; marks where a statement failed to parse
+            Statement::Error { span } => self.node("ErrorStatement", *span, json!({})),
+        }
+    }
+
+    // `init`, `test` and `update` of a `for` header hold expressions, except for a
+    // declaration in `init`
+    fn for_header_part(&self, statements: &[StatementId]) -> Value {
+        match statements.first().map(|id| &self.ast[*id]) {
+            None => Value::Null,
+            Some(Statement::ExpressionStatement { expression, .. }) => self.expression(*expression),
+            Some(Statement::AssignmentStatement(statement)) => {
+                self.assignment_expression(statement.target, statement.value)
+            }
+            Some(_) => self.sub_statement(statements),
+        }
+    }
+
+    fn for_in_of_left(&self, left: &ForInOfLeft) -> Value {
+        match left {
+            ForInOfLeft::Declaration { token, name, span } => self.node(
+                "VariableDeclaration",
+                *span,
+                json!({
+                    "declarations": [self.node(
+                        "VariableDeclarator",
+                        name.span(),
+                        json!({ "id": self.binding_pattern(name), "init": null }),
+                    )],
+                    "kind": match token {
+                        Token::Keyword(KeywordToken::Var) => "var",
+                        Token::Keyword(KeywordToken::Const) => "const",
+                        _ => "let",
+                    },
+                }),
+            ),
+            ForInOfLeft::Target(target) => self.expression(*target),
+        }
+    }
+
+    fn assignment_expression(&self, target: ExpressionId, value: ExpressionId) -> Value {
+        self.node(
+            "AssignmentExpression",
+            self.ast[target].span().to(self.ast[value].span()),
+            json!({
+                "operator": "=",
+                "left": self.expression(target),
+                "right": self.expression(value),
+            }),
+        )
+    }
+
+    fn optional_expression(&self, expression: Option<ExpressionId>) -> Value {
+        match expression {
+            Some(expression) => self.expression(expression),
+            None => Value::Null,
+        }
+    }
+
+    fn identifier(&self, identifier: &Identifier) -> Value {
+        self.node(
+            "Identifier",
+            identifier.span(),
+            json!({ "name": &self.ast[identifier.value] }),
+        )
+    }
+
+    // Private names are stored with their `#`, which ESTree leaves out
+    fn private_identifier(&self, identifier: &Identifier) -> Value {
+        self.node(
+            "PrivateIdentifier",
+            identifier.span(),
+            json!({ "name": self.ast[identifier.value].trim_start_matches('#') }),
+        )
+    }
+
+    fn literal(&self, value: &LiteralToken, span: Span) -> Value {
+        let value = match value {
+            LiteralToken::Number(NumberLiteral::Value(number)) => number_value(*number),
+            LiteralToken::Number(NumberLiteral::BigInt(number)) => {
+                return self.node(
+                    "Literal",
+                    span,
+                    json!({
+                        "value": null,
+                        "raw": self.text.raw(span),
+                        "bigint": number.to_string(),
+                    }),
+                );
+            }
+            LiteralToken::Number(NumberLiteral::NaN | NumberLiteral::Infinity) => Value::Null,
+            LiteralToken::String(value) => json!(value),
+            LiteralToken::Boolean(value) => json!(value),
+            LiteralToken::Null | LiteralToken::Undefined => Value::Null,
+        };
+        self.node(
+            "Literal",
+            span,
+            json!({ "value": value, "raw": self.text.raw(span) }),
+        )
+    }
+
+    fn expression(&self, id: ExpressionId) -> Value {
+        let expression = &self.ast[id];
+        match expression {
+            Expression::Identifier(identifier) => self.identifier(identifier),
+            Expression::PrivateIdentifier(identifier) => self.private_identifier(identifier),
+            // The lexer treats these globals as literals, ESTree keeps them identifiers
+            Expression::Literal {
+                value:
+                    LiteralToken::Undefined
+                    | LiteralToken::Number(NumberLiteral::NaN | NumberLiteral::Infinity),
+                span,
+            } => self.node("Identifier", *span, json!({ "name": self.text.raw(*span) })),
+            Expression::Literal { value, span } => self.literal(value, *span),
+            Expression::TemplateLiteral(template) => self.template_literal(template),
+            Expression::ThisExpression { span } => self.node("ThisExpression", *span, json!({})),
+            Expression::SpreadElement { argument, span } => self.node(
+                "SpreadElement",
+                *span,
+                json!({ "argument": self.expression(*argument) }),
+            ),
+            Expression::MemberAccess {
+                object,
+                property,
+                computed,
+                span,
+            } => self.node(
+                "MemberExpression",
+                *span,
+                json!({
+                    "object": self.expression(*object),
+                    "property": match &self.ast[*property] {
+                        Expression::Identifier(name) if self.ast[name.value].starts_with('#') => {
+                            self.private_identifier(name)
+                        }
+                        _ => self.expression(*property),
+                    },
+                    "computed": computed,
+                    "optional": false,
+                }),
+            ),
+            Expression::Unary {
+                op,
+                op_type,
+                expr,
+                span,
+            } => {
+                let is_update = op.text == "++" || op.text == "--";
+                self.node(
+                    if is_update {
+                        "UpdateExpression"
+                    } else {
+                        "UnaryExpression"
+                    },
+                    *span,
+                    json!({
+                        "operator": op.text,
+                        "prefix": op_type == "Prefix",
+                        "argument": self.expression(*expr),
+                    }),
+                )
+            }
+            Expression::Binary {
+                left,
+                op,
+                right,
+                span,
+            } => self.node(
+                "BinaryExpression",
+                *span,
+                json!({
+                    "left": self.expression(*left),
+                    "operator": op.text,
+                    "right": self.expression(*right),
+                }),
+            ),
+            Expression::FunctionCall { callee, args, span } => self.node(
+                "CallExpression",
+                *span,
+                json!({
+                    "callee": self.expression(*callee),
+                    "arguments": args.iter().map(|arg| self.expression(*arg)).collect::<Vec<_>>(),
+                    "optional": false,
+                }),
+            ),
+            Expression::ArrayLiteral { elements, span } => self.node(
+                "ArrayExpression",
+                *span,
+                json!({
+                    "elements": elements.iter().map(|&element| match &self.ast[element] {
+                        Expression::Literal { value: LiteralToken::Undefined, span }
+                            if span.start == span.end => Value::Null,
+                        _ => self.expression(element),
+                    }).collect::<Vec<_>>(),
+                }),
+            ),
+            Expression::ObjectLiteral { properties, span } => self.node(
+                "ObjectExpression",
+                *span,
+                json!({
+                    "properties": properties
+                        .iter()
+                        .map(|property| self.property(property))
+                        .collect::<Vec<_>>(),
+                }),
+            ),
+            Expression::AwaitExpression { argument, span } => self.node(
+                "AwaitExpression",
+                *span,
+                json!({ "argument": self.expression(*argument) }),
+            ),
+            Expression::ClassExpression(class) => self.class(
+                "ClassExpression",
+                class.span,
+                class.identifier.as_ref(),
+                class.heritage,
+                &class.body,
+                class.body_span,
+            ),
+            Expression::FunctionExpression(function)
+            | Expression::GeneratorExpression(function)
+            | Expression::AsyncFunctionExpression(function)
+            | Expression::AsyncGeneratorExpression(function) => self.function(
+                "FunctionExpression",
+                function.span,
+                function.name.as_ref(),
+                &function.parameters,
+                &function.body,
+                matches!(
+                    expression,
+                    Expression::AsyncFunctionExpression(_)
+                        | Expression::AsyncGeneratorExpression(_)
+                ),
+                matches!(
+                    expression,
+                    Expression::GeneratorExpression(_) | Expression::AsyncGeneratorExpression(_)
+                ),
+            ),
+            Expression::RegularExpressionLiteral {
+                pattern,
+                flags,
+                span,
+            } => self.node(
+                "Literal",
+                *span,
+                json!({
+                    "value": {},
+                    "raw": self.text.raw(*span),
+                    "regex": { "pattern": pattern, "flags": flags },
+                }),
+            ),
+            Expression::ArrowFunctionExpression {
+                parameters,
+                body,
+                span,
+            } => {
+                // A concise body is kept as a single expression statement
+                let (body, is_expression) = match body.first().map(|&id| &self.ast[id]) {
+                    Some(Statement::ExpressionStatement { expression, .. }) => {
+                        (self.expression(*expression), true)
+                    }
+                    _ => (self.sub_statement(body), false),
+                };
+                self.node(
+                    "ArrowFunctionExpression",
+                    *span,
+                    json!({
+                        "id": null,
+                        "expression": is_expression,
+                        "generator": false,
+                        "async": false,
+                        "params": self.parameters(parameters),
+                        "body": body,
+                    }),
+                )
+            }
+            Expression::JSXElement(element) => self.jsx_element(element),
+            Expression::JSXFragment(fragment) => self.jsx_fragment(fragment),
+        }
+    }
+
+    fn template_literal(&self, template: &TemplateLiteral) -> Value {
+        // Quasis are the string literals between substitutions
+        let mut quasis = Vec::new();
+        let mut expressions = Vec::new();
+        match template {
+            TemplateLiteral::NonSubstitutionTemplate { text, .. } => quasis.push(*text),
+            TemplateLiteral::SubstitutionTemplate {
+                template_head,
+                expression,
+                template_spans,
+                ..
+            } => {
+                quasis.push(*template_head);
+                expressions.push(*expression);
+                let mut middle = template_spans.template_middle_list.as_ref();
+                while let Some(list) = middle {
+                    quasis.push(list.template_middle);
+                    expressions.push(list.expression);
+                    middle = list.template_middle_list.as_deref();
+                }
+                quasis.push(template_spans.template_tail);
+            }
+        }
+
+        let count = quasis.len();
+        let quasis: Vec<Value> = quasis
+            .into_iter()
+            .enumerate()
+            .map(|(index, quasi)| {
+                let quasi = &self.ast[quasi];
+                let cooked = match quasi {
+                    Expression::Literal {
+                        value: LiteralToken::String(value),
+                        ..
+                    } => json!(value),
+                    _ => Value::Null,
+                };
+                self.node(
+                    "TemplateElement",
+                    quasi.span(),
+                    json!({
+                        "value": { "raw": self.text.raw(quasi.span()), "cooked": cooked },
+                        "tail": index + 1 == count,
+                    }),
+                )
+            })
+            .collect();
+
+        self.node(
+            "TemplateLiteral",
+            template.span(),
+            json!({
+                "expressions": expressions
+                    .into_iter()
+                    .map(|expression| self.expression(expression))
+                    .collect::<Vec<_>>(),
+                "quasis": quasis,
+            }),
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn function(
+        &self,
+        kind: &str,
+        span: Span,
+        name: Option<&Identifier>,
+        parameters: &[Parameter],
+        body: &[StatementId],
+        is_async: bool,
+        is_generator: bool,
+    ) -> Value {
+        self.node(
+            kind,
+            span,
+            json!({
+                "id": name.map(|name| self.identifier(name)),
+                "expression": false,
+                "generator": is_generator,
+                "async": is_async,
+                "params": self.parameters(parameters),
+                "body": self.function_body(body),
+            }),
+        )
+    }
+
+    // Function bodies are a single block statement whose prologue may hold directives
+    fn function_body(&self, body: &[StatementId]) -> Value {
+        match body.first().map(|&id| &self.ast[id]) {
+            Some(Statement::BlockStatement(block)) => self.node(
+                "BlockStatement",
+                block.span,
+                json!({ "body": self.statement_list(&block.statements, true) }),
+            ),
+            _ => self.sub_statement(body),
+        }
+    }
+
+    fn parameters(&self, parameters: &[Parameter]) -> Vec<Value> {
+        parameters
+            .iter()
+            .map(|parameter| match parameter {
+                Parameter::Identifier(identifier) => self.identifier(identifier),
+                Parameter::Pattern(pattern) => self.binding_pattern(pattern),
+                Parameter::Default {
+                    target,
+                    default,
+                    span,
+                } => self.assignment_pattern(self.binding_pattern(target), *default, *span),
+                Parameter::RestParameter { argument, span } => self.node(
+                    "RestElement",
+                    *span,
+                    json!({ "argument": self.binding_pattern(argument) }),
+                ),
+            })
+            .collect()
+    }
+
+    fn class(
+        &self,
+        kind: &str,
+        span: Span,
+        name: Option<&Identifier>,
+        heritage: Option<ExpressionId>,
+        body: &[ClassElement],
+        body_span: Span,
+    ) -> Value {
+        self.node(
+            kind,
+            span,
+            json!({
+                "id": name.map(|name| self.identifier(name)),
+                "superClass": self.optional_expression(heritage),
+                "body": self.node(
+                    "ClassBody",
+                    body_span,
+                    json!({
+                        "body": body
+                            .iter()
+                            .map(|element| self.class_element(element))
+                            .collect::<Vec<_>>(),
+                    }),
+                ),
+            }),
+        )
+    }
+
+    fn class_element(&self, element: &ClassElement) -> Value {
+        let (name, params, body, is_static, kind, is_async, is_generator, span, function_span) =
+            match element {
+                ClassElement::Constructor {
+                    name,
+                    params,
+                    body,
+                    span,
+                    function_span,
+                } => (
+                    name,
+                    params.as_slice(),
+                    body,
+                    false,
+                    "constructor",
+                    false,
+                    false,
+                    span,
+                    function_span,
+                ),
+                ClassElement::MethodDefinition {
+                    name,
+                    params,
+                    body,
+                    is_static,
+                    span,
+                    function_span,
+                } => (
+                    name,
+                    params.as_slice(),
+                    body,
+                    *is_static,
+                    "method",
+                    false,
+                    false,
+                    span,
+                    function_span,
+                ),
+                ClassElement::AsyncMethodDefinition {
+                    name,
+                    params,
+                    body,
+                    is_static,
+                    span,
+                    function_span,
+                } => (
+                    name,
+                    params.as_slice(),
+                    body,
+                    *is_static,
+                    "method",
+                    true,
+                    false,
+                    span,
+                    function_span,
+                ),
+                ClassElement::GeneratorMethodDefinition {
+                    name,
+                    params,
+                    body,
+                    is_static,
+                    span,
+                    function_span,
+                } => (
+                    name,
+                    params.as_slice(),
+                    body,
+                    *is_static,
+                    "method",
+                    false,
+                    true,
+                    span,
+                    function_span,
+                ),
+                ClassElement::AsyncGeneratorMethodDefinition {
+                    name,
+                    params,
+                    body,
+                    is_static,
+                    span,
+                    function_span,
+                } => (
+                    name,
+                    params.as_slice(),
+                    body,
+                    *is_static,
+                    "method",
+                    true,
+                    true,
+                    span,
+                    function_span,
+                ),
+                ClassElement::GetterDefinition {
+                    name,
+                    body,
+                    is_static,
+                    span,
+                    function_span,
+                } => (
+                    name,
+                    [].as_slice(),
+                    body,
+                    *is_static,
+                    "get",
+                    false,
+                    false,
+                    span,
+                    function_span,
+                ),
+                ClassElement::SetterDefinition {
+                    name,
+                    param,
+                    body,
+                    is_static,
+                    span,
+                    function_span,
+                } => (
+                    name,
+                    std::slice::from_ref(param),
+                    body,
+                    *is_static,
+                    "set",
+                    false,
+                    false,
+                    span,
+                    function_span,
+                ),
+                ClassElement::FieldDefinition {
+                    name,
+                    value,
+                    is_static,
+                    span,
+                }
+                | ClassElement::AccessorDefinition {
+                    name,
+                    value,
+                    is_static,
+                    span,
+                } => {
+                    let kind = match element {
+                        ClassElement::AccessorDefinition { .. } => "AccessorProperty",
+                        _ => "PropertyDefinition",
+                    };
+                    return self.node(
+                        kind,
+                        *span,
+                        json!({
+                            "static": is_static,
+                            "computed": matches!(name, ClassElementName::Computed(_)),
+                            "key": self.class_element_name(name),
+                            "value": self.optional_expression(*value),
+                        }),
+                    );
+                }
+                ClassElement::StaticBlock { body, span } => {
+                    let statements = match body.first().map(|&id| &self.ast[id]) {
+                        Some(Statement::BlockStatement(block)) => {
+                            self.statement_list(&block.statements, false)
+                        }
+                        _ => vec![],
+                    };
+                    return self.node("StaticBlock", *span, json!({ "body": statements }));
+                }
+            };
+
+        self.node(
+            "MethodDefinition",
+            *span,
+            json!({
+                "static": is_static,
+                "computed": matches!(name, ClassElementName::Computed(_)),
+                "key": self.class_element_name(name),
+                "kind": kind,
+                "value": self.function(
+                    "FunctionExpression",
+                    *function_span,
+                    None,
+                    params,
+                    body,
+                    is_async,
+                    is_generator,
+                ),
+            }),
+        )
+    }
+
+    fn class_element_name(&self, name: &ClassElementName) -> Value {
+        match name {
+            ClassElementName::PropertyName(name) => self.identifier(name),
+            ClassElementName::StringLiteral { value, span } => {
+                self.literal(&LiteralToken::String(value.clone()), *span)
+            }
+            ClassElementName::NumberLiteral { value, span } => {
+                self.literal(&LiteralToken::Number(value.clone()), *span)
+            }
+            ClassElementName::PrivateIdentifier(name) => self.private_identifier(name),
+            ClassElementName::Computed(expression) => self.expression(*expression),
+        }
+    }
+
+    fn property_key(&self, key: &PropertyKey) -> Value {
+        match key {
+            PropertyKey::Identifier(identifier) => self.identifier(identifier),
+            PropertyKey::StringLiteral { value, span } => {
+                self.literal(&LiteralToken::String(value.clone()), *span)
+            }
+            PropertyKey::NumberLiteral { value, span } => {
+                self.literal(&LiteralToken::Number(value.clone()), *span)
+            }
+            PropertyKey::Computed(expression) => self.expression(*expression),
+        }
+    }
+
+    fn property(&self, property: &Property) -> Value {
+        let object_property = |key: &PropertyKey, value: Value, kind: &str, method: bool, span| {
+            self.node(
+                "Property",
+                span,
+                json!({
+                    "method": method,
+                    "shorthand": false,
+                    "computed": matches!(key, PropertyKey::Computed(_)),
+                    "key": self.property_key(key),
+                    "value": value,
+                    "kind": kind,
+                }),
+            )
+        };
+        let accessor = |params: &[Parameter], body: &StatementId, function_span| {
+            self.function(
+                "FunctionExpression",
+                function_span,
+                None,
+                params,
+                std::slice::from_ref(body),
+                false,
+                false,
+            )
+        };
+
+        match property {
+            Property::KeyValue { key, value, span } => {
+                object_property(key, self.expression(*value), "init", false, *span)
+            }
+            Property::Shorthand(identifier) => self.node(
+                "Property",
+                identifier.span(),
+                json!({
+                    "method": false,
+                    "shorthand": true,
+                    "computed": false,
+                    "key": self.identifier(identifier),
+                    "value": self.identifier(identifier),
+                    "kind": "init",
+                }),
+            ),
+            Property::Method {
+                key,
+                params,
+                body,
+                span,
+                function_span,
+            } => object_property(
+                key,
+                self.function(
+                    "FunctionExpression",
+                    *function_span,
+                    None,
+                    params,
+                    body,
+                    false,
+                    false,
+                ),
+                "init",
+                true,
+                *span,
+            ),
+            Property::Getter {
+                key,
+                body,
+                span,
+                function_span,
+            } => object_property(
+                key,
+                accessor(&[], body, *function_span),
+                "get",
+                false,
+                *span,
+            ),
+            Property::Setter {
+                key,
+                param,
+                body,
+                span,
+                function_span,
+            } => object_property(
+                key,
+                accessor(std::slice::from_ref(param), body, *function_span),
+                "set",
+                false,
+                *span,
+            ),
+            Property::Spread(expression) => self.expression(*expression),
+        }
+    }
+
+    fn binding_pattern(&self, pattern: &BindingPattern) -> Value {
+        match pattern {
+            BindingPattern::Identifier(identifier) => self.identifier(identifier),
+            BindingPattern::ObjectPattern {
+                properties,
+                rest,
+                span,
+            } => {
+                let mut values: Vec<Value> = properties
+                    .iter()
+                    .map(|property| self.binding_property(property))
+                    .collect();
+                values.extend(rest.iter().map(|rest| self.rest_element(rest)));
+                self.node("ObjectPattern", *span, json!({ "properties": values }))
+            }
+            BindingPattern::ArrayPattern {
+                elements,
+                rest,
+                span,
+            } => {
+                let mut values: Vec<Value> = elements
+                    .iter()
+                    .map(|element| match element {
+                        Some(element) => self.binding_element(element),
+                        None => Value::Null,
+                    })
+                    .collect();
+                values.extend(rest.iter().map(|rest| self.rest_element(rest)));
+                self.node("ArrayPattern", *span, json!({ "elements": values }))
+            }
+        }
+    }
+
+    fn binding_element(&self, element: &BindingElement) -> Value {
+        match &element.default {
+            Some(default) => self.assignment_pattern(
+                self.binding_pattern(&element.target),
+                *default,
+                element.span,
+            ),
+            None => self.binding_pattern(&element.target),
+        }
+    }
+
+    fn binding_property(&self, property: &BindingProperty) -> Value {
+        let (key, value, shorthand, span) = match property {
+            BindingProperty::KeyValue { key, value, span } => (
+                self.property_key(key),
+                self.binding_element(value),
+                false,
+                span,
+            ),
+            BindingProperty::Shorthand {
+                name,
+                default,
+                span,
+            } => {
+                let value = match default {
+                    Some(default) => {
+                        self.assignment_pattern(self.identifier(name), *default, *span)
+                    }
+                    None => self.identifier(name),
+                };
+                (self.identifier(name), value, true, span)
+            }
+        };
+        let computed = matches!(
+            property,
+            BindingProperty::KeyValue {
+                key: PropertyKey::Computed(_),
+                ..
+            }
+        );
+
+        self.node(
+            "Property",
+            *span,
+            json!({
+                "method": false,
+                "shorthand": shorthand,
+                "computed": computed,
+                "key": key,
+                "value": value,
+                "kind": "init",
+            }),
+        )
+    }
+
+    fn rest_element(&self, rest: &BindingRestElement) -> Value {
+        self.node(
+            "RestElement",
+            rest.span,
+            json!({ "argument": self.binding_pattern(&rest.argument) }),
+        )
+    }
+
+    fn assignment_pattern(&self, left: Value, right: ExpressionId, span: Span) -> Value {
+        self.node(
+            "AssignmentPattern",
+            span,
+            json!({ "left": left, "right": self.expression(right) }),
+        )
+    }
+
+    fn jsx_element(&self, element: &JSXElement) -> Value {
+        let opening = &element.opening;
+        self.node(
+            "JSXElement",
+            element.span,
+            json!({
+                "openingElement": self.node(
+                    "JSXOpeningElement",
+                    opening.span,
+                    json!({
+                        "name": self.jsx_element_name(&opening.name),
+                        "attributes": opening
+                            .attributes
+                            .iter()
+                            .map(|attribute| self.jsx_attribute(attribute))
+                            .collect::<Vec<_>>(),
+                        "selfClosing": opening.self_closing,
+                    }),
+                ),
+                "closingElement": element.closing.as_ref().map(|closing| self.node(
+                    "JSXClosingElement",
+                    closing.span,
+                    json!({ "name": self.jsx_element_name(&closing.name) }),
+                )),
+                "children": self.jsx_children(&element.children),
+            }),
+        )
+    }
+
+    fn jsx_fragment(&self, fragment: &JSXFragment) -> Value {
+        self.node(
+            "JSXFragment",
+            fragment.span,
+            json!({
+                "openingFragment": self.node("JSXOpeningFragment", fragment.opening_span, json!({})),
+                "closingFragment": self.node("JSXClosingFragment", fragment.closing_span, json!({})),
+                "children": self.jsx_children(&fragment.children),
+            }),
+        )
+    }
+
+    fn jsx_identifier(&self, identifier: &Identifier) -> Value {
+        self.node(
+            "JSXIdentifier",
+            identifier.span(),
+            json!({ "name": &self.ast[identifier.value] }),
+        )
+    }
+
+    fn jsx_element_name(&self, name: &JSXElementName) -> Value {
+        match name {
+            JSXElementName::Identifier(identifier) => self.jsx_identifier(identifier),
+            JSXElementName::Namespaced {
+                namespace,
+                name: local_name,
+            } => self.node(
+                "JSXNamespacedName",
+                name.span(),
+                json!({
+                    "namespace": self.jsx_identifier(namespace),
+                    "name": self.jsx_identifier(local_name),
+                }),
+            ),
+            JSXElementName::Member { object, property } => self.node(
+                "JSXMemberExpression",
+                name.span(),
+                json!({
+                    "object": self.jsx_element_name(object),
+                    "property": self.jsx_identifier(property),
+                }),
+            ),
+        }
+    }
+
+    fn jsx_attribute(&self, attribute: &JSXAttribute) -> Value {
+        match attribute {
+            JSXAttribute::Attribute { name, value, span } => self.node(
+                "JSXAttribute",
+                *span,
+                json!({
+                    "name": self.jsx_element_name(name),
+                    "value": match value {
+                        Some(JSXAttributeValue::StringLiteral { value, span }) => {
+                            self.literal(&LiteralToken::String(value.clone()), *span)
+                        }
+                        Some(JSXAttributeValue::ExpressionContainer(container)) => {
+                            self.jsx_expression_container(container)
+                        }
+                        Some(JSXAttributeValue::Element(element)) => self.jsx_element(element),
+                        Some(JSXAttributeValue::Fragment(fragment)) => self.jsx_fragment(fragment),
+                        None => Value::Null,
+                    },
+                }),
+            ),
+            JSXAttribute::SpreadAttribute { argument, span } => self.node(
+                "JSXSpreadAttribute",
+                *span,
+                json!({ "argument": self.expression(*argument) }),
+            ),
+        }
+    }
+
+    fn jsx_expression_container(&self, container: &JSXExpressionContainer) -> Value {
+        let expression = match container {
+            JSXExpressionContainer::Expression { expression, .. } => self.expression(*expression),
+            // The empty expression sits between the braces
+            JSXExpressionContainer::Empty { span } => self.node(
+                "JSXEmptyExpression",
+                Span::new(
+                    span.start + 1,
+                    span.end.saturating_sub(1).max(span.start + 1),
+                ),
+                json!({}),
+            ),
+        };
+        self.node(
+            "JSXExpressionContainer",
+            container.span(),
+            json!({ "expression": expression }),
+        )
+    }
+
+    fn jsx_children(&self, children: &[JSXChild]) -> Vec<Value> {
+        children
+            .iter()
+            .map(|child| match child {
+                JSXChild::Text { value, span } => self.node(
+                    "JSXText",
+                    *span,
+                    json!({ "value": value, "raw": self.text.raw(*span) }),
+                ),
+                JSXChild::Element(element) => self.jsx_element(element),
+                JSXChild::Fragment(fragment) => self.jsx_fragment(fragment),
+                JSXChild::ExpressionContainer(container) => {
+                    self.jsx_expression_container(container)
+                }
+                JSXChild::Spread { expression, span } => self.node(
+                    "JSXSpreadChild",
+                    *span,
+                    json!({ "expression": self.expression(*expression) }),
+                ),
+            })
+            .collect()
+    }
+}
+
+// Integral numbers are written without a fraction, `1` rather than `1.0`, like acorn and espree
+// print them. Past 2^53 an `f64` no longer holds every integer, so those stay floats.
+fn number_value(number: f64) -> Value {
+    if number.fract() == 0.0 && number.abs() < 2f64.powi(53) {
+        json!(number as i64)
+    } else {
+        json!(number)
+    }
+}
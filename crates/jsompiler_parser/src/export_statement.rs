@@ -0,0 +1,120 @@
+use super::{ExpressionId, Parser, Span, Statement, StatementId};
+use crate::{Error, ErrorKind};
+use jsompiler_lexer::symbol::{ContextualKeywordToken, KeywordToken, Token};
+
+#[derive(Debug, Clone)]
+pub enum ExportStatement {
+    // export function f() {}, export let a = 1, b = 2
+    Declaration {
+        declarations: Vec<StatementId>,
+        span: Span,
+    },
+    // export default function () {} or class {}
+    DefaultDeclaration {
+        declaration: StatementId,
+        span: Span,
+    },
+    // export default a + b
+    DefaultExpression {
+        expression: ExpressionId,
+        span: Span,
+    },
+}
+
+impl ExportStatement {
+    pub fn span(&self) -> Span {
+        match self {
+            ExportStatement::Declaration { span, .. }
+            | ExportStatement::DefaultDeclaration { span, .. }
+            | ExportStatement::DefaultExpression { span, .. } => *span,
+        }
+    }
+
+    pub fn span_mut(&mut self) -> &mut Span {
+        match self {
+            ExportStatement::Declaration { span, .. }
+            | ExportStatement::DefaultDeclaration { span, .. }
+            | ExportStatement::DefaultExpression { span, .. } => span,
+        }
+    }
+}
+
+impl Parser {
+    pub fn parse_export_statement(&mut self) -> Result<Vec<Statement>, Vec<Error>> {
+        let start = self.current;
+        if !self.match_token(&Token::Keyword(KeywordToken::Export)) {
+            return Err(vec![
+                self.error(ErrorKind::UnexpectedToken, "Expected 'export'")
+            ]);
+        }
+
+        if self.match_token(&Token::Keyword(KeywordToken::Default)) {
+            return self.parse_export_default(start);
+        }
+
+        let declaration = match self.peek().token {
+            Token::Keyword(KeywordToken::Function) => self.parse_function_declaration(true)?,
+            Token::ContextualKeyword(ContextualKeywordToken::Async)
+                if self.next().token == Token::Keyword(KeywordToken::Function) =>
+            {
+                self.parse_function_declaration(true)?
+            }
+            Token::Keyword(KeywordToken::Class) => self.parse_class_declaration(true)?,
+            Token::ContextualKeyword(ContextualKeywordToken::Let)
+            | Token::Keyword(KeywordToken::Var)
+            | Token::Keyword(KeywordToken::Const) => self.parse_let_statement()?,
+            _ => {
+                return Err(vec![self.error(
+                    ErrorKind::UnexpectedToken,
+                    "Expected declaration after 'export'",
+                )]);
+            }
+        };
+
+        Ok(vec![Statement::ExportStatement(
+            ExportStatement::Declaration {
+                declarations: self.ast.alloc_statements(declaration),
+                span: self.span_from(start),
+            },
+        )])
+    }
+
+    fn parse_export_default(&mut self, start: usize) -> Result<Vec<Statement>, Vec<Error>> {
+        // Function and class declarations may be anonymous here
+        let declaration = match self.peek().token {
+            Token::Keyword(KeywordToken::Function) => Some(self.parse_function_declaration(false)?),
+            Token::ContextualKeyword(ContextualKeywordToken::Async)
+                if self.next().token == Token::Keyword(KeywordToken::Function) =>
+            {
+                Some(self.parse_function_declaration(false)?)
+            }
+            Token::Keyword(KeywordToken::Class) => Some(self.parse_class_declaration(false)?),
+            _ => None,
+        };
+
+        if let Some(mut declaration) = declaration {
+            return Ok(vec![Statement::ExportStatement(
+                ExportStatement::DefaultDeclaration {
+                    declaration: self.ast.alloc_statement(declaration.remove(0)),
+                    span: self.span_from(start),
+                },
+            )]);
+        }
+
+        let expression = self.expression()?;
+
+        if !self.consume_semicolon() {
+            return Err(vec![self.error(
+                ErrorKind::UnexpectedToken,
+                "Expected ';' after export default expression",
+            )]);
+        }
+
+        Ok(vec![Statement::ExportStatement(
+            ExportStatement::DefaultExpression {
+                expression: self.ast.alloc_expression(expression),
+                span: self.span_from(start),
+            },
+        )])
+    }
+}
@@ -1,45 +1,66 @@
-use super::{Identifier, Parser, Statement};
+use super::{ExpressionId, Identifier, Parser, Span, Statement, StatementId};
 use crate::class_expression::ClassExpression;
 use crate::function_expression::{FunctionExpression, Parameter};
+use crate::jsx_expression::{JSXElement, JSXFragment};
 use crate::object_expression::Property;
 use crate::template_literal::TemplateLiteral;
 use crate::{Error, ErrorKind};
 use jsompiler_lexer::symbol::{
-    DelimiterToken, KeywordToken, Lexeme, LiteralToken, OperatorToken, Token,
+    ContextualKeywordToken, DelimiterToken, JSXToken, KeywordToken, Lexeme, LiteralToken,
+    OperatorToken, Token,
 };
 
 #[derive(Debug, Clone)]
 pub enum Expression {
     Identifier(Identifier),
-    Literal(LiteralToken),
+    PrivateIdentifier(Identifier), // Only as the left side of `#x in obj`
+    Literal {
+        value: LiteralToken,
+        span: Span,
+    },
     TemplateLiteral(Box<TemplateLiteral>),
-    ThisExpression,
-    SpreadElement(Box<Expression>),
+    ThisExpression {
+        span: Span,
+    },
+    SpreadElement {
+        argument: ExpressionId,
+        span: Span,
+    },
     MemberAccess {
-        object: Box<Expression>,
-        property: Box<Expression>,
+        object: ExpressionId,
+        property: ExpressionId,
+        computed: bool, // obj[key] rather than obj.key
+        span: Span,
     },
     Unary {
         op: Lexeme,
         op_type: String,
-        expr: Box<Expression>,
+        expr: ExpressionId,
+        span: Span,
     },
     Binary {
-        left: Box<Expression>,
+        left: ExpressionId,
         op: Lexeme,
-        right: Box<Expression>,
+        right: ExpressionId,
+        span: Span,
     },
     FunctionCall {
-        callee: Box<Expression>,
-        args: Vec<Expression>,
+        callee: ExpressionId,
+        args: Vec<ExpressionId>,
+        span: Span,
     },
     ArrayLiteral {
-        elements: Vec<Expression>,
+        elements: Vec<ExpressionId>, // Holes are undefined literals with an empty span
+        span: Span,
     },
     ObjectLiteral {
         properties: Vec<Property>,
+        span: Span,
+    },
+    AwaitExpression {
+        argument: ExpressionId,
+        span: Span,
     },
-    AwaitExpression(Box<Expression>),
     ClassExpression(ClassExpression),
     FunctionExpression(FunctionExpression),
     GeneratorExpression(FunctionExpression),
@@ -48,11 +69,45 @@ pub enum Expression {
     RegularExpressionLiteral {
         pattern: String,
         flags: String,
+        span: Span,
     },
     ArrowFunctionExpression {
         parameters: Vec<Parameter>,
-        body: Box<Vec<Statement>>,
+        body: Vec<StatementId>,
+        span: Span,
     },
+    JSXElement(Box<JSXElement>),
+    JSXFragment(JSXFragment),
+}
+
+impl Expression {
+    pub fn span(&self) -> Span {
+        match self {
+            Expression::Identifier(identifier) | Expression::PrivateIdentifier(identifier) => {
+                identifier.span()
+            }
+            Expression::TemplateLiteral(template) => template.span(),
+            Expression::ClassExpression(class) => class.span,
+            Expression::FunctionExpression(function)
+            | Expression::GeneratorExpression(function)
+            | Expression::AsyncFunctionExpression(function)
+            | Expression::AsyncGeneratorExpression(function) => function.span,
+            Expression::JSXElement(element) => element.span,
+            Expression::JSXFragment(fragment) => fragment.span,
+            Expression::Literal { span, .. }
+            | Expression::ThisExpression { span }
+            | Expression::SpreadElement { span, .. }
+            | Expression::MemberAccess { span, .. }
+            | Expression::Unary { span, .. }
+            | Expression::Binary { span, .. }
+            | Expression::FunctionCall { span, .. }
+            | Expression::ArrayLiteral { span, .. }
+            | Expression::ObjectLiteral { span, .. }
+            | Expression::AwaitExpression { span, .. }
+            | Expression::RegularExpressionLiteral { span, .. }
+            | Expression::ArrowFunctionExpression { span, .. } => *span,
+        }
+    }
 }
 
 impl Parser {
@@ -61,12 +116,10 @@ impl Parser {
         self.advance(); // Consuming open parenthesis
         if self.peek().token == Token::Delimiter(DelimiterToken::CloseParen) {
             if self.next().token != Token::Operator(OperatorToken::Arrow) {
-                return Err(vec![Error {
-                    error_kind: ErrorKind::UnexpectedToken,
-                    message: "Expected expression or arrow function".to_string(),
-                    line_number: 1,
-                    pos: 2,
-                }]);
+                return Err(vec![self.error(
+                    ErrorKind::UnexpectedToken,
+                    "Expected expression or arrow function",
+                )]);
             }
             self.current = current;
             return self.parse_arrow_expression();
@@ -82,59 +135,78 @@ impl Parser {
                     }
                     Ok(exp)
                 } else {
+                    // Unless it turns out to be an arrow function's parameter list, the
+                    // parenthesized expression is missing its `)`
+                    let error =
+                        self.error(ErrorKind::UnexpectedToken, "Expected ')' after expression");
                     self.current = current;
-                    self.parse_arrow_expression()
+                    self.parse_arrow_expression().map_err(|_| vec![error])
                 }
             }
-            Err(err) => Err(err),
+            Err(err) => {
+                // Parameter patterns such as `({ a }) => a` aren't valid expressions
+                self.current = current;
+                self.parse_arrow_expression().map_err(|_| err)
+            }
         }
     }
 
     pub fn parse_arrow_expression(&mut self) -> Result<Expression, Vec<Error>> {
-        let params = self.parse_function_parameters();
+        let start = self.current;
+        let params = self.parse_function_parameters()?;
+        self.parse_arrow_function_rest(start, params)
+    }
+
+    // From the `=>` on, once the parameters are parsed
+    fn parse_arrow_function_rest(
+        &mut self,
+        start: usize,
+        params: Vec<Parameter>,
+    ) -> Result<Expression, Vec<Error>> {
         if self.peek().token == Token::Operator(OperatorToken::Arrow) {
             self.advance(); //Consume Arrow Operator
 
             if self.peek().token == Token::Delimiter(DelimiterToken::OpenBrace) {
-                let body = self.parse_block_statement();
-                match body {
-                    Ok(body) => Ok(Expression::ArrowFunctionExpression {
-                        parameters: params?,
-                        body: Box::new(body),
-                    }),
-                    Err(err) => Err(err),
-                }
+                let body = self.parse_function_body_block(&params, true)?;
+                Ok(Expression::ArrowFunctionExpression {
+                    parameters: params,
+                    body: self.ast.alloc_statements(body),
+                    span: self.span_from(start),
+                })
             } else {
-                let body = self.expression();
-                match body {
-                    Ok(body) => Ok(Expression::ArrowFunctionExpression {
-                        parameters: params?,
-                        body: Box::new(vec![Statement::ExpressionStatement(body)]),
-                    }),
-                    Err(err) => Err(err),
-                }
+                let body_start = self.current;
+                let body = self.expression()?;
+                self.check_duplicate_parameters(&params, true)?;
+                let body = Statement::ExpressionStatement {
+                    expression: self.ast.alloc_expression(body),
+                    span: self.span_from(body_start),
+                };
+                Ok(Expression::ArrowFunctionExpression {
+                    parameters: params,
+                    body: vec![self.ast.alloc_statement(body)],
+                    span: self.span_from(start),
+                })
             }
         } else {
-            return Err(vec![Error {
-                error_kind: ErrorKind::UnexpectedToken,
-                message: "Expected '=>'".to_string(),
-                line_number: 1,
-                pos: 2,
-            }]);
+            Err(vec![self.error(ErrorKind::UnexpectedToken, "Expected '=>'")])
         }
     }
 
     pub fn parse_expression(&mut self) -> Result<Vec<Statement>, Vec<Error>> {
-        let expr = self.expression();
-        match expr {
-            Ok(expr) => Ok(vec![Statement::ExpressionStatement(expr)]),
-            Err(err) => {
-                for e in err.iter() {
-                    self.errors.push(e.clone());
-                }
-                Err(self.errors.clone())
-            }
+        let start = self.current;
+        let expr = self.expression()?;
+
+        if !self.consume_semicolon() {
+            return Err(vec![self.error(
+                ErrorKind::UnexpectedToken,
+                "Expected ';' after expression",
+            )]);
         }
+
+        Ok(vec![Statement::ExpressionStatement {
+            expression: self.ast.alloc_expression(expr),
+            span: self.span_from(start),
+        }])
     }
 
     pub fn expression(&mut self) -> Result<Expression, Vec<Error>> {
@@ -142,9 +214,6 @@ impl Parser {
         if self.peek().token == Token::Delimiter(DelimiterToken::OpenBracket) {
             return self.array_expression();
         }
-        if self.peek().token == Token::Delimiter(DelimiterToken::OpenParen) {
-            return self.parenthesis_expression();
-        }
         if self.peek().token == Token::Delimiter(DelimiterToken::OpenBrace) {
             return self.parse_object_expression();
         }
@@ -183,65 +252,98 @@ impl Parser {
     }
 
     fn unary(&mut self) -> Result<Expression, Vec<Error>> {
+        let start = self.current;
         if self.match_token(&Token::Operator(OperatorToken::Minus))
             || self.match_token(&Token::Operator(OperatorToken::Not))
             || self.match_token(&Token::Operator(OperatorToken::Plus))
             || self.match_token(&Token::Operator(OperatorToken::Increment))
             || self.match_token(&Token::Operator(OperatorToken::Decrement))
+            || self.match_token(&Token::Keyword(KeywordToken::Delete))
+            || self.match_token(&Token::Keyword(KeywordToken::Typeof))
+            || self.match_token(&Token::Keyword(KeywordToken::Void))
         {
             let op = self.previous().clone();
             let expr = self.unary()?;
             Ok(Expression::Unary {
                 op,
                 op_type: "Prefix".to_string(),
-                expr: Box::new(expr),
+                expr: self.ast.alloc_expression(expr),
+                span: self.span_from(start),
             })
         } else {
             self.primary()
         }
     }
 
-    fn primary(&mut self) -> Result<Expression, Vec<Error>> {
-        let mut expr = if self.match_token(&Token::Delimiter(DelimiterToken::OpenParen)) {
-            let expr = self.expression()?;
-            if !self.match_token(&Token::Delimiter(DelimiterToken::CloseParen)) {
-                return Err(vec![Error {
-                    error_kind: ErrorKind::UnexpectedToken,
-                    message: "Expected ')' after expression".to_string(),
-                    line_number: 1,
-                    pos: 2,
-                }]);
-            }
-            expr
+    pub fn primary(&mut self) -> Result<Expression, Vec<Error>> {
+        let start = self.current;
+        // Parenthesized expressions and arrow functions can be operands too, as in `(a + b) * c`
+        let mut expr = if self.peek().token == Token::Delimiter(DelimiterToken::OpenParen) {
+            self.parenthesis_expression()?
         } else if let Token::Keyword(KeywordToken::This) = self.peek().token {
             self.advance(); // Consume 'this'
-            Expression::ThisExpression
-        } else if let Token::Identifier(_) = self.peek().token {
+            Expression::ThisExpression {
+                span: self.previous().span,
+            }
+        } else if let Token::Identifier(_)
+        | Token::ContextualKeyword(
+            ContextualKeywordToken::Get
+            | ContextualKeywordToken::Set
+            | ContextualKeywordToken::Meta
+            | ContextualKeywordToken::Target
+            | ContextualKeywordToken::From
+            | ContextualKeywordToken::As
+            | ContextualKeywordToken::Of
+            | ContextualKeywordToken::Eval
+            | ContextualKeywordToken::Arguments,
+        ) = self.peek().token
+        {
+            // Contextual keywords that are never reserved are plain references here
             self.advance();
-            let identifier = self.previous().clone();
-            Expression::Identifier(Identifier {
-                token: identifier.clone(),
-                value: identifier.text.clone(),
-            })
+            let identifier = self.previous_identifier();
+            // `x => x`, the single parameter of an arrow function without parentheses
+            if self.peek().token == Token::Operator(OperatorToken::Arrow) {
+                return self
+                    .parse_arrow_function_rest(start, vec![Parameter::Identifier(identifier)]);
+            }
+            Expression::Identifier(identifier)
         } else if let Some(literal) = self.match_literal() {
-            Expression::Literal(literal)
+            Expression::Literal {
+                value: literal,
+                span: self.previous().span,
+            }
+        } else if let Token::JSX(JSXToken::LessThan) | Token::JSX(JSXToken::FragmentStart) =
+            self.peek().token
+        {
+            self.parse_jsx_element_or_fragment()?
+        } else if self.peek().token == Token::Operator(OperatorToken::Less) && self.relex_jsx() {
+            self.parse_jsx_element_or_fragment()?
+        } else if let Token::PrivateIdentifier(_) = self.peek().token {
+            // Private names are only valid on their own as the left side of a brand check
+            if self.next().token != Token::Keyword(KeywordToken::In) {
+                return Err(vec![self.error(
+                    ErrorKind::UnexpectedToken,
+                    "Unexpected private identifier",
+                )]);
+            }
+            self.advance();
+            let identifier = self.previous_identifier();
+            self.reference_private_name(identifier)?;
+            return Ok(Expression::PrivateIdentifier(identifier));
         } else {
-            return Err(vec![Error {
-                error_kind: ErrorKind::UnexpectedToken,
-                message: "Expected expression".to_string(),
-                line_number: 1,
-                pos: 2,
-            }]);
+            return Err(vec![
+                self.error(ErrorKind::UnexpectedToken, "Expected expression")
+            ]);
         };
 
         // After parsing the primary expression, look for member access or function calls
         loop {
             if self.peek().token == Token::Delimiter(DelimiterToken::Dot) {
-                expr = self.parse_member_access(expr)?;
+                expr = self.parse_member_access(start, expr)?;
             } else if self.peek().token == Token::Delimiter(DelimiterToken::OpenParen) {
-                expr = self.parse_function_call(expr)?;
+                expr = self.parse_function_call(start, expr)?;
             } else if self.peek().token == Token::Delimiter(DelimiterToken::OpenBracket) {
-                expr = self.parse_member_access(expr)?;
+                expr = self.parse_member_access(start, expr)?;
             } else if self.match_token(&Token::Operator(OperatorToken::Increment))
                 || self.match_token(&Token::Operator(OperatorToken::Decrement))
             {
@@ -249,7 +351,8 @@ impl Parser {
                 expr = Expression::Unary {
                     op: self.previous().clone(),
                     op_type: "Postfix".to_string(),
-                    expr: Box::new(expr),
+                    expr: self.ast.alloc_expression(expr),
+                    span: self.span_from(start),
                 };
             } else {
                 break; // No more member access or function calls
@@ -264,18 +367,22 @@ impl Parser {
     }
 
     fn factor(&mut self) -> Result<Expression, Vec<Error>> {
+        let start = self.current;
         let mut left = self.unary()?; // Parse unary first
 
         while let Some(op) = self.match_operator(&[OperatorToken::Asterisk, OperatorToken::Slash]) {
+            let op_span = self.previous().span;
             let right = self.unary()?; // Parse the second operand
             left = Expression::Binary {
-                left: Box::new(left),
+                left: self.ast.alloc_expression(left),
                 op: Lexeme {
                     token: Token::Operator(op.clone()),
                     text: op.to_string(),
                     len: 1,
+                    span: op_span,
                 },
-                right: Box::new(right),
+                right: self.ast.alloc_expression(right),
+                span: self.span_from(start),
             };
         }
 
@@ -283,18 +390,22 @@ impl Parser {
     }
 
     fn term(&mut self) -> Result<Expression, Vec<Error>> {
+        let start = self.current;
         let mut left = self.factor()?; // Parse the first operand
 
         while let Some(op) = self.match_operator(&[OperatorToken::Plus, OperatorToken::Minus]) {
+            let op_span = self.previous().span;
             let right = self.factor()?; // Parse the second operand
             left = Expression::Binary {
-                left: Box::new(left),
+                left: self.ast.alloc_expression(left),
                 op: Lexeme {
                     token: Token::Operator(op.clone()),
                     text: op.to_string(),
                     len: 1,
+                    span: op_span,
                 },
-                right: Box::new(right),
+                right: self.ast.alloc_expression(right),
+                span: self.span_from(start),
             };
         }
 
@@ -302,53 +413,59 @@ impl Parser {
     }
 
     fn comparison(&mut self) -> Result<Expression, Vec<Error>> {
+        let start = self.current;
         let mut left = self.term()?; // Parse arithmetic first
 
-        while let Some(op) = self.match_operator(&[
-            OperatorToken::NotEqual,
-            OperatorToken::DoubleEqual,
-            OperatorToken::StrictEqual,
-            OperatorToken::StrictNotEqual,
-            OperatorToken::Less,
-            OperatorToken::LessEqual,
-            OperatorToken::Greater,
-            OperatorToken::GreaterEqual,
-        ]) {
-            let right = self.term()?; // Parse the right-hand side
-            left = Expression::Binary {
-                left: Box::new(left),
-                op: Lexeme {
+        loop {
+            let op = if let Some(op) = self.match_operator(&[
+                OperatorToken::NotEqual,
+                OperatorToken::DoubleEqual,
+                OperatorToken::StrictEqual,
+                OperatorToken::StrictNotEqual,
+                OperatorToken::Less,
+                OperatorToken::LessEqual,
+                OperatorToken::Greater,
+                OperatorToken::GreaterEqual,
+            ]) {
+                Lexeme {
                     token: Token::Operator(op.clone()),
                     text: op.to_string(),
                     len: 1,
-                },
-                right: Box::new(right),
+                    span: self.previous().span,
+                }
+            } else if self.match_token(&Token::Keyword(KeywordToken::In))
+                || self.match_token(&Token::Keyword(KeywordToken::Instanceof))
+            {
+                self.previous().clone()
+            } else {
+                break;
+            };
+
+            let right = self.term()?; // Parse the right-hand side
+            left = Expression::Binary {
+                left: self.ast.alloc_expression(left),
+                op,
+                right: self.ast.alloc_expression(right),
+                span: self.span_from(start),
             };
         }
 
         Ok(left)
     }
 
-    fn parse_function_call(&mut self, callee: Expression) -> Result<Expression, Vec<Error>> {
-        println!("Parsing function call");
+    fn parse_function_call(
+        &mut self,
+        start: usize,
+        callee: Expression,
+    ) -> Result<Expression, Vec<Error>> {
         self.advance(); // Consume open parenthesis
 
         let mut args = Vec::new();
 
         // Parse arguments
         while self.peek().token != Token::Delimiter(DelimiterToken::CloseParen) {
-            let lexeme = self.peek().clone();
-            if let Token::Identifier(_) = lexeme.token {
-                args.push(Expression::Identifier(Identifier {
-                    token: lexeme.clone(),
-                    value: lexeme.text.clone(),
-                }));
-                self.advance();
-            } else if let Token::Literal(_) = lexeme.token {
-                if let Some(literal) = self.match_literal() {
-                    args.push(Expression::Literal(literal));
-                }
-            }
+            let argument = self.expression()?;
+            args.push(self.ast.alloc_expression(argument));
             if !self.match_token(&Token::Delimiter(DelimiterToken::Comma)) {
                 break; // Stop if there's no comma
             }
@@ -356,89 +473,89 @@ impl Parser {
 
         // Expect ')'
         if !self.match_token(&Token::Delimiter(DelimiterToken::CloseParen)) {
-            return Err(vec![Error {
-                error_kind: ErrorKind::UnexpectedToken,
-                message: "Expected ')' after parameters".to_string(),
-                line_number: 1,
-                pos: 2,
-            }]);
+            return Err(vec![self.error(
+                ErrorKind::UnexpectedToken,
+                "Expected ',' or ')' after argument",
+            )]);
         }
 
         Ok(Expression::FunctionCall {
-            callee: Box::new(callee),
+            callee: self.ast.alloc_expression(callee),
             args,
+            span: self.span_from(start),
         })
     }
 
     pub fn array_expression(&mut self) -> Result<Expression, Vec<Error>> {
+        let start = self.current;
         self.advance(); // Consume open bracket
         let mut elements = Vec::new();
 
         while self.peek().token != Token::Delimiter(DelimiterToken::CloseBracket) {
             if self.peek().token == Token::Delimiter(DelimiterToken::Comma) {
-                let expr = Expression::Literal(LiteralToken::Undefined);
-                elements.push(expr);
+                let position = self.peek().span.start;
+                let expr = Expression::Literal {
+                    value: LiteralToken::Undefined,
+                    span: Span::new(position, position),
+                };
+                elements.push(self.ast.alloc_expression(expr));
             } else {
                 let expr = self.expression()?;
-                elements.push(expr);
+                elements.push(self.ast.alloc_expression(expr));
             }
 
             if !self.match_token(&Token::Delimiter(DelimiterToken::Comma))
                 && self.peek().token != Token::Delimiter(DelimiterToken::CloseBracket)
             {
-                return Err(vec![Error {
-                    error_kind: ErrorKind::UnexpectedToken,
-                    message: "Expected ',' or ']'".to_string(),
-                    line_number: 1,
-                    pos: 2,
-                }]);
+                return Err(vec![
+                    self.error(ErrorKind::UnexpectedToken, "Expected ',' or ']'")
+                ]);
             }
         }
 
         if !self.match_token(&Token::Delimiter(DelimiterToken::CloseBracket)) {
-            return Err(vec![Error {
-                error_kind: ErrorKind::UnexpectedToken,
-                message: "Expected ']'".to_string(),
-                line_number: 1,
-                pos: 2,
-            }]);
+            return Err(vec![self.error(ErrorKind::UnexpectedToken, "Expected ']'")]);
         }
 
-        Ok(Expression::ArrayLiteral { elements })
+        Ok(Expression::ArrayLiteral {
+            elements,
+            span: self.span_from(start),
+        })
     }
 
-    fn parse_member_access(&mut self, expr: Expression) -> Result<Expression, Vec<Error>> {
+    fn parse_member_access(
+        &mut self,
+        start: usize,
+        expr: Expression,
+    ) -> Result<Expression, Vec<Error>> {
         match self.peek().token {
             Token::Delimiter(DelimiterToken::Dot) => {
                 self.advance(); // Consume the dot
 
                 if let Token::Identifier(_) = self.peek().token {
                     self.advance();
-                    let property = self.previous().clone();
+                    let property = Expression::Identifier(self.previous_identifier());
                     Ok(Expression::MemberAccess {
-                        object: Box::new(expr),
-                        property: Box::new(Expression::Identifier(Identifier {
-                            token: property.clone(),
-                            value: property.text.clone(),
-                        })),
+                        object: self.ast.alloc_expression(expr),
+                        property: self.ast.alloc_expression(property),
+                        computed: false,
+                        span: self.span_from(start),
                     })
                 } else if let Token::PrivateIdentifier(_) = self.peek().token {
                     self.advance();
-                    let property = self.previous().clone();
+                    let property = self.previous_identifier();
+                    self.reference_private_name(property)?;
                     Ok(Expression::MemberAccess {
-                        object: Box::new(expr),
-                        property: Box::new(Expression::Identifier(Identifier {
-                            token: property.clone(),
-                            value: property.text.clone(),
-                        })),
+                        object: self.ast.alloc_expression(expr),
+                        property: self.ast.alloc_expression(Expression::Identifier(property)),
+                        computed: false,
+                        span: self.span_from(start),
                     })
                 } else {
-                    Err(vec![Error {
-                        error_kind: ErrorKind::UnexpectedToken,
-                        message: "Expected identifier after '.'".to_string(),
-                        line_number: 1,
-                        pos: 2,
-                    }])
+                    Err(vec![self.error(
+                        ErrorKind::UnexpectedToken,
+                        "Expected identifier after '.'",
+                    )])
                 }
             }
 
@@ -446,56 +563,52 @@ impl Parser {
                 self.advance(); // Consume the open bracket
                 let property = self.expression()?;
                 if !self.match_token(&Token::Delimiter(DelimiterToken::CloseBracket)) {
-                    return Err(vec![Error {
-                        error_kind: ErrorKind::UnexpectedToken,
-                        message: "Expected ']'".to_string(),
-                        line_number: 1,
-                        pos: 2,
-                    }]);
+                    return Err(vec![self.error(ErrorKind::UnexpectedToken, "Expected ']'")]);
                 }
                 Ok(Expression::MemberAccess {
-                    object: Box::new(expr),
-                    property: Box::new(property),
+                    object: self.ast.alloc_expression(expr),
+                    property: self.ast.alloc_expression(property),
+                    computed: true,
+                    span: self.span_from(start),
                 })
             }
 
-            _ => Err(vec![Error {
-                error_kind: ErrorKind::UnexpectedToken,
-                message: "Expected during member access".to_string(),
-                line_number: 1,
-                pos: 2,
-            }]),
+            _ => Err(vec![self.error(
+                ErrorKind::UnexpectedToken,
+                "Expected during member access",
+            )]),
         }
     }
 
     fn parse_spread_operator(&mut self) -> Result<Expression, Vec<Error>> {
+        let start = self.current;
         self.advance(); // Consume the spread operator
         let expr = self.expression()?;
-        Ok(Expression::SpreadElement(Box::new(expr)))
+        Ok(Expression::SpreadElement {
+            argument: self.ast.alloc_expression(expr),
+            span: self.span_from(start),
+        })
     }
 
     fn parse_await_expression(&mut self) -> Result<Expression, Vec<Error>> {
+        let start = self.current;
         self.advance(); // Consume 'await'
         let expr = self.expression()?;
-        Ok(Expression::AwaitExpression(Box::new(expr)))
+        Ok(Expression::AwaitExpression {
+            argument: self.ast.alloc_expression(expr),
+            span: self.span_from(start),
+        })
     }
 
     fn parse_async_function_expression(&mut self) -> Result<Expression, Vec<Error>> {
+        let start = self.current;
         self.advance(); // Consume 'async'
-        let expr = self.parse_function_expression()?;
+        let (function_expression, is_generator) = self.parse_function(start)?;
 
-        if let Expression::FunctionExpression(function_expression) = expr {
-            return Ok(Expression::AsyncFunctionExpression(function_expression));
-        }
-        if let Expression::GeneratorExpression(function_expression) = expr {
-            return Ok(Expression::AsyncGeneratorExpression(function_expression));
+        if is_generator {
+            Ok(Expression::AsyncGeneratorExpression(function_expression))
         } else {
-            Err(vec![Error {
-                error_kind: ErrorKind::UnexpectedToken,
-                message: "Expected async function expression".to_string(),
-                line_number: 1,
-                pos: 2,
-            }])
+            Ok(Expression::AsyncFunctionExpression(function_expression))
         }
     }
 
@@ -504,14 +617,16 @@ impl Parser {
             let pattern = pattern.clone();
             let flags = flags.clone();
             self.advance(); // Consume the regular expression token
-            Ok(Expression::RegularExpressionLiteral { pattern, flags })
+            Ok(Expression::RegularExpressionLiteral {
+                pattern,
+                flags,
+                span: self.previous().span,
+            })
         } else {
-            Err(vec![Error {
-                error_kind: ErrorKind::UnexpectedToken,
-                message: "Expected regular expression".to_string(),
-                line_number: 1,
-                pos: 2,
-            }])
+            Err(vec![self.error(
+                ErrorKind::UnexpectedToken,
+                "Expected regular expression",
+            )])
         }
     }
 }
@@ -0,0 +1,127 @@
+use super::{ExpressionId, Parser, Span, Statement, StatementId};
+use crate::binding_pattern::BindingPattern;
+use crate::{Error, ErrorKind};
+use jsompiler_lexer::symbol::{ContextualKeywordToken, DelimiterToken, KeywordToken, Token};
+
+#[derive(Debug, Clone)]
+pub enum ForInOfLeft {
+    // for (const x of xs)
+    Declaration {
+        token: Token,
+        name: BindingPattern,
+        span: Span,
+    },
+    Target(ExpressionId), // for (obj.key in obj)
+}
+
+#[derive(Debug, Clone)]
+pub struct ForInStatement {
+    pub left: ForInOfLeft,
+    pub right: ExpressionId,
+    pub body: Vec<StatementId>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub struct ForOfStatement {
+    pub left: ForInOfLeft,
+    pub right: ExpressionId,
+    pub body: Vec<StatementId>,
+    pub is_await: bool,
+    pub span: Span,
+}
+
+impl Parser {
+    // Look for the left side of a `for-in` or `for-of` header. Rewinds and returns `None`
+    // when the header belongs to a plain `for` loop.
+    pub fn parse_for_in_of_left(&mut self) -> Option<ForInOfLeft> {
+        let checkpoint = self.current;
+
+        let left = match self.peek().token {
+            Token::ContextualKeyword(ContextualKeywordToken::Let)
+            | Token::Keyword(KeywordToken::Var)
+            | Token::Keyword(KeywordToken::Const) => {
+                let token = self.peek().token.clone();
+                self.advance();
+                self.parse_binding_pattern()
+                    .ok()
+                    .map(|name| ForInOfLeft::Declaration {
+                        token,
+                        name,
+                        span: self.span_from(checkpoint),
+                    })
+            }
+            // Patterns are parsed as literals, which stop before `in`
+            Token::Delimiter(DelimiterToken::OpenBracket)
+            | Token::Delimiter(DelimiterToken::OpenBrace) => self
+                .expression()
+                .ok()
+                .map(|target| ForInOfLeft::Target(self.ast.alloc_expression(target))),
+            Token::Identifier(_) | Token::Keyword(KeywordToken::This) => self
+                .primary()
+                .ok()
+                .map(|target| ForInOfLeft::Target(self.ast.alloc_expression(target))),
+            _ => None,
+        };
+
+        let is_in_or_of = self.check(&Token::Keyword(KeywordToken::In))
+            || self.check(&Token::ContextualKeyword(ContextualKeywordToken::Of));
+        match left {
+            Some(left) if is_in_or_of => Some(left),
+            _ => {
+                self.current = checkpoint;
+                None
+            }
+        }
+    }
+
+    pub fn parse_for_in_of_statement(
+        &mut self,
+        start: usize,
+        left: ForInOfLeft,
+        is_await: bool,
+    ) -> Result<Vec<Statement>, Vec<Error>> {
+        let is_of = self.match_token(&Token::ContextualKeyword(ContextualKeywordToken::Of));
+        if !is_of {
+            self.advance(); // Consume 'in'
+        }
+
+        if is_await && !is_of {
+            return Err(vec![self.error(
+                ErrorKind::SyntaxError,
+                "Expected 'of' in 'for await' loop",
+            )]);
+        }
+
+        let right = self.expression()?;
+        let right = self.ast.alloc_expression(right);
+        self.skip_newlines();
+
+        if !self.match_token(&Token::Delimiter(DelimiterToken::CloseParen)) {
+            return Err(vec![self.error(
+                ErrorKind::UnexpectedToken,
+                "Expected ')' after for loop head",
+            )]);
+        }
+
+        let body = self.parse_iteration_body()?;
+        let body = self.ast.alloc_statements(body);
+
+        if is_of {
+            Ok(vec![Statement::ForOfStatement(ForOfStatement {
+                left,
+                right,
+                body,
+                is_await,
+                span: self.span_from(start),
+            })])
+        } else {
+            Ok(vec![Statement::ForInStatement(ForInStatement {
+                left,
+                right,
+                body,
+                span: self.span_from(start),
+            })])
+        }
+    }
+}
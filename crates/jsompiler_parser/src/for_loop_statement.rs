@@ -1,92 +1,105 @@
-use super::{Parser, Statement};
+use super::{Parser, Span, Statement, StatementId};
 use crate::Error;
-use jsompiler_lexer::symbol::{DelimiterToken, KeywordToken, Token};
+use jsompiler_lexer::symbol::{ContextualKeywordToken, DelimiterToken, KeywordToken, Token};
 
 #[derive(Debug, Clone)]
 pub struct ForLoopStatement {
-    pub initializer: Box<Vec<Statement>>,
-    pub condition: Box<Vec<Statement>>,
-    pub increment: Box<Vec<Statement>>,
-    pub body: Box<Vec<Statement>>,
+    pub initializer: Vec<StatementId>,
+    pub condition: Vec<StatementId>,
+    pub increment: Vec<StatementId>,
+    pub body: Vec<StatementId>,
+    pub span: Span,
 }
 
 impl Parser {
     pub fn parser_for_loop_statement(&mut self) -> Result<Vec<Statement>, Vec<Error>> {
+        let start = self.current;
         if !self.match_token(&Token::Keyword(KeywordToken::For)) {
-            return Err(vec![Error {
-                error_kind: crate::ErrorKind::UnexpectedToken,
-                message: "Expected 'for' keyword".to_string(),
-                line_number: 1,
-                pos: 2,
-            }]);
+            return Err(vec![self.error(
+                crate::ErrorKind::UnexpectedToken,
+                "Expected 'for' keyword",
+            )]);
         }
 
+        let is_await = self.match_token(&Token::ContextualKeyword(ContextualKeywordToken::Await));
+
         // Expect '('
         if !self.match_token(&Token::Delimiter(DelimiterToken::OpenParen)) {
-            return Err(vec![Error {
-                error_kind: crate::ErrorKind::UnexpectedToken,
-                message: "Expected '(' after 'for' keyword".to_string(),
-                line_number: 1,
-                pos: 2,
-            }]);
+            return Err(vec![self.error(
+                crate::ErrorKind::UnexpectedToken,
+                "Expected '(' after 'for' keyword",
+            )]);
         }
 
-        // Parse initializer
-        let mut initializer = vec![];
-        if self.peek().token == Token::Delimiter(DelimiterToken::Semicolon) {
-            self.advance(); // Consume ';'
-        } else {
-            initializer = self.parse_statement()?;
-            self.advance(); // Consume ';'
+        if let Some(left) = self.parse_for_in_of_left() {
+            return self.parse_for_in_of_statement(start, left, is_await);
+        }
+
+        if is_await {
+            return Err(vec![self.error(
+                crate::ErrorKind::SyntaxError,
+                "Expected 'of' in 'for await' loop",
+            )]);
         }
-        println!("Initializers: {:#?}", initializer);
+
+        // Parse initializer
+        let initializer = match self.peek().token {
+            Token::Delimiter(DelimiterToken::Semicolon) => vec![],
+            Token::ContextualKeyword(ContextualKeywordToken::Let)
+            | Token::Keyword(KeywordToken::Var)
+            | Token::Keyword(KeywordToken::Const) => self.parse_variable_declarations()?,
+            _ => vec![self.parse_assignment_or_expression()?],
+        };
+        self.expect_for_header_semicolon()?;
 
         // Parse condition
-        let mut condition = vec![];
-        if self.peek().token == Token::Delimiter(DelimiterToken::Semicolon) {
-            self.advance(); // Consume ';'
+        let condition = if self.check(&Token::Delimiter(DelimiterToken::Semicolon)) {
+            vec![]
         } else {
-            condition = self.parse_statement()?;
-            self.advance(); // Consume ';'
-        }
-        println!("Condition: {:#?}", condition);
+            let condition_start = self.current;
+            let expression = self.expression()?;
+            vec![Statement::ExpressionStatement {
+                expression: self.ast.alloc_expression(expression),
+                span: self.span_from(condition_start),
+            }]
+        };
+        self.expect_for_header_semicolon()?;
 
         // Parse increment
-        let mut increment = vec![];
-        println!("Peek: {:#?}", self.peek());
-        if self.peek().token != Token::Delimiter(DelimiterToken::CloseParen) {
-            increment = self.parse_statement()?;
-        }
-        println!("Increment: {:#?}", increment);
+        let increment = if self.check(&Token::Delimiter(DelimiterToken::CloseParen)) {
+            vec![]
+        } else {
+            vec![self.parse_assignment_or_expression()?]
+        };
 
         // Expect ')'
         if !self.match_token(&Token::Delimiter(DelimiterToken::CloseParen)) {
-            return Err(vec![Error {
-                error_kind: crate::ErrorKind::UnexpectedToken,
-                message: "Expected ')' after for loop condition".to_string(),
-                line_number: 1,
-                pos: 2,
-            }]);
+            return Err(vec![self.error(
+                crate::ErrorKind::UnexpectedToken,
+                "Expected ')' after for loop condition",
+            )]);
         }
 
-        // Parse body
-        let body;
-        if self.peek().token == Token::Delimiter(DelimiterToken::OpenBrace) {
-            body = self.parse_block_statement()?;
-        } else {
-            return Err(vec![Error {
-                error_kind: crate::ErrorKind::UnexpectedToken,
-                message: "Expected '{' ".to_string(),
-                line_number: 1,
-                pos: 2,
-            }]);
-        }
+        let body = self.parse_iteration_body()?;
 
         Ok(vec![Statement::ForLoopStatement(ForLoopStatement {
-            initializer: Box::new(initializer),
-            condition: Box::new(condition),
-            increment: Box::new(increment),
-            body: Box::new(body),
+            initializer: self.ast.alloc_statements(initializer),
+            condition: self.ast.alloc_statements(condition),
+            increment: self.ast.alloc_statements(increment),
+            body: self.ast.alloc_statements(body),
+            span: self.span_from(start),
         })])
     }
+
+    fn expect_for_header_semicolon(&mut self) -> Result<(), Vec<Error>> {
+        self.skip_newlines();
+        if !self.match_token(&Token::Delimiter(DelimiterToken::Semicolon)) {
+            return Err(vec![self.error(
+                crate::ErrorKind::UnexpectedToken,
+                "Expected ';' in for loop header",
+            )]);
+        }
+        self.skip_newlines();
+        Ok(())
+    }
 }
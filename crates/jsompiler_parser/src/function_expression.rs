@@ -1,74 +1,148 @@
-use super::{Identifier, Parser, Statement};
-use crate::{expression::Expression, Error, ErrorKind};
-use jsompiler_lexer::symbol::{DelimiterToken, KeywordToken, OperatorToken, Token};
+use super::{ExpressionId, Identifier, Parser, Span, Statement, StatementId};
+use crate::binding_pattern::{BindingElement, BindingPattern};
+use crate::{expression::Expression, Atom, Error, ErrorKind};
+use jsompiler_lexer::symbol::{
+    ContextualKeywordToken, DelimiterToken, KeywordToken, LiteralToken, OperatorToken, Token,
+};
 
 #[derive(Clone, Debug)]
 pub struct FunctionExpression {
-    pub name: Identifier,
+    pub name: Option<Identifier>,
     pub parameters: Vec<Parameter>,
-    pub body: Box<Vec<Statement>>,
+    pub body: Vec<StatementId>,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug)]
+pub struct FunctionDeclaration {
+    pub name: Option<Identifier>, // Only `None` for `export default function () {}`
+    pub parameters: Vec<Parameter>,
+    pub body: Vec<StatementId>,
+    pub is_async: bool,
+    pub is_generator: bool,
+    pub span: Span,
 }
 
 #[derive(Clone, Debug)]
 pub enum Parameter {
     Identifier(Identifier),
-    RestParameter(Identifier),
+    Pattern(BindingPattern),
+    Default {
+        target: BindingPattern,
+        default: ExpressionId,
+        span: Span,
+    }, // For parameters with initializers like `a = 1` or `{ b } = {}`
+    RestParameter {
+        argument: BindingPattern,
+        span: Span,
+    },
+}
+
+impl Parameter {
+    pub fn bound_names(&self) -> Vec<Identifier> {
+        match self {
+            Parameter::Identifier(identifier) => vec![*identifier],
+            Parameter::Pattern(pattern)
+            | Parameter::Default {
+                target: pattern, ..
+            }
+            | Parameter::RestParameter {
+                argument: pattern, ..
+            } => pattern.bound_names(),
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            Parameter::Identifier(identifier) => identifier.span(),
+            Parameter::Pattern(pattern) => pattern.span(),
+            Parameter::Default { span, .. } | Parameter::RestParameter { span, .. } => *span,
+        }
+    }
+}
+
+// A simple parameter list only contains plain identifiers
+pub fn is_simple_parameter_list(parameters: &[Parameter]) -> bool {
+    parameters
+        .iter()
+        .all(|parameter| matches!(parameter, Parameter::Identifier(_)))
 }
 
 impl Parser {
     pub fn parse_function_expression(&mut self) -> Result<Expression, Vec<Error>> {
+        let start = self.current;
+        let (function, is_generator) = self.parse_function(start)?;
+
+        if is_generator {
+            Ok(Expression::GeneratorExpression(function))
+        } else {
+            Ok(Expression::FunctionExpression(function))
+        }
+    }
+
+    pub fn parse_function_declaration(
+        &mut self,
+        name_required: bool,
+    ) -> Result<Vec<Statement>, Vec<Error>> {
+        let start = self.current;
+        let is_async = self.match_token(&Token::ContextualKeyword(ContextualKeywordToken::Async));
+        let (function, is_generator) = self.parse_function(start)?;
+
+        // Only `export default function () {}` may omit the name
+        if name_required && function.name.is_none() {
+            return Err(vec![self.error(
+                ErrorKind::SyntaxError,
+                "Function statements require a function name",
+            )]);
+        }
+
+        Ok(vec![Statement::FunctionDeclaration(FunctionDeclaration {
+            name: function.name,
+            parameters: function.parameters,
+            body: function.body,
+            is_async,
+            is_generator,
+            span: function.span,
+        })])
+    }
+
+    // Parse `function [*] [name] (parameters) { body }`, returning whether it is a generator.
+    // `start` is where the function begins, before any `async`.
+    pub fn parse_function(
+        &mut self,
+        start: usize,
+    ) -> Result<(FunctionExpression, bool), Vec<Error>> {
         // Expect `function` keyword
         if !self.match_token(&Token::Keyword(KeywordToken::Function)) {
-            return Err(vec![Error {
-                error_kind: ErrorKind::UnexpectedToken,
-                message: "Expected 'function' keyword".to_string(),
-                line_number: 1,
-                pos: 2,
-            }]);
+            return Err(vec![
+                self.error(ErrorKind::UnexpectedToken, "Expected 'function' keyword")
+            ]);
         }
 
-        if self.peek().token == Token::Operator(OperatorToken::Asterisk) {
-            self.advance(); // Consume the *
-            return self.parse_generator_function();
-        }
+        let is_generator = self.match_token(&Token::Operator(OperatorToken::Asterisk));
 
-        // Expect function name (identifier)
+        // Optional function name (identifier)
         let name = if let Token::Identifier(_) = self.peek().token {
             self.advance();
-            Identifier {
-                token: self.previous().clone(),
-                value: self.previous().text.clone(),
-            }
+            Some(self.previous_identifier())
         } else {
-            return Err(vec![Error {
-                error_kind: ErrorKind::UnexpectedToken,
-                message: "Expected function name".to_string(),
-                line_number: 1,
-                pos: 2,
-            }]);
+            None
         };
 
         let parameters = self.parse_function_parameters()?;
 
-        // Expect '{' (Start of function body)
-        if !self.match_token(&Token::Delimiter(DelimiterToken::OpenBrace)) {
-            return Err(vec![Error {
-                error_kind: ErrorKind::UnexpectedToken,
-                message: "Expected '{' before function body".to_string(),
-                line_number: 1,
-                pos: 2,
-            }]);
-        }
-
         // Parse function body (a block statement)
-        let body = self.parse_block_statement()?;
-
-        // Return FunctionStatement node
-        Ok(Expression::FunctionExpression(FunctionExpression {
-            name,
-            parameters,
-            body: Box::new(body),
-        }))
+        let body = self.parse_function_body_block(&parameters, false)?;
+
+        Ok((
+            FunctionExpression {
+                name,
+                parameters,
+                body: self.ast.alloc_statements(body),
+                span: self.span_from(start),
+            },
+            is_generator,
+        ))
     }
 
     pub fn parse_function_parameters(&mut self) -> Result<Vec<Parameter>, Vec<Error>> {
@@ -76,147 +150,199 @@ impl Parser {
         if !self.match_token(&Token::Delimiter(
             jsompiler_lexer::symbol::DelimiterToken::OpenParen,
         )) {
-            return Err(vec![Error {
-                error_kind: ErrorKind::UnexpectedToken,
-                message: "Expected '(' after function name".to_string(),
-                line_number: 1,
-                pos: 2,
-            }]);
+            return Err(vec![self.error(
+                ErrorKind::UnexpectedToken,
+                "Expected '(' after function name",
+            )]);
         }
 
         // Parse parameters
         let mut parameters = Vec::new();
-        let mut rest_param_seen = false;
 
         loop {
-            println!("Current token: {:#?}", self.peek().token);
+            self.skip_newlines();
             match self.peek().token {
-                Token::Identifier(_) => {
-                    self.advance();
-                    parameters.push(Parameter::Identifier(Identifier {
-                        token: self.previous().clone(),
-                        value: self.previous().text.clone(),
-                    }));
-
-                    if self.peek().token == Token::Delimiter(DelimiterToken::Comma) {
-                        self.advance(); // Consume the comma
-                    } else if self.peek().token == Token::Delimiter(DelimiterToken::CloseParen) {
-                        break; // Stop if we reach the closing parenthesis
-                    } else {
-                        return Err(vec![Error {
-                            error_kind: ErrorKind::UnexpectedToken,
-                            message: "Expected ',' or ')' after parameter".to_string(),
-                            line_number: 1,
-                            pos: 2,
-                        }]);
-                    }
+                Token::Delimiter(DelimiterToken::CloseParen) => {
+                    break; // Stop if we reach the closing parenthesis
                 }
 
                 Token::Operator(OperatorToken::Spread) => {
+                    let start = self.current;
                     self.advance();
+                    let argument = self.parse_binding_pattern()?;
+                    parameters.push(Parameter::RestParameter {
+                        argument,
+                        span: self.span_from(start),
+                    });
+
                     // Rest parameter must be the last parameter
-                    if rest_param_seen {
-                        return Err(vec![Error {
-                            error_kind: ErrorKind::SyntaxError,
-                            message: "Only one rest parameter is allowed".to_string(),
-                            line_number: 1,
-                            pos: 2,
-                        }]);
-                    }
-                    if let Token::Identifier(_) = self.peek().token {
-                        self.advance();
-                        rest_param_seen = true;
-                        parameters.push(Parameter::RestParameter(Identifier {
-                            token: self.previous().clone(),
-                            value: self.previous().text.clone(),
-                        }));
-
-                        // If we've seen a rest parameter, no more parameters are allowed
-                        if rest_param_seen
-                            && self.peek().token != Token::Delimiter(DelimiterToken::CloseParen)
-                        {
-                            return Err(vec![Error {
-                                error_kind: ErrorKind::SyntaxError,
-                                message: "Rest parameter must be the last parameter".to_string(),
-                                line_number: 1,
-                                pos: 2,
-                            }]);
-                        }
-                    } else {
-                        return Err(vec![Error {
-                            error_kind: ErrorKind::UnexpectedToken,
-                            message: "Expected identifier after '...'".to_string(),
-                            line_number: 1,
-                            pos: 2,
-                        }]);
+                    self.skip_newlines();
+                    if self.peek().token != Token::Delimiter(DelimiterToken::CloseParen) {
+                        return Err(vec![self.error(
+                            ErrorKind::SyntaxError,
+                            "Rest parameter must be the last parameter",
+                        )]);
                     }
                 }
 
-                Token::Delimiter(DelimiterToken::CloseParen) => {
-                    break; // Stop if we reach the closing parenthesis
+                Token::Identifier(_)
+                | Token::Delimiter(DelimiterToken::OpenBrace)
+                | Token::Delimiter(DelimiterToken::OpenBracket) => {
+                    parameters.push(self.parse_parameter()?);
+
+                    self.skip_newlines();
+                    if self.peek().token == Token::Delimiter(DelimiterToken::Comma) {
+                        self.advance(); // Consume the comma
+                    } else if self.peek().token != Token::Delimiter(DelimiterToken::CloseParen) {
+                        return Err(vec![self.error(
+                            ErrorKind::UnexpectedToken,
+                            "Expected ',' or ')' after parameter",
+                        )]);
+                    }
                 }
 
                 _ => {
-                    return Err(vec![Error {
-                        error_kind: ErrorKind::UnexpectedToken,
-                        message: "Unexpected parameter in function".to_string(),
-                        line_number: 1,
-                        pos: 2,
-                    }]);
+                    return Err(vec![self.error(
+                        ErrorKind::UnexpectedToken,
+                        "Unexpected parameter in function",
+                    )]);
                 }
             }
         }
 
         // Expect ')'
         if !self.match_token(&Token::Delimiter(DelimiterToken::CloseParen)) {
-            return Err(vec![Error {
-                error_kind: ErrorKind::UnexpectedToken,
-                message: "Expected ')' after parameters".to_string(),
-                line_number: 1,
-                pos: 2,
-            }]);
+            return Err(vec![self.error(
+                ErrorKind::UnexpectedToken,
+                "Expected ')' after parameters",
+            )]);
         }
 
         Ok(parameters)
     }
 
-    pub fn parse_generator_function(&mut self) -> Result<Expression, Vec<Error>> {
-        // Expect function name (identifier)
-        let name = if let Token::Identifier(_) = self.peek().token {
-            self.advance();
-            Identifier {
-                token: self.previous().clone(),
-                value: self.previous().text.clone(),
+    // Parse a single non-rest parameter: `a`, `a = 1`, `{ b }`, `[c] = []`
+    pub fn parse_parameter(&mut self) -> Result<Parameter, Vec<Error>> {
+        let element = self.parse_binding_element()?;
+        Ok(match element {
+            BindingElement {
+                target: BindingPattern::Identifier(identifier),
+                default: None,
+                ..
+            } => Parameter::Identifier(identifier),
+            BindingElement {
+                target,
+                default: None,
+                ..
+            } => Parameter::Pattern(target),
+            BindingElement {
+                target,
+                default: Some(default),
+                span,
+            } => Parameter::Default {
+                target,
+                default,
+                span,
+            },
+        })
+    }
+
+    // Parse a function body and check its parameters against it. `unique_parameters`
+    // is set for arrow functions and methods, which never allow duplicate parameters.
+    pub fn parse_function_body_block(
+        &mut self,
+        parameters: &[Parameter],
+        unique_parameters: bool,
+    ) -> Result<Vec<Statement>, Vec<Error>> {
+        if self.peek().token != Token::Delimiter(DelimiterToken::OpenBrace) {
+            return Err(vec![self.error(
+                ErrorKind::UnexpectedToken,
+                "Expected '{' before function body",
+            )]);
+        }
+
+        let outer_strict_mode = self.strict_mode;
+        if self.has_use_strict_directive(self.peek_index() + 1) {
+            if !is_simple_parameter_list(parameters) {
+                return Err(vec![self.error(
+                    ErrorKind::SyntaxError,
+                    "Illegal 'use strict' directive in function with non-simple parameter list",
+                )]);
             }
-        } else {
-            return Err(vec![Error {
-                error_kind: ErrorKind::UnexpectedToken,
-                message: "Expected function name".to_string(),
-                line_number: 1,
-                pos: 2,
-            }]);
-        };
+            self.strict_mode = true;
+        }
 
-        let parameters = self.parse_function_parameters()?;
+        // Labels and loops outside the function can't be targeted from inside it
+        let outer_label_set = std::mem::take(&mut self.label_set);
+        let body = self.parse_block_statement();
+        let checked = self.check_duplicate_parameters(parameters, unique_parameters);
+        self.strict_mode = outer_strict_mode;
+        self.label_set = outer_label_set;
+
+        let body = body?;
+        checked?;
+        Ok(body)
+    }
 
-        // Expect '{' (Start of function body)
-        if !self.match_token(&Token::Delimiter(DelimiterToken::OpenBrace)) {
-            return Err(vec![Error {
-                error_kind: ErrorKind::UnexpectedToken,
-                message: "Expected '{' before function body".to_string(),
-                line_number: 1,
-                pos: 2,
-            }]);
+    // Duplicate parameter names are an early error in strict mode, in arrow functions
+    // and methods, and whenever the parameter list is not simple.
+    pub fn check_duplicate_parameters(
+        &self,
+        parameters: &[Parameter],
+        unique_parameters: bool,
+    ) -> Result<(), Vec<Error>> {
+        if !unique_parameters && !self.strict_mode && is_simple_parameter_list(parameters) {
+            return Ok(());
         }
 
-        // Parse function body (a block statement)
-        let body = self.parse_block_statement()?;
-
-        // Return FunctionStatement node
-        Ok(Expression::GeneratorExpression(FunctionExpression {
-            name,
-            parameters,
-            body: Box::new(body),
-        }))
+        let mut seen: Vec<Atom> = Vec::new();
+        for name in parameters.iter().flat_map(Parameter::bound_names) {
+            if seen.contains(&name.value) {
+                return Err(vec![self.error_at(
+                    name.span,
+                    ErrorKind::SyntaxError,
+                    format!(
+                        "Duplicate parameter name '{}' not allowed in this context",
+                        &self.ast[name.value]
+                    ),
+                )]);
+            }
+            seen.push(name.value);
+        }
+
+        Ok(())
+    }
+
+    // Looks ahead for a "use strict" directive in the prologue starting at `index`, the
+    // token after the '{' of a function body or the first token of the program
+    pub fn has_use_strict_directive(&self, index: usize) -> bool {
+        let mut index = index;
+        while let Some(lexeme) = self.tokens.get(index) {
+            match &lexeme.token {
+                Token::Delimiter(DelimiterToken::NewLine) => index += 1,
+                Token::Literal(LiteralToken::String(_)) => {
+                    // A string is only a directive when it is a statement of its own
+                    let directive = matches!(
+                        self.tokens.get(index + 1).map(|lexeme| &lexeme.token),
+                        Some(Token::Delimiter(DelimiterToken::Semicolon))
+                            | Some(Token::Delimiter(DelimiterToken::NewLine))
+                            | Some(Token::Delimiter(DelimiterToken::CloseBrace))
+                            | Some(Token::EOF)
+                            | None
+                    );
+                    if !directive {
+                        return false;
+                    }
+                    // The raw text must not contain escapes, so compare without the quotes
+                    let text = &lexeme.text;
+                    if text.len() >= 2 && &text[1..text.len() - 1] == "use strict" {
+                        return true;
+                    }
+                    index += 2;
+                }
+                _ => return false,
+            }
+        }
+        false
     }
 }
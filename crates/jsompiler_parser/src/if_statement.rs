@@ -1,50 +1,44 @@
-use super::{expression::Expression, Parser, Statement};
+use super::{ExpressionId, Parser, Span, Statement, StatementId};
 use crate::{Error, ErrorKind};
 use jsompiler_lexer::symbol::{KeywordToken, Token};
 
 #[derive(Debug, Clone)]
 pub struct IfStatement {
-    pub condition: Expression,
-    pub consequence: Box<Vec<Statement>>,
-    pub alternative: Option<Box<Vec<Statement>>>,
+    pub condition: ExpressionId,
+    pub consequence: Vec<StatementId>,
+    pub alternative: Option<Vec<StatementId>>,
+    pub span: Span,
 }
 
 impl Parser {
     pub fn parse_if_statement(&mut self) -> Result<Vec<Statement>, Vec<Error>> {
+        let start = self.current;
         if !self.match_token(&Token::Keyword(KeywordToken::If)) {
-            return Err(vec![Error {
-                error_kind: ErrorKind::UnexpectedToken,
-                message: "Expected 'then' after condition".to_string(),
-                line_number: 1,
-                pos: 2,
-            }]);
+            return Err(vec![self.error(ErrorKind::UnexpectedToken, "Expected 'if'")]);
         }
 
         let value = self.parenthesis_expression()?;
-        let consequence = Box::new(self.parse_block_statement()?);
-        // Decode expression from statement
+        let value = self.ast.alloc_expression(value);
+        let consequence = self.parse_sub_statement()?;
+        let consequence = self.ast.alloc_statements(consequence);
 
-        if self.match_token(&Token::Keyword(KeywordToken::Else)) {
-            if self.peek().token == Token::Keyword(KeywordToken::If) {
-                let alternative = Box::new(self.parse_if_statement()?);
-                return Ok(vec![Statement::IfStatement(IfStatement {
-                    condition: value,
-                    consequence,
-                    alternative: Some(alternative),
-                })]);
-            }
-            let alternative = Box::new(self.parse_block_statement()?);
-            return Ok(vec![Statement::IfStatement(IfStatement {
-                condition: value,
-                consequence,
-                alternative: Some(alternative),
-            })]);
-        }
+        // `else` may follow on a later line; an `else if` chain nests through the recursive
+        // call, so a dangling `else` binds to the nearest `if`
+        let checkpoint = self.current;
+        self.skip_newlines();
+        let alternative = if self.match_token(&Token::Keyword(KeywordToken::Else)) {
+            let alternative = self.parse_sub_statement()?;
+            Some(self.ast.alloc_statements(alternative))
+        } else {
+            self.current = checkpoint;
+            None
+        };
 
         Ok(vec![Statement::IfStatement(IfStatement {
             condition: value,
             consequence,
-            alternative: None,
+            alternative,
+            span: self.span_from(start),
         })])
     }
 }
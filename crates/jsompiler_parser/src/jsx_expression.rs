@@ -0,0 +1,465 @@
+use super::{expression::Expression, Ast, ExpressionId, Identifier, Parser, Span};
+use crate::{Error, ErrorKind};
+use jsompiler_lexer::symbol::{JSXToken, Token};
+
+#[derive(Debug, Clone)]
+pub struct JSXElement {
+    pub opening: JSXOpeningElement,
+    pub children: Vec<JSXChild>,
+    pub closing: Option<JSXClosingElement>, // `None` for self-closing elements
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub struct JSXOpeningElement {
+    pub name: JSXElementName,
+    pub attributes: Vec<JSXAttribute>,
+    pub self_closing: bool,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub struct JSXClosingElement {
+    pub name: JSXElementName,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub struct JSXFragment {
+    pub children: Vec<JSXChild>,
+    pub span: Span,
+    pub opening_span: Span, // <>
+    pub closing_span: Span, // </>
+}
+
+#[derive(Debug, Clone)]
+pub enum JSXElementName {
+    Identifier(Identifier), // div
+    Namespaced {
+        namespace: Identifier,
+        name: Identifier,
+    }, // svg:rect
+    Member {
+        object: Box<JSXElementName>,
+        property: Identifier,
+    }, // Foo.Bar
+}
+
+#[derive(Debug, Clone)]
+pub enum JSXAttribute {
+    Attribute {
+        name: JSXElementName, // Only identifiers and namespaced names
+        value: Option<JSXAttributeValue>,
+        span: Span,
+    },
+    SpreadAttribute {
+        argument: ExpressionId,
+        span: Span,
+    }, // {...props}
+}
+
+#[derive(Debug, Clone)]
+pub enum JSXAttributeValue {
+    StringLiteral { value: String, span: Span },
+    ExpressionContainer(JSXExpressionContainer),
+    Element(Box<JSXElement>),
+    Fragment(Box<JSXFragment>),
+}
+
+#[derive(Debug, Clone)]
+pub enum JSXExpressionContainer {
+    Expression {
+        expression: ExpressionId,
+        span: Span,
+    },
+    Empty {
+        span: Span,
+    }, // {} or a container holding only a comment
+}
+
+#[derive(Debug, Clone)]
+pub enum JSXChild {
+    Text {
+        value: String,
+        span: Span,
+    },
+    Element(Box<JSXElement>),
+    Fragment(Box<JSXFragment>),
+    ExpressionContainer(JSXExpressionContainer),
+    Spread {
+        expression: ExpressionId,
+        span: Span,
+    }, // {...children}
+}
+
+impl JSXElementName {
+    pub fn span(&self) -> Span {
+        match self {
+            JSXElementName::Identifier(name) => name.span(),
+            JSXElementName::Namespaced { namespace, name } => namespace.span().to(name.span()),
+            JSXElementName::Member { object, property } => object.span().to(property.span()),
+        }
+    }
+}
+
+impl JSXExpressionContainer {
+    pub fn span(&self) -> Span {
+        match self {
+            JSXExpressionContainer::Expression { span, .. }
+            | JSXExpressionContainer::Empty { span } => *span,
+        }
+    }
+}
+
+impl JSXElementName {
+    // The name as written, like `svg:rect` or `Foo.Bar`
+    pub fn text(&self, ast: &Ast) -> String {
+        match self {
+            JSXElementName::Identifier(name) => ast[name.value].to_string(),
+            JSXElementName::Namespaced { namespace, name } => {
+                format!("{}:{}", &ast[namespace.value], &ast[name.value])
+            }
+            JSXElementName::Member { object, property } => {
+                format!("{}.{}", object.text(ast), &ast[property.value])
+            }
+        }
+    }
+}
+
+// Closing tags are matched by name only, wherever they appear
+impl PartialEq for JSXElementName {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (JSXElementName::Identifier(a), JSXElementName::Identifier(b)) => a.value == b.value,
+            (
+                JSXElementName::Namespaced { namespace, name },
+                JSXElementName::Namespaced {
+                    namespace: other_namespace,
+                    name: other_name,
+                },
+            ) => namespace.value == other_namespace.value && name.value == other_name.value,
+            (
+                JSXElementName::Member { object, property },
+                JSXElementName::Member {
+                    object: other_object,
+                    property: other_property,
+                },
+            ) => object == other_object && property.value == other_property.value,
+            _ => false,
+        }
+    }
+}
+
+impl Parser {
+    // A `<` where an expression starts begins a JSX element. The lexer took it for an
+    // operator, so the source is lexed again from there and the tokens after it replaced.
+    pub fn relex_jsx(&mut self) -> bool {
+        let index = self.peek_index();
+        let position = self.tokens[index].span.start;
+        let Some(lexer) = &mut self.lexer else {
+            return false;
+        };
+        if !self.options.jsx || !lexer.relex_jsx(position) {
+            return false;
+        }
+        let tokens = std::mem::take(&mut lexer.tokens);
+        self.replace_tokens_from(index, tokens);
+        true
+    }
+
+    pub fn parse_jsx_element_or_fragment(&mut self) -> Result<Expression, Vec<Error>> {
+        if !self.options.jsx {
+            return Err(vec![
+                self.error(ErrorKind::SyntaxError, "JSX syntax is not enabled")
+            ]);
+        }
+
+        match self.peek().token {
+            Token::JSX(JSXToken::FragmentStart) => {
+                Ok(Expression::JSXFragment(self.parse_jsx_fragment()?))
+            }
+            _ => Ok(Expression::JSXElement(Box::new(self.parse_jsx_element()?))),
+        }
+    }
+
+    fn parse_jsx_element(&mut self) -> Result<JSXElement, Vec<Error>> {
+        let start = self.current;
+        self.expect_jsx(JSXToken::LessThan, "Expected '<'")?;
+
+        let name = self.parse_jsx_element_name()?;
+        let attributes = self.parse_jsx_attributes()?;
+
+        if self.match_token(&Token::JSX(JSXToken::SlashGreaterThan)) {
+            let span = self.span_from(start);
+            return Ok(JSXElement {
+                opening: JSXOpeningElement {
+                    name,
+                    attributes,
+                    self_closing: true,
+                    span,
+                },
+                children: vec![],
+                closing: None,
+                span,
+            });
+        }
+        self.expect_jsx(
+            JSXToken::GreaterThan,
+            "Expected '>' or '/>' after JSX attributes",
+        )?;
+        let opening_span = self.span_from(start);
+
+        let children = self.parse_jsx_children()?;
+
+        let closing_start = self.current;
+        self.expect_jsx(JSXToken::LessThanSlash, "Expected JSX closing tag")?;
+        let closing_name = self.parse_jsx_element_name()?;
+        if closing_name != name {
+            return Err(vec![self.error(
+                ErrorKind::SyntaxError,
+                format!(
+                    "Expected corresponding JSX closing tag for <{}>",
+                    name.text(&self.ast)
+                ),
+            )]);
+        }
+        self.expect_jsx(
+            JSXToken::GreaterThan,
+            "Expected '>' after JSX closing tag name",
+        )?;
+
+        Ok(JSXElement {
+            opening: JSXOpeningElement {
+                name,
+                attributes,
+                self_closing: false,
+                span: opening_span,
+            },
+            children,
+            closing: Some(JSXClosingElement {
+                name: closing_name,
+                span: self.span_from(closing_start),
+            }),
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_jsx_fragment(&mut self) -> Result<JSXFragment, Vec<Error>> {
+        let start = self.current;
+        self.expect_jsx(JSXToken::FragmentStart, "Expected '<>'")?;
+        let opening_span = self.previous().span;
+        let children = self.parse_jsx_children()?;
+        let closing_start = self.current;
+
+        // `</>` may also be split as `</` and `>`
+        if !self.match_token(&Token::JSX(JSXToken::FragmentEnd)) {
+            if self.match_token(&Token::JSX(JSXToken::LessThanSlash)) {
+                if let Token::JSX(JSXToken::Identifier(_)) = self.peek().token {
+                    return Err(vec![self.error(
+                        ErrorKind::SyntaxError,
+                        "Expected corresponding JSX closing tag for <>",
+                    )]);
+                }
+            }
+            self.expect_jsx(JSXToken::GreaterThan, "Expected '</>'")?;
+        }
+
+        Ok(JSXFragment {
+            children,
+            span: self.span_from(start),
+            opening_span,
+            closing_span: self.span_from(closing_start),
+        })
+    }
+
+    // `div`, `svg:rect` or `Foo.Bar.Baz`
+    fn parse_jsx_element_name(&mut self) -> Result<JSXElementName, Vec<Error>> {
+        let name = self.parse_jsx_identifier()?;
+
+        if self.match_token(&Token::JSX(JSXToken::Colon)) {
+            return Ok(JSXElementName::Namespaced {
+                namespace: name,
+                name: self.parse_jsx_identifier()?,
+            });
+        }
+
+        let mut name = JSXElementName::Identifier(name);
+        while self.match_token(&Token::JSX(JSXToken::Dot)) {
+            name = JSXElementName::Member {
+                object: Box::new(name),
+                property: self.parse_jsx_identifier()?,
+            };
+        }
+        Ok(name)
+    }
+
+    fn parse_jsx_identifier(&mut self) -> Result<Identifier, Vec<Error>> {
+        let Token::JSX(JSXToken::Identifier(name)) = &self.peek().token else {
+            return Err(vec![
+                self.error(ErrorKind::UnexpectedToken, "Expected JSX identifier")
+            ]);
+        };
+        let name = name.clone();
+        let name = Identifier::new(self.ast.atoms.intern(&name), self.peek().span);
+        self.advance();
+        Ok(name)
+    }
+
+    fn parse_jsx_attributes(&mut self) -> Result<Vec<JSXAttribute>, Vec<Error>> {
+        let mut attributes = Vec::new();
+
+        loop {
+            let start = self.current;
+            match self.peek().token {
+                Token::JSX(JSXToken::LeftBrace) => {
+                    self.advance();
+                    self.expect_jsx(JSXToken::Ellipsis, "Expected '...' in JSX spread attribute")?;
+                    let argument = self.expression()?;
+                    self.expect_jsx(
+                        JSXToken::RightBrace,
+                        "Expected '}' after JSX spread attribute",
+                    )?;
+                    attributes.push(JSXAttribute::SpreadAttribute {
+                        argument: self.ast.alloc_expression(argument),
+                        span: self.span_from(start),
+                    });
+                }
+                Token::JSX(JSXToken::Identifier(_)) => {
+                    let mut name = JSXElementName::Identifier(self.parse_jsx_identifier()?);
+                    if self.match_token(&Token::JSX(JSXToken::Colon)) {
+                        if let JSXElementName::Identifier(namespace) = name {
+                            name = JSXElementName::Namespaced {
+                                namespace,
+                                name: self.parse_jsx_identifier()?,
+                            };
+                        }
+                    }
+
+                    let value = if self.match_token(&Token::JSX(JSXToken::Equals)) {
+                        Some(self.parse_jsx_attribute_value()?)
+                    } else {
+                        None
+                    };
+                    attributes.push(JSXAttribute::Attribute {
+                        name,
+                        value,
+                        span: self.span_from(start),
+                    });
+                }
+                _ => return Ok(attributes),
+            }
+        }
+    }
+
+    fn parse_jsx_attribute_value(&mut self) -> Result<JSXAttributeValue, Vec<Error>> {
+        match &self.peek().token {
+            Token::JSX(JSXToken::StringLiteral(value)) => {
+                let value = value.clone();
+                self.advance();
+                Ok(JSXAttributeValue::StringLiteral {
+                    value,
+                    span: self.previous().span,
+                })
+            }
+            Token::JSX(JSXToken::LeftBrace) => {
+                let container = self.parse_jsx_expression_container()?;
+                if let JSXExpressionContainer::Empty { .. } = container {
+                    return Err(vec![self.error(
+                        ErrorKind::SyntaxError,
+                        "JSX attributes must only be assigned a non-empty expression",
+                    )]);
+                }
+                Ok(JSXAttributeValue::ExpressionContainer(container))
+            }
+            Token::JSX(JSXToken::LessThan) => Ok(JSXAttributeValue::Element(Box::new(
+                self.parse_jsx_element()?,
+            ))),
+            Token::JSX(JSXToken::FragmentStart) => Ok(JSXAttributeValue::Fragment(Box::new(
+                self.parse_jsx_fragment()?,
+            ))),
+            _ => Err(vec![self.error(
+                ErrorKind::UnexpectedToken,
+                "Expected JSX attribute value",
+            )]),
+        }
+    }
+
+    fn parse_jsx_expression_container(&mut self) -> Result<JSXExpressionContainer, Vec<Error>> {
+        let start = self.current;
+        self.expect_jsx(JSXToken::LeftBrace, "Expected '{'")?;
+
+        // A container holding only comments, like `{/* note */}`, is empty
+        self.skip_newlines();
+        if self.match_token(&Token::JSX(JSXToken::RightBrace)) {
+            return Ok(JSXExpressionContainer::Empty {
+                span: self.span_from(start),
+            });
+        }
+        let expression = self.expression()?;
+        self.skip_newlines();
+        self.expect_jsx(JSXToken::RightBrace, "Expected '}' after JSX expression")?;
+        Ok(JSXExpressionContainer::Expression {
+            expression: self.ast.alloc_expression(expression),
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_jsx_children(&mut self) -> Result<Vec<JSXChild>, Vec<Error>> {
+        let mut children = Vec::new();
+
+        loop {
+            let start = self.current;
+            let child = match &self.peek().token {
+                Token::JSX(JSXToken::Text(text)) => {
+                    let value = text.clone();
+                    self.advance();
+                    JSXChild::Text {
+                        value,
+                        span: self.previous().span,
+                    }
+                }
+                Token::JSX(JSXToken::LessThan) => {
+                    JSXChild::Element(Box::new(self.parse_jsx_element()?))
+                }
+                Token::JSX(JSXToken::FragmentStart) => {
+                    JSXChild::Fragment(Box::new(self.parse_jsx_fragment()?))
+                }
+                Token::JSX(JSXToken::LeftBrace)
+                    if self.next().token == Token::JSX(JSXToken::Ellipsis) =>
+                {
+                    self.advance(); // Consume '{'
+                    self.advance(); // Consume '...'
+                    let expression = self.expression()?;
+                    self.expect_jsx(JSXToken::RightBrace, "Expected '}' after JSX spread child")?;
+                    JSXChild::Spread {
+                        expression: self.ast.alloc_expression(expression),
+                        span: self.span_from(start),
+                    }
+                }
+                Token::JSX(JSXToken::LeftBrace) => {
+                    JSXChild::ExpressionContainer(self.parse_jsx_expression_container()?)
+                }
+                Token::JSX(JSXToken::LessThanSlash) | Token::JSX(JSXToken::FragmentEnd) => {
+                    return Ok(children);
+                }
+                _ => {
+                    return Err(vec![self.error(
+                        ErrorKind::UnexpectedToken,
+                        "Expected JSX child or closing tag",
+                    )]);
+                }
+            };
+            children.push(child);
+        }
+    }
+
+    fn expect_jsx(&mut self, token: JSXToken, message: &str) -> Result<(), Vec<Error>> {
+        if self.match_token(&Token::JSX(token)) {
+            Ok(())
+        } else {
+            Err(vec![
+                self.error(ErrorKind::UnexpectedToken, message.to_string())
+            ])
+        }
+    }
+}
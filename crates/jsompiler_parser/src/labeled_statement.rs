@@ -0,0 +1,136 @@
+use super::{Atom, Identifier, Parser, Span, Statement, StatementId};
+use crate::{Error, ErrorKind};
+use jsompiler_lexer::symbol::{DelimiterToken, KeywordToken, OperatorToken, Token};
+
+#[derive(Debug, Clone)]
+pub struct LabeledStatement {
+    pub label: Identifier,
+    pub body: Vec<StatementId>,
+    pub span: Span,
+}
+
+#[derive(Debug)]
+pub struct Label {
+    name: Atom,
+    is_iteration: bool, // Only labels of loops can be targeted by `continue`
+}
+
+// Labels and loops enclosing the statement being parsed. Function bodies start a new set.
+#[derive(Debug, Default)]
+pub struct LabelSet {
+    labels: Vec<Label>,
+    pub iteration_depth: usize,
+    pub breakable_depth: usize,
+}
+
+impl Parser {
+    pub fn parse_labeled_statement(&mut self) -> Result<Vec<Statement>, Vec<Error>> {
+        let start = self.current;
+        let label = self.peek_identifier();
+        self.advance(); // Consume the label
+
+        if !self.match_token(&Token::Operator(OperatorToken::Colon)) {
+            return Err(vec![
+                self.error(ErrorKind::UnexpectedToken, "Expected ':' after label")
+            ]);
+        }
+
+        if self
+            .label_set
+            .labels
+            .iter()
+            .any(|existing| existing.name == label.value)
+        {
+            return Err(vec![self.error(
+                ErrorKind::SyntaxError,
+                format!(
+                    "Label '{}' has already been declared",
+                    &self.ast[label.value]
+                ),
+            )]);
+        }
+
+        let is_iteration = self.is_iteration_statement_ahead();
+        self.label_set.labels.push(Label {
+            name: label.value,
+            is_iteration,
+        });
+        let body = self.parse_statement();
+        self.label_set.labels.pop();
+        let body = body?;
+
+        Ok(vec![Statement::LabeledStatement(LabeledStatement {
+            label,
+            body: self.ast.alloc_statements(body),
+            span: self.span_from(start),
+        })])
+    }
+
+    // Parse the body of a loop, where `break` and `continue` are allowed
+    pub fn parse_iteration_body(&mut self) -> Result<Vec<Statement>, Vec<Error>> {
+        self.label_set.iteration_depth += 1;
+        self.label_set.breakable_depth += 1;
+        let body = self.parse_sub_statement();
+        self.label_set.iteration_depth -= 1;
+        self.label_set.breakable_depth -= 1;
+        body
+    }
+
+    // Validate the target of `break` or `continue`
+    pub fn check_jump_target(
+        &self,
+        label: &Option<Identifier>,
+        is_continue: bool,
+    ) -> Result<(), Vec<Error>> {
+        let message = match label {
+            Some(label) => match self
+                .label_set
+                .labels
+                .iter()
+                .rev()
+                .find(|existing| existing.name == label.value)
+            {
+                None => format!("Undefined label '{}'", &self.ast[label.value]),
+                Some(existing) if is_continue && !existing.is_iteration => format!(
+                    "Illegal continue statement: '{}' does not denote an iteration statement",
+                    &self.ast[label.value]
+                ),
+                Some(_) => return Ok(()),
+            },
+            None if is_continue && self.label_set.iteration_depth == 0 => {
+                "Illegal continue statement: no surrounding iteration statement".to_string()
+            }
+            None if !is_continue && self.label_set.breakable_depth == 0 => {
+                "Illegal break statement".to_string()
+            }
+            None => return Ok(()),
+        };
+
+        // At the label, or at the keyword without one
+        Err(vec![self.error_at(
+            self.previous().span,
+            ErrorKind::SyntaxError,
+            message,
+        )])
+    }
+
+    // Looks past any further labels (`a: b: while ...`) for a loop
+    fn is_iteration_statement_ahead(&self) -> bool {
+        let mut index = self.current;
+        loop {
+            match self.tokens.get(index).map(|lexeme| &lexeme.token) {
+                Some(Token::Delimiter(DelimiterToken::NewLine)) => index += 1,
+                Some(Token::Identifier(_))
+                    if self.tokens.get(index + 1).map(|lexeme| &lexeme.token)
+                        == Some(&Token::Operator(OperatorToken::Colon)) =>
+                {
+                    index += 2
+                }
+                Some(Token::Keyword(KeywordToken::For))
+                | Some(Token::Keyword(KeywordToken::While))
+                | Some(Token::Keyword(KeywordToken::Do)) => return true,
+                _ => return false,
+            }
+        }
+    }
+}
@@ -1,83 +1,103 @@
-use super::expression::Expression;
-use super::{Identifier, Parser, Statement};
+use super::{ExpressionId, Parser, Span, Statement};
+use crate::binding_pattern::BindingPattern;
 use crate::{Error, ErrorKind};
 use jsompiler_lexer::symbol::{
-    ContextualKeywordToken, DelimiterToken, KeywordToken, LiteralToken, OperatorToken, Token,
+    ContextualKeywordToken, DelimiterToken, KeywordToken, OperatorToken, Token,
 };
 
 #[derive(Debug, Clone)]
 pub struct LetStatement {
     pub token: Token,
-    pub name: Identifier,
-    pub value: Box<Expression>,
+    pub name: BindingPattern,
+    pub value: Option<ExpressionId>,
+    pub span: Span, // The whole declaration, shared by all of its declarators
+    pub declarator_span: Span,
 }
 
 impl Parser {
     pub fn parse_let_statement(&mut self) -> Result<Vec<Statement>, Vec<Error>> {
+        let start = self.current;
+        let mut declarations = self.parse_variable_declarations()?;
+
+        // Ensure a valid statement terminator
+        if !self.consume_semicolon() {
+            return Err(vec![self.error(
+                ErrorKind::UnexpectedToken,
+                "Expected ';' or newline after variable declaration",
+            )]);
+        }
+
+        let span = self.span_from(start);
+        for declaration in &mut declarations {
+            *declaration.span_mut() = span;
+        }
+        Ok(declarations)
+    }
+
+    // A declaration list without its terminator, as used by `for` headers
+    pub fn parse_variable_declarations(&mut self) -> Result<Vec<Statement>, Vec<Error>> {
+        let start = self.current;
         if !self.match_token(&Token::ContextualKeyword(ContextualKeywordToken::Let))
             && !self.match_token(&Token::Keyword(KeywordToken::Var))
             && !self.match_token(&Token::Keyword(KeywordToken::Const))
         {
-            return Err(vec![Error {
-                error_kind: ErrorKind::UnexpectedToken,
-                message: "Expected 'let', 'var' or 'const'".to_string(),
-                line_number: 1,
-                pos: 2,
-            }]);
+            return Err(vec![self.error(
+                ErrorKind::UnexpectedToken,
+                "Expected 'let', 'var' or 'const'",
+            )]);
         }
 
         let mut declarations = Vec::new();
-        let keyword_token = self.previous().clone(); // Store 'let', 'var', or 'const'
+        let keyword_token = self.previous().token.clone(); // Store 'let', 'var', or 'const'
 
         loop {
-            // Expect an identifier
-            if let Token::Identifier(_) = &self.peek().token {
-                self.advance();
-                let name = self.previous().clone();
-
-                // Check for optional assignment
-                let value = if self.match_token(&Token::Operator(OperatorToken::EqualTo)) {
-                    Some(Box::new(self.expression()?))
-                } else {
-                    None
-                };
+            let declarator_start = self.current;
+            // Expect an identifier or a destructuring pattern
+            let name = match &self.peek().token {
+                Token::Identifier(_)
+                | Token::Delimiter(DelimiterToken::OpenBrace)
+                | Token::Delimiter(DelimiterToken::OpenBracket) => self.parse_binding_pattern()?,
+                _ => {
+                    return Err(vec![self.error(
+                        ErrorKind::UnexpectedToken,
+                        "Expected identifier after 'let'",
+                    )]);
+                }
+            };
 
-                declarations.push(Statement::LetStatement(LetStatement {
-                    token: keyword_token.token.clone(),
-                    name: Identifier {
-                        token: name.clone(),
-                        value: name.text,
-                    },
-                    value: value
-                        .unwrap_or_else(|| Box::new(Expression::Literal(LiteralToken::Undefined))),
-                }));
+            // Check for optional assignment
+            let value = if self.match_token(&Token::Operator(OperatorToken::EqualTo)) {
+                let value = self.expression()?;
+                Some(self.ast.alloc_expression(value))
             } else {
-                return Err(vec![Error {
-                    error_kind: ErrorKind::UnexpectedToken,
-                    message: "Expected identifier after 'let'".to_string(),
-                    line_number: 1,
-                    pos: 2,
-                }]);
+                None
+            };
+
+            if value.is_none() && !matches!(name, BindingPattern::Identifier(_)) {
+                return Err(vec![self.error(
+                    ErrorKind::SyntaxError,
+                    "Missing initializer in destructuring declaration",
+                )]);
             }
 
+            declarations.push(Statement::LetStatement(LetStatement {
+                token: keyword_token.clone(),
+                name,
+                value,
+                span: Span::default(),
+                declarator_span: self.span_from(declarator_start),
+            }));
+
             // Stop if no more comma
             if !self.match_token(&Token::Delimiter(DelimiterToken::Comma)) {
                 break;
             }
         }
 
-        // Ensure a valid statement terminator
-        if !self.match_token(&Token::Delimiter(DelimiterToken::Semicolon))
-            && !self.match_token(&Token::Delimiter(DelimiterToken::NewLine))
-        {
-            return Err(vec![Error {
-                error_kind: ErrorKind::UnexpectedToken,
-                message: "Expected ';' or newline after variable declaration".to_string(),
-                line_number: 1,
-                pos: 2,
-            }]);
+        let span = self.span_from(start);
+        for declaration in &mut declarations {
+            *declaration.span_mut() = span;
         }
-
         Ok(declarations)
     }
 }
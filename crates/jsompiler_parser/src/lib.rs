@@ -1,123 +1,429 @@
 mod assignment_statement;
+mod ast;
+mod binding_pattern;
 mod block_statement;
 mod break_continue_statement;
 mod class_expression;
+mod comment;
+mod debugger_statement;
+mod do_while_statement;
+mod estree;
+mod export_statement;
 mod expression;
+mod for_in_of_statement;
 mod for_loop_statement;
 mod function_expression;
 mod if_statement;
+mod jsx_expression;
+mod labeled_statement;
 mod let_statement;
 mod object_expression;
 mod return_statement;
+mod switch_statement;
 mod template_literal;
+mod test;
+mod throw_statement;
+mod try_statement;
+pub mod visit;
 mod while_statement;
+mod with_statement;
 mod yield_statement;
 
-use assignment_statement::AssignmentStatement;
-use block_statement::BlockStatement;
-use expression::Expression;
-use for_loop_statement::ForLoopStatement;
-use if_statement::IfStatement;
-use let_statement::LetStatement;
-use return_statement::ReturnStatement;
-use while_statement::WhileStatement;
-use yield_statement::YieldStatement;
-
-use jsompiler_common::{Error, ErrorKind};
+use class_expression::PrivateNameScope;
+use labeled_statement::LabelSet;
+
+// The node types of the AST, for crates that read or build it
+pub use assignment_statement::AssignmentStatement;
+pub use binding_pattern::{BindingElement, BindingPattern, BindingProperty, BindingRestElement};
+pub use block_statement::BlockStatement;
+pub use class_expression::{ClassDeclaration, ClassElement, ClassElementName, ClassExpression};
+pub use comment::{Comment, CommentKind, CommentPlacement};
+pub use do_while_statement::DoWhileStatement;
+pub use export_statement::ExportStatement;
+pub use expression::Expression;
+pub use for_in_of_statement::{ForInOfLeft, ForInStatement, ForOfStatement};
+pub use for_loop_statement::ForLoopStatement;
+pub use function_expression::{FunctionDeclaration, FunctionExpression, Parameter};
+pub use if_statement::IfStatement;
+pub use jsx_expression::{
+    JSXAttribute, JSXAttributeValue, JSXChild, JSXClosingElement, JSXElement, JSXElementName,
+    JSXExpressionContainer, JSXFragment, JSXOpeningElement,
+};
+pub use labeled_statement::LabeledStatement;
+pub use let_statement::LetStatement;
+pub use object_expression::{Property, PropertyKey};
+pub use return_statement::ReturnStatement;
+pub use switch_statement::{SwitchCase, SwitchStatement};
+pub use template_literal::{TemplateLiteral, TemplateMiddleList, TemplateSpan};
+pub use throw_statement::ThrowStatement;
+pub use try_statement::{CatchClause, TryStatement};
+pub use while_statement::WhileStatement;
+pub use with_statement::WithStatement;
+pub use yield_statement::YieldStatement;
+
+pub use ast::{Ast, ExpressionId, NodeId, StatementId};
+pub use estree::{from_estree, from_estree_str, to_estree, to_estree_string};
+use jsompiler_common::{Atom, Error, ErrorKind};
+pub use jsompiler_lexer::symbol::Span;
 use jsompiler_lexer::symbol::{
     ContextualKeywordToken, DelimiterToken, KeywordToken, Lexeme, LiteralToken, OperatorToken,
     Token,
 };
+use jsompiler_lexer::Lexer;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Node {
-    Expression(Expression),
-    Statement(Vec<Statement>),
+    Expression(ExpressionId),
+    Statement(Vec<StatementId>),
 }
 
 #[derive(Debug, Clone)]
 pub enum Statement {
     LetStatement(LetStatement),
     ReturnStatement(ReturnStatement),
-    ExpressionStatement(Expression),
+    ExpressionStatement {
+        expression: ExpressionId,
+        span: Span,
+    },
     BlockStatement(BlockStatement),
     IfStatement(IfStatement),
     WhileStatement(WhileStatement),
     AssignmentStatement(AssignmentStatement),
     ForLoopStatement(ForLoopStatement),
     YieldStatement(YieldStatement),
-    BreakStatement { label: Option<Expression> },
-    ContinueStatement { label: Option<Expression> },
+    FunctionDeclaration(FunctionDeclaration),
+    ClassDeclaration(ClassDeclaration),
+    ExportStatement(ExportStatement),
+    LabeledStatement(LabeledStatement),
+    DoWhileStatement(DoWhileStatement),
+    ForInStatement(ForInStatement),
+    ForOfStatement(ForOfStatement),
+    SwitchStatement(SwitchStatement),
+    ThrowStatement(ThrowStatement),
+    TryStatement(TryStatement),
+    WithStatement(WithStatement),
+    EmptyStatement {
+        span: Span,
+    },
+    DebuggerStatement {
+        span: Span,
+    },
+    BreakStatement {
+        label: Option<Identifier>,
+        span: Span,
+    },
+    ContinueStatement {
+        label: Option<Identifier>,
+        span: Span,
+    },
+    Error {
+        span: Span,
+    }, // Placeholder for a statement that failed to parse
 }
 
-#[derive(Debug, Clone)]
+impl Statement {
+    pub fn span(&self) -> Span {
+        match self {
+            Statement::LetStatement(statement) => statement.span,
+            Statement::ReturnStatement(statement) => statement.span,
+            Statement::BlockStatement(statement) => statement.span,
+            Statement::IfStatement(statement) => statement.span,
+            Statement::WhileStatement(statement) => statement.span,
+            Statement::AssignmentStatement(statement) => statement.span,
+            Statement::ForLoopStatement(statement) => statement.span,
+            Statement::YieldStatement(statement) => statement.span,
+            Statement::FunctionDeclaration(statement) => statement.span,
+            Statement::ClassDeclaration(statement) => statement.span,
+            Statement::ExportStatement(statement) => statement.span(),
+            Statement::LabeledStatement(statement) => statement.span,
+            Statement::DoWhileStatement(statement) => statement.span,
+            Statement::ForInStatement(statement) => statement.span,
+            Statement::ForOfStatement(statement) => statement.span,
+            Statement::SwitchStatement(statement) => statement.span,
+            Statement::ThrowStatement(statement) => statement.span,
+            Statement::TryStatement(statement) => statement.span,
+            Statement::WithStatement(statement) => statement.span,
+            Statement::ExpressionStatement { span, .. }
+            | Statement::EmptyStatement { span }
+            | Statement::DebuggerStatement { span }
+            | Statement::BreakStatement { span, .. }
+            | Statement::ContinueStatement { span, .. }
+            | Statement::Error { span } => *span,
+        }
+    }
+
+    pub fn span_mut(&mut self) -> &mut Span {
+        match self {
+            Statement::LetStatement(statement) => &mut statement.span,
+            Statement::ReturnStatement(statement) => &mut statement.span,
+            Statement::BlockStatement(statement) => &mut statement.span,
+            Statement::IfStatement(statement) => &mut statement.span,
+            Statement::WhileStatement(statement) => &mut statement.span,
+            Statement::AssignmentStatement(statement) => &mut statement.span,
+            Statement::ForLoopStatement(statement) => &mut statement.span,
+            Statement::YieldStatement(statement) => &mut statement.span,
+            Statement::FunctionDeclaration(statement) => &mut statement.span,
+            Statement::ClassDeclaration(statement) => &mut statement.span,
+            Statement::ExportStatement(statement) => statement.span_mut(),
+            Statement::LabeledStatement(statement) => &mut statement.span,
+            Statement::DoWhileStatement(statement) => &mut statement.span,
+            Statement::ForInStatement(statement) => &mut statement.span,
+            Statement::ForOfStatement(statement) => &mut statement.span,
+            Statement::SwitchStatement(statement) => &mut statement.span,
+            Statement::ThrowStatement(statement) => &mut statement.span,
+            Statement::TryStatement(statement) => &mut statement.span,
+            Statement::WithStatement(statement) => &mut statement.span,
+            Statement::ExpressionStatement { span, .. }
+            | Statement::EmptyStatement { span }
+            | Statement::DebuggerStatement { span }
+            | Statement::BreakStatement { span, .. }
+            | Statement::ContinueStatement { span, .. }
+            | Statement::Error { span } => span,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
 pub struct Identifier {
-    pub token: Lexeme,
-    pub value: String,
+    pub value: Atom,
+    pub span: Span,
+}
+
+impl Identifier {
+    pub fn new(value: Atom, span: Span) -> Self {
+        Self { value, span }
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+
+// Syntax extensions the parser accepts on top of ECMAScript
+#[derive(Debug, Clone, Default)]
+pub struct ParserOptions {
+    pub jsx: bool,
 }
 
 pub struct Parser {
+    options: ParserOptions,
     tokens: Vec<Lexeme>,
+    lexer: Option<Lexer>, // Lexes JSX again where the parser finds it, see `with_lexer`
     current: usize,
+    strict_mode: bool,
+    private_name_scopes: Vec<PrivateNameScope>,
+    label_set: LabelSet,
+    line_breaks: Vec<usize>, // Where comments are attached depends on which line they are on
+    line_starts: Vec<usize>, // Where every line after the first starts, for error positions
     pub errors: Vec<Error>,
-    pub ast: Vec<Node>,
+    pub lexer_errors: Vec<Error>, // What the lexer reported for the tokens that were parsed
+    pub ast: Ast,
 }
 
 impl Parser {
     pub fn new(tokens: Vec<Lexeme>) -> Self {
+        Self::with_options(tokens, ParserOptions::default())
+    }
+
+    // Comments are taken out of `tokens` before parsing and kept on the `Ast`
+    pub fn with_options(tokens: Vec<Lexeme>, options: ParserOptions) -> Self {
+        let line_starts = line_starts(&tokens);
+        let (tokens, trivia) = comment::split_trivia(tokens);
+        let mut ast = Ast::new();
+        ast.comments = trivia.comments;
+        ast.hashbang = trivia.hashbang;
         Self {
+            options,
             tokens,
+            lexer: None,
             current: 0,
+            strict_mode: false,
+            private_name_scopes: Vec::new(),
+            label_set: LabelSet::default(),
+            line_breaks: trivia.line_breaks,
+            line_starts,
             errors: Vec::new(),
-            ast: Vec::new(),
+            lexer_errors: Vec::new(),
+            ast,
+        }
+    }
+
+    // Parse the tokens of a lexer that has scanned its source. A `<` is always lexed as an
+    // operator, so where the parser expects an expression and finds one it has the lexer lex
+    // the source again from there as JSX. Lexer errors are only known after parsing, as
+    // lexing JSX as JavaScript can give errors that lexing it as JSX does not.
+    pub fn with_lexer(mut lexer: Lexer, options: ParserOptions) -> Self {
+        let tokens = std::mem::take(&mut lexer.tokens);
+        Self {
+            lexer: Some(lexer),
+            ..Self::with_options(tokens, options)
         }
     }
 
+    // Replace the tokens from `index` on with `tokens`, lexed again from the same position,
+    // and the comments and line breaks among the old ones with those among the new ones
+    fn replace_tokens_from(&mut self, index: usize, tokens: Vec<Lexeme>) {
+        let position = self.tokens[index].span.start;
+        self.line_starts.retain(|&start| start <= position);
+        self.line_starts.extend(line_starts(&tokens));
+        let (tokens, trivia) = comment::split_trivia(tokens);
+        self.tokens.truncate(index);
+        self.tokens.extend(tokens);
+        self.ast
+            .comments
+            .retain(|comment| comment.span.start < position);
+        self.ast.comments.extend(trivia.comments);
+        self.line_breaks.retain(|&start| start < position);
+        self.line_breaks.extend(trivia.line_breaks);
+    }
+
     pub fn parse(&mut self) {
+        self.strict_mode = self.has_use_strict_directive(0);
         while !self.is_at_end() {
             match &self.peek().token {
-                Token::Delimiter(DelimiterToken::Semicolon)
-                | Token::Delimiter(DelimiterToken::NewLine)
-                | Token::Delimiter(DelimiterToken::CloseBrace) => {
+                Token::Delimiter(DelimiterToken::NewLine) => {
+                    self.advance();
+                    continue;
+                }
+                // Nothing is open at the top level for a `}` to close
+                Token::Delimiter(DelimiterToken::CloseBrace) => {
+                    let error = self.error(ErrorKind::UnexpectedToken, "Unexpected token '}'");
+                    self.errors.push(error);
                     self.advance();
+                    continue;
                 }
                 Token::EOF => break,
                 _ => {}
             }
+            let start = self.current;
             match self.parse_statement() {
                 Ok(statement) => {
-                    self.ast.push(Node::Statement(statement));
+                    let statement = self.ast.alloc_statements(statement);
+                    self.ast.body.push(Node::Statement(statement));
                 }
                 Err(errors) => {
+                    // Record the errors and keep going from the next statement boundary
                     self.errors.extend(errors);
-                    break;
+                    self.synchronize(start);
+                    // A `}` recovery stopped at closes a block of the failed statement
+                    if self.peek().token == Token::Delimiter(DelimiterToken::CloseBrace) {
+                        self.advance();
+                    }
+                    let statement = self.ast.alloc_statement(Statement::Error {
+                        span: self.span_from(start),
+                    });
+                    self.ast.body.push(Node::Statement(vec![statement]));
+                }
+            }
+        }
+        comment::attach_comments(&mut self.ast, &self.line_breaks);
+
+        // The lexer is built against the published `jsompiler_common`, so its errors are
+        // converted
+        if let Some(lexer) = &self.lexer {
+            self.lexer_errors = lexer
+                .errors
+                .iter()
+                .map(|error| {
+                    Error::new(
+                        ErrorKind::LexerError,
+                        error.message.clone(),
+                        error.line_number,
+                        error.pos,
+                    )
+                })
+                .collect();
+        }
+    }
+
+    // Panic-mode recovery: skip tokens until a point where a new statement can start.
+    // Stops after a `;` or line break, before a statement keyword, or before a `}` that
+    // closes the enclosing block. Braces opened while skipping are skipped as a whole.
+    fn synchronize(&mut self, start: usize) {
+        if self.current == start {
+            self.advance(); // Always make progress past the offending token
+        }
+
+        let mut depth = 0;
+        while !self.is_at_end() {
+            match self.peek().token {
+                Token::Delimiter(DelimiterToken::OpenBrace) => depth += 1,
+                Token::Delimiter(DelimiterToken::CloseBrace) if depth == 0 => return,
+                Token::Delimiter(DelimiterToken::CloseBrace) => depth -= 1,
+                Token::Delimiter(DelimiterToken::Semicolon)
+                | Token::Delimiter(DelimiterToken::NewLine)
+                    if depth == 0 =>
+                {
+                    self.advance();
+                    return;
+                }
+                Token::ContextualKeyword(ContextualKeywordToken::Let)
+                | Token::Keyword(KeywordToken::Var)
+                | Token::Keyword(KeywordToken::Const)
+                | Token::Keyword(KeywordToken::Function)
+                | Token::Keyword(KeywordToken::Class)
+                | Token::Keyword(KeywordToken::If)
+                | Token::Keyword(KeywordToken::For)
+                | Token::Keyword(KeywordToken::While)
+                | Token::Keyword(KeywordToken::Do)
+                | Token::Keyword(KeywordToken::Return)
+                | Token::Keyword(KeywordToken::Switch)
+                | Token::Keyword(KeywordToken::Throw)
+                | Token::Keyword(KeywordToken::Try)
+                    if depth == 0 =>
+                {
+                    return;
                 }
+                _ => {}
             }
+            self.advance();
         }
     }
 
     fn parse_statement(&mut self) -> Result<Vec<Statement>, Vec<Error>> {
         match &self.peek().token {
-            Token::Delimiter(DelimiterToken::Semicolon)
-            | Token::Delimiter(DelimiterToken::NewLine) => {
+            Token::Delimiter(DelimiterToken::NewLine) => {
                 self.advance();
                 self.parse_statement()
             }
+            Token::Delimiter(DelimiterToken::Semicolon) => {
+                self.advance();
+                Ok(vec![Statement::EmptyStatement {
+                    span: self.previous().span,
+                }])
+            }
+            // Statements starting with `function`, `async function` or `class` are always
+            // declarations, never expression statements
+            Token::Keyword(KeywordToken::Function) => self.parse_function_declaration(true),
+            Token::ContextualKeyword(ContextualKeywordToken::Async)
+                if self.next().token == Token::Keyword(KeywordToken::Function) =>
+            {
+                self.parse_function_declaration(true)
+            }
+            Token::Keyword(KeywordToken::Class) => self.parse_class_declaration(true),
+            Token::Keyword(KeywordToken::Export) => self.parse_export_statement(),
             Token::Literal(_)
             | Token::Operator(_)
-            | Token::Keyword(KeywordToken::Class)
-            | Token::Keyword(KeywordToken::Function)
             | Token::ContextualKeyword(ContextualKeywordToken::Await)
             | Token::ContextualKeyword(ContextualKeywordToken::Async)
+            | Token::ContextualKeyword(ContextualKeywordToken::Eval)
+            | Token::ContextualKeyword(ContextualKeywordToken::Arguments)
             | Token::Delimiter(DelimiterToken::OpenParen)
             | Token::Delimiter(DelimiterToken::Tilde)
             | Token::Keyword(KeywordToken::True)
             | Token::Keyword(KeywordToken::False)
-            | Token::Delimiter(DelimiterToken::OpenBracket) => self.parse_expression(),
+            | Token::Keyword(KeywordToken::Delete)
+            | Token::Keyword(KeywordToken::Typeof)
+            | Token::Keyword(KeywordToken::Void)
+            | Token::Delimiter(DelimiterToken::OpenBracket)
+            | Token::JSX(_) => self.parse_expression(),
             Token::RegExp {
                 pattern: _,
                 flags: _,
             } => self.parse_expression(),
+            Token::Identifier(_) if self.next().token == Token::Operator(OperatorToken::Colon) => {
+                self.parse_labeled_statement()
+            }
             Token::Identifier(_) | Token::Keyword(KeywordToken::This) => {
                 self.parse_assignment_statement()
             }
@@ -127,22 +433,56 @@ impl Parser {
             Token::Keyword(KeywordToken::Return) => self.parse_return_statement(),
             Token::Keyword(KeywordToken::If) => self.parse_if_statement(),
             Token::Keyword(KeywordToken::While) => self.parse_while_statement(),
+            Token::Keyword(KeywordToken::Do) => self.parse_do_while_statement(),
             Token::Keyword(KeywordToken::For) => self.parser_for_loop_statement(),
+            Token::Keyword(KeywordToken::Switch) => self.parse_switch_statement(),
+            Token::Keyword(KeywordToken::Throw) => self.parse_throw_statement(),
+            Token::Keyword(KeywordToken::Try) => self.parse_try_statement(),
+            Token::Keyword(KeywordToken::Debugger) => self.parse_debugger_statement(),
+            Token::ContextualKeyword(ContextualKeywordToken::With) => self.parse_with_statement(),
             Token::ContextualKeyword(ContextualKeywordToken::Yield) => self.parse_yield_statement(),
-            Token::Delimiter(DelimiterToken::OpenBrace) => self.parse_brace_block_or_object(),
+            // A statement starting with `{` is always a block, never an object literal
+            Token::Delimiter(DelimiterToken::OpenBrace) => self.parse_block_statement(),
             Token::Keyword(KeywordToken::Break) | Token::Keyword(KeywordToken::Continue) => {
                 self.parse_break_or_continue_statement()
             }
             Token::EOF => Ok(vec![]),
-            _ => Err(vec![Error {
-                error_kind: ErrorKind::UnexpectedToken,
-                message: "Expected statement".to_string(),
-                line_number: 1,
-                pos: 2,
-            }]),
+            _ => Err(vec![
+                self.error(ErrorKind::UnexpectedToken, "Expected statement")
+            ]),
         }
     }
 
+    // Parse the single statement body of `if`, loops and `with`, where a leading `{` is
+    // always a block
+    fn parse_sub_statement(&mut self) -> Result<Vec<Statement>, Vec<Error>> {
+        self.skip_newlines();
+        if self.peek().token == Token::Delimiter(DelimiterToken::OpenBrace) {
+            self.parse_block_statement()
+        } else {
+            self.parse_statement()
+        }
+    }
+
+    // Span from the token at `start` to the last token consumed, leaving out line breaks
+    // at either end
+    fn span_from(&self, start: usize) -> Span {
+        let mut start = start;
+        let mut end = self.current;
+        while end > start && self.tokens[end - 1].token == Token::Delimiter(DelimiterToken::NewLine)
+        {
+            end -= 1;
+        }
+        while start < end && self.tokens[start].token == Token::Delimiter(DelimiterToken::NewLine) {
+            start += 1;
+        }
+        if start == end {
+            let position = self.tokens[start].span.start;
+            return Span::new(position, position);
+        }
+        Span::new(self.tokens[start].span.start, self.tokens[end - 1].span.end)
+    }
+
     fn match_operator(&mut self, operators: &[OperatorToken]) -> Option<OperatorToken> {
         for op in operators {
             if self.match_token(&Token::Operator(op.clone())) {
@@ -167,10 +507,14 @@ impl Parser {
                 self.advance();
                 Some(LiteralToken::Boolean(false))
             }
-            Token::Literal(LiteralToken::Null) => {
+            Token::Literal(LiteralToken::Null) | Token::Keyword(KeywordToken::Null) => {
                 self.advance();
                 Some(LiteralToken::Null)
             }
+            Token::Literal(LiteralToken::Undefined) => {
+                self.advance();
+                Some(LiteralToken::Undefined)
+            }
             Token::Literal(LiteralToken::String(value)) => {
                 self.advance();
                 Some(LiteralToken::String(value.clone()))
@@ -194,25 +538,169 @@ impl Parser {
         self.peek().token == *token
     }
 
+    // Consume a statement terminator, inserting a semicolon automatically before a
+    // line break, a closing brace or the end of input
+    fn consume_semicolon(&mut self) -> bool {
+        if self.match_token(&Token::Delimiter(DelimiterToken::Semicolon))
+            || self.match_token(&Token::Delimiter(DelimiterToken::NewLine))
+        {
+            return true;
+        }
+        self.previous().token == Token::Delimiter(DelimiterToken::NewLine)
+            || self.check(&Token::Delimiter(DelimiterToken::CloseBrace))
+            || self.is_at_end()
+    }
+
+    fn skip_newlines(&mut self) {
+        while self.peek().token == Token::Delimiter(DelimiterToken::NewLine) {
+            self.advance();
+        }
+    }
+
     fn previous(&self) -> &Lexeme {
         &self.tokens[self.current - 1]
     }
 
     fn next(&self) -> &Lexeme {
-        &self.tokens[self.current + 1]
+        let index = self.peek_index();
+        &self.tokens[self.visible_index(index, index + 1)]
     }
 
     fn advance(&mut self) {
         if !self.is_at_end() {
-            self.current += 1;
+            self.current = self.peek_index() + 1;
         }
     }
 
     fn peek(&self) -> &Lexeme {
-        &self.tokens[self.current]
+        &self.tokens[self.peek_index()]
+    }
+
+    // The identifier made of the lexeme just consumed
+    fn previous_identifier(&mut self) -> Identifier {
+        self.identifier_at(self.current - 1)
+    }
+
+    // The identifier made of the lexeme being looked at
+    fn peek_identifier(&mut self) -> Identifier {
+        self.identifier_at(self.peek_index())
+    }
+
+    fn identifier_at(&mut self, index: usize) -> Identifier {
+        let lexeme = &self.tokens[index];
+        Identifier::new(self.ast.atoms.intern(&lexeme.text), lexeme.span)
+    }
+
+    // An error at the token being looked at
+    fn error(&self, error_kind: ErrorKind, message: impl Into<String>) -> Error {
+        self.error_at(self.peek().span, error_kind, message)
+    }
+
+    // An error at the start of `span`, with its line and its column counted from 0
+    fn error_at(&self, span: Span, error_kind: ErrorKind, message: impl Into<String>) -> Error {
+        let line = self
+            .line_starts
+            .partition_point(|&start| start <= span.start);
+        let line_start = line
+            .checked_sub(1)
+            .map_or(0, |index| self.line_starts[index]);
+        Error {
+            error_kind,
+            message: message.into(),
+            line_number: line + 1,
+            pos: span.start - line_start,
+        }
     }
 
     fn is_at_end(&self) -> bool {
-        self.tokens[self.current].token == Token::EOF
+        self.peek().token == Token::EOF
+    }
+
+    fn peek_index(&self) -> usize {
+        match self.current.checked_sub(1) {
+            Some(previous) => self.visible_index(previous, self.current),
+            None => self.current,
+        }
+    }
+
+    // A line break can't end a statement after a token like `+`, `,` or `(`, nor before one
+    // like `)` or `.`, so there the line breaks from `index` on are skipped
+    fn visible_index(&self, previous: usize, index: usize) -> usize {
+        let mut visible = index;
+        while self.tokens[visible].token == Token::Delimiter(DelimiterToken::NewLine) {
+            visible += 1;
+        }
+        if visible != index
+            && (continues_after(&self.tokens[previous].token)
+                || continues_before(&self.tokens[visible].token))
+        {
+            visible
+        } else {
+            index
+        }
+    }
+}
+
+// Character offsets where every line after the first starts. Line breaks are found in the
+// text of every token, since comments, strings and templates can span lines too.
+fn line_starts(tokens: &[Lexeme]) -> Vec<usize> {
+    let mut starts = Vec::new();
+    for lexeme in tokens {
+        let mut chars = lexeme.text.chars().enumerate().peekable();
+        while let Some((index, c)) = chars.next() {
+            let is_break = match c {
+                '\n' | '\u{2028}' | '\u{2029}' => true,
+                '\r' => chars.peek().is_none_or(|&(_, next)| next != '\n'),
+                _ => false,
+            };
+            if is_break {
+                starts.push(lexeme.span.start + index + 1);
+            }
+        }
+    }
+    starts
+}
+
+fn continues_after(token: &Token) -> bool {
+    match token {
+        Token::Operator(operator) => !matches!(
+            operator,
+            OperatorToken::Increment | OperatorToken::Decrement
+        ),
+        Token::Delimiter(delimiter) => matches!(
+            delimiter,
+            DelimiterToken::OpenParen
+                | DelimiterToken::OpenBracket
+                | DelimiterToken::Comma
+                | DelimiterToken::Dot
+        ),
+        _ => false,
+    }
+}
+
+// Tokens a statement can't start with. `+`, `-` and the like are left out, as they can start
+// one in this parser.
+fn continues_before(token: &Token) -> bool {
+    match token {
+        Token::Operator(operator) => !matches!(
+            operator,
+            OperatorToken::Plus
+                | OperatorToken::Minus
+                | OperatorToken::Not
+                | OperatorToken::BitwiseNot
+                | OperatorToken::Increment
+                | OperatorToken::Decrement
+                | OperatorToken::Spread
+                | OperatorToken::Arrow
+                | OperatorToken::Colon
+        ),
+        Token::Delimiter(delimiter) => matches!(
+            delimiter,
+            DelimiterToken::CloseParen
+                | DelimiterToken::CloseBracket
+                | DelimiterToken::Comma
+                | DelimiterToken::Dot
+        ),
+        _ => false,
     }
 }
@@ -1,46 +1,66 @@
-use crate::{expression::Expression, Identifier, Parser, Statement};
+use crate::function_expression::Parameter;
+use crate::{expression::Expression, Ast, ExpressionId, Identifier, Parser, Span, StatementId};
 use crate::{Error, ErrorKind};
 use jsompiler_lexer::symbol::{
-    ContextualKeywordToken, DelimiterToken, KeywordToken, LiteralToken, NumberLiteral,
-    OperatorToken, Token,
+    ContextualKeywordToken, DelimiterToken, LiteralToken, NumberLiteral, OperatorToken, Token,
 };
 
 // Enhanced Property types for object literals
 #[derive(Debug, Clone)]
 pub enum PropertyKey {
-    Identifier(String),
-    StringLiteral(String),
-    NumberLiteral(NumberLiteral),
-    Computed(Box<Expression>), // For computed properties like { [expr]: value }
+    Identifier(Identifier),
+    StringLiteral { value: String, span: Span },
+    NumberLiteral { value: NumberLiteral, span: Span },
+    Computed(ExpressionId), // For computed properties like { [expr]: value }
 }
 
+impl PropertyKey {
+    pub fn span(&self, ast: &Ast) -> Span {
+        match self {
+            PropertyKey::Identifier(identifier) => identifier.span(),
+            PropertyKey::StringLiteral { span, .. } | PropertyKey::NumberLiteral { span, .. } => {
+                *span
+            }
+            PropertyKey::Computed(expression) => ast[*expression].span(),
+        }
+    }
+}
+
+// `function_span` covers a method's parameters and body, the part that is a function
 #[derive(Debug, Clone)]
 pub enum Property {
     KeyValue {
         key: PropertyKey,
-        value: Box<Expression>,
+        value: ExpressionId,
+        span: Span,
     },
-    Shorthand(String), // For shorthand properties like { x } instead of { x: x }
+    Shorthand(Identifier), // For shorthand properties like { x } instead of { x: x }
     Method {
         key: PropertyKey,
-        function: Expression,
+        params: Vec<Parameter>,
+        body: Vec<StatementId>,
+        span: Span,
+        function_span: Span,
     },
     Getter {
         key: PropertyKey,
-        body: Statement,
+        body: StatementId,
+        span: Span,
+        function_span: Span,
     },
     Setter {
         key: PropertyKey,
-        param: Identifier,
-        body: Statement,
+        param: Parameter,
+        body: StatementId,
+        span: Span,
+        function_span: Span,
     },
-    Spread(Expression), // For spread properties like { ...obj }
+    Spread(ExpressionId), // For spread properties like { ...obj }, always a spread element
 }
 
 impl Parser {
     pub fn parse_object_expression(&mut self) -> Result<Expression, Vec<Error>> {
-        println!("Parsing object literal");
-        let _start = self.current;
+        let start = self.current;
         self.advance(); // Consume '{'
 
         let mut properties = Vec::new();
@@ -66,48 +86,44 @@ impl Parser {
             if self.peek().token == Token::Delimiter(DelimiterToken::Comma) {
                 self.advance();
             } else if self.peek().token != Token::Delimiter(DelimiterToken::CloseBrace) {
-                return Err(vec![Error {
-                    error_kind: ErrorKind::SyntaxError,
-                    message: "Expected ',' or '}'".to_string(),
-                    line_number: 1,
-                    pos: 2,
-                }]);
+                return Err(vec![
+                    self.error(ErrorKind::SyntaxError, "Expected ',' or '}'")
+                ]);
             }
         }
 
         // Consume closing brace
         if self.peek().token != Token::Delimiter(DelimiterToken::CloseBrace) {
-            return Err(vec![Error {
-                error_kind: ErrorKind::SyntaxError,
-                message: "Expected '}'".to_string(),
-                line_number: 1,
-                pos: 2,
-            }]);
+            return Err(vec![self.error(ErrorKind::SyntaxError, "Expected '}'")]);
         }
         self.advance();
 
-        Ok(Expression::ObjectLiteral { properties })
+        Ok(Expression::ObjectLiteral {
+            properties,
+            span: self.span_from(start),
+        })
     }
 
     fn parse_object_property(&mut self) -> Result<Property, Vec<Error>> {
         // println!("Parsing object property");
+        let start = self.current;
         // Check for getter/setter methods
         if self.peek().token == Token::ContextualKeyword(ContextualKeywordToken::Get)
             || self.peek().token == Token::ContextualKeyword(ContextualKeywordToken::Set)
         {
-            return self.parse_accessor_property();
+            return self.parse_accessor_property(start);
         }
 
         if self.peek().token == Token::Operator(OperatorToken::Spread) {
             let expr = self.expression()?;
-            return Ok(Property::Spread(expr));
+            return Ok(Property::Spread(self.ast.alloc_expression(expr)));
         }
         // Parse the property key
         let key = self.parse_property_key()?;
 
         // Check if it's a method
         if self.peek().token == Token::Delimiter(DelimiterToken::OpenParen) {
-            return self.parse_method_property(key);
+            return self.parse_method_property(start, key);
         }
 
         // Check if it's a shorthand property (no colon)
@@ -116,18 +132,15 @@ impl Parser {
                 && self.peek().token == Token::Delimiter(DelimiterToken::Comma)
             {
                 // It's a shorthand property like { name }
-                return Ok(Property::Shorthand(name.clone()));
+                return Ok(Property::Shorthand(*name));
             }
         }
 
         // Regular key-value property
         if self.peek().token != Token::Operator(OperatorToken::Colon) {
-            return Err(vec![Error {
-                error_kind: ErrorKind::SyntaxError,
-                message: "Expected ':' after property name".to_string(),
-                line_number: 1,
-                pos: 2,
-            }]);
+            return Err(vec![
+                self.error(ErrorKind::SyntaxError, "Expected ':' after property name")
+            ]);
         }
         self.advance(); // Consume ':'
 
@@ -135,201 +148,152 @@ impl Parser {
 
         Ok(Property::KeyValue {
             key,
-            value: Box::new(value),
+            value: self.ast.alloc_expression(value),
+            span: self.span_from(start),
         })
     }
 
-    fn parse_property_key(&mut self) -> Result<PropertyKey, Vec<Error>> {
+    pub fn parse_property_key(&mut self) -> Result<PropertyKey, Vec<Error>> {
         match &self.peek().token {
-            Token::Identifier(name) => {
-                let key_name = name.clone();
+            Token::Identifier(_) => {
+                let key = self.peek_identifier();
                 self.advance();
-                Ok(PropertyKey::Identifier(key_name))
+                Ok(PropertyKey::Identifier(key))
             }
             Token::Literal(LiteralToken::String(s)) => {
                 let key_str = s.clone();
                 self.advance();
-                Ok(PropertyKey::StringLiteral(key_str))
+                Ok(PropertyKey::StringLiteral {
+                    value: key_str,
+                    span: self.previous().span,
+                })
             }
             Token::Literal(LiteralToken::Number(n)) => {
                 let key_num = n.clone();
                 self.advance();
-                Ok(PropertyKey::NumberLiteral(key_num))
+                Ok(PropertyKey::NumberLiteral {
+                    value: key_num,
+                    span: self.previous().span,
+                })
             }
             Token::Delimiter(DelimiterToken::OpenBracket) => {
                 self.advance(); // Consume '['
                 let expr = self.expression()?;
                 if self.peek().token != Token::Delimiter(DelimiterToken::CloseBracket) {
-                    return Err(vec![Error {
-                        error_kind: ErrorKind::SyntaxError,
-                        message: "Expected ']'".to_string(),
-                        line_number: 1,
-                        pos: 2,
-                    }]);
+                    return Err(vec![self.error(ErrorKind::SyntaxError, "Expected ']'")]);
                 }
                 self.advance(); // Consume ']'
-                Ok(PropertyKey::Computed(Box::new(expr)))
+                Ok(PropertyKey::Computed(self.ast.alloc_expression(expr)))
+            }
+            _ => {
+                Err(vec![self.error(
+                    ErrorKind::UnexpectedToken,
+                    "Expected property name",
+                )])
             }
-            _ => Err(vec![Error {
-                error_kind: ErrorKind::UnexpectedToken,
-                message: "Expected property name".to_string(),
-                line_number: 1,
-                pos: 2,
-            }]),
         }
     }
 
-    fn parse_method_property(&mut self, key: PropertyKey) -> Result<Property, Vec<Error>> {
-        // Consume '('
-        self.advance();
-
+    fn parse_method_property(
+        &mut self,
+        start: usize,
+        key: PropertyKey,
+    ) -> Result<Property, Vec<Error>> {
         // Parse parameters
-        let function = self.parse_function_expression()?;
-        Ok(Property::Method { key, function })
+        let function_start = self.current;
+        let params = self.parse_function_parameters()?;
+        let body = self.parse_function_body_block(&params, true)?;
+        Ok(Property::Method {
+            key,
+            params,
+            body: self.ast.alloc_statements(body),
+            span: self.span_from(start),
+            function_span: self.span_from(function_start),
+        })
     }
 
-    fn parse_accessor_property(&mut self) -> Result<Property, Vec<Error>> {
+    fn parse_accessor_property(&mut self, start: usize) -> Result<Property, Vec<Error>> {
         let is_getter = self.peek().token == Token::ContextualKeyword(ContextualKeywordToken::Get);
         self.advance(); // Consume 'get' or 'set'
 
         let key = self.parse_property_key()?;
-        println!("Parsed accessor property key: {:?}", key);
 
         if self.peek().token != Token::Delimiter(DelimiterToken::OpenParen) {
-            return Err(vec![Error {
-                error_kind: ErrorKind::SyntaxError,
-                message: "Expected '(' after accessor name".to_string(),
-                line_number: 1,
-                pos: 2,
-            }]);
+            return Err(vec![
+                self.error(ErrorKind::SyntaxError, "Expected '(' after accessor name")
+            ]);
         }
+        let function_start = self.current;
         self.advance(); // Consume '('
 
         if is_getter {
             // Getter should have no parameters
             if self.peek().token != Token::Delimiter(DelimiterToken::CloseParen) {
-                return Err(vec![Error {
-                    error_kind: ErrorKind::UnexpectedToken,
-                    message: "Getter should not have parameters".to_string(),
-                    line_number: 1,
-                    pos: 2,
-                }]);
+                return Err(vec![self.error(
+                    ErrorKind::UnexpectedToken,
+                    "Getter should not have parameters",
+                )]);
             }
             self.advance(); // Consume ')'
 
             let body = self.parse_function_body()?;
-            println!("Parsed getter body: {:#?}", body);
-            Ok(Property::Getter { key, body })
+            Ok(Property::Getter {
+                key,
+                body,
+                span: self.span_from(start),
+                function_span: self.span_from(function_start),
+            })
         } else {
             // Setter should have exactly one parameter
             match &self.peek().token {
-                Token::Identifier(_) => { /* OK, it's an identifier */ }
+                Token::Identifier(_)
+                | Token::Delimiter(DelimiterToken::OpenBrace)
+                | Token::Delimiter(DelimiterToken::OpenBracket) => { /* OK, it's a binding */ }
                 _ => {
-                    return Err(vec![Error {
-                        error_kind: ErrorKind::UnexpectedToken,
-                        message: "Expected parameter name for setter".to_string(),
-                        line_number: 1,
-                        pos: 2,
-                    }]);
+                    return Err(vec![self.error(
+                        ErrorKind::UnexpectedToken,
+                        "Expected parameter name for setter",
+                    )]);
                 }
             }
 
-            let param_name = match &self.peek().token {
-                Token::Identifier(name) => Identifier {
-                    token: self.peek().clone(),
-                    value: name.to_string(),
-                },
-                _ => unreachable!(), // We already checked it's an identifier
-            };
-            self.advance(); // Consume parameter name
+            let param = self.parse_parameter()?;
+            self.check_duplicate_parameters(std::slice::from_ref(&param), true)?;
 
             if self.peek().token != Token::Delimiter(DelimiterToken::CloseParen) {
-                return Err(vec![Error {
-                    error_kind: ErrorKind::UnexpectedToken,
-                    message: "Expected ')' after setter parameter".to_string(),
-                    line_number: 1,
-                    pos: 2,
-                }]);
+                return Err(vec![self.error(
+                    ErrorKind::UnexpectedToken,
+                    "Expected ')' after setter parameter",
+                )]);
             }
             self.advance(); // Consume ')'
 
             let body = self.parse_function_body()?;
             Ok(Property::Setter {
                 key,
-                param: param_name,
+                param,
                 body,
+                span: self.span_from(start),
+                function_span: self.span_from(function_start),
             })
         }
     }
 
-    fn parse_function_body(&mut self) -> Result<Statement, Vec<Error>> {
-        println!("Parsing function body");
+    fn parse_function_body(&mut self) -> Result<StatementId, Vec<Error>> {
         if self.peek().token != Token::Delimiter(DelimiterToken::OpenBrace) {
-            return Err(vec![Error {
-                error_kind: ErrorKind::SyntaxError,
-                message: "Expected '{' to start function body".to_string(),
-                line_number: 1,
-                pos: 2,
-            }]);
-        }
-
-        let sstms = self.parse_block_statement()?;
-        match sstms.first() {
-            Some(block_stmt) => return Ok(block_stmt.clone()),
-            None => Err(vec![Error {
-                error_kind: ErrorKind::UnexpectedToken,
-                message: "Expected a block statement in function body".to_string(),
-                line_number: 1,
-                pos: 2,
-            }]),
-        }
-    }
-
-    // Add this helper method to recognize object literals vs block statements
-    pub fn parse_brace_block_or_object(&mut self) -> Result<Vec<Statement>, Vec<Error>> {
-        let checkpoint = self.current;
-        self.advance(); // Consume `{`
-
-        // Skip any newlines
-        while self.peek().token == Token::Delimiter(DelimiterToken::NewLine) {
-            self.advance();
+            return Err(vec![self.error(
+                ErrorKind::SyntaxError,
+                "Expected '{' to start function body",
+            )]);
         }
 
-        // Check if it's potentially an object literal
-        let is_object_literal = match &self.peek().token {
-            // Common start tokens for object literals
-            Token::Identifier(_)
-            | Token::Literal(LiteralToken::String(_))
-            | Token::Literal(LiteralToken::Number(_))
-            | Token::ContextualKeyword(ContextualKeywordToken::Get)
-            | Token::ContextualKeyword(ContextualKeywordToken::Set)
-            | Token::Delimiter(DelimiterToken::OpenBracket) => true,
-
-            // If it's a closing brace, it's an empty object
-            Token::Delimiter(DelimiterToken::CloseBrace) => true,
-
-            // If we see block-specific tokens, it's definitely a block
-            Token::ContextualKeyword(ContextualKeywordToken::Let)
-            | Token::Keyword(KeywordToken::Var)
-            | Token::Keyword(KeywordToken::Const)
-            | Token::Keyword(KeywordToken::Function)
-            | Token::Keyword(KeywordToken::If)
-            | Token::Keyword(KeywordToken::For)
-            | Token::Keyword(KeywordToken::While)
-            | Token::Keyword(KeywordToken::Return) => false,
-
-            // For other tokens, we need more context
-            _ => false,
-        };
-
-        // Reset position
-        self.current = checkpoint;
-
-        if is_object_literal {
-            let expr = self.parse_object_expression()?;
-            Ok(vec![Statement::ExpressionStatement(expr)])
-        } else {
-            self.parse_block_statement()
+        let mut sstms = self.parse_block_statement()?;
+        match sstms.pop() {
+            Some(block_stmt) => Ok(self.ast.alloc_statement(block_stmt)),
+            None => Err(vec![self.error(
+                ErrorKind::UnexpectedToken,
+                "Expected a block statement in function body",
+            )]),
         }
     }
 }
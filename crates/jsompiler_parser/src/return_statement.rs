@@ -1,29 +1,46 @@
-use super::{expression::Expression, Parser, Statement};
+use super::{ExpressionId, Parser, Span, Statement};
 use crate::{Error, ErrorKind};
-use jsompiler_lexer::symbol::{KeywordToken, Token};
+use jsompiler_lexer::symbol::{DelimiterToken, KeywordToken, Token};
 
 #[derive(Debug, Clone)]
 pub struct ReturnStatement {
     pub token: Token,
-    pub value: Box<Expression>,
+    pub value: Option<ExpressionId>,
+    pub span: Span,
 }
 
 impl Parser {
     pub fn parse_return_statement(&mut self) -> Result<Vec<Statement>, Vec<Error>> {
+        let start = self.current;
         if !self.match_token(&Token::Keyword(KeywordToken::Return)) {
-            return Err(vec![Error {
-                error_kind: ErrorKind::UnexpectedToken,
-                message: "Expected 'return'".to_string(),
-                line_number: 1,
-                pos: 2,
-            }]);
+            return Err(vec![
+                self.error(ErrorKind::UnexpectedToken, "Expected 'return'")
+            ]);
         }
 
-        let value = Box::new(self.expression()?);
+        // A line break after `return` ends the statement
+        let value = match self.peek().token {
+            Token::Delimiter(DelimiterToken::Semicolon)
+            | Token::Delimiter(DelimiterToken::NewLine)
+            | Token::Delimiter(DelimiterToken::CloseBrace)
+            | Token::EOF => None,
+            _ => {
+                let value = self.expression()?;
+                Some(self.ast.alloc_expression(value))
+            }
+        };
+
+        if !self.consume_semicolon() {
+            return Err(vec![self.error(
+                ErrorKind::UnexpectedToken,
+                "Expected ';' after return statement",
+            )]);
+        }
 
         Ok(vec![Statement::ReturnStatement(ReturnStatement {
             token: Token::Keyword(KeywordToken::Return),
             value,
+            span: self.span_from(start),
         })])
     }
 }
@@ -0,0 +1,118 @@
+use super::{ExpressionId, Parser, Span, Statement, StatementId};
+use crate::{Error, ErrorKind};
+use jsompiler_lexer::symbol::{DelimiterToken, KeywordToken, OperatorToken, Token};
+
+#[derive(Debug, Clone)]
+pub struct SwitchStatement {
+    pub discriminant: ExpressionId,
+    pub cases: Vec<SwitchCase>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub struct SwitchCase {
+    pub test: Option<ExpressionId>, // `None` for the default clause
+    pub consequent: Vec<StatementId>,
+    pub span: Span,
+}
+
+impl Parser {
+    pub fn parse_switch_statement(&mut self) -> Result<Vec<Statement>, Vec<Error>> {
+        let start = self.current;
+        if !self.match_token(&Token::Keyword(KeywordToken::Switch)) {
+            return Err(vec![
+                self.error(ErrorKind::UnexpectedToken, "Expected 'switch'")
+            ]);
+        }
+
+        let discriminant = self.parenthesis_expression()?;
+        let discriminant = self.ast.alloc_expression(discriminant);
+
+        self.skip_newlines();
+        if !self.match_token(&Token::Delimiter(DelimiterToken::OpenBrace)) {
+            return Err(vec![self.error(
+                ErrorKind::UnexpectedToken,
+                "Expected '{' after switch discriminant",
+            )]);
+        }
+
+        // `break` is allowed inside the cases
+        self.label_set.breakable_depth += 1;
+        let cases = self.parse_switch_cases();
+        self.label_set.breakable_depth -= 1;
+
+        Ok(vec![Statement::SwitchStatement(SwitchStatement {
+            discriminant,
+            cases: cases?,
+            span: self.span_from(start),
+        })])
+    }
+
+    fn parse_switch_cases(&mut self) -> Result<Vec<SwitchCase>, Vec<Error>> {
+        let mut cases: Vec<SwitchCase> = Vec::new();
+
+        loop {
+            self.skip_newlines();
+
+            let case_start = self.current;
+            let test = match self.peek().token {
+                Token::Delimiter(DelimiterToken::CloseBrace) => {
+                    self.advance();
+                    return Ok(cases);
+                }
+                Token::Keyword(KeywordToken::Case) => {
+                    self.advance();
+                    let test = self.expression()?;
+                    Some(self.ast.alloc_expression(test))
+                }
+                Token::Keyword(KeywordToken::Default) => {
+                    if cases.iter().any(|case| case.test.is_none()) {
+                        return Err(vec![self.error(
+                            ErrorKind::SyntaxError,
+                            "More than one default clause in switch statement",
+                        )]);
+                    }
+                    self.advance();
+                    None
+                }
+                _ => {
+                    return Err(vec![self.error(
+                        ErrorKind::UnexpectedToken,
+                        "Expected 'case', 'default' or '}' in switch body",
+                    )]);
+                }
+            };
+
+            if !self.match_token(&Token::Operator(OperatorToken::Colon)) {
+                return Err(vec![self.error(
+                    ErrorKind::UnexpectedToken,
+                    "Expected ':' after switch case",
+                )]);
+            }
+
+            // The consequent runs until the next clause or the end of the switch body
+            let mut consequent = Vec::new();
+            loop {
+                self.skip_newlines();
+                match self.peek().token {
+                    Token::Keyword(KeywordToken::Case)
+                    | Token::Keyword(KeywordToken::Default)
+                    | Token::Delimiter(DelimiterToken::CloseBrace) => break,
+                    Token::EOF => {
+                        return Err(vec![self.error(ErrorKind::UnexpectedToken, "Expected '}'")]);
+                    }
+                    _ => {
+                        let statement = self.parse_statement()?;
+                        consequent.extend(self.ast.alloc_statements(statement));
+                    }
+                }
+            }
+
+            cases.push(SwitchCase {
+                test,
+                consequent,
+                span: self.span_from(case_start),
+            });
+        }
+    }
+}
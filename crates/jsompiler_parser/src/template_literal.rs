@@ -1,49 +1,58 @@
-use super::{expression::Expression, Parser};
+use super::{expression::Expression, ExpressionId, Parser, Span};
 use crate::{Error, ErrorKind};
 use jsompiler_lexer::symbol::{DelimiterToken, Token};
 
 #[derive(Debug, Clone)]
 pub enum TemplateLiteral {
     SubstitutionTemplate {
-        template_head: Expression,
-        expression: Expression,
+        template_head: ExpressionId,
+        expression: ExpressionId,
         template_spans: TemplateSpan,
+        span: Span,
     },
     NonSubstitutionTemplate {
-        text: Expression,
+        text: ExpressionId,
+        span: Span,
     },
 }
 
+impl TemplateLiteral {
+    pub fn span(&self) -> Span {
+        match self {
+            TemplateLiteral::SubstitutionTemplate { span, .. }
+            | TemplateLiteral::NonSubstitutionTemplate { span, .. } => *span,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct TemplateSpan {
-    pub template_tail: Expression,
+    pub template_tail: ExpressionId,
     pub template_middle_list: Option<TemplateMiddleList>,
 }
 
 #[derive(Debug, Clone)]
 pub struct TemplateMiddleList {
-    pub expression: Expression,
-    pub template_middle: Expression,
+    pub expression: ExpressionId,
+    pub template_middle: ExpressionId,
     pub template_middle_list: Option<Box<TemplateMiddleList>>,
 }
 
 impl Parser {
     pub fn parse_template_literal(&mut self) -> Result<Expression, Vec<Error>> {
+        let start = self.current;
         if !self.match_token(&Token::Delimiter(DelimiterToken::Tilde)) {
-            return Err(vec![Error {
-                error_kind: ErrorKind::UnexpectedToken,
-                message: "Expected '`'".to_string(),
-                line_number: 1,
-                pos: 2,
-            }]);
+            return Err(vec![self.error(ErrorKind::UnexpectedToken, "Expected '`'")]);
         }
         let template_head = self.expression()?;
+        let template_head = self.ast.alloc_expression(template_head);
 
         if self.peek().token == Token::Delimiter(DelimiterToken::Tilde) {
             self.advance(); // consume closing '`'
             return Ok(Expression::TemplateLiteral(Box::new(
                 TemplateLiteral::NonSubstitutionTemplate {
                     text: template_head,
+                    span: self.span_from(start),
                 },
             )));
         }
@@ -53,35 +62,21 @@ impl Parser {
             self.advance(); // consume '$'
 
             if !self.match_token(&Token::Delimiter(DelimiterToken::OpenBrace)) {
-                return Err(vec![Error {
-                    error_kind: ErrorKind::UnexpectedToken,
-                    message: "Expected {".to_string(),
-                    line_number: 1,
-                    pos: 2,
-                }]);
+                return Err(vec![self.error(ErrorKind::UnexpectedToken, "Expected {")]);
             }
 
             let first_expression = self.expression()?;
+            let first_expression = self.ast.alloc_expression(first_expression);
 
             if !self.match_token(&Token::Delimiter(DelimiterToken::CloseBrace)) {
-                return Err(vec![Error {
-                    error_kind: ErrorKind::UnexpectedToken,
-                    message: "Expected }".to_string(),
-                    line_number: 1,
-                    pos: 2,
-                }]);
+                return Err(vec![self.error(ErrorKind::UnexpectedToken, "Expected }")]);
             }
 
             // Parse the template spans (potentially multiple)
             let template_spans = self.parse_template_spans()?;
 
             if !self.match_token(&Token::Delimiter(DelimiterToken::Tilde)) {
-                return Err(vec![Error {
-                    error_kind: ErrorKind::UnexpectedToken,
-                    message: "Expected `".to_string(),
-                    line_number: 1,
-                    pos: 2,
-                }]);
+                return Err(vec![self.error(ErrorKind::UnexpectedToken, "Expected `")]);
             }
 
             return Ok(Expression::TemplateLiteral(Box::new(
@@ -89,20 +84,19 @@ impl Parser {
                     template_head,
                     expression: first_expression,
                     template_spans,
+                    span: self.span_from(start),
                 },
             )));
         }
 
-        Err(vec![Error {
-            error_kind: ErrorKind::UnexpectedToken,
-            message: "Expected $ or `".to_string(),
-            line_number: 1,
-            pos: 2,
-        }])
+        Err(vec![
+            self.error(ErrorKind::UnexpectedToken, "Expected $ or `")
+        ])
     }
 
     fn parse_template_spans(&mut self) -> Result<TemplateSpan, Vec<Error>> {
         let template_tail = self.expression()?;
+        let template_tail = self.ast.alloc_expression(template_tail);
 
         // No more substitutions
         if self.peek().token == Token::Delimiter(DelimiterToken::Tilde) {
@@ -117,23 +111,14 @@ impl Parser {
             self.advance(); // consume '$'
 
             if !self.match_token(&Token::Delimiter(DelimiterToken::OpenBrace)) {
-                return Err(vec![Error {
-                    error_kind: ErrorKind::UnexpectedToken,
-                    message: "Expected {".to_string(),
-                    line_number: 1,
-                    pos: 2,
-                }]);
+                return Err(vec![self.error(ErrorKind::UnexpectedToken, "Expected {")]);
             }
 
             let expression = self.expression()?;
+            let expression = self.ast.alloc_expression(expression);
 
             if !self.match_token(&Token::Delimiter(DelimiterToken::CloseBrace)) {
-                return Err(vec![Error {
-                    error_kind: ErrorKind::UnexpectedToken,
-                    message: "Expected }".to_string(),
-                    line_number: 1,
-                    pos: 2,
-                }]);
+                return Err(vec![self.error(ErrorKind::UnexpectedToken, "Expected }")]);
             }
 
             // Recursively parse the rest of the template
@@ -141,7 +126,7 @@ impl Parser {
 
             let template_middle_list = TemplateMiddleList {
                 expression,
-                template_middle: template_tail.clone(),
+                template_middle: template_tail,
                 template_middle_list: next_spans.template_middle_list.map(Box::new),
             };
 
@@ -151,12 +136,9 @@ impl Parser {
             });
         }
 
-        Err(vec![Error {
-            error_kind: ErrorKind::UnexpectedToken,
-            message: "Internal parser error in template spans".to_string(),
-            line_number: 1,
-            pos: 2,
-        }])
+        Err(vec![self.error(
+            ErrorKind::UnexpectedToken,
+            "Internal parser error in template spans",
+        )])
     }
 }
-
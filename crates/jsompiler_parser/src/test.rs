@@ -0,0 +1,1229 @@
+#[allow(unused_imports)]
+use super::binding_pattern::{BindingPattern, BindingProperty};
+#[allow(unused_imports)]
+use super::class_expression::{ClassElement, ClassElementName};
+#[allow(unused_imports)]
+use super::export_statement::ExportStatement;
+#[allow(unused_imports)]
+use super::expression::Expression;
+#[allow(unused_imports)]
+use super::for_in_of_statement::ForInOfLeft;
+#[allow(unused_imports)]
+use super::function_expression::Parameter;
+#[allow(unused_imports)]
+use super::jsx_expression::{
+    JSXAttribute, JSXAttributeValue, JSXChild, JSXElementName, JSXExpressionContainer,
+};
+#[allow(unused_imports)]
+use super::{from_estree, from_estree_str, to_estree, Node, Parser, ParserOptions, Statement};
+#[allow(unused_imports)]
+use jsompiler_lexer::symbol::{lexeme, JSXToken, Token};
+#[allow(unused_imports)]
+use jsompiler_lexer::{Lexer, LexerOptions};
+
+#[allow(dead_code)]
+fn parse(input: &str) -> Parser {
+    let mut lexer = Lexer::new(input.to_string());
+    lexer.scan_all_tokens();
+    assert_eq!(lexer.errors, vec![]);
+    let mut parser = Parser::new(lexer.tokens);
+    parser.parse();
+    parser
+}
+
+#[allow(dead_code)]
+fn parse_jsx(input: &str) -> Parser {
+    let mut lexer = Lexer::with_options(input.to_string(), LexerOptions { jsx: true });
+    lexer.scan_all_tokens();
+    let mut parser = Parser::with_lexer(lexer, ParserOptions { jsx: true });
+    parser.parse();
+    assert_eq!(parser.lexer_errors, vec![]);
+    parser
+}
+
+#[allow(dead_code)]
+fn first_statement(parser: &Parser) -> &Statement {
+    match parser.ast.body.first() {
+        Some(Node::Statement(statements)) => match statements.first() {
+            Some(&statement) => &parser.ast[statement],
+            None => panic!("Expected statement, found empty list"),
+        },
+        other => panic!("Expected statement, found {:?}", other),
+    }
+}
+
+#[allow(dead_code)]
+fn first_expression(parser: &Parser) -> &Expression {
+    match first_statement(parser) {
+        Statement::ExpressionStatement { expression, .. } => &parser.ast[*expression],
+        other => panic!("Expected expression statement, found {:?}", other),
+    }
+}
+
+#[test]
+fn test_default_and_pattern_parameters() {
+    let parser = parse("function f(a = 1, {b, c: [d]} = {}, [e, , f = 2], ...rest) { return a }");
+    assert_eq!(parser.errors, vec![]);
+    let Statement::FunctionDeclaration(function) = first_statement(&parser) else {
+        panic!("Expected function declaration");
+    };
+    assert_eq!(function.parameters.len(), 4);
+    assert!(matches!(
+        function.parameters[0],
+        Parameter::Default {
+            target: BindingPattern::Identifier(_),
+            ..
+        }
+    ));
+    assert!(matches!(
+        &function.parameters[1],
+        Parameter::Default {
+            target: BindingPattern::ObjectPattern { properties, .. },
+            ..
+        } if matches!(properties[0], BindingProperty::Shorthand { .. })
+    ));
+    assert!(matches!(
+        &function.parameters[2],
+        Parameter::Pattern(BindingPattern::ArrayPattern { elements, .. })
+            if elements.len() == 3 && elements[1].is_none()
+    ));
+    assert!(matches!(
+        function.parameters[3],
+        Parameter::RestParameter {
+            argument: BindingPattern::Identifier(_),
+            ..
+        }
+    ));
+    let names = function
+        .parameters
+        .iter()
+        .flat_map(Parameter::bound_names)
+        .map(|name| &parser.ast[name.value])
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["a", "b", "d", "e", "f", "rest"]);
+}
+
+#[test]
+fn test_arrow_function_pattern_parameters() {
+    let parser = parse("({ a, b = 2 }, [c]) => a");
+    assert_eq!(parser.errors, vec![]);
+    assert!(matches!(
+        first_expression(&parser),
+        Expression::ArrowFunctionExpression { parameters, .. } if parameters.len() == 2
+    ));
+}
+
+#[test]
+fn test_duplicate_parameters_in_sloppy_function() {
+    let parser = parse("function f(a, a) {}");
+    assert_eq!(parser.errors, vec![]);
+}
+
+#[test]
+fn test_duplicate_parameters_in_non_simple_list() {
+    let parser = parse("function f(a, a = 1) {}");
+    assert_ne!(parser.errors, vec![]);
+}
+
+#[test]
+fn test_duplicate_parameters_in_strict_function() {
+    let parser = parse("function f(a, a) { \"use strict\"; }");
+    assert_ne!(parser.errors, vec![]);
+}
+
+#[test]
+fn test_duplicate_parameters_in_strict_program() {
+    assert_ne!(parse("\"use strict\"; function f(a, a) {}").errors, vec![]);
+    assert_ne!(parse("\n'use strict'\nfunction f(a, a) {}").errors, vec![]);
+    // Only a string at the start of the program is a directive
+    assert_eq!(
+        parse("f(); \"use strict\"; function f(a, a) {}").errors,
+        vec![]
+    );
+    assert_eq!(
+        parse("\"use strict\" + 1; function f(a, a) {}").errors,
+        vec![]
+    );
+    // A directive in one function leaves the code after it sloppy
+    assert_eq!(
+        parse("function f() { \"use strict\"; }\nfunction g(a, a) {}").errors,
+        vec![]
+    );
+}
+
+#[test]
+fn test_duplicate_parameters_in_arrow_and_method() {
+    assert_eq!(parse("(a, b) => a").errors, vec![]);
+    assert_eq!(parse("class A { m(a, b) {} }").errors, vec![]);
+    assert_eq!(parse("x = { m(a, b = 1) {} }").errors, vec![]);
+    assert_ne!(parse("(a, a) => a").errors, vec![]);
+    assert_ne!(parse("class A { m(a, a) {} }").errors, vec![]);
+    assert_ne!(parse("x = { m(a, a) {} }").errors, vec![]);
+}
+
+#[test]
+fn test_function_and_class_declarations() {
+    let parser = parse("async function* f() {}");
+    assert_eq!(parser.errors, vec![]);
+    assert!(matches!(
+        first_statement(&parser),
+        Statement::FunctionDeclaration(function)
+            if function.is_async && function.is_generator && function.name.is_some()
+    ));
+
+    let parser = parse("class A extends B {}");
+    assert_eq!(parser.errors, vec![]);
+    assert!(matches!(
+        first_statement(&parser),
+        Statement::ClassDeclaration(class) if class.identifier.is_some()
+    ));
+}
+
+#[test]
+fn test_anonymous_function_and_class_expressions() {
+    let parser = parse("(function () {})");
+    assert_eq!(parser.errors, vec![]);
+    assert!(matches!(
+        first_expression(&parser),
+        Expression::FunctionExpression(function) if function.name.is_none()
+    ));
+
+    let parser = parse("x = class {}");
+    assert_eq!(parser.errors, vec![]);
+}
+
+#[test]
+fn test_anonymous_declarations_are_rejected() {
+    assert_ne!(parse("function () {}").errors, vec![]);
+    assert_ne!(parse("class {}").errors, vec![]);
+}
+
+#[test]
+fn test_export_default_declarations() {
+    let parser = parse("export default function () {}");
+    assert_eq!(parser.errors, vec![]);
+    assert!(matches!(
+        first_statement(&parser),
+        Statement::ExportStatement(ExportStatement::DefaultDeclaration { declaration, .. })
+            if matches!(parser.ast[*declaration], Statement::FunctionDeclaration(ref function) if function.name.is_none())
+    ));
+
+    let parser = parse("export default class {}");
+    assert_eq!(parser.errors, vec![]);
+
+    let parser = parse("export default 1 + 2;");
+    assert_eq!(parser.errors, vec![]);
+    assert!(matches!(
+        first_statement(&parser),
+        Statement::ExportStatement(ExportStatement::DefaultExpression { .. })
+    ));
+
+    let parser = parse("export const a = 1, b = 2;");
+    assert!(matches!(
+        first_statement(&parser),
+        Statement::ExportStatement(ExportStatement::Declaration { declarations, .. }) if declarations.len() == 2
+    ));
+}
+
+#[test]
+fn test_let_bracket_is_a_declaration() {
+    let parser = parse("let [a, b] = c");
+    assert_eq!(parser.errors, vec![]);
+    assert!(matches!(
+        first_statement(&parser),
+        Statement::LetStatement(declaration)
+            if matches!(declaration.name, BindingPattern::ArrayPattern { .. })
+    ));
+
+    assert_ne!(parse("let [0] = c").errors, vec![]);
+    assert_ne!(parse("let [a];").errors, vec![]);
+}
+
+#[test]
+fn test_class_accessors_generators_and_computed_names() {
+    let parser = parse(
+        "class A {
+            constructor(a) { this.a = a }
+            get value() { return this.a }
+            set value(v) { this.a = v }
+            static *gen() {}
+            async *stream() {}
+            [Symbol.iterator]() {}
+            'quoted'() {}
+            static = 1
+            accessor count = 0
+        }",
+    );
+    assert_eq!(parser.errors, vec![]);
+    let Statement::ClassDeclaration(class) = first_statement(&parser) else {
+        panic!("Expected class declaration");
+    };
+    assert!(matches!(class.body[0], ClassElement::Constructor { .. }));
+    assert!(matches!(
+        class.body[1],
+        ClassElement::GetterDefinition { .. }
+    ));
+    assert!(matches!(
+        class.body[2],
+        ClassElement::SetterDefinition { .. }
+    ));
+    assert!(matches!(
+        class.body[3],
+        ClassElement::GeneratorMethodDefinition {
+            is_static: true,
+            ..
+        }
+    ));
+    assert!(matches!(
+        class.body[4],
+        ClassElement::AsyncGeneratorMethodDefinition { .. }
+    ));
+    assert!(matches!(
+        class.body[5],
+        ClassElement::MethodDefinition {
+            name: ClassElementName::Computed(_),
+            ..
+        }
+    ));
+    assert!(matches!(
+        &class.body[6],
+        ClassElement::MethodDefinition {
+            name: ClassElementName::StringLiteral { value, .. },
+            ..
+        } if value == "quoted"
+    ));
+    assert!(matches!(
+        &class.body[7],
+        ClassElement::FieldDefinition {
+            name: ClassElementName::PropertyName(name),
+            is_static: false,
+            ..
+        } if &parser.ast[name.value] == "static"
+    ));
+    assert!(matches!(
+        class.body[8],
+        ClassElement::AccessorDefinition { .. }
+    ));
+}
+
+#[test]
+fn test_class_modifiers_across_line_breaks() {
+    let class_body = |source: &str| {
+        let parser = parse(source);
+        assert_eq!(parser.errors, vec![], "{source}");
+        let Statement::ClassDeclaration(class) = first_statement(&parser) else {
+            panic!("Expected class declaration");
+        };
+        class.body.clone()
+    };
+
+    // `get`, `set` and `static` may be followed by a line break
+    let body = class_body("class C { get\n a() {} set\n a(v) {} }");
+    assert!(matches!(body[0], ClassElement::GetterDefinition { .. }));
+    assert!(matches!(body[1], ClassElement::SetterDefinition { .. }));
+    let body = class_body("class C { static\n a }");
+    assert_eq!(body.len(), 1);
+    assert!(matches!(
+        body[0],
+        ClassElement::FieldDefinition {
+            is_static: true,
+            ..
+        }
+    ));
+    let body = class_body("class C { static\n {} }");
+    assert!(matches!(body[0], ClassElement::StaticBlock { .. }));
+
+    // `async` and `accessor` may not, so they are fields of their own
+    let body = class_body("class C { async\n a() {} }");
+    assert!(matches!(body[0], ClassElement::FieldDefinition { .. }));
+    assert!(matches!(body[1], ClassElement::MethodDefinition { .. }));
+    let body = class_body("class C { accessor\n a }");
+    assert_eq!(body.len(), 2);
+    assert!(matches!(body[0], ClassElement::FieldDefinition { .. }));
+}
+
+#[test]
+fn test_class_constructor_early_errors() {
+    assert_ne!(
+        parse("class A { constructor() {} constructor() {} }").errors,
+        vec![]
+    );
+    assert_ne!(parse("class A { get constructor() {} }").errors, vec![]);
+    assert_ne!(parse("class A { *constructor() {} }").errors, vec![]);
+    assert_ne!(parse("class A { async constructor() {} }").errors, vec![]);
+    assert_ne!(parse("class A { constructor = 1 }").errors, vec![]);
+    assert_ne!(parse("class A { static prototype() {} }").errors, vec![]);
+    assert_eq!(parse("class A { static constructor() {} }").errors, vec![]);
+}
+
+#[test]
+fn test_class_private_methods_and_brand_checks() {
+    let parser = parse(
+        "class A {
+            #x = 1
+            get #y() { return 1 }
+            set #y(v) {}
+            #m() { return this.#x }
+            static is(o) { return #m in o }
+        }",
+    );
+    assert_eq!(parser.errors, vec![]);
+
+    assert_ne!(parse("class A { #x; #x }").errors, vec![]);
+    assert_ne!(parse("class A { m() { return this.#x } }").errors, vec![]);
+    assert_ne!(parse("class A { #constructor() {} }").errors, vec![]);
+    assert_eq!(
+        parse("class A { #x; m() { class B { n(o) { return #x in o } } } }").errors,
+        vec![]
+    );
+}
+
+#[test]
+fn test_labeled_statements() {
+    let parser = parse(
+        "outer: for (let i = 0; i < 3; i++) {
+            inner: while (true) {
+                if (i) { continue outer; }
+                break inner;
+            }
+        }",
+    );
+    assert_eq!(parser.errors, vec![]);
+    assert!(matches!(
+        first_statement(&parser),
+        Statement::LabeledStatement(labeled) if &parser.ast[labeled.label.value] == "outer"
+    ));
+
+    assert_eq!(parse("block: { break block; }").errors, vec![]);
+    assert_eq!(parse("a: b: while (x) { continue a; }").errors, vec![]);
+}
+
+#[test]
+fn test_label_early_errors() {
+    assert_ne!(parse("while (x) { break missing; }").errors, vec![]);
+    assert_ne!(parse("block: { continue block; }").errors, vec![]);
+    assert_ne!(parse("a: a: while (x) {}").errors, vec![]);
+    assert_ne!(parse("break;").errors, vec![]);
+    assert_ne!(parse("continue;").errors, vec![]);
+    assert_ne!(
+        parse("outer: while (x) { function f() { break outer; } }").errors,
+        vec![]
+    );
+}
+
+#[test]
+fn test_empty_and_debugger_statements() {
+    let parser = parse(";debugger;");
+    assert_eq!(parser.errors, vec![]);
+    assert!(matches!(
+        first_statement(&parser),
+        Statement::EmptyStatement { .. }
+    ));
+    assert!(matches!(
+        parser.ast.body[1],
+        Node::Statement(ref statements) if matches!(parser.ast[statements[0]], Statement::DebuggerStatement { .. })
+    ));
+
+    // A terminated statement does not leave an empty statement behind
+    let parser = parse("a = 1;");
+    assert_eq!(parser.ast.body.len(), 1);
+}
+
+#[test]
+fn test_with_statement() {
+    let parser = parse("with (obj) a = b");
+    assert_eq!(parser.errors, vec![]);
+    assert!(matches!(
+        first_statement(&parser),
+        Statement::WithStatement(_)
+    ));
+
+    assert_ne!(
+        parse("function f() { \"use strict\"; with (obj) {} }").errors,
+        vec![]
+    );
+    assert_ne!(parse("\"use strict\"; with (obj) {}").errors, vec![]);
+    assert_ne!(
+        parse("'use strict';\nfunction f() { with (obj) {} }").errors,
+        vec![]
+    );
+}
+
+#[test]
+fn test_if_else_chains() {
+    let parser = parse(
+        "if (a) x = 1
+        else if (b) { x = 2 }
+        else x = 3",
+    );
+    assert_eq!(parser.errors, vec![]);
+    let Statement::IfStatement(statement) = first_statement(&parser) else {
+        panic!("Expected if statement");
+    };
+    let alternative = statement
+        .alternative
+        .as_ref()
+        .expect("Expected else branch");
+    assert!(matches!(
+        &parser.ast[alternative[0]],
+        Statement::IfStatement(nested) if nested.alternative.is_some()
+    ));
+
+    // The dangling else belongs to the inner if
+    let parser = parse("if (a) if (b) x = 1; else x = 2");
+    assert_eq!(parser.errors, vec![]);
+    let Statement::IfStatement(outer) = first_statement(&parser) else {
+        panic!("Expected if statement");
+    };
+    assert!(outer.alternative.is_none());
+    assert!(matches!(
+        &parser.ast[outer.consequence[0]],
+        Statement::IfStatement(inner) if inner.alternative.is_some()
+    ));
+}
+
+#[test]
+fn test_remaining_statements() {
+    let parser = parse(
+        "do { i++ } while (i < 3)
+        switch (x) { case 1: y = 1; break; default: y = 2 }
+        try { f() } catch ({ message }) { g() } finally { h() }
+        try {} catch {}
+        for (const key in obj) {}
+        for (item of items) {}
+        for (;;) { break }
+        return",
+    );
+    assert_eq!(parser.errors, vec![]);
+    let statements = parser
+        .ast
+        .body
+        .iter()
+        .flat_map(|node| match node {
+            Node::Statement(statements) => statements.clone(),
+            _ => vec![],
+        })
+        .map(|id| &parser.ast[id])
+        .collect::<Vec<_>>();
+    assert!(matches!(statements[0], Statement::DoWhileStatement(_)));
+    assert!(matches!(
+        &statements[1],
+        Statement::SwitchStatement(switch) if switch.cases.len() == 2 && switch.cases[1].test.is_none()
+    ));
+    assert!(matches!(
+        &statements[2],
+        Statement::TryStatement(statement) if statement.handler.is_some() && statement.finalizer.is_some()
+    ));
+    assert!(matches!(
+        &statements[3],
+        Statement::TryStatement(statement)
+            if statement.handler.as_ref().is_some_and(|handler| handler.param.is_none())
+    ));
+    assert!(matches!(
+        &statements[4],
+        Statement::ForInStatement(statement)
+            if matches!(statement.left, ForInOfLeft::Declaration { .. })
+    ));
+    assert!(matches!(
+        &statements[5],
+        Statement::ForOfStatement(statement) if matches!(statement.left, ForInOfLeft::Target(_))
+    ));
+    assert!(matches!(statements[6], Statement::ForLoopStatement(_)));
+    assert!(matches!(
+        &statements[7],
+        Statement::ReturnStatement(statement) if statement.value.is_none()
+    ));
+}
+
+#[test]
+fn test_remaining_statement_errors() {
+    assert_ne!(parse("try {}").errors, vec![]);
+    assert_ne!(parse("throw\nerror").errors, vec![]);
+    assert_ne!(
+        parse("switch (x) { default: break; default: break }").errors,
+        vec![]
+    );
+    assert_eq!(parse("switch (x) { case 1: break }").errors, vec![]);
+}
+
+#[test]
+fn test_recovers_after_syntax_errors() {
+    let parser = parse(
+        "let = 1
+        let a = 2
+        if (a) { x = ); y = 3 }
+        let b = 4",
+    );
+    assert_eq!(parser.errors.len(), 2);
+    assert!(matches!(first_statement(&parser), Statement::Error { .. }));
+
+    let statements = parser
+        .ast
+        .body
+        .iter()
+        .flat_map(|node| match node {
+            Node::Statement(statements) => statements.clone(),
+            _ => vec![],
+        })
+        .map(|id| &parser.ast[id])
+        .collect::<Vec<_>>();
+    assert!(matches!(statements[1], Statement::LetStatement(_)));
+    let Statement::IfStatement(statement) = &statements[2] else {
+        panic!("Expected if statement");
+    };
+    let Statement::BlockStatement(block) = &parser.ast[statement.consequence[0]] else {
+        panic!("Expected block statement");
+    };
+    assert!(matches!(
+        parser.ast[block.statements[0]],
+        Statement::Error { .. }
+    ));
+    assert!(matches!(
+        parser.ast[block.statements[1]],
+        Statement::AssignmentStatement(_)
+    ));
+    assert!(matches!(statements[3], Statement::LetStatement(_)));
+}
+
+#[test]
+fn test_errors_point_at_their_token() {
+    let parser = parse(
+        "let = 1
+        let a = 2
+        if (a) { x = ); y = 3 }
+        let b = 4",
+    );
+    let positions = parser
+        .errors
+        .iter()
+        .map(|error| (error.line_number, error.pos))
+        .collect::<Vec<_>>();
+    assert_eq!(positions, vec![(1, 4), (3, 21)]);
+
+    // Lines inside comments, strings and templates count too
+    let parser = parse("/* a\nb */ x = `c\nd`\n'e\\\nf'\n  1 +;");
+    assert_eq!((parser.errors[0].line_number, parser.errors[0].pos), (6, 5));
+
+    let parser = parse("function f(a, b) {\n  'use strict';\n}\nclass A { m(c, c) {} }");
+    assert_eq!(
+        (parser.errors[0].line_number, parser.errors[0].pos),
+        (4, 15)
+    );
+}
+
+#[test]
+fn test_errors_name_the_unexpected_construct() {
+    let errors = |source: &str| {
+        parse(source)
+            .errors
+            .iter()
+            .map(|error| (error.message.clone(), error.line_number, error.pos))
+            .collect::<Vec<_>>()
+    };
+    let error = |message: &str, line_number, pos| vec![(message.to_string(), line_number, pos)];
+
+    assert_eq!(errors("a; } b;"), error("Unexpected token '}'", 1, 3));
+    assert_eq!(
+        errors("function f() {}\n}\nb;"),
+        error("Unexpected token '}'", 2, 0)
+    );
+    assert_eq!(
+        errors("if (a { x }"),
+        error("Expected ')' after expression", 1, 6)
+    );
+    assert_eq!(
+        errors("x = (1 + 2;"),
+        error("Expected ')' after expression", 1, 10)
+    );
+    assert_eq!(
+        errors("f(a { })"),
+        error("Expected ',' or ')' after argument", 1, 4)
+    );
+    // The `}` closing a block that failed to parse isn't reported again
+    assert_eq!(
+        errors("function f() { let = 1 }\nb;"),
+        error("Expected identifier after 'let'", 1, 19)
+    );
+}
+
+#[test]
+fn test_expression_errors_are_not_duplicated() {
+    let parser = parse("1 +;\n2 +;");
+    assert_eq!(parser.errors.len(), 2);
+}
+
+#[test]
+fn test_jsx_elements_and_fragments() {
+    let parser = parse_jsx("x = <Foo.Bar><svg:rect/><></></Foo.Bar>");
+    assert_eq!(parser.errors, vec![]);
+    let Statement::AssignmentStatement(statement) = first_statement(&parser) else {
+        panic!("Expected assignment statement");
+    };
+    let Expression::JSXElement(element) = &parser.ast[statement.value] else {
+        panic!("Expected JSX element");
+    };
+    assert_eq!(element.opening.name.text(&parser.ast), "Foo.Bar");
+    assert!(matches!(
+        &element.children[0],
+        JSXChild::Element(child)
+            if child.opening.self_closing
+                && matches!(child.opening.name, JSXElementName::Namespaced { .. })
+    ));
+    assert!(matches!(element.children[1], JSXChild::Fragment(_)));
+}
+
+#[test]
+fn test_jsx_closing_tag_mismatch() {
+    assert_ne!(parse_jsx("<a></b>").errors, vec![]);
+    assert_ne!(parse_jsx("<a.b></a>").errors, vec![]);
+}
+
+#[test]
+fn test_jsx_attributes_and_children() {
+    // <a b="c" d={e} {...f}>text{g}</a>
+    let jsx = |token| lexeme(String::new(), Token::JSX(token));
+    let identifier = |name: &str| jsx(JSXToken::Identifier(name.to_string()));
+    let tokens = vec![
+        jsx(JSXToken::LessThan),
+        identifier("a"),
+        identifier("b"),
+        jsx(JSXToken::Equals),
+        jsx(JSXToken::StringLiteral("c".to_string())),
+        identifier("d"),
+        jsx(JSXToken::Equals),
+        jsx(JSXToken::LeftBrace),
+        lexeme("e".to_string(), Token::Identifier("e".to_string())),
+        jsx(JSXToken::RightBrace),
+        jsx(JSXToken::LeftBrace),
+        jsx(JSXToken::Ellipsis),
+        lexeme("f".to_string(), Token::Identifier("f".to_string())),
+        jsx(JSXToken::RightBrace),
+        jsx(JSXToken::GreaterThan),
+        jsx(JSXToken::Text("text".to_string())),
+        jsx(JSXToken::LeftBrace),
+        lexeme("g".to_string(), Token::Identifier("g".to_string())),
+        jsx(JSXToken::RightBrace),
+        jsx(JSXToken::LessThanSlash),
+        identifier("a"),
+        jsx(JSXToken::GreaterThan),
+        lexeme("EOF".to_string(), Token::EOF),
+    ];
+    let mut parser = Parser::with_options(tokens, ParserOptions { jsx: true });
+    parser.parse();
+    assert_eq!(parser.errors, vec![]);
+
+    let Expression::JSXElement(element) = first_expression(&parser) else {
+        panic!("Expected JSX element");
+    };
+    let attributes = &element.opening.attributes;
+    assert!(matches!(
+        &attributes[0],
+        JSXAttribute::Attribute { value: Some(JSXAttributeValue::StringLiteral { value, .. }), .. }
+            if value == "c"
+    ));
+    assert!(matches!(
+        attributes[1],
+        JSXAttribute::Attribute {
+            value: Some(JSXAttributeValue::ExpressionContainer(_)),
+            ..
+        }
+    ));
+    assert!(matches!(
+        attributes[2],
+        JSXAttribute::SpreadAttribute { .. }
+    ));
+    assert!(matches!(&element.children[0], JSXChild::Text { value: text, .. } if text == "text"));
+    assert!(matches!(
+        element.children[1],
+        JSXChild::ExpressionContainer(_)
+    ));
+}
+
+#[test]
+fn test_jsx_from_source() {
+    let parser = parse_jsx(
+        "<ul className=\"list\" {...props}>
+            Items: {count}
+            <li key={1}>one &amp; two</li>
+            {/* nothing */}
+        </ul>",
+    );
+    assert_eq!(parser.errors, vec![]);
+    let Expression::JSXElement(element) = first_expression(&parser) else {
+        panic!("Expected JSX element");
+    };
+    assert_eq!(element.opening.attributes.len(), 2);
+    let children = element
+        .children
+        .iter()
+        .filter(
+            |child| !matches!(child, JSXChild::Text { value: text, .. } if text.trim().is_empty()),
+        )
+        .collect::<Vec<_>>();
+    assert!(matches!(children[0], JSXChild::Text { value: text, .. } if text.trim() == "Items:"));
+    assert!(matches!(children[1], JSXChild::ExpressionContainer(_)));
+    assert!(matches!(
+        children[2],
+        JSXChild::Element(li)
+            if matches!(&li.children[0], JSXChild::Text { value: text, .. } if text == "one & two")
+    ));
+    assert!(matches!(
+        children[3],
+        JSXChild::ExpressionContainer(JSXExpressionContainer::Empty { .. })
+    ));
+}
+
+#[test]
+fn test_jsx_where_the_parser_expects_an_expression() {
+    let statements = |parser: &Parser| {
+        parser
+            .ast
+            .body
+            .iter()
+            .flat_map(|node| match node {
+                Node::Statement(statements) => statements.clone(),
+                _ => vec![],
+            })
+            .map(|id| parser.ast[id].clone())
+            .collect::<Vec<_>>()
+    };
+
+    // A block ends before the element, which a look at the tokens before `<` can't tell
+    // from an object literal
+    let parser = parse_jsx("if (a) {} <b/>");
+    assert_eq!(parser.errors, vec![]);
+    let statements = statements(&parser);
+    assert!(matches!(statements[0], Statement::IfStatement(_)));
+    assert!(matches!(
+        &statements[1],
+        Statement::ExpressionStatement { expression, .. }
+            if matches!(parser.ast[*expression], Expression::JSXElement(_))
+    ));
+
+    let parser = parse_jsx("x = <a b=<c/> d={<e/>} />");
+    assert_eq!(parser.errors, vec![]);
+
+    // After an operand, `<` is still a comparison
+    let parser = parse_jsx("x = (a) < b; y = c[0] <d");
+    assert_eq!(parser.errors, vec![]);
+
+    // Text that isn't valid JavaScript, and JSX nested in JavaScript nested in JSX
+    let parser = parse_jsx("x = <p>it's {f(<b>{c}</b>)}</p>");
+    assert_eq!(parser.errors, vec![]);
+    let Statement::AssignmentStatement(statement) = first_statement(&parser) else {
+        panic!("Expected assignment statement");
+    };
+    let Expression::JSXElement(element) = &parser.ast[statement.value] else {
+        panic!("Expected JSX element");
+    };
+    assert!(matches!(&element.children[0], JSXChild::Text { value, .. } if value == "it's "));
+    assert!(matches!(
+        element.children[1],
+        JSXChild::ExpressionContainer(JSXExpressionContainer::Expression { .. })
+    ));
+
+    // Positions, line breaks and comments after the element come from lexing it again
+    let parser = parse_jsx("x = <p>\n'</p> // a\n/* b */ y = ;");
+    assert_eq!(parser.ast.comments.len(), 2);
+    assert_eq!(
+        (parser.errors[0].line_number, parser.errors[0].pos),
+        (3, 12)
+    );
+}
+
+#[test]
+fn test_jsx_requires_option() {
+    let mut lexer = Lexer::with_options("<a/>".to_string(), LexerOptions { jsx: true });
+    lexer.scan_all_tokens();
+    let mut parser = Parser::with_lexer(lexer, ParserOptions::default());
+    parser.parse();
+    assert_ne!(parser.errors, vec![]);
+
+    // Without JSX, `<` is always a comparison
+    let parser = parse("x = a < b");
+    assert_eq!(parser.errors, vec![]);
+}
+
+#[test]
+fn test_estree_locations() {
+    let source = "let a = 1, b;\nf(a);";
+    let parser = parse(source);
+    let program = to_estree(&parser.ast, source);
+
+    assert_eq!(program["type"], "Program");
+    assert_eq!(program["sourceType"], "script");
+    assert_eq!(program["range"], serde_json::json!([0, 19]));
+
+    // Both declarators belong to one declaration
+    let declaration = &program["body"][0];
+    assert_eq!(declaration["type"], "VariableDeclaration");
+    assert_eq!(declaration["kind"], "let");
+    assert_eq!(declaration["declarations"][1]["id"]["name"], "b");
+    assert_eq!(
+        declaration["declarations"][1]["init"],
+        serde_json::Value::Null
+    );
+    assert_eq!(
+        (&declaration["start"], &declaration["end"]),
+        (&0.into(), &13.into())
+    );
+
+    let call = &program["body"][1]["expression"];
+    assert_eq!(call["type"], "CallExpression");
+    assert_eq!(call["arguments"][0]["name"], "a");
+    assert_eq!(
+        call["loc"],
+        serde_json::json!({ "start": { "line": 2, "column": 0 }, "end": { "line": 2, "column": 4 } })
+    );
+}
+
+#[test]
+fn test_estree_nodes() {
+    let source = "\"use strict\";\nconst f = (x = 1) => x++;\nexport default class { #p; get p() { return this.#p; } }";
+    let parser = parse(source);
+    let program = to_estree(&parser.ast, source);
+    assert_eq!(program["sourceType"], "module");
+    assert_eq!(program["body"][0]["directive"], "use strict");
+
+    let arrow = &program["body"][1]["declarations"][0]["init"];
+    assert_eq!(arrow["type"], "ArrowFunctionExpression");
+    assert_eq!(arrow["expression"], true);
+    assert_eq!(arrow["params"][0]["type"], "AssignmentPattern");
+    assert_eq!(arrow["body"]["type"], "UpdateExpression");
+    assert_eq!(arrow["body"]["prefix"], false);
+
+    let class = &program["body"][2]["declaration"];
+    assert_eq!(program["body"][2]["type"], "ExportDefaultDeclaration");
+    assert_eq!(class["body"]["body"][0]["key"]["type"], "PrivateIdentifier");
+    assert_eq!(class["body"]["body"][0]["key"]["name"], "p");
+    assert_eq!(class["body"]["body"][1]["kind"], "get");
+    assert_eq!(
+        class["body"]["body"][1]["value"]["body"]["body"][0]["argument"]["property"]["type"],
+        "PrivateIdentifier"
+    );
+}
+
+#[test]
+fn test_estree_numbers() {
+    let source = "x = [1, 1.5, 0x10, 1e3, 1e300]";
+    let parser = parse(source);
+    let program = to_estree(&parser.ast, source);
+    let elements = &program["body"][0]["expression"]["right"]["elements"];
+    // Compared as text, since `1` and `1.0` are equal as JSON values
+    let values = (0..5)
+        .map(|index| elements[index]["value"].to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(values, ["1", "1.5", "16", "1000", "1e+300"]);
+}
+
+#[test]
+fn test_estree_utf16_columns() {
+    // Astral characters take two UTF-16 code units, like in JavaScript
+    let source = "'😀'; x";
+    let parser = parse(source);
+    let program = to_estree(&parser.ast, source);
+    assert_eq!(program["body"][0]["expression"]["raw"], "'😀'");
+    assert_eq!(program["body"][1]["start"], 6);
+    assert_eq!(program["body"][1]["loc"]["start"]["column"], 6);
+}
+
+#[test]
+fn test_estree_jsx() {
+    let source = "<a.b x=\"1\" {...y}>hi {}</a.b>";
+    let parser = parse_jsx(source);
+    let program = to_estree(&parser.ast, source);
+    let element = &program["body"][0]["expression"];
+    assert_eq!(element["type"], "JSXElement");
+    assert_eq!(
+        element["openingElement"]["name"]["type"],
+        "JSXMemberExpression"
+    );
+    assert_eq!(
+        element["openingElement"]["attributes"][0]["value"]["value"],
+        "1"
+    );
+    assert_eq!(
+        element["openingElement"]["attributes"][1]["type"],
+        "JSXSpreadAttribute"
+    );
+    assert_eq!(element["children"][0]["type"], "JSXText");
+    assert_eq!(
+        element["children"][1]["expression"]["type"],
+        "JSXEmptyExpression"
+    );
+    assert_eq!(element["children"][1]["expression"]["start"], 22);
+    assert_eq!(element["closingElement"]["end"], 29);
+}
+
+#[allow(dead_code)]
+fn assert_estree_round_trip(source: &str, jsx: bool) {
+    let parser = if jsx {
+        parse_jsx(source)
+    } else {
+        parse(source)
+    };
+    assert_eq!(parser.errors, vec![], "{source}");
+
+    let json = to_estree(&parser.ast, source);
+    let ast = from_estree(&json, source).unwrap_or_else(|error| panic!("{source}: {error:?}"));
+    assert_eq!(to_estree(&ast, source), json, "{source}");
+}
+
+#[test]
+fn test_estree_round_trip() {
+    let corpus = [
+        "\"use strict\";\nlet a = 1, b;\nconst c = [1, , \"x\"];",
+        "function f(x, y = 2, { z, w: [v = 1] }, ...rest) { return x + y; }",
+        "if (a) { b = a.c[0](1); } else b++;",
+        "for (let i = 0; i < 3; i++) {}\nfor (const k of c) continue;\nfor (k in o) {}",
+        "var o = { a, b: 2, get c() { return 1; }, set c(v) {}, m(q) {}, [a]: 3, 'd': 4, ...o };",
+        "class K extends f { #p = 1; static s; 'q'() {} constructor() {} get g() { return this.#p; } static { a; } }",
+        "let t = `x${a}y${b}z${c}`, u = `plain`;",
+        "let arrow = (p) => p * 2, block = () => { -p; };",
+        "switch (a) { case 1: break; default: }",
+        "try { throw a; } catch ({ e }) {} finally {}",
+        "label: while (a) break label;\ndo ; while (0)",
+        "let re = /ab+/gi, n = 10n, s = '😀', x = NaN;",
+        "async function* g() { yield 1; await x; }",
+        "export default class {}\nexport const e = 1;",
+        "let k = typeof a === \"string\", v = void 0;\ndelete a.b;",
+    ];
+    for source in corpus {
+        assert_estree_round_trip(source, false);
+    }
+    assert_estree_round_trip(
+        "<a.b x=\"1\" {...y}><c:d/>hi {} {z} <>{...w}</></a.b>",
+        true,
+    );
+}
+
+#[test]
+fn test_from_estree_errors() {
+    let source = "new X()";
+    let json = r#"{
+        "type": "Program", "start": 0, "end": 7, "body": [{
+            "type": "ExpressionStatement", "start": 0, "end": 7,
+            "loc": { "start": { "line": 1, "column": 0 }, "end": { "line": 1, "column": 7 } },
+            "expression": {
+                "type": "NewExpression", "start": 0, "end": 7,
+                "loc": { "start": { "line": 1, "column": 0 }, "end": { "line": 1, "column": 7 } },
+                "callee": { "type": "Identifier", "start": 4, "end": 5, "name": "X" },
+                "arguments": []
+            }
+        }]
+    }"#;
+    let error = from_estree_str(json, source).unwrap_err();
+    assert_eq!(error.message, "Unsupported ESTree node `NewExpression`");
+    assert_eq!((error.line_number, error.pos), (1, 0));
+
+    assert!(from_estree_str("[]", source).is_err());
+    assert!(from_estree_str("{", source).is_err());
+}
+
+#[test]
+fn test_ast_ids_and_interned_names() {
+    let parser = parse("a + a");
+    let Expression::Binary { left, right, .. } = first_expression(&parser) else {
+        panic!("Expected binary expression");
+    };
+    assert_ne!(left, right);
+    assert!(right.node_id().index() < parser.ast.len());
+    let (Expression::Identifier(left), Expression::Identifier(right)) =
+        (&parser.ast[*left], &parser.ast[*right])
+    else {
+        panic!("Expected identifiers");
+    };
+    // Both names are the same atom of the AST's own string table
+    assert_eq!(left.value, right.value);
+    assert_eq!(&parser.ast[left.value], "a");
+    assert_eq!(parser.ast.atoms.len(), 1);
+}
+
+#[test]
+fn test_visit_order_and_skip() {
+    use super::visit::{AstNode, Visit, Walk};
+    use jsompiler_common::Atom;
+
+    // Records identifiers, skipping function bodies, and checks every enter has its exit
+    #[derive(Default)]
+    struct Collector {
+        names: Vec<Atom>,
+        depth: usize,
+    }
+
+    impl Visit for Collector {
+        fn enter(&mut self, node: AstNode) -> Walk {
+            self.depth += 1;
+            match node {
+                AstNode::Identifier(identifier) => self.names.push(identifier.value),
+                AstNode::Statement(_, Statement::BlockStatement(_)) => return Walk::Skip,
+                _ => {}
+            }
+            Walk::Continue
+        }
+
+        fn exit(&mut self, _node: AstNode) {
+            self.depth -= 1;
+        }
+    }
+
+    let parser =
+        parse("let a = b + c.d;\nfunction f(x, { y = z }) { hidden; }\nclass K { #p = `a${q}`; }");
+    let mut collector = Collector::default();
+    collector.visit_program(&parser.ast);
+    let names = collector
+        .names
+        .iter()
+        .map(|&name| &parser.ast[name])
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        vec!["a", "b", "c", "d", "f", "x", "y", "z", "K", "#p", "q"]
+    );
+    assert_eq!(collector.depth, 0);
+}
+
+#[test]
+fn test_visit_mut_renames() {
+    use super::visit::VisitMut;
+    use super::{Ast, Identifier};
+
+    // Overriding a visit method replaces the walk for that node type
+    struct Rename;
+
+    impl VisitMut for Rename {
+        fn visit_identifier(&mut self, ast: &mut Ast, identifier: &mut Identifier) {
+            let name = ast[identifier.value].to_uppercase();
+            identifier.value = ast.atoms.intern(&name);
+        }
+    }
+
+    let mut parser = parse("[f(a), b];");
+    Rename.visit_program(&mut parser.ast);
+    let Expression::ArrayLiteral { elements, .. } = first_expression(&parser) else {
+        panic!("Expected array");
+    };
+    assert!(matches!(
+        &parser.ast[elements[0]],
+        Expression::FunctionCall { callee, args, .. }
+            if matches!(&parser.ast[*callee], Expression::Identifier(name) if &parser.ast[name.value] == "F")
+                && matches!(&parser.ast[args[0]], Expression::Identifier(name) if &parser.ast[name.value] == "A")
+    ));
+    assert!(
+        matches!(&parser.ast[elements[1]], Expression::Identifier(name) if &parser.ast[name.value] == "B")
+    );
+}
+
+#[test]
+fn test_comments_are_trivia() {
+    use super::CommentKind;
+
+    // Comments anywhere between tokens leave the program unchanged
+    let parser = parse("#!/usr/bin/env node\nx = /* one */ f(1, /* two */ 2); // three\n");
+    assert_eq!(parser.errors, vec![]);
+    assert_eq!(parser.ast.hashbang.as_deref(), Some("/usr/bin/env node"));
+    let texts: Vec<_> = parser
+        .ast
+        .comments
+        .iter()
+        .map(|comment| (comment.kind, comment.text.as_str()))
+        .collect();
+    assert_eq!(
+        texts,
+        vec![
+            (CommentKind::Block, " one "),
+            (CommentKind::Block, " two "),
+            (CommentKind::Line, " three")
+        ]
+    );
+
+    // A block comment spanning lines ends the statement like a line break
+    let parser = parse("a = 1 /*\n*/ b = 2");
+    assert_eq!(parser.errors, vec![]);
+    assert_eq!(parser.ast.body.len(), 2);
+}
+
+#[test]
+fn test_comment_attachment() {
+    use super::CommentPlacement::{Dangling, Leading, Trailing};
+    use super::{CommentPlacement, NodeId};
+
+    let parser =
+        parse("// first\na = 1; // a\nb = /* two */ 2;\nfunction f() { /* empty */ }\n// end\n");
+    assert_eq!(parser.errors, vec![]);
+    let statements: Vec<NodeId> = parser
+        .ast
+        .body
+        .iter()
+        .flat_map(|node| match node {
+            Node::Statement(statements) => statements.iter().map(|&id| id.into()),
+            other => panic!("Expected statement, found {:?}", other),
+        })
+        .collect();
+    let placed = |node: NodeId| -> Vec<(&str, CommentPlacement)> {
+        parser
+            .ast
+            .comments_of(node)
+            .map(|comment| (comment.text.as_str(), comment.attachment.unwrap().1))
+            .collect()
+    };
+
+    assert_eq!(
+        placed(statements[0]),
+        vec![(" first", Leading), (" a", Trailing)]
+    );
+    assert_eq!(placed(statements[2]), vec![(" end", Trailing)]);
+
+    // The function body is empty, so its comment dangles inside it
+    let empty = &parser.ast.comments[3];
+    assert_eq!(empty.text, " empty ");
+    assert_eq!(empty.attachment.unwrap().1, Dangling);
+
+    // A comment in the middle of a statement goes to the node right after it, the `2`
+    let two = &parser.ast.comments[2];
+    assert_eq!(two.text, " two ");
+    let (node, placement) = two.attachment.unwrap();
+    assert_eq!(placement, Leading);
+    assert!(!statements.contains(&node));
+}
+
+#[test]
+fn test_line_breaks_inside_expressions() {
+    // A line break after `(`, `,` or an operator, or before `)`, doesn't end the statement
+    let parser = parse("f(\n  a.b,\n  c + 1,\n);\nlet x =\n  y +\n  z,\n  w = [\n    1,\n  ];\n");
+    assert_eq!(parser.errors, vec![]);
+    let statements: Vec<&Statement> = parser
+        .ast
+        .body
+        .iter()
+        .flat_map(|node| match node {
+            Node::Statement(statements) => statements.iter().map(|&id| &parser.ast[id]),
+            other => panic!("Expected statement, found {:?}", other),
+        })
+        .collect();
+    assert_eq!(statements.len(), 3);
+    let Statement::ExpressionStatement { expression, .. } = statements[0] else {
+        panic!(
+            "Expected an expression statement, found {:?}",
+            statements[0]
+        );
+    };
+    let Expression::FunctionCall { args, .. } = &parser.ast[*expression] else {
+        panic!("Expected a call");
+    };
+    assert_eq!(args.len(), 2);
+
+    // Elsewhere a line break still separates statements
+    let parser = parse("a()\nb = 1\nc\n++d\n");
+    assert_eq!(parser.errors, vec![]);
+    let count: usize = parser
+        .ast
+        .body
+        .iter()
+        .map(|node| match node {
+            Node::Statement(statements) => statements.len(),
+            _ => 0,
+        })
+        .sum();
+    assert_eq!(count, 4);
+}
@@ -0,0 +1,42 @@
+use super::{ExpressionId, Parser, Span, Statement};
+use crate::{Error, ErrorKind};
+use jsompiler_lexer::symbol::{DelimiterToken, KeywordToken, Token};
+
+#[derive(Debug, Clone)]
+pub struct ThrowStatement {
+    pub argument: ExpressionId,
+    pub span: Span,
+}
+
+impl Parser {
+    pub fn parse_throw_statement(&mut self) -> Result<Vec<Statement>, Vec<Error>> {
+        let start = self.current;
+        if !self.match_token(&Token::Keyword(KeywordToken::Throw)) {
+            return Err(vec![
+                self.error(ErrorKind::UnexpectedToken, "Expected 'throw'")
+            ]);
+        }
+
+        // Unlike `return`, a line break here is an error rather than an inserted semicolon
+        if self.check(&Token::Delimiter(DelimiterToken::NewLine)) {
+            return Err(vec![
+                self.error(ErrorKind::SyntaxError, "Illegal newline after throw")
+            ]);
+        }
+
+        let argument = self.expression()?;
+        let argument = self.ast.alloc_expression(argument);
+
+        if !self.consume_semicolon() {
+            return Err(vec![self.error(
+                ErrorKind::UnexpectedToken,
+                "Expected ';' after throw statement",
+            )]);
+        }
+
+        Ok(vec![Statement::ThrowStatement(ThrowStatement {
+            argument,
+            span: self.span_from(start),
+        })])
+    }
+}
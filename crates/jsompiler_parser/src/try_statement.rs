@@ -0,0 +1,95 @@
+use super::{Parser, Span, Statement, StatementId};
+use crate::binding_pattern::BindingPattern;
+use crate::{Error, ErrorKind};
+use jsompiler_lexer::symbol::{DelimiterToken, KeywordToken, Token};
+
+#[derive(Debug, Clone)]
+pub struct TryStatement {
+    pub block: Vec<StatementId>,
+    pub handler: Option<CatchClause>,
+    pub finalizer: Option<Vec<StatementId>>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub struct CatchClause {
+    pub param: Option<BindingPattern>, // `None` for `catch { ... }`
+    pub body: Vec<StatementId>,
+    pub span: Span,
+}
+
+impl Parser {
+    pub fn parse_try_statement(&mut self) -> Result<Vec<Statement>, Vec<Error>> {
+        let start = self.current;
+        if !self.match_token(&Token::Keyword(KeywordToken::Try)) {
+            return Err(vec![
+                self.error(ErrorKind::UnexpectedToken, "Expected 'try'")
+            ]);
+        }
+
+        let block = self.parse_required_block("try")?;
+
+        let checkpoint = self.current;
+        self.skip_newlines();
+        let catch_start = self.current;
+        let handler = if self.match_token(&Token::Keyword(KeywordToken::Catch)) {
+            let param = if self.match_token(&Token::Delimiter(DelimiterToken::OpenParen)) {
+                let param = self.parse_binding_pattern()?;
+                if !self.match_token(&Token::Delimiter(DelimiterToken::CloseParen)) {
+                    return Err(vec![self.error(
+                        ErrorKind::UnexpectedToken,
+                        "Expected ')' after catch parameter",
+                    )]);
+                }
+                Some(param)
+            } else {
+                None
+            };
+            let body = self.parse_required_block("catch")?;
+            Some(CatchClause {
+                param,
+                body,
+                span: self.span_from(catch_start),
+            })
+        } else {
+            self.current = checkpoint;
+            None
+        };
+
+        let checkpoint = self.current;
+        self.skip_newlines();
+        let finalizer = if self.match_token(&Token::Keyword(KeywordToken::Finally)) {
+            Some(self.parse_required_block("finally")?)
+        } else {
+            self.current = checkpoint;
+            None
+        };
+
+        if handler.is_none() && finalizer.is_none() {
+            return Err(vec![self.error(
+                ErrorKind::SyntaxError,
+                "Missing catch or finally after try",
+            )]);
+        }
+
+        Ok(vec![Statement::TryStatement(TryStatement {
+            block,
+            handler,
+            finalizer,
+            span: self.span_from(start),
+        })])
+    }
+
+    // `try`, `catch` and `finally` only accept blocks, not single statements
+    fn parse_required_block(&mut self, keyword: &str) -> Result<Vec<StatementId>, Vec<Error>> {
+        self.skip_newlines();
+        if !self.check(&Token::Delimiter(DelimiterToken::OpenBrace)) {
+            return Err(vec![self.error(
+                ErrorKind::UnexpectedToken,
+                format!("Expected '{{' after '{}'", keyword),
+            )]);
+        }
+        let block = self.parse_block_statement()?;
+        Ok(self.ast.alloc_statements(block))
+    }
+}
@@ -0,0 +1,1422 @@
+use crate::binding_pattern::{BindingElement, BindingPattern, BindingProperty};
+use crate::class_expression::{ClassElement, ClassElementName};
+use crate::export_statement::ExportStatement;
+use crate::expression::Expression;
+use crate::for_in_of_statement::{ForInOfLeft, ForInStatement, ForOfStatement};
+use crate::function_expression::Parameter;
+use crate::jsx_expression::{
+    JSXAttribute, JSXAttributeValue, JSXChild, JSXElement, JSXElementName, JSXExpressionContainer,
+    JSXFragment,
+};
+use crate::object_expression::{Property, PropertyKey};
+use crate::switch_statement::SwitchCase;
+use crate::template_literal::TemplateLiteral;
+use crate::try_statement::CatchClause;
+use crate::{Ast, ExpressionId, Identifier, Node, Span, Statement, StatementId};
+
+// Returned from `enter` to decide whether the children of a node are walked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Walk {
+    #[default]
+    Continue,
+    Skip,
+}
+
+// The nodes handed to `enter` and `exit`
+#[derive(Debug, Clone, Copy)]
+pub enum AstNode<'a> {
+    Statement(StatementId, &'a Statement),
+    Expression(ExpressionId, &'a Expression),
+    Identifier(&'a Identifier),
+    Parameter(&'a Parameter),
+    BindingPattern(&'a BindingPattern),
+    Property(&'a Property),
+    PropertyKey(&'a PropertyKey),
+    ClassElement(&'a ClassElement),
+    ClassElementName(&'a ClassElementName),
+    TemplateLiteral(&'a TemplateLiteral),
+    SwitchCase(&'a SwitchCase),
+    CatchClause(&'a CatchClause),
+    JSXElement(&'a JSXElement),
+    JSXFragment(&'a JSXFragment),
+    JSXElementName(&'a JSXElementName),
+    JSXAttribute(&'a JSXAttribute),
+    JSXChild(&'a JSXChild),
+    JSXExpressionContainer(&'a JSXExpressionContainer),
+}
+
+// Walks the AST in source order. Every `visit_*` method calls `enter`, walks the children
+// unless `enter` returned `Walk::Skip`, then calls `exit`. Override `visit_*` to replace the
+// traversal of a node type and call the matching `walk_*` function to keep going. Statements
+// and expressions are visited by ID and looked up in the `Ast` passed along.
+pub trait Visit {
+    fn enter(&mut self, _node: AstNode) -> Walk {
+        Walk::Continue
+    }
+
+    fn exit(&mut self, _node: AstNode) {}
+
+    fn visit_program(&mut self, ast: &Ast) {
+        walk_program(self, ast);
+    }
+
+    fn visit_statement(&mut self, ast: &Ast, id: StatementId) {
+        let statement = &ast[id];
+        if self.enter(AstNode::Statement(id, statement)) == Walk::Continue {
+            walk_statement(self, ast, statement);
+        }
+        self.exit(AstNode::Statement(id, statement));
+    }
+
+    fn visit_expression(&mut self, ast: &Ast, id: ExpressionId) {
+        let expression = &ast[id];
+        if self.enter(AstNode::Expression(id, expression)) == Walk::Continue {
+            walk_expression(self, ast, expression);
+        }
+        self.exit(AstNode::Expression(id, expression));
+    }
+
+    // Identifiers have no children, so only `enter` and `exit` are called
+    fn visit_identifier(&mut self, _ast: &Ast, identifier: &Identifier) {
+        self.enter(AstNode::Identifier(identifier));
+        self.exit(AstNode::Identifier(identifier));
+    }
+
+    fn visit_parameter(&mut self, ast: &Ast, parameter: &Parameter) {
+        if self.enter(AstNode::Parameter(parameter)) == Walk::Continue {
+            walk_parameter(self, ast, parameter);
+        }
+        self.exit(AstNode::Parameter(parameter));
+    }
+
+    fn visit_binding_pattern(&mut self, ast: &Ast, pattern: &BindingPattern) {
+        if self.enter(AstNode::BindingPattern(pattern)) == Walk::Continue {
+            walk_binding_pattern(self, ast, pattern);
+        }
+        self.exit(AstNode::BindingPattern(pattern));
+    }
+
+    fn visit_property(&mut self, ast: &Ast, property: &Property) {
+        if self.enter(AstNode::Property(property)) == Walk::Continue {
+            walk_property(self, ast, property);
+        }
+        self.exit(AstNode::Property(property));
+    }
+
+    fn visit_property_key(&mut self, ast: &Ast, key: &PropertyKey) {
+        if self.enter(AstNode::PropertyKey(key)) == Walk::Continue {
+            walk_property_key(self, ast, key);
+        }
+        self.exit(AstNode::PropertyKey(key));
+    }
+
+    fn visit_class_element(&mut self, ast: &Ast, element: &ClassElement) {
+        if self.enter(AstNode::ClassElement(element)) == Walk::Continue {
+            walk_class_element(self, ast, element);
+        }
+        self.exit(AstNode::ClassElement(element));
+    }
+
+    fn visit_class_element_name(&mut self, ast: &Ast, name: &ClassElementName) {
+        if self.enter(AstNode::ClassElementName(name)) == Walk::Continue {
+            walk_class_element_name(self, ast, name);
+        }
+        self.exit(AstNode::ClassElementName(name));
+    }
+
+    fn visit_template_literal(&mut self, ast: &Ast, template: &TemplateLiteral) {
+        if self.enter(AstNode::TemplateLiteral(template)) == Walk::Continue {
+            walk_template_literal(self, ast, template);
+        }
+        self.exit(AstNode::TemplateLiteral(template));
+    }
+
+    fn visit_switch_case(&mut self, ast: &Ast, case: &SwitchCase) {
+        if self.enter(AstNode::SwitchCase(case)) == Walk::Continue {
+            walk_switch_case(self, ast, case);
+        }
+        self.exit(AstNode::SwitchCase(case));
+    }
+
+    fn visit_catch_clause(&mut self, ast: &Ast, clause: &CatchClause) {
+        if self.enter(AstNode::CatchClause(clause)) == Walk::Continue {
+            walk_catch_clause(self, ast, clause);
+        }
+        self.exit(AstNode::CatchClause(clause));
+    }
+
+    fn visit_jsx_element(&mut self, ast: &Ast, element: &JSXElement) {
+        if self.enter(AstNode::JSXElement(element)) == Walk::Continue {
+            walk_jsx_element(self, ast, element);
+        }
+        self.exit(AstNode::JSXElement(element));
+    }
+
+    fn visit_jsx_fragment(&mut self, ast: &Ast, fragment: &JSXFragment) {
+        if self.enter(AstNode::JSXFragment(fragment)) == Walk::Continue {
+            walk_jsx_fragment(self, ast, fragment);
+        }
+        self.exit(AstNode::JSXFragment(fragment));
+    }
+
+    fn visit_jsx_element_name(&mut self, ast: &Ast, name: &JSXElementName) {
+        if self.enter(AstNode::JSXElementName(name)) == Walk::Continue {
+            walk_jsx_element_name(self, ast, name);
+        }
+        self.exit(AstNode::JSXElementName(name));
+    }
+
+    fn visit_jsx_attribute(&mut self, ast: &Ast, attribute: &JSXAttribute) {
+        if self.enter(AstNode::JSXAttribute(attribute)) == Walk::Continue {
+            walk_jsx_attribute(self, ast, attribute);
+        }
+        self.exit(AstNode::JSXAttribute(attribute));
+    }
+
+    fn visit_jsx_child(&mut self, ast: &Ast, child: &JSXChild) {
+        if self.enter(AstNode::JSXChild(child)) == Walk::Continue {
+            walk_jsx_child(self, ast, child);
+        }
+        self.exit(AstNode::JSXChild(child));
+    }
+
+    fn visit_jsx_expression_container(&mut self, ast: &Ast, container: &JSXExpressionContainer) {
+        if self.enter(AstNode::JSXExpressionContainer(container)) == Walk::Continue {
+            walk_jsx_expression_container(self, ast, container);
+        }
+        self.exit(AstNode::JSXExpressionContainer(container));
+    }
+}
+
+pub fn walk_program<V: Visit + ?Sized>(visitor: &mut V, ast: &Ast) {
+    for node in &ast.body {
+        match node {
+            Node::Statement(statements) => walk_statements(visitor, ast, statements),
+            Node::Expression(expression) => visitor.visit_expression(ast, *expression),
+        }
+    }
+}
+
+fn walk_statements<V: Visit + ?Sized>(visitor: &mut V, ast: &Ast, statements: &[StatementId]) {
+    for statement in statements {
+        visitor.visit_statement(ast, *statement);
+    }
+}
+
+fn walk_parameters<V: Visit + ?Sized>(visitor: &mut V, ast: &Ast, parameters: &[Parameter]) {
+    for parameter in parameters {
+        visitor.visit_parameter(ast, parameter);
+    }
+}
+
+pub fn walk_statement<V: Visit + ?Sized>(visitor: &mut V, ast: &Ast, statement: &Statement) {
+    match statement {
+        Statement::LetStatement(statement) => {
+            visitor.visit_binding_pattern(ast, &statement.name);
+            if let Some(value) = &statement.value {
+                visitor.visit_expression(ast, *value);
+            }
+        }
+        Statement::ReturnStatement(statement) => {
+            if let Some(value) = &statement.value {
+                visitor.visit_expression(ast, *value);
+            }
+        }
+        Statement::ExpressionStatement { expression, .. } => {
+            visitor.visit_expression(ast, *expression)
+        }
+        Statement::BlockStatement(block) => walk_statements(visitor, ast, &block.statements),
+        Statement::IfStatement(statement) => {
+            visitor.visit_expression(ast, statement.condition);
+            walk_statements(visitor, ast, &statement.consequence);
+            if let Some(alternative) = &statement.alternative {
+                walk_statements(visitor, ast, alternative);
+            }
+        }
+        Statement::WhileStatement(statement) => {
+            visitor.visit_expression(ast, statement.condition);
+            walk_statements(visitor, ast, &statement.consequence);
+        }
+        Statement::AssignmentStatement(statement) => {
+            visitor.visit_expression(ast, statement.target);
+            visitor.visit_expression(ast, statement.value);
+        }
+        Statement::ForLoopStatement(statement) => {
+            walk_statements(visitor, ast, &statement.initializer);
+            walk_statements(visitor, ast, &statement.condition);
+            walk_statements(visitor, ast, &statement.increment);
+            walk_statements(visitor, ast, &statement.body);
+        }
+        Statement::YieldStatement(statement) => {
+            for expression in &statement.expression {
+                visitor.visit_expression(ast, *expression);
+            }
+        }
+        Statement::FunctionDeclaration(function) => {
+            if let Some(name) = &function.name {
+                visitor.visit_identifier(ast, name);
+            }
+            walk_parameters(visitor, ast, &function.parameters);
+            walk_statements(visitor, ast, &function.body);
+        }
+        Statement::ClassDeclaration(class) => {
+            if let Some(identifier) = &class.identifier {
+                visitor.visit_identifier(ast, identifier);
+            }
+            if let Some(heritage) = &class.heritage {
+                visitor.visit_expression(ast, *heritage);
+            }
+            for element in &class.body {
+                visitor.visit_class_element(ast, element);
+            }
+        }
+        Statement::ExportStatement(export) => match export {
+            ExportStatement::Declaration { declarations, .. } => {
+                walk_statements(visitor, ast, declarations)
+            }
+            ExportStatement::DefaultDeclaration { declaration, .. } => {
+                visitor.visit_statement(ast, *declaration)
+            }
+            ExportStatement::DefaultExpression { expression, .. } => {
+                visitor.visit_expression(ast, *expression)
+            }
+        },
+        Statement::LabeledStatement(statement) => {
+            visitor.visit_identifier(ast, &statement.label);
+            walk_statements(visitor, ast, &statement.body);
+        }
+        Statement::DoWhileStatement(statement) => {
+            walk_statements(visitor, ast, &statement.body);
+            visitor.visit_expression(ast, statement.condition);
+        }
+        Statement::ForInStatement(ForInStatement {
+            left, right, body, ..
+        })
+        | Statement::ForOfStatement(ForOfStatement {
+            left, right, body, ..
+        }) => {
+            match left {
+                ForInOfLeft::Declaration { name, .. } => visitor.visit_binding_pattern(ast, name),
+                ForInOfLeft::Target(target) => visitor.visit_expression(ast, *target),
+            }
+            visitor.visit_expression(ast, *right);
+            walk_statements(visitor, ast, body);
+        }
+        Statement::SwitchStatement(statement) => {
+            visitor.visit_expression(ast, statement.discriminant);
+            for case in &statement.cases {
+                visitor.visit_switch_case(ast, case);
+            }
+        }
+        Statement::ThrowStatement(statement) => visitor.visit_expression(ast, statement.argument),
+        Statement::TryStatement(statement) => {
+            walk_statements(visitor, ast, &statement.block);
+            if let Some(handler) = &statement.handler {
+                visitor.visit_catch_clause(ast, handler);
+            }
+            if let Some(finalizer) = &statement.finalizer {
+                walk_statements(visitor, ast, finalizer);
+            }
+        }
+        Statement::WithStatement(statement) => {
+            visitor.visit_expression(ast, statement.object);
+            walk_statements(visitor, ast, &statement.body);
+        }
+        Statement::BreakStatement { label, .. } | Statement::ContinueStatement { label, .. } => {
+            if let Some(label) = label {
+                visitor.visit_identifier(ast, label);
+            }
+        }
+        Statement::EmptyStatement { .. }
+        | Statement::DebuggerStatement { .. }
+        | Statement::Error { .. } => {}
+    }
+}
+
+pub fn walk_expression<V: Visit + ?Sized>(visitor: &mut V, ast: &Ast, expression: &Expression) {
+    match expression {
+        Expression::Identifier(identifier) | Expression::PrivateIdentifier(identifier) => {
+            visitor.visit_identifier(ast, identifier)
+        }
+        Expression::TemplateLiteral(template) => visitor.visit_template_literal(ast, template),
+        Expression::SpreadElement { argument, .. }
+        | Expression::AwaitExpression { argument, .. } => visitor.visit_expression(ast, *argument),
+        Expression::MemberAccess {
+            object, property, ..
+        } => {
+            visitor.visit_expression(ast, *object);
+            visitor.visit_expression(ast, *property);
+        }
+        Expression::Unary { expr, .. } => visitor.visit_expression(ast, *expr),
+        Expression::Binary { left, right, .. } => {
+            visitor.visit_expression(ast, *left);
+            visitor.visit_expression(ast, *right);
+        }
+        Expression::FunctionCall { callee, args, .. } => {
+            visitor.visit_expression(ast, *callee);
+            for arg in args {
+                visitor.visit_expression(ast, *arg);
+            }
+        }
+        // Holes are visited as the undefined literals they are stored as
+        Expression::ArrayLiteral { elements, .. } => {
+            for element in elements {
+                visitor.visit_expression(ast, *element);
+            }
+        }
+        Expression::ObjectLiteral { properties, .. } => {
+            for property in properties {
+                visitor.visit_property(ast, property);
+            }
+        }
+        Expression::ClassExpression(class) => {
+            if let Some(identifier) = &class.identifier {
+                visitor.visit_identifier(ast, identifier);
+            }
+            if let Some(heritage) = &class.heritage {
+                visitor.visit_expression(ast, *heritage);
+            }
+            for element in &class.body {
+                visitor.visit_class_element(ast, element);
+            }
+        }
+        Expression::FunctionExpression(function)
+        | Expression::GeneratorExpression(function)
+        | Expression::AsyncFunctionExpression(function)
+        | Expression::AsyncGeneratorExpression(function) => {
+            if let Some(name) = &function.name {
+                visitor.visit_identifier(ast, name);
+            }
+            walk_parameters(visitor, ast, &function.parameters);
+            walk_statements(visitor, ast, &function.body);
+        }
+        Expression::ArrowFunctionExpression {
+            parameters, body, ..
+        } => {
+            walk_parameters(visitor, ast, parameters);
+            walk_statements(visitor, ast, body);
+        }
+        Expression::JSXElement(element) => visitor.visit_jsx_element(ast, element),
+        Expression::JSXFragment(fragment) => visitor.visit_jsx_fragment(ast, fragment),
+        Expression::Literal { .. }
+        | Expression::ThisExpression { .. }
+        | Expression::RegularExpressionLiteral { .. } => {}
+    }
+}
+
+pub fn walk_parameter<V: Visit + ?Sized>(visitor: &mut V, ast: &Ast, parameter: &Parameter) {
+    match parameter {
+        Parameter::Identifier(identifier) => visitor.visit_identifier(ast, identifier),
+        Parameter::Pattern(pattern)
+        | Parameter::RestParameter {
+            argument: pattern, ..
+        } => visitor.visit_binding_pattern(ast, pattern),
+        Parameter::Default {
+            target, default, ..
+        } => {
+            visitor.visit_binding_pattern(ast, target);
+            visitor.visit_expression(ast, *default);
+        }
+    }
+}
+
+fn walk_binding_element<V: Visit + ?Sized>(visitor: &mut V, ast: &Ast, element: &BindingElement) {
+    visitor.visit_binding_pattern(ast, &element.target);
+    if let Some(default) = &element.default {
+        visitor.visit_expression(ast, *default);
+    }
+}
+
+pub fn walk_binding_pattern<V: Visit + ?Sized>(
+    visitor: &mut V,
+    ast: &Ast,
+    pattern: &BindingPattern,
+) {
+    match pattern {
+        BindingPattern::Identifier(identifier) => visitor.visit_identifier(ast, identifier),
+        BindingPattern::ObjectPattern {
+            properties, rest, ..
+        } => {
+            for property in properties {
+                match property {
+                    BindingProperty::KeyValue { key, value, .. } => {
+                        visitor.visit_property_key(ast, key);
+                        walk_binding_element(visitor, ast, value);
+                    }
+                    BindingProperty::Shorthand { name, default, .. } => {
+                        visitor.visit_identifier(ast, name);
+                        if let Some(default) = default {
+                            visitor.visit_expression(ast, *default);
+                        }
+                    }
+                }
+            }
+            if let Some(rest) = rest {
+                visitor.visit_binding_pattern(ast, &rest.argument);
+            }
+        }
+        BindingPattern::ArrayPattern { elements, rest, .. } => {
+            for element in elements.iter().flatten() {
+                walk_binding_element(visitor, ast, element);
+            }
+            if let Some(rest) = rest {
+                visitor.visit_binding_pattern(ast, &rest.argument);
+            }
+        }
+    }
+}
+
+pub fn walk_property<V: Visit + ?Sized>(visitor: &mut V, ast: &Ast, property: &Property) {
+    match property {
+        Property::KeyValue { key, value, .. } => {
+            visitor.visit_property_key(ast, key);
+            visitor.visit_expression(ast, *value);
+        }
+        Property::Shorthand(identifier) => visitor.visit_identifier(ast, identifier),
+        Property::Method {
+            key, params, body, ..
+        } => {
+            visitor.visit_property_key(ast, key);
+            walk_parameters(visitor, ast, params);
+            walk_statements(visitor, ast, body);
+        }
+        Property::Getter { key, body, .. } => {
+            visitor.visit_property_key(ast, key);
+            visitor.visit_statement(ast, *body);
+        }
+        Property::Setter {
+            key, param, body, ..
+        } => {
+            visitor.visit_property_key(ast, key);
+            visitor.visit_parameter(ast, param);
+            visitor.visit_statement(ast, *body);
+        }
+        Property::Spread(expression) => visitor.visit_expression(ast, *expression),
+    }
+}
+
+pub fn walk_property_key<V: Visit + ?Sized>(visitor: &mut V, ast: &Ast, key: &PropertyKey) {
+    match key {
+        PropertyKey::Identifier(identifier) => visitor.visit_identifier(ast, identifier),
+        PropertyKey::Computed(expression) => visitor.visit_expression(ast, *expression),
+        PropertyKey::StringLiteral { .. } | PropertyKey::NumberLiteral { .. } => {}
+    }
+}
+
+pub fn walk_class_element<V: Visit + ?Sized>(visitor: &mut V, ast: &Ast, element: &ClassElement) {
+    match element {
+        ClassElement::Constructor {
+            name, params, body, ..
+        }
+        | ClassElement::MethodDefinition {
+            name, params, body, ..
+        }
+        | ClassElement::AsyncMethodDefinition {
+            name, params, body, ..
+        }
+        | ClassElement::GeneratorMethodDefinition {
+            name, params, body, ..
+        }
+        | ClassElement::AsyncGeneratorMethodDefinition {
+            name, params, body, ..
+        } => {
+            visitor.visit_class_element_name(ast, name);
+            walk_parameters(visitor, ast, params);
+            walk_statements(visitor, ast, body);
+        }
+        ClassElement::GetterDefinition { name, body, .. } => {
+            visitor.visit_class_element_name(ast, name);
+            walk_statements(visitor, ast, body);
+        }
+        ClassElement::SetterDefinition {
+            name, param, body, ..
+        } => {
+            visitor.visit_class_element_name(ast, name);
+            visitor.visit_parameter(ast, param);
+            walk_statements(visitor, ast, body);
+        }
+        ClassElement::FieldDefinition { name, value, .. }
+        | ClassElement::AccessorDefinition { name, value, .. } => {
+            visitor.visit_class_element_name(ast, name);
+            if let Some(value) = value {
+                visitor.visit_expression(ast, *value);
+            }
+        }
+        ClassElement::StaticBlock { body, .. } => walk_statements(visitor, ast, body),
+    }
+}
+
+pub fn walk_class_element_name<V: Visit + ?Sized>(
+    visitor: &mut V,
+    ast: &Ast,
+    name: &ClassElementName,
+) {
+    match name {
+        ClassElementName::PropertyName(identifier)
+        | ClassElementName::PrivateIdentifier(identifier) => {
+            visitor.visit_identifier(ast, identifier)
+        }
+        ClassElementName::Computed(expression) => visitor.visit_expression(ast, *expression),
+        ClassElementName::StringLiteral { .. } | ClassElementName::NumberLiteral { .. } => {}
+    }
+}
+
+// Quasis are visited as the string literals they are stored as, in source order
+pub fn walk_template_literal<V: Visit + ?Sized>(
+    visitor: &mut V,
+    ast: &Ast,
+    template: &TemplateLiteral,
+) {
+    match template {
+        TemplateLiteral::NonSubstitutionTemplate { text, .. } => {
+            visitor.visit_expression(ast, *text)
+        }
+        TemplateLiteral::SubstitutionTemplate {
+            template_head,
+            expression,
+            template_spans,
+            ..
+        } => {
+            visitor.visit_expression(ast, *template_head);
+            visitor.visit_expression(ast, *expression);
+            let mut middle = template_spans.template_middle_list.as_ref();
+            while let Some(list) = middle {
+                visitor.visit_expression(ast, list.template_middle);
+                visitor.visit_expression(ast, list.expression);
+                middle = list.template_middle_list.as_deref();
+            }
+            visitor.visit_expression(ast, template_spans.template_tail);
+        }
+    }
+}
+
+pub fn walk_switch_case<V: Visit + ?Sized>(visitor: &mut V, ast: &Ast, case: &SwitchCase) {
+    if let Some(test) = &case.test {
+        visitor.visit_expression(ast, *test);
+    }
+    walk_statements(visitor, ast, &case.consequent);
+}
+
+pub fn walk_catch_clause<V: Visit + ?Sized>(visitor: &mut V, ast: &Ast, clause: &CatchClause) {
+    if let Some(param) = &clause.param {
+        visitor.visit_binding_pattern(ast, param);
+    }
+    walk_statements(visitor, ast, &clause.body);
+}
+
+pub fn walk_jsx_element<V: Visit + ?Sized>(visitor: &mut V, ast: &Ast, element: &JSXElement) {
+    visitor.visit_jsx_element_name(ast, &element.opening.name);
+    for attribute in &element.opening.attributes {
+        visitor.visit_jsx_attribute(ast, attribute);
+    }
+    for child in &element.children {
+        visitor.visit_jsx_child(ast, child);
+    }
+    if let Some(closing) = &element.closing {
+        visitor.visit_jsx_element_name(ast, &closing.name);
+    }
+}
+
+pub fn walk_jsx_fragment<V: Visit + ?Sized>(visitor: &mut V, ast: &Ast, fragment: &JSXFragment) {
+    for child in &fragment.children {
+        visitor.visit_jsx_child(ast, child);
+    }
+}
+
+pub fn walk_jsx_element_name<V: Visit + ?Sized>(visitor: &mut V, ast: &Ast, name: &JSXElementName) {
+    match name {
+        JSXElementName::Identifier(identifier) => visitor.visit_identifier(ast, identifier),
+        JSXElementName::Namespaced { namespace, name } => {
+            visitor.visit_identifier(ast, namespace);
+            visitor.visit_identifier(ast, name);
+        }
+        JSXElementName::Member { object, property } => {
+            visitor.visit_jsx_element_name(ast, object);
+            visitor.visit_identifier(ast, property);
+        }
+    }
+}
+
+pub fn walk_jsx_attribute<V: Visit + ?Sized>(visitor: &mut V, ast: &Ast, attribute: &JSXAttribute) {
+    match attribute {
+        JSXAttribute::Attribute { name, value, .. } => {
+            visitor.visit_jsx_element_name(ast, name);
+            match value {
+                Some(JSXAttributeValue::ExpressionContainer(container)) => {
+                    visitor.visit_jsx_expression_container(ast, container)
+                }
+                Some(JSXAttributeValue::Element(element)) => {
+                    visitor.visit_jsx_element(ast, element)
+                }
+                Some(JSXAttributeValue::Fragment(fragment)) => {
+                    visitor.visit_jsx_fragment(ast, fragment)
+                }
+                Some(JSXAttributeValue::StringLiteral { .. }) | None => {}
+            }
+        }
+        JSXAttribute::SpreadAttribute { argument, .. } => visitor.visit_expression(ast, *argument),
+    }
+}
+
+pub fn walk_jsx_child<V: Visit + ?Sized>(visitor: &mut V, ast: &Ast, child: &JSXChild) {
+    match child {
+        JSXChild::Element(element) => visitor.visit_jsx_element(ast, element),
+        JSXChild::Fragment(fragment) => visitor.visit_jsx_fragment(ast, fragment),
+        JSXChild::ExpressionContainer(container) => {
+            visitor.visit_jsx_expression_container(ast, container)
+        }
+        JSXChild::Spread { expression, .. } => visitor.visit_expression(ast, *expression),
+        JSXChild::Text { .. } => {}
+    }
+}
+
+pub fn walk_jsx_expression_container<V: Visit + ?Sized>(
+    visitor: &mut V,
+    ast: &Ast,
+    container: &JSXExpressionContainer,
+) {
+    match container {
+        JSXExpressionContainer::Expression { expression, .. } => {
+            visitor.visit_expression(ast, *expression)
+        }
+        JSXExpressionContainer::Empty { .. } => {}
+    }
+}
+
+// The nodes handed to `enter` and `exit` of `VisitMut`
+#[derive(Debug)]
+pub enum AstNodeMut<'a> {
+    Statement(StatementId, &'a mut Statement),
+    Expression(ExpressionId, &'a mut Expression),
+    Identifier(&'a mut Identifier),
+    Parameter(&'a mut Parameter),
+    BindingPattern(&'a mut BindingPattern),
+    Property(&'a mut Property),
+    PropertyKey(&'a mut PropertyKey),
+    ClassElement(&'a mut ClassElement),
+    ClassElementName(&'a mut ClassElementName),
+    TemplateLiteral(&'a mut TemplateLiteral),
+    SwitchCase(&'a mut SwitchCase),
+    CatchClause(&'a mut CatchClause),
+    JSXElement(&'a mut JSXElement),
+    JSXFragment(&'a mut JSXFragment),
+    JSXElementName(&'a mut JSXElementName),
+    JSXAttribute(&'a mut JSXAttribute),
+    JSXChild(&'a mut JSXChild),
+    JSXExpressionContainer(&'a mut JSXExpressionContainer),
+}
+
+// Like `Visit`, with mutable access so passes can rewrite the tree in place. The walk
+// functions have a `_mut` suffix. A statement or expression is taken out of the arena while
+// it is visited, so looking up its own ID in the `Ast` from inside the walk finds a placeholder.
+pub trait VisitMut {
+    fn enter(&mut self, _node: AstNodeMut) -> Walk {
+        Walk::Continue
+    }
+
+    fn exit(&mut self, _node: AstNodeMut) {}
+
+    fn visit_program(&mut self, ast: &mut Ast) {
+        walk_program_mut(self, ast);
+    }
+
+    fn visit_statement(&mut self, ast: &mut Ast, id: StatementId) {
+        let placeholder = Statement::Error {
+            span: Span::default(),
+        };
+        let mut statement = std::mem::replace(&mut ast[id], placeholder);
+        if self.enter(AstNodeMut::Statement(id, &mut statement)) == Walk::Continue {
+            walk_statement_mut(self, ast, &mut statement);
+        }
+        self.exit(AstNodeMut::Statement(id, &mut statement));
+        ast[id] = statement;
+    }
+
+    fn visit_expression(&mut self, ast: &mut Ast, id: ExpressionId) {
+        let placeholder = Expression::ThisExpression {
+            span: Span::default(),
+        };
+        let mut expression = std::mem::replace(&mut ast[id], placeholder);
+        if self.enter(AstNodeMut::Expression(id, &mut expression)) == Walk::Continue {
+            walk_expression_mut(self, ast, &mut expression);
+        }
+        self.exit(AstNodeMut::Expression(id, &mut expression));
+        ast[id] = expression;
+    }
+
+    // Identifiers have no children, so only `enter` and `exit` are called
+    fn visit_identifier(&mut self, _ast: &mut Ast, identifier: &mut Identifier) {
+        self.enter(AstNodeMut::Identifier(identifier));
+        self.exit(AstNodeMut::Identifier(identifier));
+    }
+
+    fn visit_parameter(&mut self, ast: &mut Ast, parameter: &mut Parameter) {
+        if self.enter(AstNodeMut::Parameter(parameter)) == Walk::Continue {
+            walk_parameter_mut(self, ast, parameter);
+        }
+        self.exit(AstNodeMut::Parameter(parameter));
+    }
+
+    fn visit_binding_pattern(&mut self, ast: &mut Ast, pattern: &mut BindingPattern) {
+        if self.enter(AstNodeMut::BindingPattern(pattern)) == Walk::Continue {
+            walk_binding_pattern_mut(self, ast, pattern);
+        }
+        self.exit(AstNodeMut::BindingPattern(pattern));
+    }
+
+    fn visit_property(&mut self, ast: &mut Ast, property: &mut Property) {
+        if self.enter(AstNodeMut::Property(property)) == Walk::Continue {
+            walk_property_mut(self, ast, property);
+        }
+        self.exit(AstNodeMut::Property(property));
+    }
+
+    fn visit_property_key(&mut self, ast: &mut Ast, key: &mut PropertyKey) {
+        if self.enter(AstNodeMut::PropertyKey(key)) == Walk::Continue {
+            walk_property_key_mut(self, ast, key);
+        }
+        self.exit(AstNodeMut::PropertyKey(key));
+    }
+
+    fn visit_class_element(&mut self, ast: &mut Ast, element: &mut ClassElement) {
+        if self.enter(AstNodeMut::ClassElement(element)) == Walk::Continue {
+            walk_class_element_mut(self, ast, element);
+        }
+        self.exit(AstNodeMut::ClassElement(element));
+    }
+
+    fn visit_class_element_name(&mut self, ast: &mut Ast, name: &mut ClassElementName) {
+        if self.enter(AstNodeMut::ClassElementName(name)) == Walk::Continue {
+            walk_class_element_name_mut(self, ast, name);
+        }
+        self.exit(AstNodeMut::ClassElementName(name));
+    }
+
+    fn visit_template_literal(&mut self, ast: &mut Ast, template: &mut TemplateLiteral) {
+        if self.enter(AstNodeMut::TemplateLiteral(template)) == Walk::Continue {
+            walk_template_literal_mut(self, ast, template);
+        }
+        self.exit(AstNodeMut::TemplateLiteral(template));
+    }
+
+    fn visit_switch_case(&mut self, ast: &mut Ast, case: &mut SwitchCase) {
+        if self.enter(AstNodeMut::SwitchCase(case)) == Walk::Continue {
+            walk_switch_case_mut(self, ast, case);
+        }
+        self.exit(AstNodeMut::SwitchCase(case));
+    }
+
+    fn visit_catch_clause(&mut self, ast: &mut Ast, clause: &mut CatchClause) {
+        if self.enter(AstNodeMut::CatchClause(clause)) == Walk::Continue {
+            walk_catch_clause_mut(self, ast, clause);
+        }
+        self.exit(AstNodeMut::CatchClause(clause));
+    }
+
+    fn visit_jsx_element(&mut self, ast: &mut Ast, element: &mut JSXElement) {
+        if self.enter(AstNodeMut::JSXElement(element)) == Walk::Continue {
+            walk_jsx_element_mut(self, ast, element);
+        }
+        self.exit(AstNodeMut::JSXElement(element));
+    }
+
+    fn visit_jsx_fragment(&mut self, ast: &mut Ast, fragment: &mut JSXFragment) {
+        if self.enter(AstNodeMut::JSXFragment(fragment)) == Walk::Continue {
+            walk_jsx_fragment_mut(self, ast, fragment);
+        }
+        self.exit(AstNodeMut::JSXFragment(fragment));
+    }
+
+    fn visit_jsx_element_name(&mut self, ast: &mut Ast, name: &mut JSXElementName) {
+        if self.enter(AstNodeMut::JSXElementName(name)) == Walk::Continue {
+            walk_jsx_element_name_mut(self, ast, name);
+        }
+        self.exit(AstNodeMut::JSXElementName(name));
+    }
+
+    fn visit_jsx_attribute(&mut self, ast: &mut Ast, attribute: &mut JSXAttribute) {
+        if self.enter(AstNodeMut::JSXAttribute(attribute)) == Walk::Continue {
+            walk_jsx_attribute_mut(self, ast, attribute);
+        }
+        self.exit(AstNodeMut::JSXAttribute(attribute));
+    }
+
+    fn visit_jsx_child(&mut self, ast: &mut Ast, child: &mut JSXChild) {
+        if self.enter(AstNodeMut::JSXChild(child)) == Walk::Continue {
+            walk_jsx_child_mut(self, ast, child);
+        }
+        self.exit(AstNodeMut::JSXChild(child));
+    }
+
+    fn visit_jsx_expression_container(
+        &mut self,
+        ast: &mut Ast,
+        container: &mut JSXExpressionContainer,
+    ) {
+        if self.enter(AstNodeMut::JSXExpressionContainer(container)) == Walk::Continue {
+            walk_jsx_expression_container_mut(self, ast, container);
+        }
+        self.exit(AstNodeMut::JSXExpressionContainer(container));
+    }
+}
+
+pub fn walk_program_mut<V: VisitMut + ?Sized>(visitor: &mut V, ast: &mut Ast) {
+    let body = std::mem::take(&mut ast.body);
+    for node in &body {
+        match node {
+            Node::Statement(statements) => walk_statements_mut(visitor, ast, statements),
+            Node::Expression(expression) => visitor.visit_expression(ast, *expression),
+        }
+    }
+    ast.body = body;
+}
+
+fn walk_statements_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    ast: &mut Ast,
+    statements: &[StatementId],
+) {
+    for statement in statements {
+        visitor.visit_statement(ast, *statement);
+    }
+}
+
+fn walk_parameters_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    ast: &mut Ast,
+    parameters: &mut [Parameter],
+) {
+    for parameter in parameters {
+        visitor.visit_parameter(ast, parameter);
+    }
+}
+
+pub fn walk_statement_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    ast: &mut Ast,
+    statement: &mut Statement,
+) {
+    match statement {
+        Statement::LetStatement(statement) => {
+            visitor.visit_binding_pattern(ast, &mut statement.name);
+            if let Some(value) = &mut statement.value {
+                visitor.visit_expression(ast, *value);
+            }
+        }
+        Statement::ReturnStatement(statement) => {
+            if let Some(value) = &mut statement.value {
+                visitor.visit_expression(ast, *value);
+            }
+        }
+        Statement::ExpressionStatement { expression, .. } => {
+            visitor.visit_expression(ast, *expression)
+        }
+        Statement::BlockStatement(block) => walk_statements_mut(visitor, ast, &block.statements),
+        Statement::IfStatement(statement) => {
+            visitor.visit_expression(ast, statement.condition);
+            walk_statements_mut(visitor, ast, &statement.consequence);
+            if let Some(alternative) = &mut statement.alternative {
+                walk_statements_mut(visitor, ast, alternative);
+            }
+        }
+        Statement::WhileStatement(statement) => {
+            visitor.visit_expression(ast, statement.condition);
+            walk_statements_mut(visitor, ast, &statement.consequence);
+        }
+        Statement::AssignmentStatement(statement) => {
+            visitor.visit_expression(ast, statement.target);
+            visitor.visit_expression(ast, statement.value);
+        }
+        Statement::ForLoopStatement(statement) => {
+            walk_statements_mut(visitor, ast, &statement.initializer);
+            walk_statements_mut(visitor, ast, &statement.condition);
+            walk_statements_mut(visitor, ast, &statement.increment);
+            walk_statements_mut(visitor, ast, &statement.body);
+        }
+        Statement::YieldStatement(statement) => {
+            for expression in &mut statement.expression {
+                visitor.visit_expression(ast, *expression);
+            }
+        }
+        Statement::FunctionDeclaration(function) => {
+            if let Some(name) = &mut function.name {
+                visitor.visit_identifier(ast, name);
+            }
+            walk_parameters_mut(visitor, ast, &mut function.parameters);
+            walk_statements_mut(visitor, ast, &function.body);
+        }
+        Statement::ClassDeclaration(class) => {
+            if let Some(identifier) = &mut class.identifier {
+                visitor.visit_identifier(ast, identifier);
+            }
+            if let Some(heritage) = &mut class.heritage {
+                visitor.visit_expression(ast, *heritage);
+            }
+            for element in &mut class.body {
+                visitor.visit_class_element(ast, element);
+            }
+        }
+        Statement::ExportStatement(export) => match export {
+            ExportStatement::Declaration { declarations, .. } => {
+                walk_statements_mut(visitor, ast, declarations)
+            }
+            ExportStatement::DefaultDeclaration { declaration, .. } => {
+                visitor.visit_statement(ast, *declaration)
+            }
+            ExportStatement::DefaultExpression { expression, .. } => {
+                visitor.visit_expression(ast, *expression)
+            }
+        },
+        Statement::LabeledStatement(statement) => {
+            visitor.visit_identifier(ast, &mut statement.label);
+            walk_statements_mut(visitor, ast, &statement.body);
+        }
+        Statement::DoWhileStatement(statement) => {
+            walk_statements_mut(visitor, ast, &statement.body);
+            visitor.visit_expression(ast, statement.condition);
+        }
+        Statement::ForInStatement(ForInStatement {
+            left, right, body, ..
+        })
+        | Statement::ForOfStatement(ForOfStatement {
+            left, right, body, ..
+        }) => {
+            match left {
+                ForInOfLeft::Declaration { name, .. } => visitor.visit_binding_pattern(ast, name),
+                ForInOfLeft::Target(target) => visitor.visit_expression(ast, *target),
+            }
+            visitor.visit_expression(ast, *right);
+            walk_statements_mut(visitor, ast, body);
+        }
+        Statement::SwitchStatement(statement) => {
+            visitor.visit_expression(ast, statement.discriminant);
+            for case in &mut statement.cases {
+                visitor.visit_switch_case(ast, case);
+            }
+        }
+        Statement::ThrowStatement(statement) => visitor.visit_expression(ast, statement.argument),
+        Statement::TryStatement(statement) => {
+            walk_statements_mut(visitor, ast, &statement.block);
+            if let Some(handler) = &mut statement.handler {
+                visitor.visit_catch_clause(ast, handler);
+            }
+            if let Some(finalizer) = &mut statement.finalizer {
+                walk_statements_mut(visitor, ast, finalizer);
+            }
+        }
+        Statement::WithStatement(statement) => {
+            visitor.visit_expression(ast, statement.object);
+            walk_statements_mut(visitor, ast, &statement.body);
+        }
+        Statement::BreakStatement { label, .. } | Statement::ContinueStatement { label, .. } => {
+            if let Some(label) = label {
+                visitor.visit_identifier(ast, label);
+            }
+        }
+        Statement::EmptyStatement { .. }
+        | Statement::DebuggerStatement { .. }
+        | Statement::Error { .. } => {}
+    }
+}
+
+pub fn walk_expression_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    ast: &mut Ast,
+    expression: &mut Expression,
+) {
+    match expression {
+        Expression::Identifier(identifier) | Expression::PrivateIdentifier(identifier) => {
+            visitor.visit_identifier(ast, identifier)
+        }
+        Expression::TemplateLiteral(template) => visitor.visit_template_literal(ast, template),
+        Expression::SpreadElement { argument, .. }
+        | Expression::AwaitExpression { argument, .. } => visitor.visit_expression(ast, *argument),
+        Expression::MemberAccess {
+            object, property, ..
+        } => {
+            visitor.visit_expression(ast, *object);
+            visitor.visit_expression(ast, *property);
+        }
+        Expression::Unary { expr, .. } => visitor.visit_expression(ast, *expr),
+        Expression::Binary { left, right, .. } => {
+            visitor.visit_expression(ast, *left);
+            visitor.visit_expression(ast, *right);
+        }
+        Expression::FunctionCall { callee, args, .. } => {
+            visitor.visit_expression(ast, *callee);
+            for arg in args {
+                visitor.visit_expression(ast, *arg);
+            }
+        }
+        // Holes are visited as the undefined literals they are stored as
+        Expression::ArrayLiteral { elements, .. } => {
+            for element in elements {
+                visitor.visit_expression(ast, *element);
+            }
+        }
+        Expression::ObjectLiteral { properties, .. } => {
+            for property in properties {
+                visitor.visit_property(ast, property);
+            }
+        }
+        Expression::ClassExpression(class) => {
+            if let Some(identifier) = &mut class.identifier {
+                visitor.visit_identifier(ast, identifier);
+            }
+            if let Some(heritage) = &mut class.heritage {
+                visitor.visit_expression(ast, *heritage);
+            }
+            for element in &mut class.body {
+                visitor.visit_class_element(ast, element);
+            }
+        }
+        Expression::FunctionExpression(function)
+        | Expression::GeneratorExpression(function)
+        | Expression::AsyncFunctionExpression(function)
+        | Expression::AsyncGeneratorExpression(function) => {
+            if let Some(name) = &mut function.name {
+                visitor.visit_identifier(ast, name);
+            }
+            walk_parameters_mut(visitor, ast, &mut function.parameters);
+            walk_statements_mut(visitor, ast, &function.body);
+        }
+        Expression::ArrowFunctionExpression {
+            parameters, body, ..
+        } => {
+            walk_parameters_mut(visitor, ast, parameters);
+            walk_statements_mut(visitor, ast, body);
+        }
+        Expression::JSXElement(element) => visitor.visit_jsx_element(ast, element),
+        Expression::JSXFragment(fragment) => visitor.visit_jsx_fragment(ast, fragment),
+        Expression::Literal { .. }
+        | Expression::ThisExpression { .. }
+        | Expression::RegularExpressionLiteral { .. } => {}
+    }
+}
+
+pub fn walk_parameter_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    ast: &mut Ast,
+    parameter: &mut Parameter,
+) {
+    match parameter {
+        Parameter::Identifier(identifier) => visitor.visit_identifier(ast, identifier),
+        Parameter::Pattern(pattern)
+        | Parameter::RestParameter {
+            argument: pattern, ..
+        } => visitor.visit_binding_pattern(ast, pattern),
+        Parameter::Default {
+            target, default, ..
+        } => {
+            visitor.visit_binding_pattern(ast, target);
+            visitor.visit_expression(ast, *default);
+        }
+    }
+}
+
+fn walk_binding_element_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    ast: &mut Ast,
+    element: &mut BindingElement,
+) {
+    visitor.visit_binding_pattern(ast, &mut element.target);
+    if let Some(default) = &mut element.default {
+        visitor.visit_expression(ast, *default);
+    }
+}
+
+pub fn walk_binding_pattern_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    ast: &mut Ast,
+    pattern: &mut BindingPattern,
+) {
+    match pattern {
+        BindingPattern::Identifier(identifier) => visitor.visit_identifier(ast, identifier),
+        BindingPattern::ObjectPattern {
+            properties, rest, ..
+        } => {
+            for property in properties {
+                match property {
+                    BindingProperty::KeyValue { key, value, .. } => {
+                        visitor.visit_property_key(ast, key);
+                        walk_binding_element_mut(visitor, ast, value);
+                    }
+                    BindingProperty::Shorthand { name, default, .. } => {
+                        visitor.visit_identifier(ast, name);
+                        if let Some(default) = default {
+                            visitor.visit_expression(ast, *default);
+                        }
+                    }
+                }
+            }
+            if let Some(rest) = rest {
+                visitor.visit_binding_pattern(ast, &mut rest.argument);
+            }
+        }
+        BindingPattern::ArrayPattern { elements, rest, .. } => {
+            for element in elements.iter_mut().flatten() {
+                walk_binding_element_mut(visitor, ast, element);
+            }
+            if let Some(rest) = rest {
+                visitor.visit_binding_pattern(ast, &mut rest.argument);
+            }
+        }
+    }
+}
+
+pub fn walk_property_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    ast: &mut Ast,
+    property: &mut Property,
+) {
+    match property {
+        Property::KeyValue { key, value, .. } => {
+            visitor.visit_property_key(ast, key);
+            visitor.visit_expression(ast, *value);
+        }
+        Property::Shorthand(identifier) => visitor.visit_identifier(ast, identifier),
+        Property::Method {
+            key, params, body, ..
+        } => {
+            visitor.visit_property_key(ast, key);
+            walk_parameters_mut(visitor, ast, params);
+            walk_statements_mut(visitor, ast, body);
+        }
+        Property::Getter { key, body, .. } => {
+            visitor.visit_property_key(ast, key);
+            visitor.visit_statement(ast, *body);
+        }
+        Property::Setter {
+            key, param, body, ..
+        } => {
+            visitor.visit_property_key(ast, key);
+            visitor.visit_parameter(ast, param);
+            visitor.visit_statement(ast, *body);
+        }
+        Property::Spread(expression) => visitor.visit_expression(ast, *expression),
+    }
+}
+
+pub fn walk_property_key_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    ast: &mut Ast,
+    key: &mut PropertyKey,
+) {
+    match key {
+        PropertyKey::Identifier(identifier) => visitor.visit_identifier(ast, identifier),
+        PropertyKey::Computed(expression) => visitor.visit_expression(ast, *expression),
+        PropertyKey::StringLiteral { .. } | PropertyKey::NumberLiteral { .. } => {}
+    }
+}
+
+pub fn walk_class_element_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    ast: &mut Ast,
+    element: &mut ClassElement,
+) {
+    match element {
+        ClassElement::Constructor {
+            name, params, body, ..
+        }
+        | ClassElement::MethodDefinition {
+            name, params, body, ..
+        }
+        | ClassElement::AsyncMethodDefinition {
+            name, params, body, ..
+        }
+        | ClassElement::GeneratorMethodDefinition {
+            name, params, body, ..
+        }
+        | ClassElement::AsyncGeneratorMethodDefinition {
+            name, params, body, ..
+        } => {
+            visitor.visit_class_element_name(ast, name);
+            walk_parameters_mut(visitor, ast, params);
+            walk_statements_mut(visitor, ast, body);
+        }
+        ClassElement::GetterDefinition { name, body, .. } => {
+            visitor.visit_class_element_name(ast, name);
+            walk_statements_mut(visitor, ast, body);
+        }
+        ClassElement::SetterDefinition {
+            name, param, body, ..
+        } => {
+            visitor.visit_class_element_name(ast, name);
+            visitor.visit_parameter(ast, param);
+            walk_statements_mut(visitor, ast, body);
+        }
+        ClassElement::FieldDefinition { name, value, .. }
+        | ClassElement::AccessorDefinition { name, value, .. } => {
+            visitor.visit_class_element_name(ast, name);
+            if let Some(value) = value {
+                visitor.visit_expression(ast, *value);
+            }
+        }
+        ClassElement::StaticBlock { body, .. } => walk_statements_mut(visitor, ast, body),
+    }
+}
+
+pub fn walk_class_element_name_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    ast: &mut Ast,
+    name: &mut ClassElementName,
+) {
+    match name {
+        ClassElementName::PropertyName(identifier)
+        | ClassElementName::PrivateIdentifier(identifier) => {
+            visitor.visit_identifier(ast, identifier)
+        }
+        ClassElementName::Computed(expression) => visitor.visit_expression(ast, *expression),
+        ClassElementName::StringLiteral { .. } | ClassElementName::NumberLiteral { .. } => {}
+    }
+}
+
+// Quasis are visited as the string literals they are stored as, in source order
+pub fn walk_template_literal_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    ast: &mut Ast,
+    template: &mut TemplateLiteral,
+) {
+    match template {
+        TemplateLiteral::NonSubstitutionTemplate { text, .. } => {
+            visitor.visit_expression(ast, *text)
+        }
+        TemplateLiteral::SubstitutionTemplate {
+            template_head,
+            expression,
+            template_spans,
+            ..
+        } => {
+            visitor.visit_expression(ast, *template_head);
+            visitor.visit_expression(ast, *expression);
+            let mut middle = template_spans.template_middle_list.as_mut();
+            while let Some(list) = middle {
+                visitor.visit_expression(ast, list.template_middle);
+                visitor.visit_expression(ast, list.expression);
+                middle = list.template_middle_list.as_deref_mut();
+            }
+            visitor.visit_expression(ast, template_spans.template_tail);
+        }
+    }
+}
+
+pub fn walk_switch_case_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    ast: &mut Ast,
+    case: &mut SwitchCase,
+) {
+    if let Some(test) = &mut case.test {
+        visitor.visit_expression(ast, *test);
+    }
+    walk_statements_mut(visitor, ast, &case.consequent);
+}
+
+pub fn walk_catch_clause_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    ast: &mut Ast,
+    clause: &mut CatchClause,
+) {
+    if let Some(param) = &mut clause.param {
+        visitor.visit_binding_pattern(ast, param);
+    }
+    walk_statements_mut(visitor, ast, &clause.body);
+}
+
+pub fn walk_jsx_element_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    ast: &mut Ast,
+    element: &mut JSXElement,
+) {
+    visitor.visit_jsx_element_name(ast, &mut element.opening.name);
+    for attribute in &mut element.opening.attributes {
+        visitor.visit_jsx_attribute(ast, attribute);
+    }
+    for child in &mut element.children {
+        visitor.visit_jsx_child(ast, child);
+    }
+    if let Some(closing) = &mut element.closing {
+        visitor.visit_jsx_element_name(ast, &mut closing.name);
+    }
+}
+
+pub fn walk_jsx_fragment_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    ast: &mut Ast,
+    fragment: &mut JSXFragment,
+) {
+    for child in &mut fragment.children {
+        visitor.visit_jsx_child(ast, child);
+    }
+}
+
+pub fn walk_jsx_element_name_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    ast: &mut Ast,
+    name: &mut JSXElementName,
+) {
+    match name {
+        JSXElementName::Identifier(identifier) => visitor.visit_identifier(ast, identifier),
+        JSXElementName::Namespaced { namespace, name } => {
+            visitor.visit_identifier(ast, namespace);
+            visitor.visit_identifier(ast, name);
+        }
+        JSXElementName::Member { object, property } => {
+            visitor.visit_jsx_element_name(ast, object);
+            visitor.visit_identifier(ast, property);
+        }
+    }
+}
+
+pub fn walk_jsx_attribute_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    ast: &mut Ast,
+    attribute: &mut JSXAttribute,
+) {
+    match attribute {
+        JSXAttribute::Attribute { name, value, .. } => {
+            visitor.visit_jsx_element_name(ast, name);
+            match value {
+                Some(JSXAttributeValue::ExpressionContainer(container)) => {
+                    visitor.visit_jsx_expression_container(ast, container)
+                }
+                Some(JSXAttributeValue::Element(element)) => {
+                    visitor.visit_jsx_element(ast, element)
+                }
+                Some(JSXAttributeValue::Fragment(fragment)) => {
+                    visitor.visit_jsx_fragment(ast, fragment)
+                }
+                Some(JSXAttributeValue::StringLiteral { .. }) | None => {}
+            }
+        }
+        JSXAttribute::SpreadAttribute { argument, .. } => visitor.visit_expression(ast, *argument),
+    }
+}
+
+pub fn walk_jsx_child_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    ast: &mut Ast,
+    child: &mut JSXChild,
+) {
+    match child {
+        JSXChild::Element(element) => visitor.visit_jsx_element(ast, element),
+        JSXChild::Fragment(fragment) => visitor.visit_jsx_fragment(ast, fragment),
+        JSXChild::ExpressionContainer(container) => {
+            visitor.visit_jsx_expression_container(ast, container)
+        }
+        JSXChild::Spread { expression, .. } => visitor.visit_expression(ast, *expression),
+        JSXChild::Text { .. } => {}
+    }
+}
+
+pub fn walk_jsx_expression_container_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    ast: &mut Ast,
+    container: &mut JSXExpressionContainer,
+) {
+    match container {
+        JSXExpressionContainer::Expression { expression, .. } => {
+            visitor.visit_expression(ast, *expression)
+        }
+        JSXExpressionContainer::Empty { .. } => {}
+    }
+}
@@ -1,31 +1,33 @@
-use super::{expression::Expression, Parser, Statement};
+use super::{ExpressionId, Parser, Span, Statement, StatementId};
 use crate::{Error, ErrorKind};
 use jsompiler_lexer::symbol::{KeywordToken, Token};
 
 #[derive(Debug, Clone)]
 pub struct WhileStatement {
-    pub condition: Expression,
-    pub consequence: Vec<Statement>,
+    pub condition: ExpressionId,
+    pub consequence: Vec<StatementId>,
+    pub span: Span,
 }
 
 impl Parser {
     pub fn parse_while_statement(&mut self) -> Result<Vec<Statement>, Vec<Error>> {
+        let start = self.current;
         if !self.match_token(&Token::Keyword(KeywordToken::While)) {
-            return Err(vec![Error {
-                error_kind: ErrorKind::UnexpectedToken,
-                message: "Expected 'then' after condition".to_string(),
-                line_number: 1,
-                pos: 2,
-            }]);
+            return Err(vec![self.error(
+                ErrorKind::UnexpectedToken,
+                "Expected 'then' after condition",
+            )]);
         }
         let value = self.parenthesis_expression()?;
-        println!("{:?}", self.peek().token);
-        let consequence = self.parse_block_statement()?;
+        let value = self.ast.alloc_expression(value);
+        let consequence = self.parse_iteration_body()?;
+        let consequence = self.ast.alloc_statements(consequence);
         // Decode expression from statement
 
         Ok(vec![Statement::WhileStatement(WhileStatement {
             condition: value,
             consequence,
+            span: self.span_from(start),
         })])
     }
 }
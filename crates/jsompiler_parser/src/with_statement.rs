@@ -0,0 +1,34 @@
+use super::{ExpressionId, Parser, Span, Statement, StatementId};
+use crate::{Error, ErrorKind};
+
+#[derive(Debug, Clone)]
+pub struct WithStatement {
+    pub object: ExpressionId,
+    pub body: Vec<StatementId>,
+    pub span: Span,
+}
+
+impl Parser {
+    pub fn parse_with_statement(&mut self) -> Result<Vec<Statement>, Vec<Error>> {
+        let start = self.current;
+        self.advance(); // Consume 'with' keyword
+
+        if self.strict_mode {
+            return Err(vec![self.error(
+                ErrorKind::SyntaxError,
+                "Strict mode code may not include a with statement",
+            )]);
+        }
+
+        let object = self.parenthesis_expression()?;
+        let object = self.ast.alloc_expression(object);
+        let body = self.parse_sub_statement()?;
+        let body = self.ast.alloc_statements(body);
+
+        Ok(vec![Statement::WithStatement(WithStatement {
+            object,
+            body,
+            span: self.span_from(start),
+        })])
+    }
+}
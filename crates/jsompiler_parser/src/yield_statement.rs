@@ -1,34 +1,39 @@
-use super::{expression::Expression, Parser, Statement};
+use super::{ExpressionId, Parser, Span, Statement};
 use crate::{Error, ErrorKind};
 use jsompiler_lexer::symbol::{DelimiterToken, Token};
 
 #[derive(Debug, Clone)]
 pub struct YieldStatement {
-    pub expression: Vec<Expression>,
+    pub expression: Vec<ExpressionId>,
+    pub span: Span,
 }
 
 impl Parser {
     pub fn parse_yield_statement(&mut self) -> Result<Vec<Statement>, Vec<Error>> {
+        let start = self.current;
         self.advance(); // Consume 'yield' keyword
 
-        let expression = if self.peek().token == Token::Delimiter(DelimiterToken::Semicolon) {
-            vec![]
-        } else {
-            vec![self.expression()?]
+        let expression = match self.peek().token {
+            Token::Delimiter(DelimiterToken::Semicolon)
+            | Token::Delimiter(DelimiterToken::NewLine)
+            | Token::Delimiter(DelimiterToken::CloseBrace)
+            | Token::EOF => vec![],
+            _ => {
+                let expression = self.expression()?;
+                vec![self.ast.alloc_expression(expression)]
+            }
         };
 
-        // Expect semicolon
-        if !self.match_token(&Token::Delimiter(DelimiterToken::Semicolon)) {
-            return Err(vec![Error {
-                error_kind: ErrorKind::UnexpectedToken,
-                message: "Expected ';' after yield statement".to_string(),
-                line_number: 1,
-                pos: 2,
-            }]);
+        if !self.consume_semicolon() {
+            return Err(vec![self.error(
+                ErrorKind::UnexpectedToken,
+                "Expected ';' after yield statement",
+            )]);
         }
 
         Ok(vec![Statement::YieldStatement(YieldStatement {
             expression,
+            span: self.span_from(start),
         })])
     }
 }
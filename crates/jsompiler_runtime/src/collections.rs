@@ -0,0 +1,227 @@
+use crate::heap::{Trace, Tracer};
+use crate::object::{ObjectRef, WeakObjectRef};
+use crate::value::{Symbol, Value};
+use num_bigint::BigInt;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::rc::{Rc, Weak};
+
+// Map keys compare with SameValueZero: NaN is itself, the two zeros are the same key and
+// objects are compared by identity
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum MapKey {
+    Undefined,
+    Null,
+    Boolean(bool),
+    Number(u64), // The bits of the number, with one NaN and no negative zero
+    BigInt(Rc<BigInt>),
+    String(Rc<str>),
+    Symbol(Symbol),
+    Object(*const ()),
+}
+
+impl MapKey {
+    fn new(value: &Value) -> MapKey {
+        match value {
+            Value::Undefined => MapKey::Undefined,
+            Value::Null => MapKey::Null,
+            Value::Boolean(value) => MapKey::Boolean(*value),
+            Value::Number(value) if value.is_nan() => MapKey::Number(f64::NAN.to_bits()),
+            Value::Number(value) => MapKey::Number((value + 0.0).to_bits()),
+            Value::BigInt(value) => MapKey::BigInt(value.clone()),
+            Value::String(text) => MapKey::String(text.clone()),
+            Value::Symbol(symbol) => MapKey::Symbol(symbol.clone()),
+            Value::Object(object) => MapKey::Object(object.as_ptr()),
+        }
+    }
+}
+
+// The entries of a Map, or the values of a Set, in the order they were added. Deleting an
+// entry leaves a hole, so iterators keep their place while the map changes. The holes are
+// removed once there are many, moving the iterators' cursors along.
+#[derive(Default)]
+pub struct MapData {
+    entries: Vec<Option<(Value, Value)>>,
+    positions: HashMap<MapKey, usize>,
+    cursors: Vec<Weak<Cell<usize>>>,
+}
+
+impl MapData {
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    pub fn get(&self, key: &Value) -> Option<Value> {
+        let position = *self.positions.get(&MapKey::new(key))?;
+        self.entries[position]
+            .as_ref()
+            .map(|(_, value)| value.clone())
+    }
+
+    pub fn has(&self, key: &Value) -> bool {
+        self.positions.contains_key(&MapKey::new(key))
+    }
+
+    // Replace the value of a key, or add it at the end. A key of -0 is stored as +0, which adding
+    // +0 to it gives.
+    pub fn set(&mut self, key: Value, value: Value) {
+        let key = match key {
+            Value::Number(number) => Value::Number(number + 0.0),
+            key => key,
+        };
+        let map_key = MapKey::new(&key);
+        match self.positions.get(&map_key) {
+            Some(&position) => self.entries[position] = Some((key, value)),
+            None => {
+                self.positions.insert(map_key, self.entries.len());
+                self.entries.push(Some((key, value)));
+            }
+        }
+    }
+
+    pub fn delete(&mut self, key: &Value) -> bool {
+        let Some(position) = self.positions.remove(&MapKey::new(key)) else {
+            return false;
+        };
+        self.entries[position] = None;
+        if self.entries.len() >= 16 && self.positions.len() < self.entries.len() / 2 {
+            self.compact();
+        }
+        true
+    }
+
+    pub fn clear(&mut self) {
+        self.positions.clear();
+        self.entries.iter_mut().for_each(|entry| *entry = None);
+        self.compact();
+    }
+
+    // The first entry at or after `position`, and where it is
+    pub fn entry(&self, position: usize) -> Option<(usize, Value, Value)> {
+        self.entries
+            .iter()
+            .enumerate()
+            .skip(position)
+            .find_map(|(position, entry)| {
+                let (key, value) = entry.as_ref()?;
+                Some((position, key.clone(), value.clone()))
+            })
+    }
+
+    // A position in the entries that follows them as holes are removed, for iterating the map
+    // while it changes. It starts at the first entry.
+    pub fn cursor(&mut self) -> Rc<Cell<usize>> {
+        let cursor = Rc::new(Cell::new(0));
+        self.cursors.retain(|cursor| cursor.strong_count() > 0);
+        self.cursors.push(Rc::downgrade(&cursor));
+        cursor
+    }
+
+    fn compact(&mut self) {
+        // How many entries are left before each position
+        let mut moved = Vec::with_capacity(self.entries.len() + 1);
+        let mut count = 0;
+        for entry in &self.entries {
+            moved.push(count);
+            count += entry.is_some() as usize;
+        }
+        moved.push(count);
+        self.cursors.retain(|cursor| match cursor.upgrade() {
+            Some(cursor) => {
+                cursor.set(moved[cursor.get().min(moved.len() - 1)]);
+                true
+            }
+            None => false,
+        });
+        self.entries.retain(Option::is_some);
+        for position in self.positions.values_mut() {
+            *position = moved[*position];
+        }
+    }
+}
+
+impl Trace for MapData {
+    fn trace(&self, tracer: &mut Tracer) {
+        for (key, value) in self.entries.iter().flatten() {
+            key.trace(tracer);
+            value.trace(tracer);
+        }
+    }
+}
+
+// The entries of a WeakMap, which don't keep their keys alive. A value is only reachable
+// through the map while its key is reachable some other way.
+#[derive(Default)]
+pub struct WeakMapData {
+    entries: HashMap<*const (), (WeakObjectRef, Value)>,
+    prune_at: usize, // When to drop the entries whose keys were freed
+}
+
+impl WeakMapData {
+    // A key that was freed can't be looked up any more, since nothing can refer to it. Its
+    // allocation is kept while the entry is, so another object never gets its address.
+    pub fn get(&self, key: &ObjectRef) -> Option<Value> {
+        self.entries
+            .get(&key.as_ptr())
+            .map(|(_, value)| value.clone())
+    }
+
+    pub fn has(&self, key: &ObjectRef) -> bool {
+        self.entries.contains_key(&key.as_ptr())
+    }
+
+    pub fn set(&mut self, key: &ObjectRef, value: Value) {
+        if self.entries.len() >= self.prune_at {
+            self.entries.retain(|_, (key, _)| key.upgrade().is_some());
+            self.prune_at = (self.entries.len() * 2).max(16);
+        }
+        self.entries.insert(key.as_ptr(), (key.downgrade(), value));
+    }
+
+    pub fn delete(&mut self, key: &ObjectRef) -> bool {
+        self.entries.remove(&key.as_ptr()).is_some()
+    }
+}
+
+impl Trace for WeakMapData {
+    fn trace(&self, tracer: &mut Tracer) {
+        for (key, value) in self.entries.values() {
+            tracer.ephemeron(key, value);
+        }
+    }
+}
+
+// What an iterator over the entries of a list produces
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IterationKind {
+    Keys,
+    Values,
+    Entries,
+}
+
+// The state of an array, string, Map or Set iterator
+pub struct ListIterator {
+    pub list: Option<Value>, // `None` once it is done
+    pub position: Rc<Cell<usize>>,
+    pub kind: IterationKind,
+}
+
+impl ListIterator {
+    pub fn new(list: Value, kind: IterationKind) -> Self {
+        ListIterator {
+            list: Some(list),
+            position: Rc::new(Cell::new(0)),
+            kind,
+        }
+    }
+}
+
+impl Trace for ListIterator {
+    fn trace(&self, tracer: &mut Tracer) {
+        self.list.trace(tracer);
+    }
+}
@@ -0,0 +1,298 @@
+use crate::object::{ObjectRef, WeakObjectRef};
+use crate::value::Value;
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::rc::{Rc, Weak};
+
+// A collection runs once this many values were allocated since the last one, or as many as
+// were live after it if that is more
+const MIN_THRESHOLD: usize = 10_000;
+
+// Values that hold references to values on the heap. Objects, closures and environments
+// refer to each other in cycles that reference counting never frees, so the collector
+// traces them to find which ones nothing outside the heap can reach.
+pub trait Trace: Any {
+    // Report every `Rc` this value holds to something the heap tracks
+    fn trace(&self, tracer: &mut Tracer);
+
+    // Drop those references, which the collector does to unreachable values to break their
+    // cycles. Only values allocated on the heap need to.
+    fn clear(&mut self) {}
+}
+
+// Collects the references a value reports while it is traced
+pub struct Tracer {
+    edges: Vec<*const ()>,
+    counting: bool, // Finding which references come from inside the heap, rather than marking
+    ephemerons: Vec<(*const (), Vec<*const ()>)>, // Keys and the references of their values
+}
+
+impl Tracer {
+    pub fn edge<T: ?Sized>(&mut self, reference: &Rc<T>) {
+        self.edges.push(Rc::as_ptr(reference) as *const ());
+    }
+
+    // Whether to trace what an `Rc` the heap doesn't track holds, like the cells a closure
+    // captures. While it is shared, whoever else holds it keeps what it holds alive.
+    pub fn through<T: ?Sized>(&self, container: &Rc<T>) -> bool {
+        !self.counting || Rc::strong_count(container) == 1
+    }
+
+    // A value that is only reachable while `key` is, like the value of a WeakMap entry
+    pub fn ephemeron(&mut self, key: &WeakObjectRef, value: &Value) {
+        if self.counting {
+            value.trace(self);
+            return;
+        }
+        let edges = std::mem::take(&mut self.edges);
+        value.trace(self);
+        let value_edges = std::mem::replace(&mut self.edges, edges);
+        self.ephemerons.push((key.as_ptr(), value_edges));
+    }
+}
+
+impl Trace for Value {
+    fn trace(&self, tracer: &mut Tracer) {
+        if let Value::Object(object) = self {
+            object.trace(tracer);
+        }
+    }
+}
+
+impl Trace for ObjectRef {
+    fn trace(&self, tracer: &mut Tracer) {
+        tracer.edge(self.as_rc());
+    }
+}
+
+impl<T: Trace> Trace for Option<T> {
+    fn trace(&self, tracer: &mut Tracer) {
+        if let Some(value) = self {
+            value.trace(tracer);
+        }
+    }
+
+    fn clear(&mut self) {
+        *self = None;
+    }
+}
+
+// A value allocated on the heap, which is shared and mutable
+trait Node {
+    // False if the value is borrowed mutably and can't be traced
+    fn trace(&self, tracer: &mut Tracer) -> bool;
+    fn clear(&self);
+}
+
+impl<T: Trace> Node for RefCell<T> {
+    fn trace(&self, tracer: &mut Tracer) -> bool {
+        match self.try_borrow() {
+            Ok(value) => {
+                value.trace(tracer);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    fn clear(&self) {
+        if let Ok(mut value) = self.try_borrow_mut() {
+            value.clear();
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HeapStats {
+    pub allocated: usize,   // Values allocated since the thread started
+    pub live: usize,        // Values not freed yet
+    pub collections: usize, // Times the collector ran
+    pub collected: usize,   // Values the collector freed, being unreachable from outside the heap
+}
+
+// Every thread has a heap, which interpreters on it share
+#[derive(Default)]
+struct Heap {
+    nodes: Vec<Weak<dyn Node>>,
+    roots: Vec<Option<Value>>, // Indexed by `Root`, `None` where one was dropped
+    stats: HeapStats,
+    since_collection: usize, // Allocations since the last collection
+    threshold: usize,
+    prune_at: usize, // When to forget the values that were freed
+}
+
+thread_local! {
+    static HEAP: RefCell<Heap> = RefCell::new(Heap::default());
+}
+
+// Allocate a value the collector tracks
+pub fn allocate<T: Trace>(value: T) -> Rc<RefCell<T>> {
+    let allocation = Rc::new(RefCell::new(value));
+    let node: Rc<dyn Node> = allocation.clone();
+    HEAP.with_borrow_mut(|heap| {
+        if heap.nodes.len() >= heap.prune_at {
+            heap.nodes.retain(|node| node.strong_count() > 0);
+            heap.prune_at = (heap.nodes.len() * 2).max(MIN_THRESHOLD);
+        }
+        heap.nodes.push(Rc::downgrade(&node));
+        heap.stats.allocated += 1;
+        heap.since_collection += 1;
+    });
+    allocation
+}
+
+pub fn heap_stats() -> HeapStats {
+    HEAP.with_borrow(|heap| HeapStats {
+        live: heap
+            .nodes
+            .iter()
+            .filter(|node| node.strong_count() > 0)
+            .count(),
+        ..heap.stats
+    })
+}
+
+// Collect if enough was allocated since the last collection. Returns how many values were
+// freed.
+pub fn collect_if_needed() -> usize {
+    let due = HEAP.with_borrow(|heap| heap.since_collection >= heap.threshold.max(MIN_THRESHOLD));
+    if due { collect() } else { 0 }
+}
+
+// Free the values nothing outside the heap can reach, and return how many there were.
+//
+// There is no list of the references the Rust stack holds, so the roots are found by trial
+// deletion: a value with more strong references than the heap has to it is referenced from
+// outside. Values reachable from those and from `Root`s are marked, and the rest are cleared,
+// which frees them once the collector lets go of them. This is safe to run at any time.
+pub fn collect() -> usize {
+    let (nodes, roots): (Vec<Rc<dyn Node>>, Vec<Value>) = HEAP.with_borrow_mut(|heap| {
+        let nodes = heap.nodes.iter().filter_map(Weak::upgrade).collect();
+        let roots = heap.roots.iter().flatten().cloned().collect();
+        (nodes, roots)
+    });
+    let index: HashMap<*const (), usize> = nodes
+        .iter()
+        .enumerate()
+        .map(|(i, node)| (Rc::as_ptr(node) as *const (), i))
+        .collect();
+
+    // What is left of the strong count after the references from inside the heap, not
+    // counting the one `nodes` holds
+    let mut tracer = Tracer {
+        edges: Vec::new(),
+        counting: true,
+        ephemerons: Vec::new(),
+    };
+    let mut external: Vec<usize> = nodes
+        .iter()
+        .map(|node| Rc::strong_count(node) - 1)
+        .collect();
+    let mut pending = Vec::new();
+    for (i, node) in nodes.iter().enumerate() {
+        tracer.edges.clear();
+        if !node.trace(&mut tracer) {
+            pending.push(i);
+            continue;
+        }
+        for edge in &tracer.edges {
+            if let Some(&target) = index.get(edge) {
+                external[target] = external[target].saturating_sub(1);
+            }
+        }
+    }
+
+    // Mark
+    tracer.counting = false;
+    pending.extend((0..nodes.len()).filter(|&i| external[i] > 0));
+    for root in &roots {
+        tracer.edges.clear();
+        root.trace(&mut tracer);
+        pending.extend(tracer.edges.iter().filter_map(|edge| index.get(edge)));
+    }
+    let mut marked = vec![false; nodes.len()];
+    // What ephemerons refer to, by the key that isn't marked yet
+    let mut waiting: HashMap<usize, Vec<usize>> = HashMap::new();
+    while let Some(i) = pending.pop() {
+        if marked[i] {
+            continue;
+        }
+        marked[i] = true;
+        if let Some(edges) = waiting.remove(&i) {
+            pending.extend(edges);
+        }
+        tracer.edges.clear();
+        nodes[i].trace(&mut tracer);
+        pending.extend(tracer.edges.iter().filter_map(|edge| index.get(edge)));
+        for (key, edges) in tracer.ephemerons.drain(..) {
+            let Some(&key) = index.get(&key) else {
+                continue;
+            };
+            let edges = edges.iter().filter_map(|edge| index.get(edge).copied());
+            if marked[key] {
+                pending.extend(edges);
+            } else {
+                waiting.entry(key).or_default().extend(edges);
+            }
+        }
+    }
+
+    // Sweep
+    let mut collected = 0;
+    for (node, _) in nodes.iter().zip(&marked).filter(|(_, marked)| !**marked) {
+        node.clear();
+        collected += 1;
+    }
+    drop(roots);
+    drop(nodes);
+    HEAP.with_borrow_mut(|heap| {
+        heap.nodes.retain(|node| node.strong_count() > 0);
+        heap.stats.collections += 1;
+        heap.stats.collected += collected;
+        heap.since_collection = 0;
+        heap.threshold = heap.nodes.len();
+    });
+    collected
+}
+
+// A value the heap keeps alive until the root is dropped, for embedders that hold on to
+// values between calls into the interpreter
+pub struct Root {
+    index: usize,
+    thread: PhantomData<Rc<()>>, // The heap belongs to the thread
+}
+
+impl Root {
+    pub fn new(value: Value) -> Root {
+        let index =
+            HEAP.with_borrow_mut(|heap| match heap.roots.iter().position(Option::is_none) {
+                Some(index) => {
+                    heap.roots[index] = Some(value);
+                    index
+                }
+                None => {
+                    heap.roots.push(Some(value));
+                    heap.roots.len() - 1
+                }
+            });
+        Root {
+            index,
+            thread: PhantomData,
+        }
+    }
+
+    pub fn value(&self) -> Value {
+        HEAP.with_borrow(|heap| heap.roots[self.index].clone())
+            .expect("a root keeps its value until it is dropped")
+    }
+}
+
+impl Drop for Root {
+    fn drop(&mut self) {
+        // The value is dropped after the heap is no longer borrowed
+        let value = HEAP.with_borrow_mut(|heap| heap.roots[self.index].take());
+        drop(value);
+    }
+}
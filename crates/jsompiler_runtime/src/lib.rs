@@ -0,0 +1,14 @@
+mod collections;
+mod heap;
+mod object;
+mod property;
+mod test;
+mod value;
+
+pub use collections::{IterationKind, ListIterator, MapData, WeakMapData};
+pub use heap::{HeapStats, Root, Trace, Tracer, allocate, collect, collect_if_needed, heap_stats};
+pub use object::{
+    Arguments, BoundFunction, Object, ObjectKind, ObjectRef, ParameterMap, WeakObjectRef,
+};
+pub use property::{Property, PropertyDescriptor, PropertyKey, PropertyValue, array_index};
+pub use value::{Symbol, Value};
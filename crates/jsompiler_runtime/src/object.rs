@@ -0,0 +1,677 @@
+use crate::collections::{ListIterator, MapData, WeakMapData};
+use crate::heap::{Trace, Tracer, allocate};
+use crate::property::{Property, PropertyDescriptor, PropertyKey, PropertyValue};
+use crate::value::Value;
+use std::cell::{Ref, RefCell, RefMut};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::rc::{Rc, Weak};
+
+// A shared reference to an object. Objects refer to each other in cycles, so `Debug` only
+// prints what kind of object this is.
+#[derive(Clone)]
+pub struct ObjectRef(Rc<RefCell<Object>>);
+
+impl ObjectRef {
+    pub fn new(object: Object) -> Self {
+        ObjectRef(allocate(object))
+    }
+
+    pub(crate) fn as_rc(&self) -> &Rc<RefCell<Object>> {
+        &self.0
+    }
+
+    pub(crate) fn as_ptr(&self) -> *const () {
+        Rc::as_ptr(&self.0) as *const ()
+    }
+
+    pub fn downgrade(&self) -> WeakObjectRef {
+        WeakObjectRef(Rc::downgrade(&self.0))
+    }
+
+    pub fn borrow(&self) -> Ref<'_, Object> {
+        self.0.borrow()
+    }
+
+    pub fn borrow_mut(&self) -> RefMut<'_, Object> {
+        self.0.borrow_mut()
+    }
+
+    pub fn ptr_eq(&self, other: &ObjectRef) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+
+    pub fn is_callable(&self) -> bool {
+        matches!(
+            self.borrow().kind,
+            ObjectKind::Function(_) | ObjectKind::BoundFunction(_)
+        )
+    }
+
+    pub fn prototype(&self) -> Option<ObjectRef> {
+        self.borrow().prototype.clone()
+    }
+
+    // [[SetPrototypeOf]], which fails for objects that aren't extensible and prototypes that
+    // would make the chain a cycle
+    pub fn set_prototype_of(&self, prototype: Option<ObjectRef>) -> bool {
+        let current = self.prototype();
+        let unchanged = match (&current, &prototype) {
+            (Some(current), Some(prototype)) => current.ptr_eq(prototype),
+            (None, None) => true,
+            _ => false,
+        };
+        if unchanged {
+            return true;
+        }
+        if !self.borrow().extensible {
+            return false;
+        }
+        let mut ancestor = prototype.clone();
+        while let Some(object) = ancestor {
+            if object.ptr_eq(self) {
+                return false;
+            }
+            ancestor = object.prototype();
+        }
+        self.borrow_mut().prototype = prototype;
+        true
+    }
+
+    // The property `key` of this object or the closest prototype that has it
+    pub fn find_property(&self, key: &PropertyKey) -> Option<Property> {
+        let mut object = Some(self.clone());
+        while let Some(current) = object {
+            if let Some(property) = current.borrow().get_own(key) {
+                return Some(property);
+            }
+            object = current.prototype();
+        }
+        None
+    }
+
+    pub fn has_property(&self, key: &PropertyKey) -> bool {
+        self.find_property(key).is_some()
+    }
+}
+
+// A reference that doesn't keep the object alive, like the keys of a WeakMap
+#[derive(Clone)]
+pub struct WeakObjectRef(Weak<RefCell<Object>>);
+
+impl WeakObjectRef {
+    pub fn upgrade(&self) -> Option<ObjectRef> {
+        self.0.upgrade().map(ObjectRef)
+    }
+
+    pub(crate) fn as_ptr(&self) -> *const () {
+        Weak::as_ptr(&self.0) as *const ()
+    }
+}
+
+impl fmt::Debug for ObjectRef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let object = self.borrow();
+        match &object.kind {
+            ObjectKind::Ordinary => write!(f, "Object"),
+            ObjectKind::Array => write!(f, "Array({})", object.array_length().unwrap_or(0)),
+            ObjectKind::Arguments(_) => write!(f, "Arguments"),
+            ObjectKind::Function(_) => write!(f, "Function"),
+            ObjectKind::BoundFunction(_) => write!(f, "BoundFunction"),
+            ObjectKind::Error => write!(f, "Error"),
+            ObjectKind::Primitive(value) => write!(f, "Object({value:?})"),
+            ObjectKind::Map(map) => write!(f, "Map({})", map.len()),
+            ObjectKind::Set(set) => write!(f, "Set({})", set.len()),
+            ObjectKind::WeakMap(_) => write!(f, "WeakMap"),
+            ObjectKind::ArrayIterator(_)
+            | ObjectKind::StringIterator(_)
+            | ObjectKind::MapIterator(_)
+            | ObjectKind::SetIterator(_) => write!(f, "Iterator"),
+        }
+    }
+}
+
+pub struct Object {
+    pub prototype: Option<ObjectRef>,
+    pub extensible: bool, // New properties can be added
+    pub kind: ObjectKind,
+    properties: Properties,
+}
+
+// Ordinary objects and the exotic ones, whose own properties behave differently
+pub enum ObjectKind {
+    Ordinary,
+    Array, // `length` is always greater than every index
+    Arguments(Arguments),
+    Function(Box<dyn Trace>), // What runs when it is called, which is up to the evaluator
+    BoundFunction(BoundFunction),
+    Error,
+    Primitive(Value), // A Boolean, Number, String, Symbol or BigInt object
+    Map(MapData),
+    Set(MapData), // Values are the keys of the entries
+    WeakMap(WeakMapData),
+    ArrayIterator(ListIterator),
+    StringIterator(ListIterator),
+    MapIterator(ListIterator),
+    SetIterator(ListIterator),
+}
+
+// The `arguments` object of a function. Indices stay mapped to the parameter of the same
+// position until they are deleted or redefined as something else.
+pub struct Arguments {
+    pub parameters: Option<Rc<dyn ParameterMap>>, // For sloppy mode functions with simple parameters
+    pub mapped: Vec<bool>,
+}
+
+// The parameters of a function call, which its `arguments` object reads and writes through
+pub trait ParameterMap: Trace {
+    fn get(&self, index: u32) -> Value;
+    fn set(&self, index: u32, value: Value);
+}
+
+// A function made by `bind`, which calls its target with a fixed `this` and leading arguments
+pub struct BoundFunction {
+    pub target: ObjectRef,
+    pub this: Value,
+    pub arguments: Vec<Value>,
+}
+
+impl Arguments {
+    fn mapped_index(&self, key: &PropertyKey) -> Option<(u32, &Rc<dyn ParameterMap>)> {
+        let index = key.as_index()?;
+        let parameters = self.parameters.as_ref()?;
+        match self.mapped.get(index as usize) {
+            Some(true) => Some((index, parameters)),
+            _ => None,
+        }
+    }
+
+    fn unmap(&mut self, key: &PropertyKey) {
+        if let Some(mapped) = key
+            .as_index()
+            .and_then(|index| self.mapped.get_mut(index as usize))
+        {
+            *mapped = false;
+        }
+    }
+}
+
+// Own properties: array indices in numeric order, then the other keys in the order they were
+// added, with symbols after strings
+#[derive(Default)]
+struct Properties {
+    indices: BTreeMap<u32, Property>,
+    named: HashMap<PropertyKey, (usize, Property)>,
+    added: usize, // How many named properties were ever added, for their order
+}
+
+impl Properties {
+    fn get(&self, key: &PropertyKey) -> Option<&Property> {
+        match key {
+            PropertyKey::Index(index) => self.indices.get(index),
+            _ => self.named.get(key).map(|(_, property)| property),
+        }
+    }
+
+    // Replacing a property keeps its place in the order
+    fn insert(&mut self, key: PropertyKey, property: Property) {
+        match key {
+            PropertyKey::Index(index) => {
+                self.indices.insert(index, property);
+            }
+            _ => match self.named.get_mut(&key) {
+                Some((_, existing)) => *existing = property,
+                None => {
+                    self.added += 1;
+                    self.named.insert(key, (self.added, property));
+                }
+            },
+        }
+    }
+
+    fn remove(&mut self, key: &PropertyKey) {
+        match key {
+            PropertyKey::Index(index) => {
+                self.indices.remove(index);
+            }
+            _ => {
+                self.named.remove(key);
+            }
+        }
+    }
+
+    fn keys(&self) -> Vec<PropertyKey> {
+        let mut named: Vec<(&PropertyKey, usize)> = self
+            .named
+            .iter()
+            .map(|(key, (order, _))| (key, *order))
+            .collect();
+        named.sort_by_key(|(key, order)| (key.is_symbol(), *order));
+        self.indices
+            .keys()
+            .map(|index| PropertyKey::Index(*index))
+            .chain(named.into_iter().map(|(key, _)| key.clone()))
+            .collect()
+    }
+}
+
+impl Trace for Property {
+    fn trace(&self, tracer: &mut Tracer) {
+        match &self.value {
+            PropertyValue::Data { value, .. } => value.trace(tracer),
+            PropertyValue::Accessor { get, set } => {
+                get.trace(tracer);
+                set.trace(tracer);
+            }
+        }
+    }
+}
+
+impl Trace for Object {
+    fn trace(&self, tracer: &mut Tracer) {
+        self.prototype.trace(tracer);
+        let properties = &self.properties;
+        for property in properties.indices.values() {
+            property.trace(tracer);
+        }
+        for (_, property) in properties.named.values() {
+            property.trace(tracer);
+        }
+        match &self.kind {
+            ObjectKind::Arguments(Arguments {
+                parameters: Some(parameters),
+                ..
+            }) if tracer.through(parameters) => parameters.trace(tracer),
+            ObjectKind::Function(function) => function.trace(tracer),
+            ObjectKind::BoundFunction(bound) => {
+                bound.target.trace(tracer);
+                bound.this.trace(tracer);
+                for argument in &bound.arguments {
+                    argument.trace(tracer);
+                }
+            }
+            ObjectKind::Primitive(value) => value.trace(tracer),
+            ObjectKind::Map(map) | ObjectKind::Set(map) => map.trace(tracer),
+            ObjectKind::WeakMap(map) => map.trace(tracer),
+            ObjectKind::ArrayIterator(iterator)
+            | ObjectKind::StringIterator(iterator)
+            | ObjectKind::MapIterator(iterator)
+            | ObjectKind::SetIterator(iterator) => iterator.trace(tracer),
+            _ => {}
+        }
+    }
+
+    fn clear(&mut self) {
+        self.prototype = None;
+        self.properties = Properties::default();
+        self.kind = ObjectKind::Ordinary;
+    }
+}
+
+fn length_key() -> PropertyKey {
+    PropertyKey::String("length".into())
+}
+
+impl Object {
+    pub fn new(prototype: Option<ObjectRef>, kind: ObjectKind) -> Self {
+        Object {
+            prototype,
+            extensible: true,
+            kind,
+            properties: Properties::default(),
+        }
+    }
+
+    // An array of the elements, which are its indices
+    pub fn array(prototype: Option<ObjectRef>, elements: Vec<Value>) -> Self {
+        let mut object = Object::new(prototype, ObjectKind::Array);
+        let length = Value::Number(elements.len() as f64);
+        let length = Property::new(length, true, false, false);
+        object.properties.insert(length_key(), length);
+        for (index, element) in elements.into_iter().enumerate() {
+            object
+                .properties
+                .insert(PropertyKey::from(index), Property::data(element));
+        }
+        object
+    }
+
+    // An `arguments` object for the values a function was called with. `parameters` maps
+    // indices to the bindings of the parameters, for those where `mapped` is true.
+    pub fn arguments(
+        prototype: Option<ObjectRef>,
+        values: &[Value],
+        parameters: Option<(Rc<dyn ParameterMap>, Vec<bool>)>,
+    ) -> Self {
+        let (parameters, mapped) = match parameters {
+            Some((parameters, mapped)) => (Some(parameters), mapped),
+            None => (None, Vec::new()),
+        };
+        let kind = ObjectKind::Arguments(Arguments { parameters, mapped });
+        let mut object = Object::new(prototype, kind);
+        let length = Value::Number(values.len() as f64);
+        object
+            .properties
+            .insert(length_key(), Property::hidden(length));
+        for (index, value) in values.iter().enumerate() {
+            object
+                .properties
+                .insert(PropertyKey::from(index), Property::data(value.clone()));
+        }
+        object
+    }
+
+    // A function that calls `target` with `this` and `arguments` before its own arguments
+    pub fn bound_function(target: &ObjectRef, this: Value, arguments: Vec<Value>) -> Self {
+        let bound = BoundFunction {
+            target: target.clone(),
+            this,
+            arguments,
+        };
+        Object::new(target.prototype(), ObjectKind::BoundFunction(bound))
+    }
+
+    // The `length` of an array
+    pub fn array_length(&self) -> Option<u32> {
+        if !matches!(self.kind, ObjectKind::Array) {
+            return None;
+        }
+        match self.properties.get(&length_key())?.value {
+            PropertyValue::Data {
+                value: Value::Number(length),
+                ..
+            } => Some(length as u32),
+            _ => None,
+        }
+    }
+
+    // [[GetOwnProperty]]
+    pub fn get_own(&self, key: &PropertyKey) -> Option<Property> {
+        let mut property = self.properties.get(key)?.clone();
+        if let ObjectKind::Arguments(arguments) = &self.kind
+            && let Some((index, parameters)) = arguments.mapped_index(key)
+            && let PropertyValue::Data { value, .. } = &mut property.value
+        {
+            *value = parameters.get(index);
+        }
+        Some(property)
+    }
+
+    pub fn has_own(&self, key: &PropertyKey) -> bool {
+        self.properties.get(key).is_some()
+    }
+
+    // [[DefineOwnProperty]]: change or add a property as the descriptor says, unless that
+    // would break what the current property or the object allow
+    pub fn define_own_property(
+        &mut self,
+        key: PropertyKey,
+        descriptor: PropertyDescriptor,
+    ) -> bool {
+        match &self.kind {
+            ObjectKind::Array if key == "length" => self.set_array_length(descriptor),
+            ObjectKind::Array => {
+                let Some(index) = key.as_index() else {
+                    return self.define_ordinary(key, descriptor);
+                };
+                let length = self.array_length().unwrap_or(0);
+                let length_writable = self
+                    .properties
+                    .get(&length_key())
+                    .is_some_and(Property::is_writable);
+                if index >= length && !length_writable {
+                    return false;
+                }
+                if !self.define_ordinary(key, descriptor) {
+                    return false;
+                }
+                if index >= length {
+                    self.write_array_length(index + 1);
+                }
+                true
+            }
+            ObjectKind::Arguments(arguments) => {
+                let Some((index, parameters)) = arguments.mapped_index(&key) else {
+                    return self.define_ordinary(key, descriptor);
+                };
+                let parameters = parameters.clone();
+                let mut ordinary = descriptor.clone();
+                // A mapped index made read-only keeps the value of its parameter
+                if !descriptor.is_accessor()
+                    && descriptor.value.is_none()
+                    && descriptor.writable == Some(false)
+                {
+                    ordinary.value = Some(parameters.get(index));
+                }
+                if !self.define_ordinary(key.clone(), ordinary) {
+                    return false;
+                }
+                if let Some(value) = &descriptor.value {
+                    parameters.set(index, value.clone());
+                }
+                if (descriptor.is_accessor() || descriptor.writable == Some(false))
+                    && let ObjectKind::Arguments(arguments) = &mut self.kind
+                {
+                    arguments.unmap(&key);
+                }
+                true
+            }
+            _ => self.define_ordinary(key, descriptor),
+        }
+    }
+
+    // ValidateAndApplyPropertyDescriptor
+    fn define_ordinary(&mut self, key: PropertyKey, descriptor: PropertyDescriptor) -> bool {
+        let Some(current) = self.properties.get(&key) else {
+            if !self.extensible {
+                return false;
+            }
+            self.properties.insert(key, descriptor.to_property());
+            return true;
+        };
+        if !current.configurable {
+            if descriptor.configurable == Some(true)
+                || descriptor
+                    .enumerable
+                    .is_some_and(|enumerable| enumerable != current.enumerable)
+                || (!descriptor.is_generic() && descriptor.is_accessor() != current.is_accessor())
+            {
+                return false;
+            }
+            match &current.value {
+                PropertyValue::Accessor { get, set } => {
+                    let same = |function: &Option<ObjectRef>, other: &Option<ObjectRef>| match (
+                        function, other,
+                    ) {
+                        (Some(function), Some(other)) => function.ptr_eq(other),
+                        (None, None) => true,
+                        _ => false,
+                    };
+                    if descriptor
+                        .get
+                        .as_ref()
+                        .is_some_and(|other| !same(get, other))
+                        || descriptor
+                            .set
+                            .as_ref()
+                            .is_some_and(|other| !same(set, other))
+                    {
+                        return false;
+                    }
+                }
+                PropertyValue::Data {
+                    value,
+                    writable: false,
+                } => {
+                    if descriptor.writable == Some(true)
+                        || descriptor
+                            .value
+                            .as_ref()
+                            .is_some_and(|other| !other.same_value(value))
+                    {
+                        return false;
+                    }
+                }
+                PropertyValue::Data { .. } => {}
+            }
+        }
+
+        let mut property = current.clone();
+        if descriptor.is_accessor() && !current.is_accessor() {
+            property.value = PropertyValue::Accessor {
+                get: None,
+                set: None,
+            };
+        } else if descriptor.is_data() && current.is_accessor() {
+            property.value = PropertyValue::Data {
+                value: Value::Undefined,
+                writable: false,
+            };
+        }
+        match &mut property.value {
+            PropertyValue::Data { value, writable } => {
+                if let Some(new_value) = descriptor.value {
+                    *value = new_value;
+                }
+                if let Some(new_writable) = descriptor.writable {
+                    *writable = new_writable;
+                }
+            }
+            PropertyValue::Accessor { get, set } => {
+                if let Some(new_get) = descriptor.get {
+                    *get = new_get;
+                }
+                if let Some(new_set) = descriptor.set {
+                    *set = new_set;
+                }
+            }
+        }
+        if let Some(enumerable) = descriptor.enumerable {
+            property.enumerable = enumerable;
+        }
+        if let Some(configurable) = descriptor.configurable {
+            property.configurable = configurable;
+        }
+        self.properties.insert(key, property);
+        true
+    }
+
+    // ArraySetLength: a shorter length deletes the indices past it, stopping at the first that
+    // can't be deleted. The evaluator converts the new length to a number first.
+    fn set_array_length(&mut self, descriptor: PropertyDescriptor) -> bool {
+        let Some(value) = &descriptor.value else {
+            return self.define_ordinary(length_key(), descriptor);
+        };
+        let new_length = match value {
+            Value::Number(length)
+                if length.fract() == 0.0 && (0.0..=u32::MAX as f64).contains(length) =>
+            {
+                *length as u32
+            }
+            _ => return false,
+        };
+        let length = self.array_length().unwrap_or(0);
+        if new_length >= length {
+            return self.define_ordinary(length_key(), descriptor);
+        }
+        let Some(current) = self.properties.get(&length_key()) else {
+            return false;
+        };
+        if !current.is_writable() {
+            return false;
+        }
+        // The length is only made read-only once the indices are deleted
+        let read_only = descriptor.writable == Some(false);
+        let mut writable_descriptor = descriptor.clone();
+        writable_descriptor.writable = None;
+        if !self.define_ordinary(length_key(), writable_descriptor) {
+            return false;
+        }
+        let removed: Vec<u32> = self
+            .properties
+            .indices
+            .range(new_length..)
+            .map(|(index, _)| *index)
+            .collect();
+        for index in removed.into_iter().rev() {
+            if !self.delete(&PropertyKey::Index(index)) {
+                self.write_array_length(index + 1);
+                if read_only {
+                    self.make_length_read_only();
+                }
+                return false;
+            }
+        }
+        if read_only {
+            self.make_length_read_only();
+        }
+        true
+    }
+
+    fn write_array_length(&mut self, length: u32) {
+        let key = length_key();
+        if let Some((_, property)) = self.properties.named.get_mut(&key)
+            && let PropertyValue::Data { value, .. } = &mut property.value
+        {
+            *value = Value::Number(length as f64);
+        }
+    }
+
+    fn make_length_read_only(&mut self) {
+        let key = length_key();
+        if let Some((_, property)) = self.properties.named.get_mut(&key)
+            && let PropertyValue::Data { writable, .. } = &mut property.value
+        {
+            *writable = false;
+        }
+    }
+
+    // Add or replace a property, unless the current one can't be changed that way
+    pub fn define(&mut self, key: impl Into<PropertyKey>, property: Property) -> bool {
+        self.define_own_property(key.into(), property.into())
+    }
+
+    // The part of [[Set]] that happens on the receiver: change the value of a writable data
+    // property, or add one if there is none
+    pub fn set_own(&mut self, key: PropertyKey, value: Value) -> bool {
+        match self.properties.get(&key) {
+            Some(property) if !property.is_writable() => false,
+            Some(_) => self.define_own_property(key, PropertyDescriptor::value(value)),
+            None => self.define_own_property(key, Property::data(value).into()),
+        }
+    }
+
+    // [[Delete]], which fails for properties that aren't configurable
+    pub fn delete(&mut self, key: &PropertyKey) -> bool {
+        match self.properties.get(key) {
+            None => return true,
+            Some(property) if !property.configurable => return false,
+            Some(_) => {}
+        }
+        self.properties.remove(key);
+        if let ObjectKind::Arguments(arguments) = &mut self.kind {
+            arguments.unmap(key);
+        }
+        true
+    }
+
+    // [[OwnPropertyKeys]]: array indices in numeric order, then strings and then symbols in
+    // the order they were added
+    pub fn own_keys(&self) -> Vec<PropertyKey> {
+        self.properties.keys()
+    }
+
+    // Keys of own enumerable string properties, as `for-in` and `Object.keys` see them
+    pub fn enumerable_keys(&self) -> Vec<PropertyKey> {
+        self.own_keys()
+            .into_iter()
+            .filter(|key| {
+                !key.is_symbol()
+                    && self
+                        .properties
+                        .get(key)
+                        .is_some_and(|property| property.enumerable)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,253 @@
+use crate::object::ObjectRef;
+use crate::value::{Symbol, Value};
+use std::fmt;
+use std::rc::Rc;
+
+// The key of a property. Array indices are kept as numbers so "1" and 1 are the same key,
+// and a `String` key is never one.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PropertyKey {
+    Index(u32),
+    String(Rc<str>),
+    Symbol(Symbol),
+}
+
+// The index `key` names, if it is an array index like "0" but not "00", "-1" or 2 ** 32 - 1
+pub fn array_index(key: &str) -> Option<u32> {
+    if key == "0" {
+        return Some(0);
+    }
+    if key.starts_with('0') || key.is_empty() || !key.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    key.parse::<u32>().ok().filter(|&index| index != u32::MAX)
+}
+
+impl PropertyKey {
+    pub fn as_index(&self) -> Option<u32> {
+        match self {
+            PropertyKey::Index(index) => Some(*index),
+            _ => None,
+        }
+    }
+
+    pub fn is_symbol(&self) -> bool {
+        matches!(self, PropertyKey::Symbol(_))
+    }
+
+    // The key as a value, which is a string unless it is a symbol
+    pub fn to_value(&self) -> Value {
+        match self {
+            PropertyKey::Index(index) => Value::from(index.to_string()),
+            PropertyKey::String(text) => Value::String(text.clone()),
+            PropertyKey::Symbol(symbol) => Value::Symbol(symbol.clone()),
+        }
+    }
+}
+
+impl From<&str> for PropertyKey {
+    fn from(key: &str) -> Self {
+        match array_index(key) {
+            Some(index) => PropertyKey::Index(index),
+            None => PropertyKey::String(key.into()),
+        }
+    }
+}
+
+impl From<String> for PropertyKey {
+    fn from(key: String) -> Self {
+        PropertyKey::from(key.as_str())
+    }
+}
+
+impl From<Rc<str>> for PropertyKey {
+    fn from(key: Rc<str>) -> Self {
+        match array_index(&key) {
+            Some(index) => PropertyKey::Index(index),
+            None => PropertyKey::String(key),
+        }
+    }
+}
+
+// Indices up to 2 ** 32 - 2. Larger numbers are string keys.
+impl From<usize> for PropertyKey {
+    fn from(index: usize) -> Self {
+        match u32::try_from(index) {
+            Ok(index) if index != u32::MAX => PropertyKey::Index(index),
+            _ => PropertyKey::String(index.to_string().into()),
+        }
+    }
+}
+
+impl From<Symbol> for PropertyKey {
+    fn from(symbol: Symbol) -> Self {
+        PropertyKey::Symbol(symbol)
+    }
+}
+
+impl PartialEq<&str> for PropertyKey {
+    fn eq(&self, other: &&str) -> bool {
+        match self {
+            PropertyKey::String(text) => **text == **other,
+            PropertyKey::Index(index) => array_index(other) == Some(*index),
+            PropertyKey::Symbol(_) => false,
+        }
+    }
+}
+
+impl fmt::Display for PropertyKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PropertyKey::Index(index) => write!(f, "{index}"),
+            PropertyKey::String(text) => write!(f, "{text}"),
+            PropertyKey::Symbol(symbol) => write!(f, "{symbol}"),
+        }
+    }
+}
+
+// An own property of an object
+#[derive(Debug, Clone)]
+pub struct Property {
+    pub value: PropertyValue,
+    pub enumerable: bool,   // Seen by `for-in`, `Object.keys` and `console.log`
+    pub configurable: bool, // Can be deleted, and redefined as something else
+}
+
+#[derive(Debug, Clone)]
+pub enum PropertyValue {
+    Data {
+        value: Value,
+        writable: bool,
+    },
+    Accessor {
+        get: Option<ObjectRef>,
+        set: Option<ObjectRef>,
+    },
+}
+
+impl Property {
+    pub fn new(value: Value, writable: bool, enumerable: bool, configurable: bool) -> Self {
+        Property {
+            value: PropertyValue::Data { value, writable },
+            enumerable,
+            configurable,
+        }
+    }
+
+    // A property as assignment creates it
+    pub fn data(value: Value) -> Self {
+        Property::new(value, true, true, true)
+    }
+
+    // Methods and built-in properties are left out of `for-in` and `console.log`
+    pub fn hidden(value: Value) -> Self {
+        Property::new(value, true, false, true)
+    }
+
+    // Built-in properties that can't be assigned, like the `name` and `length` of functions
+    pub fn read_only(value: Value) -> Self {
+        Property::new(value, false, false, true)
+    }
+
+    pub fn accessor(get: Option<ObjectRef>, set: Option<ObjectRef>, enumerable: bool) -> Self {
+        Property {
+            value: PropertyValue::Accessor { get, set },
+            enumerable,
+            configurable: true,
+        }
+    }
+
+    pub fn is_accessor(&self) -> bool {
+        matches!(self.value, PropertyValue::Accessor { .. })
+    }
+
+    pub fn is_writable(&self) -> bool {
+        matches!(self.value, PropertyValue::Data { writable: true, .. })
+    }
+
+    // The value of a data property, or `undefined` for an accessor
+    pub fn data_value(&self) -> Value {
+        match &self.value {
+            PropertyValue::Data { value, .. } => value.clone(),
+            PropertyValue::Accessor { .. } => Value::Undefined,
+        }
+    }
+}
+
+// The fields of a property to define, as `Object.defineProperty` takes them. Fields that are
+// left out keep their current value, or get the default of `undefined` or `false` for a new
+// property. `get` and `set` are `Some(None)` when given as `undefined`.
+#[derive(Debug, Clone, Default)]
+pub struct PropertyDescriptor {
+    pub value: Option<Value>,
+    pub writable: Option<bool>,
+    pub get: Option<Option<ObjectRef>>,
+    pub set: Option<Option<ObjectRef>>,
+    pub enumerable: Option<bool>,
+    pub configurable: Option<bool>,
+}
+
+impl PropertyDescriptor {
+    pub fn is_accessor(&self) -> bool {
+        self.get.is_some() || self.set.is_some()
+    }
+
+    pub fn is_data(&self) -> bool {
+        self.value.is_some() || self.writable.is_some()
+    }
+
+    // Neither a data nor an accessor descriptor, which only changes the attributes
+    pub fn is_generic(&self) -> bool {
+        !self.is_accessor() && !self.is_data()
+    }
+
+    // A descriptor that only changes the value
+    pub fn value(value: Value) -> Self {
+        PropertyDescriptor {
+            value: Some(value),
+            ..Default::default()
+        }
+    }
+
+    // The property a descriptor creates where there is none, with the defaults filled in
+    pub fn to_property(&self) -> Property {
+        let value = if self.is_accessor() {
+            PropertyValue::Accessor {
+                get: self.get.clone().flatten(),
+                set: self.set.clone().flatten(),
+            }
+        } else {
+            PropertyValue::Data {
+                value: self.value.clone().unwrap_or(Value::Undefined),
+                writable: self.writable.unwrap_or(false),
+            }
+        };
+        Property {
+            value,
+            enumerable: self.enumerable.unwrap_or(false),
+            configurable: self.configurable.unwrap_or(false),
+        }
+    }
+}
+
+// A descriptor with every field of the property, as `Object.getOwnPropertyDescriptor` gives
+impl From<Property> for PropertyDescriptor {
+    fn from(property: Property) -> Self {
+        let mut descriptor = PropertyDescriptor {
+            enumerable: Some(property.enumerable),
+            configurable: Some(property.configurable),
+            ..Default::default()
+        };
+        match property.value {
+            PropertyValue::Data { value, writable } => {
+                descriptor.value = Some(value);
+                descriptor.writable = Some(writable);
+            }
+            PropertyValue::Accessor { get, set } => {
+                descriptor.get = Some(get);
+                descriptor.set = Some(set);
+            }
+        }
+        descriptor
+    }
+}
@@ -0,0 +1,458 @@
+#[allow(unused_imports)]
+use super::{
+    MapData, Object, ObjectKind, ObjectRef, ParameterMap, Property, PropertyDescriptor,
+    PropertyKey, PropertyValue, Root, Symbol, Trace, Tracer, Value, WeakMapData, collect,
+    heap_stats,
+};
+#[allow(unused_imports)]
+use std::cell::RefCell;
+#[allow(unused_imports)]
+use std::rc::Rc;
+
+#[allow(dead_code)]
+fn ordinary() -> Object {
+    Object::new(None, ObjectKind::Ordinary)
+}
+
+#[allow(dead_code)]
+fn keys(object: &Object) -> Vec<String> {
+    object
+        .own_keys()
+        .iter()
+        .map(|key| key.to_string())
+        .collect()
+}
+
+#[allow(dead_code)]
+fn number(object: &Object, key: &str) -> Option<f64> {
+    match object.get_own(&key.into())?.value {
+        PropertyValue::Data {
+            value: Value::Number(number),
+            ..
+        } => Some(number),
+        _ => None,
+    }
+}
+
+// Parameters kept in a vector, standing in for the bindings of a function call
+#[allow(dead_code)]
+struct Parameters(RefCell<Vec<Value>>);
+
+impl Trace for Parameters {
+    fn trace(&self, tracer: &mut Tracer) {
+        for value in self.0.borrow().iter() {
+            value.trace(tracer);
+        }
+    }
+}
+
+impl ParameterMap for Parameters {
+    fn get(&self, index: u32) -> Value {
+        self.0.borrow()[index as usize].clone()
+    }
+
+    fn set(&self, index: u32, value: Value) {
+        self.0.borrow_mut()[index as usize] = value;
+    }
+}
+
+// Code of a function, which refers to nothing
+#[allow(dead_code)]
+struct Code;
+
+impl Trace for Code {
+    fn trace(&self, _: &mut Tracer) {}
+}
+
+// Two objects that refer to each other, and so are never freed by reference counting
+#[allow(dead_code)]
+fn cycle() -> (ObjectRef, ObjectRef) {
+    let first = ObjectRef::new(ordinary());
+    let second = ObjectRef::new(Object::new(Some(first.clone()), ObjectKind::Ordinary));
+    first
+        .borrow_mut()
+        .define("next", Property::data(second.clone().into()));
+    (first, second)
+}
+
+#[test]
+fn test_property_keys() {
+    assert_eq!(PropertyKey::from("7"), PropertyKey::Index(7));
+    assert_eq!(PropertyKey::from(7), PropertyKey::Index(7));
+    assert_eq!(
+        PropertyKey::from("4294967294"),
+        PropertyKey::Index(4294967294)
+    );
+    for key in ["07", "-1", "1.5", "4294967295", ""] {
+        assert_eq!(PropertyKey::from(key), PropertyKey::String(key.into()));
+    }
+    assert!(PropertyKey::Index(0) == "0");
+    let symbol = Symbol::new(Some("tag"));
+    assert_eq!(PropertyKey::from(symbol.clone()).to_string(), "Symbol(tag)");
+    assert_ne!(symbol, Symbol::new(Some("tag")));
+}
+
+#[test]
+fn test_key_order() {
+    let mut object = ordinary();
+    let symbol = Symbol::new(Some("first"));
+    object.define(symbol, Property::data(Value::Null));
+    for key in ["b", "2", "a", "1", "-1", "4294967295", "0"] {
+        object.define(key, Property::data(Value::Null));
+    }
+    // Redefining a key keeps its place, deleting and adding it again moves it to the end
+    object.define("b", Property::data(Value::Boolean(true)));
+    object.delete(&"a".into());
+    object.define("a", Property::data(Value::Null));
+    assert_eq!(
+        keys(&object),
+        ["0", "1", "2", "b", "-1", "4294967295", "a", "Symbol(first)"]
+    );
+    object.define("hidden", Property::hidden(Value::Null));
+    let enumerable: Vec<String> = object
+        .enumerable_keys()
+        .iter()
+        .map(|key| key.to_string())
+        .collect();
+    assert_eq!(enumerable, ["0", "1", "2", "b", "-1", "4294967295", "a"]);
+}
+
+#[test]
+fn test_descriptors() {
+    let mut object = ordinary();
+    let read_only = PropertyDescriptor {
+        value: Some(Value::Number(1.0)),
+        ..Default::default()
+    };
+    assert!(object.define_own_property("x".into(), read_only));
+    let property = object.get_own(&"x".into()).unwrap();
+    assert!(!property.is_writable() && !property.enumerable && !property.configurable);
+
+    // Nothing about a non-configurable, read-only property can change but to the same value
+    assert!(!object.set_own("x".into(), Value::Number(2.0)));
+    assert!(object.define_own_property("x".into(), PropertyDescriptor::value(Value::Number(1.0))));
+    assert!(!object.define_own_property("x".into(), PropertyDescriptor::value(Value::Number(2.0))));
+    let enumerable = PropertyDescriptor {
+        enumerable: Some(true),
+        ..Default::default()
+    };
+    assert!(!object.define_own_property("x".into(), enumerable.clone()));
+    assert!(!object.delete(&"x".into()));
+    assert_eq!(number(&object, "x"), Some(1.0));
+
+    // NaN is the same value as NaN, but 0 and -0 differ
+    let nan = PropertyDescriptor::value(Value::Number(f64::NAN));
+    assert!(object.define_own_property("nan".into(), nan.clone()));
+    assert!(object.define_own_property("nan".into(), nan));
+    assert!(
+        object.define_own_property("zero".into(), PropertyDescriptor::value(Value::Number(0.0)))
+    );
+    assert!(!object.define_own_property(
+        "zero".into(),
+        PropertyDescriptor::value(Value::Number(-0.0))
+    ));
+
+    // A configurable property can become an accessor, which keeps the other attributes
+    object.define("y", Property::data(Value::Null));
+    let getter = ObjectRef::new(ordinary());
+    let accessor = PropertyDescriptor {
+        get: Some(Some(getter.clone())),
+        ..Default::default()
+    };
+    assert!(object.define_own_property("y".into(), accessor));
+    let property = object.get_own(&"y".into()).unwrap();
+    assert!(property.enumerable && property.configurable);
+    match property.value {
+        PropertyValue::Accessor { get, set } => {
+            assert!(get.is_some_and(|get| get.ptr_eq(&getter)) && set.is_none())
+        }
+        PropertyValue::Data { .. } => panic!("y is still a data property"),
+    }
+    assert!(!object.set_own("y".into(), Value::Null));
+
+    // A writable property that isn't configurable can still change its value or become
+    // read-only, but not the other way around
+    let fixed = PropertyDescriptor {
+        value: Some(Value::Number(1.0)),
+        writable: Some(true),
+        ..Default::default()
+    };
+    assert!(object.define_own_property("z".into(), fixed));
+    assert!(object.set_own("z".into(), Value::Number(2.0)));
+    let writable = |writable| PropertyDescriptor {
+        writable: Some(writable),
+        ..Default::default()
+    };
+    assert!(object.define_own_property("z".into(), writable(false)));
+    assert!(!object.define_own_property("z".into(), writable(true)));
+    assert_eq!(number(&object, "z"), Some(2.0));
+
+    object.extensible = false;
+    assert!(!object.define("new", Property::data(Value::Null)));
+    assert!(!object.has_own(&"new".into()));
+}
+
+#[test]
+fn test_prototypes() {
+    let base = ObjectRef::new(ordinary());
+    base.borrow_mut()
+        .define("inherited", Property::data(Value::Number(1.0)));
+    let middle = ObjectRef::new(Object::new(Some(base.clone()), ObjectKind::Ordinary));
+    let object = ObjectRef::new(Object::new(Some(middle.clone()), ObjectKind::Ordinary));
+    assert!(object.has_property(&"inherited".into()));
+    assert!(!object.borrow().has_own(&"inherited".into()));
+
+    // The chain can't become a cycle, and objects that aren't extensible keep their prototype
+    assert!(!base.set_prototype_of(Some(object.clone())));
+    assert!(!middle.set_prototype_of(Some(middle.clone())));
+    assert!(object.set_prototype_of(None));
+    assert!(!object.has_property(&"inherited".into()));
+    object.borrow_mut().extensible = false;
+    assert!(!object.set_prototype_of(Some(base.clone())));
+    assert!(object.set_prototype_of(None));
+}
+
+#[test]
+fn test_arrays() {
+    let values = ["a", "b", "c", "d"].map(Value::from).to_vec();
+    let mut array = Object::array(None, values);
+    assert_eq!(keys(&array), ["0", "1", "2", "3", "length"]);
+    assert_eq!(array.array_length(), Some(4));
+    let length = array.get_own(&"length".into()).unwrap();
+    assert!(length.is_writable() && !length.enumerable && !length.configurable);
+
+    // Indices past the end grow it, and a shorter length deletes them
+    assert!(array.define("9", Property::data(Value::Null)));
+    assert_eq!(array.array_length(), Some(10));
+    assert!(array.define_own_property(
+        "length".into(),
+        PropertyDescriptor::value(Value::Number(2.0))
+    ));
+    assert_eq!(keys(&array), ["0", "1", "length"]);
+    assert!(!array.define_own_property(
+        "length".into(),
+        PropertyDescriptor::value(Value::Number(1.5))
+    ));
+
+    // Deleting stops at an index that can't be deleted
+    let fixed = PropertyDescriptor {
+        value: Some(Value::Null),
+        writable: Some(true),
+        enumerable: Some(true),
+        configurable: Some(false),
+        ..Default::default()
+    };
+    assert!(array.define_own_property("5".into(), fixed));
+    assert!(array.define("6", Property::data(Value::Null)));
+    assert!(!array.define_own_property(
+        "length".into(),
+        PropertyDescriptor::value(Value::Number(0.0))
+    ));
+    assert_eq!(array.array_length(), Some(6));
+    assert_eq!(keys(&array), ["0", "1", "5", "length"]);
+
+    // A read-only length stops the array from growing
+    let read_only = PropertyDescriptor {
+        writable: Some(false),
+        ..Default::default()
+    };
+    assert!(array.define_own_property("length".into(), read_only));
+    assert!(!array.define("6", Property::data(Value::Null)));
+    assert!(array.set_own("0".into(), Value::Null));
+    assert!(!array.set_own("length".into(), Value::Number(10.0)));
+    assert_eq!(array.array_length(), Some(6));
+}
+
+#[test]
+fn test_arguments() {
+    let values = [1.0, 2.0, 3.0].map(Value::from).to_vec();
+    let parameters = Rc::new(Parameters(RefCell::new(values[..2].to_vec())));
+    let map: Rc<dyn ParameterMap> = parameters.clone();
+    let mut arguments = Object::arguments(None, &values, Some((map, vec![true, true])));
+    assert_eq!(keys(&arguments), ["0", "1", "2", "length"]);
+
+    // Mapped indices and parameters see each other's changes
+    parameters.0.borrow_mut()[0] = Value::from(10.0);
+    assert_eq!(number(&arguments, "0"), Some(10.0));
+    assert!(arguments.set_own("1".into(), Value::from(20.0)));
+    assert!(parameters.0.borrow()[1].strict_equals(&Value::from(20.0)));
+
+    // Until the index is deleted, or made read-only
+    assert!(arguments.delete(&"0".into()));
+    assert!(arguments.define("0", Property::data(Value::from(5.0))));
+    assert_eq!(number(&arguments, "0"), Some(5.0));
+    assert!(parameters.0.borrow()[0].strict_equals(&Value::from(10.0)));
+    let read_only = PropertyDescriptor {
+        writable: Some(false),
+        ..Default::default()
+    };
+    assert!(arguments.define_own_property("1".into(), read_only));
+    parameters.0.borrow_mut()[1] = Value::from(30.0);
+    assert_eq!(number(&arguments, "1"), Some(20.0));
+
+    // Strict mode functions get arguments that aren't mapped
+    let unmapped = Object::arguments(None, &values, None);
+    parameters.0.borrow_mut()[0] = Value::from(40.0);
+    assert_eq!(number(&unmapped, "0"), Some(1.0));
+}
+
+#[test]
+fn test_bound_functions() {
+    let prototype = ObjectRef::new(ordinary());
+    let target = ObjectRef::new(Object::new(
+        Some(prototype.clone()),
+        ObjectKind::Function(Box::new(Code)),
+    ));
+    let bound = ObjectRef::new(Object::bound_function(
+        &target,
+        Value::Null,
+        vec![Value::from(1.0)],
+    ));
+    assert!(bound.is_callable());
+    assert!(
+        bound
+            .prototype()
+            .is_some_and(|other| other.ptr_eq(&prototype))
+    );
+    match &bound.borrow().kind {
+        ObjectKind::BoundFunction(bound) => {
+            assert!(bound.target.ptr_eq(&target));
+            assert_eq!(bound.arguments.len(), 1);
+        }
+        _ => panic!("not a bound function"),
+    }
+    assert_eq!(Value::from(bound).type_of(), "function");
+}
+
+#[test]
+fn test_garbage_collection() {
+    collect();
+    let before = heap_stats();
+
+    // Cycles nothing else refers to are freed, and the objects they refer to with them
+    let (first, second) = cycle();
+    let leaf = ObjectRef::new(ordinary());
+    second
+        .borrow_mut()
+        .define("leaf", Property::data(leaf.into()));
+    drop((first, second));
+    assert_eq!(heap_stats().live, before.live + 3);
+    assert_eq!(collect(), 3);
+    let stats = heap_stats();
+    assert_eq!(stats.live, before.live);
+    assert_eq!(stats.allocated, before.allocated + 3);
+    assert_eq!(stats.collections, before.collections + 1);
+    assert_eq!(stats.collected, before.collected + 3);
+
+    // A cycle referred to from outside the heap stays, with what it refers to
+    let (first, second) = cycle();
+    let function = ObjectRef::new(Object::new(None, ObjectKind::Function(Box::new(Code))));
+    second
+        .borrow_mut()
+        .define("function", Property::data(function.into()));
+    drop(second);
+    assert_eq!(collect(), 0);
+    let second = first.borrow().get_own(&"next".into()).unwrap().data_value();
+    let function = second
+        .as_object()
+        .unwrap()
+        .borrow()
+        .get_own(&"function".into());
+    assert!(function.is_some_and(|function| function.data_value().type_of() == "function"));
+    drop((first, second));
+    assert_eq!(collect(), 3);
+
+    // So does a rooted one, until the root is dropped
+    let (first, second) = cycle();
+    let root = Root::new(Value::Object(second));
+    drop(first);
+    assert_eq!(collect(), 0);
+    assert!(root.value().as_object().unwrap().prototype().is_some());
+    drop(root);
+    assert_eq!(collect(), 2);
+    assert_eq!(heap_stats().live, before.live);
+}
+
+#[test]
+fn test_map_data() {
+    let mut map = MapData::default();
+    map.set(Value::Number(-0.0), Value::string("zero"));
+    map.set(Value::Number(f64::NAN), Value::string("nan"));
+    map.set(Value::string("a"), Value::Number(1.0));
+    let object = ObjectRef::new(ordinary());
+    map.set(Value::Object(object.clone()), Value::Null);
+
+    // Keys compare with SameValueZero, and -0 is stored as +0
+    assert_eq!(map.len(), 4);
+    assert!(
+        map.get(&Value::Number(0.0))
+            .is_some_and(|value| value.strict_equals(&Value::string("zero")))
+    );
+    assert!(map.has(&Value::Number(f64::NAN)));
+    assert!(map.has(&Value::Object(object.clone())));
+    assert!(!map.has(&Value::Object(ObjectRef::new(ordinary()))));
+    let (_, key, _) = map.entry(0).unwrap();
+    assert!(matches!(key, Value::Number(zero) if zero.is_sign_positive()));
+
+    // Replacing a value keeps the entry in place, and deleting one leaves a hole
+    map.set(Value::string("a"), Value::Number(2.0));
+    assert!(map.delete(&Value::Number(f64::NAN)));
+    assert!(!map.delete(&Value::Number(f64::NAN)));
+    let (position, key, value) = map.entry(1).unwrap();
+    assert_eq!(position, 2);
+    assert!(key.strict_equals(&Value::string("a")));
+    assert!(value.strict_equals(&Value::Number(2.0)));
+
+    // Cursors follow their entry when the holes are removed
+    let mut map = MapData::default();
+    for index in 0..20 {
+        map.set(Value::Number(index as f64), Value::Undefined);
+    }
+    let cursor = map.cursor();
+    cursor.set(15);
+    for index in 0..12 {
+        map.delete(&Value::Number(index as f64));
+    }
+    let (position, key, _) = map.entry(cursor.get()).unwrap();
+    assert!(position < 15);
+    assert!(key.strict_equals(&Value::Number(15.0)));
+    map.clear();
+    assert!(map.is_empty());
+    assert_eq!(cursor.get(), 0);
+    assert!(map.entry(0).is_none());
+}
+
+#[test]
+fn test_weak_maps() {
+    collect();
+    let before = heap_stats().live;
+    let map = ObjectRef::new(Object::new(
+        None,
+        ObjectKind::WeakMap(WeakMapData::default()),
+    ));
+    let set = |key: &ObjectRef, value: Value| match &mut map.borrow_mut().kind {
+        ObjectKind::WeakMap(data) => data.set(key, value),
+        _ => unreachable!(),
+    };
+
+    // A value that refers to its own key is kept while the key is reachable elsewhere
+    let key = ObjectRef::new(ordinary());
+    let value = ObjectRef::new(ordinary());
+    value
+        .borrow_mut()
+        .define("key", Property::data(key.clone().into()));
+    set(&key, value.into());
+    assert_eq!(collect(), 0);
+    let value = match &map.borrow().kind {
+        ObjectKind::WeakMap(data) => data.get(&key),
+        _ => unreachable!(),
+    };
+    assert!(value.is_some_and(|value| value.as_object().is_some()));
+
+    // Once only the map refers to the key, both are freed
+    drop(key);
+    assert_eq!(collect(), 2);
+    drop(map);
+    collect();
+    assert_eq!(heap_stats().live, before);
+}
@@ -0,0 +1,188 @@
+use crate::object::{ObjectKind, ObjectRef};
+use num_bigint::BigInt;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+// A JavaScript value. Objects and symbols are shared by reference, everything else is copied.
+#[derive(Debug, Clone)]
+pub enum Value {
+    Undefined,
+    Null,
+    Boolean(bool),
+    Number(f64),
+    BigInt(Rc<BigInt>),
+    String(Rc<str>),
+    Symbol(Symbol),
+    Object(ObjectRef),
+}
+
+impl Value {
+    pub fn string(text: &str) -> Value {
+        Value::String(text.into())
+    }
+
+    pub fn bigint(value: BigInt) -> Value {
+        Value::BigInt(Rc::new(value))
+    }
+
+    pub fn is_nullish(&self) -> bool {
+        matches!(self, Value::Undefined | Value::Null)
+    }
+
+    pub fn as_object(&self) -> Option<&ObjectRef> {
+        match self {
+            Value::Object(object) => Some(object),
+            _ => None,
+        }
+    }
+
+    // An object that can be called, which `typeof` reports as a function
+    pub fn as_function(&self) -> Option<&ObjectRef> {
+        self.as_object().filter(|object| object.is_callable())
+    }
+
+    pub fn type_of(&self) -> &'static str {
+        match self {
+            Value::Undefined => "undefined",
+            Value::Null => "object",
+            Value::Boolean(_) => "boolean",
+            Value::Number(_) => "number",
+            Value::BigInt(_) => "bigint",
+            Value::String(_) => "string",
+            Value::Symbol(_) => "symbol",
+            Value::Object(object) if object.is_callable() => "function",
+            Value::Object(_) => "object",
+        }
+    }
+
+    pub fn to_boolean(&self) -> bool {
+        match self {
+            Value::Undefined | Value::Null => false,
+            Value::Boolean(value) => *value,
+            Value::Number(value) => *value != 0.0 && !value.is_nan(),
+            Value::BigInt(value) => **value != BigInt::default(),
+            Value::String(value) => !value.is_empty(),
+            Value::Symbol(_) | Value::Object(_) => true,
+        }
+    }
+
+    // `===`
+    pub fn strict_equals(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::Undefined, Value::Undefined) | (Value::Null, Value::Null) => true,
+            (Value::Boolean(left), Value::Boolean(right)) => left == right,
+            (Value::Number(left), Value::Number(right)) => left == right,
+            (Value::BigInt(left), Value::BigInt(right)) => left == right,
+            (Value::String(left), Value::String(right)) => left == right,
+            (Value::Symbol(left), Value::Symbol(right)) => left == right,
+            (Value::Object(left), Value::Object(right)) => left.ptr_eq(right),
+            _ => false,
+        }
+    }
+
+    // SameValue, which `Object.is` and property descriptors use: NaN is itself and the two
+    // zeros are different
+    pub fn same_value(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::Number(left), Value::Number(right)) if left.is_nan() => right.is_nan(),
+            (Value::Number(left), Value::Number(right)) => {
+                left == right && left.is_sign_negative() == right.is_sign_negative()
+            }
+            _ => self.strict_equals(other),
+        }
+    }
+
+    // SameValueZero, which `includes` and Map keys use: NaN is itself and the two zeros are
+    // the same
+    pub fn same_value_zero(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::Number(left), Value::Number(right)) if left.is_nan() => right.is_nan(),
+            _ => self.strict_equals(other),
+        }
+    }
+
+    // Whether this is an error object, as created by `Error` or a failing operation
+    pub fn is_error(&self) -> bool {
+        self.as_object()
+            .is_some_and(|object| matches!(object.borrow().kind, ObjectKind::Error))
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Boolean(value)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Value::Number(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::string(value)
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::String(value.into())
+    }
+}
+
+impl From<Symbol> for Value {
+    fn from(value: Symbol) -> Self {
+        Value::Symbol(value)
+    }
+}
+
+impl From<ObjectRef> for Value {
+    fn from(value: ObjectRef) -> Self {
+        Value::Object(value)
+    }
+}
+
+// A unique value with an optional description. Two symbols are only equal if they are the
+// same symbol, so they can be property keys that no string or other symbol collides with.
+#[derive(Clone)]
+pub struct Symbol(Rc<Option<Rc<str>>>);
+
+impl Symbol {
+    pub fn new(description: Option<&str>) -> Self {
+        Symbol(Rc::new(description.map(Rc::from)))
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.0.as_deref()
+    }
+}
+
+impl PartialEq for Symbol {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for Symbol {}
+
+impl Hash for Symbol {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Rc::as_ptr(&self.0).hash(state);
+    }
+}
+
+// `Symbol(description)`, as `String(symbol)` gives
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Symbol({})", self.description().unwrap_or_default())
+    }
+}
+
+impl fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
@@ -0,0 +1,653 @@
+use crate::{
+    BindingKind, Declaration, Reference, ReferenceId, ReferenceKind, Scope, ScopeId, ScopeKind,
+    Semantic, SemanticOptions, Symbol, SymbolId,
+};
+use jsompiler_lexer::symbol::{KeywordToken, LiteralToken, OperatorToken, Token};
+use jsompiler_parser::visit::{
+    Visit, walk_expression, walk_program, walk_property, walk_statement,
+};
+use jsompiler_parser::{
+    Ast, BindingPattern, CatchClause, ClassElement, Expression, ExpressionId, ForInOfLeft,
+    ForInStatement, ForOfStatement, Identifier, JSXAttribute, JSXAttributeValue, JSXElementName,
+    Node, Parameter, Property, Span, Statement, StatementId,
+};
+
+pub(crate) fn build(ast: &Ast, options: &SemanticOptions) -> Semantic {
+    let mut builder = Builder {
+        semantic: Semantic::default(),
+        stack: Vec::new(),
+        module: options.module,
+    };
+    builder.visit_program(ast);
+    builder.resolve(ast);
+    builder.semantic
+}
+
+// Collects scopes, declarations and references in one walk. References are resolved once
+// the walk is done, as a name may be used before the `var` or function that declares it.
+struct Builder {
+    semantic: Semantic,
+    stack: Vec<ScopeId>,
+    module: bool,
+}
+
+// `a op= b`, which reads `a` before writing it
+const COMPOUND_ASSIGNMENTS: &[OperatorToken] = &[
+    OperatorToken::PlusEqual,
+    OperatorToken::MinusEqual,
+    OperatorToken::AsteriskEqual,
+    OperatorToken::SlashEqual,
+    OperatorToken::PercentEqual,
+    OperatorToken::AsteriskAsteriskEqual,
+    OperatorToken::LeftShiftEqual,
+    OperatorToken::RightShiftEqual,
+    OperatorToken::UnsignedRightShiftEqual,
+    OperatorToken::BitwiseAndEqual,
+    OperatorToken::BitwiseOrEqual,
+    OperatorToken::BitwiseXorEqual,
+    OperatorToken::LogicalAndEqual,
+    OperatorToken::LogicalOrEqual,
+    OperatorToken::NullishCoalesceEqual,
+];
+
+// Whether statements start with a "use strict" directive
+pub fn has_use_strict(ast: &Ast, statements: &[StatementId]) -> bool {
+    for statement in statements {
+        let Statement::ExpressionStatement { expression, .. } = &ast[*statement] else {
+            return false;
+        };
+        let Expression::Literal {
+            value: LiteralToken::String(value),
+            ..
+        } = &ast[*expression]
+        else {
+            return false;
+        };
+        if value == "use strict" {
+            return true;
+        }
+    }
+    false
+}
+
+// Function bodies are parsed as one block, whose declarations belong to the function
+pub fn body_statements(ast: &Ast, body: &[StatementId]) -> Vec<StatementId> {
+    match body {
+        [id] => match &ast[*id] {
+            Statement::BlockStatement(block) => block.statements.clone(),
+            _ => body.to_vec(),
+        },
+        _ => body.to_vec(),
+    }
+}
+
+impl Builder {
+    fn enter_scope(&mut self, kind: ScopeKind, span: Span) -> ScopeId {
+        let id = ScopeId(self.semantic.scopes.len() as u32);
+        let parent = self.stack.last().copied();
+        let mut strict = kind == ScopeKind::Module || kind == ScopeKind::Class;
+        if let Some(parent) = parent {
+            self.semantic.scopes[parent.index()].children.push(id);
+            strict |= self.semantic.scope(parent).strict;
+        }
+        self.semantic.scopes.push(Scope {
+            kind,
+            parent,
+            children: Vec::new(),
+            bindings: Vec::new(),
+            span,
+            arrow: false,
+            direct_eval: false,
+            strict,
+            catch_pattern: false,
+        });
+        self.stack.push(id);
+        id
+    }
+
+    fn exit_scope(&mut self) {
+        self.stack.pop();
+    }
+
+    fn current(&self) -> ScopeId {
+        *self
+            .stack
+            .last()
+            .expect("the global scope is always entered")
+    }
+
+    // The scope `var` declarations in the current one are hoisted to
+    fn var_scope(&self) -> ScopeId {
+        *self
+            .stack
+            .iter()
+            .rev()
+            .find(|id| {
+                matches!(
+                    self.semantic.scope(**id).kind,
+                    ScopeKind::Function | ScopeKind::Module | ScopeKind::Global
+                )
+            })
+            .expect("the global scope is always entered")
+    }
+
+    fn add_symbol(&mut self, scope: ScopeId, name: &Identifier, declaration: Declaration) {
+        let semantic = &mut self.semantic;
+        match semantic.lookup.get(&(scope, name.value)) {
+            Some(symbol) => semantic.symbols[symbol.index()]
+                .declarations
+                .push(declaration),
+            None => {
+                let symbol = SymbolId(semantic.symbols.len() as u32);
+                semantic.symbols.push(Symbol {
+                    name: name.value,
+                    scope,
+                    declarations: vec![declaration],
+                    references: Vec::new(),
+                });
+                semantic.scopes[scope.index()].bindings.push(symbol);
+                semantic.lookup.insert((scope, name.value), symbol);
+            }
+        }
+    }
+
+    // A name declared twice in the same scope gets one symbol with two declarations
+    fn declare(&mut self, scope: ScopeId, name: &Identifier, kind: BindingKind) {
+        let declaration = Declaration {
+            kind,
+            span: name.span,
+            scope: self.current(),
+        };
+        self.add_symbol(scope, name, declaration);
+    }
+
+    fn declare_pattern(&mut self, token: &Token, pattern: &BindingPattern) {
+        let (kind, scope) = match token {
+            Token::Keyword(KeywordToken::Var) => (BindingKind::Var, self.var_scope()),
+            Token::Keyword(KeywordToken::Const) => (BindingKind::Const, self.current()),
+            _ => (BindingKind::Let, self.current()),
+        };
+        for name in pattern.bound_names() {
+            self.declare(scope, &name, kind);
+        }
+    }
+
+    fn reference(
+        &mut self,
+        identifier: &Identifier,
+        kind: ReferenceKind,
+        expression: Option<ExpressionId>,
+    ) {
+        let id = ReferenceId(self.semantic.references.len() as u32);
+        self.semantic.references.push(Reference {
+            name: identifier.value,
+            span: identifier.span,
+            scope: self.current(),
+            kind,
+            symbol: None,
+            expression,
+        });
+        if let Some(expression) = expression {
+            self.semantic.expression_references.insert(expression, id);
+        }
+    }
+
+    // The left side of an assignment, which writes a name or a property
+    fn assignment_target(&mut self, ast: &Ast, target: ExpressionId, kind: ReferenceKind) {
+        match &ast[target] {
+            Expression::Identifier(identifier) => self.reference(identifier, kind, Some(target)),
+            _ => self.visit_expression(ast, target),
+        }
+    }
+
+    fn statements(&mut self, ast: &Ast, statements: &[StatementId]) {
+        for statement in statements {
+            self.visit_statement(ast, *statement);
+        }
+    }
+
+    fn block(&mut self, ast: &Ast, kind: ScopeKind, statements: &[StatementId], span: Span) {
+        self.enter_scope(kind, span);
+        self.statements(ast, statements);
+        self.exit_scope();
+    }
+
+    // Parameters and the top level of the body share the function scope. The name of a
+    // function expression is only visible inside it, unless the body declares it again.
+    fn function(
+        &mut self,
+        ast: &Ast,
+        name: Option<&Identifier>,
+        parameters: &[Parameter],
+        body: &[StatementId],
+        span: Span,
+        arrow: bool,
+    ) {
+        let scope = self.enter_scope(ScopeKind::Function, span);
+        let body = body_statements(ast, body);
+        let data = &mut self.semantic.scopes[scope.index()];
+        data.arrow = arrow;
+        data.strict |= has_use_strict(ast, &body);
+        for parameter in parameters {
+            for name in parameter.bound_names() {
+                self.declare(scope, &name, BindingKind::Parameter);
+            }
+        }
+        for parameter in parameters {
+            self.visit_parameter(ast, parameter);
+        }
+        self.statements(ast, &body);
+        if let Some(name) = name
+            && self.semantic.binding(scope, name.value).is_none()
+        {
+            self.declare(scope, name, BindingKind::FunctionName);
+        }
+        self.exit_scope();
+    }
+
+    // The class scope holds the name as seen from inside the class, also for declarations
+    fn class(
+        &mut self,
+        ast: &Ast,
+        name: Option<&Identifier>,
+        heritage: Option<ExpressionId>,
+        body: &[ClassElement],
+        span: Span,
+    ) {
+        let scope = self.enter_scope(ScopeKind::Class, span);
+        if let Some(name) = name {
+            self.declare(scope, name, BindingKind::Class);
+        }
+        if let Some(heritage) = heritage {
+            self.visit_expression(ast, heritage);
+        }
+        for element in body {
+            self.visit_class_element(ast, element);
+        }
+        self.exit_scope();
+    }
+
+    // Look each reference up from the scope it was made in. `arguments` that nothing
+    // declares refers to the implicit binding of the closest function that isn't an arrow.
+    fn resolve(&mut self, ast: &Ast) {
+        let arguments = ast.atoms.find("arguments");
+        for index in 0..self.semantic.references.len() {
+            let reference = &self.semantic.references[index];
+            let name = reference.name;
+            let mut symbol = None;
+            let mut scope = Some(reference.scope);
+            while let Some(id) = scope {
+                if let Some(found) = self.semantic.lookup.get(&(id, name)) {
+                    symbol = Some(*found);
+                    break;
+                }
+                let scope_data = self.semantic.scope(id);
+                if Some(name) == arguments
+                    && scope_data.kind == ScopeKind::Function
+                    && !scope_data.arrow
+                {
+                    let identifier = Identifier {
+                        value: name,
+                        span: scope_data.span,
+                    };
+                    let declaration = Declaration {
+                        kind: BindingKind::Arguments,
+                        span: scope_data.span,
+                        scope: id,
+                    };
+                    self.add_symbol(id, &identifier, declaration);
+                    symbol = self.semantic.lookup.get(&(id, name)).copied();
+                    break;
+                }
+                scope = scope_data.parent;
+            }
+            self.semantic.references[index].symbol = symbol;
+            if let Some(symbol) = symbol {
+                self.semantic.symbols[symbol.index()]
+                    .references
+                    .push(ReferenceId(index as u32));
+            }
+        }
+    }
+}
+
+impl Visit for Builder {
+    fn visit_program(&mut self, ast: &Ast) {
+        let global = self.enter_scope(ScopeKind::Global, Span::default());
+        let body: Vec<StatementId> = ast
+            .body
+            .iter()
+            .flat_map(|node| match node {
+                Node::Statement(statements) => statements.clone(),
+                Node::Expression(_) => Vec::new(),
+            })
+            .collect();
+        self.semantic.scopes[global.index()].strict = has_use_strict(ast, &body);
+        if self.module {
+            self.enter_scope(ScopeKind::Module, Span::default());
+        }
+        walk_program(self, ast);
+        self.stack.clear();
+    }
+
+    fn visit_statement(&mut self, ast: &Ast, id: StatementId) {
+        let statement = &ast[id];
+        match statement {
+            Statement::LetStatement(declarator) => {
+                self.declare_pattern(&declarator.token, &declarator.name);
+                walk_statement(self, ast, statement);
+            }
+            Statement::FunctionDeclaration(function) => {
+                if let Some(name) = &function.name {
+                    self.declare(self.current(), name, BindingKind::Function);
+                }
+                self.function(
+                    ast,
+                    None,
+                    &function.parameters,
+                    &function.body,
+                    function.span,
+                    false,
+                );
+            }
+            Statement::ClassDeclaration(class) => {
+                if let Some(name) = &class.identifier {
+                    self.declare(self.current(), name, BindingKind::Class);
+                }
+                self.class(
+                    ast,
+                    class.identifier.as_ref(),
+                    class.heritage,
+                    &class.body,
+                    class.span,
+                );
+            }
+            Statement::BlockStatement(block) => {
+                self.block(ast, ScopeKind::Block, &block.statements, block.span)
+            }
+            Statement::AssignmentStatement(assignment) => {
+                self.assignment_target(ast, assignment.target, ReferenceKind::Write);
+                self.visit_expression(ast, assignment.value);
+            }
+            Statement::ForLoopStatement(for_loop) => {
+                self.enter_scope(ScopeKind::Block, for_loop.span);
+                walk_statement(self, ast, statement);
+                self.exit_scope();
+            }
+            Statement::ForInStatement(ForInStatement {
+                left,
+                right,
+                body,
+                span,
+            })
+            | Statement::ForOfStatement(ForOfStatement {
+                left,
+                right,
+                body,
+                span,
+                ..
+            }) => {
+                self.enter_scope(ScopeKind::Block, *span);
+                match left {
+                    ForInOfLeft::Declaration { token, name, .. } => {
+                        self.declare_pattern(token, name);
+                        self.visit_binding_pattern(ast, name);
+                    }
+                    ForInOfLeft::Target(target) => {
+                        self.assignment_target(ast, *target, ReferenceKind::Write)
+                    }
+                }
+                self.visit_expression(ast, *right);
+                self.statements(ast, body);
+                self.exit_scope();
+            }
+            // The cases share one scope
+            Statement::SwitchStatement(switch) => {
+                self.visit_expression(ast, switch.discriminant);
+                self.enter_scope(ScopeKind::Block, switch.span);
+                for case in &switch.cases {
+                    self.visit_switch_case(ast, case);
+                }
+                self.exit_scope();
+            }
+            Statement::WithStatement(with) => {
+                self.visit_expression(ast, with.object);
+                self.block(ast, ScopeKind::With, &with.body, with.span);
+            }
+            _ => walk_statement(self, ast, statement),
+        }
+    }
+
+    fn visit_expression(&mut self, ast: &Ast, id: ExpressionId) {
+        let expression = &ast[id];
+        match expression {
+            Expression::Identifier(identifier) => {
+                self.reference(identifier, ReferenceKind::Read, Some(id))
+            }
+            // `a.b` only refers to `a`
+            Expression::MemberAccess {
+                object,
+                property,
+                computed,
+                ..
+            } => {
+                self.visit_expression(ast, *object);
+                if *computed {
+                    self.visit_expression(ast, *property);
+                }
+            }
+            Expression::Unary { op, expr, .. }
+                if matches!(
+                    op.token,
+                    Token::Operator(OperatorToken::Increment | OperatorToken::Decrement)
+                ) =>
+            {
+                self.assignment_target(ast, *expr, ReferenceKind::ReadWrite)
+            }
+            Expression::Binary {
+                left, op, right, ..
+            } if matches!(&op.token, Token::Operator(op) if COMPOUND_ASSIGNMENTS.contains(op)) => {
+                self.assignment_target(ast, *left, ReferenceKind::ReadWrite);
+                self.visit_expression(ast, *right);
+            }
+            Expression::FunctionCall { callee, .. } => {
+                if let Expression::Identifier(identifier) = &ast[*callee]
+                    && &ast[identifier.value] == "eval"
+                {
+                    let scope = self.current();
+                    self.semantic.scopes[scope.index()].direct_eval = true;
+                }
+                walk_expression(self, ast, expression);
+            }
+            Expression::FunctionExpression(function)
+            | Expression::GeneratorExpression(function)
+            | Expression::AsyncFunctionExpression(function)
+            | Expression::AsyncGeneratorExpression(function) => self.function(
+                ast,
+                function.name.as_ref(),
+                &function.parameters,
+                &function.body,
+                function.span,
+                false,
+            ),
+            Expression::ArrowFunctionExpression {
+                parameters,
+                body,
+                span,
+            } => self.function(ast, None, parameters, body, *span, true),
+            Expression::ClassExpression(class) => self.class(
+                ast,
+                class.identifier.as_ref(),
+                class.heritage,
+                &class.body,
+                class.span,
+            ),
+            _ => walk_expression(self, ast, expression),
+        }
+    }
+
+    fn visit_property(&mut self, ast: &Ast, property: &Property) {
+        match property {
+            Property::Shorthand(identifier) => {
+                self.reference(identifier, ReferenceKind::Read, None)
+            }
+            Property::Method {
+                key,
+                params,
+                body,
+                function_span,
+                ..
+            } => {
+                self.visit_property_key(ast, key);
+                self.function(ast, None, params, body, *function_span, false);
+            }
+            Property::Getter {
+                key,
+                body,
+                function_span,
+                ..
+            } => {
+                self.visit_property_key(ast, key);
+                let body = std::slice::from_ref(body);
+                self.function(ast, None, &[], body, *function_span, false);
+            }
+            Property::Setter {
+                key,
+                param,
+                body,
+                function_span,
+                ..
+            } => {
+                self.visit_property_key(ast, key);
+                let (params, body) = (std::slice::from_ref(param), std::slice::from_ref(body));
+                self.function(ast, None, params, body, *function_span, false);
+            }
+            _ => walk_property(self, ast, property),
+        }
+    }
+
+    fn visit_class_element(&mut self, ast: &Ast, element: &ClassElement) {
+        match element {
+            ClassElement::Constructor {
+                name,
+                params,
+                body,
+                function_span,
+                ..
+            }
+            | ClassElement::MethodDefinition {
+                name,
+                params,
+                body,
+                function_span,
+                ..
+            }
+            | ClassElement::AsyncMethodDefinition {
+                name,
+                params,
+                body,
+                function_span,
+                ..
+            }
+            | ClassElement::GeneratorMethodDefinition {
+                name,
+                params,
+                body,
+                function_span,
+                ..
+            }
+            | ClassElement::AsyncGeneratorMethodDefinition {
+                name,
+                params,
+                body,
+                function_span,
+                ..
+            } => {
+                self.visit_class_element_name(ast, name);
+                self.function(ast, None, params, body, *function_span, false);
+            }
+            ClassElement::GetterDefinition {
+                name,
+                body,
+                function_span,
+                ..
+            } => {
+                self.visit_class_element_name(ast, name);
+                self.function(ast, None, &[], body, *function_span, false);
+            }
+            ClassElement::SetterDefinition {
+                name,
+                param,
+                body,
+                function_span,
+                ..
+            } => {
+                self.visit_class_element_name(ast, name);
+                let params = std::slice::from_ref(param);
+                self.function(ast, None, params, body, *function_span, false);
+            }
+            ClassElement::StaticBlock { body, span } => {
+                self.function(ast, None, &[], body, *span, false)
+            }
+            ClassElement::FieldDefinition { name, value, .. }
+            | ClassElement::AccessorDefinition { name, value, .. } => {
+                self.visit_class_element_name(ast, name);
+                if let Some(value) = value {
+                    self.visit_expression(ast, *value);
+                }
+            }
+        }
+    }
+
+    fn visit_catch_clause(&mut self, ast: &Ast, clause: &CatchClause) {
+        let scope = self.enter_scope(ScopeKind::Catch, clause.span);
+        if let Some(param) = &clause.param {
+            self.semantic.scopes[scope.index()].catch_pattern =
+                !matches!(param, BindingPattern::Identifier(_));
+            for name in param.bound_names() {
+                self.declare(scope, &name, BindingKind::CatchParameter);
+            }
+            self.visit_binding_pattern(ast, param);
+        }
+        self.statements(ast, &body_statements(ast, &clause.body));
+        self.exit_scope();
+    }
+
+    // `<div>` names a tag, `<Foo>` and `<foo.Bar>` refer to bindings
+    fn visit_jsx_element_name(&mut self, ast: &Ast, name: &JSXElementName) {
+        match name {
+            JSXElementName::Identifier(identifier) => {
+                if !ast[identifier.value].starts_with(|c: char| c.is_ascii_lowercase()) {
+                    self.reference(identifier, ReferenceKind::Read, None);
+                }
+            }
+            JSXElementName::Member { .. } => {
+                let mut name = name;
+                while let JSXElementName::Member { object, .. } = name {
+                    name = object;
+                }
+                if let JSXElementName::Identifier(identifier) = name {
+                    self.reference(identifier, ReferenceKind::Read, None);
+                }
+            }
+            JSXElementName::Namespaced { .. } => {}
+        }
+    }
+
+    // Attribute names are not references
+    fn visit_jsx_attribute(&mut self, ast: &Ast, attribute: &JSXAttribute) {
+        match attribute {
+            JSXAttribute::Attribute { value, .. } => match value {
+                Some(JSXAttributeValue::ExpressionContainer(container)) => {
+                    self.visit_jsx_expression_container(ast, container)
+                }
+                Some(JSXAttributeValue::Element(element)) => self.visit_jsx_element(ast, element),
+                Some(JSXAttributeValue::Fragment(fragment)) => {
+                    self.visit_jsx_fragment(ast, fragment)
+                }
+                Some(JSXAttributeValue::StringLiteral { .. }) | None => {}
+            },
+            JSXAttribute::SpreadAttribute { argument, .. } => self.visit_expression(ast, *argument),
+        }
+    }
+}
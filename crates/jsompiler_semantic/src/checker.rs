@@ -0,0 +1,373 @@
+use crate::builder::{body_statements, has_use_strict};
+use crate::{BindingKind, Declaration, ReferenceKind, ScopeId, ScopeKind, Semantic};
+use jsompiler_common::{Atom, Error, ErrorKind};
+use jsompiler_lexer::symbol::{KeywordToken, Token};
+use jsompiler_parser::visit::{AstNode, Visit, Walk};
+use jsompiler_parser::{
+    Ast, ClassElement, Expression, ForInStatement, ForOfStatement, Property, PropertyKey, Span,
+    Statement, StatementId,
+};
+
+// Report the early errors of a program: redeclared names, `var` that clashes with a lexical
+// declaration or a destructured `catch` parameter, declarations where only a statement may
+// go, assignments to `const`, `await` and `yield` outside the functions they belong to,
+// duplicate `__proto__` properties, and duplicate parameters, `with` and `delete` of a name
+// in strict mode. Errors come in source order.
+pub fn check(ast: &Ast, semantic: &Semantic, source: &str) -> Vec<Error> {
+    let program = semantic.scope(semantic.program_scope());
+    let mut checker = Checker {
+        ast,
+        contexts: vec![Context {
+            is_async: program.kind == ScopeKind::Module, // Modules allow a top level `await`
+            is_generator: false,
+            strict: program.strict,
+        }],
+        errors: Vec::new(),
+    };
+    checker.visit_program(ast);
+    check_bindings(ast, semantic, &mut checker.errors);
+    for scope in &semantic.scopes {
+        if scope.kind == ScopeKind::With && scope.strict {
+            let message = "Strict mode code may not include a with statement".to_string();
+            checker.errors.push((scope.span, message));
+        }
+    }
+
+    let lines = LineStarts::new(source);
+    let mut errors = checker.errors;
+    errors.sort_by_key(|(span, _)| span.start);
+    errors
+        .into_iter()
+        .map(|(span, message)| {
+            Error::new(
+                ErrorKind::SemanticError,
+                message,
+                lines.line(span.start),
+                span.start,
+            )
+        })
+        .collect()
+}
+
+// Line numbers of character offsets
+struct LineStarts(Vec<usize>);
+
+impl LineStarts {
+    fn new(source: &str) -> Self {
+        let mut starts = vec![0];
+        for (index, character) in source.chars().enumerate() {
+            if character == '\n' {
+                starts.push(index + 1);
+            }
+        }
+        LineStarts(starts)
+    }
+
+    fn line(&self, offset: usize) -> usize {
+        self.0.partition_point(|&start| start <= offset)
+    }
+}
+
+// Whether a declaration of `kind` is lexical in a scope of `scope_kind`. Functions are
+// lexical in blocks and at the top level of modules, and act like `var` elsewhere.
+fn is_lexical(kind: BindingKind, scope_kind: ScopeKind) -> bool {
+    kind.is_lexical()
+        || kind == BindingKind::Function
+            && matches!(
+                scope_kind,
+                ScopeKind::Block | ScopeKind::Catch | ScopeKind::Module
+            )
+}
+
+fn redeclaration(name: &str) -> String {
+    format!("Identifier '{name}' has already been declared")
+}
+
+fn check_bindings(ast: &Ast, semantic: &Semantic, errors: &mut Vec<(Span, String)>) {
+    for symbol in &semantic.symbols {
+        let scope = semantic.scope(symbol.scope);
+        // A name may only be declared again if neither declaration is lexical. Sloppy mode
+        // still allows two functions of the same name in a block, and two parameters.
+        for (index, later) in symbol.declarations.iter().enumerate().skip(1) {
+            if later.kind == BindingKind::Parameter && scope.strict {
+                let message = format!(
+                    "Duplicate parameter name '{}' not allowed in this context",
+                    &ast[symbol.name]
+                );
+                errors.push((later.span, message));
+                continue;
+            }
+            let clashes = symbol.declarations[..index].iter().any(|earlier| {
+                let both_functions =
+                    earlier.kind == BindingKind::Function && later.kind == BindingKind::Function;
+                (is_lexical(earlier.kind, scope.kind) || is_lexical(later.kind, scope.kind))
+                    && !(both_functions && !scope.strict && scope.kind != ScopeKind::Module)
+            });
+            if clashes {
+                errors.push((later.span, redeclaration(&ast[symbol.name])));
+            }
+        }
+
+        // `var` is hoisted through the scopes between its declaration and its binding,
+        // and clashes with a lexical declaration of the same name in any of them. A `catch`
+        // parameter only allows it when it is a plain name.
+        for declaration in &symbol.declarations {
+            if declaration.kind == BindingKind::Var
+                && hoists_through_lexical(semantic, declaration, symbol.scope, symbol.name)
+            {
+                errors.push((declaration.span, redeclaration(&ast[symbol.name])));
+            }
+        }
+
+        if symbol.kind() == BindingKind::Const {
+            for reference in &symbol.references {
+                let reference = semantic.reference(*reference);
+                if reference.kind != ReferenceKind::Read {
+                    let message =
+                        format!("Assignment to constant variable '{}'", &ast[symbol.name]);
+                    errors.push((reference.span, message));
+                }
+            }
+        }
+    }
+}
+
+fn hoists_through_lexical(
+    semantic: &Semantic,
+    declaration: &Declaration,
+    target: ScopeId,
+    name: Atom,
+) -> bool {
+    semantic
+        .ancestors(declaration.scope)
+        .take_while(|&scope| scope != target)
+        .any(|id| {
+            let scope = semantic.scope(id);
+            semantic.binding(id, name).is_some_and(|symbol| {
+                semantic
+                    .symbol(symbol)
+                    .declarations
+                    .iter()
+                    .any(|declaration| {
+                        is_lexical(declaration.kind, scope.kind)
+                            || declaration.kind == BindingKind::CatchParameter
+                                && scope.catch_pattern
+                    })
+            })
+        })
+}
+
+// What the function being checked allows
+#[derive(Debug, Clone, Copy)]
+struct Context {
+    is_async: bool,
+    is_generator: bool,
+    strict: bool,
+}
+
+struct Checker<'a> {
+    ast: &'a Ast,
+    contexts: Vec<Context>,
+    errors: Vec<(Span, String)>,
+}
+
+impl Checker<'_> {
+    fn context(&self) -> Context {
+        *self
+            .contexts
+            .last()
+            .expect("the program context is always there")
+    }
+
+    fn function(&self, is_async: bool, is_generator: bool, body: &[StatementId]) -> Context {
+        let body = body_statements(self.ast, body);
+        Context {
+            is_async,
+            is_generator,
+            strict: self.context().strict || has_use_strict(self.ast, &body),
+        }
+    }
+
+    // The context a node starts, if it is a function or a class
+    fn context_of(&self, node: &AstNode) -> Option<Context> {
+        let context = match node {
+            AstNode::Statement(_, Statement::FunctionDeclaration(function)) => {
+                self.function(function.is_async, function.is_generator, &function.body)
+            }
+            AstNode::Statement(_, Statement::ClassDeclaration(_))
+            | AstNode::Expression(_, Expression::ClassExpression(_)) => Context {
+                strict: true,
+                ..self.context()
+            },
+            AstNode::Expression(_, expression) => match expression {
+                Expression::FunctionExpression(function) => {
+                    self.function(false, false, &function.body)
+                }
+                Expression::GeneratorExpression(function) => {
+                    self.function(false, true, &function.body)
+                }
+                Expression::AsyncFunctionExpression(function) => {
+                    self.function(true, false, &function.body)
+                }
+                Expression::AsyncGeneratorExpression(function) => {
+                    self.function(true, true, &function.body)
+                }
+                Expression::ArrowFunctionExpression { body, .. } => {
+                    self.function(false, false, body)
+                }
+                _ => return None,
+            },
+            AstNode::Property(property) => match property {
+                Property::Method { body, .. } => self.function(false, false, body),
+                Property::Getter { body, .. } | Property::Setter { body, .. } => {
+                    self.function(false, false, std::slice::from_ref(body))
+                }
+                _ => return None,
+            },
+            // Field initializers and static blocks can use neither `await` nor `yield`
+            AstNode::ClassElement(element) => match element {
+                ClassElement::AsyncMethodDefinition { body, .. } => {
+                    self.function(true, false, body)
+                }
+                ClassElement::GeneratorMethodDefinition { body, .. } => {
+                    self.function(false, true, body)
+                }
+                ClassElement::AsyncGeneratorMethodDefinition { body, .. } => {
+                    self.function(true, true, body)
+                }
+                _ => self.function(false, false, &[]),
+            },
+            _ => return None,
+        };
+        Some(context)
+    }
+
+    fn error(&mut self, span: Span, message: &str) {
+        self.errors.push((span, message.to_string()));
+    }
+
+    // The body of `if`, a loop, a label or `with` is one statement unless it is a block, so
+    // a declaration there would have no scope to go to
+    fn single_statement(&mut self, body: &[StatementId], in_loop: bool) {
+        let context = self.context();
+        for statement in body {
+            match &self.ast[*statement] {
+                Statement::LetStatement(declaration)
+                    if declaration.token != Token::Keyword(KeywordToken::Var) =>
+                {
+                    let span = declaration.span;
+                    self.error(
+                        span,
+                        "Lexical declaration cannot appear in a single-statement context",
+                    );
+                    // The other declarators of the same declaration share its span
+                    return;
+                }
+                Statement::ClassDeclaration(class) => {
+                    let span = class.span;
+                    self.error(
+                        span,
+                        "Class declaration cannot appear in a single-statement context",
+                    );
+                }
+                Statement::FunctionDeclaration(function)
+                    if in_loop || context.strict || function.is_async || function.is_generator =>
+                {
+                    let span = function.span;
+                    self.error(
+                        span,
+                        "Functions can only be declared at the top level or inside a block",
+                    );
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::IfStatement(statement) => {
+                self.single_statement(&statement.consequence, false);
+                if let Some(alternative) = &statement.alternative {
+                    self.single_statement(alternative, false);
+                }
+            }
+            Statement::WhileStatement(statement) => {
+                self.single_statement(&statement.consequence, true)
+            }
+            Statement::DoWhileStatement(statement) => self.single_statement(&statement.body, true),
+            Statement::ForLoopStatement(statement) => self.single_statement(&statement.body, true),
+            Statement::ForInStatement(ForInStatement { body, .. })
+            | Statement::ForOfStatement(ForOfStatement { body, .. }) => {
+                self.single_statement(body, true)
+            }
+            Statement::LabeledStatement(statement) => self.single_statement(&statement.body, false),
+            Statement::WithStatement(statement) => self.single_statement(&statement.body, false),
+            Statement::YieldStatement(statement) if !self.context().is_generator => self.error(
+                statement.span,
+                "'yield' is only valid in generator functions",
+            ),
+            _ => {}
+        }
+    }
+
+    fn expression(&mut self, expression: &Expression) {
+        match expression {
+            Expression::AwaitExpression { span, .. } if !self.context().is_async => self.error(
+                *span,
+                "'await' is only valid in async functions and the top level bodies of modules",
+            ),
+            Expression::Unary { op, expr, span, .. }
+                if op.token == Token::Keyword(KeywordToken::Delete)
+                    && self.context().strict
+                    && matches!(self.ast[*expr], Expression::Identifier(_)) =>
+            {
+                self.error(*span, "Delete of an unqualified identifier in strict mode")
+            }
+            Expression::ObjectLiteral { properties, .. } => {
+                let mut protos = properties.iter().filter_map(|property| match property {
+                    Property::KeyValue {
+                        key: PropertyKey::Identifier(identifier),
+                        span,
+                        ..
+                    } if &self.ast[identifier.value] == "__proto__" => Some(*span),
+                    Property::KeyValue {
+                        key: PropertyKey::StringLiteral { value, .. },
+                        span,
+                        ..
+                    } if value == "__proto__" => Some(*span),
+                    _ => None,
+                });
+                protos.next();
+                let duplicates: Vec<Span> = protos.collect();
+                for span in duplicates {
+                    self.error(
+                        span,
+                        "Duplicate __proto__ fields are not allowed in object literals",
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Visit for Checker<'_> {
+    fn enter(&mut self, node: AstNode) -> Walk {
+        match node {
+            AstNode::Statement(_, statement) => self.statement(statement),
+            AstNode::Expression(_, expression) => self.expression(expression),
+            _ => {}
+        }
+        if let Some(context) = self.context_of(&node) {
+            self.contexts.push(context);
+        }
+        Walk::Continue
+    }
+
+    fn exit(&mut self, node: AstNode) {
+        if self.context_of(&node).is_some() {
+            self.contexts.pop();
+        }
+    }
+}
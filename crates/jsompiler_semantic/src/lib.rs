@@ -0,0 +1,220 @@
+mod builder;
+mod checker;
+mod test;
+
+pub use builder::{body_statements, has_use_strict};
+pub use checker::check;
+
+use jsompiler_common::Atom;
+use jsompiler_parser::{Ast, ExpressionId, Span};
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Clone, Default)]
+pub struct SemanticOptions {
+    pub module: bool, // Top level declarations go to a module scope instead of the global one
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ScopeId(u32);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SymbolId(u32);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ReferenceId(u32);
+
+impl ScopeId {
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl SymbolId {
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl ReferenceId {
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScopeKind {
+    Global,
+    Module,
+    Function, // Also arrows, methods, accessors and static blocks
+    Block,    // Blocks, loop heads and the cases of a `switch`
+    Catch,    // The parameter and body of a `catch` clause
+    Class,    // Holds the name of the class as seen from inside it
+    With,     // The body of a `with` statement, where any name may be a property
+}
+
+#[derive(Debug, Clone)]
+pub struct Scope {
+    pub kind: ScopeKind,
+    pub parent: Option<ScopeId>,
+    pub children: Vec<ScopeId>,
+    pub bindings: Vec<SymbolId>, // In the order they were declared
+    pub span: Span,
+    pub arrow: bool, // An arrow function, which has no `this` or `arguments` of its own
+    pub direct_eval: bool, // Calls `eval` directly, which can read and declare any name in scope
+    pub strict: bool, // Modules, classes, and code under a "use strict" directive
+    pub catch_pattern: bool, // A `catch` clause whose parameter is a destructuring pattern
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BindingKind {
+    Var,
+    Let,
+    Const,
+    Function,
+    Class,
+    Parameter,
+    CatchParameter,
+    FunctionName, // The name of a function expression, visible only inside it
+    Arguments,    // The implicit `arguments` of a function that refers to it
+}
+
+impl BindingKind {
+    pub fn is_lexical(self) -> bool {
+        matches!(
+            self,
+            BindingKind::Let | BindingKind::Const | BindingKind::Class
+        )
+    }
+}
+
+// One place a name is declared. A symbol declared twice, as `var a; var a`, has two.
+#[derive(Debug, Clone, Copy)]
+pub struct Declaration {
+    pub kind: BindingKind,
+    pub span: Span,
+    pub scope: ScopeId, // Where the declaration is written, which a `var` is hoisted out of
+}
+
+#[derive(Debug, Clone)]
+pub struct Symbol {
+    pub name: Atom,
+    pub scope: ScopeId,
+    pub declarations: Vec<Declaration>,
+    pub references: Vec<ReferenceId>,
+}
+
+impl Symbol {
+    // The kind of the first declaration
+    pub fn kind(&self) -> BindingKind {
+        self.declarations[0].kind
+    }
+
+    pub fn span(&self) -> Span {
+        self.declarations[0].span
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceKind {
+    Read,
+    Write,
+    ReadWrite, // `a += 1` and `a++`
+}
+
+#[derive(Debug, Clone)]
+pub struct Reference {
+    pub name: Atom,
+    pub span: Span,
+    pub scope: ScopeId,
+    pub kind: ReferenceKind,
+    pub symbol: Option<SymbolId>,         // `None` for a global
+    pub expression: Option<ExpressionId>, // `None` for shorthand properties and JSX tag names
+}
+
+// The scope tree of a program with every binding and every reference to one. Scopes,
+// symbols and references are numbered in the order they are found, the global scope first.
+#[derive(Debug, Clone, Default)]
+pub struct Semantic {
+    pub scopes: Vec<Scope>,
+    pub symbols: Vec<Symbol>,
+    pub references: Vec<Reference>,
+    lookup: HashMap<(ScopeId, Atom), SymbolId>,
+    expression_references: HashMap<ExpressionId, ReferenceId>,
+}
+
+// Build the scope tree of a parsed program and resolve its references
+pub fn analyze(ast: &Ast, options: &SemanticOptions) -> Semantic {
+    builder::build(ast, options)
+}
+
+impl Semantic {
+    pub fn global_scope(&self) -> ScopeId {
+        ScopeId(0)
+    }
+
+    // The scope top level declarations go to: the module scope of a module, else the global one
+    pub fn program_scope(&self) -> ScopeId {
+        match self.scopes.get(1) {
+            Some(scope) if scope.kind == ScopeKind::Module => ScopeId(1),
+            _ => ScopeId(0),
+        }
+    }
+
+    pub fn scope(&self, id: ScopeId) -> &Scope {
+        &self.scopes[id.index()]
+    }
+
+    pub fn symbol(&self, id: SymbolId) -> &Symbol {
+        &self.symbols[id.index()]
+    }
+
+    pub fn reference(&self, id: ReferenceId) -> &Reference {
+        &self.references[id.index()]
+    }
+
+    // The binding declared for `name` in exactly this scope
+    pub fn binding(&self, scope: ScopeId, name: Atom) -> Option<SymbolId> {
+        self.lookup.get(&(scope, name)).copied()
+    }
+
+    // What `name` refers to from `scope`, looking outwards
+    pub fn find_binding(&self, mut scope: ScopeId, name: Atom) -> Option<SymbolId> {
+        loop {
+            if let Some(symbol) = self.lookup.get(&(scope, name)) {
+                return Some(*symbol);
+            }
+            scope = self.scope(scope).parent?;
+        }
+    }
+
+    // The reference an identifier expression makes
+    pub fn reference_of(&self, expression: ExpressionId) -> Option<ReferenceId> {
+        self.expression_references.get(&expression).copied()
+    }
+
+    // The binding an identifier expression refers to, `None` for a global
+    pub fn symbol_of(&self, expression: ExpressionId) -> Option<SymbolId> {
+        self.reference(self.reference_of(expression)?).symbol
+    }
+
+    // References no declaration in the program resolves
+    pub fn unresolved_references(&self) -> impl Iterator<Item = &Reference> {
+        self.references
+            .iter()
+            .filter(|reference| reference.symbol.is_none())
+    }
+
+    // Names of the globals the program uses without declaring, in order of first use
+    pub fn unresolved_globals(&self) -> Vec<Atom> {
+        let mut seen = HashSet::new();
+        self.unresolved_references()
+            .map(|reference| reference.name)
+            .filter(|name| seen.insert(*name))
+            .collect()
+    }
+
+    // Scopes from `scope` out to the global scope
+    pub fn ancestors(&self, scope: ScopeId) -> impl Iterator<Item = ScopeId> + '_ {
+        std::iter::successors(Some(scope), |&scope| self.scope(scope).parent)
+    }
+}
@@ -0,0 +1,428 @@
+#[allow(unused_imports)]
+use super::{
+    BindingKind, ReferenceKind, ScopeKind, Semantic, SemanticOptions, SymbolId, analyze, check,
+};
+#[allow(unused_imports)]
+use jsompiler_common::ErrorKind;
+#[allow(unused_imports)]
+use jsompiler_lexer::Lexer;
+#[allow(unused_imports)]
+use jsompiler_parser::{Ast, Parser};
+
+#[allow(dead_code)]
+fn analyzed(source: &str, options: &SemanticOptions) -> (Ast, Semantic) {
+    let mut lexer = Lexer::new(source.to_string());
+    lexer.scan_all_tokens();
+    assert!(lexer.errors.is_empty(), "{source}: {:?}", lexer.errors);
+    let mut parser = Parser::new(lexer.tokens);
+    parser.parse();
+    assert!(parser.errors.is_empty(), "{source}: {:?}", parser.errors);
+    let semantic = analyze(&parser.ast, options);
+    (parser.ast, semantic)
+}
+
+#[allow(dead_code)]
+fn script(source: &str) -> (Ast, Semantic) {
+    analyzed(source, &SemanticOptions::default())
+}
+
+// Each reference as `name -> kind of the scope of its binding`, or `name -> global`
+#[allow(dead_code)]
+fn resolutions(ast: &Ast, semantic: &Semantic) -> Vec<String> {
+    semantic
+        .references
+        .iter()
+        .map(|reference| match reference.symbol {
+            Some(symbol) => {
+                let scope = semantic.symbol(symbol).scope;
+                format!(
+                    "{} -> {:?}",
+                    &ast[reference.name],
+                    semantic.scope(scope).kind
+                )
+            }
+            None => format!("{} -> global", &ast[reference.name]),
+        })
+        .collect()
+}
+
+#[allow(dead_code)]
+fn kinds(ast: &Ast, semantic: &Semantic) -> Vec<(String, BindingKind)> {
+    semantic
+        .symbols
+        .iter()
+        .map(|symbol| (ast[symbol.name].to_string(), symbol.kind()))
+        .collect()
+}
+
+#[test]
+fn test_scope_tree() {
+    let (_, semantic) = script(
+        "function f(a) {\n  let b = 1\n  { const c = 2 }\n  try { g() } catch (e) { h(e) }\n  with (a) { b }\n}\nclass C {}\n",
+    );
+    let scopes: Vec<_> = semantic
+        .scopes
+        .iter()
+        .map(|scope| (scope.kind, scope.parent.map(|parent| parent.index())))
+        .collect();
+    assert_eq!(
+        scopes,
+        [
+            (ScopeKind::Global, None),
+            (ScopeKind::Function, Some(0)),
+            (ScopeKind::Block, Some(1)),
+            (ScopeKind::Block, Some(1)),
+            (ScopeKind::Catch, Some(1)),
+            (ScopeKind::With, Some(1)),
+            (ScopeKind::Block, Some(5)),
+            (ScopeKind::Class, Some(0)),
+        ]
+    );
+    assert_eq!(semantic.scope(semantic.global_scope()).children.len(), 2);
+
+    let (ast, module) = analyzed("export let a = 1\n", &SemanticOptions { module: true });
+    let a = ast.atoms.find("a").unwrap();
+    assert_eq!(module.scope(module.program_scope()).kind, ScopeKind::Module);
+    assert!(module.binding(module.program_scope(), a).is_some());
+    assert!(module.binding(module.global_scope(), a).is_none());
+}
+
+#[test]
+fn test_declarations_are_recorded() {
+    let (ast, semantic) = script(
+        "var a = 1\nlet b = 2\nconst { c, d: [e] } = f\nfunction g(h, ...i) {}\nclass J {}\n",
+    );
+    assert_eq!(
+        kinds(&ast, &semantic),
+        [
+            ("a".to_string(), BindingKind::Var),
+            ("b".to_string(), BindingKind::Let),
+            ("c".to_string(), BindingKind::Const),
+            ("e".to_string(), BindingKind::Const),
+            ("g".to_string(), BindingKind::Function),
+            ("h".to_string(), BindingKind::Parameter),
+            ("i".to_string(), BindingKind::Parameter),
+            ("J".to_string(), BindingKind::Class),
+            ("J".to_string(), BindingKind::Class),
+        ]
+    );
+    // The second `J` is the name as seen from inside the class
+    let inner = semantic.symbol(SymbolId(8)).scope;
+    assert_eq!(semantic.scope(inner).kind, ScopeKind::Class);
+
+    // Declaring a name again adds a declaration to the same symbol
+    let (_, semantic) = script("var a\nvar a\n");
+    assert_eq!(semantic.symbols.len(), 1);
+    assert_eq!(semantic.symbols[0].declarations.len(), 2);
+}
+
+#[test]
+fn test_var_is_hoisted_out_of_blocks() {
+    let (ast, semantic) = script("function f() {\n  { var a = 1; let b = 2 }\n  a\n  b\n}\n");
+    assert_eq!(
+        resolutions(&ast, &semantic),
+        ["a -> Function", "b -> global"]
+    );
+    // The declaration remembers the block it is written in
+    let a = semantic
+        .symbols
+        .iter()
+        .find(|symbol| &ast[symbol.name] == "a")
+        .unwrap();
+    assert_eq!(
+        semantic.scope(a.declarations[0].scope).kind,
+        ScopeKind::Block
+    );
+}
+
+#[test]
+fn test_references_are_resolved() {
+    let (ast, semantic) = script(
+        "let x = 1\nfunction f(y) {\n  let x = y\n  return function g() { return x + g + z }\n}\nh(f, x)\n",
+    );
+    assert_eq!(
+        resolutions(&ast, &semantic),
+        [
+            "y -> Function",
+            "x -> Function",
+            "g -> Function",
+            "z -> global",
+            "h -> global",
+            "f -> Global",
+            "x -> Global",
+        ]
+    );
+    assert_eq!(
+        semantic.symbols.last().map(|symbol| symbol.kind()),
+        Some(BindingKind::FunctionName)
+    );
+    // A use before the declaration still refers to it
+    let (ast, semantic) = script("a()\nfunction a() {}\n");
+    assert_eq!(resolutions(&ast, &semantic), ["a -> Global"]);
+    // Property names, labels and keys are not references
+    let (ast, semantic) = script("a.b\nc[d]\ne = { f: g }\nl: for (;;) break l\n");
+    assert_eq!(
+        resolutions(&ast, &semantic),
+        [
+            "a -> global",
+            "c -> global",
+            "d -> global",
+            "e -> global",
+            "g -> global",
+        ]
+    );
+}
+
+#[test]
+fn test_reference_kinds() {
+    let (_, semantic) = script("let a = 1\na = 2\na++\nfor (a of b) {}\n");
+    let kinds: Vec<_> = semantic
+        .references
+        .iter()
+        .map(|reference| reference.kind)
+        .collect();
+    assert_eq!(
+        kinds,
+        [
+            ReferenceKind::Write,
+            ReferenceKind::ReadWrite,
+            ReferenceKind::Write,
+            ReferenceKind::Read,
+        ]
+    );
+}
+
+#[test]
+fn test_catch_class_and_arguments() {
+    let (ast, semantic) = script(
+        "try {} catch ({ message }) { message }\nconst C = class D extends E { m() { return D } }\n",
+    );
+    assert_eq!(
+        resolutions(&ast, &semantic),
+        ["message -> Catch", "E -> global", "D -> Class"]
+    );
+
+    // `arguments` belongs to the closest function that isn't an arrow
+    let (ast, semantic) = script("function f() { return () => arguments }\narguments\n");
+    assert_eq!(
+        resolutions(&ast, &semantic),
+        ["arguments -> Function", "arguments -> global"]
+    );
+    let arguments = semantic.references[0].symbol.unwrap();
+    assert_eq!(semantic.symbol(arguments).kind(), BindingKind::Arguments);
+    assert!(!semantic.scope(semantic.symbol(arguments).scope).arrow);
+}
+
+#[test]
+fn test_unresolved_globals() {
+    let (ast, semantic) =
+        script("let a = b\nfunction f() { return console.log(a, b, c) }\nc = 1\n");
+    let globals: Vec<_> = semantic
+        .unresolved_globals()
+        .iter()
+        .map(|&name| ast[name].to_string())
+        .collect();
+    assert_eq!(globals, ["b", "console", "c"]);
+}
+
+#[test]
+fn test_expressions_map_to_symbols() {
+    let mut lexer = Lexer::new("let a = 1\na\nb\n".to_string());
+    lexer.scan_all_tokens();
+    let mut parser = Parser::new(lexer.tokens);
+    parser.parse();
+    let semantic = analyze(&parser.ast, &SemanticOptions::default());
+    let a = semantic.references[0].expression.unwrap();
+    let b = semantic.references[1].expression.unwrap();
+    assert_eq!(
+        semantic.symbol_of(a),
+        semantic.binding(semantic.global_scope(), parser.ast.atoms.find("a").unwrap())
+    );
+    assert_eq!(semantic.symbol_of(b), None);
+    assert_eq!(semantic.reference_of(b).map(|id| id.index()), Some(1));
+}
+
+#[test]
+fn test_direct_eval_is_marked() {
+    let (_, semantic) = script("function f() { eval(\"1\") }\nfunction g() { h() }\n");
+    let marked: Vec<_> = semantic
+        .scopes
+        .iter()
+        .map(|scope| scope.direct_eval)
+        .collect();
+    assert_eq!(marked, [false, true, false]);
+}
+
+// Early errors as `line: message`
+#[allow(dead_code)]
+fn early_errors(source: &str, options: &SemanticOptions) -> Vec<String> {
+    let mut lexer = Lexer::new(source.to_string());
+    lexer.scan_all_tokens();
+    let mut parser = Parser::new(lexer.tokens);
+    parser.parse();
+    assert!(parser.errors.is_empty(), "{source}: {:?}", parser.errors);
+    let semantic = analyze(&parser.ast, options);
+    check(&parser.ast, &semantic, source)
+        .into_iter()
+        .map(|error| {
+            assert_eq!(error.error_kind, ErrorKind::SemanticError);
+            format!("{}: {}", error.line_number, error.message)
+        })
+        .collect()
+}
+
+#[allow(dead_code)]
+fn script_errors(source: &str) -> Vec<String> {
+    early_errors(source, &SemanticOptions::default())
+}
+
+#[test]
+fn test_redeclarations() {
+    assert_eq!(
+        script_errors("let x = 1\nlet x = 2\n"),
+        ["2: Identifier 'x' has already been declared"]
+    );
+    assert_eq!(
+        script_errors("var a\nlet a\nfunction f(b) { const b = 1 }\n"),
+        [
+            "2: Identifier 'a' has already been declared",
+            "3: Identifier 'b' has already been declared",
+        ]
+    );
+    assert_eq!(
+        script_errors("try {} catch (e) { let e }\nclass C {}\nclass C {}\n"),
+        [
+            "1: Identifier 'e' has already been declared",
+            "3: Identifier 'C' has already been declared",
+        ]
+    );
+    // `var` may repeat, and so may functions outside of blocks
+    assert!(script_errors("var a\nvar a\nfunction f() {}\nfunction f() {}\nvar f\n").is_empty());
+    assert!(script_errors("function f(a) { var a }\n{ let b } { let b }\n").is_empty());
+    // In a module, top level functions are lexical
+    assert_eq!(
+        early_errors(
+            "function f() {}\nvar f\n",
+            &SemanticOptions { module: true }
+        ),
+        ["2: Identifier 'f' has already been declared"]
+    );
+}
+
+#[test]
+fn test_var_clashes_with_lexical_declarations() {
+    assert_eq!(
+        script_errors("function f() {\n  let a\n  {\n    var a\n  }\n}\n"),
+        ["4: Identifier 'a' has already been declared"]
+    );
+    assert_eq!(
+        script_errors("for (let i of items) { var i }\n"),
+        ["1: Identifier 'i' has already been declared"]
+    );
+    assert!(script_errors("{ let a } { var a }\n").is_empty());
+    // A `catch` parameter may be declared again with `var` only when it is a plain name
+    assert_eq!(
+        script_errors("try {} catch ([e]) {\n  var e\n}\n"),
+        ["2: Identifier 'e' has already been declared"]
+    );
+    assert!(script_errors("try {} catch (e) { var e }\n").is_empty());
+}
+
+#[test]
+fn test_declarations_in_single_statement_positions() {
+    assert_eq!(
+        script_errors("if (a) let x = 1, y = 2\nl: const z = 1\n"),
+        [
+            "1: Lexical declaration cannot appear in a single-statement context",
+            "2: Lexical declaration cannot appear in a single-statement context",
+        ]
+    );
+    assert_eq!(
+        script_errors("while (a) function f() {}\nif (a) class C {}\n"),
+        [
+            "1: Functions can only be declared at the top level or inside a block",
+            "2: Class declaration cannot appear in a single-statement context",
+        ]
+    );
+    // Sloppy mode allows a function as the body of `if`, strict mode doesn't
+    assert!(script_errors("if (a) function f() {}\nif (b) { let c }\n").is_empty());
+    assert_eq!(
+        script_errors("\"use strict\"\nif (a) function f() {}\n"),
+        ["2: Functions can only be declared at the top level or inside a block"]
+    );
+}
+
+#[test]
+fn test_assignment_to_const() {
+    assert_eq!(
+        script_errors("const c = 1\nc = 2\nc++\nfor (const x of y) { x = 1 }\n"),
+        [
+            "2: Assignment to constant variable 'c'",
+            "3: Assignment to constant variable 'c'",
+            "4: Assignment to constant variable 'x'",
+        ]
+    );
+    // A `let` shadowing the constant can be assigned
+    assert!(script_errors("const c = 1\n{ let c = 2; c = 3 }\n").is_empty());
+}
+
+#[test]
+fn test_await_and_yield_outside_their_functions() {
+    assert_eq!(
+        script_errors("await x\nfunction f() { yield 1 }\n"),
+        [
+            "1: 'await' is only valid in async functions and the top level bodies of modules",
+            "2: 'yield' is only valid in generator functions",
+        ]
+    );
+    assert_eq!(
+        script_errors("async function f() { return () => await x }\n"),
+        ["1: 'await' is only valid in async functions and the top level bodies of modules"]
+    );
+    assert!(
+        script_errors("async function f() { await x }\nfunction* g() { yield 1 }\n").is_empty()
+    );
+    assert!(early_errors("await x\n", &SemanticOptions { module: true }).is_empty());
+}
+
+#[test]
+fn test_duplicate_proto_and_strict_delete() {
+    assert_eq!(
+        script_errors("x = { __proto__: a, b: 1, \"__proto__\": c }\n"),
+        ["1: Duplicate __proto__ fields are not allowed in object literals"]
+    );
+    assert!(script_errors("x = { __proto__: a, [\"__proto__\"]: b }\ndelete x\n").is_empty());
+    assert_eq!(
+        script_errors("class A { m() { delete x; delete x.y } }\n"),
+        ["1: Delete of an unqualified identifier in strict mode"]
+    );
+    assert_eq!(
+        early_errors("delete x\n", &SemanticOptions { module: true }),
+        ["1: Delete of an unqualified identifier in strict mode"]
+    );
+}
+
+// The parser has no module goal, so in a module only the scopes know the code is strict
+#[test]
+fn test_strict_scopes_reject_with_and_duplicate_parameters() {
+    let module = SemanticOptions { module: true };
+    assert_eq!(
+        early_errors("with (o) {}\nfunction f(a, b, a) {}\n", &module),
+        [
+            "1: Strict mode code may not include a with statement",
+            "2: Duplicate parameter name 'a' not allowed in this context",
+        ]
+    );
+    assert!(script_errors("with (o) {}\nfunction f(a, b, a) {}\n").is_empty());
+
+    // A directive at the start of the program makes every scope strict
+    let (_, semantic) = script("\"use strict\"\nfunction f(a) { { a } }\nx = () => a\n");
+    assert!(semantic.scopes.iter().all(|scope| scope.strict));
+    assert_eq!(
+        script_errors("\"use strict\"\nif (a) function f() {}\ndelete x\n"),
+        [
+            "2: Functions can only be declared at the top level or inside a block",
+            "3: Delete of an unqualified identifier in strict mode",
+        ]
+    );
+}
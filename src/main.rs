@@ -1,22 +1,342 @@
-use jsompiler_lexer::Lexer;
-use jsompiler_parser::Parser;
+use jsompiler_common::{Error, ErrorKind};
+use jsompiler_formatter::{FormatOptions, Quote, TrailingCommas, format};
+use jsompiler_interpreter::{Interpreter, STACK_SIZE, compile, disassemble, heap_stats};
+use jsompiler_lexer::{Lexer, LexerOptions};
+use jsompiler_minifier::{MinifyOptions, minify};
+use jsompiler_parser::{Parser, ParserOptions, to_estree_string};
+use jsompiler_semantic::{SemanticOptions, analyze, check};
 use std::fs::read_to_string;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::process::ExitCode;
 
-fn main() {
-    let file = read_to_string("./test.js");
-    if file.is_err() {
-        return;
+fn main() -> ExitCode {
+    if std::env::args().nth(1).as_deref() == Some("fmt") {
+        return fmt(std::env::args().skip(2).collect());
     }
-    let file = file.unwrap();
-    println!("{file}");
+    if std::env::args().nth(1).as_deref() == Some("minify") {
+        return minify_command(std::env::args().skip(2).collect());
+    }
+    if std::env::args().nth(1).as_deref() == Some("run") {
+        return run(std::env::args().skip(2).collect());
+    }
+    if std::env::args().nth(1).as_deref() == Some("disasm") {
+        return disasm(std::env::args().nth(2));
+    }
+
+    // Without a subcommand the file is checked for syntax and early errors. `--estree` prints
+    // its ESTree JSON so it can be diffed against other parsers, and `--debug` dumps the
+    // source, tokens, AST and errors.
+    let mut path = "./test.js".to_string();
+    let mut jsx = None;
+    let mut estree = false;
+    let mut debug = false;
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--jsx" => jsx = Some(true),
+            "--no-jsx" => jsx = Some(false),
+            "--estree" => estree = true,
+            "--debug" => debug = true,
+            _ => path = arg,
+        }
+    }
+
+    // JSX is enabled for .jsx and .tsx files unless a flag says otherwise
+    let jsx = jsx.unwrap_or_else(|| is_jsx_path(&path));
 
-    let mut lexer = Lexer::new(file);
+    let file = match read_source(&Some(path.clone())) {
+        Ok(file) => file,
+        Err(code) => return code,
+    };
+
+    let mut lexer = Lexer::with_options(file.clone(), LexerOptions { jsx });
     lexer.scan_all_tokens();
-    println!("{:#?}", lexer.tokens);
-    println!("{:#?}", lexer.errors);
+    if debug {
+        println!("{file}");
+        println!("{:#?}", lexer.tokens);
+    }
 
-    let mut parser = Parser::new(lexer.tokens);
+    // JSX is lexed again as the parser finds it, so lexer errors are only known after parsing
+    let mut parser = Parser::with_lexer(lexer, ParserOptions { jsx });
     parser.parse();
-    println!("{:#?}", parser.ast);
-    println!("{:#?}", parser.errors);
+    if debug {
+        println!("{:#?}", parser.lexer_errors);
+        println!("{:#?}", parser.ast);
+        println!("{:#?}", parser.errors);
+    }
+    // A partial AST isn't worth going on with, so only the errors are printed
+    if !parser.lexer_errors.is_empty() {
+        return report(&path, &parser.lexer_errors);
+    }
+    if !parser.errors.is_empty() {
+        return report(&path, &parser.errors);
+    }
+
+    if estree {
+        println!("{}", to_estree_string(&parser.ast, &file));
+        return ExitCode::SUCCESS;
+    }
+
+    let semantic = analyze(&parser.ast, &SemanticOptions::default());
+    let errors = check(&parser.ast, &semantic, &file);
+    if debug {
+        println!("{errors:#?}");
+    }
+    if !errors.is_empty() {
+        return report(&path, &errors);
+    }
+    ExitCode::SUCCESS
+}
+
+fn is_jsx_path(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .is_some_and(|extension| extension == "jsx" || extension == "tsx")
+}
+
+// `fmt [options] [paths]` rewrites files in place, or formats stdin to stdout without paths.
+// With `--check`, files are only listed if formatting would change them, and the exit code is
+// 1 if any would. Files that fail to parse give exit code 2.
+fn fmt(args: Vec<String>) -> ExitCode {
+    let mut options = FormatOptions::default();
+    let mut check = false;
+    let mut jsx = None;
+    let mut paths = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            let value = args.next();
+            if value.is_none() {
+                eprintln!("{name} needs a value");
+            }
+            value
+        };
+        match arg.as_str() {
+            "--check" => check = true,
+            "--jsx" => jsx = Some(true),
+            "--no-jsx" => jsx = Some(false),
+            "--tabs" => options.use_tabs = true,
+            "--single-quote" => options.quote = Quote::Single,
+            "--no-semi" => options.semicolons = false,
+            "--width" | "--indent" => {
+                let Some(number) = value(&arg).and_then(|value| value.parse().ok()) else {
+                    eprintln!("{arg} expects a number");
+                    return ExitCode::from(2);
+                };
+                if arg == "--width" {
+                    options.print_width = number;
+                } else {
+                    options.indent_width = number;
+                }
+            }
+            "--trailing-comma" => {
+                options.trailing_commas = match value(&arg).as_deref() {
+                    Some("none") => TrailingCommas::None,
+                    Some("es5") => TrailingCommas::Es5,
+                    Some("all") => TrailingCommas::All,
+                    _ => {
+                        eprintln!("--trailing-comma expects none, es5 or all");
+                        return ExitCode::from(2);
+                    }
+                }
+            }
+            _ => paths.push(arg),
+        }
+    }
+
+    if paths.is_empty() {
+        let mut source = String::new();
+        if std::io::stdin().read_to_string(&mut source).is_err() {
+            eprintln!("could not read stdin");
+            return ExitCode::from(2);
+        }
+        options.jsx = jsx.unwrap_or(false);
+        return match format(&source, &options) {
+            Ok(formatted) if check && formatted != source => {
+                println!("<stdin>");
+                ExitCode::from(1)
+            }
+            Ok(_) if check => ExitCode::SUCCESS,
+            Ok(formatted) => {
+                let _ = std::io::stdout().write_all(formatted.as_bytes());
+                ExitCode::SUCCESS
+            }
+            Err(errors) => {
+                for error in errors {
+                    eprintln!("<stdin>:{}: {}", error.line_number, error.message);
+                }
+                ExitCode::from(2)
+            }
+        };
+    }
+
+    let mut unformatted = false;
+    let mut failed = false;
+    for path in paths {
+        let Ok(source) = read_to_string(&path) else {
+            eprintln!("{path}: could not read file");
+            failed = true;
+            continue;
+        };
+        options.jsx = jsx.unwrap_or_else(|| is_jsx_path(&path));
+        match format(&source, &options) {
+            Ok(formatted) if formatted == source => {}
+            Ok(_) if check => {
+                println!("{path}");
+                unformatted = true;
+            }
+            Ok(formatted) => {
+                if std::fs::write(&path, formatted).is_err() {
+                    eprintln!("{path}: could not write file");
+                    failed = true;
+                }
+            }
+            Err(errors) => {
+                for error in errors {
+                    eprintln!("{path}:{}: {}", error.line_number, error.message);
+                }
+                failed = true;
+            }
+        }
+    }
+    if failed {
+        ExitCode::from(2)
+    } else if unformatted {
+        ExitCode::from(1)
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+// `minify [options] [path]` prints the minified file, or minified stdin without a path.
+// Files that fail to parse give exit code 2.
+fn minify_command(args: Vec<String>) -> ExitCode {
+    let mut options = MinifyOptions::default();
+    let mut jsx = None;
+    let mut path = None;
+    for arg in args {
+        match arg.as_str() {
+            "--jsx" => jsx = Some(true),
+            "--no-jsx" => jsx = Some(false),
+            "--no-compress" => options.compress = false,
+            "--no-mangle" => options.mangle = false,
+            _ => path = Some(arg),
+        }
+    }
+
+    let source = match read_source(&path) {
+        Ok(source) => source,
+        Err(code) => return code,
+    };
+    let name = path.as_deref().unwrap_or("<stdin>");
+    options.jsx = jsx.unwrap_or_else(|| is_jsx_path(name));
+    match minify(&source, &options) {
+        Ok(minified) => {
+            println!("{minified}");
+            ExitCode::SUCCESS
+        }
+        Err(errors) => {
+            for error in errors {
+                eprintln!("{name}:{}: {}", error.line_number, error.message);
+            }
+            ExitCode::from(2)
+        }
+    }
+}
+
+// The file at `path`, or stdin without a path
+fn read_source(path: &Option<String>) -> Result<String, ExitCode> {
+    match path {
+        Some(path) => read_to_string(path).map_err(|_| {
+            eprintln!("{path}: could not read file");
+            ExitCode::from(2)
+        }),
+        None => {
+            let mut source = String::new();
+            if std::io::stdin().read_to_string(&mut source).is_err() {
+                eprintln!("could not read stdin");
+                return Err(ExitCode::from(2));
+            }
+            Ok(source)
+        }
+    }
+}
+
+// Print errors as `name:line: message`. Exit code 1 if they are all uncaught exceptions,
+// else 2 for errors found before running.
+fn report(name: &str, errors: &[Error]) -> ExitCode {
+    for error in errors {
+        eprintln!("{name}:{}: {}", error.line_number, error.message);
+    }
+    if errors
+        .iter()
+        .all(|error| error.error_kind == ErrorKind::RuntimeError)
+    {
+        ExitCode::from(1)
+    } else {
+        ExitCode::from(2)
+    }
+}
+
+// `run [--bytecode] [--expose-gc] [--gc-stats] [path]` interprets the file, or stdin without
+// a path. With `--bytecode` it is compiled and run by the VM instead of the tree-walking
+// interpreter. `--expose-gc` defines a global `gc()` and `--gc-stats` prints what the garbage
+// collector did to stderr. An uncaught exception gives exit code 1 and a file that fails to
+// parse gives exit code 2.
+fn run(args: Vec<String>) -> ExitCode {
+    let flag = |name: &str| args.iter().any(|arg| arg == name);
+    let (bytecode, expose_gc, gc_stats) =
+        (flag("--bytecode"), flag("--expose-gc"), flag("--gc-stats"));
+    let path = args.into_iter().find(|arg| !arg.starts_with("--"));
+    let source = match read_source(&path) {
+        Ok(source) => source,
+        Err(code) => return code,
+    };
+    let name = path.unwrap_or_else(|| "<stdin>".to_string());
+    // Deeply recursive scripts need more stack than the main thread has
+    let interpreter = std::thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(move || {
+            let mut interpreter = Interpreter::new();
+            if expose_gc {
+                interpreter.expose_gc();
+            }
+            let result = if bytecode {
+                interpreter.run_bytecode(&source)
+            } else {
+                interpreter.run(&source)
+            };
+            if gc_stats {
+                let stats = heap_stats();
+                eprintln!(
+                    "allocated {}, live {}, collections {}, collected {}",
+                    stats.allocated, stats.live, stats.collections, stats.collected
+                );
+            }
+            result.map(|_| ())
+        })
+        .expect("could not start the interpreter thread");
+    match interpreter.join() {
+        Ok(Ok(())) => ExitCode::SUCCESS,
+        Ok(Err(errors)) => report(&name, &errors),
+        Err(_) => ExitCode::from(2),
+    }
+}
+
+// `disasm [path]` prints the bytecode that the file, or stdin without a path, compiles to
+fn disasm(path: Option<String>) -> ExitCode {
+    let source = match read_source(&path) {
+        Ok(source) => source,
+        Err(code) => return code,
+    };
+    let name = path.unwrap_or_else(|| "<stdin>".to_string());
+    let compiled = jsompiler_interpreter::parse(&source)
+        .and_then(|(ast, semantic)| compile(&ast, &semantic, &source).map_err(|error| vec![error]));
+    match compiled {
+        Ok(code) => {
+            print!("{}", disassemble(&code));
+            ExitCode::SUCCESS
+        }
+        Err(errors) => report(&name, &errors),
+    }
 }